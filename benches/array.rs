@@ -1,7 +1,7 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use criterion::*;
-use risinglight::array::I32Array;
+use risinglight::array::{BoolArray, I32Array};
 
 fn array_mul(c: &mut Criterion) {
     let mut group = c.benchmark_group("array mul");
@@ -33,6 +33,36 @@ fn array_mul(c: &mut Criterion) {
     group.finish();
 }
 
+fn array_cmp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("array cmp");
+    group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+    for size in [1, 16, 256, 4096, 65536] {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            use risinglight::executor::evaluator;
+            let a1: I32Array = (0..size).collect();
+            let a2: I32Array = (0..size).rev().collect();
+            b.iter(|| {
+                let _: BoolArray = evaluator::binary_op(&a1, &a2, |a, b| a < b);
+            });
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("array cmp no nulls");
+    group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+    for size in [1, 16, 256, 4096, 65536] {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            use risinglight::executor::evaluator;
+            let a1: I32Array = (0..size).collect();
+            let a2: I32Array = (0..size).rev().collect();
+            b.iter(|| {
+                let _: BoolArray = evaluator::primitive_binary_op(&a1, &a2, |a, b| a < b);
+            });
+        });
+    }
+    group.finish();
+}
+
 fn array_sum(c: &mut Criterion) {
     let mut group = c.benchmark_group("array sum");
     group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
@@ -60,5 +90,5 @@ fn array_sum(c: &mut Criterion) {
     }
     group.finish();
 }
-criterion_group!(benches, array_mul, array_sum);
+criterion_group!(benches, array_mul, array_cmp, array_sum);
 criterion_main!(benches);
@@ -1,6 +1,7 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use criterion::*;
+use risinglight::storage::SecondaryStorageOptions;
 use risinglight::Database;
 
 fn create_table(c: &mut Criterion) {
@@ -75,5 +76,169 @@ fn select_add(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, create_table, insert, select_add);
+fn group_by(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("group by");
+    const ROWS: usize = 65536;
+    // compare hash aggregation with sorted aggregation over a table sorted by the group key
+    let aggs = [
+        (
+            "hash",
+            "create table t(v1 int, v2 int)",
+            "select v1, sum(v2), count(v2) from t group by v1",
+        ),
+        (
+            "sorted",
+            "create table t(v1 int, v2 int, primary key (v1, v2))",
+            "select v1, sum(v2), count(v2) from t group by v1 order by v1",
+        ),
+    ];
+    for groups in [1, 16, 256, 4096, 65536] {
+        let insert_sql = std::iter::once("insert into t values ".to_string())
+            .chain((0..ROWS).map(|i| format!("({},{}),", i % groups, i)))
+            .collect::<String>();
+        let insert_sql = insert_sql.trim_end_matches(',').to_string();
+        for (name, create_sql, sql) in aggs {
+            group.bench_with_input(BenchmarkId::new(name, groups), &groups, |b, _| {
+                b.to_async(&runtime).iter_batched(
+                    || async {
+                        let db = Database::new_in_memory();
+                        db.run(create_sql).await.unwrap();
+                        db.run(&insert_sql).await.unwrap();
+                        db
+                    },
+                    |db| async {
+                        db.await.run(sql).await.unwrap();
+                    },
+                    BatchSize::LargeInput,
+                );
+            });
+        }
+    }
+    group.finish();
+}
+
+fn parallel_group_by(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("parallel group by");
+    const ROWS: usize = 65536;
+    const GROUPS: usize = 4096;
+    let insert_sql = std::iter::once("insert into t values ".to_string())
+        .chain((0..ROWS).map(|i| format!("({},{}),", i % GROUPS, i)))
+        .collect::<String>();
+    let insert_sql = insert_sql.trim_end_matches(',').to_string();
+    for parallelism in [1, 2, 4, 8] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(parallelism),
+            &parallelism,
+            |b, &parallelism| {
+                b.to_async(&runtime).iter_batched(
+                    || async {
+                        let db = Database::new_in_memory().with_parallelism(parallelism);
+                        db.run("create table t(v1 int, v2 int)").await.unwrap();
+                        db.run(&insert_sql).await.unwrap();
+                        db
+                    },
+                    |db| async {
+                        db.await
+                            .run("select v1, sum(v2), count(v2) from t group by v1")
+                            .await
+                            .unwrap();
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+    group.finish();
+}
+
+fn order_by_limit(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("order by limit");
+    const ROWS: usize = 65536;
+    let insert_sql = std::iter::once("insert into t values ".to_string())
+        .chain((0..ROWS).map(|i| format!("({},{}),", (i * 7919) % ROWS, i)))
+        .collect::<String>();
+    let insert_sql = insert_sql.trim_end_matches(',').to_string();
+    // compare top N with a full sort
+    for (name, sql) in [
+        ("sort", "select v1, v2 from t order by v1 desc, v2"),
+        (
+            "top n",
+            "select v1, v2 from t order by v1 desc, v2 limit 10",
+        ),
+    ] {
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter_batched(
+                || async {
+                    let db = Database::new_in_memory();
+                    db.run("create table t(v1 int, v2 int)").await.unwrap();
+                    db.run(&insert_sql).await.unwrap();
+                    db
+                },
+                |db| async {
+                    db.await.run(sql).await.unwrap();
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn parallel_scan(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("parallel scan");
+    const ROWS: usize = 65536;
+    const ROWSETS: usize = 16;
+    let insert_sql = std::iter::once("insert into t values ".to_string())
+        .chain((0..ROWS / ROWSETS).map(|i| format!("({},{}),", i % 256, i)))
+        .collect::<String>();
+    let insert_sql = insert_sql.trim_end_matches(',').to_string();
+    for parallelism in [1, 2, 4, 8] {
+        let tempdir = tempfile::tempdir().unwrap();
+        let db = runtime.block_on(async {
+            let db = Database::new_on_disk(SecondaryStorageOptions {
+                scan_parallelism: parallelism,
+                ..SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf())
+            })
+            .await;
+            db.run("create table t(v1 int, v2 int)").await.unwrap();
+            // each insert writes a rowset
+            for _ in 0..ROWSETS {
+                db.run(&insert_sql).await.unwrap();
+            }
+            db
+        });
+        group.bench_with_input(
+            BenchmarkId::from_parameter(parallelism),
+            &parallelism,
+            |b, _| {
+                b.to_async(&runtime).iter(|| async {
+                    db.run("select v1, sum(v2), count(v2) from t group by v1")
+                        .await
+                        .unwrap();
+                });
+            },
+        );
+        runtime.block_on(db.shutdown()).unwrap();
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    create_table,
+    insert,
+    select_add,
+    group_by,
+    parallel_group_by,
+    order_by_limit,
+    parallel_scan
+);
 criterion_main!(benches);
@@ -0,0 +1,224 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+//! Conversions between [`ArrayImpl`] / [`DataChunk`] and Apache Arrow arrays / record batches.
+//!
+//! | RisingLight | Arrow                     |
+//! | ----------- | ------------------------- |
+//! | `Bool`      | `Boolean`                 |
+//! | `Int32`     | `Int32`                   |
+//! | `Int64`     | `Int64`                   |
+//! | `Float64`   | `Float64`                 |
+//! | `Utf8`      | `Utf8`                    |
+//! | `Blob`      | `Binary`                  |
+//! | `Decimal`   | `Decimal(38, max scale)`  |
+//! | `Date`      | `Date32`                  |
+//! | `Timestamp` | `Timestamp(Microsecond)`  |
+//!
+//! Intervals and lists are not supported yet.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array as ArrowArray, ArrayRef, BinaryArray, BooleanArray, Date32Array, DecimalArray,
+    DecimalBuilder, Float64Array, Int32Array, Int64Array, StringArray, TimestampMicrosecondArray,
+};
+use arrow::datatypes::{DataType as ArrowDataType, SchemaRef, TimeUnit};
+use arrow::error::{ArrowError, Result};
+use arrow::record_batch::RecordBatch;
+
+use super::*;
+
+/// The precision of exported decimals, which is the maximum precision of Arrow.
+const DECIMAL_PRECISION: usize = 38;
+
+impl ArrayImpl {
+    /// Convert the array to an Arrow array.
+    pub fn to_arrow(&self) -> Result<ArrayRef> {
+        Ok(match self {
+            Self::Bool(a) => Arc::new(BooleanArray::from_iter(a.iter().map(|v| v.cloned()))),
+            Self::Int32(a) => Arc::new(Int32Array::from_iter(a.iter().map(|v| v.cloned()))),
+            Self::Int64(a) => Arc::new(Int64Array::from_iter(a.iter().map(|v| v.cloned()))),
+            Self::Float64(a) => Arc::new(Float64Array::from_iter(a.iter().map(|v| v.cloned()))),
+            Self::Utf8(a) => Arc::new(StringArray::from_iter(a.iter())),
+            Self::Blob(a) => Arc::new(BinaryArray::from_iter(a.iter().map(|v| v.map(|b| &**b)))),
+            Self::Decimal(a) => {
+                // all values are rescaled to the maximum scale
+                let scale = a.iter().flatten().map(|d| d.scale()).max().unwrap_or(0);
+                let mut builder = DecimalBuilder::new(a.len(), DECIMAL_PRECISION, scale as usize);
+                for v in a.iter() {
+                    match v {
+                        Some(d) => {
+                            let mut d = *d;
+                            d.rescale(scale);
+                            builder.append_value(d.mantissa())?;
+                        }
+                        None => builder.append_null()?,
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            Self::Date(a) => Arc::new(Date32Array::from_iter(
+                a.iter().map(|v| v.map(|d| d.get_inner())),
+            )),
+            Self::Timestamp(a) => Arc::new(TimestampMicrosecondArray::from_iter(
+                a.iter().map(|v| v.map(|t| t.get_inner())),
+            )),
+            Self::Interval(_) => {
+                return Err(ArrowError::NotYetImplemented(
+                    "exporting intervals to arrow".into(),
+                ))
+            }
+            Self::List(_) => {
+                return Err(ArrowError::NotYetImplemented(
+                    "exporting lists to arrow".into(),
+                ))
+            }
+        })
+    }
+
+    /// Convert an Arrow array to an array.
+    pub fn from_arrow(array: &dyn ArrowArray) -> Result<Self> {
+        fn downcast<T: 'static>(array: &dyn ArrowArray) -> &T {
+            array.as_any().downcast_ref::<T>().unwrap()
+        }
+        Ok(match array.data_type() {
+            ArrowDataType::Boolean => Self::Bool(downcast::<BooleanArray>(array).iter().collect()),
+            ArrowDataType::Int32 => Self::Int32(downcast::<Int32Array>(array).iter().collect()),
+            ArrowDataType::Int64 => Self::Int64(downcast::<Int64Array>(array).iter().collect()),
+            ArrowDataType::Float64 => {
+                Self::Float64(downcast::<Float64Array>(array).iter().collect())
+            }
+            ArrowDataType::Utf8 => Self::Utf8(downcast::<StringArray>(array).iter().collect()),
+            ArrowDataType::Binary => Self::Blob(
+                (downcast::<BinaryArray>(array).iter())
+                    .map(|v| v.map(Blob::from))
+                    .collect(),
+            ),
+            ArrowDataType::Decimal(_, scale) => {
+                let a = downcast::<DecimalArray>(array);
+                Self::Decimal(
+                    (0..a.len())
+                        .map(|i| {
+                            a.is_valid(i)
+                                .then(|| Decimal::from_i128_with_scale(a.value(i), *scale as u32))
+                        })
+                        .collect(),
+                )
+            }
+            ArrowDataType::Date32 => Self::Date(
+                (downcast::<Date32Array>(array).iter())
+                    .map(|v| v.map(Date::new))
+                    .collect(),
+            ),
+            ArrowDataType::Timestamp(TimeUnit::Microsecond, None) => Self::Timestamp(
+                (downcast::<TimestampMicrosecondArray>(array).iter())
+                    .map(|v| v.map(Timestamp::new))
+                    .collect(),
+            ),
+            ty => {
+                return Err(ArrowError::NotYetImplemented(format!(
+                    "importing arrow type {}",
+                    ty
+                )))
+            }
+        })
+    }
+}
+
+impl DataChunk {
+    /// Convert the chunk to an Arrow record batch with the given schema.
+    ///
+    /// The schema must have one field for each column, whose type matches the Arrow type of the
+    /// column.
+    pub fn to_record_batch(&self, schema: SchemaRef) -> Result<RecordBatch> {
+        let columns = (self.arrays().iter())
+            .map(|a| a.to_arrow())
+            .collect::<Result<Vec<_>>>()?;
+        RecordBatch::try_new(schema, columns)
+    }
+
+    /// Convert an Arrow record batch to a chunk.
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self> {
+        (batch.columns().iter())
+            .map(|a| ArrayImpl::from_arrow(a.as_ref()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{Field, Schema};
+
+    use super::*;
+
+    fn round_trip(array: ArrayImpl) {
+        let arrow = array.to_arrow().unwrap();
+        assert_eq!(arrow.len(), array.len());
+        assert_eq!(arrow.null_count(), 1);
+        assert_eq!(ArrayImpl::from_arrow(arrow.as_ref()).unwrap(), array);
+    }
+
+    #[test]
+    fn test_array_round_trip() {
+        round_trip(ArrayImpl::Bool(
+            [Some(true), None, Some(false)].into_iter().collect(),
+        ));
+        round_trip(ArrayImpl::Int32(
+            [Some(1), None, Some(-1)].into_iter().collect(),
+        ));
+        round_trip(ArrayImpl::Int64(
+            [Some(i64::MAX), None].into_iter().collect(),
+        ));
+        round_trip(ArrayImpl::Float64([None, Some(1.5)].into_iter().collect()));
+        round_trip(ArrayImpl::Utf8(
+            [Some("a"), None, Some("")].into_iter().collect(),
+        ));
+        round_trip(ArrayImpl::Blob(
+            [Some(Blob::from(&b"\x01\x02"[..])), None]
+                .into_iter()
+                .collect(),
+        ));
+        round_trip(ArrayImpl::Decimal(
+            [Some(Decimal::new(123, 2)), None, Some(Decimal::new(-5, 0))]
+                .into_iter()
+                .collect(),
+        ));
+        round_trip(ArrayImpl::Date(
+            [Some(Date::new(0)), None, Some(Date::new(19000))]
+                .into_iter()
+                .collect(),
+        ));
+        round_trip(ArrayImpl::Timestamp(
+            [None, Some(Timestamp::new(1_640_995_200_000_000))]
+                .into_iter()
+                .collect(),
+        ));
+        assert!(ArrayImpl::Interval([None].into_iter().collect())
+            .to_arrow()
+            .is_err());
+    }
+
+    #[test]
+    fn test_record_batch_round_trip() {
+        let chunk: DataChunk = [
+            ArrayImpl::Int32([Some(1), Some(2)].into_iter().collect()),
+            ArrayImpl::Utf8([Some("a"), None].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", ArrowDataType::Int32, false),
+            Field::new("b", ArrowDataType::Utf8, true),
+        ]));
+        let batch = chunk.to_record_batch(schema).unwrap();
+        assert_eq!((batch.num_rows(), batch.num_columns()), (2, 2));
+        assert_eq!(DataChunk::from_record_batch(&batch).unwrap(), chunk);
+
+        // mismatched types
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", ArrowDataType::Int64, false),
+            Field::new("b", ArrowDataType::Utf8, true),
+        ]));
+        assert!(chunk.to_record_batch(schema).is_err());
+    }
+}
@@ -89,6 +89,22 @@ impl DataChunk {
         }
     }
 
+    /// Keep the rows where the mask is true and create a new chunk. NULLs in the mask are treated
+    /// as false, which is the semantic of `WHERE`.
+    pub fn filter_by_mask(&self, mask: &BoolArray) -> Self {
+        assert_eq!(self.cardinality(), mask.len(), "mask length mismatch");
+        let arrays = if mask.iter().all(|b| b == Some(&true)) {
+            // all rows are selected, so the arrays can be shared
+            self.arrays.clone()
+        } else {
+            self.arrays.iter().map(|a| a.filter_by_mask(mask)).collect()
+        };
+        DataChunk {
+            arrays,
+            header: None,
+        }
+    }
+
     /// Return the number of columns.
     pub fn column_count(&self) -> usize {
         self.arrays.len()
@@ -103,6 +119,21 @@ impl DataChunk {
         }
     }
 
+    /// Split the chunk into pieces of `size` rows. The last piece may have fewer rows.
+    ///
+    /// A chunk that is not larger than `size` is returned as a whole without copying.
+    pub fn split(&self, size: usize) -> impl Iterator<Item = DataChunk> + '_ {
+        assert!(size > 0, "size must be positive");
+        let cardinality = self.cardinality();
+        (0..cardinality).step_by(size).map(move |start| {
+            if cardinality <= size {
+                self.clone()
+            } else {
+                self.slice(start..(start + size).min(cardinality))
+            }
+        })
+    }
+
     /// Get the estimated in-memory size.
     pub fn estimated_size(&self) -> usize {
         self.arrays.iter().map(|a| a.get_estimated_size()).sum()
@@ -161,7 +192,9 @@ pub fn datachunk_to_sqllogictest_string(chunk: &DataChunk) -> String {
                 DataValue::Blob(s) => write!(output, "{}", s),
                 DataValue::Decimal(v) => write!(output, "{}", v),
                 DataValue::Date(v) => write!(output, "{}", v),
+                DataValue::Timestamp(v) => write!(output, "{}", v),
                 DataValue::Interval(v) => write!(output, "{}", v),
+                DataValue::List(v) => write!(output, "{}", v),
             }
             .unwrap();
         }
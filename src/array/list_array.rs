@@ -0,0 +1,144 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::iter::FromIterator;
+
+use bitvec::vec::BitVec;
+use serde::{Deserialize, Serialize};
+
+use super::{Array, ArrayBuilder, ArrayEstimateExt, ArrayValidExt};
+use crate::types::{DataValue, ListRef};
+
+/// A collection of lists.
+///
+/// The elements of all lists are flattened into one vector, and the elements of the `i`-th list
+/// are `values[offset[i]..offset[i + 1]]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListArray {
+    offset: Vec<usize>,
+    valid: BitVec,
+    values: Vec<DataValue>,
+}
+
+impl Array for ListArray {
+    type Item = ListRef;
+    type Builder = ListArrayBuilder;
+
+    fn get(&self, idx: usize) -> Option<&ListRef> {
+        if self.valid[idx] {
+            Some(ListRef::new(
+                &self.values[self.offset[idx]..self.offset[idx + 1]],
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.valid.len()
+    }
+}
+
+impl ArrayValidExt for ListArray {
+    fn get_valid_bitmap(&self) -> &BitVec {
+        &self.valid
+    }
+}
+
+impl ArrayEstimateExt for ListArray {
+    fn get_estimated_size(&self) -> usize {
+        self.values.len() * std::mem::size_of::<DataValue>()
+            + self.offset.len() * std::mem::size_of::<usize>()
+            + self.valid.len() / 8
+    }
+}
+
+/// A builder that uses `&ListRef` to build an [`ListArray`].
+pub struct ListArrayBuilder {
+    offset: Vec<usize>,
+    valid: BitVec,
+    values: Vec<DataValue>,
+}
+
+impl ArrayBuilder for ListArrayBuilder {
+    type Array = ListArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        let mut offset = Vec::with_capacity(capacity + 1);
+        offset.push(0);
+        Self {
+            offset,
+            valid: BitVec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, value: Option<&ListRef>) {
+        self.valid.push(value.is_some());
+        if let Some(x) = value {
+            self.values.extend_from_slice(x);
+        }
+        self.offset.push(self.values.len());
+    }
+
+    fn append(&mut self, other: &ListArray) {
+        self.valid.extend_from_bitslice(&other.valid);
+        self.values.extend_from_slice(&other.values);
+        let start = *self.offset.last().unwrap();
+        for other_offset in &other.offset[1..] {
+            self.offset.push(*other_offset + start);
+        }
+    }
+
+    fn finish(self) -> ListArray {
+        ListArray {
+            offset: self.offset,
+            valid: self.valid,
+            values: self.values,
+        }
+    }
+}
+
+// Enable `collect()` an array from iterator of `Option<&ListRef>` or `Option<List>`.
+impl<O: AsRef<ListRef>> FromIterator<Option<O>> for ListArray {
+    fn from_iter<I: IntoIterator<Item = Option<O>>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut builder = <Self as Array>::Builder::with_capacity(iter.size_hint().0);
+        for e in iter {
+            if let Some(l) = e {
+                builder.push(Some(l.as_ref()));
+            } else {
+                builder.push(None);
+            }
+        }
+        builder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::List;
+
+    #[test]
+    fn test_list_builder() {
+        let lists = [
+            Some(List::from(vec![DataValue::Int32(1), DataValue::Int32(2)])),
+            None,
+            Some(List::from(vec![])),
+            Some(List::from(vec![DataValue::Null])),
+        ];
+        let array: ListArray = lists.iter().cloned().collect();
+        assert_eq!(array.len(), 4);
+        for (i, list) in lists.iter().enumerate() {
+            assert_eq!(array.get(i), list.as_deref());
+        }
+
+        let mut builder = ListArrayBuilder::new();
+        builder.append(&array);
+        builder.append(&array);
+        let array2 = builder.finish();
+        assert_eq!(array2.len(), 8);
+        assert_eq!(array2.get(4), lists[0].as_deref());
+        assert_eq!(array2.get(7), lists[3].as_deref());
+    }
+}
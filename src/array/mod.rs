@@ -7,15 +7,20 @@ use std::ops::{Bound, RangeBounds};
 use rust_decimal::prelude::FromStr;
 use rust_decimal::Decimal;
 
-use crate::types::{Blob, ConvertError, DataType, DataValue, Date, Interval, PhysicalDataTypeKind};
+use crate::types::{
+    Blob, ConvertError, DataType, DataTypeExt, DataTypeKind, DataValue, Date, Interval,
+    PhysicalDataTypeKind, Timestamp,
+};
 
 mod data_chunk;
 mod iterator;
+mod list_array;
 mod primitive_array;
 mod utf8_array;
 
 pub use self::data_chunk::*;
 pub use self::iterator::ArrayIter;
+pub use self::list_array::*;
 pub use self::primitive_array::*;
 pub use self::utf8_array::*;
 
@@ -25,6 +30,12 @@ pub use internal_ext::*;
 mod shuffle_ext;
 pub use shuffle_ext::*;
 
+mod pretty;
+pub use pretty::*;
+
+#[cfg(feature = "arrow")]
+mod arrow_ext;
+
 /// A trait over all array builders.
 ///
 /// `ArrayBuilder` is a trait over all builders. You could build an array with
@@ -96,6 +107,9 @@ pub trait ArrayExt: Array {
     /// Filter the elements and return a new array.
     fn filter(&self, visibility: impl Iterator<Item = bool>) -> Self;
 
+    /// Keep the elements where the mask is true. NULLs in the mask are treated as false.
+    fn filter_by_mask(&self, mask: &BoolArray) -> Self;
+
     /// Return a slice of self for the provided range.
     fn slice(&self, range: impl RangeBounds<usize>) -> Self;
 }
@@ -112,6 +126,19 @@ impl<A: Array> ArrayExt for A {
         builder.finish()
     }
 
+    /// Keep the elements where the mask is true. NULLs in the mask are treated as false.
+    fn filter_by_mask(&self, mask: &BoolArray) -> Self {
+        assert_eq!(self.len(), mask.len(), "mask length mismatch");
+        let count = mask.iter().filter(|b| b == &Some(&true)).count();
+        let mut builder = Self::Builder::with_capacity(count);
+        for (a, visible) in self.iter().zip(mask.iter()) {
+            if visible == Some(&true) {
+                builder.push(a);
+            }
+        }
+        builder.finish()
+    }
+
     /// Return a slice of self for the provided range.
     fn slice(&self, range: impl RangeBounds<usize>) -> Self {
         let len = self.len();
@@ -142,6 +169,7 @@ pub type I64Array = PrimitiveArray<i64>;
 pub type F64Array = PrimitiveArray<f64>;
 pub type DecimalArray = PrimitiveArray<Decimal>;
 pub type DateArray = PrimitiveArray<Date>;
+pub type TimestampArray = PrimitiveArray<Timestamp>;
 pub type IntervalArray = PrimitiveArray<Interval>;
 
 /// Embeds all types of arrays in `array` module.
@@ -157,7 +185,9 @@ pub enum ArrayImpl {
     Blob(BlobArray),
     Decimal(DecimalArray),
     Date(DateArray),
+    Timestamp(TimestampArray),
     Interval(IntervalArray),
+    List(ListArray),
 }
 
 pub type BoolArrayBuilder = PrimitiveArrayBuilder<bool>;
@@ -166,6 +196,7 @@ pub type I64ArrayBuilder = PrimitiveArrayBuilder<i64>;
 pub type F64ArrayBuilder = PrimitiveArrayBuilder<f64>;
 pub type DecimalArrayBuilder = PrimitiveArrayBuilder<Decimal>;
 pub type DateArrayBuilder = PrimitiveArrayBuilder<Date>;
+pub type TimestampArrayBuilder = PrimitiveArrayBuilder<Timestamp>;
 pub type IntervalArrayBuilder = PrimitiveArrayBuilder<Interval>;
 
 /// Embeds all types of array builders in `array` module.
@@ -180,7 +211,9 @@ pub enum ArrayBuilderImpl {
     Blob(BlobArrayBuilder),
     Decimal(DecimalArrayBuilder),
     Date(DateArrayBuilder),
+    Timestamp(TimestampArrayBuilder),
     Interval(IntervalArrayBuilder),
+    List(ListArrayBuilder),
 }
 
 /// `for_all_variants` includes all variants of our array types. If you added a new array
@@ -204,7 +237,9 @@ macro_rules! for_all_variants {
             { Bool, bool, BoolArray, BoolArrayBuilder, Bool },
             { Decimal, decimal, DecimalArray, DecimalArrayBuilder, Decimal },
             { Date, date, DateArray, DateArrayBuilder, Date },
-            { Interval, interval, IntervalArray, IntervalArrayBuilder, Interval }
+            { Timestamp, timestamp, TimestampArray, TimestampArrayBuilder, Timestamp },
+            { Interval, interval, IntervalArray, IntervalArrayBuilder, Interval },
+            { List, list, ListArray, ListArrayBuilder, List }
         }
     };
 }
@@ -364,7 +399,9 @@ impl ArrayBuilderImpl {
             Self::Blob(a) if null => a.push(None),
             Self::Decimal(a) if null => a.push(None),
             Self::Date(a) if null => a.push(None),
+            Self::Timestamp(a) if null => a.push(None),
             Self::Interval(a) if null => a.push(None),
+            Self::List(a) if null => a.push(None),
             Self::Bool(a) => a.push(Some(
                 &s.parse::<bool>()
                     .map_err(|e| ConvertError::ParseBool(s.to_string(), e))?,
@@ -392,7 +429,12 @@ impl ArrayBuilderImpl {
             Self::Date(a) => a.push(Some(
                 &Date::from_str(s).map_err(|e| ConvertError::ParseDate(s.to_string(), e))?,
             )),
-            Self::Interval(_) => return Err(ConvertError::ParseInterval(s.to_string())),
+            Self::Timestamp(a) => a
+                .push(Some(&Timestamp::from_str(s).map_err(|e| {
+                    ConvertError::ParseTimestamp(s.to_string(), e)
+                })?)),
+            Self::Interval(a) => a.push(Some(&s.parse::<Interval>()?)),
+            Self::List(_) => return Err(ConvertError::Cast(s.to_string(), "list")),
         }
         Ok(())
     }
@@ -442,6 +484,15 @@ macro_rules! impl_array {
                 }
             }
 
+            /// Keep the elements where the mask is true. NULLs in the mask are treated as false.
+            pub fn filter_by_mask(&self, mask: &BoolArray) -> Self {
+                match self {
+                    $(
+                        Self::$Abc(a) => Self::$Abc(a.filter_by_mask(mask)),
+                    )*
+                }
+            }
+
             /// Return a slice of self for the provided range.
             pub fn slice(&self, range: impl RangeBounds<usize>) -> Self {
                 match self {
@@ -461,6 +512,32 @@ impl ArrayImpl {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Get the nullable data type of the array.
+    ///
+    /// Type parameters such as the length of strings or the precision of decimals are unknown.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::Bool(_) => DataTypeKind::Boolean,
+            Self::Int32(_) => DataTypeKind::Int(None),
+            Self::Int64(_) => DataTypeKind::BigInt(None),
+            Self::Float64(_) => DataTypeKind::Double,
+            Self::Utf8(_) => DataTypeKind::String,
+            Self::Blob(_) => DataTypeKind::Bytea,
+            Self::Decimal(_) => DataTypeKind::Decimal(None, None),
+            Self::Date(_) => DataTypeKind::Date,
+            Self::Timestamp(_) => DataTypeKind::Timestamp,
+            Self::Interval(_) => DataTypeKind::Interval,
+            Self::List(a) => {
+                // the element type is taken from the first non-NULL element
+                let element = (a.iter().flatten().flat_map(|l| l.iter()))
+                    .find_map(|v| v.data_type())
+                    .map_or(DataTypeKind::Int(None), |ty| ty.kind());
+                DataTypeKind::Array(Box::new(element))
+            }
+        }
+        .nullable()
+    }
 }
 
 /// Create a single element array from data value.
@@ -475,7 +552,9 @@ impl From<&DataValue> for ArrayImpl {
             DataValue::Blob(v) => Self::Blob([Some(v)].into_iter().collect()),
             &DataValue::Decimal(v) => Self::Decimal([v].into_iter().collect()),
             &DataValue::Date(v) => Self::Date([v].into_iter().collect()),
+            &DataValue::Timestamp(v) => Self::Timestamp([v].into_iter().collect()),
             &DataValue::Interval(v) => Self::Interval([v].into_iter().collect()),
+            DataValue::List(v) => Self::List([Some(v)].into_iter().collect()),
             DataValue::Null => panic!("can not build array from NULL"),
         }
     }
@@ -499,6 +578,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_filter_by_mask() {
+        let values = [
+            (DataValue::Bool(true), DataValue::Bool(false)),
+            (DataValue::Int32(1), DataValue::Int32(2)),
+            (DataValue::Int64(1), DataValue::Int64(2)),
+            (DataValue::Float64(1.5), DataValue::Float64(2.5)),
+            (DataValue::String("a".into()), DataValue::String("b".into())),
+            (
+                DataValue::Blob(vec![1u8].into()),
+                DataValue::Blob(vec![2u8].into()),
+            ),
+            (
+                DataValue::Decimal(Decimal::new(15, 1)),
+                DataValue::Decimal(Decimal::new(25, 1)),
+            ),
+            (
+                DataValue::Date("2022-01-01".parse().unwrap()),
+                DataValue::Date("2022-01-02".parse().unwrap()),
+            ),
+            (
+                DataValue::Timestamp("2022-01-01 00:00:00".parse().unwrap()),
+                DataValue::Timestamp("2022-01-01 00:00:01".parse().unwrap()),
+            ),
+            (
+                DataValue::Interval(Interval::from_days(1)),
+                DataValue::Interval(Interval::from_days(2)),
+            ),
+            (
+                DataValue::List(vec![DataValue::Int32(1)].into()),
+                DataValue::List(vec![DataValue::Null, DataValue::Int32(2)].into()),
+            ),
+        ];
+        let mask: BoolArray = [Some(true), Some(true), Some(false), None]
+            .into_iter()
+            .collect();
+        let all_true: BoolArray = [Some(true); 4].into_iter().collect();
+        let all_false: BoolArray = [Some(false); 4].into_iter().collect();
+        for (a, b) in values {
+            let mut builder = ArrayBuilderImpl::from_type_of_array(&ArrayImpl::from(&a));
+            for v in [&a, &DataValue::Null, &b, &a] {
+                builder.push(v);
+            }
+            let array = builder.finish();
+
+            let filtered = array.filter_by_mask(&mask);
+            assert_eq!(filtered.len(), 2);
+            assert_eq!(filtered.get(0), a);
+            assert_eq!(filtered.get(1), DataValue::Null);
+            assert_eq!(array.filter_by_mask(&all_true), array);
+            assert_eq!(array.filter_by_mask(&all_false).len(), 0);
+
+            let chunk: DataChunk = [array.clone(), array].into_iter().collect();
+            assert_eq!(chunk.filter_by_mask(&mask).cardinality(), 2);
+            assert_eq!(chunk.filter_by_mask(&all_true), chunk);
+            let empty = chunk.filter_by_mask(&all_false);
+            assert_eq!((empty.cardinality(), empty.column_count()), (0, 2));
+        }
+    }
+
+    #[test]
+    fn test_chunk_slice_and_split() {
+        let ints: I32Array = (0..10).map(|i| (i % 3 != 0).then(|| i)).collect();
+        let strs: Utf8Array = (0..10).map(|i| Some(i.to_string())).collect();
+        let chunk: DataChunk = [ArrayImpl::Int32(ints), ArrayImpl::Utf8(strs)]
+            .into_iter()
+            .collect();
+
+        let slice = chunk.slice(4..7);
+        assert_eq!(slice.cardinality(), 3);
+        for i in 0..3 {
+            assert_eq!(
+                slice.row(i).values().collect::<Vec<_>>(),
+                chunk.row(i + 4).values().collect::<Vec<_>>()
+            );
+        }
+
+        let pieces = chunk.split(4).collect::<Vec<_>>();
+        assert_eq!(
+            pieces.iter().map(|c| c.cardinality()).collect::<Vec<_>>(),
+            vec![4, 4, 2]
+        );
+        // reassemble the pieces
+        let mut builders = chunk
+            .arrays()
+            .iter()
+            .map(ArrayBuilderImpl::from_type_of_array)
+            .collect::<Vec<_>>();
+        for piece in &pieces {
+            for (builder, array) in builders.iter_mut().zip(piece.arrays()) {
+                builder.append(array);
+            }
+        }
+        assert_eq!(builders.into_iter().collect::<DataChunk>(), chunk);
+
+        assert_eq!(chunk.split(10).collect::<Vec<_>>(), vec![chunk.clone()]);
+        assert_eq!(chunk.slice(0..0).split(4).count(), 0);
+    }
+
     use num_traits::cast::AsPrimitive;
     use num_traits::ops::checked::CheckedAdd;
 
@@ -0,0 +1,152 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+//! Rendering query results as aligned ASCII tables.
+
+use super::*;
+use crate::types::DataType;
+
+/// The default maximum display width of a column.
+pub const DEFAULT_MAX_COLUMN_WIDTH: usize = 40;
+
+/// The suffix of truncated values.
+const ELLIPSIS: &str = "...";
+
+/// Renders result chunks as an aligned ASCII table.
+///
+/// Numeric columns are right-aligned and other columns are left-aligned. Values wider than
+/// the maximum column width are truncated with a trailing `...`.
+pub struct TableFormatter<'a> {
+    names: &'a [String],
+    types: &'a [DataType],
+    max_width: usize,
+}
+
+impl<'a> TableFormatter<'a> {
+    pub fn new(names: &'a [String], types: &'a [DataType]) -> Self {
+        assert_eq!(names.len(), types.len(), "column names and types mismatch");
+        TableFormatter {
+            names,
+            types,
+            max_width: DEFAULT_MAX_COLUMN_WIDTH,
+        }
+    }
+
+    /// Set the maximum display width of a column.
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        assert!(max_width > ELLIPSIS.len(), "column width is too small");
+        self.max_width = max_width;
+        self
+    }
+
+    /// Render the rows of all chunks as a single table.
+    pub fn format(&self, chunks: &[DataChunk]) -> String {
+        if self.names.is_empty() {
+            return String::new();
+        }
+        let header: Vec<String> = self.names.iter().map(|s| self.truncate(s)).collect();
+        let rows: Vec<Vec<String>> = chunks
+            .iter()
+            .flat_map(|chunk| {
+                (0..chunk.cardinality()).map(move |i| {
+                    (chunk.arrays().iter())
+                        .map(|a| self.truncate(&a.get_to_string(i)))
+                        .collect()
+                })
+            })
+            .collect();
+
+        let mut widths: Vec<usize> = header.iter().map(|s| s.chars().count()).collect();
+        for row in &rows {
+            for (width, value) in widths.iter_mut().zip(row) {
+                *width = (*width).max(value.chars().count());
+            }
+        }
+
+        let mut border = String::from("+");
+        for width in &widths {
+            border.push_str(&"-".repeat(width + 2));
+            border.push('+');
+        }
+        border.push('\n');
+
+        let mut output = border.clone();
+        output.push_str(&self.format_row(&header, &widths));
+        output.push_str(&border);
+        for row in &rows {
+            output.push_str(&self.format_row(row, &widths));
+        }
+        output.push_str(&border);
+        output
+    }
+
+    fn format_row(&self, row: &[String], widths: &[usize]) -> String {
+        let mut line = String::from("|");
+        for ((value, width), ty) in row.iter().zip(widths).zip(self.types) {
+            let cell = if ty.is_numeric() {
+                format!(" {:>width$} |", value, width = width)
+            } else {
+                format!(" {:<width$} |", value, width = width)
+            };
+            line.push_str(&cell);
+        }
+        line.push('\n');
+        line
+    }
+
+    fn truncate(&self, value: &str) -> String {
+        if value.chars().count() <= self.max_width {
+            return value.into();
+        }
+        let mut truncated: String = value
+            .chars()
+            .take(self.max_width - ELLIPSIS.len())
+            .collect();
+        truncated.push_str(ELLIPSIS);
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    #[test]
+    fn test_format_table() {
+        let names = vec!["id".to_string(), "name".to_string(), "score".to_string()];
+        let types = vec![
+            DataTypeKind::Int(None).not_null(),
+            DataTypeKind::String.nullable(),
+            DataTypeKind::Double.nullable(),
+        ];
+        let chunks: Vec<DataChunk> = vec![
+            [
+                ArrayImpl::Int32([Some(1), Some(20)].into_iter().collect()),
+                ArrayImpl::Utf8([Some("alice"), None].into_iter().collect()),
+                ArrayImpl::Float64([Some(1.5), None].into_iter().collect()),
+            ]
+            .into_iter()
+            .collect(),
+            [
+                ArrayImpl::Int32([Some(300)].into_iter().collect()),
+                ArrayImpl::Utf8([Some("a very long name")].into_iter().collect()),
+                ArrayImpl::Float64([Some(-0.25)].into_iter().collect()),
+            ]
+            .into_iter()
+            .collect(),
+        ];
+        let output = TableFormatter::new(&names, &types)
+            .max_width(10)
+            .format(&chunks);
+        let expected = "\
++-----+------------+-------+
+|  id | name       | score |
++-----+------------+-------+
+|   1 | alice      |   1.5 |
+|  20 | NULL       |  NULL |
+| 300 | a very ... | -0.25 |
++-----+------------+-------+
+";
+        assert_eq!(output, expected);
+    }
+}
@@ -37,6 +37,26 @@ impl<T: NativeType> FromIterator<T> for PrimitiveArray<T> {
     }
 }
 
+impl<T: NativeType> PrimitiveArray<T> {
+    /// Create an array of non-null values.
+    pub fn from_values(data: Vec<T>) -> Self {
+        Self {
+            valid: BitVec::repeat(true, data.len()),
+            data,
+        }
+    }
+
+    /// Returns true if the array has no null value.
+    pub fn no_nulls(&self) -> bool {
+        self.valid.all()
+    }
+
+    /// Returns the values of the array, where the nulls are represented by default values.
+    pub fn values(&self) -> &[T] {
+        &self.data
+    }
+}
+
 impl<T: NativeType> Array for PrimitiveArray<T> {
     type Item = T;
     type Builder = PrimitiveArrayBuilder<T>;
@@ -18,6 +18,15 @@ pub enum AggKind {
     Min,
     Sum,
     Count,
+    StddevPop,
+    StddevSamp,
+    VarPop,
+    VarSamp,
+    BoolAnd,
+    BoolOr,
+    StringAgg,
+    First,
+    Last,
 }
 
 impl std::fmt::Display for AggKind {
@@ -32,6 +41,15 @@ impl std::fmt::Display for AggKind {
                 Max => "max",
                 Min => "min",
                 Sum => "sum",
+                StddevPop => "stddev_pop",
+                StddevSamp => "stddev_samp",
+                VarPop => "var_pop",
+                VarSamp => "var_samp",
+                BoolAnd => "bool_and",
+                BoolOr => "bool_or",
+                StringAgg => "string_agg",
+                First => "first_value",
+                Last => "last_value",
             }
         )
     }
@@ -43,23 +61,26 @@ pub struct BoundAggCall {
     pub kind: AggKind,
     pub args: Vec<BoundExpr>,
     pub return_type: DataType,
-    // TODO: add distinct keyword
+    pub distinct: bool,
 }
 
 impl std::fmt::Debug for BoundAggCall {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{:?}({:?}) -> {:?}",
-            self.kind, self.args, self.return_type
+            "{:?}({}{:?}) -> {:?}",
+            self.kind,
+            if self.distinct { "distinct " } else { "" },
+            self.args,
+            self.return_type
         )
     }
 }
 
 impl Binder {
     pub fn bind_function(&mut self, func: &Function) -> Result<BoundExpr, BindError> {
-        // TODO: Support scalar function
         let mut args = Vec::new();
+        let mut is_wildcard = false;
         for arg in &func.args {
             let arg = match &arg {
                 FunctionArg::Named { arg, .. } => arg,
@@ -70,79 +91,324 @@ impl Binder {
                 FunctionArgExpr::Wildcard => {
                     // No argument in row count
                     args.clear();
+                    is_wildcard = true;
                     break;
                 }
-                _ => todo!("Support aggregate argument: {:?}", arg),
+                _ => return Err(BindError::UnsupportedAggregateArgument(arg.to_string())),
             }
         }
-        let (kind, return_type) = match func.name.to_string().to_lowercase().as_str() {
-            "avg" => (
-                AggKind::Avg,
-                Some(DataType::new(DataTypeKind::Double, false)),
-            ),
-            "count" => {
-                if args.is_empty() {
-                    for ref_id in self.context.regular_tables.values() {
-                        let table = self.catalog.get_table(ref_id).unwrap();
-                        if let Some(col) = table.get_column_by_id(0) {
-                            let column_ref_id = ColumnRefId::from_table(*ref_id, col.id());
-                            self.record_regular_table_column(
-                                &table.name(),
-                                col.name(),
-                                col.id(),
-                                col.desc().clone(),
-                            );
-                            let expr = BoundExpr::ColumnRef(BoundColumnRef {
-                                table_name: table.name(),
-                                column_ref_id,
-                                is_primary_key: col.is_primary(),
-                                desc: col.desc().clone(),
-                            });
-                            args.push(expr);
-                            break;
-                        }
+        let name = func.name.to_string().to_lowercase();
+        if let Some(kind) = ScalarFunctionKind::from_name(&name) {
+            return self.bind_scalar_function(kind, args);
+        }
+
+        let kind = match name.as_str() {
+            "avg" => AggKind::Avg,
+            "count" if is_wildcard => AggKind::RowCount,
+            "count" => AggKind::Count,
+            "max" => AggKind::Max,
+            "min" => AggKind::Min,
+            "sum" => AggKind::Sum,
+            "stddev_pop" => AggKind::StddevPop,
+            "stddev_samp" | "stddev" => AggKind::StddevSamp,
+            "var_pop" => AggKind::VarPop,
+            "var_samp" | "variance" => AggKind::VarSamp,
+            "bool_and" => AggKind::BoolAnd,
+            "bool_or" => AggKind::BoolOr,
+            "string_agg" | "group_concat" => AggKind::StringAgg,
+            "first_value" => AggKind::First,
+            "last_value" => AggKind::Last,
+            _ => return Err(BindError::UnsupportedFunction(func.name.to_string())),
+        };
+
+        // Check the number and type of arguments
+        let num_args = match kind {
+            AggKind::RowCount => 0,
+            AggKind::StringAgg => args.len().clamp(1, 2),
+            AggKind::Avg
+            | AggKind::Count
+            | AggKind::Max
+            | AggKind::Min
+            | AggKind::Sum
+            | AggKind::StddevPop
+            | AggKind::StddevSamp
+            | AggKind::VarPop
+            | AggKind::VarSamp
+            | AggKind::BoolAnd
+            | AggKind::BoolOr
+            | AggKind::First
+            | AggKind::Last => 1,
+        };
+        if args.len() != num_args {
+            return Err(BindError::InvalidArgumentCount(name, num_args, args.len()));
+        }
+        if matches!(
+            kind,
+            AggKind::Avg
+                | AggKind::Sum
+                | AggKind::StddevPop
+                | AggKind::StddevSamp
+                | AggKind::VarPop
+                | AggKind::VarSamp
+        ) {
+            let arg_type = args[0].return_type();
+            if !arg_type.as_ref().map_or(false, DataType::is_numeric) {
+                return Err(BindError::TypeMismatch(name, format!("{:?}", arg_type)));
+            }
+        }
+
+        let return_type = match kind {
+            AggKind::Avg => {
+                // Keep the precision of decimal, and divide other numeric types as double to
+                // avoid integer division.
+                let kind = match args[0].return_type().unwrap().kind() {
+                    DataTypeKind::Decimal(p, s) => DataTypeKind::Decimal(p, s),
+                    _ => DataTypeKind::Double,
+                };
+                Some(DataType::new(kind, true))
+            }
+            AggKind::RowCount => {
+                for ref_id in self.context.regular_tables.values() {
+                    let table = self.catalog.get_table(ref_id).unwrap();
+                    if let Some(col) = table.get_column_by_id(0) {
+                        let column_ref_id = ColumnRefId::from_table(*ref_id, col.id());
+                        self.record_regular_table_column(
+                            &table.name(),
+                            col.name(),
+                            col.id(),
+                            col.desc().clone(),
+                        );
+                        let expr = BoundExpr::ColumnRef(BoundColumnRef {
+                            table_name: table.name(),
+                            column_ref_id,
+                            is_primary_key: col.is_primary(),
+                            desc: col.desc().clone(),
+                        });
+                        args.push(expr);
+                        break;
                     }
-                    (
-                        AggKind::RowCount,
-                        Some(DataType::new(DataTypeKind::Int(None), false)),
-                    )
-                } else {
-                    (
-                        AggKind::Count,
-                        Some(DataType::new(DataTypeKind::Int(None), false)),
-                    )
                 }
+                Some(DataType::new(DataTypeKind::Int(None), false))
+            }
+            AggKind::Count => Some(DataType::new(DataTypeKind::Int(None), false)),
+            AggKind::Max | AggKind::Min | AggKind::Sum => args[0].return_type(),
+            AggKind::First | AggKind::Last => args[0]
+                .return_type()
+                .map(|ty| DataType::new(ty.kind(), true)),
+            AggKind::StddevPop | AggKind::StddevSamp | AggKind::VarPop | AggKind::VarSamp => {
+                Some(DataType::new(DataTypeKind::Double, true))
+            }
+            AggKind::BoolAnd | AggKind::BoolOr => {
+                let arg_type = args[0].return_type();
+                if arg_type.as_ref().map(DataType::kind) != Some(DataTypeKind::Boolean) {
+                    return Err(BindError::TypeMismatch(
+                        name.clone(),
+                        format!("{:?}", arg_type),
+                    ));
+                }
+                Some(DataType::new(DataTypeKind::Boolean, true))
+            }
+            AggKind::StringAgg => {
+                // The separator defaults to an empty string
+                if args.len() == 1 {
+                    args.push(BoundExpr::Constant(DataValue::String(String::new())));
+                }
+                if !matches!(args[1], BoundExpr::Constant(DataValue::String(_))) {
+                    return Err(BindError::InvalidExpression(format!(
+                        "separator of {} must be a string literal",
+                        name
+                    )));
+                }
+                let arg_type = args[0].return_type();
+                if !matches!(
+                    arg_type.as_ref().map(DataType::kind),
+                    Some(DataTypeKind::Char(_) | DataTypeKind::Varchar(_) | DataTypeKind::String)
+                ) {
+                    return Err(BindError::TypeMismatch(
+                        name.clone(),
+                        format!("{:?}", arg_type),
+                    ));
+                }
+                Some(DataType::new(DataTypeKind::Varchar(None), true))
             }
-            "max" => (AggKind::Max, args[0].return_type()),
-            "min" => (AggKind::Min, args[0].return_type()),
-            "sum" => (AggKind::Sum, args[0].return_type()),
-            _ => panic!("Unsupported function: {}", func.name),
         };
 
         match kind {
             // Rewrite `avg` into `sum / count`
-            AggKind::Avg => Ok(BoundExpr::BinaryOp(BoundBinaryOp {
-                op: BinaryOperator::Divide,
-                left_expr: Box::new(BoundExpr::AggCall(BoundAggCall {
+            AggKind::Avg => {
+                let ty = return_type.as_ref().unwrap().kind();
+                let sum = BoundExpr::AggCall(BoundAggCall {
                     kind: AggKind::Sum,
                     args: args.clone(),
                     return_type: args[0].return_type().unwrap(),
-                })),
-                right_expr: Box::new(BoundExpr::TypeCast(BoundTypeCast {
-                    ty: args[0].return_type().unwrap().kind(),
-                    expr: Box::new(BoundExpr::AggCall(BoundAggCall {
-                        kind: AggKind::Count,
-                        args,
-                        return_type: DataType::new(DataTypeKind::Int(None), false),
+                    distinct: func.distinct,
+                });
+                let count = BoundExpr::AggCall(BoundAggCall {
+                    kind: AggKind::Count,
+                    args,
+                    return_type: DataType::new(DataTypeKind::Int(None), false),
+                    distinct: func.distinct,
+                });
+                Ok(BoundExpr::BinaryOp(BoundBinaryOp {
+                    op: BinaryOperator::Divide,
+                    left_expr: Box::new(BoundExpr::TypeCast(BoundTypeCast {
+                        ty: ty.clone(),
+                        expr: Box::new(sum),
                     })),
-                })),
-                return_type,
-            })),
+                    right_expr: Box::new(BoundExpr::TypeCast(BoundTypeCast {
+                        ty,
+                        expr: Box::new(count),
+                    })),
+                    return_type,
+                }))
+            }
             _ => Ok(BoundExpr::AggCall(BoundAggCall {
                 kind,
                 args,
                 return_type: return_type.unwrap(),
+                distinct: func.distinct,
             })),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::binder::BoundStatement;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::parse;
+    use crate::types::DataTypeExt;
+
+    fn create_binder() -> Binder {
+        let catalog = Arc::new(RootCatalog::new());
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        schema
+            .add_table(
+                "t".into(),
+                vec![
+                    ColumnCatalog::new(0, DataTypeKind::Int(None).not_null().to_column("a".into())),
+                    ColumnCatalog::new(1, DataTypeKind::Boolean.nullable().to_column("b".into())),
+                ],
+                false,
+            )
+            .unwrap();
+        Binder::new(catalog)
+    }
+
+    #[test]
+    fn bind_unsupported_function() {
+        let mut binder = create_binder();
+        let sql = "
+            select foo(a) from t;
+            select count(t.*) from t;";
+        let stmts = parse(sql).unwrap();
+
+        assert_eq!(
+            binder.bind(&stmts[0]),
+            Err(BindError::UnsupportedFunction("foo".into()))
+        );
+        assert!(matches!(
+            binder.bind(&stmts[1]),
+            Err(BindError::UnsupportedAggregateArgument(_))
+        ));
+    }
+
+    #[test]
+    fn bind_bool_and_or() {
+        let mut binder = create_binder();
+        let sql = "
+            select bool_and(b) from t;
+            select bool_or(b) from t;
+            select bool_and(a) from t;
+            select bool_or(a) from t;";
+        let stmts = parse(sql).unwrap();
+
+        for (stmt, kind) in stmts.iter().zip([AggKind::BoolAnd, AggKind::BoolOr]) {
+            match binder.bind(stmt).unwrap() {
+                BoundStatement::Select(select) => match &select.select_list[0] {
+                    BoundExpr::AggCall(agg) => {
+                        assert_eq!(agg.kind, kind);
+                        assert_eq!(agg.return_type, DataTypeKind::Boolean.nullable());
+                    }
+                    expr => panic!("not an aggregation: {:?}", expr),
+                },
+                _ => unreachable!(),
+            }
+        }
+        assert!(matches!(
+            binder.bind(&stmts[2]),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            binder.bind(&stmts[3]),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+    }
+
+    #[test]
+    fn bind_invalid_argument_count() {
+        let mut binder = create_binder();
+        for name in [
+            "avg",
+            "count",
+            "max",
+            "min",
+            "sum",
+            "first_value",
+            "last_value",
+            "stddev_pop",
+            "stddev_samp",
+            "var_pop",
+            "var_samp",
+            "bool_and",
+            "bool_or",
+        ] {
+            let sql = format!("select {name}() from t; select {name}(a, a) from t;");
+            let stmts = parse(&sql).unwrap();
+            assert_eq!(
+                binder.bind(&stmts[0]),
+                Err(BindError::InvalidArgumentCount(name.into(), 1, 0))
+            );
+            assert_eq!(
+                binder.bind(&stmts[1]),
+                Err(BindError::InvalidArgumentCount(name.into(), 1, 2))
+            );
+        }
+
+        let sql = "
+            select string_agg() from t;
+            select string_agg(a, ',', ',') from t;";
+        let stmts = parse(sql).unwrap();
+        assert_eq!(
+            binder.bind(&stmts[0]),
+            Err(BindError::InvalidArgumentCount("string_agg".into(), 1, 0))
+        );
+        assert_eq!(
+            binder.bind(&stmts[1]),
+            Err(BindError::InvalidArgumentCount("string_agg".into(), 2, 3))
+        );
+    }
+
+    #[test]
+    fn bind_non_numeric_sum() {
+        let mut binder = create_binder();
+        let sql = "
+            select sum(b) from t;
+            select avg(b) from t;
+            select sum(a) from t;";
+        let stmts = parse(sql).unwrap();
+        assert!(matches!(
+            binder.bind(&stmts[0]),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            binder.bind(&stmts[1]),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        binder.bind(&stmts[2]).unwrap();
+    }
+}
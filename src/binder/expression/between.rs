@@ -0,0 +1,66 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use serde::Serialize;
+
+use super::*;
+
+/// A bound `between` expression, e.g. `x between low and high`.
+///
+/// It is equivalent to `x >= low and x <= high`, or `x < low or x > high` if negated, except that
+/// `x` is evaluated only once.
+#[derive(PartialEq, Clone, Serialize)]
+pub struct BoundBetween {
+    pub expr: Box<BoundExpr>,
+    pub negated: bool,
+    pub low: Box<BoundExpr>,
+    pub high: Box<BoundExpr>,
+}
+
+impl std::fmt::Debug for BoundBetween {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} {}between {:?} and {:?}",
+            self.expr,
+            if self.negated { "not " } else { "" },
+            self.low,
+            self.high
+        )
+    }
+}
+
+impl Binder {
+    /// Bind a `between` expression. All operands are casted to their common type.
+    pub fn bind_between(
+        &mut self,
+        expr: &Expr,
+        negated: bool,
+        low: &Expr,
+        high: &Expr,
+    ) -> Result<BoundExpr, BindError> {
+        let mut expr = self.bind_expr(expr)?;
+        let mut low = self.bind_expr(low)?;
+        let mut high = self.bind_expr(high)?;
+
+        let mut ty: Option<DataType> = None;
+        for t in [&expr, &low, &high].iter().filter_map(|e| e.return_type()) {
+            ty = match ty {
+                None => Some(t),
+                Some(ty) => Some(coerce(&ty, &t).ok_or_else(|| {
+                    BindError::TypeMismatch(format!("{:?}", ty), format!("{:?}", t))
+                })?),
+            };
+        }
+        if let Some(ty) = &ty {
+            cast_to(&mut expr, ty);
+            cast_to(&mut low, ty);
+            cast_to(&mut high, ty);
+        }
+        Ok(BoundExpr::Between(BoundBetween {
+            expr: Box::new(expr),
+            negated,
+            low: Box::new(low),
+            high: Box::new(high),
+        }))
+    }
+}
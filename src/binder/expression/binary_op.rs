@@ -4,7 +4,7 @@ use serde::Serialize;
 
 use super::*;
 use crate::parser::BinaryOperator;
-use crate::types::{DataTypeExt, DataTypeKind};
+use crate::types::{DataTypeExt, DataTypeKind, PhysicalDataTypeKind};
 
 /// A bound binary operation expression.
 #[derive(PartialEq, Clone, Serialize)]
@@ -35,8 +35,24 @@ impl Binder {
         use BinaryOperator as Op;
 
         use crate::types::PhysicalDataTypeKind::*;
-        let mut left_bound_expr = self.bind_expr(left)?;
-        let mut right_bound_expr = self.bind_expr(right)?;
+        if *op == Op::StringConcat && self.concat_skips_nulls {
+            let args = vec![self.bind_expr(left)?, self.bind_expr(right)?];
+            return self.bind_scalar_function(ScalarFunctionKind::Concat, args);
+        }
+        let (mut left_bound_expr, mut right_bound_expr) = self.bind_operands(left, right)?;
+
+        // An untyped NULL adopts the type implied by the operator, or the type of the other side.
+        let null_type = |other: Option<DataType>| match op {
+            Op::And | Op::Or => DataTypeKind::Boolean.nullable(),
+            Op::StringConcat => DataTypeKind::String.nullable(),
+            _ => other.unwrap_or_else(default_null_type),
+        };
+        let (left_type, right_type) = (
+            left_bound_expr.return_type(),
+            right_bound_expr.return_type(),
+        );
+        type_null(&mut left_bound_expr, &null_type(right_type));
+        type_null(&mut right_bound_expr, &null_type(left_type));
 
         // Implicit type cast
         let left_data_type_kind = match (
@@ -44,37 +60,45 @@ impl Binder {
             right_bound_expr.return_type(),
         ) {
             (Some(left_data_type), Some(right_data_type)) => {
-                let left_physical_kind = left_data_type.physical_kind();
-                let right_physical_kind = right_data_type.physical_kind();
-                // Check if implicit type conversion is needed
-                if left_physical_kind != right_physical_kind {
-                    // Insert type cast expr
-                    match (left_physical_kind, right_physical_kind) {
-                        (Float64 | Decimal, Int32 | Int64)
-                        | (Date, String)
-                        | (Decimal, Float64) => {
-                            right_bound_expr = BoundExpr::TypeCast(BoundTypeCast {
-                                expr: Box::new(right_bound_expr),
-                                ty: left_data_type.kind(),
-                            });
-                        }
-                        (Int32 | Int64, Float64 | Decimal)
-                        | (String, Date)
-                        | (Float64, Decimal) => {
-                            left_bound_expr = BoundExpr::TypeCast(BoundTypeCast {
-                                expr: Box::new(left_bound_expr),
-                                ty: right_data_type.kind(),
-                            });
-                        }
-                        (Date, Interval) => {}
-                        (left_kind, right_kind) => todo!(
-                            "Support implicit conversion of {:?} and {:?}",
-                            left_kind,
-                            right_kind
-                        ),
+                match (
+                    left_data_type.physical_kind(),
+                    right_data_type.physical_kind(),
+                ) {
+                    (String, _) | (_, String) if *op == Op::StringConcat => {
+                        // the other side is converted to its string representation
+                        cast_to_physical(&mut left_bound_expr, DataTypeKind::String);
+                        cast_to_physical(&mut right_bound_expr, DataTypeKind::String);
+                        Some(DataTypeKind::Varchar(None).nullable())
+                    }
+                    _ if *op == Op::StringConcat => {
+                        return Err(BindError::BinaryOpTypeMismatch(
+                            format!("{:?}", left_data_type),
+                            format!("{:?}", right_data_type),
+                        ))
+                    }
+                    (Date, Interval) => Some(left_data_type.kind().nullable()),
+                    (Interval, Date) if *op == Op::Plus => {
+                        // `interval + date` is the same as `date + interval`
+                        std::mem::swap(&mut left_bound_expr, &mut right_bound_expr);
+                        return Ok(BoundExpr::BinaryOp(BoundBinaryOp {
+                            op: op.clone(),
+                            left_expr: left_bound_expr.into(),
+                            right_expr: right_bound_expr.into(),
+                            return_type: Some(right_data_type.kind().nullable()),
+                        }));
+                    }
+                    _ => {
+                        let ty = coerce(&left_data_type, &right_data_type).ok_or_else(|| {
+                            BindError::BinaryOpTypeMismatch(
+                                format!("{:?}", left_data_type),
+                                format!("{:?}", right_data_type),
+                            )
+                        })?;
+                        cast_to_physical(&mut left_bound_expr, ty.kind());
+                        cast_to_physical(&mut right_bound_expr, ty.kind());
+                        Some(ty.kind().nullable())
                     }
                 }
-                Some(left_data_type.kind().nullable())
             }
             (None, None) => None,
             (left, right) => {
@@ -86,10 +110,21 @@ impl Binder {
         };
 
         let return_type = match op {
-            Op::Plus | Op::Minus | Op::Multiply | Op::Divide | Op::Modulo => left_data_type_kind,
+            Op::Plus | Op::Minus | Op::Multiply | Op::Divide | Op::Modulo => {
+                match (
+                    left_bound_expr.return_type().map(|ty| ty.kind()),
+                    right_bound_expr.return_type().map(|ty| ty.kind()),
+                ) {
+                    (Some(DataTypeKind::Decimal(p1, s1)), Some(DataTypeKind::Decimal(p2, s2))) => {
+                        Some(decimal_result_type(op, (p1, s1), (p2, s2)).nullable())
+                    }
+                    _ => left_data_type_kind,
+                }
+            }
             Op::Gt | Op::GtEq | Op::Lt | Op::LtEq | Op::Eq | Op::NotEq | Op::And | Op::Or => {
                 Some(DataTypeKind::Boolean.nullable())
             }
+            Op::StringConcat => left_data_type_kind,
             _ => todo!("Support more binary operators"),
         };
         Ok(BoundExpr::BinaryOp(BoundBinaryOp {
@@ -100,3 +135,227 @@ impl Binder {
         }))
     }
 }
+
+/// Cast the expression to the type if their physical kinds are different.
+///
+/// Unlike [`cast_to`], types of the same physical kind are kept, e.g. the precision and scale of
+/// decimals, which decide the result type of arithmetic operations.
+pub(super) fn cast_to_physical(expr: &mut BoundExpr, ty: DataTypeKind) {
+    match expr.return_type() {
+        Some(t) if t.physical_kind() != PhysicalDataTypeKind::from(ty.clone()) => {
+            cast_to(expr, &ty.nullable())
+        }
+        _ => {}
+    }
+}
+
+/// Maximum precision of decimals supported by `rust_decimal`.
+const DECIMAL_MAX_PRECISION: u64 = 28;
+
+/// Get the result type of an arithmetic operation on two decimals.
+///
+/// The precision and scale are widened so that the result can be represented exactly, and the
+/// precision is capped to [`DECIMAL_MAX_PRECISION`]. If the precision of either side is
+/// unspecified, the precision and scale of the result are unspecified too.
+fn decimal_result_type(
+    op: &BinaryOperator,
+    (p1, s1): (Option<u64>, Option<u64>),
+    (p2, s2): (Option<u64>, Option<u64>),
+) -> DataTypeKind {
+    let (p1, s1, p2, s2) = match (p1, p2) {
+        (Some(p1), Some(p2)) => (p1, s1.unwrap_or(0), p2, s2.unwrap_or(0)),
+        _ => return DataTypeKind::Decimal(None, None),
+    };
+    // integer digits of both sides
+    let (i1, i2) = (p1.saturating_sub(s1), p2.saturating_sub(s2));
+    let (precision, scale) = match op {
+        BinaryOperator::Plus | BinaryOperator::Minus => {
+            let scale = s1.max(s2);
+            (i1.max(i2) + scale + 1, scale)
+        }
+        BinaryOperator::Multiply => (p1 + p2 + 1, s1 + s2),
+        BinaryOperator::Divide => {
+            let scale = 6.max(s1 + p2 + 1);
+            (i1 + s2 + scale, scale)
+        }
+        BinaryOperator::Modulo => {
+            let scale = s1.max(s2);
+            (i1.min(i2) + scale, scale)
+        }
+        _ => unreachable!("not an arithmetic operator: {:?}", op),
+    };
+    let precision = precision.min(DECIMAL_MAX_PRECISION);
+    DataTypeKind::Decimal(Some(precision), Some(scale.min(precision)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::RootCatalog;
+    use crate::parser::Value;
+
+    fn cast(value: Value, data_type: DataTypeKind) -> Expr {
+        Expr::Cast {
+            expr: Box::new(Expr::Value(value)),
+            data_type,
+        }
+    }
+
+    fn operand_types(expr: &BoundExpr) -> (DataTypeKind, DataTypeKind) {
+        match expr {
+            BoundExpr::BinaryOp(op) => (
+                op.left_expr.return_type().unwrap().kind(),
+                op.right_expr.return_type().unwrap().kind(),
+            ),
+            _ => panic!("not a binary operation: {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn test_bind_numeric_coercion() {
+        use DataTypeKind as T;
+        let mut binder = Binder::new(Arc::new(RootCatalog::new()));
+        let one = |ty: &T| cast(Value::Number("1".into(), false), ty.clone());
+        // numeric types from the narrowest to the widest
+        let types = [
+            T::Int(None),
+            T::BigInt(None),
+            T::Decimal(Some(10), Some(2)),
+            T::Double,
+        ];
+        for (i, left) in types.iter().enumerate() {
+            for (j, right) in types.iter().enumerate() {
+                let expected = &types[i.max(j)];
+                for op in [BinaryOperator::Plus, BinaryOperator::Lt] {
+                    let expr = binder.bind_binary_op(&one(left), &op, &one(right)).unwrap();
+                    // the narrower side is casted to the wider type
+                    assert_eq!(
+                        operand_types(&expr),
+                        (expected.clone(), expected.clone()),
+                        "{:?} {:?} {:?}",
+                        left,
+                        op,
+                        right
+                    );
+                    if op == BinaryOperator::Plus && i != j {
+                        assert_eq!(expr.return_type(), Some(expected.clone().nullable()));
+                    }
+                }
+            }
+        }
+
+        // numbers and strings are not coerced
+        let string = Expr::Value(Value::SingleQuotedString("1".into()));
+        let err = binder.bind_binary_op(&one(&T::Int(None)), &BinaryOperator::Plus, &string);
+        assert!(matches!(err, Err(BindError::BinaryOpTypeMismatch(_, _))));
+    }
+
+    #[test]
+    fn test_bind_null() {
+        use DataTypeKind as T;
+        let mut binder = Binder::new(Arc::new(RootCatalog::new()));
+        let null = Expr::Value(Value::Null);
+        let double = cast(Value::Number("1".into(), false), T::Double);
+
+        // NULL adopts the type of the other side
+        for (left, right) in [(&null, &double), (&double, &null)] {
+            let expr = binder
+                .bind_binary_op(left, &BinaryOperator::Plus, right)
+                .unwrap();
+            assert_eq!(operand_types(&expr), (T::Double, T::Double));
+            assert_eq!(expr.return_type(), Some(T::Double.nullable()));
+            let expr = binder
+                .bind_binary_op(left, &BinaryOperator::Eq, right)
+                .unwrap();
+            assert_eq!(operand_types(&expr), (T::Double, T::Double));
+            assert_eq!(expr.return_type(), Some(T::Boolean.nullable()));
+        }
+
+        // or the type implied by the operator, or the default type
+        let expr = (binder.bind_binary_op(&null, &BinaryOperator::And, &null)).unwrap();
+        assert_eq!(operand_types(&expr), (T::Boolean, T::Boolean));
+        let expr = (binder.bind_binary_op(&null, &BinaryOperator::StringConcat, &null)).unwrap();
+        assert_eq!(operand_types(&expr), (T::String, T::String));
+        let expr = (binder.bind_binary_op(&null, &BinaryOperator::Plus, &null)).unwrap();
+        let default = default_null_type().kind();
+        assert_eq!(operand_types(&expr), (default.clone(), default.clone()));
+        assert_eq!(expr.return_type(), Some(default.nullable()));
+    }
+
+    #[test]
+    fn test_bind_string_concat() {
+        let mut binder = Binder::new(Arc::new(RootCatalog::new()));
+        let string = Expr::Value(Value::SingleQuotedString("a".into()));
+        let int = Expr::Value(Value::Number("1".into(), false));
+        let concat = BinaryOperator::StringConcat;
+
+        let varchar = DataValue::String("a".into()).data_type().unwrap().kind();
+
+        // the other side is casted to string
+        let expr = binder.bind_binary_op(&string, &concat, &int).unwrap();
+        assert_eq!(
+            operand_types(&expr),
+            (varchar.clone(), DataTypeKind::String)
+        );
+        assert_eq!(
+            expr.return_type(),
+            Some(DataTypeKind::Varchar(None).nullable())
+        );
+        let expr = binder.bind_binary_op(&int, &concat, &string).unwrap();
+        assert_eq!(operand_types(&expr), (DataTypeKind::String, varchar));
+
+        // at least one side should be a string
+        let err = binder.bind_binary_op(&int, &concat, &int);
+        assert!(matches!(err, Err(BindError::BinaryOpTypeMismatch(_, _))));
+
+        // NULLs are skipped as in `concat` if configured
+        let mut binder = Binder::new(Arc::new(RootCatalog::new())).with_concat_skips_nulls(true);
+        let null = Expr::Value(Value::Null);
+        let expr = binder.bind_binary_op(&null, &concat, &int).unwrap();
+        match &expr {
+            BoundExpr::ScalarFunction(func) => assert_eq!(func.kind, ScalarFunctionKind::Concat),
+            expr => panic!("not a scalar function: {:?}", expr),
+        }
+        assert_eq!(
+            expr.return_type(),
+            Some(DataTypeKind::Varchar(None).not_null())
+        );
+    }
+
+    #[test]
+    fn test_decimal_result_type() {
+        let ty = |p, s| (Some(p), Some(s));
+        let decimal = |p, s| DataTypeKind::Decimal(Some(p), Some(s));
+        assert_eq!(
+            decimal_result_type(&BinaryOperator::Plus, ty(10, 2), ty(5, 3)),
+            decimal(12, 3)
+        );
+        assert_eq!(
+            decimal_result_type(&BinaryOperator::Minus, ty(10, 2), ty(10, 2)),
+            decimal(11, 2)
+        );
+        assert_eq!(
+            decimal_result_type(&BinaryOperator::Multiply, ty(10, 2), ty(5, 3)),
+            decimal(16, 5)
+        );
+        assert_eq!(
+            decimal_result_type(&BinaryOperator::Divide, ty(10, 2), ty(5, 3)),
+            decimal(19, 8)
+        );
+        assert_eq!(
+            decimal_result_type(&BinaryOperator::Modulo, ty(10, 2), ty(5, 3)),
+            decimal(5, 3)
+        );
+        // precision is capped
+        assert_eq!(
+            decimal_result_type(&BinaryOperator::Multiply, ty(20, 2), ty(20, 2)),
+            decimal(28, 4)
+        );
+        assert_eq!(
+            decimal_result_type(&BinaryOperator::Plus, (None, None), ty(5, 3)),
+            DataTypeKind::Decimal(None, None)
+        );
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use super::*;
+
+/// A bound `case` expression.
+#[derive(PartialEq, Clone, Serialize)]
+pub struct BoundCase {
+    /// The pairs of condition and result. The result of the first true condition is returned.
+    pub when_then: Vec<(BoundExpr, BoundExpr)>,
+    /// The result if no condition is true. NULL is returned if it is `None`.
+    pub else_result: Option<Box<BoundExpr>>,
+    pub return_type: Option<DataType>,
+}
+
+impl std::fmt::Debug for BoundCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "case")?;
+        for (cond, result) in &self.when_then {
+            write!(f, " when {:?} then {:?}", cond, result)?;
+        }
+        if let Some(else_result) = &self.else_result {
+            write!(f, " else {:?}", else_result)?;
+        }
+        write!(f, " end")
+    }
+}
+
+impl Binder {
+    /// Bind a `case` expression. The simple form `case x when v then ...` is bound as
+    /// `case when x = v then ...`.
+    pub fn bind_case(
+        &mut self,
+        operand: Option<&Expr>,
+        conditions: &[Expr],
+        results: &[Expr],
+        else_result: Option<&Expr>,
+    ) -> Result<BoundExpr, BindError> {
+        let mut when_then = vec![];
+        for (cond, result) in conditions.iter().zip_eq(results) {
+            let cond = match operand {
+                Some(operand) => self.bind_binary_op(operand, &BinaryOperator::Eq, cond)?,
+                None => self.bind_expr(cond)?,
+            };
+            when_then.push((cond, self.bind_expr(result)?));
+        }
+        let mut else_result = match else_result {
+            Some(expr) => Some(self.bind_expr(expr)?),
+            None => None,
+        };
+
+        // the result type is the common type of all branches
+        let mut return_type: Option<DataType> = None;
+        let results = when_then.iter().map(|(_, r)| r).chain(else_result.iter());
+        for ty in results.filter_map(|r| r.return_type()) {
+            return_type = match return_type {
+                None => Some(ty),
+                Some(t) => Some(coerce(&t, &ty).ok_or_else(|| {
+                    BindError::TypeMismatch(format!("{:?}", t), format!("{:?}", ty))
+                })?),
+            };
+        }
+        if let Some(ty) = &mut return_type {
+            if else_result.is_none() {
+                ty.nullable = true;
+            }
+            for (_, result) in &mut when_then {
+                cast_to(result, ty);
+            }
+            if let Some(result) = &mut else_result {
+                cast_to(result, ty);
+            }
+        }
+        Ok(BoundExpr::Case(BoundCase {
+            when_then,
+            else_result: else_result.map(Box::new),
+            return_type,
+        }))
+    }
+}
@@ -22,32 +22,71 @@ impl std::fmt::Debug for BoundColumnRef {
 }
 
 impl Binder {
+    /// Bind all columns of the tables in the `FROM` clause, e.g. `*`, in the order of tables.
     pub fn bind_all_column_refs(&mut self) -> Result<Vec<BoundExpr>, BindError> {
         let mut exprs = vec![];
-        for ref_id in self.context.regular_tables.values().cloned().collect_vec() {
-            let table = self.catalog.get_table(&ref_id).unwrap();
-            for (col_id, col) in &table.all_columns() {
-                let column_ref_id = ColumnRefId::from_table(ref_id, *col_id);
-                self.record_regular_table_column(
-                    &table.name(),
-                    col.name(),
-                    *col_id,
-                    col.desc().clone(),
-                );
-                let expr = BoundExpr::ColumnRef(BoundColumnRef {
-                    table_name: table.name().clone(),
-                    column_ref_id,
-                    is_primary_key: col.is_primary(),
-                    desc: col.desc().clone(),
-                });
-                exprs.push(expr);
-            }
+        for table_name in self.context.table_names.clone() {
+            exprs.extend(self.bind_table_column_refs(&table_name)?);
         }
+        Ok(exprs)
+    }
 
+    /// Bind all columns of a table in the `FROM` clause, e.g. `t.*`.
+    pub fn bind_table_column_refs(
+        &mut self,
+        table_name: &str,
+    ) -> Result<Vec<BoundExpr>, BindError> {
+        if let Some(values) = self.context.values_tables.get(table_name) {
+            return Ok((values.column_descs.iter())
+                .map(|desc| Self::bind_values_column_ref(values, desc.name()).unwrap())
+                .collect());
+        }
+        let ref_id = *(self.context.regular_tables.get(table_name))
+            .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
+        let table = self.catalog.get_table(&ref_id).unwrap();
+        let mut exprs = vec![];
+        for (col_id, col) in &table.all_columns() {
+            let column_ref_id = ColumnRefId::from_table(ref_id, *col_id);
+            self.record_regular_table_column(table_name, col.name(), *col_id, col.desc().clone());
+            exprs.push(BoundExpr::ColumnRef(BoundColumnRef {
+                table_name: table_name.into(),
+                column_ref_id,
+                is_primary_key: col.is_primary(),
+                desc: col.desc().clone(),
+            }));
+        }
         Ok(exprs)
     }
 
+    /// Bind a column reference. In a subquery, columns not found in the subquery are bound to
+    /// the outer query, and recorded as outer references of the subquery.
     pub fn bind_column_ref(&mut self, idents: &[Ident]) -> Result<BoundExpr, BindError> {
+        let err = match self.bind_column_ref_in_context(idents) {
+            Err(err @ (BindError::InvalidColumn(_) | BindError::InvalidTable(_))) => err,
+            ret => return ret,
+        };
+        if self.outer_refs.is_none() {
+            return Err(err);
+        }
+        // bind the column in the context of the outer query
+        let outer_context = self.upper_contexts.pop().unwrap();
+        let inner_context = std::mem::replace(&mut self.context, outer_context);
+        let ret = self.bind_column_ref_in_context(idents);
+        let outer_context = std::mem::replace(&mut self.context, inner_context);
+        self.upper_contexts.push(outer_context);
+        match ret {
+            Ok(BoundExpr::ColumnRef(column_ref)) => {
+                let outer_refs = self.outer_refs.as_mut().unwrap();
+                if !outer_refs.contains(&column_ref) {
+                    outer_refs.push(column_ref.clone());
+                }
+                Ok(BoundExpr::ColumnRef(column_ref))
+            }
+            _ => Err(err),
+        }
+    }
+
+    fn bind_column_ref_in_context(&mut self, idents: &[Ident]) -> Result<BoundExpr, BindError> {
         let idents = idents
             .iter()
             .map(|ident| Ident::new(ident.value.to_lowercase()))
@@ -59,6 +98,10 @@ impl Binder {
             _ => return Err(BindError::InvalidTableName(idents)),
         };
         if let Some(name) = table_name {
+            if let Some(values) = self.context.values_tables.get(name) {
+                return Self::bind_values_column_ref(values, column_name)
+                    .ok_or_else(|| BindError::InvalidColumn(column_name.clone()));
+            }
             if !self.context.regular_tables.contains_key(name) {
                 return Err(BindError::InvalidTable(name.clone()));
             }
@@ -76,8 +119,23 @@ impl Binder {
                 desc: col.desc().clone(),
             }))
         } else {
+            // the aliases of output columns shadow the columns of tables
+            if self.context.aliases_visible {
+                match (self.context.aliases.iter())
+                    .filter(|alias| *alias == column_name)
+                    .count()
+                {
+                    0 => {}
+                    1 => {
+                        return Ok(BoundExpr::Alias(BoundAlias {
+                            alias: column_name.clone(),
+                        }))
+                    }
+                    _ => return Err(BindError::AmbiguousColumn),
+                }
+            }
             let mut info = None;
-            for ref_id in self.context.regular_tables.values() {
+            for (name, ref_id) in &self.context.regular_tables {
                 let table = self.catalog.get_table(ref_id).unwrap();
                 if let Some(col) = table.get_column_by_name(column_name) {
                     if info.is_some() {
@@ -85,38 +143,40 @@ impl Binder {
                     }
                     let column_ref_id = ColumnRefId::from_table(*ref_id, col.id());
                     info = Some((
-                        table.name().clone(),
+                        name.clone(),
                         column_ref_id,
                         col.is_primary(),
                         col.desc().clone(),
                     ));
                 }
             }
-            if info == None {
-                if self.context.aliases.contains(column_name) {
-                    Ok(BoundExpr::Alias(BoundAlias {
-                        alias: column_name.clone(),
-                    }))
-                } else {
-                    Err(BindError::InvalidColumn(column_name.clone()))
+            let mut values_column = None;
+            for values in self.context.values_tables.values() {
+                if let Some(expr) = Self::bind_values_column_ref(values, column_name) {
+                    if info.is_some() || values_column.is_some() {
+                        return Err(BindError::AmbiguousColumn);
+                    }
+                    values_column = Some(expr);
                 }
-            } else {
-                let (table_name, column_ref_id, is_primary_key, desc) =
-                    info.ok_or_else(|| BindError::InvalidColumn(column_name.clone()))?;
-                self.record_regular_table_column(
-                    &table_name,
-                    column_name,
-                    column_ref_id.column_id,
-                    desc.clone(),
-                );
-
-                Ok(BoundExpr::ColumnRef(BoundColumnRef {
-                    table_name: table_name.clone(),
-                    column_ref_id,
-                    is_primary_key,
-                    desc,
-                }))
             }
+            if let Some(expr) = values_column {
+                return Ok(expr);
+            }
+            let (table_name, column_ref_id, is_primary_key, desc) =
+                info.ok_or_else(|| BindError::InvalidColumn(column_name.clone()))?;
+            self.record_regular_table_column(
+                &table_name,
+                column_name,
+                column_ref_id.column_id,
+                desc.clone(),
+            );
+
+            Ok(BoundExpr::ColumnRef(BoundColumnRef {
+                table_name,
+                column_ref_id,
+                is_primary_key,
+                desc,
+            }))
         }
     }
 
@@ -144,3 +204,142 @@ impl Binder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::{parse, Statement};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    /// Create tables `foo(a, b)` and `bar(a, c)`.
+    fn catalog() -> Arc<RootCatalog> {
+        let catalog = Arc::new(RootCatalog::new());
+        let schema = (catalog.get_database_by_id(0).unwrap())
+            .get_schema_by_id(0)
+            .unwrap();
+        for (name, columns) in [("foo", ["a", "b"]), ("bar", ["a", "c"])] {
+            let columns = (columns.iter().enumerate())
+                .map(|(id, name)| {
+                    let desc = DataTypeKind::Int(None)
+                        .nullable()
+                        .to_column(name.to_string());
+                    ColumnCatalog::new(id as ColumnId, desc)
+                })
+                .collect();
+            schema.add_table(name.into(), columns, false).unwrap();
+        }
+        catalog
+    }
+
+    fn bind(catalog: &Arc<RootCatalog>, sql: &str) -> Result<Box<BoundSelect>, BindError> {
+        let stmts = parse(sql).unwrap();
+        match &stmts[0] {
+            Statement::Query(query) => Binder::new(catalog.clone()).bind_select(query),
+            _ => panic!("not a query"),
+        }
+    }
+
+    fn table_name(expr: &BoundExpr) -> &str {
+        match expr {
+            BoundExpr::ColumnRef(column_ref) => &column_ref.table_name,
+            _ => panic!("not a column reference: {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn bind_table_alias() {
+        let catalog = catalog();
+        let select = bind(&catalog, "select t1.a, b from foo as t1").unwrap();
+        assert_eq!(table_name(&select.select_list[0]), "t1");
+        assert_eq!(table_name(&select.select_list[1]), "t1");
+        let select = bind(&catalog, "select * from foo t1").unwrap();
+        assert_eq!(table_name(&select.select_list[0]), "t1");
+
+        // the table name is hidden by the alias
+        assert!(matches!(
+            bind(&catalog, "select foo.a from foo as t1"),
+            Err(BindError::InvalidTable(_))
+        ));
+        assert!(matches!(
+            bind(&catalog, "select * from foo as t1, bar as t1"),
+            Err(BindError::DuplicatedTable(_))
+        ));
+    }
+
+    #[test]
+    fn bind_wildcard() {
+        let catalog = catalog();
+        let names = |select: &BoundSelect| {
+            (select.select_list.iter())
+                .map(|expr| match expr {
+                    BoundExpr::ColumnRef(column_ref) => {
+                        format!("{}.{}", column_ref.table_name, column_ref.desc.name())
+                    }
+                    _ => panic!("not a column reference: {:?}", expr),
+                })
+                .collect::<Vec<_>>()
+        };
+        // columns follow the order of tables in the `FROM` clause
+        let select = bind(&catalog, "select * from bar, foo").unwrap();
+        assert_eq!(names(&select), ["bar.a", "bar.c", "foo.a", "foo.b"]);
+        let select = bind(&catalog, "select foo.* from bar, foo").unwrap();
+        assert_eq!(names(&select), ["foo.a", "foo.b"]);
+        let select = bind(
+            &catalog,
+            "select t.*, bar.c from foo as t join bar on t.a = bar.a",
+        );
+        assert_eq!(names(&select.unwrap()), ["t.a", "t.b", "bar.c"]);
+        let select = bind(&catalog, "select v.*, foo.* from foo, (values (1)) as v(x)").unwrap();
+        assert_eq!(names(&select), ["v.x", "foo.a", "foo.b"]);
+
+        assert!(matches!(
+            bind(&catalog, "select baz.* from foo"),
+            Err(BindError::InvalidTable(_))
+        ));
+    }
+
+    #[test]
+    fn bind_column_alias_in_order_by() {
+        let catalog = catalog();
+        let select = bind(&catalog, "select a as x from foo order by x").unwrap();
+        assert!(matches!(select.orderby[0].expr, BoundExpr::Alias(_)));
+
+        // the alias of an output column shadows the column of the table
+        let select = bind(&catalog, "select b as a, a as b from foo order by a").unwrap();
+        assert_eq!(
+            select.orderby[0].expr,
+            BoundExpr::Alias(BoundAlias { alias: "a".into() })
+        );
+        let select = bind(&catalog, "select b as a from foo order by foo.a").unwrap();
+        assert!(matches!(select.orderby[0].expr, BoundExpr::ColumnRef(_)));
+
+        // aliases are only visible in `ORDER BY`
+        assert!(matches!(
+            bind(&catalog, "select a as x, x from foo"),
+            Err(BindError::InvalidColumn(_))
+        ));
+        assert!(matches!(
+            bind(&catalog, "select a as x from foo where x = 1"),
+            Err(BindError::InvalidColumn(_))
+        ));
+    }
+
+    #[test]
+    fn bind_ambiguous_column() {
+        let catalog = catalog();
+        assert!(matches!(
+            bind(&catalog, "select a from foo, bar"),
+            Err(BindError::AmbiguousColumn)
+        ));
+        assert!(matches!(
+            bind(&catalog, "select a as x, b as x from foo order by x"),
+            Err(BindError::AmbiguousColumn)
+        ));
+        let select = bind(&catalog, "select f.a, b, c from foo as f, bar").unwrap();
+        assert_eq!(table_name(&select.select_list[0]), "f");
+        assert_eq!(table_name(&select.select_list[2]), "bar");
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use serde::Serialize;
+
+use super::*;
+
+/// A bound `like` expression, e.g. `x like 'a%b_'`.
+///
+/// `%` matches any sequence of characters and `_` matches any single character.
+#[derive(PartialEq, Clone, Serialize)]
+pub struct BoundLike {
+    pub expr: Box<BoundExpr>,
+    pub pattern: Box<BoundExpr>,
+    pub negated: bool,
+    /// The character to match `%`, `_` or itself literally. No character is escaped if it is
+    /// `None`.
+    pub escape: Option<char>,
+}
+
+impl std::fmt::Debug for BoundLike {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} {}like {:?}",
+            self.expr,
+            if self.negated { "not " } else { "" },
+            self.pattern
+        )?;
+        if let Some(escape) = self.escape {
+            write!(f, " escape {:?}", escape)?;
+        }
+        Ok(())
+    }
+}
+
+impl Binder {
+    /// Bind a `like` expression. Both sides must be strings.
+    ///
+    /// The escape character is a backslash as in PostgreSQL, since the `escape` clause can not be
+    /// parsed yet.
+    pub fn bind_like(
+        &mut self,
+        expr: &Expr,
+        negated: bool,
+        pattern: &Expr,
+    ) -> Result<BoundExpr, BindError> {
+        let expr = self.bind_expr(expr)?;
+        let pattern = self.bind_expr(pattern)?;
+        for ty in [&expr, &pattern].iter().filter_map(|e| e.return_type()) {
+            if ty.physical_kind() != PhysicalDataTypeKind::String {
+                return Err(BindError::TypeMismatch("like".into(), format!("{:?}", ty)));
+            }
+        }
+        Ok(BoundExpr::Like(BoundLike {
+            expr: Box::new(expr),
+            pattern: Box::new(pattern),
+            negated,
+            escape: Some('\\'),
+        }))
+    }
+}
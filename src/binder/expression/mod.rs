@@ -7,23 +7,37 @@ use sqlparser::ast::BinaryOperator;
 use super::*;
 use crate::catalog::ColumnRefId;
 use crate::parser::{DateTimeField, Expr, Function, UnaryOperator, Value};
-use crate::types::{DataType, DataTypeExt, DataTypeKind, DataValue, Interval};
+use crate::types::{
+    DataType, DataTypeExt, DataTypeKind, DataValue, Interval, PhysicalDataTypeKind,
+};
 
 mod agg_call;
+mod between;
 mod binary_op;
+mod case;
 mod column_ref;
 mod expr_with_alias;
 mod input_ref;
 mod isnull;
+mod like;
+mod parameter;
+mod scalar_function;
+mod subquery;
 mod type_cast;
 mod unary_op;
 
 pub use self::agg_call::*;
+pub use self::between::*;
 pub use self::binary_op::*;
+pub use self::case::*;
 pub use self::column_ref::*;
 pub use self::expr_with_alias::*;
 pub use self::input_ref::*;
 pub use self::isnull::*;
+pub use self::like::*;
+pub(crate) use self::parameter::*;
+pub use self::scalar_function::*;
+pub use self::subquery::*;
 pub use self::type_cast::*;
 pub use self::unary_op::*;
 
@@ -38,9 +52,14 @@ pub enum BoundExpr {
     UnaryOp(BoundUnaryOp),
     TypeCast(BoundTypeCast),
     AggCall(BoundAggCall),
+    ScalarFunction(BoundScalarFunction),
     IsNull(BoundIsNull),
+    Case(BoundCase),
+    Between(BoundBetween),
+    Like(BoundLike),
     ExprWithAlias(BoundExprWithAlias),
     Alias(BoundAlias),
+    Subquery(BoundSubquery),
 }
 
 impl BoundExpr {
@@ -52,10 +71,15 @@ impl BoundExpr {
             Self::UnaryOp(expr) => expr.return_type.clone(),
             Self::TypeCast(expr) => Some(expr.ty.clone().nullable()),
             Self::AggCall(expr) => Some(expr.return_type.clone()),
+            Self::ScalarFunction(expr) => Some(expr.return_type.clone()),
             Self::InputRef(expr) => Some(expr.return_type.clone()),
             Self::IsNull(_) => Some(DataTypeKind::Boolean.not_null()),
+            Self::Case(expr) => expr.return_type.clone(),
+            Self::Between(_) => Some(DataTypeKind::Boolean.nullable()),
+            Self::Like(_) => Some(DataTypeKind::Boolean.nullable()),
             Self::ExprWithAlias(expr) => expr.expr.return_type(),
             Self::Alias(_) => None,
+            Self::Subquery(expr) => Some(expr.return_type.clone()),
         }
     }
 
@@ -79,11 +103,39 @@ impl BoundExpr {
                     sub_expr.get_filter_column_inner(filter_column);
                 }
             }
+            Self::ScalarFunction(expr) => {
+                for sub_expr in &expr.args {
+                    sub_expr.get_filter_column_inner(filter_column);
+                }
+            }
             Self::IsNull(expr) => expr.expr.get_filter_column_inner(filter_column),
+            Self::Case(expr) => {
+                for (cond, result) in &expr.when_then {
+                    cond.get_filter_column_inner(filter_column);
+                    result.get_filter_column_inner(filter_column);
+                }
+                if let Some(else_result) = &expr.else_result {
+                    else_result.get_filter_column_inner(filter_column);
+                }
+            }
+            Self::Between(expr) => {
+                expr.expr.get_filter_column_inner(filter_column);
+                expr.low.get_filter_column_inner(filter_column);
+                expr.high.get_filter_column_inner(filter_column);
+            }
+            Self::Like(expr) => {
+                expr.expr.get_filter_column_inner(filter_column);
+                expr.pattern.get_filter_column_inner(filter_column);
+            }
             Self::ExprWithAlias(expr) => {
                 expr.expr.get_filter_column_inner(filter_column);
             }
             Self::Alias(_) => {}
+            Self::Subquery(expr) => {
+                for arg in &expr.args {
+                    arg.get_filter_column_inner(filter_column);
+                }
+            }
         }
     }
 
@@ -103,10 +155,15 @@ impl std::fmt::Debug for BoundExpr {
             Self::UnaryOp(expr) => write!(f, "{:?}", expr)?,
             Self::TypeCast(expr) => write!(f, "{:?}", expr)?,
             Self::AggCall(expr) => write!(f, "{:?} (agg)", expr)?,
+            Self::ScalarFunction(expr) => write!(f, "{:?}", expr)?,
             Self::InputRef(expr) => write!(f, "InputRef #{:?}", expr)?,
             Self::IsNull(expr) => write!(f, "{:?} (isnull)", expr)?,
+            Self::Case(expr) => write!(f, "{:?}", expr)?,
+            Self::Between(expr) => write!(f, "{:?}", expr)?,
+            Self::Like(expr) => write!(f, "{:?}", expr)?,
             Self::ExprWithAlias(expr) => write!(f, "{:?}", expr)?,
             Self::Alias(expr) => write!(f, "{:?}", expr)?,
+            Self::Subquery(expr) => write!(f, "{:?}", expr)?,
         }
         Ok(())
     }
@@ -116,9 +173,17 @@ impl Binder {
     /// Bind an expression.
     pub fn bind_expr(&mut self, expr: &Expr) -> Result<BoundExpr, BindError> {
         match expr {
-            Expr::Value(v) => Ok(BoundExpr::Constant(v.into())),
+            Expr::Value(v) => Ok(BoundExpr::Constant(v.try_into()?)),
+            Expr::Identifier(_) if parameter_index(expr).is_some() => {
+                self.bind_parameter(parameter_index(expr).unwrap())
+            }
             Expr::Identifier(ident) => self.bind_column_ref(std::slice::from_ref(ident)),
             Expr::CompoundIdentifier(idents) => self.bind_column_ref(idents),
+            Expr::BinaryOp {
+                left,
+                op: op @ (BinaryOperator::Like | BinaryOperator::NotLike),
+                right,
+            } => self.bind_like(left, *op == BinaryOperator::NotLike, right),
             Expr::BinaryOp { left, op, right } => self.bind_binary_op(left, op, right),
             Expr::UnaryOp { op, expr } => self.bind_unary_op(op, expr),
             Expr::Nested(expr) => self.bind_expr(expr),
@@ -133,13 +198,38 @@ impl Binder {
                     return_type: Some(DataTypeKind::Boolean.not_null()),
                 }))
             }
+            Expr::IsDistinctFrom(left, right) => self.bind_is_distinct_from(left, false, right),
+            Expr::IsNotDistinctFrom(left, right) => self.bind_is_distinct_from(left, true, right),
             Expr::TypedString { data_type, value } => self.bind_typed_string(data_type, value),
             Expr::Between {
                 expr,
                 negated,
                 low,
                 high,
-            } => self.bind_between(expr, negated, low, high),
+            } => self.bind_between(expr, *negated, low, high),
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => self.bind_case(
+                operand.as_deref(),
+                conditions,
+                results,
+                else_result.as_deref(),
+            ),
+            Expr::Substring {
+                expr,
+                substring_from,
+                substring_for,
+            } => self.bind_substring(expr, substring_from.as_deref(), substring_for.as_deref()),
+            Expr::Trim { expr, trim_where } => self.bind_trim(expr, trim_where),
+            Expr::Extract { field, expr } => self.bind_extract(field, expr),
+            Expr::Subquery(query) => self.bind_subquery(query),
+            Expr::Array(array) => self.bind_array(&array.elem),
+            Expr::ArrayIndex { obj, indexs } => self.bind_array_index(obj, indexs),
+            // `IN` and `EXISTS` subqueries are joined if they are conjuncts of `WHERE`
+            Expr::InSubquery { .. } | Expr::Exists(_) => Err(BindError::UnsupportedSubquery),
             _ => todo!("bind expression: {:?}", expr),
         }
     }
@@ -156,38 +246,102 @@ impl Binder {
                 })?;
                 Ok(BoundExpr::Constant(DataValue::Date(date)))
             }
+            DataTypeKind::Timestamp => {
+                let timestamp = value.parse().map_err(|_| {
+                    BindError::CastError(DataValue::String(value.into()), DataTypeKind::Timestamp)
+                })?;
+                Ok(BoundExpr::Constant(DataValue::Timestamp(timestamp)))
+            }
             t => todo!("support typed string: {:?}", t),
         }
     }
+}
 
-    fn bind_between(
-        &mut self,
-        expr: &Expr,
-        negated: &bool,
-        low: &Expr,
-        high: &Expr,
-    ) -> Result<BoundExpr, BindError> {
-        use BinaryOperator::{And, Gt, GtEq, Lt, LtEq, Or};
+/// Get the common type of two types, to which both sides can be casted implicitly. Returns `None`
+/// if the types are incompatible.
+///
+/// The coercion rules are:
+///
+/// - Types of the same physical kind, e.g. `CHAR` and `VARCHAR`, are coerced to the left type.
+/// - Numeric types are promoted along `INT -> BIGINT -> DECIMAL -> DOUBLE`. E.g. `INT` and
+///   `DOUBLE` are coerced to `DOUBLE`, and `BIGINT` and `DECIMAL(10, 2)` to `DECIMAL(10, 2)`.
+///   Like PostgreSQL, `DECIMAL` and `DOUBLE` are coerced to `DOUBLE`.
+/// - Strings are coerced to `DATE` or `TIMESTAMP`, and `DATE` is coerced to `TIMESTAMP`.
+/// - Other types, e.g. numbers and strings, are incompatible.
+///
+/// The result is nullable if either type is nullable.
+pub(super) fn coerce(left: &DataType, right: &DataType) -> Option<DataType> {
+    use PhysicalDataTypeKind::*;
+    let kind = match (left.physical_kind(), right.physical_kind()) {
+        (l, r) if l == r => left.kind(),
+        (Int32, Int64 | Float64 | Decimal) | (Int64, Float64 | Decimal) | (Decimal, Float64) => {
+            right.kind()
+        }
+        (Int64 | Float64 | Decimal, Int32) | (Float64 | Decimal, Int64) | (Float64, Decimal) => {
+            left.kind()
+        }
+        (String | Date, Timestamp) | (String, Date) => right.kind(),
+        (Timestamp, String | Date) | (Date, String) => left.kind(),
+        _ => return None,
+    };
+    Some(DataType::new(
+        kind,
+        left.is_nullable() || right.is_nullable(),
+    ))
+}
 
-        let (left_op, right_op, final_op) = match negated {
-            false => (GtEq, LtEq, And),
-            true => (Lt, Gt, Or),
-        };
+/// Cast the expression to the kind of given type if they are different. An untyped NULL adopts
+/// the type, see [`type_null`].
+pub(super) fn cast_to(expr: &mut BoundExpr, ty: &DataType) {
+    match expr.return_type() {
+        Some(t) if t.kind() != ty.kind() => {
+            let inner = std::mem::replace(expr, BoundExpr::Constant(DataValue::Null));
+            *expr = BoundExpr::TypeCast(BoundTypeCast {
+                expr: Box::new(inner),
+                ty: ty.kind(),
+            });
+        }
+        Some(_) => {}
+        None => type_null(expr, ty),
+    }
+}
+
+/// The type of NULLs whose type can not be inferred from the context, e.g. `SELECT NULL`.
+pub(super) fn default_null_type() -> DataType {
+    DataTypeKind::Int(None).nullable()
+}
 
-        let left_expr = self.bind_binary_op(expr, &left_op, low)?;
-        let right_expr = self.bind_binary_op(expr, &right_op, high)?;
-        Ok(BoundExpr::BinaryOp(BoundBinaryOp {
-            op: final_op,
-            left_expr: Box::new(left_expr),
-            right_expr: Box::new(right_expr),
-            return_type: Some(DataType::new(DataTypeKind::Boolean, false)),
-        }))
+/// Give the expression the type if it is an untyped NULL, e.g. `NULL`, `NULL AS a`, or a `CASE`
+/// whose results are all NULL. Other expressions are kept as they are.
+///
+/// A typed NULL is represented as a NULL casted to the type.
+pub(super) fn type_null(expr: &mut BoundExpr, ty: &DataType) {
+    match expr {
+        BoundExpr::Constant(DataValue::Null) => {
+            *expr = BoundExpr::TypeCast(BoundTypeCast {
+                expr: Box::new(BoundExpr::Constant(DataValue::Null)),
+                ty: ty.kind(),
+            });
+        }
+        BoundExpr::ExprWithAlias(alias) => type_null(&mut alias.expr, ty),
+        BoundExpr::Case(case) if case.return_type.is_none() => {
+            for (_, result) in &mut case.when_then {
+                type_null(result, ty);
+            }
+            if let Some(result) = &mut case.else_result {
+                type_null(result, ty);
+            }
+            case.return_type = Some(ty.kind().nullable());
+        }
+        _ => {}
     }
 }
 
-impl From<&Value> for DataValue {
-    fn from(v: &Value) -> Self {
-        match v {
+impl TryFrom<&Value> for DataValue {
+    type Error = BindError;
+
+    fn try_from(v: &Value) -> Result<Self, Self::Error> {
+        Ok(match v {
             Value::Number(n, _) => {
                 if let Ok(int) = n.parse::<i32>() {
                     Self::Int32(int)
@@ -205,19 +359,105 @@ impl From<&Value> for DataValue {
                 value,
                 leading_field,
                 ..
-            } => match leading_field {
-                Some(DateTimeField::Day) => {
-                    Self::Interval(Interval::from_days(value.parse().unwrap()))
-                }
-                Some(DateTimeField::Month) => {
-                    Self::Interval(Interval::from_months(value.parse().unwrap()))
-                }
-                Some(DateTimeField::Year) => {
-                    Self::Interval(Interval::from_years(value.parse().unwrap()))
-                }
-                _ => todo!("Support interval with leading field: {:?}", leading_field),
-            },
+            } => {
+                let unit = match leading_field {
+                    Some(DateTimeField::Year) => "year",
+                    Some(DateTimeField::Month) => "month",
+                    Some(DateTimeField::Day) => "day",
+                    Some(DateTimeField::Hour) => "hour",
+                    Some(DateTimeField::Minute) => "minute",
+                    Some(DateTimeField::Second) => "second",
+                    // the unit is in the string, e.g. `interval '1 day'`
+                    None => "",
+                };
+                let s = format!("{} {}", value, unit);
+                let interval = s
+                    .parse::<Interval>()
+                    .map_err(|_| BindError::InvalidInterval(s.trim().to_string()))?;
+                Self::Interval(interval)
+            }
             _ => todo!("parse value: {:?}", v),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::RootCatalog;
+    use crate::types::Timestamp;
+
+    #[test]
+    fn test_coerce() {
+        let int = DataTypeKind::Int(None).not_null();
+        let double = DataTypeKind::Double.nullable();
+        let string = DataTypeKind::String.not_null();
+        assert_eq!(coerce(&int, &int), Some(int.clone()));
+        assert_eq!(coerce(&int, &double), Some(double.clone()));
+        assert_eq!(coerce(&double, &int), Some(double));
+        assert_eq!(coerce(&int, &string), None);
+        assert_eq!(coerce(&string, &int), None);
+
+        let date = DataTypeKind::Date.not_null();
+        let timestamp = DataTypeKind::Timestamp.not_null();
+        assert_eq!(coerce(&date, &timestamp), Some(timestamp.clone()));
+        assert_eq!(coerce(&string, &timestamp), Some(timestamp));
+    }
+
+    #[test]
+    fn test_coerce_numeric() {
+        use DataTypeKind as T;
+        // numeric types from the narrowest to the widest
+        let types = [
+            T::Int(None),
+            T::BigInt(None),
+            T::Decimal(Some(10), Some(2)),
+            T::Double,
+        ];
+        for (i, left) in types.iter().enumerate() {
+            for (j, right) in types.iter().enumerate() {
+                let expected = types[i.max(j)].clone().not_null();
+                let ty = coerce(&left.clone().not_null(), &right.clone().not_null());
+                assert_eq!(ty, Some(expected), "{:?} and {:?}", left, right);
+            }
         }
+        // `FLOAT` and `DOUBLE` are the same physical type
+        assert_eq!(
+            coerce(&T::Float(None).not_null(), &T::Double.not_null()),
+            Some(T::Float(None).not_null())
+        );
+        assert_eq!(
+            coerce(&T::Int(None).not_null(), &T::BigInt(None).nullable()),
+            Some(T::BigInt(None).nullable())
+        );
+        assert_eq!(
+            coerce(&T::Boolean.not_null(), &T::Int(None).not_null()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bind_typed_string() {
+        let mut binder = Binder::new(Arc::new(RootCatalog::new()));
+        let mut bind = |data_type: DataTypeKind, value: &str| {
+            binder.bind_expr(&Expr::TypedString {
+                data_type,
+                value: value.into(),
+            })
+        };
+        assert_eq!(
+            bind(DataTypeKind::Date, "2022-01-01").unwrap(),
+            BoundExpr::Constant(DataValue::Date("2022-01-01".parse().unwrap()))
+        );
+        assert_eq!(
+            bind(DataTypeKind::Timestamp, "2022-01-01 12:00:00").unwrap(),
+            BoundExpr::Constant(DataValue::Timestamp(Timestamp::new(1_641_038_400_000_000)))
+        );
+        assert!(matches!(
+            bind(DataTypeKind::Timestamp, "2022-01-01 25:00:00"),
+            Err(BindError::CastError(_, _))
+        ));
     }
 }
@@ -0,0 +1,91 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+
+/// Get the index of a parameter placeholder `$n`, starting from 0.
+///
+/// Placeholders are quoted into identifiers before parsing, see [`crate::parser::parse`].
+pub(crate) fn parameter_index(expr: &Expr) -> Option<usize> {
+    match expr {
+        Expr::Identifier(ident) if ident.quote_style == Some('"') => {
+            match ident.value.strip_prefix('$')?.parse::<usize>() {
+                Ok(n) if n > 0 => Some(n - 1),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+impl Binder {
+    /// Bind the parameter `$n` with the given index.
+    ///
+    /// When a statement is being prepared, a parameter is bound as a NULL of its type. When a
+    /// prepared statement is being executed, it is bound as the given value, casted to the type
+    /// of the parameter if known.
+    pub(super) fn bind_parameter(&mut self, index: usize) -> Result<BoundExpr, BindError> {
+        if self.parameter_types.len() <= index {
+            self.parameter_types.resize(index + 1, None);
+        }
+        let ty = self.parameter_types[index].clone();
+        let value = match &self.parameters {
+            Some(values) => values
+                .get(index)
+                .cloned()
+                .ok_or(BindError::InvalidParameter(index + 1))?,
+            None => {
+                return Ok(match ty {
+                    Some(ty) => BoundExpr::TypeCast(BoundTypeCast {
+                        expr: Box::new(BoundExpr::Constant(DataValue::Null)),
+                        ty: ty.kind(),
+                    }),
+                    None => BoundExpr::Constant(DataValue::Null),
+                })
+            }
+        };
+        let mut expr = self.bind_expr(&value)?;
+        if let Some(ty) = ty {
+            cast_to(&mut expr, &ty);
+        }
+        Ok(expr)
+    }
+
+    /// Bind the operands of a binary operation.
+    ///
+    /// If either operand is a parameter of unknown type, its type is inferred from the other.
+    pub(super) fn bind_operands(
+        &mut self,
+        left: &Expr,
+        right: &Expr,
+    ) -> Result<(BoundExpr, BoundExpr), BindError> {
+        if self.is_untyped_parameter(left) {
+            let right = self.bind_expr(right)?;
+            self.infer_parameter_type(left, right.return_type());
+            let left = self.bind_expr(left)?;
+            Ok((left, right))
+        } else {
+            let left = self.bind_expr(left)?;
+            self.infer_parameter_type(right, left.return_type());
+            let right = self.bind_expr(right)?;
+            Ok((left, right))
+        }
+    }
+
+    fn is_untyped_parameter(&self, expr: &Expr) -> bool {
+        match parameter_index(expr) {
+            Some(index) => !matches!(self.parameter_types.get(index), Some(Some(_))),
+            None => false,
+        }
+    }
+
+    fn infer_parameter_type(&mut self, expr: &Expr, ty: Option<DataType>) {
+        if let (true, Some(index), Some(ty)) =
+            (self.is_untyped_parameter(expr), parameter_index(expr), ty)
+        {
+            if self.parameter_types.len() <= index {
+                self.parameter_types.resize(index + 1, None);
+            }
+            self.parameter_types[index] = Some(ty.kind().nullable());
+        }
+    }
+}
@@ -0,0 +1,872 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt::Formatter;
+
+use serde::Serialize;
+
+use super::*;
+use crate::parser::{DateTimeField, TrimWhereField};
+use crate::types::{DataType, DataTypeExt, DataTypeKind, PhysicalDataTypeKind};
+
+/// Scalar function kind
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum ScalarFunctionKind {
+    Abs,
+    Length,
+    Upper,
+    Lower,
+    Round,
+    Ceil,
+    Floor,
+    Coalesce,
+    NullIf,
+    Greatest,
+    Least,
+    IsDistinctFrom,
+    IsNotDistinctFrom,
+    Substring,
+    Trim,
+    Ltrim,
+    Rtrim,
+    Replace,
+    Concat,
+    Power,
+    Sqrt,
+    Mod,
+    Ln,
+    Log,
+    Exp,
+    Extract(ExtractField),
+    /// `ARRAY[a, b, ...]`
+    Array,
+    /// `list[index]`
+    ArrayElement,
+}
+
+/// The field to extract from a date or timestamp.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum ExtractField {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl From<&DateTimeField> for ExtractField {
+    fn from(field: &DateTimeField) -> Self {
+        match field {
+            DateTimeField::Year => Self::Year,
+            DateTimeField::Month => Self::Month,
+            DateTimeField::Day => Self::Day,
+            DateTimeField::Hour => Self::Hour,
+            DateTimeField::Minute => Self::Minute,
+            DateTimeField::Second => Self::Second,
+        }
+    }
+}
+
+impl ScalarFunctionKind {
+    /// Get the scalar function kind from a lower-case function name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        use ScalarFunctionKind::*;
+        Some(match name {
+            "abs" => Abs,
+            "length" => Length,
+            "upper" => Upper,
+            "lower" => Lower,
+            "round" => Round,
+            "ceil" | "ceiling" => Ceil,
+            "floor" => Floor,
+            "coalesce" => Coalesce,
+            "nullif" => NullIf,
+            "greatest" => Greatest,
+            "least" => Least,
+            "substring" | "substr" => Substring,
+            "trim" | "btrim" => Trim,
+            "ltrim" => Ltrim,
+            "rtrim" => Rtrim,
+            "replace" => Replace,
+            "concat" => Concat,
+            "power" | "pow" => Power,
+            "sqrt" => Sqrt,
+            "mod" => Mod,
+            "ln" => Ln,
+            "log" => Log,
+            "exp" => Exp,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for ScalarFunctionKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use ScalarFunctionKind::*;
+        write!(
+            f,
+            "{}",
+            match self {
+                Abs => "abs",
+                Length => "length",
+                Upper => "upper",
+                Lower => "lower",
+                Round => "round",
+                Ceil => "ceil",
+                Floor => "floor",
+                Coalesce => "coalesce",
+                NullIf => "nullif",
+                Greatest => "greatest",
+                Least => "least",
+                IsDistinctFrom => "is distinct from",
+                IsNotDistinctFrom => "is not distinct from",
+                Substring => "substring",
+                Trim => "trim",
+                Ltrim => "ltrim",
+                Rtrim => "rtrim",
+                Replace => "replace",
+                Concat => "concat",
+                Power => "power",
+                Sqrt => "sqrt",
+                Mod => "mod",
+                Ln => "ln",
+                Log => "log",
+                Exp => "exp",
+                Extract(_) => "extract",
+                Array => "array",
+                ArrayElement => "array_element",
+            }
+        )
+    }
+}
+
+/// Represents a scalar function call
+#[derive(PartialEq, Clone, Serialize)]
+pub struct BoundScalarFunction {
+    pub kind: ScalarFunctionKind,
+    pub args: Vec<BoundExpr>,
+    pub return_type: DataType,
+}
+
+impl std::fmt::Debug for BoundScalarFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?}({:?}) -> {:?}",
+            self.kind, self.args, self.return_type
+        )
+    }
+}
+
+impl Binder {
+    pub fn bind_scalar_function(
+        &mut self,
+        kind: ScalarFunctionKind,
+        args: Vec<BoundExpr>,
+    ) -> Result<BoundExpr, BindError> {
+        use ScalarFunctionKind::*;
+        if let Coalesce | NullIf | Greatest | Least = kind {
+            return self.bind_null_handling_function(kind, args);
+        }
+        if let Substring | Trim | Ltrim | Rtrim | Replace = kind {
+            return self.bind_string_function(kind, args);
+        }
+        if kind == Concat {
+            return self.bind_concat(args);
+        }
+        if let Power | Sqrt | Mod | Ln | Log | Exp = kind {
+            return self.bind_math_function(kind, args);
+        }
+        if args.len() != 1 {
+            return Err(BindError::InvalidArgumentCount(
+                kind.to_string(),
+                1,
+                args.len(),
+            ));
+        }
+        let arg_type = args[0].return_type().ok_or_else(|| {
+            BindError::InvalidExpression(format!("can not infer the argument type of {}", kind))
+        })?;
+        let is_numeric = arg_type.is_numeric();
+        let is_string = matches!(
+            arg_type.kind(),
+            DataTypeKind::Char(_) | DataTypeKind::Varchar(_) | DataTypeKind::String
+        );
+        let is_datetime = matches!(
+            arg_type.kind(),
+            DataTypeKind::Date | DataTypeKind::Timestamp
+        );
+        let return_type = match kind {
+            Abs | Round | Ceil | Floor if is_numeric => arg_type,
+            Upper | Lower if is_string => arg_type,
+            Length if is_string => DataType::new(DataTypeKind::Int(None), arg_type.is_nullable()),
+            Extract(_) if is_datetime => {
+                DataType::new(DataTypeKind::Int(None), arg_type.is_nullable())
+            }
+            _ => {
+                return Err(BindError::TypeMismatch(
+                    kind.to_string(),
+                    format!("{:?}", arg_type),
+                ))
+            }
+        };
+        Ok(BoundExpr::ScalarFunction(BoundScalarFunction {
+            kind,
+            args,
+            return_type,
+        }))
+    }
+
+    /// Bind `substring(s [from start] [for len])`.
+    pub fn bind_substring(
+        &mut self,
+        expr: &Expr,
+        from: Option<&Expr>,
+        len: Option<&Expr>,
+    ) -> Result<BoundExpr, BindError> {
+        let mut args = vec![self.bind_expr(expr)?];
+        args.push(match from {
+            Some(from) => self.bind_expr(from)?,
+            None => BoundExpr::Constant(DataValue::Int32(1)),
+        });
+        if let Some(len) = len {
+            args.push(self.bind_expr(len)?);
+        }
+        self.bind_string_function(ScalarFunctionKind::Substring, args)
+    }
+
+    /// Bind `trim([both | leading | trailing] [chars from] s)`.
+    pub fn bind_trim(
+        &mut self,
+        expr: &Expr,
+        trim_where: &Option<(TrimWhereField, Box<Expr>)>,
+    ) -> Result<BoundExpr, BindError> {
+        let mut args = vec![self.bind_expr(expr)?];
+        let kind = match trim_where {
+            Some((field, chars)) => {
+                args.push(self.bind_expr(chars)?);
+                match field {
+                    TrimWhereField::Both => ScalarFunctionKind::Trim,
+                    TrimWhereField::Leading => ScalarFunctionKind::Ltrim,
+                    TrimWhereField::Trailing => ScalarFunctionKind::Rtrim,
+                }
+            }
+            None => ScalarFunctionKind::Trim,
+        };
+        self.bind_string_function(kind, args)
+    }
+
+    /// Bind `extract(field from expr)`. The date or timestamp argument is checked in
+    /// [`Self::bind_scalar_function`].
+    pub fn bind_extract(
+        &mut self,
+        field: &DateTimeField,
+        expr: &Expr,
+    ) -> Result<BoundExpr, BindError> {
+        let arg = self.bind_expr(expr)?;
+        self.bind_scalar_function(ScalarFunctionKind::Extract(field.into()), vec![arg])
+    }
+
+    /// Bind `ARRAY[a, b, ...]`. The elements are casted to their common type.
+    pub fn bind_array(&mut self, elems: &[Expr]) -> Result<BoundExpr, BindError> {
+        let mut args = (elems.iter())
+            .map(|elem| self.bind_expr(elem))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut elem_type: Option<DataType> = None;
+        for ty in args.iter().filter_map(|arg| arg.return_type()) {
+            elem_type = match elem_type {
+                None => Some(ty),
+                Some(t) => Some(coerce(&t, &ty).ok_or_else(|| {
+                    BindError::TypeMismatch(format!("{:?}", t), format!("{:?}", ty))
+                })?),
+            };
+        }
+        // all elements are NULL
+        let elem_type = elem_type.unwrap_or_else(default_null_type);
+        for arg in &mut args {
+            cast_to(arg, &elem_type);
+        }
+        Ok(BoundExpr::ScalarFunction(BoundScalarFunction {
+            kind: ScalarFunctionKind::Array,
+            args,
+            return_type: DataTypeKind::Array(Box::new(elem_type.kind())).not_null(),
+        }))
+    }
+
+    /// Bind `list[index]` or `list[i][j]...`. The index is 1-based, and the result is NULL if the
+    /// index is out of range.
+    pub fn bind_array_index(
+        &mut self,
+        obj: &Expr,
+        indexes: &[Expr],
+    ) -> Result<BoundExpr, BindError> {
+        let mut list = self.bind_expr(obj)?;
+        for index in indexes {
+            let index = self.bind_expr(index)?;
+            let kind = ScalarFunctionKind::ArrayElement;
+            let mismatch = |ty| BindError::TypeMismatch(kind.to_string(), format!("{:?}", ty));
+            let elem_kind = match list.return_type() {
+                Some(ty) => match ty.kind() {
+                    DataTypeKind::Array(elem) => *elem,
+                    _ => return Err(mismatch(ty)),
+                },
+                None => return Err(BindError::InvalidExpression("can not index a NULL".into())),
+            };
+            if let Some(ty) = index.return_type() {
+                if !matches!(
+                    ty.physical_kind(),
+                    PhysicalDataTypeKind::Int32 | PhysicalDataTypeKind::Int64
+                ) {
+                    return Err(mismatch(ty));
+                }
+            }
+            list = BoundExpr::ScalarFunction(BoundScalarFunction {
+                kind,
+                args: vec![list, index],
+                return_type: elem_kind.nullable(),
+            });
+        }
+        Ok(list)
+    }
+
+    /// Bind a string function with multiple arguments. The result is always a varchar.
+    ///
+    /// - `substring(s, start [, len])`
+    /// - `trim(s [, chars])`, `ltrim(s [, chars])` and `rtrim(s [, chars])`
+    /// - `replace(s, from, to)`
+    fn bind_string_function(
+        &mut self,
+        kind: ScalarFunctionKind,
+        args: Vec<BoundExpr>,
+    ) -> Result<BoundExpr, BindError> {
+        use ScalarFunctionKind::*;
+        let (min_args, max_args) = match kind {
+            Substring => (2, 3),
+            Trim | Ltrim | Rtrim => (1, 2),
+            Replace => (3, 3),
+            _ => unreachable!("not a string function: {}", kind),
+        };
+        if args.len() < min_args || args.len() > max_args {
+            return Err(BindError::InvalidArgumentCount(
+                kind.to_string(),
+                if args.len() < min_args {
+                    min_args
+                } else {
+                    max_args
+                },
+                args.len(),
+            ));
+        }
+        let mut nullable = false;
+        for (i, arg) in args.iter().enumerate() {
+            let ty = match arg.return_type() {
+                Some(ty) => ty,
+                // NULL
+                None => {
+                    nullable = true;
+                    continue;
+                }
+            };
+            // the start and length of `substring` are integers, and others are strings
+            let expected = match (kind, i) {
+                (Substring, 1 | 2) => PhysicalDataTypeKind::Int32,
+                _ => PhysicalDataTypeKind::String,
+            };
+            if ty.physical_kind() != expected {
+                return Err(BindError::TypeMismatch(
+                    kind.to_string(),
+                    format!("{:?}", ty),
+                ));
+            }
+            nullable |= ty.is_nullable();
+        }
+        Ok(BoundExpr::ScalarFunction(BoundScalarFunction {
+            kind,
+            args,
+            return_type: DataType::new(DataTypeKind::Varchar(None), nullable),
+        }))
+    }
+
+    /// Bind `concat(a, b, ...)`. Non-string arguments are converted to their string
+    /// representations, and NULLs are skipped, so the result is never NULL.
+    fn bind_concat(&mut self, mut args: Vec<BoundExpr>) -> Result<BoundExpr, BindError> {
+        if args.is_empty() {
+            return Err(BindError::InvalidArgumentCount(
+                ScalarFunctionKind::Concat.to_string(),
+                1,
+                0,
+            ));
+        }
+        for arg in &mut args {
+            type_null(arg, &DataTypeKind::String.nullable());
+            cast_to_physical(arg, DataTypeKind::String);
+        }
+        Ok(BoundExpr::ScalarFunction(BoundScalarFunction {
+            kind: ScalarFunctionKind::Concat,
+            args,
+            return_type: DataTypeKind::Varchar(None).not_null(),
+        }))
+    }
+
+    /// Bind a math function. The result is a double, except that `mod` of two integers returns
+    /// their common type. Arguments out of the domain, e.g. `sqrt(-1)`, are errors.
+    ///
+    /// - `sqrt(x)`, `ln(x)` and `exp(x)`
+    /// - `power(x, y)`, `mod(x, y)` and `log(base, x)`
+    fn bind_math_function(
+        &mut self,
+        kind: ScalarFunctionKind,
+        mut args: Vec<BoundExpr>,
+    ) -> Result<BoundExpr, BindError> {
+        use ScalarFunctionKind::*;
+        let num_args = match kind {
+            Sqrt | Ln | Exp => 1,
+            Power | Mod | Log => 2,
+            _ => unreachable!("not a math function: {}", kind),
+        };
+        if args.len() != num_args {
+            return Err(BindError::InvalidArgumentCount(
+                kind.to_string(),
+                num_args,
+                args.len(),
+            ));
+        }
+        let mut common: Option<DataType> = None;
+        let mut all_int = true;
+        for ty in args.iter().filter_map(|arg| arg.return_type()) {
+            if !ty.is_numeric() {
+                return Err(BindError::TypeMismatch(
+                    kind.to_string(),
+                    format!("{:?}", ty),
+                ));
+            }
+            all_int &= matches!(
+                ty.physical_kind(),
+                PhysicalDataTypeKind::Int32 | PhysicalDataTypeKind::Int64
+            );
+            common = match common {
+                None => Some(ty),
+                Some(t) => coerce(&t, &ty),
+            };
+        }
+        let return_type = match common {
+            Some(ty) if kind == Mod && all_int => DataType::new(ty.kind(), true),
+            _ => DataTypeKind::Double.nullable(),
+        };
+        for arg in &mut args {
+            cast_to(arg, &return_type);
+        }
+        Ok(BoundExpr::ScalarFunction(BoundScalarFunction {
+            kind,
+            args,
+            return_type,
+        }))
+    }
+
+    /// Bind `coalesce`, `nullif`, `greatest` or `least`. The arguments are casted to their common
+    /// type.
+    ///
+    /// `coalesce`, `greatest` and `least` ignore NULLs and return NULL only if all arguments are
+    /// NULL, while `nullif` can always return NULL.
+    fn bind_null_handling_function(
+        &mut self,
+        kind: ScalarFunctionKind,
+        mut args: Vec<BoundExpr>,
+    ) -> Result<BoundExpr, BindError> {
+        match kind {
+            ScalarFunctionKind::Coalesce
+            | ScalarFunctionKind::Greatest
+            | ScalarFunctionKind::Least
+                if args.is_empty() =>
+            {
+                return Err(BindError::InvalidArgumentCount(kind.to_string(), 1, 0));
+            }
+            ScalarFunctionKind::NullIf if args.len() != 2 => {
+                return Err(BindError::InvalidArgumentCount(
+                    kind.to_string(),
+                    2,
+                    args.len(),
+                ));
+            }
+            _ => {}
+        }
+        let mut return_type: Option<DataType> = None;
+        for ty in args.iter().filter_map(|arg| arg.return_type()) {
+            return_type = match return_type {
+                None => Some(ty),
+                Some(t) => Some(coerce(&t, &ty).ok_or_else(|| {
+                    BindError::TypeMismatch(format!("{:?}", t), format!("{:?}", ty))
+                })?),
+            };
+        }
+        // all arguments are NULL
+        let mut return_type = return_type.unwrap_or_else(default_null_type);
+        return_type.nullable = match kind {
+            ScalarFunctionKind::Coalesce
+            | ScalarFunctionKind::Greatest
+            | ScalarFunctionKind::Least => args
+                .iter()
+                .all(|arg| arg.return_type().map_or(true, |ty| ty.is_nullable())),
+            _ => true,
+        };
+        for arg in &mut args {
+            cast_to(arg, &return_type);
+        }
+        Ok(BoundExpr::ScalarFunction(BoundScalarFunction {
+            kind,
+            args,
+            return_type,
+        }))
+    }
+
+    /// Bind `left IS [NOT] DISTINCT FROM right`, which compares NULL as an ordinary value and
+    /// never returns NULL.
+    pub fn bind_is_distinct_from(
+        &mut self,
+        left: &Expr,
+        negated: bool,
+        right: &Expr,
+    ) -> Result<BoundExpr, BindError> {
+        let mut args = vec![self.bind_expr(left)?, self.bind_expr(right)?];
+        if let (Some(l), Some(r)) = (args[0].return_type(), args[1].return_type()) {
+            let ty = coerce(&l, &r)
+                .ok_or_else(|| BindError::TypeMismatch(format!("{:?}", l), format!("{:?}", r)))?;
+            for arg in &mut args {
+                cast_to(arg, &ty);
+            }
+        }
+        let kind = match negated {
+            false => ScalarFunctionKind::IsDistinctFrom,
+            true => ScalarFunctionKind::IsNotDistinctFrom,
+        };
+        Ok(BoundExpr::ScalarFunction(BoundScalarFunction {
+            kind,
+            args,
+            return_type: DataTypeKind::Boolean.not_null(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::binder::BoundStatement;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::parse;
+
+    fn bind_select_item(sql: &str) -> Result<BoundExpr, BindError> {
+        let catalog = Arc::new(RootCatalog::new());
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        schema
+            .add_table(
+                "t".into(),
+                vec![
+                    ColumnCatalog::new(0, DataTypeKind::Int(None).not_null().to_column("a".into())),
+                    ColumnCatalog::new(1, DataTypeKind::Double.nullable().to_column("b".into())),
+                    ColumnCatalog::new(
+                        2,
+                        DataTypeKind::Varchar(Some(10))
+                            .nullable()
+                            .to_column("c".into()),
+                    ),
+                ],
+                false,
+            )
+            .unwrap();
+        let mut binder = Binder::new(catalog);
+        let stmts = parse(sql).unwrap();
+        match binder.bind(&stmts[0])? {
+            BoundStatement::Select(select) => Ok(select.select_list[0].clone()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn return_type(sql: &str) -> DataType {
+        match bind_select_item(sql).unwrap() {
+            BoundExpr::ScalarFunction(func) => func.return_type,
+            expr => panic!("not a scalar function: {:?}", expr),
+        }
+    }
+
+    #[test]
+    fn bind_scalar_function() {
+        let expr = bind_select_item("select abs(a) from t").unwrap();
+        match expr {
+            BoundExpr::ScalarFunction(func) => {
+                assert_eq!(func.kind, ScalarFunctionKind::Abs);
+                assert_eq!(func.args.len(), 1);
+                assert!(matches!(func.args[0], BoundExpr::ColumnRef(_)));
+                assert_eq!(func.return_type, DataTypeKind::Int(None).not_null());
+            }
+            expr => panic!("not a scalar function: {:?}", expr),
+        }
+
+        assert_eq!(
+            return_type("select abs(b) from t"),
+            DataTypeKind::Double.nullable()
+        );
+        assert_eq!(
+            return_type("select length(c) from t"),
+            DataTypeKind::Int(None).nullable()
+        );
+        assert_eq!(
+            return_type("select upper(c) from t"),
+            DataTypeKind::Varchar(Some(10)).nullable()
+        );
+        assert_eq!(
+            return_type("select lower(c) from t"),
+            DataTypeKind::Varchar(Some(10)).nullable()
+        );
+        assert_eq!(
+            return_type("select round(b) from t"),
+            DataTypeKind::Double.nullable()
+        );
+        assert_eq!(
+            return_type("select ceil(a) from t"),
+            DataTypeKind::Int(None).not_null()
+        );
+        assert_eq!(
+            return_type("select floor(b) from t"),
+            DataTypeKind::Double.nullable()
+        );
+
+        assert!(matches!(
+            bind_select_item("select length(a) from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_select_item("select abs(a, b) from t"),
+            Err(BindError::InvalidArgumentCount(_, 1, 2))
+        ));
+    }
+
+    #[test]
+    fn bind_null_handling_function() {
+        assert_eq!(
+            return_type("select coalesce(b, a) from t"),
+            DataTypeKind::Double.not_null()
+        );
+        assert_eq!(
+            return_type("select coalesce(null, b, 1) from t"),
+            DataTypeKind::Double.not_null()
+        );
+        assert_eq!(
+            return_type("select coalesce(null, b) from t"),
+            DataTypeKind::Double.nullable()
+        );
+        assert_eq!(
+            return_type("select nullif(a, 1) from t"),
+            DataTypeKind::Int(None).nullable()
+        );
+
+        assert!(matches!(
+            bind_select_item("select coalesce(a, c) from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_select_item("select nullif(a) from t"),
+            Err(BindError::InvalidArgumentCount(_, 2, 1))
+        ));
+        assert!(matches!(
+            bind_select_item("select coalesce() from t"),
+            Err(BindError::InvalidArgumentCount(_, 1, 0))
+        ));
+    }
+
+    #[test]
+    fn bind_greatest_least() {
+        assert_eq!(
+            return_type("select greatest(a, b, 1) from t"),
+            DataTypeKind::Double.not_null()
+        );
+        assert_eq!(
+            return_type("select least(a, 2) from t"),
+            DataTypeKind::Int(None).not_null()
+        );
+        assert_eq!(
+            return_type("select greatest(null, c) from t"),
+            DataTypeKind::Varchar(Some(10)).nullable()
+        );
+
+        assert!(matches!(
+            bind_select_item("select greatest(a, c) from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_select_item("select least(b, 'x', a) from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_select_item("select least() from t"),
+            Err(BindError::InvalidArgumentCount(_, 1, 0))
+        ));
+    }
+
+    #[test]
+    fn bind_concat() {
+        // NULLs are skipped, so the result is never NULL
+        assert_eq!(
+            return_type("select concat(c, null, 'x') from t"),
+            DataTypeKind::Varchar(None).not_null()
+        );
+        match bind_select_item("select concat(c, a) from t").unwrap() {
+            // `a` is converted to its string representation
+            BoundExpr::ScalarFunction(func) => {
+                assert!(matches!(func.args[1], BoundExpr::TypeCast(_)))
+            }
+            expr => panic!("not a scalar function: {:?}", expr),
+        }
+
+        assert!(matches!(
+            bind_select_item("select concat() from t"),
+            Err(BindError::InvalidArgumentCount(_, 1, 0))
+        ));
+    }
+
+    #[test]
+    fn bind_is_distinct_from() {
+        let expr = bind_select_item("select a is distinct from b from t").unwrap();
+        match expr {
+            BoundExpr::ScalarFunction(func) => {
+                assert_eq!(func.kind, ScalarFunctionKind::IsDistinctFrom);
+                // `a` is casted to double
+                assert!(matches!(func.args[0], BoundExpr::TypeCast(_)));
+                assert_eq!(func.return_type, DataTypeKind::Boolean.not_null());
+            }
+            expr => panic!("not a scalar function: {:?}", expr),
+        }
+        assert_eq!(
+            return_type("select null is not distinct from b from t"),
+            DataTypeKind::Boolean.not_null()
+        );
+
+        assert!(matches!(
+            bind_select_item("select a is distinct from c from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+    }
+
+    #[test]
+    fn bind_array() {
+        let list = |kind| DataTypeKind::Array(Box::new(kind));
+        assert_eq!(
+            return_type("select array[a, 2] from t"),
+            list(DataTypeKind::Int(None)).not_null()
+        );
+        assert_eq!(
+            return_type("select array[a, b, null] from t"),
+            list(DataTypeKind::Double).not_null()
+        );
+        assert_eq!(
+            return_type("select array[a, b][1] from t"),
+            DataTypeKind::Double.nullable()
+        );
+        assert_eq!(
+            return_type("select array[c][a] from t"),
+            DataTypeKind::Varchar(Some(10)).nullable()
+        );
+
+        assert!(matches!(
+            bind_select_item("select array[a, c] from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_select_item("select array[a][c] from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+    }
+
+    #[test]
+    fn bind_string_function() {
+        assert_eq!(
+            return_type("select substr(c, 2, 3) from t"),
+            DataTypeKind::Varchar(None).nullable()
+        );
+        assert_eq!(
+            return_type("select ltrim('  a') from t"),
+            DataTypeKind::Varchar(None).not_null()
+        );
+        assert_eq!(
+            return_type("select replace(c, 'a', 'b') from t"),
+            DataTypeKind::Varchar(None).nullable()
+        );
+
+        assert!(matches!(
+            bind_select_item("select substr(c, 'a') from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_select_item("select rtrim(a) from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_select_item("select replace(c, 'a') from t"),
+            Err(BindError::InvalidArgumentCount(_, 3, 2))
+        ));
+        assert!(matches!(
+            bind_select_item("select rtrim(c, 'a', 'b') from t"),
+            Err(BindError::InvalidArgumentCount(_, 2, 3))
+        ));
+    }
+
+    #[test]
+    fn bind_math_function() {
+        assert_eq!(
+            return_type("select sqrt(a) from t"),
+            DataTypeKind::Double.nullable()
+        );
+        assert_eq!(
+            return_type("select power(a, 2) from t"),
+            DataTypeKind::Double.nullable()
+        );
+        assert_eq!(
+            return_type("select log(2, b) from t"),
+            DataTypeKind::Double.nullable()
+        );
+        assert_eq!(
+            return_type("select mod(a, 3) from t"),
+            DataTypeKind::Int(None).nullable()
+        );
+        assert_eq!(
+            return_type("select mod(b, 3) from t"),
+            DataTypeKind::Double.nullable()
+        );
+
+        assert!(matches!(
+            bind_select_item("select ln(c) from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_select_item("select exp(a, b) from t"),
+            Err(BindError::InvalidArgumentCount(_, 1, 2))
+        ));
+        assert!(matches!(
+            bind_select_item("select mod(a) from t"),
+            Err(BindError::InvalidArgumentCount(_, 2, 1))
+        ));
+    }
+
+    #[test]
+    fn bind_extract() {
+        let expr = bind_select_item("select extract(year from date '2022-01-01') from t").unwrap();
+        match expr {
+            BoundExpr::ScalarFunction(func) => {
+                assert_eq!(func.kind, ScalarFunctionKind::Extract(ExtractField::Year));
+                assert_eq!(func.return_type, DataTypeKind::Int(None).not_null());
+            }
+            expr => panic!("not a scalar function: {:?}", expr),
+        }
+        assert_eq!(
+            return_type("select extract(second from timestamp '2022-01-01 12:00:00') from t"),
+            DataTypeKind::Int(None).not_null()
+        );
+
+        assert!(matches!(
+            bind_select_item("select extract(year from c) from t"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use serde::Serialize;
+
+use super::*;
+use crate::parser::Query;
+
+/// A bound scalar subquery, e.g. `(select max(b) from t2 where t2.k = t1.k)`.
+///
+/// Columns of outer queries referenced in `query` are listed in `params`, and stay as column refs
+/// in `query`. `args` are the same columns, which are resolved against the input of the outer
+/// query, and their values are substituted for `params` when the subquery is evaluated.
+#[derive(PartialEq, Clone, Serialize)]
+pub struct BoundSubquery {
+    #[serde(skip_serializing)]
+    pub query: Box<BoundSelect>,
+    pub params: Vec<BoundColumnRef>,
+    pub args: Vec<BoundExpr>,
+    pub return_type: DataType,
+}
+
+impl std::fmt::Debug for BoundSubquery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Subquery({:?})", self.args)
+    }
+}
+
+impl Binder {
+    pub fn bind_subquery(&mut self, query: &Query) -> Result<BoundExpr, BindError> {
+        if !self.context.subquery_allowed {
+            return Err(BindError::UnsupportedSubquery);
+        }
+        let outer_refs = self.outer_refs.replace(vec![]);
+        let select = self.bind_select(query);
+        let params = std::mem::replace(&mut self.outer_refs, outer_refs).unwrap();
+        let mut select = select?;
+        if select.select_list.len() != 1 {
+            return Err(BindError::SubqueryColumnCount(select.select_list.len()));
+        }
+        select.type_nulls();
+        // the value is NULL if no row is returned
+        let return_type = select.select_list[0]
+            .return_type()
+            .map_or_else(default_null_type, |ty| ty.kind().nullable());
+        Ok(BoundExpr::Subquery(BoundSubquery {
+            query: select,
+            args: params.iter().cloned().map(BoundExpr::ColumnRef).collect(),
+            params,
+            return_type,
+        }))
+    }
+}
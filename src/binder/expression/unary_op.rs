@@ -20,7 +20,12 @@ impl Binder {
         expr: &Expr,
     ) -> Result<BoundExpr, BindError> {
         // use UnaryOperator as Op;
-        let bound_expr = self.bind_expr(expr)?;
+        let mut bound_expr = self.bind_expr(expr)?;
+        let null_type = match op {
+            UnaryOperator::Not => DataTypeKind::Boolean.nullable(),
+            _ => default_null_type(),
+        };
+        type_null(&mut bound_expr, &null_type);
         Ok(BoundExpr::UnaryOp(BoundUnaryOp {
             op: op.clone(),
             // TODO: check data type
@@ -7,8 +7,8 @@ use std::vec::Vec;
 use crate::catalog::{
     ColumnDesc, RootCatalog, TableRefId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME,
 };
-use crate::parser::{Ident, ObjectName, Statement};
-use crate::types::{ColumnId, DataTypeKind, DataValue};
+use crate::parser::{Expr, Ident, ObjectName, SetExpr, Statement};
+use crate::types::{ColumnId, DataType, DataTypeKind, DataValue};
 
 mod expression;
 pub(crate) mod statement;
@@ -22,12 +22,18 @@ pub use self::table_ref::*;
 #[derive(Debug, PartialEq, Clone)]
 pub enum BoundStatement {
     CreateTable(BoundCreateTable),
+    AlterTable(BoundAlterTable),
     Drop(BoundDrop),
     Insert(BoundInsert),
     Copy(BoundCopy),
     Select(Box<BoundSelect>),
+    SetOperation(Box<BoundSetOperation>),
     Explain(Box<BoundStatement>),
     Delete(Box<BoundDelete>),
+    Update(Box<BoundUpdate>),
+    Analyze(BoundAnalyze),
+    Truncate(BoundTruncate),
+    CreateIndex(BoundCreateIndex),
 }
 
 /// The error type of bind operations.
@@ -45,6 +51,10 @@ pub enum BindError {
     DuplicatedTable(String),
     #[error("duplicated column {0}")]
     DuplicatedColumn(String),
+    #[error("duplicated index on column {0}")]
+    DuplicatedIndex(String),
+    #[error("multiple primary keys for table {0} are not allowed")]
+    MultiplePrimaryKeys(String),
     #[error("invalid expression: {0}")]
     InvalidExpression(String),
     #[error("not nullable column: {0}")]
@@ -59,11 +69,55 @@ pub enum BindError {
     InvalidSQL,
     #[error("cannot cast {0:?} to {1:?}")]
     CastError(DataValue, DataTypeKind),
+    #[error("unsupported function: {0}")]
+    UnsupportedFunction(String),
+    #[error("unsupported aggregate argument: {0}")]
+    UnsupportedAggregateArgument(String),
+    #[error("type mismatch: {0} can not be applied to {1}")]
+    TypeMismatch(String, String),
+    #[error("function {0} expects {1} arguments, but got {2}")]
+    InvalidArgumentCount(String, usize, usize),
+    #[error("each {0} query must have the same number of columns of compatible types")]
+    SetOperationColumnMismatch(String),
+    #[error("column {0} must appear in the GROUP BY clause or be used in an aggregate function")]
+    ColumnNotInGroupBy(String),
+    #[error("VALUES lists must all be the same length: expected {0} but got {1}")]
+    ValuesArityMismatch(usize, usize),
+    #[error("table {0:?} has {1} columns available but {2} columns specified")]
+    TooManyColumnAliases(String, usize, usize),
+    #[error("INSERT has {0} target columns but the query returns {1} columns")]
+    InsertArityMismatch(usize, usize),
+    #[error("column {0} is of type {1:?} but the query returns {2:?}")]
+    InsertTypeMismatch(String, DataTypeKind, DataTypeKind),
+    #[error("subquery must return only one column, but got {0}")]
+    SubqueryColumnCount(usize),
+    #[error("subqueries are only supported in the select list and IN or EXISTS conditions")]
+    UnsupportedSubquery,
+    #[error("unsupported ALTER TABLE operation: {0}")]
+    UnsupportedAlterTable(String),
+    #[error("unsupported index: {0}")]
+    UnsupportedIndex(String),
+    #[error("there is no parameter ${0}")]
+    InvalidParameter(usize),
+    #[error("prepared statement expects {0} parameters, but got {1}")]
+    ParameterCountMismatch(usize, usize),
+    #[error("for SELECT DISTINCT, ORDER BY expressions must appear in select list")]
+    OrderByNotInSelectList,
+    #[error("{0} position {1} is not in select list")]
+    InvalidOrdinal(String, usize),
+    #[error("unsupported correlated subquery: {0}")]
+    UnsupportedCorrelatedSubquery(String),
+    #[error("not supported: {0}")]
+    NotSupported(String),
+    #[error("invalid interval: {0:?}")]
+    InvalidInterval(String),
 }
 
 /// The context of binder execution.
 #[derive(Debug, Default)]
 struct BinderContext {
+    // The names of tables in the `FROM` clause, in order
+    table_names: Vec<String>,
     regular_tables: HashMap<String, TableRefId>,
     // Mapping the table name to column names
     column_names: HashMap<String, HashSet<String>>,
@@ -71,8 +125,14 @@ struct BinderContext {
     column_ids: HashMap<String, Vec<ColumnId>>,
     // Mapping table name to its column descrptions
     column_descs: HashMap<String, Vec<ColumnDesc>>,
-    // Stores alias information
+    // The aliases of output columns in the select list
     aliases: Vec<String>,
+    // Whether the aliases of output columns can be referred, i.e. in `ORDER BY`
+    aliases_visible: bool,
+    // Mapping the name of `VALUES` relations and subqueries in `FROM` to their columns
+    values_tables: HashMap<String, BoundValues>,
+    // Whether subqueries are allowed in the expression being bound
+    subquery_allowed: bool,
 }
 
 /// The binder resolves all expressions referring to schema objects such as
@@ -82,6 +142,17 @@ pub struct Binder {
     context: BinderContext,
     upper_contexts: Vec<BinderContext>,
     base_table_refs: Vec<String>,
+    /// The number of `VALUES` relations and joined subqueries bound, used to allocate their table
+    /// ids.
+    values_count: u32,
+    /// The columns of the outer query referenced in the subquery being bound, if any.
+    outer_refs: Option<Vec<BoundColumnRef>>,
+    /// The types of parameters of the statement being prepared or executed.
+    parameter_types: Vec<Option<DataType>>,
+    /// The values of parameters of the prepared statement being executed.
+    parameters: Option<Vec<Expr>>,
+    /// Whether `||` skips NULLs like `concat`, instead of returning NULL if either side is NULL.
+    concat_skips_nulls: bool,
 }
 
 impl Binder {
@@ -92,6 +163,20 @@ impl Binder {
             upper_contexts: Vec::new(),
             context: BinderContext::default(),
             base_table_refs: Vec::new(),
+            values_count: 0,
+            outer_refs: None,
+            parameter_types: Vec::new(),
+            parameters: None,
+            concat_skips_nulls: false,
+        }
+    }
+
+    /// Set whether `||` skips NULLs like `concat`, as in Oracle. By default, `||` returns NULL if
+    /// either side is NULL, as in PostgreSQL.
+    pub fn with_concat_skips_nulls(self, concat_skips_nulls: bool) -> Self {
+        Binder {
+            concat_skips_nulls,
+            ..self
         }
     }
 
@@ -111,11 +196,29 @@ impl Binder {
             Statement::CreateTable { .. } => {
                 Ok(BoundStatement::CreateTable(self.bind_create_table(stmt)?))
             }
+            Statement::AlterTable { .. } => {
+                Ok(BoundStatement::AlterTable(self.bind_alter_table(stmt)?))
+            }
             Statement::Drop { .. } => Ok(BoundStatement::Drop(self.bind_drop(stmt)?)),
             Statement::Insert { .. } => Ok(BoundStatement::Insert(self.bind_insert(stmt)?)),
             Statement::Delete { .. } => Ok(BoundStatement::Delete(self.bind_delete(stmt)?)),
+            Statement::Update { .. } => Ok(BoundStatement::Update(self.bind_update(stmt)?)),
             Statement::Copy { .. } => Ok(BoundStatement::Copy(self.bind_copy(stmt)?)),
-            Statement::Query(query) => Ok(BoundStatement::Select(self.bind_select(&*query)?)),
+            Statement::Analyze { .. } => Ok(BoundStatement::Analyze(self.bind_analyze(stmt)?)),
+            Statement::Truncate { .. } => Ok(BoundStatement::Truncate(self.bind_truncate(stmt)?)),
+            Statement::CreateIndex { .. } => {
+                Ok(BoundStatement::CreateIndex(self.bind_create_index(stmt)?))
+            }
+            Statement::Query(query) => match &query.body {
+                SetExpr::SetOperation { .. } => Ok(BoundStatement::SetOperation(Box::new(
+                    self.bind_set_operation(&*query)?,
+                ))),
+                _ => {
+                    let mut select = self.bind_select(&*query)?;
+                    select.type_nulls();
+                    Ok(BoundStatement::Select(select))
+                }
+            },
             Statement::Explain { statement, .. } => {
                 Ok(BoundStatement::Explain((self.bind(&*statement)?).into()))
             }
@@ -125,7 +228,7 @@ impl Binder {
 }
 
 /// Split an object name into `(database name, schema name, table name)`.
-fn split_name(name: &ObjectName) -> Result<(&str, &str, &str), BindError> {
+pub(crate) fn split_name(name: &ObjectName) -> Result<(&str, &str, &str), BindError> {
     Ok(match name.0.as_slice() {
         [table] => (DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, &table.value),
         [schema, table] => (DEFAULT_DATABASE_NAME, &schema.value, &table.value),
@@ -135,7 +238,7 @@ fn split_name(name: &ObjectName) -> Result<(&str, &str, &str), BindError> {
 }
 
 /// Convert an object name into lower case
-fn lower_case_name(name: &ObjectName) -> ObjectName {
+pub(crate) fn lower_case_name(name: &ObjectName) -> ObjectName {
     ObjectName(
         name.0
             .iter()
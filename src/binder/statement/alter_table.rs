@@ -0,0 +1,181 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::array::DataChunk;
+use crate::catalog::ColumnCatalog;
+use crate::parser::{AlterTableOperation, ColumnOption, Expr, Statement};
+use crate::types::DataValue;
+
+/// A bound `alter table` statement.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundAlterTable {
+    pub table_ref_id: TableRefId,
+    /// The column to add, which always has a default value.
+    pub column: ColumnCatalog,
+}
+
+impl Binder {
+    pub fn bind_alter_table(&mut self, stmt: &Statement) -> Result<BoundAlterTable, BindError> {
+        match stmt {
+            Statement::AlterTable { name, operation } => {
+                let column_def = match operation {
+                    AlterTableOperation::AddColumn { column_def, .. } => column_def,
+                    _ => return Err(BindError::UnsupportedAlterTable(operation.to_string())),
+                };
+                let name = &lower_case_name(name);
+                let (database_name, schema_name, table_name) = split_name(name)?;
+                let table_ref_id = self
+                    .catalog
+                    .get_table_id_by_name(database_name, schema_name, table_name)
+                    .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
+                let table = self.catalog.get_table(&table_ref_id).unwrap();
+
+                // the default value is bound separately from the other options
+                let mut column_def = column_def.clone();
+                let mut default = None;
+                column_def.options.retain(|opt| match &opt.option {
+                    ColumnOption::Default(expr) => {
+                        default = Some(expr.clone());
+                        false
+                    }
+                    _ => true,
+                });
+                let mut column = ColumnCatalog::from(&column_def);
+                if table.contains_column(column.name()) {
+                    return Err(BindError::DuplicatedColumn(column.name().into()));
+                }
+                if column.is_primary() {
+                    return Err(BindError::UnsupportedAlterTable(
+                        "add primary key column".into(),
+                    ));
+                }
+                column.set_id(table.all_columns().len() as ColumnId);
+
+                let default = match default {
+                    Some(expr) => self.bind_default_value(&expr, &column)?,
+                    None => DataValue::Null,
+                };
+                // the existing rows get the default value
+                if default == DataValue::Null && !column.is_nullable() {
+                    return Err(BindError::NotNullableColumn(column.name().into()));
+                }
+                column.set_default(default);
+
+                Ok(BoundAlterTable {
+                    table_ref_id,
+                    column,
+                })
+            }
+            _ => panic!("mismatched statement type"),
+        }
+    }
+
+    /// Bind the default value of a column, which must be a constant expression.
+    fn bind_default_value(
+        &mut self,
+        expr: &Expr,
+        column: &ColumnCatalog,
+    ) -> Result<DataValue, BindError> {
+        self.push_context();
+        let expr = self.bind_expr(expr);
+        self.pop_context();
+        let mut expr = expr?;
+        if !is_constant(&expr) {
+            return Err(BindError::InvalidExpression(format!(
+                "default value of column {} must be a constant",
+                column.name()
+            )));
+        }
+        cast_to(&mut expr, &column.datatype());
+        let array = (expr.eval(&DataChunk::single(0)))
+            .map_err(|e| BindError::InvalidExpression(e.to_string()))?;
+        Ok(array.get(0))
+    }
+}
+
+/// Check if the expression can be evaluated without any input.
+fn is_constant(expr: &BoundExpr) -> bool {
+    use BoundExpr::*;
+
+    match expr {
+        Constant(_) => true,
+        BinaryOp(binary_op) => {
+            is_constant(&binary_op.left_expr) && is_constant(&binary_op.right_expr)
+        }
+        UnaryOp(unary_op) => is_constant(&unary_op.expr),
+        TypeCast(type_cast) => is_constant(&type_cast.expr),
+        IsNull(isnull) => is_constant(&isnull.expr),
+        Case(case) => {
+            (case.when_then.iter()).all(|(cond, result)| is_constant(cond) && is_constant(result))
+                && case.else_result.iter().all(|expr| is_constant(expr))
+        }
+        Between(between) => {
+            is_constant(&between.expr) && is_constant(&between.low) && is_constant(&between.high)
+        }
+        Like(like) => is_constant(&like.expr) && is_constant(&like.pattern),
+        ExprWithAlias(expr_with_alias) => is_constant(&expr_with_alias.expr),
+        ScalarFunction(func) => func.args.iter().all(is_constant),
+        ColumnRef(_) | InputRef(_) | AggCall(_) | Alias(_) | Subquery(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::RootCatalog;
+    use crate::parser::parse;
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    #[test]
+    fn bind_alter_table() {
+        let catalog = Arc::new(RootCatalog::new());
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        schema
+            .add_table(
+                "t".into(),
+                vec![ColumnCatalog::new(
+                    0,
+                    DataTypeKind::Int(None).not_null().to_column("a".into()),
+                )],
+                false,
+            )
+            .unwrap();
+        let mut binder = Binder::new(catalog);
+
+        let stmts = parse("alter table t add column b float default 1 + 1").unwrap();
+        let mut column = ColumnCatalog::new(
+            1,
+            DataTypeKind::Float(None).nullable().to_column("b".into()),
+        );
+        column.set_default(DataValue::Float64(2.0));
+        assert_eq!(
+            binder.bind_alter_table(&stmts[0]).unwrap(),
+            BoundAlterTable {
+                table_ref_id: TableRefId::new(0, 0, 0),
+                column,
+            }
+        );
+
+        let stmts = parse("alter table t add column c int").unwrap();
+        let column = binder.bind_alter_table(&stmts[0]).unwrap().column;
+        assert_eq!(column.default_value(), DataValue::Null);
+
+        let stmts = parse("alter table t add column a int").unwrap();
+        assert_eq!(
+            binder.bind_alter_table(&stmts[0]),
+            Err(BindError::DuplicatedColumn("a".into()))
+        );
+
+        let stmts = parse("alter table t add column c int not null").unwrap();
+        assert_eq!(
+            binder.bind_alter_table(&stmts[0]),
+            Err(BindError::NotNullableColumn("c".into()))
+        );
+
+        let stmts = parse("alter table t add column c int default a").unwrap();
+        assert!(binder.bind_alter_table(&stmts[0]).is_err());
+    }
+}
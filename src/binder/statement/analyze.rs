@@ -0,0 +1,92 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::parser::Statement;
+
+/// A bound `analyze` statement.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundAnalyze {
+    /// The table to analyze, with all its columns.
+    pub table: BoundTableRef,
+}
+
+impl Binder {
+    pub fn bind_analyze(&mut self, stmt: &Statement) -> Result<BoundAnalyze, BindError> {
+        self.push_context();
+        let ret = self.bind_analyze_internal(stmt);
+        self.pop_context();
+        ret
+    }
+
+    fn bind_analyze_internal(&mut self, stmt: &Statement) -> Result<BoundAnalyze, BindError> {
+        if let Statement::Analyze { table_name, .. } = stmt {
+            let table_name = &lower_case_name(table_name);
+            let (database_name, schema_name, table_name) = split_name(table_name)?;
+            let mut table =
+                self.bind_table_ref_with_name(database_name, schema_name, table_name)?;
+            let table_catalog = (self.catalog)
+                .get_table(&self.context.regular_tables[table_name])
+                .unwrap();
+            for (column_id, col) in table_catalog.all_columns() {
+                self.record_regular_table_column(
+                    table_name,
+                    col.name(),
+                    column_id,
+                    col.desc().clone(),
+                );
+            }
+            self.bind_column_ids(&mut table);
+            Ok(BoundAnalyze { table })
+        } else {
+            panic!("unmatched statement type")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::parse;
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    #[test]
+    fn bind_analyze() {
+        let catalog = Arc::new(RootCatalog::new());
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        schema
+            .add_table(
+                "t".into(),
+                vec![
+                    ColumnCatalog::new(0, DataTypeKind::Int(None).not_null().to_column("a".into())),
+                    ColumnCatalog::new(1, DataTypeKind::Int(None).nullable().to_column("b".into())),
+                ],
+                false,
+            )
+            .unwrap();
+        let mut binder = Binder::new(catalog);
+
+        let stmts = parse("analyze table t").unwrap();
+        let bound = binder.bind_analyze(&stmts[0]).unwrap();
+        match bound.table {
+            BoundTableRef::BaseTableRef {
+                table_name,
+                column_ids,
+                ..
+            } => {
+                assert_eq!(table_name, "t");
+                assert_eq!(column_ids, vec![0, 1]);
+            }
+            _ => panic!("not a base table"),
+        }
+
+        let stmts = parse("analyze table t1").unwrap();
+        assert_eq!(
+            binder.bind_analyze(&stmts[0]),
+            Err(BindError::InvalidTable("t1".into()))
+        );
+    }
+}
@@ -27,6 +27,8 @@ pub enum FileFormat {
         escape: Option<char>,
         /// Whether or not the file has a header line.
         header: bool,
+        /// The string that represents a NULL value.
+        null: String,
     },
 }
 
@@ -63,6 +65,7 @@ impl FileFormat {
         let mut quote = '"';
         let mut escape = None;
         let mut header = false;
+        let mut null = String::new();
         for opt in options {
             match opt {
                 CopyOption::Format(fmt) => {
@@ -72,6 +75,7 @@ impl FileFormat {
                 CopyOption::Header(b) => header = *b,
                 CopyOption::Quote(c) => quote = *c,
                 CopyOption::Escape(c) => escape = Some(*c),
+                CopyOption::Null(s) => null = s.clone(),
                 o => panic!("unsupported copy option: {:?}", o),
             }
         }
@@ -80,6 +84,7 @@ impl FileFormat {
             quote,
             escape,
             header,
+            null,
         }
     }
 }
@@ -0,0 +1,130 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::parser::Statement;
+
+/// A bound `create index` statement.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundCreateIndex {
+    pub table_ref_id: TableRefId,
+    /// The column to index, or `None` if it is indexed and `IF NOT EXISTS` is given.
+    pub column_id: Option<ColumnId>,
+}
+
+impl Binder {
+    pub fn bind_create_index(&mut self, stmt: &Statement) -> Result<BoundCreateIndex, BindError> {
+        match stmt {
+            Statement::CreateIndex {
+                table_name,
+                columns,
+                unique,
+                if_not_exists,
+                ..
+            } => {
+                let name = &lower_case_name(table_name);
+                let (database_name, schema_name, table_name) = split_name(name)?;
+                let table_ref_id = self
+                    .catalog
+                    .get_table_id_by_name(database_name, schema_name, table_name)
+                    .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
+                let table = self.catalog.get_table(&table_ref_id).unwrap();
+
+                if *unique {
+                    return Err(BindError::UnsupportedIndex("unique index".into()));
+                }
+                let column_name = match columns.as_slice() {
+                    [column] => match &column.expr {
+                        Expr::Identifier(ident) => ident.value.to_lowercase(),
+                        expr => return Err(BindError::UnsupportedIndex(expr.to_string())),
+                    },
+                    _ => return Err(BindError::UnsupportedIndex("multi-column index".into())),
+                };
+                let column_id = table
+                    .get_column_id_by_name(&column_name)
+                    .ok_or_else(|| BindError::InvalidColumn(column_name.clone()))?;
+
+                if !table.indexes().contains(&column_id) {
+                    Ok(BoundCreateIndex {
+                        table_ref_id,
+                        column_id: Some(column_id),
+                    })
+                } else if *if_not_exists {
+                    Ok(BoundCreateIndex {
+                        table_ref_id,
+                        column_id: None,
+                    })
+                } else {
+                    Err(BindError::DuplicatedIndex(column_name))
+                }
+            }
+            _ => panic!("mismatched statement type"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::parse;
+    use crate::types::DataTypeExt;
+
+    #[test]
+    fn bind_create_index() {
+        let catalog = Arc::new(RootCatalog::new());
+        let mut binder = Binder::new(catalog.clone());
+
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        let columns = ["a", "b"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                let desc = DataTypeKind::Int(None)
+                    .nullable()
+                    .to_column(name.to_string());
+                ColumnCatalog::new(id as ColumnId, desc)
+            })
+            .collect();
+        schema.add_table("mytable".into(), columns, false).unwrap();
+
+        let stmts = parse("create index on mytable(b)").unwrap();
+        assert_eq!(
+            binder.bind_create_index(&stmts[0]).unwrap(),
+            BoundCreateIndex {
+                table_ref_id: TableRefId::new(0, 0, 0),
+                column_id: Some(1),
+            }
+        );
+
+        let stmts = parse("create index idx on mytable(c)").unwrap();
+        assert_eq!(
+            binder.bind_create_index(&stmts[0]),
+            Err(BindError::InvalidColumn("c".into()))
+        );
+        let stmts = parse("create index idx on mytable(a, b)").unwrap();
+        assert!(matches!(
+            binder.bind_create_index(&stmts[0]),
+            Err(BindError::UnsupportedIndex(_))
+        ));
+
+        // indexed columns are skipped with `IF NOT EXISTS`
+        let table = catalog.get_table(&TableRefId::new(0, 0, 0)).unwrap();
+        table.add_index(1).unwrap();
+        let stmts = parse("create index idx on mytable(b)").unwrap();
+        assert_eq!(
+            binder.bind_create_index(&stmts[0]),
+            Err(BindError::DuplicatedIndex("b".into()))
+        );
+        let stmts = parse("create index if not exists idx on mytable(b)").unwrap();
+        assert_eq!(
+            binder.bind_create_index(&stmts[0]).unwrap(),
+            BoundCreateIndex {
+                table_ref_id: TableRefId::new(0, 0, 0),
+                column_id: None,
+            }
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 use super::*;
 use crate::catalog::{ColumnCatalog, ColumnDesc};
-use crate::parser::{ColumnDef, ColumnOption, Statement};
+use crate::parser::{ColumnDef, ColumnOption, Statement, TableConstraint};
 use crate::types::{DataType, DatabaseId, SchemaId};
 
 /// A bound `create table` statement.
@@ -17,7 +17,12 @@ pub struct BoundCreateTable {
 impl Binder {
     pub fn bind_create_table(&mut self, stmt: &Statement) -> Result<BoundCreateTable, BindError> {
         match stmt {
-            Statement::CreateTable { name, columns, .. } => {
+            Statement::CreateTable {
+                name,
+                columns,
+                constraints,
+                ..
+            } => {
                 let name = &lower_case_name(name);
                 let (database_name, schema_name, table_name) = split_name(name)?;
                 let db = self
@@ -37,7 +42,7 @@ impl Binder {
                         return Err(BindError::DuplicatedColumn(col.name.value.clone()));
                     }
                 }
-                let columns = columns
+                let mut columns: Vec<ColumnCatalog> = columns
                     .iter()
                     .enumerate()
                     .map(|(idx, col)| {
@@ -46,6 +51,7 @@ impl Binder {
                         col
                     })
                     .collect();
+                self.bind_primary_key(table_name, &mut columns, constraints)?;
                 Ok(BoundCreateTable {
                     database_id: db.id(),
                     schema_id: schema.id(),
@@ -56,6 +62,53 @@ impl Binder {
             _ => panic!("mismatched statement type"),
         }
     }
+
+    /// Set the layout of the primary key, which is either a single column with `primary key`
+    /// option, or the columns of a `primary key (...)` table constraint in order. The columns of
+    /// the primary key are implicitly `not null`.
+    fn bind_primary_key(
+        &mut self,
+        table_name: &str,
+        columns: &mut [ColumnCatalog],
+        constraints: &[TableConstraint],
+    ) -> Result<(), BindError> {
+        let mut primary_keys = columns.iter().filter(|col| col.is_primary()).count();
+        for constraint in constraints {
+            match constraint {
+                TableConstraint::Unique {
+                    columns: key_columns,
+                    is_primary: true,
+                    ..
+                } => {
+                    primary_keys += 1;
+                    if primary_keys > 1 {
+                        break;
+                    }
+                    for (index, key_column) in key_columns.iter().enumerate() {
+                        let name = key_column.value.to_lowercase();
+                        let column = (columns.iter_mut())
+                            .find(|col| col.name() == name)
+                            .ok_or(BindError::InvalidColumn(name))?;
+                        if column.is_primary() {
+                            return Err(BindError::DuplicatedColumn(column.name().into()));
+                        }
+                        column.set_primary_key_index(index);
+                        column.set_nullable(false);
+                    }
+                }
+                _ => {
+                    return Err(BindError::NotSupported(format!(
+                        "table constraint {}",
+                        constraint
+                    )))
+                }
+            }
+        }
+        if primary_keys > 1 {
+            return Err(BindError::MultiplePrimaryKeys(table_name.into()));
+        }
+        Ok(())
+    }
 }
 
 impl From<&ColumnDef> for ColumnCatalog {
@@ -73,7 +126,7 @@ impl From<&ColumnDef> for ColumnCatalog {
         ColumnCatalog::new(
             0,
             ColumnDesc::new(
-                DataType::new(cdef.data_type.clone(), is_nullable),
+                DataType::new(cdef.data_type.clone(), is_nullable && !is_primary_),
                 cdef.name.value.to_lowercase(),
                 is_primary_,
             ),
@@ -85,6 +138,8 @@ impl From<&ColumnDef> for ColumnCatalog {
 mod tests {
     use std::sync::Arc;
 
+    use itertools::Itertools;
+
     use super::*;
     use crate::catalog::RootCatalog;
     use crate::parser::parse;
@@ -97,7 +152,14 @@ mod tests {
         let sql = "
             create table t1 (v1 int not null, v2 int); 
             create table t2 (a int not null, a int not null);
-            create table t3 (v1 int not null);";
+            create table t3 (v1 int not null);
+            create table t4 (a int not null, b int not null, c int, primary key (b, a));
+            create table t5 (a int primary key, b int primary key);
+            create table t6 (a int primary key, b int, primary key (b));
+            create table t7 (a int, primary key (b));
+            create table t8 (a int primary key, b int);
+            create table t9 (a int, b int, primary key (b));
+            create table t10 (a int, unique (a));";
         let stmts = parse(sql).unwrap();
 
         assert_eq!(
@@ -131,5 +193,39 @@ mod tests {
             binder.bind_create_table(&stmts[2]),
             Err(BindError::DuplicatedTable("t3".into()))
         );
+
+        let columns = binder.bind_create_table(&stmts[3]).unwrap().columns;
+        assert_eq!(
+            columns
+                .iter()
+                .map(|col| col.primary_key_index())
+                .collect_vec(),
+            vec![Some(1), Some(0), None]
+        );
+        assert_eq!(
+            binder.bind_create_table(&stmts[4]),
+            Err(BindError::MultiplePrimaryKeys("t5".into()))
+        );
+        assert_eq!(
+            binder.bind_create_table(&stmts[5]),
+            Err(BindError::MultiplePrimaryKeys("t6".into()))
+        );
+        assert_eq!(
+            binder.bind_create_table(&stmts[6]),
+            Err(BindError::InvalidColumn("b".into()))
+        );
+
+        // the columns of the primary key are not null
+        for (stmt, nullable) in [(&stmts[7], [false, true]), (&stmts[8], [true, false])] {
+            let columns = binder.bind_create_table(stmt).unwrap().columns;
+            assert_eq!(
+                columns.iter().map(|col| col.is_nullable()).collect_vec(),
+                nullable
+            );
+        }
+        assert!(matches!(
+            binder.bind_create_table(&stmts[9]),
+            Err(BindError::NotSupported(_))
+        ));
     }
 }
@@ -34,6 +34,20 @@ impl Binder {
                 Some(expr) => Some(self.bind_expr(expr)?),
                 None => None,
             };
+            // The storage can not scan row handlers only, so at least one column is scanned to
+            // find the rows to be deleted, e.g. in `delete from t`.
+            if self.context.column_ids[table_name].is_empty() {
+                let table = (self.catalog)
+                    .get_table(&self.context.regular_tables[table_name])
+                    .unwrap();
+                let (column_id, col) = table.all_columns().into_iter().next().unwrap();
+                self.record_regular_table_column(
+                    table_name,
+                    col.name(),
+                    column_id,
+                    col.desc().clone(),
+                );
+            }
             self.bind_column_ids(&mut from_table);
             Ok(Box::new(BoundDelete {
                 from_table,
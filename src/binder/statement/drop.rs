@@ -8,7 +8,8 @@ use crate::parser::{ObjectType, Statement};
 /// A bound `drop` statement.
 #[derive(Debug, PartialEq, Clone)]
 pub struct BoundDrop {
-    pub object: Object,
+    /// The objects to drop. Absent tables are skipped with `IF EXISTS`.
+    pub objects: Vec<Object>,
     pub if_exists: bool,
     pub cascade: bool,
 }
@@ -31,15 +32,21 @@ impl Binder {
                 cascade,
                 ..
             } if *object_type == ObjectType::Table => {
-                let name = &lower_case_name(&names[0]);
-                let (database_name, schema_name, table_name) = split_name(name)?;
-                let table_ref_id = self
-                    .catalog
-                    .get_table_id_by_name(database_name, schema_name, table_name)
-                    .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
-
+                let mut objects = vec![];
+                for name in names {
+                    let name = &lower_case_name(name);
+                    let (database_name, schema_name, table_name) = split_name(name)?;
+                    let table_ref_id =
+                        self.catalog
+                            .get_table_id_by_name(database_name, schema_name, table_name);
+                    match table_ref_id {
+                        Some(table_ref_id) => objects.push(Object::Table(table_ref_id)),
+                        None if *if_exists => {}
+                        None => return Err(BindError::InvalidTable(table_name.into())),
+                    }
+                }
                 Ok(BoundDrop {
-                    object: Object::Table(table_ref_id),
+                    objects,
                     if_exists: *if_exists,
                     cascade: *cascade,
                 })
@@ -70,10 +77,27 @@ mod tests {
         assert_eq!(
             binder.bind_drop(&stmts[0]).unwrap(),
             BoundDrop {
-                object: Object::Table(TableRefId::new(0, 0, 0)),
+                objects: vec![Object::Table(TableRefId::new(0, 0, 0))],
                 if_exists: false,
                 cascade: false,
             }
         );
+
+        let stmts = parse("drop table mytable1").unwrap();
+        assert_eq!(
+            binder.bind_drop(&stmts[0]),
+            Err(BindError::InvalidTable("mytable1".into()))
+        );
+
+        // absent tables are skipped with `IF EXISTS`
+        let stmts = parse("drop table if exists mytable1, mytable").unwrap();
+        assert_eq!(
+            binder.bind_drop(&stmts[0]).unwrap(),
+            BoundDrop {
+                objects: vec![Object::Table(TableRefId::new(0, 0, 0))],
+                if_exists: true,
+                cascade: false,
+            }
+        );
     }
 }
@@ -4,7 +4,7 @@ use itertools::Itertools;
 
 use super::*;
 use crate::catalog::{ColumnCatalog, TableCatalog};
-use crate::parser::{SetExpr, Statement};
+use crate::parser::{Query, SetExpr, Statement};
 use crate::types::{ColumnId, DataType};
 
 /// A bound `insert` statement.
@@ -15,6 +15,8 @@ pub struct BoundInsert {
     pub column_types: Vec<DataType>,
     pub column_descs: Vec<ColumnDesc>,
     pub values: Vec<Vec<BoundExpr>>,
+    /// The query of `insert into .. select ..`, whose outputs are casted to the column types.
+    pub select_stmt: Option<Box<BoundSelect>>,
 }
 
 impl Binder {
@@ -32,16 +34,29 @@ impl Binder {
                 let column_types = columns.iter().map(|col| col.datatype()).collect_vec();
                 let column_descs = columns.iter().map(|col| col.desc().clone()).collect_vec();
 
-                // Check columns after transforming.
+                // Check columns after transforming. Omitted columns get their default values.
                 let col_set: HashSet<ColumnId> = column_ids.iter().cloned().collect();
                 for (id, col) in table.all_columns() {
-                    if !col_set.contains(&id) && !col.is_nullable() {
+                    if !col_set.contains(&id)
+                        && !col.is_nullable()
+                        && col.desc().default().is_none()
+                    {
                         return Err(BindError::NotNullableColumn(col.name().into()));
                     }
                 }
 
                 let values = match &source.body {
-                    SetExpr::Select(_) => todo!("handle 'insert into .. select .. from ..' case."),
+                    SetExpr::Select(_) => {
+                        let select_stmt = self.bind_insert_select(source, &columns)?;
+                        return Ok(BoundInsert {
+                            table_ref_id,
+                            column_ids,
+                            column_types,
+                            column_descs,
+                            values: vec![],
+                            select_stmt: Some(select_stmt),
+                        });
+                    }
                     SetExpr::Values(values) => &values.0,
                     _ => todo!("handle insert ???"),
                 };
@@ -96,12 +111,50 @@ impl Binder {
                     column_types,
                     column_descs,
                     values: bound_values,
+                    select_stmt: None,
                 })
             }
             _ => panic!("mismatched statement type"),
         }
     }
 
+    /// Bind the query of `insert into .. select ..`. The query must return one column for each
+    /// inserted column, and each of them must be implicitly castable to the column type.
+    fn bind_insert_select(
+        &mut self,
+        query: &Query,
+        columns: &[ColumnCatalog],
+    ) -> Result<Box<BoundSelect>, BindError> {
+        let mut select = self.bind_select(query)?;
+        if select.select_list.len() != columns.len() {
+            return Err(BindError::InsertArityMismatch(
+                columns.len(),
+                select.select_list.len(),
+            ));
+        }
+        for (expr, col) in select.select_list.iter_mut().zip(columns) {
+            match expr.return_type() {
+                Some(ty) => {
+                    let common = coerce(&ty, &col.datatype()).map(|t| t.physical_kind());
+                    if common != Some(col.datatype().physical_kind()) {
+                        return Err(BindError::InsertTypeMismatch(
+                            col.name().into(),
+                            col.datatype().kind(),
+                            ty.kind(),
+                        ));
+                    }
+                    cast_to(expr, &col.datatype());
+                }
+                None if !col.is_nullable() => {
+                    return Err(BindError::NotNullableColumn(col.name().into()));
+                }
+                // NULL
+                None => type_null(expr, &col.datatype()),
+            }
+        }
+        Ok(select)
+    }
+
     /// Bind `table_name [ (column_name [, ...] ) ]`
     pub(super) fn bind_table_columns(
         &mut self,
@@ -182,5 +235,22 @@ mod tests {
             Err(BindError::NotNullableColumn(_))
         ));
         binder.bind_insert(&stmts[2]).unwrap();
+
+        let sql = "
+            insert into t select b, a from t where a > 1;
+            insert into t select a from t;
+            insert into t select 'a', b from t;";
+        let stmts = parse(sql).unwrap();
+
+        let insert = binder.bind_insert(&stmts[0]).unwrap();
+        assert_eq!(insert.select_stmt.unwrap().select_list.len(), 2);
+        assert!(matches!(
+            binder.bind_insert(&stmts[1]),
+            Err(BindError::InsertArityMismatch(2, 1))
+        ));
+        assert!(matches!(
+            binder.bind_insert(&stmts[2]),
+            Err(BindError::InsertTypeMismatch(_, _, _))
+        ));
     }
 }
@@ -2,16 +2,30 @@
 
 use super::*;
 
+mod alter_table;
+mod analyze;
 pub(crate) mod copy;
+mod create_index;
 mod create_table;
 mod delete;
 pub(crate) mod drop;
 mod insert;
+mod prepare;
 mod select;
+mod set_operation;
+mod truncate;
+mod update;
 
+pub use alter_table::*;
+pub use analyze::*;
 pub use copy::*;
+pub use create_index::*;
 pub use create_table::*;
 pub use delete::*;
 pub use drop::*;
 pub use insert::*;
+pub use prepare::*;
 pub use select::*;
+pub use set_operation::*;
+pub use truncate::*;
+pub use update::*;
@@ -0,0 +1,129 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::parser::{Expr, Statement};
+use crate::types::{DataType, DataTypeExt};
+
+/// A statement prepared by `PREPARE`.
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    statement: Statement,
+    /// The types of parameters `$1`, `$2`, ..., or `None` if it can not be inferred.
+    parameter_types: Vec<Option<DataType>>,
+}
+
+impl PreparedStatement {
+    /// Get the types of parameters.
+    pub fn parameter_types(&self) -> &[Option<DataType>] {
+        &self.parameter_types
+    }
+}
+
+impl Binder {
+    /// Prepare a statement with parameters.
+    ///
+    /// The types of parameters are either declared, or inferred from the contexts they appear in.
+    /// The statement is bound once to check that it is valid.
+    pub fn prepare(
+        &mut self,
+        stmt: &Statement,
+        declared_types: &[DataTypeKind],
+    ) -> Result<PreparedStatement, BindError> {
+        self.parameters = None;
+        self.parameter_types = (declared_types.iter())
+            .map(|ty| Some(ty.clone().nullable()))
+            .collect();
+        let result = self.bind(stmt);
+        let parameter_types = std::mem::take(&mut self.parameter_types);
+        result?;
+        Ok(PreparedStatement {
+            statement: stmt.clone(),
+            parameter_types,
+        })
+    }
+
+    /// Bind a prepared statement with the values of its parameters.
+    pub fn bind_prepared(
+        &mut self,
+        prepared: &PreparedStatement,
+        values: &[Expr],
+    ) -> Result<BoundStatement, BindError> {
+        if values.len() != prepared.parameter_types.len() {
+            return Err(BindError::ParameterCountMismatch(
+                prepared.parameter_types.len(),
+                values.len(),
+            ));
+        }
+        self.parameters = Some(values.to_vec());
+        self.parameter_types = prepared.parameter_types.clone();
+        let result = self.bind(&prepared.statement);
+        self.parameters = None;
+        self.parameter_types.clear();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::{parse, Value};
+    use crate::types::DataTypeKind;
+
+    fn binder() -> Binder {
+        let catalog = Arc::new(RootCatalog::new());
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        schema
+            .add_table(
+                "t".into(),
+                vec![
+                    ColumnCatalog::new(0, DataTypeKind::Int(None).not_null().to_column("a".into())),
+                    ColumnCatalog::new(1, DataTypeKind::String.nullable().to_column("b".into())),
+                ],
+                false,
+            )
+            .unwrap();
+        Binder::new(catalog)
+    }
+
+    #[test]
+    fn infer_parameter_types() {
+        let mut binder = binder();
+        let stmt = &parse("select b from t where a = $1 and $2 = b").unwrap()[0];
+        let prepared = binder.prepare(stmt, &[]).unwrap();
+        assert_eq!(
+            prepared.parameter_types(),
+            &[
+                Some(DataTypeKind::Int(None).nullable()),
+                Some(DataTypeKind::String.nullable()),
+            ]
+        );
+
+        // declared types take precedence
+        let prepared = binder.prepare(stmt, &[DataTypeKind::Double]).unwrap();
+        assert_eq!(
+            prepared.parameter_types()[0],
+            Some(DataTypeKind::Double.nullable())
+        );
+    }
+
+    #[test]
+    fn bind_prepared_parameters() {
+        let mut binder = binder();
+        let stmt = &parse("select b from t where a = $1").unwrap()[0];
+        let prepared = binder.prepare(stmt, &[]).unwrap();
+
+        let values = vec![
+            Expr::Value(Value::Number("1".into(), false)),
+            Expr::Value(Value::Number("2".into(), false)),
+        ];
+        binder.bind_prepared(&prepared, &values[..1]).unwrap();
+        assert_eq!(
+            binder.bind_prepared(&prepared, &values),
+            Err(BindError::ParameterCountMismatch(1, 2))
+        );
+    }
+}
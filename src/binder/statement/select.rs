@@ -3,7 +3,7 @@ use serde::Serialize;
 
 use super::BoundExpr::*;
 use super::{BoundExpr, BoundTableRef, *};
-use crate::parser::{Query, SelectItem, SetExpr};
+use crate::parser::{Expr, Query, Select, SelectItem, SetExpr, Value};
 use crate::types::DataValue::Bool;
 
 /// A bound `select` statement.
@@ -14,46 +14,72 @@ pub struct BoundSelect {
     pub where_clause: Option<BoundExpr>,
     pub select_distinct: bool,
     pub group_by: Vec<BoundExpr>,
+    pub having: Option<BoundExpr>,
     pub orderby: Vec<BoundOrderBy>,
     pub limit: Option<BoundExpr>,
     pub offset: Option<BoundExpr>,
     // pub return_names: Vec<String>,
 }
 
+impl BoundSelect {
+    /// Give the untyped NULLs in the select list the default type, e.g. `SELECT NULL`.
+    ///
+    /// It should be called once the types can not be inferred from the context, e.g. the other
+    /// side of a set operation.
+    pub(crate) fn type_nulls(&mut self) {
+        for expr in &mut self.select_list {
+            type_null(expr, &default_null_type());
+        }
+    }
+}
+
 impl Binder {
     pub fn bind_select(&mut self, query: &Query) -> Result<Box<BoundSelect>, BindError> {
+        let select = match &query.body {
+            SetExpr::Select(select) => &**select,
+            _ => todo!("not select"),
+        };
         self.push_context();
-        let ret = self.bind_select_internal(query);
+        let ret = self.bind_select_internal(select, Some(query));
         self.pop_context();
         ret
     }
 
-    fn bind_select_internal(&mut self, query: &Query) -> Result<Box<BoundSelect>, BindError> {
-        let select = match &query.body {
-            SetExpr::Select(select) => &**select,
-            _ => todo!("not select"),
-        };
+    /// Bind a `select` without `order by`, `limit` and `offset`, e.g. a branch of `union`.
+    pub fn bind_select_body(&mut self, select: &Select) -> Result<Box<BoundSelect>, BindError> {
+        self.push_context();
+        let ret = self.bind_select_internal(select, None);
+        self.pop_context();
+        ret
+    }
 
+    /// Bind a `select`, with the `order by`, `limit` and `offset` of the query if any.
+    fn bind_select_internal(
+        &mut self,
+        select: &Select,
+        query: Option<&Query>,
+    ) -> Result<Box<BoundSelect>, BindError> {
         // Bind table ref
         let mut from_table = if select.from.is_empty() {
             None
         } else if select.from.len() == 1 {
             Some(self.bind_table_with_joins(&select.from[0])?)
         } else {
-            // Bind cross join
-            let relation = self.bind_table_ref(&select.from[0].relation)?;
-            assert!(select.from[0].joins.is_empty());
-            let mut join_tables = vec![];
-            for table_with_join in &select.from[1..] {
-                let join_table = self.bind_table_ref(&table_with_join.relation)?;
-                assert!(table_with_join.joins.is_empty());
-                let join_ref = BoundedSingleJoinTableRef {
+            // Bind comma-separated tables as cross joins
+            let mut tables = vec![];
+            for table_with_joins in &select.from {
+                tables.push(self.bind_table_with_joins(table_with_joins)?);
+            }
+            self.reorder_by_row_count(&mut tables);
+            let mut tables = tables.into_iter();
+            let relation = tables.next().unwrap();
+            let join_tables = tables
+                .map(|join_table| BoundedSingleJoinTableRef {
                     table_ref: (join_table.into()),
                     join_op: BoundJoinOperator::Inner,
                     join_cond: Constant(Bool(true)),
-                };
-                join_tables.push(join_ref);
-            }
+                })
+                .collect();
             Some(BoundTableRef::JoinTableRef {
                 relation: (relation.into()),
                 join_tables,
@@ -61,51 +87,117 @@ impl Binder {
         };
 
         let where_clause = match &select.selection {
-            Some(expr) => Some(self.bind_expr(expr)?),
+            Some(expr) => self.bind_where_clause(expr, &mut from_table)?,
             None => None,
         };
-        let limit = match &query.limit {
+        let limit = match query.and_then(|q| q.limit.as_ref()) {
             Some(expr) => Some(self.bind_expr(expr)?),
             None => None,
         };
-        let offset = match &query.offset {
+        let offset = match query.and_then(|q| q.offset.as_ref()) {
             Some(offset) => Some(self.bind_expr(&offset.value)?),
             None => None,
         };
-        let mut group_by = vec![];
-        for group_key in &select.group_by {
-            group_by.push(self.bind_expr(group_key)?);
-        }
-
-        // Bind the select list.
+        // Bind the select list. Scalar subqueries are only allowed here.
         let mut select_list = vec![];
         // let mut return_names = vec![];
         for item in &select.projection {
             match item {
                 SelectItem::UnnamedExpr(expr) => {
-                    let expr = self.bind_expr(expr)?;
-                    select_list.push(expr);
+                    self.context.subquery_allowed = true;
+                    let expr = self.bind_expr(expr);
+                    self.context.subquery_allowed = false;
+                    select_list.push(expr?);
                 }
                 SelectItem::ExprWithAlias { expr, alias } => {
-                    let expr = self.bind_expr(expr)?;
-                    let expr = self.bind_alias(expr, alias.clone());
+                    self.context.subquery_allowed = true;
+                    let expr = self.bind_expr(expr);
+                    self.context.subquery_allowed = false;
+                    let expr = self.bind_alias(expr?, alias.clone());
                     select_list.push(expr);
                 }
                 SelectItem::Wildcard => {
                     select_list.extend_from_slice(self.bind_all_column_refs()?.as_slice())
                 }
+                SelectItem::QualifiedWildcard(name) => {
+                    let name = lower_case_name(name);
+                    let (_, _, table_name) = split_name(&name)?;
+                    select_list.extend(self.bind_table_column_refs(table_name)?);
+                }
                 _ => todo!("bind select list"),
             };
             // return_names.push(expr.get_name());
         }
 
+        // The group keys are bound after the select list, which they may refer to by positions or
+        // aliases.
+        let mut group_by = vec![];
+        for group_key in &select.group_by {
+            let expr = match ordinal(group_key, "GROUP BY", select_list.len())? {
+                Some(index) => match &select_list[index] {
+                    ExprWithAlias(e) => (*e.expr).clone(),
+                    expr => expr.clone(),
+                },
+                None => match self.bind_expr(group_key) {
+                    // the columns of tables take precedence over the aliases of output columns
+                    Err(BindError::InvalidColumn(name))
+                        if matches!(group_key, Expr::Identifier(_)) =>
+                    {
+                        (select_list.iter())
+                            .find_map(|expr| match expr {
+                                ExprWithAlias(e) if e.alias == name => Some((*e.expr).clone()),
+                                _ => None,
+                            })
+                            .ok_or(BindError::InvalidColumn(name))?
+                    }
+                    expr => expr?,
+                },
+            };
+            group_by.push(expr);
+        }
+        let having = match &select.having {
+            Some(expr) => {
+                let expr = self.bind_expr(expr)?;
+                check_grouped(&expr, &group_by)?;
+                Some(expr)
+            }
+            None => None,
+        };
+
         let mut orderby = vec![];
-        for e in &query.order_by {
+        for e in query.iter().flat_map(|q| &q.order_by) {
+            let descending = e.asc == Some(false);
+            let expr = match ordinal(&e.expr, "ORDER BY", select_list.len())? {
+                // refer to the outputs of projection by `InputRef`, as aliases do
+                Some(index) => match &select_list[index] {
+                    ColumnRef(column_ref) => ColumnRef(column_ref.clone()),
+                    expr => InputRef(BoundInputRef {
+                        index,
+                        return_type: expr.return_type().unwrap_or_else(default_null_type),
+                    }),
+                },
+                None => {
+                    self.context.aliases_visible = true;
+                    let expr = self.bind_expr(&e.expr);
+                    self.context.aliases_visible = false;
+                    expr?
+                }
+            };
             orderby.push(BoundOrderBy {
-                expr: self.bind_expr(&e.expr)?,
-                descending: e.asc == Some(false),
+                expr,
+                descending,
+                // NULL is treated as the smallest value by default
+                nulls_first: e.nulls_first.unwrap_or(!descending),
             });
         }
+        // rows are deduplicated by the outputs, so they can only be sorted by the outputs
+        if select.distinct {
+            for e in &orderby {
+                if !matches!(e.expr, Alias(_) | InputRef(_)) && !select_list.contains(&e.expr) {
+                    return Err(BindError::OrderByNotInSelectList);
+                }
+            }
+        }
         // Add referred columns for base table reference
         if let Some(table_ref) = &mut from_table {
             self.bind_column_ids(table_ref);
@@ -117,12 +209,45 @@ impl Binder {
             where_clause,
             select_distinct: select.distinct,
             group_by,
+            having,
             orderby,
             limit,
             offset,
         }))
     }
 
+    /// Reorder comma-separated tables by the row counts collected by `ANALYZE`, from the largest
+    /// to the smallest. So the largest table is probed, and smaller tables are built into hash
+    /// tables by the joins.
+    ///
+    /// The tables are kept in order if any of them is not analyzed.
+    fn reorder_by_row_count(&self, tables: &mut Vec<BoundTableRef>) {
+        let row_counts: Option<Vec<usize>> = tables
+            .iter()
+            .map(|table| self.estimate_row_count(table))
+            .collect();
+        if let Some(row_counts) = row_counts {
+            let mut sorted: Vec<_> = tables.drain(..).zip(row_counts).collect();
+            // the sort is stable, tables of the same size are kept in order
+            sorted.sort_by(|(_, a), (_, b)| b.cmp(a));
+            tables.extend(sorted.into_iter().map(|(table, _)| table));
+        }
+    }
+
+    /// Get the row count of a base table collected by `ANALYZE`.
+    fn estimate_row_count(&self, table: &BoundTableRef) -> Option<usize> {
+        match table {
+            BoundTableRef::BaseTableRef { ref_id, .. } => {
+                Some(self.catalog.get_table(ref_id)?.statistics()?.row_count)
+            }
+            BoundTableRef::JoinTableRef {
+                relation,
+                join_tables,
+            } if join_tables.is_empty() => self.estimate_row_count(relation),
+            _ => None,
+        }
+    }
+
     pub fn bind_column_ids(&self, table_ref: &mut BoundTableRef) {
         match table_ref {
             BoundTableRef::BaseTableRef {
@@ -143,7 +268,70 @@ impl Binder {
                     self.bind_column_ids(&mut table.table_ref);
                 }
             }
+            // all columns of `VALUES` and subqueries are output
+            BoundTableRef::Values(_) | BoundTableRef::Subquery(_) => {}
+        }
+    }
+}
+
+/// Resolve a positive integer literal in `clause`, e.g. `GROUP BY 1`, to the 0-based index of the
+/// output column it refers to. Returns `None` if the expression is not an integer literal.
+pub(super) fn ordinal(
+    expr: &Expr,
+    clause: &str,
+    num_columns: usize,
+) -> Result<Option<usize>, BindError> {
+    let position = match expr {
+        Expr::Value(Value::Number(n, _)) => match n.parse::<usize>() {
+            Ok(position) => position,
+            Err(_) => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    if position == 0 || position > num_columns {
+        return Err(BindError::InvalidOrdinal(clause.into(), position));
+    }
+    Ok(Some(position - 1))
+}
+
+/// Check that the columns referred outside of aggregations are group keys.
+fn check_grouped(expr: &BoundExpr, group_by: &[BoundExpr]) -> Result<(), BindError> {
+    if group_by.contains(expr) {
+        return Ok(());
+    }
+    match expr {
+        ColumnRef(column_ref) => Err(BindError::ColumnNotInGroupBy(
+            column_ref.desc.name().to_string(),
+        )),
+        BinaryOp(binary_op) => {
+            check_grouped(&binary_op.left_expr, group_by)?;
+            check_grouped(&binary_op.right_expr, group_by)
+        }
+        UnaryOp(unary_op) => check_grouped(&unary_op.expr, group_by),
+        TypeCast(type_cast) => check_grouped(&type_cast.expr, group_by),
+        IsNull(isnull) => check_grouped(&isnull.expr, group_by),
+        Case(case) => {
+            for (cond, result) in &case.when_then {
+                check_grouped(cond, group_by)?;
+                check_grouped(result, group_by)?;
+            }
+            (case.else_result.iter()).try_for_each(|expr| check_grouped(expr, group_by))
         }
+        Between(between) => {
+            check_grouped(&between.expr, group_by)?;
+            check_grouped(&between.low, group_by)?;
+            check_grouped(&between.high, group_by)
+        }
+        Like(like) => {
+            check_grouped(&like.expr, group_by)?;
+            check_grouped(&like.pattern, group_by)
+        }
+        ExprWithAlias(expr_with_alias) => check_grouped(&expr_with_alias.expr, group_by),
+        Subquery(subquery) => {
+            (subquery.args.iter()).try_for_each(|arg| check_grouped(arg, group_by))
+        }
+        ScalarFunction(func) => (func.args.iter()).try_for_each(|arg| check_grouped(arg, group_by)),
+        AggCall(_) | Constant(_) | InputRef(_) | Alias(_) => Ok(()),
     }
 }
 
@@ -152,15 +340,287 @@ impl Binder {
 pub struct BoundOrderBy {
     pub expr: BoundExpr,
     pub descending: bool,
+    pub nulls_first: bool,
 }
 
 impl std::fmt::Debug for BoundOrderBy {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{:?} ({})",
+            "{:?} ({}, nulls {})",
             self.expr,
-            if self.descending { "desc" } else { "asc" }
+            if self.descending { "desc" } else { "asc" },
+            if self.nulls_first { "first" } else { "last" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::{ColumnCatalog, ColumnRefId, RootCatalog, TableStatistics};
+    use crate::parser::{parse, Statement};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    fn table_names(table_ref: &BoundTableRef) -> Vec<String> {
+        match table_ref {
+            BoundTableRef::BaseTableRef { table_name, .. } => vec![table_name.clone()],
+            BoundTableRef::JoinTableRef {
+                relation,
+                join_tables,
+            } => {
+                let mut names = table_names(relation);
+                for join_table in join_tables {
+                    names.extend(table_names(&join_table.table_ref));
+                }
+                names
+            }
+            BoundTableRef::Values(_) | BoundTableRef::Subquery(_) => vec![],
+        }
+    }
+
+    #[test]
+    fn reorder_tables_by_row_count() {
+        let catalog = Arc::new(RootCatalog::new());
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        for (name, row_count) in [
+            ("a", Some(10)),
+            ("b", Some(1000)),
+            ("c", Some(100)),
+            ("d", None),
+        ] {
+            let column =
+                ColumnCatalog::new(0, DataTypeKind::Int(None).not_null().to_column(name.into()));
+            let table_id = schema.add_table(name.into(), vec![column], false).unwrap();
+            if let Some(row_count) = row_count {
+                (schema.get_table_by_id(table_id).unwrap()).set_statistics(TableStatistics {
+                    row_count,
+                    columns: HashMap::new(),
+                });
+            }
+        }
+
+        let bind = |sql: &str| {
+            let stmts = parse(sql).unwrap();
+            let query = match &stmts[0] {
+                Statement::Query(query) => query,
+                _ => panic!("not a query"),
+            };
+            let select = Binder::new(catalog.clone()).bind_select(query).unwrap();
+            table_names(select.from_table.as_ref().unwrap())
+        };
+        assert_eq!(bind("select * from a, b, c"), ["b", "c", "a"]);
+        // not reordered if any table is not analyzed
+        assert_eq!(bind("select * from a, d, b"), ["a", "d", "b"]);
+        // explicit joins are kept in order
+        assert_eq!(bind("select * from a join b on a = b"), ["a", "b"]);
+    }
+
+    #[test]
+    fn bind_select_distinct() {
+        let catalog = Arc::new(RootCatalog::new());
+        let schema = (catalog.get_database_by_id(0).unwrap())
+            .get_schema_by_id(0)
+            .unwrap();
+        let columns = ["a", "b"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                let desc = DataTypeKind::Int(None)
+                    .nullable()
+                    .to_column(name.to_string());
+                ColumnCatalog::new(id as ColumnId, desc)
+            })
+            .collect();
+        schema.add_table("t".into(), columns, false).unwrap();
+
+        let bind = |sql: &str| {
+            let stmts = parse(sql).unwrap();
+            match &stmts[0] {
+                Statement::Query(query) => Binder::new(catalog.clone()).bind_select(query),
+                _ => panic!("not a query"),
+            }
+        };
+        assert!(bind("select distinct a, b from t").unwrap().select_distinct);
+        bind("select distinct a, b from t order by b").unwrap();
+        bind("select distinct a as x from t order by x").unwrap();
+        assert!(matches!(
+            bind("select distinct a from t order by b"),
+            Err(BindError::OrderByNotInSelectList)
+        ));
+    }
+
+    #[test]
+    fn bind_ordinals() {
+        let catalog = Arc::new(RootCatalog::new());
+        let schema = (catalog.get_database_by_id(0).unwrap())
+            .get_schema_by_id(0)
+            .unwrap();
+        let columns = ["a", "b"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                let desc = DataTypeKind::Int(None)
+                    .nullable()
+                    .to_column(name.to_string());
+                ColumnCatalog::new(id as ColumnId, desc)
+            })
+            .collect();
+        schema.add_table("t".into(), columns, false).unwrap();
+
+        let bind = |sql: &str| {
+            let stmts = parse(sql).unwrap();
+            match &stmts[0] {
+                Statement::Query(query) => Binder::new(catalog.clone()).bind_select(query),
+                _ => panic!("not a query"),
+            }
+        };
+        let select = bind("select b, a + 1 as x from t group by 2, 1").unwrap();
+        assert!(matches!(select.group_by[0], BinaryOp(_)));
+        assert!(matches!(select.group_by[1], ColumnRef(_)));
+        let select = bind("select a + 1 as x from t group by x").unwrap();
+        assert!(matches!(select.group_by[0], BinaryOp(_)));
+
+        let select = bind("select a, b + 1 from t order by 2 desc, 1").unwrap();
+        assert!(matches!(
+            select.orderby[0].expr,
+            InputRef(BoundInputRef { index: 1, .. })
+        ));
+        assert!(select.orderby[0].descending);
+        assert!(matches!(select.orderby[1].expr, ColumnRef(_)));
+
+        assert!(matches!(
+            bind("select a, b from t group by 3"),
+            Err(BindError::InvalidOrdinal(_, 3))
+        ));
+        assert!(matches!(
+            bind("select a from t order by 0"),
+            Err(BindError::InvalidOrdinal(_, 0))
+        ));
+    }
+
+    #[test]
+    fn bind_subquery_joins() {
+        let catalog = Arc::new(RootCatalog::new());
+        let schema = (catalog.get_database_by_id(0).unwrap())
+            .get_schema_by_id(0)
+            .unwrap();
+        for name in ["t1", "t2"] {
+            let columns = ["k", "v"]
+                .iter()
+                .enumerate()
+                .map(|(id, name)| {
+                    let desc = DataTypeKind::Int(None)
+                        .nullable()
+                        .to_column(name.to_string());
+                    ColumnCatalog::new(id as ColumnId, desc)
+                })
+                .collect();
+            schema.add_table(name.into(), columns, false).unwrap();
+        }
+
+        let bind = |sql: &str| {
+            let stmts = parse(sql).unwrap();
+            match &stmts[0] {
+                Statement::Query(query) => Binder::new(catalog.clone()).bind_select(query),
+                _ => panic!("not a query"),
+            }
+        };
+        let join_ops = |select: &BoundSelect| match select.from_table.as_ref().unwrap() {
+            BoundTableRef::JoinTableRef { join_tables, .. } => {
+                join_tables.iter().map(|t| t.join_op).collect::<Vec<_>>()
+            }
+            _ => panic!("not a join"),
+        };
+        let select = bind("select v from t1 where k in (select k from t2) and v > 1").unwrap();
+        assert_eq!(join_ops(&select), [BoundJoinOperator::LeftSemi]);
+        assert!(select.where_clause.is_some());
+        let select = bind("select v from t1 where k not in (select k from t2)").unwrap();
+        assert_eq!(join_ops(&select), [BoundJoinOperator::NullAwareLeftAnti]);
+        assert!(select.where_clause.is_none());
+        let select = bind(
+            "select v from t1 where not exists (select * from t2 where t2.k = t1.k and t2.v > 1)",
         )
+        .unwrap();
+        assert_eq!(join_ops(&select), [BoundJoinOperator::LeftAnti]);
+
+        // the correlated column `t2.k` is appended to the outputs of the subquery
+        let subquery = match select.from_table.as_ref().unwrap() {
+            BoundTableRef::JoinTableRef { join_tables, .. } => match &*join_tables[0].table_ref {
+                BoundTableRef::Subquery(subquery) => subquery.query.clone(),
+                _ => panic!("not a subquery"),
+            },
+            _ => panic!("not a join"),
+        };
+        assert_eq!(subquery.select_list.len(), 3);
+        assert!(subquery.where_clause.is_some());
+
+        assert!(matches!(
+            bind("select v from t1 where k = 1 or k in (select k from t2)"),
+            Err(BindError::UnsupportedSubquery)
+        ));
+        assert!(matches!(
+            bind("select v from t1 where exists (select max(v) from t2 where t2.k = t1.k)"),
+            Err(BindError::UnsupportedCorrelatedSubquery(_))
+        ));
+    }
+
+    #[test]
+    fn bind_subqueries_in_from() {
+        let catalog = Arc::new(RootCatalog::new());
+        let schema = (catalog.get_database_by_id(0).unwrap())
+            .get_schema_by_id(0)
+            .unwrap();
+        let columns = ["k", "v"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                let desc = DataTypeKind::Int(None)
+                    .nullable()
+                    .to_column(name.to_string());
+                ColumnCatalog::new(id as ColumnId, desc)
+            })
+            .collect();
+        schema.add_table("t".into(), columns, false).unwrap();
+
+        let bind = |sql: &str| {
+            let stmts = parse(sql).unwrap();
+            match &stmts[0] {
+                Statement::Query(query) => Binder::new(catalog.clone()).bind_select(query),
+                _ => panic!("not a query"),
+            }
+        };
+        let select =
+            bind("select s.x, v from (select k + 1 as x, v from t order by k desc) as s").unwrap();
+        let subquery = match select.from_table.as_ref().unwrap() {
+            BoundTableRef::JoinTableRef { relation, .. } => match &**relation {
+                BoundTableRef::Subquery(subquery) => subquery,
+                _ => panic!("not a subquery"),
+            },
+            _ => panic!("not a join"),
+        };
+        assert_eq!(subquery.query.orderby.len(), 1);
+        for (index, expr) in select.select_list.iter().enumerate() {
+            match expr {
+                ColumnRef(c) => assert_eq!(
+                    c.column_ref_id,
+                    ColumnRefId::from_table(subquery.table_ref_id, index as ColumnId)
+                ),
+                _ => panic!("not a column"),
+            }
+        }
+        bind("select * from (select k from t) as s(a)").unwrap();
+        assert!(matches!(
+            bind("select * from (select k from t) as s(a, b)"),
+            Err(BindError::TooManyColumnAliases(_, 1, 2))
+        ));
+        assert!(matches!(
+            bind("select k from (select k from t) as s, (select k from t) as s"),
+            Err(BindError::DuplicatedTable(_))
+        ));
     }
 }
@@ -0,0 +1,183 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use serde::Serialize;
+
+use super::select::ordinal;
+use super::*;
+use crate::parser::{Expr, Query, SetExpr, SetOperator};
+use crate::types::{DataType, DataTypeExt};
+
+/// A bound set operation, e.g. `select ... union select ...`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundSetOperation {
+    pub op: BoundSetOperator,
+    /// Whether duplicate rows are kept, e.g. `union all`.
+    pub all: bool,
+    pub left: BoundSetExpr,
+    pub right: BoundSetExpr,
+    /// The output types, to which the outputs of both sides should be casted.
+    pub return_types: Vec<DataType>,
+    /// The outputs are sorted by `orderby`, which refer to them by `InputRef`.
+    pub orderby: Vec<BoundOrderBy>,
+    pub limit: Option<BoundExpr>,
+    pub offset: Option<BoundExpr>,
+}
+
+/// An operand of a set operation.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BoundSetExpr {
+    Select(Box<BoundSelect>),
+    SetOperation(Box<BoundSetOperation>),
+}
+
+/// Set operators.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum BoundSetOperator {
+    Union,
+    Intersect,
+    Except,
+}
+
+impl BoundSetExpr {
+    /// Get the output types.
+    pub fn return_types(&self) -> Vec<Option<DataType>> {
+        match self {
+            Self::Select(select) => select.select_list.iter().map(|e| e.return_type()).collect(),
+            Self::SetOperation(op) => op.return_types.iter().cloned().map(Some).collect(),
+        }
+    }
+
+    /// Get the output names, which are the names of the left-most operand.
+    pub fn output_names(&self) -> Vec<String> {
+        match self {
+            Self::Select(select) => (select.select_list.iter())
+                .map(|expr| match expr {
+                    BoundExpr::ColumnRef(column_ref) => column_ref.desc.name().to_string(),
+                    BoundExpr::ExprWithAlias(expr_with_alias) => expr_with_alias.alias.clone(),
+                    _ => "?column?".to_string(),
+                })
+                .collect(),
+            Self::SetOperation(op) => op.left.output_names(),
+        }
+    }
+}
+
+impl Binder {
+    /// Bind a set operation, with the `order by`, `limit` and `offset` of the query if any.
+    ///
+    /// The outputs can only be sorted by their names or positions, e.g. `order by v1` or
+    /// `order by 1`, where the names are those of the left-most operand.
+    pub fn bind_set_operation(&mut self, query: &Query) -> Result<BoundSetOperation, BindError> {
+        let mut set_op = match self.bind_set_expr(&query.body)? {
+            BoundSetExpr::SetOperation(op) => *op,
+            BoundSetExpr::Select(_) => {
+                return Err(BindError::NotSupported(format!(
+                    "{} as a set operation",
+                    query.body
+                )))
+            }
+        };
+        let names = set_op.left.output_names();
+        for e in &query.order_by {
+            let index = match ordinal(&e.expr, "ORDER BY", names.len())? {
+                Some(index) => index,
+                None => match &e.expr {
+                    Expr::Identifier(ident) => {
+                        let name = ident.value.to_lowercase();
+                        match names.iter().filter(|n| **n == name).count() {
+                            0 => return Err(BindError::InvalidColumn(name)),
+                            1 => names.iter().position(|n| *n == name).unwrap(),
+                            _ => return Err(BindError::AmbiguousColumn),
+                        }
+                    }
+                    expr => {
+                        return Err(BindError::NotSupported(format!(
+                            "ORDER BY {} on set operations, only output columns can be used",
+                            expr
+                        )))
+                    }
+                },
+            };
+            let descending = e.asc == Some(false);
+            set_op.orderby.push(BoundOrderBy {
+                expr: BoundExpr::InputRef(BoundInputRef {
+                    index,
+                    return_type: set_op.return_types[index].clone(),
+                }),
+                descending,
+                nulls_first: e.nulls_first.unwrap_or(!descending),
+            });
+        }
+        if let Some(limit) = &query.limit {
+            set_op.limit = Some(self.bind_expr(limit)?);
+        }
+        if let Some(offset) = &query.offset {
+            set_op.offset = Some(self.bind_expr(&offset.value)?);
+        }
+        Ok(set_op)
+    }
+
+    fn bind_set_expr(&mut self, expr: &SetExpr) -> Result<BoundSetExpr, BindError> {
+        match expr {
+            SetExpr::Select(select) => Ok(BoundSetExpr::Select(self.bind_select_body(select)?)),
+            SetExpr::Query(query) => match &query.body {
+                SetExpr::Select(_) => Ok(BoundSetExpr::Select(self.bind_select(query)?)),
+                _ => Ok(BoundSetExpr::SetOperation(Box::new(
+                    self.bind_set_operation(query)?,
+                ))),
+            },
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                let mut left = self.bind_set_expr(left)?;
+                let mut right = self.bind_set_expr(right)?;
+                let mismatch = || BindError::SetOperationColumnMismatch(op.to_string());
+                let (left_types, right_types) = (left.return_types(), right.return_types());
+                if left_types.len() != right_types.len() {
+                    return Err(mismatch());
+                }
+                let return_types = (left_types.iter().zip(&right_types))
+                    .map(|(l, r)| match (l, r) {
+                        (Some(l), Some(r)) => coerce(l, r),
+                        (Some(ty), None) | (None, Some(ty)) => Some(ty.kind().nullable()),
+                        (None, None) => Some(default_null_type()),
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(mismatch)?;
+                // NULLs adopt the types of the other side
+                for select in [&mut left, &mut right]
+                    .into_iter()
+                    .filter_map(|expr| match expr {
+                        BoundSetExpr::Select(select) => Some(select),
+                        BoundSetExpr::SetOperation(_) => None,
+                    })
+                {
+                    for (expr, ty) in select.select_list.iter_mut().zip(&return_types) {
+                        type_null(expr, ty);
+                    }
+                }
+                Ok(BoundSetExpr::SetOperation(Box::new(BoundSetOperation {
+                    op: match op {
+                        SetOperator::Union => BoundSetOperator::Union,
+                        SetOperator::Intersect => BoundSetOperator::Intersect,
+                        SetOperator::Except => BoundSetOperator::Except,
+                    },
+                    all: *all,
+                    left,
+                    right,
+                    return_types,
+                    orderby: vec![],
+                    limit: None,
+                    offset: None,
+                })))
+            }
+            _ => Err(BindError::NotSupported(format!(
+                "{} in set operations",
+                expr
+            ))),
+        }
+    }
+}
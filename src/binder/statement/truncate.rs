@@ -0,0 +1,60 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::parser::Statement;
+
+/// A bound `truncate` statement.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundTruncate {
+    pub table_ref_id: TableRefId,
+}
+
+impl Binder {
+    pub fn bind_truncate(&mut self, stmt: &Statement) -> Result<BoundTruncate, BindError> {
+        match stmt {
+            Statement::Truncate { table_name, .. } => {
+                let name = &lower_case_name(table_name);
+                let (database_name, schema_name, table_name) = split_name(name)?;
+                let table_ref_id = self
+                    .catalog
+                    .get_table_id_by_name(database_name, schema_name, table_name)
+                    .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
+                Ok(BoundTruncate { table_ref_id })
+            }
+            _ => panic!("mismatched statement type"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::RootCatalog;
+    use crate::parser::parse;
+
+    #[test]
+    fn bind_truncate() {
+        let catalog = Arc::new(RootCatalog::new());
+        let mut binder = Binder::new(catalog.clone());
+
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        schema.add_table("mytable".into(), vec![], false).unwrap();
+
+        let stmts = parse("truncate table mytable").unwrap();
+        assert_eq!(
+            binder.bind_truncate(&stmts[0]).unwrap(),
+            BoundTruncate {
+                table_ref_id: TableRefId::new(0, 0, 0)
+            }
+        );
+
+        let stmts = parse("truncate table mytable1").unwrap();
+        assert_eq!(
+            binder.bind_truncate(&stmts[0]),
+            Err(BindError::InvalidTable("mytable1".into()))
+        );
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::parser::Assignment;
+
+/// A bound `update` statement.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundUpdate {
+    pub from_table: BoundTableRef,
+    /// The new value of each column in the table, in the order of column ids. Columns not
+    /// assigned keep their old values.
+    pub values: Vec<BoundExpr>,
+    pub where_clause: Option<BoundExpr>,
+}
+
+impl Binder {
+    pub fn bind_update(&mut self, stmt: &Statement) -> Result<Box<BoundUpdate>, BindError> {
+        self.push_context();
+        let ret = self.bind_update_internal(stmt);
+        self.pop_context();
+        ret
+    }
+
+    fn bind_update_internal(&mut self, stmt: &Statement) -> Result<Box<BoundUpdate>, BindError> {
+        if let Statement::Update {
+            table_name,
+            assignments,
+            selection,
+        } = stmt
+        {
+            let table_name = &lower_case_name(table_name);
+            let (database_name, schema_name, table_name) = split_name(table_name)?;
+            let mut from_table =
+                self.bind_table_ref_with_name(database_name, schema_name, table_name)?;
+            let table = self
+                .catalog
+                .get_table(&self.context.regular_tables[table_name])
+                .unwrap();
+            // all columns are scanned to write the new versions of rows
+            let mut values = self.bind_all_column_refs()?;
+            let mut assigned = vec![false; values.len()];
+            for Assignment { id, value } in assignments {
+                let column_name = id.value.to_lowercase();
+                let col = table
+                    .get_column_by_name(&column_name)
+                    .ok_or_else(|| BindError::InvalidColumn(column_name.clone()))?;
+                let idx = (table.all_columns().keys())
+                    .position(|&col_id| col_id == col.id())
+                    .unwrap();
+                if assigned[idx] {
+                    return Err(BindError::DuplicatedColumn(column_name));
+                }
+                assigned[idx] = true;
+
+                let mut value = self.bind_expr(value)?;
+                match value.return_type() {
+                    Some(ty) => {
+                        if coerce(&ty, &col.datatype()).is_none() {
+                            return Err(BindError::TypeMismatch(
+                                format!("{:?}", ty),
+                                format!("{:?}", col.datatype()),
+                            ));
+                        }
+                        cast_to(&mut value, &col.datatype());
+                    }
+                    // NULL
+                    None if !col.is_nullable() => {
+                        return Err(BindError::NotNullableColumn(column_name));
+                    }
+                    // give the NULL a type so that it can be written into the column
+                    None => type_null(&mut value, &col.datatype()),
+                }
+                values[idx] = value;
+            }
+            let where_clause = match &selection {
+                Some(expr) => Some(self.bind_expr(expr)?),
+                None => None,
+            };
+            self.bind_column_ids(&mut from_table);
+            Ok(Box::new(BoundUpdate {
+                from_table,
+                values,
+                where_clause,
+            }))
+        } else {
+            panic!("unmatched statement type")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::parse;
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    #[test]
+    fn bind_update() {
+        let catalog = Arc::new(RootCatalog::new());
+        let mut binder = Binder::new(catalog.clone());
+
+        let database = catalog.get_database_by_id(0).unwrap();
+        let schema = database.get_schema_by_id(0).unwrap();
+        schema
+            .add_table(
+                "t".into(),
+                vec![
+                    ColumnCatalog::new(0, DataTypeKind::Int(None).not_null().to_column("a".into())),
+                    ColumnCatalog::new(1, DataTypeKind::Double.nullable().to_column("b".into())),
+                ],
+                false,
+            )
+            .unwrap();
+
+        let sql = "
+            update t set b = a + 1 where a > 1;
+            update t set b = null;
+            update t set a = null;
+            update t set a = 'a';
+            update t set c = 1;
+            update t set a = 1, a = 2;";
+        let stmts = parse(sql).unwrap();
+
+        let update = binder.bind_update(&stmts[0]).unwrap();
+        assert!(matches!(update.values[0], BoundExpr::ColumnRef(_)));
+        assert!(matches!(update.values[1], BoundExpr::TypeCast(_)));
+        assert!(update.where_clause.is_some());
+        binder.bind_update(&stmts[1]).unwrap();
+        assert!(matches!(
+            binder.bind_update(&stmts[2]),
+            Err(BindError::NotNullableColumn(_))
+        ));
+        assert!(matches!(
+            binder.bind_update(&stmts[3]),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            binder.bind_update(&stmts[4]),
+            Err(BindError::InvalidColumn(_))
+        ));
+        assert!(matches!(
+            binder.bind_update(&stmts[5]),
+            Err(BindError::DuplicatedColumn(_))
+        ));
+    }
+}
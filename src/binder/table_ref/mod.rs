@@ -9,6 +9,12 @@ use super::*;
 use crate::parser::{JoinConstraint, JoinOperator, TableFactor, TableWithJoins};
 use crate::types::DataValue::Bool;
 
+mod subquery;
+mod values;
+
+pub use self::subquery::*;
+pub use self::values::*;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct BoundedSingleJoinTableRef {
     pub table_ref: Box<BoundTableRef>,
@@ -29,6 +35,8 @@ pub enum BoundTableRef {
         relation: Box<BoundTableRef>,
         join_tables: Vec<BoundedSingleJoinTableRef>,
     },
+    Values(BoundValues),
+    Subquery(BoundSubqueryRef),
 }
 
 #[derive(PartialEq, Clone, Copy, Serialize)]
@@ -37,6 +45,24 @@ pub enum BoundJoinOperator {
     LeftOuter,
     RightOuter,
     FullOuter,
+    /// Outputs each left row at most once if it matches any right row, e.g. `x IN (subquery)`.
+    LeftSemi,
+    /// Outputs left rows that match no right row, e.g. `NOT EXISTS (subquery)`.
+    LeftAnti,
+    /// Like [`BoundJoinOperator::LeftAnti`], but follows the three-valued logic of
+    /// `x NOT IN (subquery)`: a left row is not output if its key is NULL or any right key is
+    /// NULL, unless the right side is empty.
+    NullAwareLeftAnti,
+}
+
+impl BoundJoinOperator {
+    /// Whether the join only outputs the columns of the left side.
+    pub fn is_semi_or_anti(&self) -> bool {
+        matches!(
+            self,
+            Self::LeftSemi | Self::LeftAnti | Self::NullAwareLeftAnti
+        )
+    }
 }
 
 impl std::fmt::Debug for BoundJoinOperator {
@@ -46,6 +72,9 @@ impl std::fmt::Debug for BoundJoinOperator {
             Self::LeftOuter => write!(f, "Left Outer"),
             Self::RightOuter => write!(f, "Right Outer"),
             Self::FullOuter => write!(f, "Full Outer"),
+            Self::LeftSemi => write!(f, "Left Semi"),
+            Self::LeftAnti => write!(f, "Left Anti"),
+            Self::NullAwareLeftAnti => write!(f, "Null-aware Left Anti"),
         }
     }
 }
@@ -117,33 +146,48 @@ impl Binder {
         schema_name: &str,
         table_name: &str,
     ) -> Result<BoundTableRef, BindError> {
-        if self.context.regular_tables.contains_key(table_name) {
-            return Err(BindError::DuplicatedTable(table_name.into()));
-        }
+        self.bind_table_ref_with_alias(database_name, schema_name, table_name, table_name)
+    }
 
+    /// Bind a base table referred by the alias in the query, e.g. `t1` in `foo AS t1`. The name
+    /// of the table is hidden by the alias, so `foo.a` is an invalid reference then.
+    ///
+    /// A table can only be referred once in a query, even by different aliases, since the
+    /// columns are identified by the table id.
+    pub fn bind_table_ref_with_alias(
+        &mut self,
+        database_name: &str,
+        schema_name: &str,
+        table_name: &str,
+        alias: &str,
+    ) -> Result<BoundTableRef, BindError> {
         let ref_id = self
             .catalog
             .get_table_id_by_name(database_name, schema_name, table_name)
             .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
-        self.context
-            .regular_tables
-            .insert(table_name.into(), ref_id);
+
+        if self.context.regular_tables.contains_key(alias)
+            || self.context.values_tables.contains_key(alias)
+        {
+            return Err(BindError::DuplicatedTable(alias.into()));
+        }
+        if self.context.regular_tables.values().any(|id| *id == ref_id) {
+            return Err(BindError::DuplicatedTable(table_name.into()));
+        }
+        self.context.table_names.push(alias.into());
+        self.context.regular_tables.insert(alias.into(), ref_id);
         self.context
             .column_names
-            .insert(table_name.into(), HashSet::new());
-        self.context
-            .column_ids
-            .insert(table_name.into(), Vec::new());
-        self.context
-            .column_descs
-            .insert(table_name.into(), Vec::new());
+            .insert(alias.into(), HashSet::new());
+        self.context.column_ids.insert(alias.into(), Vec::new());
+        self.context.column_descs.insert(alias.into(), Vec::new());
         let base_table_ref = BoundTableRef::BaseTableRef {
             ref_id,
-            table_name: table_name.into(),
+            table_name: alias.into(),
             column_ids: vec![],
             column_descs: vec![],
         };
-        self.base_table_refs.push(table_name.into());
+        self.base_table_refs.push(alias.into());
         Ok(base_table_ref)
     }
 
@@ -151,12 +195,27 @@ impl Binder {
         match table {
             TableFactor::Table { name, alias, .. } => {
                 let name = &lower_case_name(name);
-                let (database_name, schema_name, mut table_name) = split_name(name)?;
-                if let Some(alias) = alias {
-                    table_name = &alias.name.value;
+                let (database_name, schema_name, table_name) = split_name(name)?;
+                match alias {
+                    Some(alias) => self.bind_table_ref_with_alias(
+                        database_name,
+                        schema_name,
+                        table_name,
+                        &alias.name.value.to_lowercase(),
+                    ),
+                    None => self.bind_table_ref_with_name(database_name, schema_name, table_name),
                 }
-                self.bind_table_ref_with_name(database_name, schema_name, table_name)
             }
+            TableFactor::Derived {
+                subquery, alias, ..
+            } => match &subquery.body {
+                SetExpr::Values(values) => Ok(BoundTableRef::Values(
+                    self.bind_values(values, alias.as_ref())?,
+                )),
+                _ => Ok(BoundTableRef::Subquery(
+                    self.bind_derived_table(subquery, alias.as_ref())?,
+                )),
+            },
             _ => panic!("bind table ref"),
         }
     }
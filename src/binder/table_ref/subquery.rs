@@ -0,0 +1,429 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::catalog::ColumnRefId;
+use crate::parser::{BinaryOperator, Expr, Query, TableAlias, UnaryOperator};
+use crate::types::{DataTypeExt, DataTypeKind};
+
+/// A subquery in `FROM`, or a subquery joined with the outer query by a semi or anti join, e.g.
+/// the subquery of `x IN (SELECT ...)` or `NOT EXISTS (SELECT ...)` in `WHERE`.
+///
+/// Like `VALUES` relations, each subquery gets a table id under [`VALUES_DATABASE_ID`], so that
+/// its outputs can be referred by [`ColumnRefId`]s in the join condition.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundSubqueryRef {
+    pub table_ref_id: TableRefId,
+    pub query: Box<BoundSelect>,
+}
+
+impl Binder {
+    /// Bind a subquery in `FROM` and register its outputs as the columns of a relation.
+    ///
+    /// Without an alias, the relation is named `*SUBQUERY*`. The columns are named by the
+    /// aliases if any, then by the output names of the subquery, or `column1`, `column2`, etc.
+    pub(in crate::binder) fn bind_derived_table(
+        &mut self,
+        query: &Query,
+        alias: Option<&TableAlias>,
+    ) -> Result<BoundSubqueryRef, BindError> {
+        // the subquery can't refer to the tables beside it in `FROM`
+        let outer_refs = self.outer_refs.take();
+        let select = self.bind_select(query);
+        self.outer_refs = outer_refs;
+        let mut select = select?;
+        select.type_nulls();
+
+        let (table_name, column_names) = match alias {
+            Some(alias) => (
+                alias.name.value.to_lowercase(),
+                (alias.columns.iter())
+                    .map(|col| col.value.to_lowercase())
+                    .collect(),
+            ),
+            None => ("*SUBQUERY*".to_string(), vec![]),
+        };
+        let arity = select.select_list.len();
+        if column_names.len() > arity {
+            return Err(BindError::TooManyColumnAliases(
+                table_name,
+                arity,
+                column_names.len(),
+            ));
+        }
+        let column_descs = (select.select_list.iter().enumerate())
+            .map(|(i, expr)| {
+                let name = match (column_names.get(i), expr) {
+                    (Some(name), _) => name.clone(),
+                    (None, BoundExpr::ExprWithAlias(e)) => e.alias.clone(),
+                    (None, BoundExpr::ColumnRef(c)) => c.desc.name().to_string(),
+                    (None, _) => format!("column{}", i + 1),
+                };
+                expr.return_type().unwrap().to_column(name)
+            })
+            .collect();
+
+        if self.context.regular_tables.contains_key(&table_name)
+            || self.context.values_tables.contains_key(&table_name)
+        {
+            return Err(BindError::DuplicatedTable(table_name));
+        }
+        let table_ref_id = TableRefId::new(VALUES_DATABASE_ID, 0, self.values_count);
+        self.values_count += 1;
+        // the columns are resolved like those of a `VALUES` relation without rows
+        self.context.table_names.push(table_name.clone());
+        self.context.values_tables.insert(
+            table_name.clone(),
+            BoundValues {
+                table_ref_id,
+                table_name,
+                column_descs,
+                values: vec![],
+            },
+        );
+        Ok(BoundSubqueryRef {
+            table_ref_id,
+            query: select,
+        })
+    }
+
+    /// Bind the `WHERE` clause of a `select`.
+    ///
+    /// The conjuncts of the forms `x [NOT] IN (subquery)` and `[NOT] EXISTS (subquery)` are
+    /// joined with `from_table` by semi or anti joins, and the other conjuncts are returned as
+    /// the filter.
+    pub(in crate::binder) fn bind_where_clause(
+        &mut self,
+        selection: &Expr,
+        from_table: &mut Option<BoundTableRef>,
+    ) -> Result<Option<BoundExpr>, BindError> {
+        let mut conjuncts = vec![];
+        split_conjuncts(selection, &mut conjuncts);
+        if conjuncts
+            .iter()
+            .all(|expr| subquery_predicate(expr, false).is_none())
+        {
+            return Ok(Some(self.bind_expr(selection)?));
+        }
+
+        let mut join_tables = vec![];
+        let mut filter: Option<Expr> = None;
+        for expr in conjuncts {
+            match subquery_predicate(expr, false) {
+                Some((join_op, left, query)) => {
+                    join_tables.push(self.bind_subquery_join(join_op, left, query)?);
+                }
+                None => {
+                    filter = Some(match filter {
+                        Some(filter) => Expr::BinaryOp {
+                            left: Box::new(filter),
+                            op: BinaryOperator::And,
+                            right: Box::new(expr.clone()),
+                        },
+                        None => expr.clone(),
+                    });
+                }
+            }
+        }
+        // the subqueries are joined after all tables in `FROM`
+        *from_table = Some(match from_table.take() {
+            Some(BoundTableRef::JoinTableRef {
+                relation,
+                join_tables: mut tables,
+            }) => {
+                tables.append(&mut join_tables);
+                BoundTableRef::JoinTableRef {
+                    relation,
+                    join_tables: tables,
+                }
+            }
+            Some(relation) => BoundTableRef::JoinTableRef {
+                relation: relation.into(),
+                join_tables,
+            },
+            None => return Err(BindError::UnsupportedSubquery),
+        });
+        match filter {
+            Some(expr) => Ok(Some(self.bind_expr(&expr)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Bind the subquery of `left [NOT] IN (query)` or `[NOT] EXISTS (query)` as a join.
+    ///
+    /// `left IN (SELECT y ...)` is joined on `left = y`. The conjuncts in the `WHERE` clause of a
+    /// correlated subquery that refer to the outer query are pulled up into the join condition,
+    /// and the columns of the subquery they refer to are appended to its outputs.
+    fn bind_subquery_join(
+        &mut self,
+        join_op: BoundJoinOperator,
+        left: Option<&Expr>,
+        query: &Query,
+    ) -> Result<BoundedSingleJoinTableRef, BindError> {
+        let left = left.map(|expr| self.bind_expr(expr)).transpose()?;
+        let outer_refs = self.outer_refs.replace(vec![]);
+        let select = self.bind_select(query);
+        let params = std::mem::replace(&mut self.outer_refs, outer_refs).unwrap();
+        let mut select = select?;
+        select.type_nulls();
+        let table_ref_id = TableRefId::new(VALUES_DATABASE_ID, 0, self.values_count);
+        self.values_count += 1;
+
+        let mut conds = vec![];
+        if let Some(mut left) = left {
+            if select.select_list.len() != 1 {
+                return Err(BindError::SubqueryColumnCount(select.select_list.len()));
+            }
+            let right_type = select.select_list[0].return_type().unwrap();
+            type_null(&mut left, &right_type);
+            let left_type = left.return_type().unwrap();
+            let ty = coerce(&left_type, &right_type).ok_or_else(|| {
+                BindError::BinaryOpTypeMismatch(
+                    format!("{:?}", left_type),
+                    format!("{:?}", right_type),
+                )
+            })?;
+            cast_to(&mut left, &ty);
+            match &mut select.select_list[0] {
+                BoundExpr::ExprWithAlias(e) => cast_to(&mut e.expr, &ty),
+                expr => cast_to(expr, &ty),
+            }
+            conds.push(BoundExpr::BinaryOp(BoundBinaryOp {
+                op: BinaryOperator::Eq,
+                left_expr: Box::new(left),
+                right_expr: Box::new(output_ref(table_ref_id, &select.select_list, 0)),
+                return_type: Some(DataTypeKind::Boolean.nullable()),
+            }));
+        }
+        if !params.is_empty() {
+            conds.extend(decorrelate(&mut select, &params, table_ref_id)?);
+        }
+
+        let join_cond = conds
+            .into_iter()
+            .reduce(|left, right| {
+                BoundExpr::BinaryOp(BoundBinaryOp {
+                    op: BinaryOperator::And,
+                    left_expr: Box::new(left),
+                    right_expr: Box::new(right),
+                    return_type: Some(DataTypeKind::Boolean.nullable()),
+                })
+            })
+            .unwrap_or(BoundExpr::Constant(DataValue::Bool(true)));
+        Ok(BoundedSingleJoinTableRef {
+            table_ref: Box::new(BoundTableRef::Subquery(BoundSubqueryRef {
+                table_ref_id,
+                query: select,
+            })),
+            join_op,
+            join_cond,
+        })
+    }
+}
+
+/// Split an expression into the conjuncts linked by `AND`.
+fn split_conjuncts<'a>(expr: &'a Expr, conjuncts: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            split_conjuncts(left, conjuncts);
+            split_conjuncts(right, conjuncts);
+        }
+        Expr::Nested(expr) => split_conjuncts(expr, conjuncts),
+        _ => conjuncts.push(expr),
+    }
+}
+
+/// Get the join operator, the left operand and the subquery if the expression is
+/// `x [NOT] IN (subquery)` or `[NOT] EXISTS (subquery)`.
+fn subquery_predicate(
+    expr: &Expr,
+    negated: bool,
+) -> Option<(BoundJoinOperator, Option<&Expr>, &Query)> {
+    match expr {
+        Expr::Nested(expr) => subquery_predicate(expr, negated),
+        Expr::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => subquery_predicate(expr, !negated),
+        Expr::InSubquery {
+            expr,
+            subquery,
+            negated: not_in,
+        } => {
+            let join_op = match negated != *not_in {
+                true => BoundJoinOperator::NullAwareLeftAnti,
+                false => BoundJoinOperator::LeftSemi,
+            };
+            Some((join_op, Some(expr), subquery))
+        }
+        Expr::Exists(subquery) => {
+            let join_op = match negated {
+                true => BoundJoinOperator::LeftAnti,
+                false => BoundJoinOperator::LeftSemi,
+            };
+            Some((join_op, None, subquery))
+        }
+        _ => None,
+    }
+}
+
+/// Refer to the output of a subquery at `index`.
+fn output_ref(table_ref_id: TableRefId, select_list: &[BoundExpr], index: usize) -> BoundExpr {
+    BoundExpr::ColumnRef(BoundColumnRef {
+        table_name: "*SUBQUERY*".into(),
+        column_ref_id: ColumnRefId::from_table(table_ref_id, index as ColumnId),
+        is_primary_key: false,
+        desc: (select_list[index].return_type().unwrap()).to_column(format!("column{}", index + 1)),
+    })
+}
+
+/// Pull the conjuncts referring to the outer columns `params` out of the `WHERE` clause of the
+/// subquery, and rewrite the columns of the subquery in them to refer to its outputs.
+///
+/// Only the correlations in the conjuncts of `WHERE` are supported. And the subquery can't be
+/// aggregated, deduplicated or limited, whose results depend on the correlated conditions.
+fn decorrelate(
+    select: &mut BoundSelect,
+    params: &[BoundColumnRef],
+    table_ref_id: TableRefId,
+) -> Result<Vec<BoundExpr>, BindError> {
+    let has_agg = (select.select_list.iter_mut()).any(|expr| {
+        let mut has_agg = false;
+        visit_mut(expr, &mut |e| has_agg |= matches!(e, BoundExpr::AggCall(_)));
+        has_agg
+    });
+    if has_agg
+        || !select.group_by.is_empty()
+        || select.having.is_some()
+        || select.select_distinct
+        || select.limit.is_some()
+        || select.offset.is_some()
+    {
+        return Err(BindError::UnsupportedCorrelatedSubquery(
+            "aggregation, DISTINCT or LIMIT in a correlated subquery".into(),
+        ));
+    }
+    let refers_to_params = |expr: &mut BoundExpr| {
+        let mut refers = false;
+        visit_mut(expr, &mut |e| {
+            refers |= matches!(e, BoundExpr::ColumnRef(c) if params.contains(c))
+        });
+        refers
+    };
+
+    let mut conjuncts = vec![];
+    let mut stack: Vec<_> = select.where_clause.take().into_iter().collect();
+    while let Some(expr) = stack.pop() {
+        match expr {
+            BoundExpr::BinaryOp(op) if op.op == BinaryOperator::And => {
+                stack.push(*op.right_expr);
+                stack.push(*op.left_expr);
+            }
+            expr => conjuncts.push(expr),
+        }
+    }
+    let mut correlated = vec![];
+    let mut uncorrelated = vec![];
+    for mut expr in conjuncts {
+        match refers_to_params(&mut expr) {
+            true => correlated.push(expr),
+            false => uncorrelated.push(expr),
+        }
+    }
+    select.where_clause = uncorrelated.into_iter().reduce(|left, right| {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op: BinaryOperator::And,
+            left_expr: Box::new(left),
+            right_expr: Box::new(right),
+            return_type: Some(DataTypeKind::Boolean.nullable()),
+        })
+    });
+    let correlated_elsewhere = (select.select_list.iter_mut())
+        .chain(select.orderby.iter_mut().map(|o| &mut o.expr))
+        .chain(select.where_clause.iter_mut())
+        .any(&refers_to_params)
+        || select.from_table.as_mut().map_or(false, |table| {
+            let mut refers = false;
+            visit_join_conds_mut(table, &mut |cond| refers |= refers_to_params(cond));
+            refers
+        });
+    if correlated_elsewhere {
+        return Err(BindError::UnsupportedCorrelatedSubquery(
+            "outer columns referred outside the conjuncts of WHERE".into(),
+        ));
+    }
+
+    // the columns of the subquery are output after its select list
+    for cond in &mut correlated {
+        visit_mut(cond, &mut |e| {
+            if matches!(e, BoundExpr::ColumnRef(c) if !params.contains(c)) {
+                let index = match select.select_list.iter().position(|item| *item == *e) {
+                    Some(index) => index,
+                    None => {
+                        select.select_list.push(e.clone());
+                        select.select_list.len() - 1
+                    }
+                };
+                *e = output_ref(table_ref_id, &select.select_list, index);
+            }
+        });
+    }
+    Ok(correlated)
+}
+
+/// Visit the join conditions in a table reference.
+fn visit_join_conds_mut(table_ref: &mut BoundTableRef, f: &mut impl FnMut(&mut BoundExpr)) {
+    if let BoundTableRef::JoinTableRef {
+        relation,
+        join_tables,
+    } = table_ref
+    {
+        visit_join_conds_mut(relation, f);
+        for join_table in join_tables {
+            visit_join_conds_mut(&mut join_table.table_ref, f);
+            f(&mut join_table.join_cond);
+        }
+    }
+}
+
+/// Visit the expression and its sub-expressions in pre-order. The sub-expressions are visited
+/// after the expression is possibly replaced by `f`.
+fn visit_mut(expr: &mut BoundExpr, f: &mut impl FnMut(&mut BoundExpr)) {
+    use BoundExpr::*;
+    f(expr);
+    match expr {
+        BinaryOp(binary_op) => {
+            visit_mut(&mut binary_op.left_expr, f);
+            visit_mut(&mut binary_op.right_expr, f);
+        }
+        UnaryOp(unary_op) => visit_mut(&mut unary_op.expr, f),
+        TypeCast(type_cast) => visit_mut(&mut type_cast.expr, f),
+        AggCall(agg) => agg.args.iter_mut().for_each(|arg| visit_mut(arg, f)),
+        ScalarFunction(func) => func.args.iter_mut().for_each(|arg| visit_mut(arg, f)),
+        IsNull(isnull) => visit_mut(&mut isnull.expr, f),
+        Case(case) => {
+            for (cond, result) in &mut case.when_then {
+                visit_mut(cond, f);
+                visit_mut(result, f);
+            }
+            if let Some(else_result) = &mut case.else_result {
+                visit_mut(else_result, f);
+            }
+        }
+        Between(between) => {
+            visit_mut(&mut between.expr, f);
+            visit_mut(&mut between.low, f);
+            visit_mut(&mut between.high, f);
+        }
+        Like(like) => {
+            visit_mut(&mut like.expr, f);
+            visit_mut(&mut like.pattern, f);
+        }
+        ExprWithAlias(expr_with_alias) => visit_mut(&mut expr_with_alias.expr, f),
+        Subquery(subquery) => subquery.args.iter_mut().for_each(|arg| visit_mut(arg, f)),
+        Constant(_) | ColumnRef(_) | InputRef(_) | Alias(_) => {}
+    }
+}
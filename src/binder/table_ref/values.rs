@@ -0,0 +1,209 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::catalog::ColumnRefId;
+use crate::parser::{TableAlias, Values};
+use crate::types::{DataType, DataTypeExt, DatabaseId};
+
+/// The database id of `VALUES` relations, which are not stored in the catalog.
+///
+/// Each `VALUES` relation in a statement gets a table id under this database, so that its
+/// columns can be referred by [`ColumnRefId`]s like the columns of base tables.
+pub const VALUES_DATABASE_ID: DatabaseId = DatabaseId::MAX;
+
+/// A bound `VALUES` relation, e.g. `(VALUES (1, 'a'), (2, 'b')) AS t(id, name)`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundValues {
+    pub table_ref_id: TableRefId,
+    pub table_name: String,
+    pub column_descs: Vec<ColumnDesc>,
+    /// Each row is casted to the types of columns.
+    pub values: Vec<Vec<BoundExpr>>,
+}
+
+impl BoundValues {
+    /// Get the types of columns.
+    pub fn column_types(&self) -> Vec<DataType> {
+        self.column_descs
+            .iter()
+            .map(|desc| desc.datatype().clone())
+            .collect()
+    }
+}
+
+impl Binder {
+    /// Bind a `VALUES` relation and register it in the context.
+    ///
+    /// The type of each column is the common type of values in all rows. Without an alias, the
+    /// relation is named `*VALUES*` and the columns are named `column1`, `column2`, etc.
+    pub fn bind_values(
+        &mut self,
+        values: &Values,
+        alias: Option<&TableAlias>,
+    ) -> Result<BoundValues, BindError> {
+        let mut bound_values: Vec<Vec<BoundExpr>> = vec![];
+        for row in &values.0 {
+            let row = row
+                .iter()
+                .map(|expr| self.bind_expr(expr))
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(first) = bound_values.first() {
+                if row.len() != first.len() {
+                    return Err(BindError::ValuesArityMismatch(first.len(), row.len()));
+                }
+            }
+            bound_values.push(row);
+        }
+        let arity = bound_values.first().map_or(0, |row| row.len());
+
+        // unify the types of each column
+        let mut column_types = vec![];
+        for i in 0..arity {
+            let mut ty: Option<DataType> = None;
+            let mut nullable = false;
+            for row in &bound_values {
+                let t = match row[i].return_type() {
+                    Some(t) => t,
+                    // NULL
+                    None => {
+                        nullable = true;
+                        continue;
+                    }
+                };
+                ty = match ty {
+                    None => Some(t),
+                    Some(ty) => Some(coerce(&ty, &t).ok_or_else(|| {
+                        BindError::TypeMismatch(format!("{:?}", ty), format!("{:?}", t))
+                    })?),
+                };
+            }
+            // all values are NULL
+            let mut ty = ty.unwrap_or_else(default_null_type);
+            ty.nullable |= nullable;
+            column_types.push(ty);
+        }
+        for row in &mut bound_values {
+            for (expr, ty) in row.iter_mut().zip(&column_types) {
+                cast_to(expr, ty);
+            }
+        }
+
+        let (table_name, column_names) = match alias {
+            Some(alias) => (
+                alias.name.value.to_lowercase(),
+                alias
+                    .columns
+                    .iter()
+                    .map(|col| col.value.to_lowercase())
+                    .collect(),
+            ),
+            None => ("*VALUES*".to_string(), vec![]),
+        };
+        if column_names.len() > arity {
+            return Err(BindError::ValuesArityMismatch(arity, column_names.len()));
+        }
+        let column_descs = column_types
+            .into_iter()
+            .enumerate()
+            .map(|(i, ty)| match column_names.get(i) {
+                Some(name) => ty.to_column(name.clone()),
+                None => ty.to_column(format!("column{}", i + 1)),
+            })
+            .collect();
+
+        if self.context.regular_tables.contains_key(&table_name)
+            || self.context.values_tables.contains_key(&table_name)
+        {
+            return Err(BindError::DuplicatedTable(table_name));
+        }
+        let table_ref_id = TableRefId::new(VALUES_DATABASE_ID, 0, self.values_count);
+        self.values_count += 1;
+        let values = BoundValues {
+            table_ref_id,
+            table_name: table_name.clone(),
+            column_descs,
+            values: bound_values,
+        };
+        self.context.table_names.push(table_name.clone());
+        self.context
+            .values_tables
+            .insert(table_name, values.clone());
+        Ok(values)
+    }
+
+    /// Bind a column of a `VALUES` relation by its name.
+    pub(in crate::binder) fn bind_values_column_ref(
+        values: &BoundValues,
+        column_name: &str,
+    ) -> Option<BoundExpr> {
+        let idx = (values.column_descs.iter()).position(|desc| desc.name() == column_name)?;
+        Some(BoundExpr::ColumnRef(BoundColumnRef {
+            table_name: values.table_name.clone(),
+            column_ref_id: ColumnRefId::from_table(values.table_ref_id, idx as ColumnId),
+            is_primary_key: false,
+            desc: values.column_descs[idx].clone(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::RootCatalog;
+    use crate::parser::{parse, SetExpr, Statement};
+    use crate::types::PhysicalDataTypeKind;
+
+    fn bind_values(sql: &str) -> Result<BoundValues, BindError> {
+        let mut binder = Binder::new(Arc::new(RootCatalog::new()));
+        let stmts = parse(sql).unwrap();
+        match &stmts[0] {
+            Statement::Query(query) => match &query.body {
+                SetExpr::Select(select) => match &select.from[0].relation {
+                    TableFactor::Derived {
+                        subquery, alias, ..
+                    } => match &subquery.body {
+                        SetExpr::Values(values) => binder.bind_values(values, alias.as_ref()),
+                        _ => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn bind_values_type_unification() {
+        let values =
+            bind_values("select * from (values (1, 'a', null), (2.5, null, null)) as t(id)")
+                .unwrap();
+        assert_eq!(values.table_name, "t");
+        let types = values.column_types();
+        assert_eq!(types[0], DataTypeKind::Double.not_null());
+        assert_eq!(types[1].physical_kind(), PhysicalDataTypeKind::String);
+        assert!(types[1].is_nullable());
+        assert_eq!(types[2], DataTypeKind::Int(None).nullable());
+        let names = (values.column_descs.iter())
+            .map(|desc| desc.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["id", "column2", "column3"]);
+        // the integer is casted to double
+        assert!(matches!(values.values[0][0], BoundExpr::TypeCast(_)));
+
+        assert!(matches!(
+            bind_values("select * from (values (1), ('a'))"),
+            Err(BindError::TypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            bind_values("select * from (values (1, 2), (3))"),
+            Err(BindError::ValuesArityMismatch(2, 1))
+        ));
+        assert!(matches!(
+            bind_values("select * from (values (1)) as t(a, b)"),
+            Err(BindError::ValuesArityMismatch(1, 2))
+        ));
+    }
+}
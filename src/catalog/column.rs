@@ -1,15 +1,20 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use crate::types::{ColumnId, DataType};
+use crate::types::{ColumnId, DataType, DataValue};
 
 /// A descriptor of a column.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ColumnDesc {
     datatype: DataType,
     name: String,
-    is_primary: bool,
+    /// The position of the column in the primary key, if it is a part of the primary key.
+    primary_key_index: Option<usize>,
+    /// The value of the column in rows that don't provide it, e.g. rows written before the column
+    /// is added.
+    default: Option<DataValue>,
 }
 
 impl ColumnDesc {
@@ -17,16 +22,38 @@ impl ColumnDesc {
         ColumnDesc {
             datatype,
             name,
-            is_primary,
+            primary_key_index: if is_primary { Some(0) } else { None },
+            default: None,
         }
     }
 
     pub fn set_primary(&mut self, is_primary: bool) {
-        self.is_primary = is_primary;
+        self.primary_key_index = if is_primary { Some(0) } else { None };
     }
 
     pub fn is_primary(&self) -> bool {
-        self.is_primary
+        self.primary_key_index.is_some()
+    }
+
+    /// Set the position of the column in a composite primary key.
+    pub fn set_primary_key_index(&mut self, index: usize) {
+        self.primary_key_index = Some(index);
+    }
+
+    pub fn primary_key_index(&self) -> Option<usize> {
+        self.primary_key_index
+    }
+
+    pub fn set_default(&mut self, default: DataValue) {
+        self.default = Some(default);
+    }
+
+    pub fn default(&self) -> Option<&DataValue> {
+        self.default.as_ref()
+    }
+
+    pub fn set_nullable(&mut self, nullable: bool) {
+        self.datatype.nullable = nullable;
     }
 
     pub fn is_nullable(&self) -> bool {
@@ -92,23 +119,39 @@ impl ColumnCatalog {
         self.desc.is_primary()
     }
 
+    pub fn set_primary_key_index(&mut self, index: usize) {
+        self.desc.set_primary_key_index(index);
+    }
+
+    pub fn primary_key_index(&self) -> Option<usize> {
+        self.desc.primary_key_index()
+    }
+
+    pub fn set_default(&mut self, default: DataValue) {
+        self.desc.set_default(default);
+    }
+
+    /// Get the default value of the column, or `NULL` if it has none.
+    pub fn default_value(&self) -> DataValue {
+        self.desc.default().cloned().unwrap_or(DataValue::Null)
+    }
+
+    pub fn set_nullable(&mut self, nullable: bool) {
+        self.desc.set_nullable(nullable);
+    }
+
     pub fn is_nullable(&self) -> bool {
         self.desc.is_nullable()
     }
 }
 
-/// Find the id of the sort key among column catalogs
-pub fn find_sort_key_id(column_infos: &[ColumnCatalog]) -> Option<usize> {
-    let mut key = None;
-    for (id, column_info) in column_infos.iter().enumerate() {
-        if column_info.is_primary() {
-            if key.is_some() {
-                panic!("only one primary key is supported");
-            }
-            key = Some(id);
-        }
-    }
-    key
+/// Find the ids of the sort key among column catalogs, in the order of the primary key.
+pub fn find_sort_key_ids(column_infos: &[ColumnCatalog]) -> Vec<usize> {
+    (column_infos.iter().enumerate())
+        .filter_map(|(id, column_info)| Some((column_info.primary_key_index()?, id)))
+        .sorted()
+        .map(|(_, id)| id)
+        .collect()
 }
 
 #[cfg(test)]
@@ -127,4 +170,20 @@ mod tests {
         col_catalog.set_primary(true);
         assert!(col_catalog.is_primary());
     }
+
+    #[test]
+    fn test_find_sort_key_ids() {
+        let mut columns = (0..4)
+            .map(|id| {
+                let desc = DataTypeKind::Int(None)
+                    .not_null()
+                    .to_column(format!("v{}", id));
+                ColumnCatalog::new(id, desc)
+            })
+            .collect_vec();
+        assert!(find_sort_key_ids(&columns).is_empty());
+        columns[3].set_primary_key_index(0);
+        columns[1].set_primary_key_index(1);
+        assert_eq!(find_sort_key_ids(&columns), vec![3, 1]);
+    }
 }
@@ -8,6 +8,7 @@ pub use self::column::*;
 pub use self::database::*;
 pub use self::root::*;
 pub use self::schema::*;
+pub use self::statistics::*;
 pub use self::table::*;
 use crate::types::*;
 
@@ -18,6 +19,7 @@ mod column;
 mod database;
 mod root;
 mod schema;
+mod statistics;
 mod table;
 
 pub type RootCatalogRef = Arc<RootCatalog>;
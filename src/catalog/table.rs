@@ -1,7 +1,7 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use std::collections::{BTreeMap, HashMap};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use super::*;
 use crate::types::{ColumnId, TableId};
@@ -21,6 +21,10 @@ struct Inner {
     #[allow(dead_code)]
     is_materialized_view: bool,
     next_column_id: ColumnId,
+    /// The statistics collected by the last `ANALYZE`, if any.
+    statistics: Option<Arc<TableStatistics>>,
+    /// Columns with a secondary index.
+    indexes: Vec<ColumnId>,
 }
 
 impl TableCatalog {
@@ -38,6 +42,8 @@ impl TableCatalog {
                 columns: BTreeMap::new(),
                 is_materialized_view,
                 next_column_id: 0,
+                statistics: None,
+                indexes: vec![],
             }),
         };
         for col_catalog in columns {
@@ -100,6 +106,40 @@ impl TableCatalog {
     pub fn id(&self) -> TableId {
         self.id
     }
+
+    /// Get the statistics of the table collected by `ANALYZE`.
+    pub fn statistics(&self) -> Option<Arc<TableStatistics>> {
+        let inner = self.inner.lock().unwrap();
+        inner.statistics.clone()
+    }
+
+    pub fn set_statistics(&self, statistics: TableStatistics) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.statistics = Some(Arc::new(statistics));
+    }
+
+    /// Remove the statistics of the table, e.g. after all rows are removed.
+    pub fn clear_statistics(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.statistics = None;
+    }
+
+    /// Record a secondary index on the column.
+    pub fn add_index(&self, column_id: ColumnId) -> Result<(), CatalogError> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.indexes.contains(&column_id) {
+            let name = inner.columns[&column_id].name().to_string();
+            return Err(CatalogError::Duplicated("index", name));
+        }
+        inner.indexes.push(column_id);
+        Ok(())
+    }
+
+    /// Get the columns with a secondary index.
+    pub fn indexes(&self) -> Vec<ColumnId> {
+        let inner = self.inner.lock().unwrap();
+        inner.indexes.clone()
+    }
 }
 
 #[cfg(test)]
@@ -1,23 +1,30 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use futures::TryStreamExt;
 use risinglight_proto::rowset::block_statistics::BlockStatisticsType;
 use tracing::debug;
 
-use crate::array::{ArrayBuilder, ArrayBuilderImpl, DataChunk, I32ArrayBuilder, Utf8ArrayBuilder};
-use crate::binder::{BindError, Binder};
-use crate::catalog::RootCatalogRef;
-use crate::executor::{ExecutorBuilder, ExecutorError};
+use crate::array::{
+    ArrayBuilder, ArrayBuilderImpl, BoolArrayBuilder, DataChunk, I32ArrayBuilder, Utf8ArrayBuilder,
+};
+use crate::binder::{
+    lower_case_name, split_name, BindError, Binder, BoundStatement, PreparedStatement,
+};
+use crate::catalog::{
+    CatalogError, RootCatalogRef, TableRefId, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME,
+};
+use crate::executor::{ExecutorBuilder, ExecutorError, QueryStats};
 use crate::logical_planner::{LogicalPlanError, LogicalPlaner};
 use crate::optimizer::logical_plan_rewriter::{InputRefResolver, PlanRewriter};
 use crate::optimizer::plan_nodes::PlanRef;
 use crate::optimizer::Optimizer;
-use crate::parser::{parse, ParserError};
+use crate::parser::{parse, Ident, ObjectName, ParserError, Statement};
 use crate::storage::{
-    InMemoryStorage, SecondaryStorage, SecondaryStorageOptions, Storage, StorageColumnRef,
-    StorageImpl, Table,
+    ExplicitTxnImpl, InMemoryStorage, SecondaryStorage, SecondaryStorageOptions, Storage,
+    StorageColumnRef, StorageImpl, Table,
 };
 
 /// The database instance.
@@ -25,6 +32,12 @@ pub struct Database {
     catalog: RootCatalogRef,
     executor_builder: ExecutorBuilder,
     storage: StorageImpl,
+    /// The statements prepared by `PREPARE`.
+    prepared_statements: Mutex<HashMap<String, PreparedStatement>>,
+    /// The transaction started by `BEGIN`, if any.
+    txn: Mutex<Option<ExplicitTxnImpl>>,
+    /// Whether `||` skips NULLs like `concat`.
+    concat_skips_nulls: bool,
 }
 
 impl Database {
@@ -38,6 +51,9 @@ impl Database {
             catalog,
             executor_builder: execution_manager,
             storage,
+            prepared_statements: Mutex::new(HashMap::new()),
+            txn: Mutex::new(None),
+            concat_skips_nulls: false,
         }
     }
 
@@ -52,6 +68,35 @@ impl Database {
             catalog,
             executor_builder: execution_manager,
             storage,
+            prepared_statements: Mutex::new(HashMap::new()),
+            txn: Mutex::new(None),
+            concat_skips_nulls: false,
+        }
+    }
+
+    /// Set the number of tasks running a parallel operator, e.g. hash aggregation.
+    pub fn with_parallelism(self, parallelism: usize) -> Self {
+        Database {
+            executor_builder: self.executor_builder.with_parallelism(parallelism),
+            ..self
+        }
+    }
+
+    /// Set the maximum memory usage in bytes of an operator, e.g. the hash table of aggregation,
+    /// beyond which data is spilled to temporary files.
+    pub fn with_memory_limit(self, memory_limit: Option<usize>) -> Self {
+        Database {
+            executor_builder: self.executor_builder.with_memory_limit(memory_limit),
+            ..self
+        }
+    }
+
+    /// Set whether `||` skips NULLs like `concat`, instead of returning NULL if either side is
+    /// NULL.
+    pub fn with_concat_skips_nulls(self, concat_skips_nulls: bool) -> Self {
+        Database {
+            concat_skips_nulls,
+            ..self
         }
     }
 
@@ -92,6 +137,66 @@ impl Database {
         Ok(vec![DataChunk::from_iter(vecs.into_iter())])
     }
 
+    /// Handle `SHOW TABLES` and `DESCRIBE`, which only read the catalog. Returns `None` if the
+    /// statement is not one of them.
+    fn run_catalog_statement(&self, stmt: &Statement) -> Result<Option<Vec<DataChunk>>, Error> {
+        match stmt {
+            Statement::ShowTables { .. } => Ok(Some(self.run_show_tables())),
+            Statement::ExplainTable { table_name, .. } => Ok(Some(self.run_describe(table_name)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// List the names of tables in the default schema.
+    fn run_show_tables(&self) -> Vec<DataChunk> {
+        let mut names = (self.catalog.get_database_by_name(DEFAULT_DATABASE_NAME))
+            .and_then(|database| database.get_schema_by_name(DEFAULT_SCHEMA_NAME))
+            .map(|schema| {
+                (schema.all_tables().values())
+                    .map(|table| table.name())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        names.sort();
+        let mut table_vec = Utf8ArrayBuilder::with_capacity(names.len());
+        for name in &names {
+            table_vec.push(Some(name));
+        }
+        let mut chunk = DataChunk::from_iter([ArrayBuilderImpl::from(table_vec)]);
+        chunk.set_header(vec!["table_name".into()]);
+        vec![chunk]
+    }
+
+    /// List the columns of a table with their types and nullability.
+    fn run_describe(&self, name: &ObjectName) -> Result<Vec<DataChunk>, Error> {
+        let name = lower_case_name(name);
+        let (database_name, schema_name, table_name) = split_name(&name)?;
+        let table = (self.catalog)
+            .get_table_id_by_name(database_name, schema_name, table_name)
+            .and_then(|table_id| self.catalog.get_table(&table_id))
+            .ok_or_else(|| CatalogError::NotFound("table", name.to_string()))?;
+        let columns = table.all_columns();
+        let mut name_vec = Utf8ArrayBuilder::with_capacity(columns.len());
+        let mut type_vec = Utf8ArrayBuilder::with_capacity(columns.len());
+        let mut nullable_vec = BoolArrayBuilder::with_capacity(columns.len());
+        for column in columns.values() {
+            name_vec.push(Some(column.name()));
+            type_vec.push(Some(&column.datatype().kind().to_string()));
+            nullable_vec.push(Some(&column.is_nullable()));
+        }
+        let mut chunk = DataChunk::from_iter([
+            ArrayBuilderImpl::from(name_vec),
+            ArrayBuilderImpl::from(type_vec),
+            ArrayBuilderImpl::from(nullable_vec),
+        ]);
+        chunk.set_header(vec![
+            "column_name".into(),
+            "data_type".into(),
+            "nullable".into(),
+        ]);
+        Ok(vec![chunk])
+    }
+
     pub async fn run_internal(&self, cmd: &str) -> Result<Vec<DataChunk>, Error> {
         if let Some((cmd, arg)) = cmd.split_once(' ') {
             if cmd == "stat" {
@@ -109,19 +214,21 @@ impl Database {
                         .expect("column not found");
                     let table = storage.get_table(table_id)?;
                     let txn = table.read().await?;
-                    let row_count = txn.aggreagate_block_stat(&[
-                        (
-                            BlockStatisticsType::RowCount,
-                            // Note that `col_id` is the column catalog id instead of storage
-                            // column id. This should be fixed in the
-                            // future.
-                            StorageColumnRef::Idx(col_id),
-                        ),
-                        (
-                            BlockStatisticsType::DistinctValue,
-                            StorageColumnRef::Idx(col_id),
-                        ),
-                    ]);
+                    let row_count = txn
+                        .aggreagate_block_stat(&[
+                            (
+                                BlockStatisticsType::RowCount,
+                                // Note that `col_id` is the column catalog id instead of storage
+                                // column id. This should be fixed in the
+                                // future.
+                                StorageColumnRef::Idx(col_id),
+                            ),
+                            (
+                                BlockStatisticsType::DistinctValue,
+                                StorageColumnRef::Idx(col_id),
+                            ),
+                        ])
+                        .await?;
                     let mut stat_name = Utf8ArrayBuilder::with_capacity(2);
                     let mut stat_value = Utf8ArrayBuilder::with_capacity(2);
                     stat_name.push(Some("RowCount"));
@@ -163,60 +270,235 @@ impl Database {
 
     /// Run SQL queries and return the outputs.
     pub async fn run(&self, sql: &str) -> Result<Vec<DataChunk>, Error> {
+        let (outputs, _) = self.run_with_stats(sql).await?;
+        Ok(outputs)
+    }
+
+    /// Run SQL queries, and return the outputs with the statistics of the execution.
+    pub async fn run_with_stats(
+        &self,
+        sql: &str,
+    ) -> Result<(Vec<DataChunk>, Arc<QueryStats>), Error> {
+        let stats = Arc::new(QueryStats::default());
         if let Some(cmdline) = sql.strip_prefix('\\') {
-            return self.run_internal(cmdline).await;
+            return Ok((self.run_internal(cmdline).await?, stats));
         }
 
         // parse
         let stmts = parse(sql)?;
 
-        let mut binder = Binder::new(self.catalog.clone());
+        let mut binder =
+            Binder::new(self.catalog.clone()).with_concat_skips_nulls(self.concat_skips_nulls);
         let logical_planner = LogicalPlaner::default();
         let mut optimizer = Optimizer {
             enable_filter_scan: self.storage.enable_filter_scan(),
+            enable_limit_scan: self.storage.enable_limit_scan(),
+            enable_index_scan: self.storage.enable_index_scan(),
+            catalog: self.catalog.clone(),
         };
         // TODO: parallelize
         let mut outputs = vec![];
         for stmt in stmts {
-            let stmt = binder.bind(&stmt)?;
-            debug!("{:#?}", stmt);
-            let logical_plan = logical_planner.plan(stmt)?;
-            debug!("{:#?}", logical_plan);
-            // Resolve input reference
-            let mut input_ref_resolver = InputRefResolver::default();
-            let logical_plan = input_ref_resolver.rewrite(logical_plan);
-            let column_names = logical_plan.out_names();
-            debug!("{:#?}", logical_plan);
-            let optimized_plan = optimizer.optimize(logical_plan);
-            debug!("{:#?}", optimized_plan);
-            let executor = self.executor_builder.clone().build(optimized_plan);
-            let mut output: Vec<DataChunk> = executor.try_collect().await.map_err(|e| {
-                debug!("error: {}", e);
-                e
-            })?;
-            for chunk in &output {
-                debug!("output:\n{}", chunk);
+            if self.run_transaction_statement(&stmt).await? {
+                continue;
             }
-            if !column_names.is_empty() && !output.is_empty() {
-                output[0].set_header(column_names);
+            if let Some(output) = self.run_catalog_statement(&stmt)? {
+                outputs.extend(output);
+                continue;
             }
-            outputs.extend(output);
+            let txn = self.txn.lock().unwrap().clone();
+            if let Some(txn) = &txn {
+                if txn.is_failed() {
+                    return Err(Error::Transaction(
+                        "current transaction is aborted, commands ignored until end of \
+                         transaction block"
+                            .into(),
+                    ));
+                }
+                if matches!(
+                    stmt,
+                    Statement::CreateTable { .. }
+                        | Statement::AlterTable { .. }
+                        | Statement::Drop { .. }
+                        | Statement::Truncate { .. }
+                        | Statement::CreateIndex { .. }
+                ) {
+                    return Err(Error::Transaction(
+                        "DDL statements cannot run inside a transaction block".into(),
+                    ));
+                }
+            }
+            let output = self
+                .run_statement(
+                    &mut binder,
+                    &logical_planner,
+                    &mut optimizer,
+                    stmt,
+                    &stats,
+                    txn.clone(),
+                )
+                .await;
+            // a failed statement may have written part of its rows
+            if let (Err(_), Some(txn)) = (&output, &txn) {
+                txn.set_failed();
+            }
+            outputs.extend(output?);
+        }
+        Ok((outputs, stats))
+    }
+
+    /// Run a statement, in the transaction started by `BEGIN` if any.
+    async fn run_statement(
+        &self,
+        binder: &mut Binder,
+        logical_planner: &LogicalPlaner,
+        optimizer: &mut Optimizer,
+        stmt: Statement,
+        stats: &Arc<QueryStats>,
+        txn: Option<ExplicitTxnImpl>,
+    ) -> Result<Vec<DataChunk>, Error> {
+        let stmt = match self.bind_statement(binder, stmt)? {
+            Some(stmt) => stmt,
+            None => return Ok(vec![]),
+        };
+        debug!("{:#?}", stmt);
+        let logical_plan = logical_planner.plan(stmt)?;
+        debug!("{:#?}", logical_plan);
+        // Resolve input reference
+        let mut input_ref_resolver = InputRefResolver::default();
+        let logical_plan = input_ref_resolver.rewrite(logical_plan);
+        let column_names = logical_plan.out_names();
+        debug!("{:#?}", logical_plan);
+        let optimized_plan = optimizer.optimize(logical_plan);
+        debug!("{:#?}", optimized_plan);
+        let executor = (self.executor_builder.clone())
+            .with_stats(stats.clone())
+            .with_txn(txn)
+            .build(optimized_plan);
+        let mut output: Vec<DataChunk> = executor.try_collect().await.map_err(|e| {
+            debug!("error: {}", e);
+            e
+        })?;
+        for chunk in &output {
+            debug!("output:\n{}", chunk);
+        }
+        if !column_names.is_empty() && !output.is_empty() {
+            output[0].set_header(column_names);
+        }
+        Ok(output)
+    }
+
+    /// Handle `BEGIN`, `COMMIT` and `ROLLBACK`. Returns false if the statement is not one of them.
+    ///
+    /// `BEGIN` captures a snapshot of all tables. The writes of the following statements are
+    /// only visible to themselves until `COMMIT`, and are discarded by `ROLLBACK`.
+    async fn run_transaction_statement(&self, stmt: &Statement) -> Result<bool, Error> {
+        match stmt {
+            Statement::StartTransaction { .. } => {
+                if self.txn.lock().unwrap().is_some() {
+                    return Err(Error::Transaction(
+                        "there is already a transaction in progress".into(),
+                    ));
+                }
+                let txn = ExplicitTxnImpl::begin(&self.storage, &self.all_table_ids()).await?;
+                *self.txn.lock().unwrap() = Some(txn);
+            }
+            Statement::Commit { .. } => {
+                let txn = self.txn.lock().unwrap().take();
+                if let Some(txn) = txn {
+                    if txn.is_failed() {
+                        txn.rollback().await?;
+                        return Err(Error::Transaction(
+                            "the transaction is rolled back as a statement in it failed".into(),
+                        ));
+                    }
+                    txn.commit().await?;
+                }
+            }
+            Statement::Rollback { .. } => {
+                let txn = self.txn.lock().unwrap().take();
+                if let Some(txn) = txn {
+                    txn.rollback().await?;
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn all_table_ids(&self) -> Vec<TableRefId> {
+        let mut table_ids = vec![];
+        for (_, database) in self.catalog.all_databases() {
+            for (_, schema) in database.all_schemas() {
+                for (_, table) in schema.all_tables() {
+                    table_ids.push(TableRefId::new(database.id(), schema.id(), table.id()));
+                }
+            }
+        }
+        table_ids
+    }
+
+    /// Bind a statement, or handle it if it manages prepared statements.
+    fn bind_statement(
+        &self,
+        binder: &mut Binder,
+        stmt: Statement,
+    ) -> Result<Option<BoundStatement>, Error> {
+        let mut prepared_statements = self.prepared_statements.lock().unwrap();
+        let not_exist = |name: &Ident| {
+            Error::PreparedStatement(format!("prepared statement {} does not exist", name))
+        };
+        match stmt {
+            Statement::Prepare {
+                name,
+                data_types,
+                statement,
+            } => {
+                if prepared_statements.contains_key(&name.value) {
+                    return Err(Error::PreparedStatement(format!(
+                        "prepared statement {} already exists",
+                        name
+                    )));
+                }
+                let prepared = binder.prepare(&statement, &data_types)?;
+                prepared_statements.insert(name.value, prepared);
+                Ok(None)
+            }
+            Statement::Execute { name, parameters } => {
+                let prepared = prepared_statements
+                    .get(&name.value)
+                    .ok_or_else(|| not_exist(&name))?;
+                Ok(Some(binder.bind_prepared(prepared, &parameters)?))
+            }
+            Statement::Deallocate { name, .. } => {
+                prepared_statements
+                    .remove(&name.value)
+                    .ok_or_else(|| not_exist(&name))?;
+                Ok(None)
+            }
+            stmt => Ok(Some(binder.bind(&stmt)?)),
         }
-        Ok(outputs)
     }
 
     // Generate the execution plans for SQL queries.
     pub fn generate_execution_plan(&self, sql: &str) -> Result<Vec<PlanRef>, Error> {
         let stmts = parse(sql)?;
 
-        let mut binder = Binder::new(self.catalog.clone());
+        let mut binder =
+            Binder::new(self.catalog.clone()).with_concat_skips_nulls(self.concat_skips_nulls);
         let logical_planner = LogicalPlaner::default();
         let mut optimizer = Optimizer {
             enable_filter_scan: self.storage.enable_filter_scan(),
+            enable_limit_scan: self.storage.enable_limit_scan(),
+            enable_index_scan: self.storage.enable_index_scan(),
+            catalog: self.catalog.clone(),
         };
         let mut plans = vec![];
         for stmt in stmts {
-            let stmt = binder.bind(&stmt)?;
+            let stmt = match self.bind_statement(&mut binder, stmt)? {
+                Some(stmt) => stmt,
+                None => continue,
+            };
             debug!("{:#?}", stmt);
             let logical_plan = logical_planner.plan(stmt)?;
             debug!("{:#?}", logical_plan);
@@ -266,6 +548,16 @@ pub enum Error {
         #[backtrace]
         crate::storage::TracedStorageError,
     ),
+    #[error("catalog error: {0}")]
+    Catalog(
+        #[source]
+        #[from]
+        CatalogError,
+    ),
     #[error("Internal error: {0}")]
     InternalError(String),
+    #[error("{0}")]
+    PreparedStatement(String),
+    #[error("{0}")]
+    Transaction(String),
 }
@@ -0,0 +1,95 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::array::Array;
+
+/// State for `bool_and` or `bool_or` aggregation
+pub struct BoolAndOrAggregationState {
+    result: DataValue,
+    is_and: bool,
+}
+
+impl BoolAndOrAggregationState {
+    pub fn new(is_and: bool) -> Self {
+        Self {
+            result: DataValue::Null,
+            is_and,
+        }
+    }
+
+    /// Whether the result can no longer be changed by further input, i.e. `false` for `bool_and`
+    /// and `true` for `bool_or`.
+    fn is_determined(&self) -> bool {
+        self.result == DataValue::Bool(!self.is_and)
+    }
+
+    fn update_bool(&mut self, value: bool) {
+        self.result = match self.result {
+            DataValue::Null => DataValue::Bool(value),
+            DataValue::Bool(res) if self.is_and => DataValue::Bool(res && value),
+            DataValue::Bool(res) => DataValue::Bool(res || value),
+            _ => panic!("Mismatched type"),
+        };
+    }
+}
+
+impl AggregationState for BoolAndOrAggregationState {
+    fn update(&mut self, array: &ArrayImpl) -> Result<(), ExecutorError> {
+        match array {
+            ArrayImpl::Bool(arr) => {
+                for value in arr.iter().flatten() {
+                    if self.is_determined() {
+                        break;
+                    }
+                    self.update_bool(*value);
+                }
+            }
+            _ => panic!("Mismatched type"),
+        }
+        Ok(())
+    }
+
+    fn update_single(&mut self, value: &DataValue) -> Result<(), ExecutorError> {
+        match value {
+            DataValue::Null => {}
+            _ if self.is_determined() => {}
+            DataValue::Bool(val) => self.update_bool(*val),
+            _ => panic!("Mismatched type"),
+        }
+        Ok(())
+    }
+
+    fn output(&self) -> DataValue {
+        self.result.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregate(is_and: bool, values: &[Option<bool>]) -> DataValue {
+        let mut state = BoolAndOrAggregationState::new(is_and);
+        let array = ArrayImpl::Bool(values.iter().cloned().collect());
+        state.update(&array).unwrap();
+        state.output()
+    }
+
+    #[test]
+    fn test_bool_and_or() {
+        let values = [Some(true), None, Some(false), Some(true)];
+        assert_eq!(aggregate(true, &values), DataValue::Bool(false));
+        assert_eq!(aggregate(false, &values), DataValue::Bool(true));
+
+        let values = [Some(true), None, Some(true)];
+        assert_eq!(aggregate(true, &values), DataValue::Bool(true));
+        assert_eq!(aggregate(false, &values), DataValue::Bool(true));
+
+        let values = [Some(false), None];
+        assert_eq!(aggregate(true, &values), DataValue::Bool(false));
+        assert_eq!(aggregate(false, &values), DataValue::Bool(false));
+
+        assert_eq!(aggregate(true, &[None, None]), DataValue::Null);
+        assert_eq!(aggregate(false, &[]), DataValue::Null);
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashSet;
+
+use super::*;
+
+/// State for aggregation with `DISTINCT` keyword.
+///
+/// It forwards each non-null value to the inner state only when the value is seen for the first
+/// time.
+pub struct DistinctAggregationState {
+    inner: Box<dyn AggregationState>,
+    distinct_values: HashSet<DataValue>,
+}
+
+impl DistinctAggregationState {
+    pub fn new(inner: Box<dyn AggregationState>) -> Self {
+        Self {
+            inner,
+            distinct_values: HashSet::new(),
+        }
+    }
+}
+
+impl AggregationState for DistinctAggregationState {
+    fn update(&mut self, array: &ArrayImpl) -> Result<(), ExecutorError> {
+        for idx in 0..array.len() {
+            self.update_single(&array.get(idx))?;
+        }
+        Ok(())
+    }
+
+    fn update_single(&mut self, value: &DataValue) -> Result<(), ExecutorError> {
+        if *value != DataValue::Null && self.distinct_values.insert(value.clone()) {
+            self.inner.update_single(value)?;
+        }
+        Ok(())
+    }
+
+    fn output(&self) -> DataValue {
+        self.inner.output()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataTypeKind;
+
+    #[test]
+    fn test_distinct() {
+        let array = ArrayImpl::Int32(
+            [Some(1), Some(2), Some(2), None, Some(1)]
+                .into_iter()
+                .collect(),
+        );
+
+        let mut state = CountAggregationState::new(DataValue::Int32(0));
+        state.update(&array).unwrap();
+        assert_eq!(state.output(), DataValue::Int32(4));
+
+        let mut state = DistinctAggregationState::new(Box::new(CountAggregationState::new(
+            DataValue::Int32(0),
+        )));
+        state.update(&array).unwrap();
+        assert_eq!(state.output(), DataValue::Int32(2));
+
+        let mut state = DistinctAggregationState::new(Box::new(SumAggregationState::new(
+            DataTypeKind::Int(None),
+        )));
+        state.update(&array).unwrap();
+        assert_eq!(state.output(), DataValue::Int32(3));
+    }
+}
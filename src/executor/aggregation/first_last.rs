@@ -0,0 +1,90 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+
+/// State for `first_value` or `last_value` aggregation
+///
+/// The state keeps the value of the first or last row in the order that rows arrive, and NULL
+/// values are respected. Aggregation never reorders its input, so the result is only
+/// deterministic when the child produces rows in a well-defined order, e.g. a sorted scan.
+pub struct FirstLastAggregationState {
+    result: Option<DataValue>,
+    is_first: bool,
+}
+
+impl FirstLastAggregationState {
+    pub fn new(is_first: bool) -> Self {
+        Self {
+            result: None,
+            is_first,
+        }
+    }
+}
+
+impl AggregationState for FirstLastAggregationState {
+    fn update(&mut self, array: &ArrayImpl) -> Result<(), ExecutorError> {
+        if array.is_empty() {
+            return Ok(());
+        }
+        if self.is_first {
+            self.update_single(&array.get(0))
+        } else {
+            self.update_single(&array.get(array.len() - 1))
+        }
+    }
+
+    fn update_single(&mut self, value: &DataValue) -> Result<(), ExecutorError> {
+        if !self.is_first || self.result.is_none() {
+            self.result = Some(value.clone());
+        }
+        Ok(())
+    }
+
+    fn output(&self) -> DataValue {
+        self.result.clone().unwrap_or(DataValue::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{I32Array, Utf8Array};
+
+    #[test]
+    fn test_first_last() {
+        // Chunks are consumed in the order produced by the child, e.g. a sort executor.
+        let chunks = [
+            ArrayImpl::Int32([None, Some(2)].into_iter().collect::<I32Array>()),
+            ArrayImpl::Int32([Some(3), Some(4)].into_iter().collect::<I32Array>()),
+        ];
+        let mut first = FirstLastAggregationState::new(true);
+        let mut last = FirstLastAggregationState::new(false);
+        for array in &chunks {
+            first.update(array).unwrap();
+            last.update(array).unwrap();
+        }
+        assert_eq!(first.output(), DataValue::Null);
+        assert_eq!(last.output(), DataValue::Int32(4));
+
+        let array = ArrayImpl::Utf8(
+            [Some("a"), Some("b"), None]
+                .into_iter()
+                .collect::<Utf8Array>(),
+        );
+        let mut first = FirstLastAggregationState::new(true);
+        let mut last = FirstLastAggregationState::new(false);
+        for idx in 0..array.len() {
+            first.update_single(&array.get(idx)).unwrap();
+            last.update_single(&array.get(idx)).unwrap();
+        }
+        assert_eq!(first.output(), DataValue::String("a".into()));
+        assert_eq!(last.output(), DataValue::Null);
+
+        let mut first = FirstLastAggregationState::new(true);
+        assert_eq!(first.output(), DataValue::Null);
+        first
+            .update(&ArrayImpl::Bool([true, false].into_iter().collect()))
+            .unwrap();
+        assert_eq!(first.output(), DataValue::Bool(true));
+    }
+}
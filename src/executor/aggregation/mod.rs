@@ -2,17 +2,28 @@
 
 use super::*;
 use crate::array::ArrayImpl;
-use crate::types::DataValue;
+use crate::binder::{AggKind, BoundAggCall};
+use crate::types::{DataValue, PhysicalDataTypeKind};
 
+mod bool_and_or;
 mod count;
+mod distinct;
+mod first_last;
 mod min_max;
 mod rowcount;
+mod string_agg;
 mod sum;
+mod variance;
 
+pub use bool_and_or::*;
 pub use count::*;
+pub use distinct::*;
+pub use first_last::*;
 pub use min_max::*;
 pub use rowcount::*;
+pub use string_agg::*;
 pub use sum::*;
+pub use variance::*;
 
 /// `AggregationState` records the state of an aggregation
 pub trait AggregationState: 'static + Send + Sync {
@@ -22,3 +33,21 @@ pub trait AggregationState: 'static + Send + Sync {
 
     fn output(&self) -> DataValue;
 }
+
+/// Whether the result of the aggregation does not depend on the order of input rows, so that
+/// rows can be fed to it in any order. Sums of floating-point numbers are excluded, as they are
+/// rounded differently when added in a different order.
+pub fn is_order_insensitive(agg: &BoundAggCall) -> bool {
+    match agg.kind {
+        AggKind::RowCount
+        | AggKind::Count
+        | AggKind::Max
+        | AggKind::Min
+        | AggKind::BoolAnd
+        | AggKind::BoolOr => true,
+        AggKind::Sum | AggKind::Avg => agg.args[0].return_type().map_or(true, |ty| {
+            ty.physical_kind() != PhysicalDataTypeKind::Float64
+        }),
+        _ => false,
+    }
+}
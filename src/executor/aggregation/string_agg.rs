@@ -0,0 +1,85 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::array::Array;
+
+/// State for `string_agg` aggregation
+pub struct StringAggAggregationState {
+    result: Option<String>,
+    separator: String,
+}
+
+impl StringAggAggregationState {
+    pub fn new(separator: String) -> Self {
+        Self {
+            result: None,
+            separator,
+        }
+    }
+
+    fn update_str(&mut self, value: &str) {
+        match &mut self.result {
+            None => self.result = Some(value.to_string()),
+            Some(res) => {
+                res.push_str(&self.separator);
+                res.push_str(value);
+            }
+        }
+    }
+}
+
+impl AggregationState for StringAggAggregationState {
+    fn update(&mut self, array: &ArrayImpl) -> Result<(), ExecutorError> {
+        match array {
+            ArrayImpl::Utf8(arr) => {
+                for value in arr.iter().flatten() {
+                    self.update_str(value);
+                }
+            }
+            _ => panic!("Mismatched type"),
+        }
+        Ok(())
+    }
+
+    fn update_single(&mut self, value: &DataValue) -> Result<(), ExecutorError> {
+        match value {
+            DataValue::Null => {}
+            DataValue::String(val) => self.update_str(val),
+            _ => panic!("Mismatched type"),
+        }
+        Ok(())
+    }
+
+    fn output(&self) -> DataValue {
+        match &self.result {
+            Some(res) => DataValue::String(res.clone()),
+            None => DataValue::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_agg() {
+        let array = ArrayImpl::Utf8(
+            [Some("a"), None, Some("b"), Some("c")]
+                .into_iter()
+                .collect(),
+        );
+
+        let mut state = StringAggAggregationState::new(", ".into());
+        state.update(&array).unwrap();
+        assert_eq!(state.output(), DataValue::String("a, b, c".into()));
+
+        let mut state = StringAggAggregationState::new("".into());
+        state.update(&array).unwrap();
+        assert_eq!(state.output(), DataValue::String("abc".into()));
+
+        let mut state = StringAggAggregationState::new(", ".into());
+        state.update_single(&DataValue::Null).unwrap();
+        assert_eq!(state.output(), DataValue::Null);
+    }
+}
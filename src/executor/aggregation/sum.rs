@@ -1,12 +1,15 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
-use rust_decimal::Decimal;
-
 use super::*;
 use crate::array::Array;
 use crate::types::DataTypeKind;
 
 /// State for sum aggregation
+///
+/// Integer and decimal sums are checked: an overflow returns
+/// [`ExecutorError::NumericOverflow`] instead of wrapping. The sum of integers is still an
+/// integer of the same type, so `sum` over large `INT` values may overflow and should be computed
+/// on `BIGINT` or `DECIMAL` instead.
 pub struct SumAggregationState {
     result: DataValue,
     input_datatype: DataTypeKind,
@@ -33,34 +36,40 @@ macro_rules! sum_func_gen {
     };
 }
 
-sum_func_gen!(sum_i32, i32, i32);
 sum_func_gen!(sum_f64, f64, f64);
-sum_func_gen!(sum_decimal, Decimal, Decimal);
+
+impl SumAggregationState {
+    /// Add an integer or a decimal to the sum, returning an error on overflow.
+    fn checked_add(&self, val: DataValue) -> Result<DataValue, ExecutorError> {
+        let res = match (&self.result, val) {
+            (DataValue::Null, val) => Some(val),
+            (DataValue::Int32(res), DataValue::Int32(v)) => {
+                res.checked_add(v).map(DataValue::Int32)
+            }
+            (DataValue::Int64(res), DataValue::Int64(v)) => {
+                res.checked_add(v).map(DataValue::Int64)
+            }
+            (DataValue::Decimal(res), DataValue::Decimal(v)) => {
+                res.checked_add(v).map(DataValue::Decimal)
+            }
+            _ => panic!("Mismatched type"),
+        };
+        res.ok_or_else(|| ExecutorError::NumericOverflow(self.input_datatype.clone()))
+    }
+}
 
 impl AggregationState for SumAggregationState {
     fn update(&mut self, array: &ArrayImpl) -> Result<(), ExecutorError> {
         // TODO: refactor into macros
         match (array, &self.input_datatype) {
             (ArrayImpl::Int32(arr), DataTypeKind::Int(_)) => {
-                let mut temp: Option<i32> = None;
-                #[cfg(feature = "simd")]
-                {
-                    use crate::array::ArrayValidExt;
-                    let bitmap = arr.get_valid_bitmap();
-                    if bitmap.any() {
-                        temp = Some(arr.batch_iter::<32>().sum());
-                    }
-                }
-                #[cfg(not(feature = "simd"))]
-                {
-                    temp = arr.iter().fold(temp, sum_i32);
+                for val in arr.iter().flatten() {
+                    self.result = self.checked_add(DataValue::Int32(*val))?;
                 }
-                if let Some(val) = temp {
-                    self.result = match self.result {
-                        DataValue::Null => DataValue::Int32(val),
-                        DataValue::Int32(res) => DataValue::Int32(res + val),
-                        _ => panic!("Mismatched type"),
-                    }
+            }
+            (ArrayImpl::Int64(arr), DataTypeKind::BigInt(_)) => {
+                for val in arr.iter().flatten() {
+                    self.result = self.checked_add(DataValue::Int64(*val))?;
                 }
             }
             (ArrayImpl::Float64(arr), DataTypeKind::Double) => {
@@ -86,14 +95,8 @@ impl AggregationState for SumAggregationState {
                 }
             }
             (ArrayImpl::Decimal(arr), DataTypeKind::Decimal(_, _)) => {
-                let mut temp: Option<Decimal> = None;
-                temp = arr.iter().fold(temp, sum_decimal);
-                if let Some(val) = temp {
-                    self.result = match self.result {
-                        DataValue::Null => DataValue::Decimal(val),
-                        DataValue::Decimal(res) => DataValue::Decimal(res + val),
-                        _ => panic!("Mismatched type"),
-                    }
+                for val in arr.iter().flatten() {
+                    self.result = self.checked_add(DataValue::Decimal(*val))?;
                 }
             }
             _ => panic!("Mismatched type"),
@@ -103,12 +106,10 @@ impl AggregationState for SumAggregationState {
 
     fn update_single(&mut self, value: &DataValue) -> Result<(), ExecutorError> {
         match (value, &self.input_datatype) {
-            (DataValue::Int32(val), DataTypeKind::Int(_)) => {
-                self.result = match self.result {
-                    DataValue::Null => DataValue::Int32(*val),
-                    DataValue::Int32(res) => DataValue::Int32(res + val),
-                    _ => panic!("Mismatched type"),
-                }
+            (DataValue::Int32(_), DataTypeKind::Int(_))
+            | (DataValue::Int64(_), DataTypeKind::BigInt(_))
+            | (DataValue::Decimal(_), DataTypeKind::Decimal(_, _)) => {
+                self.result = self.checked_add(value.clone())?;
             }
             (DataValue::Float64(val), DataTypeKind::Double) => {
                 self.result = match self.result {
@@ -117,13 +118,6 @@ impl AggregationState for SumAggregationState {
                     _ => panic!("Mismatched type"),
                 }
             }
-            (DataValue::Decimal(val), DataTypeKind::Decimal(_, _)) => {
-                self.result = match self.result {
-                    DataValue::Null => DataValue::Decimal(*val),
-                    DataValue::Decimal(res) => DataValue::Decimal(res + val),
-                    _ => panic!("Mismatched type"),
-                }
-            }
             _ => panic!("Mismatched type"),
         }
         Ok(())
@@ -136,6 +130,8 @@ impl AggregationState for SumAggregationState {
 
 #[cfg(test)]
 mod tests {
+    use rust_decimal::Decimal;
+
     use super::*;
 
     #[test]
@@ -150,4 +146,35 @@ mod tests {
         state.update(&array).unwrap();
         assert_eq!(state.output(), DataValue::Float64(1.));
     }
+
+    #[test]
+    fn test_sum_decimal() {
+        // 0.1 can not be represented exactly in doubles, but the sum of decimals is exact
+        let decimals = ArrayImpl::Decimal(std::iter::repeat(Decimal::new(1, 1)).take(10).collect());
+        let mut state = SumAggregationState::new(DataTypeKind::Decimal(Some(10), Some(1)));
+        state.update(&decimals).unwrap();
+        assert_eq!(state.output(), DataValue::Decimal(Decimal::new(10, 1)));
+    }
+
+    #[test]
+    fn test_sum_int_overflow() {
+        let mut state = SumAggregationState::new(DataTypeKind::Int(None));
+        let array = ArrayImpl::Int32([Some(i32::MAX - 1), None, Some(1)].into_iter().collect());
+        state.update(&array).unwrap();
+        assert_eq!(state.output(), DataValue::Int32(i32::MAX));
+
+        // the sum is not wrapped to a negative value
+        let err = state.update_single(&DataValue::Int32(1)).unwrap_err();
+        assert!(matches!(err, ExecutorError::NumericOverflow(_)));
+        let array = ArrayImpl::Int32([Some(1)].into_iter().collect());
+        assert!(state.update(&array).is_err());
+    }
+
+    #[test]
+    fn test_sum_decimal_overflow() {
+        let mut state = SumAggregationState::new(DataTypeKind::Decimal(None, None));
+        let array = ArrayImpl::Decimal([Decimal::MAX, Decimal::ONE].into_iter().collect());
+        let err = state.update(&array).unwrap_err();
+        assert!(matches!(err, ExecutorError::NumericOverflow(_)));
+    }
 }
@@ -0,0 +1,102 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use num_traits::ToPrimitive;
+
+use super::*;
+
+/// State for variance and standard deviation aggregation
+///
+/// It tracks the count, sum and sum of squares of the input values.
+pub struct VarianceAggregationState {
+    count: usize,
+    sum: f64,
+    sum_of_squares: f64,
+    is_sample: bool,
+    is_stddev: bool,
+}
+
+impl VarianceAggregationState {
+    pub fn new(is_sample: bool, is_stddev: bool) -> Self {
+        Self {
+            count: 0,
+            sum: 0.0,
+            sum_of_squares: 0.0,
+            is_sample,
+            is_stddev,
+        }
+    }
+}
+
+impl AggregationState for VarianceAggregationState {
+    fn update(&mut self, array: &ArrayImpl) -> Result<(), ExecutorError> {
+        for idx in 0..array.len() {
+            self.update_single(&array.get(idx))?;
+        }
+        Ok(())
+    }
+
+    fn update_single(&mut self, value: &DataValue) -> Result<(), ExecutorError> {
+        let value = match value {
+            DataValue::Null => return Ok(()),
+            DataValue::Int32(v) => *v as f64,
+            DataValue::Int64(v) => *v as f64,
+            DataValue::Float64(v) => *v,
+            DataValue::Decimal(v) => v.to_f64().unwrap(),
+            _ => panic!("Mismatched type"),
+        };
+        self.count += 1;
+        self.sum += value;
+        self.sum_of_squares += value * value;
+        Ok(())
+    }
+
+    fn output(&self) -> DataValue {
+        let divisor = match self.is_sample {
+            true if self.count < 2 => return DataValue::Null,
+            true => self.count - 1,
+            false if self.count == 0 => return DataValue::Null,
+            false => self.count,
+        };
+        let count = self.count as f64;
+        let variance = (self.sum_of_squares - self.sum * self.sum / count) / divisor as f64;
+        // avoid negative variance caused by floating point errors
+        let variance = variance.max(0.0);
+        if self.is_stddev {
+            DataValue::Float64(variance.sqrt())
+        } else {
+            DataValue::Float64(variance)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregate(is_sample: bool, is_stddev: bool, values: &[i32]) -> DataValue {
+        let mut state = VarianceAggregationState::new(is_sample, is_stddev);
+        let array = ArrayImpl::Int32(values.iter().cloned().collect());
+        state.update(&array).unwrap();
+        state.output()
+    }
+
+    #[test]
+    fn test_variance() {
+        let values = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(aggregate(false, false, &values), DataValue::Float64(4.0));
+        assert_eq!(aggregate(false, true, &values), DataValue::Float64(2.0));
+        assert_eq!(
+            aggregate(true, false, &values),
+            DataValue::Float64(32.0 / 7.0)
+        );
+        assert_eq!(
+            aggregate(true, true, &values),
+            DataValue::Float64((32.0f64 / 7.0).sqrt())
+        );
+
+        assert_eq!(aggregate(false, false, &[]), DataValue::Null);
+        assert_eq!(aggregate(false, false, &[3]), DataValue::Float64(0.0));
+        assert_eq!(aggregate(true, false, &[3]), DataValue::Null);
+        assert_eq!(aggregate(true, true, &[3]), DataValue::Null);
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use super::*;
+use crate::optimizer::plan_nodes::PhysicalAlterTable;
+use crate::storage::Storage;
+
+/// The executor of `alter table` statement.
+pub struct AlterTableExecutor<S: Storage> {
+    pub plan: PhysicalAlterTable,
+    pub storage: Arc<S>,
+}
+
+impl<S: Storage> AlterTableExecutor<S> {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        self.storage
+            .add_column(
+                self.plan.logical().table_ref_id(),
+                self.plan.logical().column(),
+            )
+            .await?;
+
+        let mut chunk = DataChunk::single(0);
+        chunk.set_header(vec!["$alter".to_string()]);
+        yield chunk
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::catalog::{ColumnStatistics, HyperLogLog, RootCatalogRef, TableRefId, TableStatistics};
+use crate::types::{ColumnId, DataValue};
+
+/// The executor of `analyze` statement.
+///
+/// It scans all columns of the table, and stores the statistics in the catalog.
+pub struct AnalyzeExecutor {
+    pub table_ref_id: TableRefId,
+    pub column_ids: Vec<ColumnId>,
+    pub catalog: RootCatalogRef,
+    pub child: BoxedExecutor,
+}
+
+/// The statistics of a column being collected.
+struct ColumnCollector {
+    null_count: usize,
+    min: DataValue,
+    max: DataValue,
+    hll: HyperLogLog,
+}
+
+impl ColumnCollector {
+    fn new() -> Self {
+        ColumnCollector {
+            null_count: 0,
+            min: DataValue::Null,
+            max: DataValue::Null,
+            hll: HyperLogLog::new(),
+        }
+    }
+
+    fn add(&mut self, value: DataValue) {
+        if value == DataValue::Null {
+            self.null_count += 1;
+            return;
+        }
+        self.hll.add(&value);
+        if self.min == DataValue::Null || value < self.min {
+            self.min = value.clone();
+        }
+        if self.max == DataValue::Null || value > self.max {
+            self.max = value;
+        }
+    }
+
+    fn finish(self) -> ColumnStatistics {
+        ColumnStatistics {
+            null_count: self.null_count,
+            min: self.min,
+            max: self.max,
+            distinct_count: self.hll.estimate(),
+        }
+    }
+}
+
+impl AnalyzeExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let mut row_count = 0;
+        let mut collectors = (self.column_ids.iter())
+            .map(|_| ColumnCollector::new())
+            .collect_vec();
+        #[for_await]
+        for chunk in self.child {
+            let chunk = chunk?;
+            for (i, collector) in collectors.iter_mut().enumerate() {
+                let array = chunk.array_at(i);
+                for idx in 0..array.len() {
+                    collector.add(array.get(idx));
+                }
+            }
+            row_count += chunk.cardinality();
+        }
+
+        let columns: HashMap<_, _> = (self.column_ids.iter().cloned())
+            .zip(collectors.into_iter().map(ColumnCollector::finish))
+            .collect();
+        let table = self.catalog.get_table(&self.table_ref_id).unwrap();
+        table.set_statistics(TableStatistics { row_count, columns });
+
+        let mut chunk = DataChunk::single(row_count as i32);
+        chunk.set_header(vec!["$analyze.row_counts".to_string()]);
+        yield chunk;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::catalog::ColumnCatalog;
+    use crate::storage::{InMemoryStorage, Storage};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    #[tokio::test]
+    async fn test_analyze() {
+        let storage = InMemoryStorage::new();
+        let columns = vec![
+            ColumnCatalog::new(0, DataTypeKind::Int(None).nullable().to_column("a".into())),
+            ColumnCatalog::new(1, DataTypeKind::Int(None).nullable().to_column("b".into())),
+        ];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table_ref_id = TableRefId::new(0, 0, 0);
+
+        // `a` has 20000 distinct values, and `b` is NULL every 4 rows
+        let chunks = (0..10)
+            .map(|i| {
+                let range = i * 5000..(i + 1) * 5000;
+                [
+                    ArrayImpl::Int32(range.clone().map(|v| Some(v % 20000)).collect()),
+                    ArrayImpl::Int32(range.map(|v| (v % 4 != 0).then(|| v)).collect()),
+                ]
+                .into_iter()
+                .collect::<DataChunk>()
+            })
+            .collect_vec();
+        let executor = AnalyzeExecutor {
+            table_ref_id,
+            column_ids: vec![0, 1],
+            catalog: storage.catalog().clone(),
+            child: futures::stream::iter(chunks.into_iter().map(Ok)).boxed(),
+        };
+        executor.execute().try_collect::<Vec<_>>().await.unwrap();
+
+        let statistics = (storage.catalog().get_table(&table_ref_id).unwrap())
+            .statistics()
+            .unwrap();
+        assert_eq!(statistics.row_count, 50000);
+
+        let a = &statistics.columns[&0];
+        assert_eq!(a.null_count, 0);
+        assert_eq!(a.min, DataValue::Int32(0));
+        assert_eq!(a.max, DataValue::Int32(19999));
+        let error = (a.distinct_count as f64 - 20000.0).abs() / 20000.0;
+        assert!(error < 0.03, "estimated = {}", a.distinct_count);
+
+        let b = &statistics.columns[&1];
+        assert_eq!(b.null_count, 12500);
+        assert_eq!(b.min, DataValue::Int32(1));
+        assert_eq!(b.max, DataValue::Int32(49999));
+        let error = (b.distinct_count as f64 - 37500.0).abs() / 37500.0;
+        assert!(error < 0.03, "estimated = {}", b.distinct_count);
+    }
+}
@@ -10,6 +10,7 @@ use super::*;
 use crate::array::ArrayBuilderImpl;
 use crate::binder::FileFormat;
 use crate::optimizer::plan_nodes::PhysicalCopyFromFile;
+use crate::types::{DataType, DataValue};
 
 /// The executor of loading file data.
 pub struct CopyFromFileExecutor {
@@ -37,18 +38,22 @@ impl CopyFromFileExecutor {
         let file = File::open(&self.plan.logical().path())?;
         let file_size = file.metadata()?.len();
         let mut buf_reader = BufReader::new(file);
-        let mut reader = match self.plan.logical().format().clone() {
+        let (mut reader, null) = match self.plan.logical().format().clone() {
             FileFormat::Csv {
                 delimiter,
                 quote,
                 escape,
                 header,
-            } => csv::ReaderBuilder::new()
-                .delimiter(delimiter as u8)
-                .quote(quote as u8)
-                .escape(escape.map(|c| c as u8))
-                .has_headers(header)
-                .from_reader(&mut buf_reader),
+                null,
+            } => (
+                csv::ReaderBuilder::new()
+                    .delimiter(delimiter as u8)
+                    .quote(quote as u8)
+                    .escape(escape.map(|c| c as u8))
+                    .has_headers(header)
+                    .from_reader(&mut buf_reader),
+                null,
+            ),
         };
 
         let bar = if file_size < IMPORT_PROGRESS_BAR_LIMIT {
@@ -64,15 +69,12 @@ impl CopyFromFileExecutor {
             bar
         };
 
-        let column_count = self.plan.logical().column_types().len();
+        let column_types = self.plan.logical().column_types();
         let mut iter = reader.records();
         let mut finished = false;
         while !finished {
             // create array builders
-            let mut array_builders = self
-                .plan
-                .logical()
-                .column_types()
+            let mut array_builders = column_types
                 .iter()
                 .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, ty))
                 .collect_vec();
@@ -86,24 +88,13 @@ impl CopyFromFileExecutor {
                         break;
                     }
                 };
-                if !(record.len() == column_count
-                    || record.len() == column_count + 1 && record.get(column_count) == Some(""))
-                {
-                    return Err(ExecutorError::LengthMismatch {
-                        expected: column_count,
-                        actual: record.len(),
-                    });
-                }
-                for ((s, builder), ty) in record
-                    .iter()
-                    .zip(&mut array_builders)
-                    .zip(&self.plan.logical().column_types().to_vec())
-                {
-                    if !ty.is_nullable() && s.is_empty() {
-                        return Err(ExecutorError::NotNullable);
-                    }
-                    builder.push_str(s)?;
-                }
+                let line = record.position().map_or(0, |pos| pos.line());
+                Self::push_record(&record, &null, &mut array_builders, column_types).map_err(
+                    |e| ExecutorError::ParseLine {
+                        line,
+                        source: Box::new(e),
+                    },
+                )?;
             }
             // update progress bar
             bar.set_position(iter.reader().position().byte());
@@ -117,6 +108,36 @@ impl CopyFromFileExecutor {
         bar.finish();
         Ok(())
     }
+
+    /// Push the values of a record to the array builders. Empty values and values equal to
+    /// `null` are NULLs.
+    fn push_record(
+        record: &csv::StringRecord,
+        null: &str,
+        array_builders: &mut [ArrayBuilderImpl],
+        column_types: &[DataType],
+    ) -> Result<(), ExecutorError> {
+        let column_count = column_types.len();
+        if !(record.len() == column_count
+            || record.len() == column_count + 1 && record.get(column_count) == Some(""))
+        {
+            return Err(ExecutorError::LengthMismatch {
+                expected: column_count,
+                actual: record.len(),
+            });
+        }
+        for ((s, builder), ty) in record.iter().zip(array_builders).zip(column_types) {
+            if s == null || s.is_empty() {
+                if !ty.is_nullable() {
+                    return Err(ExecutorError::NotNullable);
+                }
+                builder.push(&DataValue::Null);
+            } else {
+                builder.push_str(s)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -142,6 +163,7 @@ mod tests {
                     quote: '"',
                     escape: None,
                     header: false,
+                    null: String::new(),
                 },
                 vec![
                     DataTypeKind::Int(None).not_null(),
@@ -166,4 +188,78 @@ mod tests {
         .collect();
         assert_eq!(actual, expected);
     }
+
+    /// Read `v1 int not null, v2 string` from a `|` separated file with a header and `\N` as NULL.
+    fn read_csv_with_nulls(file: &tempfile::NamedTempFile) -> CopyFromFileExecutor {
+        CopyFromFileExecutor {
+            plan: PhysicalCopyFromFile::new(LogicalCopyFromFile::new(
+                file.path().into(),
+                FileFormat::Csv {
+                    delimiter: '|',
+                    quote: '"',
+                    escape: None,
+                    header: true,
+                    null: "\\N".into(),
+                },
+                vec![
+                    DataTypeKind::Int(None).not_null(),
+                    DataTypeKind::String.nullable(),
+                ],
+                vec![
+                    DataTypeKind::Int(None).not_null().to_column("v1".into()),
+                    DataTypeKind::String.nullable().to_column("v2".into()),
+                ],
+            )),
+        }
+    }
+
+    #[tokio::test]
+    async fn read_csv_with_header_quotes_and_nulls() {
+        let csv = "v1|v2\n1|\"a|b\"\n2|\\N\n3|\"say \"\"hi\"\"\"\n";
+        let mut file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        write!(file, "{}", csv).expect("failed to write file");
+        let actual = read_csv_with_nulls(&file)
+            .execute()
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+
+        let expected: DataChunk = [
+            ArrayImpl::Int32([1, 2, 3].into_iter().collect()),
+            ArrayImpl::Utf8(
+                [Some("a|b"), None, Some("say \"hi\"")]
+                    .into_iter()
+                    .collect(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn read_csv_reports_line() {
+        let mut file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        write!(file, "v1|v2\n1|a\nx|b\n").expect("failed to write file");
+        let err = read_csv_with_nulls(&file)
+            .execute()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutorError::ParseLine { line: 3, .. }));
+
+        let mut file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        write!(file, "v1|v2\n1|a\n2|b\n\\N|c\n").expect("failed to write file");
+        let err = read_csv_with_nulls(&file)
+            .execute()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutorError::ParseLine { line: 4, .. }));
+        assert_eq!(
+            err.to_string(),
+            "failed to parse line 4: value can not be null"
+        );
+    }
 }
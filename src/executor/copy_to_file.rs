@@ -7,11 +7,14 @@ use tokio::sync::mpsc;
 
 use super::*;
 use crate::binder::FileFormat;
+use crate::types::DataValue;
 
 /// The executor of saving data to file.
 pub struct CopyToFileExecutor {
     pub path: PathBuf,
     pub format: FileFormat,
+    /// The column names, written as the header row if the format requests it.
+    pub column_names: Vec<String>,
     pub child: BoxedExecutor,
 }
 
@@ -21,11 +24,13 @@ impl CopyToFileExecutor {
         let Self {
             path,
             format,
+            column_names,
             child,
         } = self;
         let (sender, recver) = mpsc::channel(1);
-        let writer =
-            tokio::task::spawn_blocking(move || Self::write_file_blocking(path, format, recver));
+        let writer = tokio::task::spawn_blocking(move || {
+            Self::write_file_blocking(path, format, column_names, recver)
+        });
         #[for_await]
         for batch in child {
             let res = sender.send(batch?).await;
@@ -42,28 +47,40 @@ impl CopyToFileExecutor {
     fn write_file_blocking(
         path: PathBuf,
         format: FileFormat,
+        column_names: Vec<String>,
         mut recver: mpsc::Receiver<DataChunk>,
     ) -> Result<usize, ExecutorError> {
         let file = File::create(&path)?;
-        let mut writer = match format {
+        let (mut writer, header, null) = match format {
             FileFormat::Csv {
                 delimiter,
                 quote,
                 escape,
                 header,
-            } => csv::WriterBuilder::new()
-                .delimiter(delimiter as u8)
-                .quote(quote as u8)
-                .escape(escape.unwrap_or(quote) as u8)
-                .has_headers(header)
-                .from_writer(file),
+                null,
+            } => (
+                csv::WriterBuilder::new()
+                    .delimiter(delimiter as u8)
+                    .quote(quote as u8)
+                    .escape(escape.unwrap_or(quote) as u8)
+                    .from_writer(file),
+                header,
+                null,
+            ),
         };
+        // `has_headers` only applies to serialized structs, so write the header row explicitly
+        if header {
+            writer.write_record(&column_names)?;
+        }
 
         let mut rows = 0;
         while let Some(chunk) = recver.blocking_recv() {
             for i in 0..chunk.cardinality() {
                 // TODO(wrj): avoid dynamic memory allocation (String)
-                let row = chunk.arrays().iter().map(|a| a.get_to_string(i));
+                let row = chunk.arrays().iter().map(|a| match a.get(i) {
+                    DataValue::Null => null.clone(),
+                    _ => a.get_to_string(i),
+                });
                 writer.write_record(row)?;
             }
             writer.flush()?;
@@ -89,7 +106,9 @@ mod tests {
                 quote: '"',
                 escape: None,
                 header: false,
+                null: String::new(),
             },
+            column_names: vec!["a".into(), "b".into(), "c".into()],
             child: async_stream::try_stream! {
                 yield [
                     ArrayImpl::Int32([1, 2].into_iter().collect()),
@@ -107,4 +126,35 @@ mod tests {
         let expected = "1,1.5,one\n2,2.5,two\n";
         assert_eq!(actual, expected);
     }
+
+    #[tokio::test]
+    async fn write_csv_with_header_quotes_and_nulls() {
+        let file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+
+        let executor = CopyToFileExecutor {
+            path: file.path().into(),
+            format: FileFormat::Csv {
+                delimiter: '|',
+                quote: '"',
+                escape: None,
+                header: true,
+                null: "NULL".into(),
+            },
+            column_names: vec!["id".into(), "name".into()],
+            child: async_stream::try_stream! {
+                yield [
+                    ArrayImpl::Int32([Some(1), Some(2), None].into_iter().collect()),
+                    ArrayImpl::Utf8([Some("a|b"), None, Some("say \"hi\"")].into_iter().collect()),
+                ]
+                .into_iter()
+                .collect();
+            }
+            .boxed(),
+        };
+        executor.execute().next().await.unwrap().unwrap();
+
+        let actual = std::fs::read_to_string(file.path()).unwrap();
+        let expected = "id|name\n1|\"a|b\"\n2|NULL\nNULL|\"say \"\"hi\"\"\"\n";
+        assert_eq!(actual, expected);
+    }
 }
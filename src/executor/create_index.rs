@@ -0,0 +1,29 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use super::*;
+use crate::optimizer::plan_nodes::PhysicalCreateIndex;
+use crate::storage::Storage;
+
+/// The executor of `create index` statement.
+pub struct CreateIndexExecutor<S: Storage> {
+    pub plan: PhysicalCreateIndex,
+    pub storage: Arc<S>,
+}
+
+impl<S: Storage> CreateIndexExecutor<S> {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let logical = self.plan.logical();
+        if let Some(column_id) = logical.column_id() {
+            self.storage
+                .create_index(logical.table_ref_id(), column_id)
+                .await?;
+        }
+
+        let mut chunk = DataChunk::single(0);
+        chunk.set_header(vec!["$create_index".to_string()]);
+        yield chunk
+    }
+}
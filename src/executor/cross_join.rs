@@ -0,0 +1,125 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use futures::TryStreamExt;
+
+use super::*;
+use crate::array::{ArrayBuilderImpl, DataChunk};
+use crate::types::DataType;
+
+/// The executor of cross join, which produces the Cartesian product of both children.
+///
+/// The right child is consumed first and replayed for each left row, so only the right side is
+/// kept in memory. Output rows contain the left columns followed by the right columns, and are
+/// produced in chunks of at most [`PROCESSING_WINDOW_SIZE`] rows.
+pub struct CrossJoinExecutor {
+    pub left_child: BoxedExecutor,
+    pub right_child: BoxedExecutor,
+    pub left_types: Vec<DataType>,
+    pub right_types: Vec<DataType>,
+}
+
+impl CrossJoinExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let right_chunks = self.right_child.try_collect::<Vec<DataChunk>>().await?;
+        let right_rows = right_chunks.iter().flat_map(|c| c.rows()).collect_vec();
+        if right_rows.is_empty() {
+            return Ok(());
+        }
+
+        let create_builders = || {
+            (self.left_types.iter())
+                .chain(self.right_types.iter())
+                .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, ty))
+                .collect_vec()
+        };
+        let mut builders = create_builders();
+        let mut num_rows = 0;
+
+        #[for_await]
+        for chunk in self.left_child {
+            let chunk = chunk?;
+            for left_row in chunk.rows() {
+                for right_row in &right_rows {
+                    let values = left_row.values().chain(right_row.values());
+                    for (builder, v) in builders.iter_mut().zip_eq(values) {
+                        builder.push(&v);
+                    }
+                    num_rows += 1;
+                    if num_rows == PROCESSING_WINDOW_SIZE {
+                        yield std::mem::replace(&mut builders, create_builders())
+                            .into_iter()
+                            .collect();
+                        num_rows = 0;
+                    }
+                }
+            }
+        }
+
+        if num_rows != 0 {
+            yield builders.into_iter().collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::types::{DataTypeExt, DataTypeKind, DataValue};
+
+    #[tokio::test]
+    async fn cross_join() {
+        let left: DataChunk = [
+            ArrayImpl::Int32([1, 2, 3].into_iter().collect()),
+            ArrayImpl::Bool([true, false, true].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        let right: DataChunk = [ArrayImpl::Float64([0.5, 1.5].into_iter().collect())]
+            .into_iter()
+            .collect();
+        let executor = CrossJoinExecutor {
+            left_child: futures::stream::iter([Ok(left.clone()), Ok(left)]).boxed(),
+            right_child: futures::stream::iter([Ok(right.clone()), Ok(right)]).boxed(),
+            left_types: vec![
+                DataTypeKind::Int(None).not_null(),
+                DataTypeKind::Boolean.not_null(),
+            ],
+            right_types: vec![DataTypeKind::Double.not_null()],
+        };
+        let outputs = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let rows = outputs
+            .iter()
+            .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+            .collect_vec();
+
+        // 6 left rows and 4 right rows
+        assert_eq!(rows.len(), 24);
+        assert!(outputs.iter().all(|chunk| chunk.column_count() == 3));
+        assert_eq!(
+            rows[..4],
+            [0.5, 1.5, 0.5, 1.5].map(|f| vec![
+                DataValue::Int32(1),
+                DataValue::Bool(true),
+                DataValue::Float64(f)
+            ])
+        );
+        assert_eq!(rows[4][0], DataValue::Int32(2));
+    }
+
+    #[tokio::test]
+    async fn cross_join_with_empty_side() {
+        let left: DataChunk = [ArrayImpl::Int32([1, 2].into_iter().collect())]
+            .into_iter()
+            .collect();
+        let executor = CrossJoinExecutor {
+            left_child: futures::stream::iter([Ok(left)]).boxed(),
+            right_child: futures::stream::iter([]).boxed(),
+            left_types: vec![DataTypeKind::Int(None).not_null()],
+            right_types: vec![DataTypeKind::Int(None).not_null()],
+        };
+        let outputs = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        assert!(outputs.is_empty());
+    }
+}
@@ -5,20 +5,22 @@ use std::sync::Arc;
 use super::*;
 use crate::array::DataChunk;
 use crate::catalog::TableRefId;
-use crate::storage::{RowHandler, Storage, Table, Transaction};
+use crate::storage::{ExplicitTransaction, RowHandler, Storage, Transaction, TxnHandle};
 
 /// The executor of `delete` statement.
 pub struct DeleteExecutor<S: Storage> {
     pub table_ref_id: TableRefId,
     pub storage: Arc<S>,
     pub child: BoxedExecutor,
+    /// The transaction started by `BEGIN`, if any.
+    pub txn: Option<Arc<ExplicitTransaction<S>>>,
 }
 
 impl<S: Storage> DeleteExecutor<S> {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
         let table = self.storage.get_table(self.table_ref_id)?;
-        let mut txn = table.update().await?;
+        let mut txn = TxnHandle::update(&table, self.txn.as_deref()).await?;
         let mut cnt = 0;
         #[for_await]
         for chunk in self.child {
@@ -37,6 +39,8 @@ impl<S: Storage> DeleteExecutor<S> {
         }
         txn.commit().await?;
 
-        yield DataChunk::single(cnt);
+        let mut chunk = DataChunk::single(cnt);
+        chunk.set_header(vec!["$delete.row_counts".to_string()]);
+        yield chunk;
     }
 }
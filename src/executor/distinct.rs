@@ -0,0 +1,72 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashSet;
+
+use super::set_operation::{filter, row_key};
+use super::*;
+use crate::array::DataChunk;
+
+/// The executor of `select distinct`.
+///
+/// Each row is hashed by its serialized values, and only its first occurrence is kept. NULLs are
+/// considered equal to each other.
+pub struct DistinctExecutor {
+    pub child: BoxedExecutor,
+}
+
+impl DistinctExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let mut seen = HashSet::new();
+        #[for_await]
+        for chunk in self.child {
+            let chunk = chunk?;
+            let visibility = (chunk.rows())
+                .map(|row| seen.insert(row_key(&row)))
+                .collect_vec();
+            if let Some(chunk) = filter(&chunk, visibility) {
+                yield chunk;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::types::DataValue;
+
+    #[tokio::test]
+    async fn distinct() {
+        let a = [Some(1), None, Some(1), None, Some(1), None, Some(2)];
+        let b = [Some(1), Some(1), Some(1), None, None, None, None];
+        let chunk = |range: std::ops::Range<usize>| -> DataChunk {
+            [
+                ArrayImpl::Int32(a[range.clone()].iter().cloned().collect()),
+                ArrayImpl::Int32(b[range].iter().cloned().collect()),
+            ]
+            .into_iter()
+            .collect()
+        };
+        // duplicates are across chunks
+        let chunks = vec![Ok(chunk(0..2)), Ok(chunk(2..4)), Ok(chunk(4..7))];
+        let executor = DistinctExecutor {
+            child: futures::stream::iter(chunks).boxed(),
+        };
+        let chunks = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let rows = (chunks.iter())
+            .flat_map(|c| c.rows())
+            .map(|row| row.values().collect_vec())
+            .collect_vec();
+        let v = |v: Option<i32>| v.map_or(DataValue::Null, DataValue::Int32);
+        // rows that differ only in the placement of NULLs are kept
+        let expected = [0, 1, 3, 4, 6]
+            .iter()
+            .map(|&i| vec![v(a[i]), v(b[i])])
+            .collect_vec();
+        assert_eq!(rows, expected);
+    }
+}
@@ -15,8 +15,10 @@ pub struct DropExecutor<S: Storage> {
 impl<S: Storage> DropExecutor<S> {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
-        match self.plan.logical().object().clone() {
-            Object::Table(ref_id) => self.storage.drop_table(ref_id).await?,
+        for object in self.plan.logical().objects() {
+            match object {
+                Object::Table(ref_id) => self.storage.drop_table(*ref_id).await?,
+            }
         }
         yield DataChunk::single(0);
     }
@@ -4,10 +4,15 @@
 
 use std::borrow::Borrow;
 
+use chrono::{Datelike, Timelike};
+
+use super::like::LikePattern;
 use crate::array::*;
-use crate::binder::BoundExpr;
+use crate::binder::{BoundExpr, ExtractField, ScalarFunctionKind};
 use crate::parser::{BinaryOperator, UnaryOperator};
-use crate::types::{Blob, ConvertError, DataTypeExt, DataTypeKind, DataValue, Date};
+use crate::types::{
+    Blob, ConvertError, DataType, DataTypeExt, DataTypeKind, DataValue, Date, List, Timestamp,
+};
 
 impl BoundExpr {
     /// Evaluate the given expression as an array.
@@ -16,27 +21,31 @@ impl BoundExpr {
             BoundExpr::InputRef(input_ref) => Ok(chunk.array_at(input_ref.index).clone()),
             BoundExpr::BinaryOp(binary_op) => {
                 let left = binary_op.left_expr.eval(chunk)?;
+                if let BinaryOperator::And | BinaryOperator::Or = binary_op.op {
+                    return eval_logical(&binary_op.op, &left, |visibility| {
+                        binary_op.right_expr.eval_visible(chunk, visibility)
+                    });
+                }
                 let right = binary_op.right_expr.eval(chunk)?;
-                Ok(left.binary_op(&binary_op.op, &right))
+                left.binary_op(&binary_op.op, &right)
             }
             BoundExpr::UnaryOp(op) => {
                 let array = op.expr.eval(chunk)?;
                 Ok(array.unary_op(&op.op))
             }
-            BoundExpr::Constant(v) => {
-                let mut builder = ArrayBuilderImpl::with_capacity(
-                    chunk.cardinality(),
-                    &self
-                        .return_type()
-                        .unwrap_or_else(|| DataTypeKind::Int(None).nullable()),
-                );
-                // TODO: optimize this
-                for _ in 0..chunk.cardinality() {
-                    builder.push(v);
-                }
-                Ok(builder.finish())
-            }
+            BoundExpr::Constant(v) => Ok(constant_array(
+                v,
+                &self
+                    .return_type()
+                    .unwrap_or_else(|| DataTypeKind::Int(None).nullable()),
+                chunk.cardinality(),
+            )),
             BoundExpr::TypeCast(cast) => {
+                if let BoundExpr::Constant(DataValue::Null) = &*cast.expr {
+                    // a typed NULL
+                    let ty = self.return_type().unwrap();
+                    return Ok(constant_array(&DataValue::Null, &ty, chunk.cardinality()));
+                }
                 let array = cast.expr.eval(chunk)?;
                 if self.return_type() == cast.expr.return_type() {
                     return Ok(array);
@@ -51,11 +60,103 @@ impl BoundExpr {
                         .collect(),
                 ))
             }
+            BoundExpr::ScalarFunction(func) => match func.kind {
+                ScalarFunctionKind::Coalesce => {
+                    eval_coalesce(&func.args, chunk.cardinality(), &func.return_type, |arg| {
+                        arg.eval(chunk)
+                    })
+                }
+                ScalarFunctionKind::NullIf => {
+                    let left = func.args[0].eval(chunk)?;
+                    let right = func.args[1].eval(chunk)?;
+                    Ok(eval_nullif(&left, &right, &func.return_type))
+                }
+                ScalarFunctionKind::Greatest | ScalarFunctionKind::Least => {
+                    let args = (func.args.iter())
+                        .map(|arg| arg.eval(chunk))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let greatest = func.kind == ScalarFunctionKind::Greatest;
+                    Ok(eval_greatest_least(&args, &func.return_type, greatest))
+                }
+                ScalarFunctionKind::IsDistinctFrom | ScalarFunctionKind::IsNotDistinctFrom => {
+                    let left = func.args[0].eval(chunk)?;
+                    let right = func.args[1].eval(chunk)?;
+                    let negated = func.kind == ScalarFunctionKind::IsNotDistinctFrom;
+                    Ok(eval_is_distinct_from(&left, &right, negated))
+                }
+                ScalarFunctionKind::Substring
+                | ScalarFunctionKind::Trim
+                | ScalarFunctionKind::Ltrim
+                | ScalarFunctionKind::Rtrim
+                | ScalarFunctionKind::Replace
+                | ScalarFunctionKind::Concat => {
+                    let args = (func.args.iter())
+                        .map(|arg| arg.eval(chunk))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(eval_string_function(func.kind, &args))
+                }
+                ScalarFunctionKind::Power
+                | ScalarFunctionKind::Sqrt
+                | ScalarFunctionKind::Mod
+                | ScalarFunctionKind::Ln
+                | ScalarFunctionKind::Log
+                | ScalarFunctionKind::Exp => {
+                    let args = (func.args.iter())
+                        .map(|arg| arg.eval(chunk))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    eval_math_function(func.kind, &args, &func.return_type)
+                }
+                ScalarFunctionKind::Array => {
+                    let args = (func.args.iter())
+                        .map(|arg| arg.eval(chunk))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(eval_array(&args, chunk.cardinality()))
+                }
+                ScalarFunctionKind::ArrayElement => {
+                    let list = func.args[0].eval(chunk)?;
+                    let index = func.args[1].eval(chunk)?;
+                    Ok(eval_array_element(&list, &index, &func.return_type))
+                }
+                _ => {
+                    let array = func.args[0].eval(chunk)?;
+                    array.scalar_function(func.kind)
+                }
+            },
+            BoundExpr::Case(case) => eval_case(
+                &case.when_then,
+                case.else_result.as_deref(),
+                chunk.cardinality(),
+                &self.return_type(),
+                |expr, visibility| expr.eval_visible(chunk, visibility),
+            ),
+            BoundExpr::Between(between) => {
+                let array = between.expr.eval(chunk)?;
+                let low = between.low.eval(chunk)?;
+                let high = between.high.eval(chunk)?;
+                array.between(&low, &high, between.negated)
+            }
+            BoundExpr::Like(like) => {
+                let array = like.expr.eval(chunk)?;
+                let pattern = like.pattern.eval(chunk)?;
+                Ok(eval_like(&array, &pattern, like.negated, like.escape))
+            }
             BoundExpr::ExprWithAlias(expr_with_alias) => expr_with_alias.expr.eval(chunk),
             _ => panic!("{:?} should not be evaluated in `eval_array`", self),
         }
     }
 
+    /// Evaluate the given expression on the visible rows of the chunk.
+    fn eval_visible(
+        &self,
+        chunk: &DataChunk,
+        visibility: &[bool],
+    ) -> Result<ArrayImpl, ConvertError> {
+        if visibility.iter().all(|visible| *visible) {
+            return self.eval(chunk);
+        }
+        self.eval(&chunk.filter(visibility.iter().copied()))
+    }
+
     /// Evaluate the given expression as an array in storage engine.
     pub fn eval_array_in_storage(
         &self,
@@ -68,25 +169,29 @@ impl BoundExpr {
                 let left = binary_op
                     .left_expr
                     .eval_array_in_storage(chunk, cardinality)?;
+                if let BinaryOperator::And | BinaryOperator::Or = binary_op.op {
+                    return eval_logical(&binary_op.op, &left, |visibility| {
+                        (binary_op.right_expr).eval_visible_in_storage(chunk, visibility)
+                    });
+                }
                 let right = binary_op
                     .right_expr
                     .eval_array_in_storage(chunk, cardinality)?;
-                Ok(left.binary_op(&binary_op.op, &right))
+                left.binary_op(&binary_op.op, &right)
             }
             BoundExpr::UnaryOp(op) => {
                 let array = op.expr.eval_array_in_storage(chunk, cardinality)?;
                 Ok(array.unary_op(&op.op))
             }
             BoundExpr::Constant(v) => {
-                let mut builder =
-                    ArrayBuilderImpl::with_capacity(cardinality, &self.return_type().unwrap());
-                // TODO: optimize this
-                for _ in 0..cardinality {
-                    builder.push(v);
-                }
-                Ok(builder.finish())
+                Ok(constant_array(v, &self.return_type().unwrap(), cardinality))
             }
             BoundExpr::TypeCast(cast) => {
+                if let BoundExpr::Constant(DataValue::Null) = &*cast.expr {
+                    // a typed NULL
+                    let ty = self.return_type().unwrap();
+                    return Ok(constant_array(&DataValue::Null, &ty, cardinality));
+                }
                 let array = cast.expr.eval_array_in_storage(chunk, cardinality)?;
                 if self.return_type() == cast.expr.return_type() {
                     return Ok(array);
@@ -101,9 +206,446 @@ impl BoundExpr {
                         .collect(),
                 ))
             }
+            BoundExpr::ScalarFunction(func) => match func.kind {
+                ScalarFunctionKind::Coalesce => {
+                    eval_coalesce(&func.args, cardinality, &func.return_type, |arg| {
+                        arg.eval_array_in_storage(chunk, cardinality)
+                    })
+                }
+                ScalarFunctionKind::NullIf => {
+                    let left = func.args[0].eval_array_in_storage(chunk, cardinality)?;
+                    let right = func.args[1].eval_array_in_storage(chunk, cardinality)?;
+                    Ok(eval_nullif(&left, &right, &func.return_type))
+                }
+                ScalarFunctionKind::Greatest | ScalarFunctionKind::Least => {
+                    let args = (func.args.iter())
+                        .map(|arg| arg.eval_array_in_storage(chunk, cardinality))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let greatest = func.kind == ScalarFunctionKind::Greatest;
+                    Ok(eval_greatest_least(&args, &func.return_type, greatest))
+                }
+                ScalarFunctionKind::IsDistinctFrom | ScalarFunctionKind::IsNotDistinctFrom => {
+                    let left = func.args[0].eval_array_in_storage(chunk, cardinality)?;
+                    let right = func.args[1].eval_array_in_storage(chunk, cardinality)?;
+                    let negated = func.kind == ScalarFunctionKind::IsNotDistinctFrom;
+                    Ok(eval_is_distinct_from(&left, &right, negated))
+                }
+                ScalarFunctionKind::Substring
+                | ScalarFunctionKind::Trim
+                | ScalarFunctionKind::Ltrim
+                | ScalarFunctionKind::Rtrim
+                | ScalarFunctionKind::Replace
+                | ScalarFunctionKind::Concat => {
+                    let args = (func.args.iter())
+                        .map(|arg| arg.eval_array_in_storage(chunk, cardinality))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(eval_string_function(func.kind, &args))
+                }
+                ScalarFunctionKind::Power
+                | ScalarFunctionKind::Sqrt
+                | ScalarFunctionKind::Mod
+                | ScalarFunctionKind::Ln
+                | ScalarFunctionKind::Log
+                | ScalarFunctionKind::Exp => {
+                    let args = (func.args.iter())
+                        .map(|arg| arg.eval_array_in_storage(chunk, cardinality))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    eval_math_function(func.kind, &args, &func.return_type)
+                }
+                ScalarFunctionKind::Array => {
+                    let args = (func.args.iter())
+                        .map(|arg| arg.eval_array_in_storage(chunk, cardinality))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(eval_array(&args, cardinality))
+                }
+                ScalarFunctionKind::ArrayElement => {
+                    let list = func.args[0].eval_array_in_storage(chunk, cardinality)?;
+                    let index = func.args[1].eval_array_in_storage(chunk, cardinality)?;
+                    Ok(eval_array_element(&list, &index, &func.return_type))
+                }
+                _ => {
+                    let array = func.args[0].eval_array_in_storage(chunk, cardinality)?;
+                    array.scalar_function(func.kind)
+                }
+            },
+            BoundExpr::Case(case) => eval_case(
+                &case.when_then,
+                case.else_result.as_deref(),
+                cardinality,
+                &self.return_type(),
+                |expr, visibility| expr.eval_visible_in_storage(chunk, visibility),
+            ),
+            BoundExpr::Between(between) => {
+                let array = between.expr.eval_array_in_storage(chunk, cardinality)?;
+                let low = between.low.eval_array_in_storage(chunk, cardinality)?;
+                let high = between.high.eval_array_in_storage(chunk, cardinality)?;
+                array.between(&low, &high, between.negated)
+            }
+            BoundExpr::Like(like) => {
+                let array = like.expr.eval_array_in_storage(chunk, cardinality)?;
+                let pattern = like.pattern.eval_array_in_storage(chunk, cardinality)?;
+                Ok(eval_like(&array, &pattern, like.negated, like.escape))
+            }
             _ => panic!("{:?} should not be evaluated in `eval_array`", self),
         }
     }
+
+    /// Evaluate the given expression on the visible rows of the chunk in storage engine.
+    fn eval_visible_in_storage(
+        &self,
+        chunk: &PackedVec<Option<ArrayImpl>>,
+        visibility: &[bool],
+    ) -> Result<ArrayImpl, ConvertError> {
+        if visibility.iter().all(|visible| *visible) {
+            return self.eval_array_in_storage(chunk, visibility.len());
+        }
+        let filtered = (chunk.iter())
+            .map(|array| (array.as_ref()).map(|a| a.filter(visibility.iter().copied())))
+            .collect();
+        let cardinality = visibility.iter().filter(|visible| **visible).count();
+        self.eval_array_in_storage(&filtered, cardinality)
+    }
+}
+
+/// Build an array repeating the constant value.
+fn constant_array(value: &DataValue, ty: &DataType, len: usize) -> ArrayImpl {
+    let mut builder = ArrayBuilderImpl::with_capacity(len, ty);
+    // TODO: optimize this
+    for _ in 0..len {
+        builder.push(value);
+    }
+    builder.finish()
+}
+
+/// Evaluate a `case` expression.
+///
+/// For each row, the result of the first true condition is selected. If no condition is true, the
+/// `else` result is selected, or NULL if there is no `else`.
+///
+/// Each condition is only evaluated on the rows where no previous condition is true, and each
+/// result only on the rows selecting it, so `case when b <> 0 then a / b end` never divides by
+/// zero. `eval` evaluates an expression on the visible rows.
+fn eval_case(
+    when_then: &[(BoundExpr, BoundExpr)],
+    else_result: Option<&BoundExpr>,
+    len: usize,
+    return_type: &Option<DataType>,
+    mut eval: impl FnMut(&BoundExpr, &[bool]) -> Result<ArrayImpl, ConvertError>,
+) -> Result<ArrayImpl, ConvertError> {
+    let mut values = vec![DataValue::Null; len];
+    // the rows where no condition is true yet
+    let mut remaining = (0..len).collect::<Vec<_>>();
+    for (cond, result) in when_then {
+        if remaining.is_empty() {
+            break;
+        }
+        let cond = eval(cond, &visibility(&remaining, len))?;
+        let (selected, unselected): (Vec<_>, Vec<_>) = (remaining.iter().enumerate())
+            .partition(|(j, _)| cond.get(*j) == DataValue::Bool(true));
+        let selected = selected.into_iter().map(|(_, i)| *i).collect::<Vec<_>>();
+        if !selected.is_empty() {
+            let result = eval(result, &visibility(&selected, len))?;
+            for (j, i) in selected.iter().enumerate() {
+                values[*i] = result.get(j);
+            }
+        }
+        remaining = unselected.into_iter().map(|(_, i)| *i).collect();
+    }
+    if let (Some(else_result), false) = (else_result, remaining.is_empty()) {
+        let result = eval(else_result, &visibility(&remaining, len))?;
+        for (j, i) in remaining.iter().enumerate() {
+            values[*i] = result.get(j);
+        }
+    }
+    let mut builder = ArrayBuilderImpl::with_capacity(
+        len,
+        &(return_type.clone()).unwrap_or_else(|| DataTypeKind::Int(None).nullable()),
+    );
+    for value in &values {
+        builder.push(value);
+    }
+    Ok(builder.finish())
+}
+
+/// Get the visibility of the given rows out of `len` rows.
+fn visibility(rows: &[usize], len: usize) -> Vec<bool> {
+    let mut visibility = vec![false; len];
+    for i in rows {
+        visibility[*i] = true;
+    }
+    visibility
+}
+
+/// Evaluate `AND` or `OR` with short circuit.
+///
+/// The right operand is only evaluated on the rows where the left operand does not decide the
+/// result, so `b <> 0 and a / b > 1` never divides by zero. `eval_right` evaluates the right
+/// operand on the visible rows.
+fn eval_logical(
+    op: &BinaryOperator,
+    left: &ArrayImpl,
+    eval_right: impl FnOnce(&[bool]) -> Result<ArrayImpl, ConvertError>,
+) -> Result<ArrayImpl, ConvertError> {
+    // `false` decides the result of `AND`, and `true` decides the result of `OR`
+    let decided = DataValue::Bool(*op == BinaryOperator::Or);
+    let visibility = (0..left.len())
+        .map(|i| left.get(i) != decided)
+        .collect::<Vec<_>>();
+    let right = eval_right(&visibility)?;
+    // the skipped rows of the right operand are filled with the deciding value
+    let mut builder =
+        ArrayBuilderImpl::with_capacity(left.len(), &DataTypeKind::Boolean.nullable());
+    let mut j = 0;
+    for visible in visibility {
+        if visible {
+            builder.push(&right.get(j));
+            j += 1;
+        } else {
+            builder.push(&decided);
+        }
+    }
+    left.binary_op(op, &builder.finish())
+}
+
+/// Evaluate `coalesce` by selecting the first non-NULL argument for each row.
+///
+/// Arguments are evaluated lazily: the remaining arguments are skipped once every row has got a
+/// non-NULL value.
+fn eval_coalesce(
+    args: &[BoundExpr],
+    len: usize,
+    return_type: &DataType,
+    mut eval: impl FnMut(&BoundExpr) -> Result<ArrayImpl, ConvertError>,
+) -> Result<ArrayImpl, ConvertError> {
+    let mut values = vec![DataValue::Null; len];
+    let mut null_count = len;
+    for arg in args {
+        if null_count == 0 {
+            break;
+        }
+        let array = eval(arg)?;
+        for (i, value) in values.iter_mut().enumerate() {
+            if *value == DataValue::Null {
+                *value = array.get(i);
+                if *value != DataValue::Null {
+                    null_count -= 1;
+                }
+            }
+        }
+    }
+    let mut builder = ArrayBuilderImpl::with_capacity(len, return_type);
+    for value in &values {
+        builder.push(value);
+    }
+    Ok(builder.finish())
+}
+
+/// Evaluate `nullif`, which returns NULL if two arguments are equal and the first one otherwise.
+fn eval_nullif(left: &ArrayImpl, right: &ArrayImpl, return_type: &DataType) -> ArrayImpl {
+    let mut builder = ArrayBuilderImpl::with_capacity(left.len(), return_type);
+    for i in 0..left.len() {
+        let value = left.get(i);
+        if value != DataValue::Null && value == right.get(i) {
+            builder.push(&DataValue::Null);
+        } else {
+            builder.push(&value);
+        }
+    }
+    builder.finish()
+}
+
+/// Evaluate `greatest` or `least`, which returns the largest or smallest non-NULL argument of each
+/// row, or NULL if all arguments are NULL.
+fn eval_greatest_least(args: &[ArrayImpl], return_type: &DataType, greatest: bool) -> ArrayImpl {
+    let mut builder = ArrayBuilderImpl::with_capacity(args[0].len(), return_type);
+    for i in 0..args[0].len() {
+        let mut result = DataValue::Null;
+        for value in args.iter().map(|a| a.get(i)) {
+            let replace = match (&result, &value) {
+                (_, DataValue::Null) => false,
+                (DataValue::Null, _) => true,
+                _ if greatest => value > result,
+                _ => value < result,
+            };
+            if replace {
+                result = value;
+            }
+        }
+        builder.push(&result);
+    }
+    builder.finish()
+}
+
+/// Evaluate `IS [NOT] DISTINCT FROM`. Two NULLs are not distinct, and a NULL is distinct from
+/// any other value.
+fn eval_is_distinct_from(left: &ArrayImpl, right: &ArrayImpl, negated: bool) -> ArrayImpl {
+    ArrayImpl::Bool(
+        (0..left.len())
+            .map(|i| (left.get(i) != right.get(i)) != negated)
+            .collect(),
+    )
+}
+
+/// Evaluate a math function. NULL is returned if any argument is NULL, and an error is returned if
+/// the arguments are out of the domain of the function.
+fn eval_math_function(
+    kind: ScalarFunctionKind,
+    args: &[ArrayImpl],
+    return_type: &DataType,
+) -> Result<ArrayImpl, ConvertError> {
+    let mut builder = ArrayBuilderImpl::with_capacity(args[0].len(), return_type);
+    for i in 0..args[0].len() {
+        let values = args.iter().map(|a| a.get(i)).collect::<Vec<_>>();
+        match math_function(kind, &values) {
+            Some(value) => builder.push(&value),
+            None => return Err(ConvertError::Domain(kind.to_string(), values)),
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Returns `None` if the arguments are out of the domain of the function.
+fn math_function(kind: ScalarFunctionKind, values: &[DataValue]) -> Option<DataValue> {
+    use DataValue::{Float64, Int32, Int64, Null};
+    use ScalarFunctionKind::*;
+    let value = match (kind, values) {
+        (_, values) if values.contains(&Null) => return Some(Null),
+        (Mod, [Int32(x), Int32(y)]) => return x.checked_rem(*y).map(Int32),
+        (Mod, [Int64(x), Int64(y)]) => return x.checked_rem(*y).map(Int64),
+        (Sqrt, [Float64(x)]) => x.sqrt(),
+        (Ln, [Float64(x)]) => x.ln(),
+        (Exp, [Float64(x)]) => x.exp(),
+        (Power, [Float64(x), Float64(y)]) => x.powf(*y),
+        (Mod, [Float64(x), Float64(y)]) => x % y,
+        (Log, [Float64(base), Float64(_)]) if *base <= 0.0 => return None,
+        (Log, [Float64(base), Float64(x)]) => x.ln() / base.ln(),
+        _ => panic!("unsupported argument type for function {}", kind),
+    };
+    // e.g. `sqrt(-1)` is NaN and `ln(0)` is -inf
+    value.is_finite().then(|| Float64(value))
+}
+
+/// Evaluate `ARRAY[...]`, which builds a list from the arguments of each row.
+fn eval_array(args: &[ArrayImpl], len: usize) -> ArrayImpl {
+    let mut builder = ListArrayBuilder::with_capacity(len);
+    for i in 0..len {
+        let values = args.iter().map(|a| a.get(i)).collect::<Vec<_>>();
+        builder.push(Some(&List::from(values)));
+    }
+    ArrayImpl::List(builder.finish())
+}
+
+/// Evaluate `list[index]` with a 1-based index. NULL is returned if the index is out of range, or
+/// either argument is NULL.
+fn eval_array_element(list: &ArrayImpl, index: &ArrayImpl, return_type: &DataType) -> ArrayImpl {
+    let mut builder = ArrayBuilderImpl::with_capacity(list.len(), return_type);
+    for i in 0..list.len() {
+        let value = match (list.get(i), index.get(i)) {
+            (DataValue::List(l), DataValue::Int32(idx)) => l.get_1based(idx as i64).cloned(),
+            (DataValue::List(l), DataValue::Int64(idx)) => l.get_1based(idx).cloned(),
+            _ => None,
+        };
+        builder.push(&value.unwrap_or(DataValue::Null));
+    }
+    builder.finish()
+}
+
+/// Evaluate a string function with multiple arguments. NULL is returned if any argument is NULL,
+/// except for `concat`, which skips NULLs.
+fn eval_string_function(kind: ScalarFunctionKind, args: &[ArrayImpl]) -> ArrayImpl {
+    let mut builder = Utf8ArrayBuilder::with_capacity(args[0].len());
+    for i in 0..args[0].len() {
+        let values = args.iter().map(|a| a.get(i)).collect::<Vec<_>>();
+        builder.push(string_function(kind, &values).as_deref());
+    }
+    ArrayImpl::Utf8(builder.finish())
+}
+
+fn string_function(kind: ScalarFunctionKind, values: &[DataValue]) -> Option<String> {
+    use DataValue::{Int32, String as Str};
+    use ScalarFunctionKind::*;
+    Some(match (kind, values) {
+        (Substring, [Str(s), Int32(start)]) => substring(s, *start as i64, None),
+        (Substring, [Str(s), Int32(start), Int32(len)]) => {
+            substring(s, *start as i64, Some(*len as i64))
+        }
+        (Trim, [Str(s)]) => s.trim().into(),
+        (Ltrim, [Str(s)]) => s.trim_start().into(),
+        (Rtrim, [Str(s)]) => s.trim_end().into(),
+        (Trim, [Str(s), Str(chars)]) => s.trim_matches(|c| chars.contains(c)).into(),
+        (Ltrim, [Str(s), Str(chars)]) => s.trim_start_matches(|c| chars.contains(c)).into(),
+        (Rtrim, [Str(s), Str(chars)]) => s.trim_end_matches(|c| chars.contains(c)).into(),
+        (Replace, [Str(s), Str(from), _]) if from.is_empty() => s.clone(),
+        (Replace, [Str(s), Str(from), Str(to)]) => s.replace(from.as_str(), to),
+        (Concat, values) => (values.iter())
+            .filter_map(|v| match v {
+                Str(s) => Some(s.as_str()),
+                _ => None,
+            })
+            .collect(),
+        // any argument is NULL
+        _ => return None,
+    })
+}
+
+/// Get the substring of `len` characters starting from the 1-based position `start`.
+///
+/// The range is clamped to the string, so positions before the first character are skipped.
+/// An empty string is returned if `len` is negative.
+fn substring(s: &str, start: i64, len: Option<i64>) -> String {
+    let end = match len {
+        Some(len) if len < 0 => return String::new(),
+        Some(len) => start.saturating_add(len),
+        None => i64::MAX,
+    };
+    let (begin, end) = (start.max(1) - 1, end.max(1) - 1);
+    s.chars()
+        .skip(begin as usize)
+        .take(end.saturating_sub(begin) as usize)
+        .collect()
+}
+
+/// Extract a field from the timestamp. The second is truncated to an integer.
+fn extract(field: ExtractField, ts: Timestamp) -> i32 {
+    let datetime = ts.to_naive();
+    match field {
+        ExtractField::Year => datetime.year(),
+        ExtractField::Month => datetime.month() as i32,
+        ExtractField::Day => datetime.day() as i32,
+        ExtractField::Hour => datetime.hour() as i32,
+        ExtractField::Minute => datetime.minute() as i32,
+        ExtractField::Second => datetime.second() as i32,
+    }
+}
+
+/// Evaluate `like`. NULL is returned if either side is NULL.
+///
+/// A pattern is compiled only when it differs from that of the previous row, so a constant
+/// pattern is compiled once per chunk.
+fn eval_like(
+    array: &ArrayImpl,
+    pattern: &ArrayImpl,
+    negated: bool,
+    escape: Option<char>,
+) -> ArrayImpl {
+    let mut compiled: Option<(String, LikePattern)> = None;
+    let mut builder = BoolArrayBuilder::with_capacity(array.len());
+    for i in 0..array.len() {
+        let (s, p) = match (array.get(i), pattern.get(i)) {
+            (DataValue::String(s), DataValue::String(p)) => (s, p),
+            _ => {
+                builder.push(None);
+                continue;
+            }
+        };
+        if !matches!(&compiled, Some((last, _)) if *last == p) {
+            let matcher = LikePattern::new(&p, escape);
+            compiled = Some((p, matcher));
+        }
+        let (_, matcher) = compiled.as_ref().unwrap();
+        builder.push(Some(&(matcher.is_match(&s) != negated)));
+    }
+    ArrayImpl::Bool(builder.finish())
 }
 
 impl ArrayImpl {
@@ -131,24 +673,91 @@ impl ArrayImpl {
         }
     }
 
+    /// Perform scalar function. Returns an error if the absolute value of an integer overflows.
+    pub fn scalar_function(&self, kind: ScalarFunctionKind) -> Result<ArrayImpl, ConvertError> {
+        type A = ArrayImpl;
+        use ScalarFunctionKind::*;
+        let overflow = |v| ConvertError::Domain(kind.to_string(), vec![v]);
+        Ok(match (kind, self) {
+            (Abs, A::Int32(a)) => A::Int32(try_unary_op(a, |&v| {
+                v.checked_abs().ok_or_else(|| overflow(DataValue::Int32(v)))
+            })?),
+            (Abs, A::Int64(a)) => A::Int64(try_unary_op(a, |&v| {
+                v.checked_abs().ok_or_else(|| overflow(DataValue::Int64(v)))
+            })?),
+            (Abs, A::Float64(a)) => A::Float64(unary_op(a, |v| v.abs())),
+            (Abs, A::Decimal(a)) => A::Decimal(unary_op(a, |v| v.abs())),
+            (Round | Ceil | Floor, A::Int32(_) | A::Int64(_)) => self.clone(),
+            (Round, A::Float64(a)) => A::Float64(unary_op(a, |v| v.round())),
+            (Ceil, A::Float64(a)) => A::Float64(unary_op(a, |v| v.ceil())),
+            (Floor, A::Float64(a)) => A::Float64(unary_op(a, |v| v.floor())),
+            (Round, A::Decimal(a)) => A::Decimal(unary_op(a, |v| {
+                v.round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+            })),
+            (Ceil, A::Decimal(a)) => A::Decimal(unary_op(a, |v| v.ceil())),
+            (Floor, A::Decimal(a)) => A::Decimal(unary_op(a, |v| v.floor())),
+            (Length, A::Utf8(a)) => A::Int32(unary_op(a, |s| s.chars().count() as i32)),
+            (Upper, A::Utf8(a)) => A::Utf8(unary_op(a, |s| s.to_uppercase())),
+            (Lower, A::Utf8(a)) => A::Utf8(unary_op(a, |s| s.to_lowercase())),
+            (Extract(field), A::Date(a)) => {
+                A::Int32(unary_op(a, |v| extract(field, Timestamp::from(*v))))
+            }
+            (Extract(field), A::Timestamp(a)) => A::Int32(unary_op(a, |v| extract(field, *v))),
+            _ => panic!("unsupported argument type for function {}", kind),
+        })
+    }
+
+    /// Check whether the values are in the range `[low, high]`, or out of the range if negated.
+    pub fn between(
+        &self,
+        low: &ArrayImpl,
+        high: &ArrayImpl,
+        negated: bool,
+    ) -> Result<ArrayImpl, ConvertError> {
+        use BinaryOperator::*;
+        let (low_op, high_op, op) = match negated {
+            false => (GtEq, LtEq, And),
+            true => (Lt, Gt, Or),
+        };
+        let left = self.binary_op(&low_op, low)?;
+        let right = self.binary_op(&high_op, high)?;
+        left.binary_op(&op, &right)
+    }
+
     /// Perform binary operation.
-    pub fn binary_op(&self, op: &BinaryOperator, right: &ArrayImpl) -> ArrayImpl {
+    ///
+    /// Returns an error if an integer or decimal operation overflows or divides by zero. Float
+    /// operations follow IEEE 754, e.g. overflow to infinity.
+    pub fn binary_op(
+        &self,
+        op: &BinaryOperator,
+        right: &ArrayImpl,
+    ) -> Result<ArrayImpl, ConvertError> {
         type A = ArrayImpl;
         macro_rules! arith {
-            ($op:tt) => {
+            ($op:tt, $checked:ident) => {
                 match (self, right) {
-                    #[cfg(feature = "simd")]
-                    (A::Int32(a), A::Int32(b)) => A::Int32(simd_op::<_, _, _, 32>(a, b, |a, b| a $op b)),
+                    (A::Int32(a), A::Int32(b)) => A::Int32(try_primitive_binary_op(a, b, |a, b| {
+                        a.$checked(*b).ok_or_else(|| {
+                            ConvertError::Arithmetic(DataValue::Int32(*a), op.clone(), DataValue::Int32(*b))
+                        })
+                    })?),
+                    (A::Int64(a), A::Int64(b)) => A::Int64(try_primitive_binary_op(a, b, |a, b| {
+                        a.$checked(*b).ok_or_else(|| {
+                            ConvertError::Arithmetic(DataValue::Int64(*a), op.clone(), DataValue::Int64(*b))
+                        })
+                    })?),
                     #[cfg(feature = "simd")]
                     (A::Float64(a), A::Float64(b)) => A::Float64(simd_op::<_, _, _, 32>(a, b, |a, b| a $op b)),
-
-                    #[cfg(not(feature = "simd"))]
-                    (A::Int32(a), A::Int32(b)) => A::Int32(binary_op(a, b, |a, b| a $op b)),
                     #[cfg(not(feature = "simd"))]
-                    (A::Float64(a), A::Float64(b)) => A::Float64(binary_op(a, b, |a, b| a $op b)),
+                    (A::Float64(a), A::Float64(b)) => A::Float64(primitive_binary_op(a, b, |a, b| a $op b)),
 
-                    (A::Decimal(a), A::Decimal(b)) => A::Decimal(binary_op(a, b, |a, b| a $op b)),
-                    (A::Date(a), A::Interval(b)) => A::Date(binary_op(a, b, |a, b| *a $op *b)),
+                    (A::Decimal(a), A::Decimal(b)) => A::Decimal(try_primitive_binary_op(a, b, |a, b| {
+                        a.$checked(*b).ok_or_else(|| {
+                            ConvertError::Arithmetic(DataValue::Decimal(*a), op.clone(), DataValue::Decimal(*b))
+                        })
+                    })?),
+                    (A::Date(a), A::Interval(b)) => A::Date(primitive_binary_op(a, b, |a, b| *a $op *b)),
                     _ => todo!("Support more types for {}", stringify!($op)),
                 }
             }
@@ -156,29 +765,35 @@ impl ArrayImpl {
         macro_rules! cmp {
             ($op:tt) => {
                 match (self, right) {
-                    (A::Bool(a), A::Bool(b)) => A::Bool(binary_op(a, b, |a, b| a $op b)),
-                    (A::Int32(a), A::Int32(b)) => A::Bool(binary_op(a, b, |a, b| a $op b)),
+                    (A::Bool(a), A::Bool(b)) => A::Bool(primitive_binary_op(a, b, |a, b| a $op b)),
+                    (A::Int32(a), A::Int32(b)) => A::Bool(primitive_binary_op(a, b, |a, b| a $op b)),
+                    (A::Int64(a), A::Int64(b)) => A::Bool(primitive_binary_op(a, b, |a, b| a $op b)),
                     #[allow(clippy::float_cmp)]
-                    (A::Float64(a), A::Float64(b)) => A::Bool(binary_op(a, b, |a, b| a $op b)),
+                    (A::Float64(a), A::Float64(b)) => A::Bool(primitive_binary_op(a, b, |a, b| a $op b)),
                     (A::Utf8(a), A::Utf8(b)) => A::Bool(binary_op(a, b, |a, b| a $op b)),
-                    (A::Date(a), A::Date(b)) => A::Bool(binary_op(a, b, |a, b| a $op b)),
-                    (A::Decimal(a), A::Decimal(b)) => A::Bool(binary_op(a, b, |a, b| a $op b)),
+                    (A::Date(a), A::Date(b)) => A::Bool(primitive_binary_op(a, b, |a, b| a $op b)),
+                    (A::Timestamp(a), A::Timestamp(b)) => A::Bool(primitive_binary_op(a, b, |a, b| a $op b)),
+                    (A::Decimal(a), A::Decimal(b)) => A::Bool(primitive_binary_op(a, b, |a, b| a $op b)),
                     _ => todo!("Support more types for {}", stringify!($op)),
                 }
             }
         }
-        match op {
-            BinaryOperator::Plus => arith!(+),
-            BinaryOperator::Minus => arith!(-),
-            BinaryOperator::Multiply => arith!(*),
-            BinaryOperator::Divide => arith!(/),
-            BinaryOperator::Modulo => arith!(%),
+        Ok(match op {
+            BinaryOperator::Plus => arith!(+, checked_add),
+            BinaryOperator::Minus => arith!(-, checked_sub),
+            BinaryOperator::Multiply => arith!(*, checked_mul),
+            BinaryOperator::Divide => arith!(/, checked_div),
+            BinaryOperator::Modulo => arith!(%, checked_rem),
             BinaryOperator::Eq => cmp!(==),
             BinaryOperator::NotEq => cmp!(!=),
             BinaryOperator::Gt => cmp!(>),
             BinaryOperator::Lt => cmp!(<),
             BinaryOperator::GtEq => cmp!(>=),
             BinaryOperator::LtEq => cmp!(<=),
+            BinaryOperator::StringConcat => match (self, right) {
+                (A::Utf8(a), A::Utf8(b)) => A::Utf8(binary_op(a, b, |a, b| format!("{}{}", a, b))),
+                _ => panic!("StringConcat can only be applied to STRING arrays"),
+            },
             BinaryOperator::And => match (self, right) {
                 (A::Bool(a), A::Bool(b)) => {
                     A::Bool(binary_op_with_null(a, b, |a, b| match (a, b) {
@@ -200,16 +815,19 @@ impl ArrayImpl {
                 _ => panic!("Or can only be applied to BOOL arrays"),
             },
             _ => todo!("evaluate operator: {:?}", op),
-        }
+        })
     }
 
-    /// Cast the array to another type.
+    /// Cast the array to the given type. Returns an error if any value can not be casted.
+    ///
+    /// Floats are truncated towards zero when casted to integers.
     pub fn try_cast(&self, data_type: DataTypeKind) -> Result<Self, ConvertError> {
         type Type = DataTypeKind;
         Ok(match self {
             Self::Bool(a) => match data_type {
                 Type::Boolean => Self::Bool(a.clone()),
                 Type::Int(_) => Self::Int32(unary_op(a, |&b| b as i32)),
+                Type::BigInt(_) => Self::Int64(unary_op(a, |&b| b as i64)),
                 Type::Float(_) | Type::Double => Self::Float64(unary_op(a, |&b| b as u8 as f64)),
                 Type::String | Type::Char(_) | Type::Varchar(_) => {
                     Self::Utf8(unary_op(a, |&b| if b { "true" } else { "false" }))
@@ -221,6 +839,7 @@ impl ArrayImpl {
             Self::Int32(a) => match data_type {
                 Type::Boolean => Self::Bool(unary_op(a, |&i| i != 0)),
                 Type::Int(_) => Self::Int32(a.clone()),
+                Type::BigInt(_) => Self::Int64(unary_op(a, |&i| i as i64)),
                 Type::Float(_) | Type::Double => Self::Float64(unary_op(a, |&i| i as f64)),
                 Type::String | Type::Char(_) | Type::Varchar(_) => {
                     Self::Utf8(unary_op(a, |&i| i.to_string()))
@@ -231,7 +850,10 @@ impl ArrayImpl {
             },
             Self::Int64(a) => match data_type {
                 Type::Boolean => Self::Bool(unary_op(a, |&i| i != 0)),
-                Type::Int(_) => Self::Int64(a.clone()),
+                Type::Int(_) => Self::Int32(try_unary_op(a, |&i| {
+                    i32::try_from(i).map_err(|_| ConvertError::Cast(i.to_string(), "INT"))
+                })?),
+                Type::BigInt(_) => Self::Int64(a.clone()),
                 Type::Float(_) | Type::Double => Self::Float64(unary_op(a, |&i| i as f64)),
                 Type::String | Type::Char(_) | Type::Varchar(_) => {
                     Self::Utf8(unary_op(a, |&i| i.to_string()))
@@ -242,7 +864,16 @@ impl ArrayImpl {
             },
             Self::Float64(a) => match data_type {
                 Type::Boolean => Self::Bool(unary_op(a, |&f| f != 0.0)),
-                Type::Int(_) => Self::Int32(unary_op(a, |&f| f as i32)),
+                Type::Int(_) => Self::Int32(try_unary_op(a, |&f| {
+                    f.trunc()
+                        .to_i32()
+                        .ok_or_else(|| ConvertError::Cast(f.to_string(), "INT"))
+                })?),
+                Type::BigInt(_) => Self::Int64(try_unary_op(a, |&f| {
+                    f.trunc()
+                        .to_i64()
+                        .ok_or_else(|| ConvertError::Cast(f.to_string(), "BIGINT"))
+                })?),
                 Type::Float(_) | Type::Double => Self::Float64(a.clone()),
                 Type::String | Type::Char(_) | Type::Varchar(_) => {
                     Self::Utf8(unary_op(a, |&f| f.to_string()))
@@ -263,24 +894,31 @@ impl ArrayImpl {
             },
             Self::Utf8(a) => match data_type {
                 Type::Boolean => Self::Bool(try_unary_op(a, |s| {
-                    s.parse::<bool>()
+                    (s.trim().to_lowercase().parse::<bool>())
                         .map_err(|e| ConvertError::ParseBool(s.to_string(), e))
                 })?),
                 Type::Int(_) => Self::Int32(try_unary_op(a, |s| {
-                    s.parse::<i32>()
-                        .map_err(|e| ConvertError::ParseInt(s.to_string(), e))
+                    (s.trim().parse::<i32>()).map_err(|e| ConvertError::ParseInt(s.to_string(), e))
+                })?),
+                Type::BigInt(_) => Self::Int64(try_unary_op(a, |s| {
+                    (s.trim().parse::<i64>()).map_err(|e| ConvertError::ParseInt(s.to_string(), e))
                 })?),
                 Type::Float(_) | Type::Double => Self::Float64(try_unary_op(a, |s| {
-                    s.parse::<f64>()
+                    (s.trim().parse::<f64>())
                         .map_err(|e| ConvertError::ParseFloat(s.to_string(), e))
                 })?),
                 Type::String | Type::Char(_) | Type::Varchar(_) => Self::Utf8(a.clone()),
                 Type::Decimal(_, _) => Self::Decimal(try_unary_op(a, |s| {
-                    Decimal::from_str(s).map_err(|e| ConvertError::ParseDecimal(s.to_string(), e))
+                    (Decimal::from_str(s.trim()))
+                        .map_err(|e| ConvertError::ParseDecimal(s.to_string(), e))
                 })?),
                 Type::Date => Self::Date(try_unary_op(a, |s| {
                     Date::from_str(s).map_err(|e| ConvertError::ParseDate(s.to_string(), e))
                 })?),
+                Type::Timestamp => Self::Timestamp(try_unary_op(a, |s| {
+                    (Timestamp::from_str(s))
+                        .map_err(|e| ConvertError::ParseTimestamp(s.to_string(), e))
+                })?),
                 Type::Bytea | Type::Blob(_) => Self::Blob(try_unary_op(a, |s| {
                     Blob::from_str(s).map_err(|e| ConvertError::ParseBlob(s.to_string(), e))
                 })?),
@@ -295,6 +933,12 @@ impl ArrayImpl {
                         DataValue::Decimal(d),
                     ))
                 })?),
+                Type::BigInt(_) => Self::Int64(try_unary_op(a, |&d| {
+                    d.to_i64().ok_or(ConvertError::FromDecimalError(
+                        DataTypeKind::BigInt(None),
+                        DataValue::Decimal(d),
+                    ))
+                })?),
                 Type::Float(_) | Type::Double => Self::Float64(try_unary_op(a, |&d| {
                     d.to_f64().ok_or(ConvertError::FromDecimalError(
                         DataTypeKind::Double,
@@ -312,18 +956,66 @@ impl ArrayImpl {
                 Type::String | Type::Char(_) | Type::Varchar(_) => {
                     Self::Utf8(unary_op(a, |&d| d.to_string()))
                 }
+                Type::Date => Self::Date(a.clone()),
+                Type::Timestamp => Self::Timestamp(unary_op(a, |&d| Timestamp::from(d))),
                 ty => return Err(ConvertError::FromDateError(ty)),
             },
+            Self::Timestamp(a) => match data_type {
+                Type::String | Type::Char(_) | Type::Varchar(_) => {
+                    Self::Utf8(unary_op(a, |&t| t.to_string()))
+                }
+                Type::Date => Self::Date(unary_op(a, |&t| t.date())),
+                Type::Timestamp => Self::Timestamp(a.clone()),
+                ty => return Err(ConvertError::FromTimestampError(ty)),
+            },
             Self::Interval(_) => return Err(ConvertError::FromIntervalError(data_type)),
+            Self::List(a) => match data_type {
+                Type::String | Type::Char(_) | Type::Varchar(_) => {
+                    Self::Utf8(unary_op(a, |l| l.to_string()))
+                }
+                Type::Array(elem) => Self::List(cast_list(a, &elem)?),
+                ty => return Err(ConvertError::FromListError(ty)),
+            },
         })
     }
 }
 
+/// Cast the elements of each list to the type.
+fn cast_list(a: &ListArray, elem: &DataTypeKind) -> Result<ListArray, ConvertError> {
+    let mut builder = ListArrayBuilder::with_capacity(a.len());
+    for list in a.iter() {
+        let list = match list {
+            Some(list) => list,
+            None => {
+                builder.push(None);
+                continue;
+            }
+        };
+        // the source type is taken from the first non-NULL element
+        let source = match list.iter().find_map(|v| v.data_type()) {
+            Some(ty) => ty,
+            // all elements are NULL
+            None => {
+                builder.push(Some(list));
+                continue;
+            }
+        };
+        let mut elems = ArrayBuilderImpl::with_capacity(list.len(), &source);
+        for value in list.iter() {
+            elems.push(value);
+        }
+        let elems = elems.finish().try_cast(elem.clone())?;
+        let values = (0..elems.len()).map(|i| elems.get(i)).collect::<Vec<_>>();
+        builder.push(Some(&List::from(values)));
+    }
+    Ok(builder.finish())
+}
+
 use std::simd::{LaneCount, Simd, SimdElement, SupportedLaneCount};
 
 use num_traits::ToPrimitive;
 use rust_decimal::prelude::FromStr;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 
 use crate::storage::PackedVec;
 use crate::types::NativeType;
@@ -370,6 +1062,56 @@ where
     builder.finish()
 }
 
+/// Perform a binary operation on primitive arrays.
+///
+/// If neither array has null, the operation is applied to all values in a tight loop without
+/// checking the validity of each element. Otherwise, it falls back to [`binary_op`].
+pub fn primitive_binary_op<A, B, O, F>(
+    a: &PrimitiveArray<A>,
+    b: &PrimitiveArray<B>,
+    f: F,
+) -> PrimitiveArray<O>
+where
+    A: NativeType,
+    B: NativeType,
+    O: NativeType,
+    F: Fn(&A, &B) -> O,
+{
+    assert_eq!(a.len(), b.len());
+    if !(a.no_nulls() && b.no_nulls()) {
+        return binary_op(a, b, f);
+    }
+    let values = (a.values().iter())
+        .zip(b.values())
+        .map(|(a, b)| f(a, b))
+        .collect();
+    PrimitiveArray::from_values(values)
+}
+
+/// Perform a fallible binary operation on primitive arrays, with the same fast path as
+/// [`primitive_binary_op`].
+fn try_primitive_binary_op<A, B, O, F, E>(
+    a: &PrimitiveArray<A>,
+    b: &PrimitiveArray<B>,
+    f: F,
+) -> Result<PrimitiveArray<O>, E>
+where
+    A: NativeType,
+    B: NativeType,
+    O: NativeType,
+    F: Fn(&A, &B) -> Result<O, E>,
+{
+    assert_eq!(a.len(), b.len());
+    if !(a.no_nulls() && b.no_nulls()) {
+        return try_binary_op(a, b, f);
+    }
+    let values = (a.values().iter())
+        .zip(b.values())
+        .map(|(a, b)| f(a, b))
+        .collect::<Result<_, E>>()?;
+    Ok(PrimitiveArray::from_values(values))
+}
+
 fn binary_op_with_null<A, B, O, F, V>(a: &A, b: &B, f: F) -> O
 where
     A: Array,
@@ -408,6 +1150,26 @@ where
     builder.finish()
 }
 
+fn try_binary_op<A, B, O, F, V, E>(a: &A, b: &B, f: F) -> Result<O, E>
+where
+    A: Array,
+    B: Array,
+    O: Array,
+    V: Borrow<O::Item>,
+    F: Fn(&A::Item, &B::Item) -> Result<V, E>,
+{
+    assert_eq!(a.len(), b.len());
+    let mut builder = O::Builder::with_capacity(a.len());
+    for (a, b) in a.iter().zip(b.iter()) {
+        if let (Some(a), Some(b)) = (a, b) {
+            builder.push(Some(f(a, b)?.borrow()));
+        } else {
+            builder.push(None);
+        }
+    }
+    Ok(builder.finish())
+}
+
 fn try_unary_op<A, O, F, V, E>(a: &A, f: F) -> Result<O, E>
 where
     A: Array,
@@ -425,3 +1187,278 @@ where
     }
     Ok(builder.finish())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_overflow() {
+        let a = ArrayImpl::Decimal([Some(Decimal::MAX), None].into_iter().collect());
+        let b = ArrayImpl::Decimal(
+            [Some(Decimal::ONE), Some(Decimal::ONE)]
+                .into_iter()
+                .collect(),
+        );
+        let err = a.binary_op(&BinaryOperator::Plus, &b).unwrap_err();
+        assert_eq!(
+            err,
+            ConvertError::Arithmetic(
+                DataValue::Decimal(Decimal::MAX),
+                BinaryOperator::Plus,
+                DataValue::Decimal(Decimal::ONE)
+            )
+        );
+
+        let res = b.binary_op(&BinaryOperator::Minus, &b).unwrap();
+        assert_eq!(res.get(0), DataValue::Decimal(Decimal::ZERO));
+
+        let zero = ArrayImpl::Decimal([Some(Decimal::ZERO), None].into_iter().collect());
+        assert!(b.binary_op(&BinaryOperator::Divide, &zero).is_err());
+    }
+
+    #[test]
+    fn test_int_overflow() {
+        let a = ArrayImpl::Int32([Some(i32::MAX), Some(1)].into_iter().collect());
+        let b = ArrayImpl::Int32([Some(1), None].into_iter().collect());
+        let err = a.binary_op(&BinaryOperator::Plus, &b).unwrap_err();
+        assert_eq!(
+            err,
+            ConvertError::Arithmetic(
+                DataValue::Int32(i32::MAX),
+                BinaryOperator::Plus,
+                DataValue::Int32(1)
+            )
+        );
+        assert!(a.binary_op(&BinaryOperator::Multiply, &a).is_err());
+
+        let res = a.binary_op(&BinaryOperator::Minus, &b).unwrap();
+        assert_eq!(res.get(0), DataValue::Int32(i32::MAX - 1));
+        assert_eq!(res.get(1), DataValue::Null);
+
+        let zero = ArrayImpl::Int32([Some(0), Some(0)].into_iter().collect());
+        assert!(a.binary_op(&BinaryOperator::Divide, &zero).is_err());
+        assert!(a.binary_op(&BinaryOperator::Modulo, &zero).is_err());
+    }
+
+    #[test]
+    fn test_cast() {
+        let a = ArrayImpl::Utf8([Some("123"), Some(" 45 "), None].into_iter().collect());
+        let b = a.try_cast(DataTypeKind::Int(None)).unwrap();
+        assert_eq!(b.get(0), DataValue::Int32(123));
+        assert_eq!(b.get(1), DataValue::Int32(45));
+        assert_eq!(b.get(2), DataValue::Null);
+
+        let a = ArrayImpl::Float64([Some(3.9), Some(-3.9), Some(1e10)].into_iter().collect());
+        let b = a.try_cast(DataTypeKind::BigInt(None)).unwrap();
+        assert_eq!(b.get(0), DataValue::Int64(3));
+        assert_eq!(b.get(1), DataValue::Int64(-3));
+        assert!(a.try_cast(DataTypeKind::Int(None)).is_err());
+
+        let a = ArrayImpl::Int64([Some(1), Some(i64::MAX)].into_iter().collect());
+        assert!(a.try_cast(DataTypeKind::Int(None)).is_err());
+    }
+
+    #[test]
+    fn test_array_element() {
+        let a = ArrayImpl::Int32([Some(1), Some(2), None].into_iter().collect());
+        let b = ArrayImpl::Int32([Some(10), None, Some(30)].into_iter().collect());
+        let lists = eval_array(&[a, b], 3);
+        assert_eq!(lists.get_to_string(1), "{2,NULL}");
+
+        let ty = DataTypeKind::Int(None).nullable();
+        let index = |i| ArrayImpl::Int32([Some(i); 3].into_iter().collect());
+        let values = |array: ArrayImpl| (0..3).map(|i| array.get(i)).collect::<Vec<_>>();
+        let int = DataValue::Int32;
+        assert_eq!(
+            values(eval_array_element(&lists, &index(1), &ty)),
+            [int(1), int(2), DataValue::Null]
+        );
+        assert_eq!(
+            values(eval_array_element(&lists, &index(2), &ty)),
+            [int(10), DataValue::Null, int(30)]
+        );
+        // out of range
+        for i in [0, 3, -1] {
+            assert_eq!(
+                values(eval_array_element(&lists, &index(i), &ty)),
+                vec![DataValue::Null; 3]
+            );
+        }
+    }
+
+    #[test]
+    fn test_greatest_least() {
+        let a = ArrayImpl::Int32([None, Some(1), Some(5), None].into_iter().collect());
+        let b = ArrayImpl::Int32([None, Some(3), None, Some(2)].into_iter().collect());
+        let c = ArrayImpl::Int32([None, Some(2), Some(4), None].into_iter().collect());
+        let args = [a, b, c];
+        let ty = DataTypeKind::Int(None).nullable();
+        let values = |array: ArrayImpl| (0..4).map(|i| array.get(i)).collect::<Vec<_>>();
+        let int = DataValue::Int32;
+
+        assert_eq!(
+            values(eval_greatest_least(&args, &ty, true)),
+            vec![DataValue::Null, int(3), int(5), int(2)]
+        );
+        assert_eq!(
+            values(eval_greatest_least(&args, &ty, false)),
+            vec![DataValue::Null, int(1), int(4), int(2)]
+        );
+    }
+
+    #[test]
+    fn test_is_distinct_from() {
+        let a = ArrayImpl::Int32([None, None, Some(1), Some(1)].into_iter().collect());
+        let b = ArrayImpl::Int32([None, Some(1), Some(1), Some(2)].into_iter().collect());
+        let distinct = eval_is_distinct_from(&a, &b, false);
+        let values = (0..4).map(|i| distinct.get(i)).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [false, true, false, true].map(DataValue::Bool).to_vec()
+        );
+        let not_distinct = eval_is_distinct_from(&a, &b, true);
+        let values = (0..4).map(|i| not_distinct.get(i)).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [true, false, true, false].map(DataValue::Bool).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_math_function() {
+        use ScalarFunctionKind::*;
+        let eval = |kind, values: &[DataValue]| math_function(kind, values);
+        let f = DataValue::Float64;
+
+        assert_eq!(eval(Sqrt, &[f(4.0)]), Some(f(2.0)));
+        assert_eq!(eval(Sqrt, &[f(-1.0)]), None);
+        assert_eq!(eval(Sqrt, &[DataValue::Null]), Some(DataValue::Null));
+        assert_eq!(eval(Power, &[f(2.0), f(10.0)]), Some(f(1024.0)));
+        assert_eq!(eval(Power, &[f(0.0), f(-1.0)]), None);
+        assert_eq!(eval(Power, &[f(-8.0), f(1.0 / 3.0)]), None);
+        assert_eq!(eval(Ln, &[f(1.0)]), Some(f(0.0)));
+        assert_eq!(eval(Ln, &[f(0.0)]), None);
+        assert_eq!(eval(Ln, &[f(-1.0)]), None);
+        assert_eq!(eval(Log, &[f(2.0), f(1.0)]), Some(f(0.0)));
+        assert_eq!(eval(Log, &[f(1.0), f(8.0)]), None);
+        assert_eq!(eval(Log, &[f(0.0), f(8.0)]), None);
+        assert_eq!(eval(Log, &[f(2.0), f(0.0)]), None);
+        assert_eq!(eval(Exp, &[f(0.0)]), Some(f(1.0)));
+        assert_eq!(eval(Exp, &[f(1000.0)]), None);
+        assert_eq!(eval(Mod, &[f(5.5), f(2.0)]), Some(f(1.5)));
+        assert_eq!(eval(Mod, &[f(5.5), f(0.0)]), None);
+
+        let i = DataValue::Int32;
+        assert_eq!(eval(Mod, &[i(-7), i(3)]), Some(i(-1)));
+        assert_eq!(eval(Mod, &[i(7), i(0)]), None);
+        assert_eq!(eval(Mod, &[i(i32::MIN), i(-1)]), None);
+        assert_eq!(
+            eval(Mod, &[DataValue::Int64(7), DataValue::Int64(4)]),
+            Some(DataValue::Int64(3))
+        );
+    }
+
+    #[test]
+    fn test_math_function_domain_error() {
+        let x = ArrayImpl::Float64([Some(4.0), None].into_iter().collect());
+        let ty = DataTypeKind::Double.nullable();
+        let kind = ScalarFunctionKind::Sqrt;
+        let res = eval_math_function(kind, &[x], &ty).unwrap();
+        assert_eq!(res.get(0), DataValue::Float64(2.0));
+        assert_eq!(res.get(1), DataValue::Null);
+        let x = ArrayImpl::Float64([Some(4.0), Some(-1.0)].into_iter().collect());
+        assert!(matches!(
+            eval_math_function(kind, &[x], &ty),
+            Err(ConvertError::Domain(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_substring() {
+        assert_eq!(substring("hello", 2, Some(3)), "ell");
+        assert_eq!(substring("hello", 2, None), "ello");
+        assert_eq!(substring("hello", 0, Some(3)), "he");
+        assert_eq!(substring("hello", -5, Some(3)), "");
+        assert_eq!(substring("hello", 4, Some(10)), "lo");
+        assert_eq!(substring("hello", 6, Some(1)), "");
+        assert_eq!(substring("hello", 1, Some(-1)), "");
+        assert_eq!(
+            substring("hello", i32::MAX as i64, Some(i32::MAX as i64)),
+            ""
+        );
+        assert_eq!(substring("数据库", 2, Some(1)), "据");
+    }
+
+    #[test]
+    fn test_concat_null_handling() {
+        let a = ArrayImpl::Utf8(["a", "b"].iter().map(|s| Some(*s)).collect());
+        let b = ArrayImpl::Utf8([Some("x"), None].into_iter().collect());
+        // `concat` skips NULLs
+        let array = eval_string_function(ScalarFunctionKind::Concat, &[a.clone(), b.clone()]);
+        assert_eq!(array.get(0), DataValue::String("ax".into()));
+        assert_eq!(array.get(1), DataValue::String("b".into()));
+        // while `||` returns NULL
+        let array = a.binary_op(&BinaryOperator::StringConcat, &b).unwrap();
+        assert_eq!(array.get(0), DataValue::String("ax".into()));
+        assert_eq!(array.get(1), DataValue::Null);
+    }
+
+    #[test]
+    fn test_extract() {
+        let ts: Timestamp = "2024-02-29 13:45:30.5".parse().unwrap();
+        assert_eq!(extract(ExtractField::Year, ts), 2024);
+        assert_eq!(extract(ExtractField::Month, ts), 2);
+        assert_eq!(extract(ExtractField::Day, ts), 29);
+        assert_eq!(extract(ExtractField::Hour, ts), 13);
+        assert_eq!(extract(ExtractField::Minute, ts), 45);
+        assert_eq!(extract(ExtractField::Second, ts), 30);
+
+        let ts: Timestamp = "1969-12-31 23:59:59".parse().unwrap();
+        assert_eq!(extract(ExtractField::Year, ts), 1969);
+        assert_eq!(extract(ExtractField::Second, ts), 59);
+    }
+
+    #[test]
+    fn test_coalesce_short_circuit() {
+        let ty = DataTypeKind::Int(None).nullable();
+        let args = [
+            BoundExpr::Constant(DataValue::Int32(1)),
+            BoundExpr::Constant(DataValue::Int32(2)),
+        ];
+        let mut evaluated = vec![];
+        let array = eval_coalesce(&args, 2, &ty, |arg| {
+            evaluated.push(arg.clone());
+            Ok(ArrayImpl::Int32([Some(1), Some(1)].into_iter().collect()))
+        })
+        .unwrap();
+        assert_eq!(array.get(1), DataValue::Int32(1));
+        assert_eq!(evaluated.len(), 1);
+    }
+
+    #[test]
+    fn test_primitive_binary_op_no_nulls() {
+        let a: I32Array = (0..1000).map(|i| i * 7 % 100).collect();
+        let b: I32Array = (0..1000).map(|i| i % 50).collect();
+        assert!(a.no_nulls() && b.no_nulls());
+        let fast: BoolArray = primitive_binary_op(&a, &b, |a, b| a < b);
+        let general: BoolArray = binary_op(&a, &b, |a, b| a < b);
+        assert_eq!(fast, general);
+        let fast: I32Array = primitive_binary_op(&a, &b, |a, b| a - b);
+        let general: I32Array = binary_op(&a, &b, |a, b| a - b);
+        assert_eq!(fast, general);
+
+        // fall back to the general path if any value is null
+        let c: I32Array = (0..1000).map(|i| (i % 3 != 0).then(|| i)).collect();
+        let result: BoolArray = primitive_binary_op(&a, &c, |a, b| a == b);
+        assert_eq!(result, binary_op(&a, &c, |a, b| a == b));
+        assert_eq!(result.get(0), None);
+
+        // errors are reported in both paths
+        let zero = ArrayImpl::Int32((0..1000).map(|_| 0).collect());
+        let a = ArrayImpl::Int32(a);
+        assert!(a.binary_op(&BinaryOperator::Divide, &zero).is_err());
+        let c = ArrayImpl::Int32(c);
+        assert!(c.binary_op(&BinaryOperator::Divide, &zero).is_err());
+    }
+}
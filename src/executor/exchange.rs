@@ -0,0 +1,154 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+use smallvec::SmallVec;
+use tokio::sync::mpsc;
+
+use super::hash_agg::serialize_datavalue;
+use super::*;
+use crate::array::{ArrayImpl, DataChunk};
+use crate::binder::BoundExpr;
+
+/// The exchange operator that repartitions the rows of its child by the hash of keys, so that
+/// rows with equal keys go to the same partition. The order of rows is kept in each partition.
+pub struct RepartitionExecutor {
+    pub keys: Vec<BoundExpr>,
+    pub num_partitions: usize,
+    pub child: BoxedExecutor,
+}
+
+type Sender = mpsc::Sender<Result<DataChunk, ExecutorError>>;
+
+impl RepartitionExecutor {
+    /// Pull the chunks of the child in a task, and return a stream of each partition.
+    ///
+    /// The partitions should be consumed concurrently, since the task is blocked when the buffer
+    /// of any partition is full.
+    pub fn execute(self) -> Vec<BoxedExecutor> {
+        // Buffer at most 16 chunks for each partition
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..self.num_partitions).map(|_| mpsc::channel(16)).unzip();
+        tokio::spawn(Self::partition(self.keys, self.child, senders));
+        receivers.into_iter().map(Self::receive).collect()
+    }
+
+    async fn partition(keys: Vec<BoundExpr>, mut child: BoxedExecutor, senders: Vec<Sender>) {
+        while let Some(chunk) = child.next().await {
+            let parts = chunk.and_then(|chunk| Self::split(&keys, &chunk, senders.len()));
+            let parts = match parts {
+                Ok(parts) => parts,
+                Err(err) => {
+                    // the error only needs to be reported once
+                    let _ = senders[0].send(Err(err)).await;
+                    return;
+                }
+            };
+            for (part, sender) in parts.into_iter().zip(&senders) {
+                // stop if any partition is dropped, which happens when its consumer fails
+                if part.cardinality() > 0 && sender.send(Ok(part)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Split the rows of a chunk into partitions by the hash of keys.
+    fn split(
+        keys: &[BoundExpr],
+        chunk: &DataChunk,
+        num_partitions: usize,
+    ) -> Result<Vec<DataChunk>, ExecutorError> {
+        let key_cols: SmallVec<[ArrayImpl; 16]> =
+            keys.iter().map(|e| e.eval(chunk)).try_collect()?;
+        let mut key_bytes = vec![];
+        let partition_of = (0..chunk.cardinality())
+            .map(|row_idx| {
+                key_bytes.clear();
+                for col in key_cols.iter() {
+                    serialize_datavalue(&col.get(row_idx), &mut key_bytes);
+                }
+                let mut hasher = DefaultHasher::new();
+                hasher.write(&key_bytes);
+                hasher.finish() as usize % num_partitions
+            })
+            .collect_vec();
+        Ok((0..num_partitions)
+            .map(|p| chunk.filter(partition_of.iter().map(move |&x| x == p)))
+            .collect())
+    }
+
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    async fn receive(mut rx: mpsc::Receiver<Result<DataChunk, ExecutorError>>) {
+        while let Some(chunk) = rx.recv().await {
+            yield chunk?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::binder::BoundInputRef;
+    use crate::types::{DataTypeExt, DataTypeKind, DataValue};
+
+    #[tokio::test]
+    async fn repartition_by_key() {
+        let input_ref = |index| {
+            BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: DataTypeKind::Int(None).not_null(),
+            })
+        };
+        let chunks = (0..4)
+            .map(|i| {
+                let chunk: DataChunk = [
+                    ArrayImpl::Int32((0..100).map(|v| v % 7).collect()),
+                    ArrayImpl::Int32((i * 100..i * 100 + 100).collect()),
+                ]
+                .into_iter()
+                .collect();
+                Ok(chunk)
+            })
+            .collect_vec();
+        let partitions = RepartitionExecutor {
+            keys: vec![input_ref(0)],
+            num_partitions: 3,
+            child: futures::stream::iter(chunks).boxed(),
+        }
+        .execute();
+        assert_eq!(partitions.len(), 3);
+
+        let outputs = futures::future::try_join_all(
+            (partitions.into_iter()).map(|partition| partition.try_collect::<Vec<_>>()),
+        )
+        .await
+        .unwrap();
+        let mut values = vec![];
+        let mut keys_of_partitions: Vec<HashSet<DataValue>> = vec![];
+        for chunks in outputs {
+            let rows = (chunks.iter())
+                .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+                .collect_vec();
+            let keys: HashSet<_> = rows.iter().map(|row| row[0].clone()).collect();
+            // rows with equal keys are in the same partition
+            for other in &keys_of_partitions {
+                assert!(keys.is_disjoint(other));
+            }
+            keys_of_partitions.push(keys);
+            // the order of rows is kept in each partition
+            let partition_values = rows.iter().map(|row| row[1].clone()).collect_vec();
+            assert!(partition_values
+                .windows(2)
+                .all(|w| w[0].partial_cmp(&w[1]) == Some(std::cmp::Ordering::Less)));
+            values.extend(partition_values);
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(values, (0..400).map(DataValue::Int32).collect_vec());
+    }
+}
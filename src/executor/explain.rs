@@ -23,3 +23,56 @@ impl ExplainExecutor {
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::Database;
+
+    #[tokio::test]
+    async fn test_explain_aggregate_over_join() {
+        let db = Database::new_in_memory();
+        db.run("create table x(a int, b int); create table y(c int, d int);")
+            .await
+            .unwrap();
+        let chunks = db
+            .run("explain select a, sum(d) from x join y on a = c where b > 1 group by a")
+            .await
+            .unwrap();
+        let explain = chunks[0].array_at(0).get_to_string(0);
+        let expected = "\
+PhysicalProjection: exprs [InputRef #0, InputRef #1]
+  PhysicalHashAgg: group keys [InputRef #0], agg calls [Sum([InputRef #3]) -> Int(None) (null)]
+    PhysicalHashJoin: op Inner, left_index [0], right_index [0]
+      PhysicalFilter: expr Gt(InputRef #1, Int32(1) (const))
+        PhysicalTableScan: table #0, columns [0, 1], with_row_handler: false, is_sorted: false, predicate: None, offset: 0, limit: None
+      PhysicalTableScan: table #1, columns [0, 1], with_row_handler: false, is_sorted: false, predicate: None, offset: 0, limit: None
+";
+        assert_eq!(explain, expected);
+    }
+
+    #[tokio::test]
+    async fn test_explain_merge_join() {
+        let db = Database::new_in_memory();
+        db.run(
+            "create table l(k int primary key, v int); create table r(k int primary key, w int);",
+        )
+        .await
+        .unwrap();
+        // the inputs sorted by the primary key are merged
+        let chunks = db
+            .run("explain select l.k, v, w from l join r on l.k = r.k order by l.k")
+            .await
+            .unwrap();
+        let explain = chunks[0].array_at(0).get_to_string(0);
+        assert!(explain.contains("PhysicalMergeJoin: op Inner, left_index [0], right_index [0]"));
+        assert!(!explain.contains("PhysicalHashJoin"));
+
+        let chunks = db
+            .run("explain select l.k, v, w from l join r on l.k = r.k")
+            .await
+            .unwrap();
+        let explain = chunks[0].array_at(0).get_to_string(0);
+        assert!(explain.contains("PhysicalHashJoin"));
+        assert!(!explain.contains("PhysicalMergeJoin"));
+    }
+}
@@ -1,73 +1,100 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::Hasher;
 
 use iter_chunks::IterChunks;
 use smallvec::SmallVec;
 
+use super::exchange::RepartitionExecutor;
+use super::order::cmp_keys;
+use super::spill::{SpillFile, SpillWriter};
 use super::*;
-use crate::array::{ArrayBuilderImpl, ArrayImpl};
-use crate::binder::{BoundAggCall, BoundExpr};
+use crate::array::ArrayBuilderImpl;
+use crate::binder::{BoundAggCall, BoundExpr, BoundOrderBy};
 use crate::executor::aggregation::AggregationState;
 use crate::types::DataValue;
 
 /// The executor of hash aggregation.
+///
+/// Once the estimated memory usage of the hash table exceeds `memory_limit`, the rows of new
+/// groups are no longer aggregated in memory, but partitioned by the hash of group keys and
+/// spilled to temporary files. The groups in memory are produced after the input ends, and then
+/// each partition is aggregated in the same way, which might spill into smaller partitions again.
 pub struct HashAggExecutor {
     pub agg_calls: Vec<BoundAggCall>,
     pub group_keys: Vec<BoundExpr>,
+    pub order: GroupOrder,
+    /// The maximum memory usage of the hash table in bytes, which is unlimited if not set. It is
+    /// ignored if groups are sorted, since groups in different partitions can not be sorted
+    /// together.
+    pub memory_limit: Option<usize>,
     pub child: BoxedExecutor,
 }
 
+/// The order of groups produced by [`HashAggExecutor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupOrder {
+    /// Groups are produced in the order of the hash table, which is nondeterministic.
+    Unordered,
+    /// Groups are sorted by group keys in ascending order, with NULLs placed first or last.
+    Sorted { nulls_first: bool },
+}
+
+impl Default for GroupOrder {
+    fn default() -> Self {
+        GroupOrder::Unordered
+    }
+}
+
 pub type HashKey = SmallVec<[DataValue; 16]>;
 pub type HashValue = SmallVec<[Box<dyn AggregationState>; 16]>;
 
+/// The hash table of aggregation.
+///
+/// Group keys are serialized into bytes before hashing, so that values which can not be hashed
+/// directly (e.g. `f64`) can also be used as group keys. The original key values are kept for
+/// output.
+pub type HashTable = HashMap<Vec<u8>, (HashKey, HashValue)>;
+
 impl HashAggExecutor {
-    fn execute_inner(
-        state_entries: &mut HashMap<Arc<HashKey>, HashValue>,
-        chunk: DataChunk,
+    /// Evaluate the group keys and the arguments of aggregations, which are the columns of the
+    /// input to the hash table.
+    fn eval_input(
+        chunk: &DataChunk,
         agg_calls: &[BoundAggCall],
         group_keys: &[BoundExpr],
-    ) -> Result<(), ExecutorError> {
-        // Eval group keys and arguments
-        let group_cols: SmallVec<[ArrayImpl; 16]> =
-            group_keys.iter().map(|e| e.eval(&chunk)).try_collect()?;
-        let arrays: SmallVec<[ArrayImpl; 16]> = agg_calls
-            .iter()
-            .map(|agg| agg.args[0].eval(&chunk))
+    ) -> Result<DataChunk, ExecutorError> {
+        // TODO: support aggregations with multiple arguments
+        let columns: DataChunk = (group_keys.iter())
+            .chain(agg_calls.iter().map(|agg| &agg.args[0]))
+            .map(|e| e.eval(chunk))
             .try_collect()?;
-
-        // Update states
-        let num_rows = chunk.cardinality();
-        for row_idx in 0..num_rows {
-            let mut group_key = HashKey::new();
-            for col in group_cols.iter() {
-                group_key.push(col.get(row_idx));
-            }
-            let group_key = Arc::new(group_key);
-
-            if !state_entries.contains_key(&group_key) {
-                state_entries.insert(group_key.clone(), create_agg_states(agg_calls));
-            }
-            // since we just checked existence, the key must exist so we `unwrap` directly
-            let states = state_entries.get_mut(&group_key).unwrap();
-            for (array, state) in arrays.iter().zip_eq(states.iter_mut()) {
-                // TODO: support aggregations with multiple arguments
-                state.update_single(&array.get(row_idx))?;
-            }
-        }
-
-        Ok(())
+        Ok(columns)
     }
 
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     async fn finish_agg(
-        state_entries: HashMap<Arc<HashKey>, HashValue>,
+        state_entries: HashTable,
         agg_calls: Vec<BoundAggCall>,
         group_keys: Vec<BoundExpr>,
+        order: GroupOrder,
     ) {
+        let mut entries = state_entries.values().collect_vec();
+        if let GroupOrder::Sorted { nulls_first } = order {
+            let comparators = (group_keys.iter())
+                .map(|key| BoundOrderBy {
+                    expr: key.clone(),
+                    descending: false,
+                    nulls_first,
+                })
+                .collect_vec();
+            entries.sort_by(|(k1, _), (k2, _)| cmp_keys(k1, k2, &comparators));
+        }
         // We use `iter_chunks::IterChunks` instead of `IterTools::Chunks` here, since
         // the latter doesn't implement Send.
-        let mut batches = IterChunks::chunks(state_entries.iter(), PROCESSING_WINDOW_SIZE);
+        let mut batches = IterChunks::chunks(entries.into_iter(), PROCESSING_WINDOW_SIZE);
         while let Some(batch) = batches.next() {
             let mut key_builders = group_keys
                 .iter()
@@ -94,18 +121,537 @@ impl HashAggExecutor {
 
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
-        let mut state_entries = HashMap::new();
+        let memory_limit = match self.order {
+            GroupOrder::Unordered => self.memory_limit.unwrap_or(usize::MAX),
+            GroupOrder::Sorted { .. } => usize::MAX,
+        };
+        let mut table = SpillingHashTable::new(memory_limit, 0);
 
         #[for_await]
         for chunk in self.child {
-            let chunk = chunk?;
-            Self::execute_inner(&mut state_entries, chunk, &self.agg_calls, &self.group_keys)?;
+            let input = Self::eval_input(&chunk?, &self.agg_calls, &self.group_keys)?;
+            table.update(&input, &self.agg_calls).await?;
         }
 
-        #[for_await]
-        for chunk in Self::finish_agg(state_entries, self.agg_calls, self.group_keys) {
-            let chunk = chunk?;
-            yield chunk
+        // the spilled partitions to be aggregated, and the times they have been spilled
+        let mut partitions = vec![];
+        loop {
+            let level = table.level;
+            let (state_entries, spilled) = table.finish().await?;
+            partitions.extend(spilled.into_iter().map(|file| (file, level + 1)));
+            #[for_await]
+            for chunk in Self::finish_agg(
+                state_entries,
+                self.agg_calls.clone(),
+                self.group_keys.clone(),
+                self.order,
+            ) {
+                let chunk = chunk?;
+                yield chunk
+            }
+
+            let (file, level) = match partitions.pop() {
+                Some(partition) => partition,
+                None => break,
+            };
+            table = SpillingHashTable::new(memory_limit, level);
+            #[for_await]
+            for input in file.read() {
+                table.update(&input?, &self.agg_calls).await?;
+            }
+        }
+    }
+}
+
+/// The number of partitions the rows are spilled into at a time.
+const SPILL_PARTITIONS: usize = 8;
+
+/// The estimated memory usage of an aggregation state in bytes.
+const AGG_STATE_SIZE: usize = 64;
+
+/// The hash table of [`HashAggExecutor`], which spills the rows of new groups into partitions
+/// once its memory usage exceeds the limit.
+///
+/// The input rows are the values of group keys followed by the arguments of aggregations.
+struct SpillingHashTable {
+    table: HashTable,
+    memory_usage: usize,
+    memory_limit: usize,
+    /// The times the input rows have been spilled, which seeds the hash of partitions, so that
+    /// the rows of a partition are split when spilled again.
+    level: u64,
+    /// The partitions of spilled rows, which are created once the table is full.
+    partitions: Vec<SpillWriter>,
+}
+
+impl SpillingHashTable {
+    fn new(memory_limit: usize, level: u64) -> Self {
+        SpillingHashTable {
+            table: HashTable::new(),
+            memory_usage: 0,
+            memory_limit,
+            level,
+            partitions: vec![],
+        }
+    }
+
+    async fn update(
+        &mut self,
+        input: &DataChunk,
+        agg_calls: &[BoundAggCall],
+    ) -> Result<(), ExecutorError> {
+        let (key_cols, arg_cols) = input
+            .arrays()
+            .split_at(input.arrays().len() - agg_calls.len());
+        // the partitions of the rows to be spilled
+        let mut spilled_to = vec![None; input.cardinality()];
+        let mut key_bytes = vec![];
+        for (row_idx, partition) in spilled_to.iter_mut().enumerate() {
+            key_bytes.clear();
+            for col in key_cols {
+                serialize_datavalue(&col.get(row_idx), &mut key_bytes);
+            }
+            if !self.table.contains_key(&key_bytes) {
+                if !self.partitions.is_empty() {
+                    *partition = Some(self.partition_of(&key_bytes));
+                    continue;
+                }
+                let group_key = key_cols.iter().map(|col| col.get(row_idx)).collect();
+                self.memory_usage += 2 * key_bytes.len()
+                    + std::mem::size_of::<(Vec<u8>, HashKey, HashValue)>()
+                    + AGG_STATE_SIZE * agg_calls.len();
+                self.table
+                    .insert(key_bytes.clone(), (group_key, create_agg_states(agg_calls)));
+                if self.memory_usage > self.memory_limit {
+                    self.partitions = (0..SPILL_PARTITIONS)
+                        .map(|_| SpillWriter::new())
+                        .try_collect()?;
+                }
+            }
+            let (_, states) = self.table.get_mut(&key_bytes).unwrap();
+            for (array, state) in arg_cols.iter().zip_eq(states.iter_mut()) {
+                state.update_single(&array.get(row_idx))?;
+            }
+        }
+
+        for (i, partition) in self.partitions.iter_mut().enumerate() {
+            let rows = input.filter(spilled_to.iter().map(|p| *p == Some(i)));
+            if rows.cardinality() > 0 {
+                partition.write(&rows).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn partition_of(&self, key_bytes: &[u8]) -> usize {
+        let mut hasher = DefaultHasher::new();
+        hasher.write_u64(self.level);
+        hasher.write(key_bytes);
+        hasher.finish() as usize % SPILL_PARTITIONS
+    }
+
+    /// Return the groups in memory and the spilled partitions.
+    async fn finish(self) -> Result<(HashTable, Vec<SpillFile>), ExecutorError> {
+        let mut files = vec![];
+        for partition in self.partitions {
+            files.push(partition.finish().await?);
+        }
+        Ok((self.table, files))
+    }
+}
+
+/// The executor of hash aggregation in parallel. The rows of the child are repartitioned by the
+/// hash of group keys, so that each group is in only one partition. Each partition is aggregated
+/// by a task, and the groups are produced one partition after another.
+pub struct ParallelHashAggExecutor {
+    pub agg_calls: Vec<BoundAggCall>,
+    pub group_keys: Vec<BoundExpr>,
+    pub num_partitions: usize,
+    /// The maximum memory usage of all partitions, which is divided equally among them.
+    pub memory_limit: Option<usize>,
+    pub child: BoxedExecutor,
+}
+
+impl ParallelHashAggExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let partitions = RepartitionExecutor {
+            keys: self.group_keys.clone(),
+            num_partitions: self.num_partitions,
+            child: self.child,
+        }
+        .execute();
+        let handlers = (partitions.into_iter())
+            .map(|child| {
+                let executor = HashAggExecutor {
+                    agg_calls: self.agg_calls.clone(),
+                    group_keys: self.group_keys.clone(),
+                    order: GroupOrder::Unordered,
+                    memory_limit: self.memory_limit.map(|limit| limit / self.num_partitions),
+                    child,
+                };
+                tokio::spawn(executor.execute().try_collect::<Vec<_>>())
+            })
+            .collect_vec();
+        for handler in handlers {
+            for chunk in handler.await.expect("failed to join aggregation thread")? {
+                yield chunk;
+            }
+        }
+    }
+}
+
+/// Serialize a value into bytes and append to the buffer.
+///
+/// Each value is prefixed with a tag of its type, and variable-length values are prefixed with
+/// their lengths, so that the concatenation of serialized values is unique.
+pub(super) fn serialize_datavalue(value: &DataValue, buf: &mut Vec<u8>) {
+    match value {
+        DataValue::Null => buf.push(0),
+        DataValue::Bool(v) => {
+            buf.push(1);
+            buf.push(*v as u8);
         }
+        DataValue::Int32(v) => {
+            buf.push(2);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        DataValue::Int64(v) => {
+            buf.push(3);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        DataValue::Float64(v) => {
+            buf.push(4);
+            // normalize -0.0 to 0.0
+            let v = if *v == 0.0 { 0.0f64 } else { *v };
+            buf.extend_from_slice(&v.to_bits().to_le_bytes());
+        }
+        DataValue::String(v) => {
+            buf.push(5);
+            buf.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            buf.extend_from_slice(v.as_bytes());
+        }
+        DataValue::Blob(v) => {
+            let bytes: &[u8] = v;
+            buf.push(6);
+            buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            buf.extend_from_slice(bytes);
+        }
+        DataValue::Decimal(v) => {
+            buf.push(7);
+            // equal decimals with different scales should have the same bytes
+            buf.extend_from_slice(&v.normalize().serialize());
+        }
+        DataValue::Date(v) => {
+            buf.push(8);
+            buf.extend_from_slice(&v.get_inner().to_le_bytes());
+        }
+        DataValue::Timestamp(v) => {
+            buf.push(10);
+            buf.extend_from_slice(&v.get_inner().to_le_bytes());
+        }
+        DataValue::Interval(v) => {
+            buf.push(9);
+            buf.extend_from_slice(&v.num_months().to_le_bytes());
+            buf.extend_from_slice(&v.days().to_le_bytes());
+            buf.extend_from_slice(&v.usecs().to_le_bytes());
+        }
+        DataValue::List(v) => {
+            buf.push(11);
+            buf.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            for value in v.iter() {
+                serialize_datavalue(value, buf);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::binder::{AggKind, BoundInputRef};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    #[tokio::test]
+    async fn hash_agg_multiple_keys() {
+        let int = || DataTypeKind::Int(None).not_null();
+        let input_ref = |index| {
+            BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: int(),
+            })
+        };
+        let agg_call = |kind| BoundAggCall {
+            kind,
+            args: vec![input_ref(2)],
+            return_type: int(),
+            distinct: false,
+        };
+        let chunk: DataChunk = [
+            ArrayImpl::Int32([1, 1, 2, 1].into_iter().collect()),
+            ArrayImpl::Int32([1, 2, 1, 1].into_iter().collect()),
+            ArrayImpl::Int32([10, 20, 30, 40].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        let executor = HashAggExecutor {
+            agg_calls: vec![
+                agg_call(AggKind::Sum),
+                agg_call(AggKind::Count),
+                agg_call(AggKind::Min),
+                agg_call(AggKind::Max),
+            ],
+            group_keys: vec![input_ref(0), input_ref(1)],
+            order: GroupOrder::Unordered,
+            memory_limit: None,
+            child: futures::stream::iter([Ok(chunk.clone()), Ok(chunk)]).boxed(),
+        };
+        let outputs = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let mut rows = outputs
+            .iter()
+            .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+            .collect_vec();
+        rows.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let expected = [
+            [1, 1, 100, 4, 10, 40],
+            [1, 2, 40, 2, 20, 20],
+            [2, 1, 60, 2, 30, 30],
+        ]
+        .iter()
+        .map(|row| row.iter().map(|v| DataValue::Int32(*v)).collect_vec())
+        .collect_vec();
+        assert_eq!(rows, expected);
+    }
+
+    #[tokio::test]
+    async fn hash_agg_sorted_groups() {
+        let int = || DataTypeKind::Int(None).nullable();
+        let input_ref = |index| {
+            BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: int(),
+            })
+        };
+        let chunk: DataChunk = [
+            ArrayImpl::Int32(
+                [Some(3), None, Some(1), Some(2), None, Some(1)]
+                    .into_iter()
+                    .collect(),
+            ),
+            ArrayImpl::Int32([1, 2, 3, 4, 5, 6].into_iter().map(Some).collect()),
+        ]
+        .into_iter()
+        .collect();
+        let run = |nulls_first| {
+            let executor = HashAggExecutor {
+                agg_calls: vec![BoundAggCall {
+                    kind: AggKind::Sum,
+                    args: vec![input_ref(1)],
+                    return_type: int(),
+                    distinct: false,
+                }],
+                group_keys: vec![input_ref(0)],
+                order: GroupOrder::Sorted { nulls_first },
+                memory_limit: None,
+                child: futures::stream::iter([Ok(chunk.clone())]).boxed(),
+            };
+            async move {
+                let outputs = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+                outputs
+                    .iter()
+                    .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+                    .collect_vec()
+            }
+        };
+        let row = |key: Option<i32>, sum| {
+            vec![
+                key.map_or(DataValue::Null, DataValue::Int32),
+                DataValue::Int32(sum),
+            ]
+        };
+
+        let expected = vec![
+            row(None, 7),
+            row(Some(1), 9),
+            row(Some(2), 4),
+            row(Some(3), 1),
+        ];
+        for _ in 0..10 {
+            assert_eq!(run(true).await, expected);
+        }
+        let expected = vec![
+            row(Some(1), 9),
+            row(Some(2), 4),
+            row(Some(3), 1),
+            row(None, 7),
+        ];
+        for _ in 0..10 {
+            assert_eq!(run(false).await, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn parallel_hash_agg_matches_single_threaded() {
+        let int = || DataTypeKind::Int(None).nullable();
+        let input_ref = |index| {
+            BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: int(),
+            })
+        };
+        let agg_calls = [AggKind::Sum, AggKind::Count, AggKind::Max]
+            .into_iter()
+            .map(|kind| BoundAggCall {
+                kind,
+                args: vec![input_ref(1)],
+                return_type: int(),
+                distinct: false,
+            })
+            .collect_vec();
+        let chunks = (0..10)
+            .map(|i| {
+                let chunk: DataChunk = [
+                    ArrayImpl::Int32(
+                        (0..100)
+                            .map(|v| if v % 13 == 0 { None } else { Some(v % 37) })
+                            .collect(),
+                    ),
+                    ArrayImpl::Int32((i * 100..i * 100 + 100).map(Some).collect()),
+                ]
+                .into_iter()
+                .collect();
+                chunk
+            })
+            .collect_vec();
+        let child = || futures::stream::iter(chunks.clone().into_iter().map(Ok)).boxed();
+        let collect_rows = |outputs: Vec<DataChunk>| {
+            let mut rows = outputs
+                .iter()
+                .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+                .collect_vec();
+            rows.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            rows
+        };
+
+        let executor = HashAggExecutor {
+            agg_calls: agg_calls.clone(),
+            group_keys: vec![input_ref(0)],
+            order: GroupOrder::Unordered,
+            memory_limit: None,
+            child: child(),
+        };
+        let expected = collect_rows(executor.execute().try_collect().await.unwrap());
+        assert_eq!(expected.len(), 38);
+
+        for num_partitions in [1, 2, 4, 7] {
+            let executor = ParallelHashAggExecutor {
+                agg_calls: agg_calls.clone(),
+                group_keys: vec![input_ref(0)],
+                num_partitions,
+                memory_limit: None,
+                child: child(),
+            };
+            let rows = collect_rows(executor.execute().try_collect().await.unwrap());
+            assert_eq!(rows, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn hash_agg_spills_to_disk() {
+        let int = || DataTypeKind::Int(None).nullable();
+        let input_ref = |index| {
+            BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: int(),
+            })
+        };
+        let agg_calls = [AggKind::Sum, AggKind::Count, AggKind::Min]
+            .into_iter()
+            .map(|kind| BoundAggCall {
+                kind,
+                args: vec![input_ref(1)],
+                return_type: int(),
+                distinct: false,
+            })
+            .collect_vec();
+        // 1000 groups whose rows are scattered over all chunks
+        let chunks = (0..10)
+            .map(|i| {
+                let values = (i * 500..i * 500 + 500).collect_vec();
+                let chunk: DataChunk = [
+                    ArrayImpl::Int32(values.iter().map(|v| Some(v * 7919 % 1000)).collect()),
+                    ArrayImpl::Int32(values.iter().map(|v| Some(*v)).collect()),
+                ]
+                .into_iter()
+                .collect();
+                chunk
+            })
+            .collect_vec();
+        let run = |memory_limit| {
+            let executor = HashAggExecutor {
+                agg_calls: agg_calls.clone(),
+                group_keys: vec![input_ref(0)],
+                order: GroupOrder::Unordered,
+                memory_limit,
+                child: futures::stream::iter(chunks.clone().into_iter().map(Ok)).boxed(),
+            };
+            async move {
+                let outputs: Vec<DataChunk> = executor.execute().try_collect().await.unwrap();
+                let mut rows = outputs
+                    .iter()
+                    .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+                    .collect_vec();
+                rows.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                rows
+            }
+        };
+        let expected = run(None).await;
+        assert_eq!(expected.len(), 1000);
+        for memory_limit in [4 << 10, 64 << 10] {
+            assert_eq!(run(Some(memory_limit)).await, expected);
+        }
+
+        // the rows of new groups are spilled once the table is full
+        let mut table = SpillingHashTable::new(16 << 10, 0);
+        for chunk in &chunks {
+            let input = HashAggExecutor::eval_input(chunk, &agg_calls, &[input_ref(0)]).unwrap();
+            table.update(&input, &agg_calls).await.unwrap();
+        }
+        let (state_entries, files) = table.finish().await.unwrap();
+        assert!(state_entries.len() < 100);
+        assert_eq!(files.len(), SPILL_PARTITIONS);
+    }
+
+    #[test]
+    fn serialize_group_key() {
+        let serialize = |values: &[DataValue]| {
+            let mut buf = vec![];
+            for value in values {
+                serialize_datavalue(value, &mut buf);
+            }
+            buf
+        };
+        assert_ne!(
+            serialize(&[
+                DataValue::String("a".into()),
+                DataValue::String("bc".into())
+            ]),
+            serialize(&[
+                DataValue::String("ab".into()),
+                DataValue::String("c".into())
+            ])
+        );
+        assert_ne!(
+            serialize(&[DataValue::Null]),
+            serialize(&[DataValue::Int32(0)])
+        );
+        assert_eq!(
+            serialize(&[DataValue::Float64(0.0)]),
+            serialize(&[DataValue::Float64(-0.0)])
+        );
     }
 }
@@ -1,24 +1,28 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::vec::Vec;
 
 use futures::TryStreamExt;
 
+use super::hash_agg::serialize_datavalue;
 use super::*;
 use crate::array::{ArrayBuilderImpl, DataChunk, RowRef};
 use crate::binder::{BoundExpr, BoundJoinOperator};
 use crate::types::{DataType, DataValue};
 
-/// The executor for hash join
+/// The executor for hash join.
+///
+/// The right child is the build side, which is fully consumed into a hash table keyed by the
+/// serialized join keys. Then the left child is streamed as the probe side.
 pub struct HashJoinExecutor {
     pub left_child: BoxedExecutor,
     pub right_child: BoxedExecutor,
     pub join_op: BoundJoinOperator,
     // TODO: filter by condition
     pub condition: BoundExpr,
-    pub left_column_index: usize,
-    pub right_column_index: usize,
+    pub left_column_indexes: Vec<usize>,
+    pub right_column_indexes: Vec<usize>,
     pub left_types: Vec<DataType>,
     pub right_types: Vec<DataType>,
 }
@@ -26,85 +30,269 @@ pub struct HashJoinExecutor {
 impl HashJoinExecutor {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
-        // collect all chunks from children
-        let (left_chunks, right_chunks) = async {
-            tokio::try_join!(
-                self.left_child.try_collect::<Vec<DataChunk>>(),
-                self.right_child.try_collect::<Vec<DataChunk>>(),
-            )
-        }
-        .await?;
-
-        // helper functions
-        let left_rows = || left_chunks.iter().flat_map(|chunk| chunk.rows());
-        let right_rows = || right_chunks.iter().flat_map(|chunk| chunk.rows());
-
-        // build
-        let mut hash_map: HashMap<DataValue, Vec<RowRef<'_>>> = HashMap::new();
-        for left_row in left_rows() {
-            let hash_value = left_row.get(self.left_column_index);
-            hash_map
-                .entry(hash_value)
-                .or_insert_with(Vec::new)
-                .push(left_row);
-        }
+        // collect all chunks from the build side
+        let right_chunks = self.right_child.try_collect::<Vec<DataChunk>>().await?;
+        let right_rows = right_chunks.iter().flat_map(|c| c.rows()).collect_vec();
 
-        // probe
-        let mut builders = (self.left_types.iter())
-            .chain(self.right_types.iter())
-            .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, ty))
-            .collect_vec();
-        for right_row in right_rows() {
-            let hash_value = right_row.get(self.right_column_index);
-            for left_row in hash_map.get(&hash_value).unwrap_or(&vec![]) {
-                let values = left_row.values().chain(right_row.values());
-                for (builder, v) in builders.iter_mut().zip_eq(values) {
-                    builder.push(&v);
-                }
+        // build: rows with the same key are chained in a list
+        let mut hash_table: HashMap<Vec<u8>, Vec<usize>> = HashMap::new();
+        let mut build_has_null = false;
+        for (i, row) in right_rows.iter().enumerate() {
+            match join_key(row, &self.right_column_indexes) {
+                Some(key) => hash_table.entry(key).or_default().push(i),
+                None => build_has_null = true,
             }
         }
 
-        // append rows for left outer join
-        if matches!(
+        let left_outer = matches!(
             self.join_op,
             BoundJoinOperator::LeftOuter | BoundJoinOperator::FullOuter
-        ) {
-            let right_keys = right_rows()
-                .map(|row| row.get(self.right_column_index))
-                .collect::<HashSet<DataValue>>();
-            for left_row in left_rows() {
-                let hash_value = left_row.get(self.left_column_index);
-                if right_keys.contains(&hash_value) {
-                    continue;
+        );
+        let right_outer = matches!(
+            self.join_op,
+            BoundJoinOperator::RightOuter | BoundJoinOperator::FullOuter
+        );
+        // whether each row of the build side has been matched
+        let mut matched = vec![false; right_rows.len()];
+
+        // semi and anti join only output the left columns
+        let semi_or_anti = self.join_op.is_semi_or_anti();
+        let right_output_types: &[DataType] = match semi_or_anti {
+            true => &[],
+            false => &self.right_types,
+        };
+        let new_builders = || {
+            (self.left_types.iter())
+                .chain(right_output_types.iter())
+                .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, ty))
+                .collect_vec()
+        };
+        let mut builders = new_builders();
+        let mut num_rows = 0;
+
+        // probe
+        #[for_await]
+        for chunk in self.left_child {
+            let chunk = chunk?;
+            for left_row in chunk.rows() {
+                let key = join_key(&left_row, &self.left_column_indexes);
+                let right_indexes = (key.as_ref())
+                    .and_then(|key| hash_table.get(key))
+                    .map_or(&[][..], |indexes| &indexes[..]);
+                if semi_or_anti {
+                    let emit = match self.join_op {
+                        BoundJoinOperator::LeftSemi => !right_indexes.is_empty(),
+                        BoundJoinOperator::LeftAnti => right_indexes.is_empty(),
+                        // `NULL NOT IN (...)` and `x NOT IN (..., NULL)` are unknown
+                        _ => {
+                            right_rows.is_empty()
+                                || (key.is_some() && !build_has_null && right_indexes.is_empty())
+                        }
+                    };
+                    if emit {
+                        for (builder, v) in builders.iter_mut().zip_eq(left_row.values()) {
+                            builder.push(&v);
+                        }
+                        num_rows += 1;
+                    }
+                } else {
+                    for &i in right_indexes {
+                        matched[i] = true;
+                        let values = left_row.values().chain(right_rows[i].values());
+                        for (builder, v) in builders.iter_mut().zip_eq(values) {
+                            builder.push(&v);
+                        }
+                        num_rows += 1;
+                    }
+                    if right_indexes.is_empty() && left_outer {
+                        // append row: (left, NULL)
+                        let values = (left_row.values())
+                            .chain(self.right_types.iter().map(|_| DataValue::Null));
+                        for (builder, v) in builders.iter_mut().zip_eq(values) {
+                            builder.push(&v);
+                        }
+                        num_rows += 1;
+                    }
                 }
-                // append row: (left, NULL)
-                let values =
-                    (left_row.values()).chain(self.right_types.iter().map(|_| DataValue::Null));
-                for (builder, v) in builders.iter_mut().zip_eq(values) {
-                    builder.push(&v);
+                if num_rows >= PROCESSING_WINDOW_SIZE {
+                    yield std::mem::replace(&mut builders, new_builders())
+                        .into_iter()
+                        .collect();
+                    num_rows = 0;
                 }
             }
         }
 
         // append rows for right outer join
-        if matches!(
-            self.join_op,
-            BoundJoinOperator::RightOuter | BoundJoinOperator::FullOuter
-        ) {
-            for right_row in right_rows() {
-                let hash_value = right_row.get(self.right_column_index);
-                if hash_map.contains_key(&hash_value) {
-                    continue;
-                }
+        if right_outer {
+            for (right_row, _) in right_rows.iter().zip(matched).filter(|(_, m)| !m) {
                 // append row: (NULL, right)
                 let values =
                     (self.left_types.iter().map(|_| DataValue::Null)).chain(right_row.values());
                 for (builder, v) in builders.iter_mut().zip_eq(values) {
                     builder.push(&v);
                 }
+                num_rows += 1;
             }
         }
 
-        yield builders.into_iter().collect();
+        if num_rows != 0 {
+            yield builders.into_iter().collect();
+        }
+    }
+}
+
+/// Serialize the join key of a row. Returns `None` if any value of the key is NULL, as NULL
+/// never matches.
+fn join_key(row: &RowRef<'_>, column_indexes: &[usize]) -> Option<Vec<u8>> {
+    let mut key = vec![];
+    for &i in column_indexes {
+        let value = row.get(i);
+        if value == DataValue::Null {
+            return None;
+        }
+        serialize_datavalue(&value, &mut key);
+    }
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    fn chunk(keys: &[Option<i32>], values: &[i32]) -> DataChunk {
+        [
+            ArrayImpl::Int32(keys.iter().cloned().collect()),
+            ArrayImpl::Int32(values.iter().cloned().collect()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    async fn hash_join(
+        join_op: BoundJoinOperator,
+        left: Vec<DataChunk>,
+        right: Vec<DataChunk>,
+    ) -> Vec<Vec<DataValue>> {
+        let ty = || vec![DataTypeKind::Int(None).nullable(); 2];
+        let executor = HashJoinExecutor {
+            left_child: futures::stream::iter(left.into_iter().map(Ok)).boxed(),
+            right_child: futures::stream::iter(right.into_iter().map(Ok)).boxed(),
+            join_op,
+            condition: BoundExpr::Constant(DataValue::Bool(true)),
+            left_column_indexes: vec![0],
+            right_column_indexes: vec![0],
+            left_types: ty(),
+            right_types: ty(),
+        };
+        let chunks = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let mut rows = (chunks.iter())
+            .flat_map(|c| c.rows())
+            .map(|row| row.values().collect_vec())
+            .collect_vec();
+        rows.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        rows
+    }
+
+    fn row(values: [Option<i32>; 4]) -> Vec<DataValue> {
+        values
+            .iter()
+            .map(|v| v.map_or(DataValue::Null, DataValue::Int32))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_hash_join_many_to_many() {
+        let left = vec![
+            chunk(&[Some(1), Some(2), None], &[0, 1, 2]),
+            chunk(&[Some(1), Some(3)], &[3, 4]),
+        ];
+        let right = vec![
+            chunk(&[Some(1), None], &[10, 11]),
+            chunk(&[Some(1), Some(2)], &[12, 13]),
+        ];
+        let rows = hash_join(BoundJoinOperator::Inner, left, right).await;
+        assert_eq!(
+            rows,
+            vec![
+                row([Some(1), Some(0), Some(1), Some(10)]),
+                row([Some(1), Some(0), Some(1), Some(12)]),
+                row([Some(1), Some(3), Some(1), Some(10)]),
+                row([Some(1), Some(3), Some(1), Some(12)]),
+                row([Some(2), Some(1), Some(2), Some(13)]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hash_join_left_outer() {
+        let left = vec![chunk(&[Some(1), Some(2), None], &[0, 1, 2])];
+        let right = vec![chunk(&[Some(1), Some(1), Some(3), None], &[10, 11, 12, 13])];
+        let rows = hash_join(BoundJoinOperator::LeftOuter, left, right).await;
+        assert_eq!(
+            rows,
+            vec![
+                row([None, Some(2), None, None]),
+                row([Some(1), Some(0), Some(1), Some(10)]),
+                row([Some(1), Some(0), Some(1), Some(11)]),
+                row([Some(2), Some(1), None, None]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hash_join_semi() {
+        // `k1 IN (SELECT k2 ...)` with duplicates in the subquery
+        let left = vec![chunk(&[Some(1), Some(2), None, Some(1)], &[0, 1, 2, 3])];
+        let right = vec![chunk(&[Some(1), Some(1), None, Some(1)], &[10, 11, 12, 13])];
+        let rows = hash_join(BoundJoinOperator::LeftSemi, left, right).await;
+        let expected = [[1, 0], [1, 3]]
+            .iter()
+            .map(|r| r.iter().map(|v| DataValue::Int32(*v)).collect_vec())
+            .collect_vec();
+        assert_eq!(rows, expected);
+    }
+
+    #[tokio::test]
+    async fn test_hash_join_anti() {
+        let left = || vec![chunk(&[Some(1), Some(2), None], &[0, 1, 2])];
+        let right = |keys: &[Option<i32>]| vec![chunk(keys, &vec![0; keys.len()])];
+        let values = |rows: &[[Option<i32>; 2]]| {
+            (rows.iter())
+                .map(|r| {
+                    r.iter()
+                        .map(|v| v.map_or(DataValue::Null, DataValue::Int32))
+                        .collect_vec()
+                })
+                .collect_vec()
+        };
+
+        // NULL keys never match in `NOT EXISTS`
+        let rows = hash_join(BoundJoinOperator::LeftAnti, left(), right(&[Some(1), None])).await;
+        assert_eq!(rows, values(&[[None, Some(2)], [Some(2), Some(1)]]));
+
+        // `NULL NOT IN (1, 3)` is unknown
+        let op = BoundJoinOperator::NullAwareLeftAnti;
+        let rows = hash_join(op, left(), right(&[Some(1), Some(3)])).await;
+        assert_eq!(rows, values(&[[Some(2), Some(1)]]));
+
+        // `2 NOT IN (1, NULL)` is unknown
+        let rows = hash_join(op, left(), right(&[Some(1), None])).await;
+        assert!(rows.is_empty());
+
+        // `x NOT IN (<empty>)` is always true
+        let rows = hash_join(op, left(), right(&[])).await;
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_hash_join_empty_build_side() {
+        let left = vec![chunk(&[Some(1)], &[0])];
+        let rows = hash_join(BoundJoinOperator::Inner, left.clone(), vec![]).await;
+        assert!(rows.is_empty());
+        let rows = hash_join(BoundJoinOperator::LeftOuter, left, vec![]).await;
+        assert_eq!(rows, vec![row([Some(1), Some(0), None, None])]);
     }
 }
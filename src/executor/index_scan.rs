@@ -0,0 +1,144 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use super::*;
+use crate::array::DataChunk;
+use crate::binder::BoundExpr;
+use crate::optimizer::plan_nodes::PhysicalTableScan;
+use crate::storage::{
+    ExplicitTransaction, SecondaryStorage, Storage, StorageColumnRef, TxnHandle, TxnIterator,
+};
+
+/// The executor of table scan on secondary storage using the secondary index on a column, which
+/// only reads the blocks containing the rows satisfying the conditions on the column.
+pub struct IndexScanExecutor {
+    pub plan: PhysicalTableScan,
+    pub expr: BoundExpr,
+    pub storage: Arc<SecondaryStorage>,
+    pub stats: Arc<QueryStats>,
+    /// The transaction started by `BEGIN`, if any.
+    pub txn: Option<Arc<ExplicitTransaction<SecondaryStorage>>>,
+}
+
+impl IndexScanExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let logical = self.plan.logical();
+        let table = self.storage.get_table(logical.table_ref_id())?;
+        let index = logical.index().expect("no index to scan");
+
+        // TODO: remove this when we have schema
+        let empty_chunk =
+            TableScanExecutor::<SecondaryStorage>::build_empty_chunk(&self.plan, &table)?;
+        let mut have_chunk = false;
+
+        let mut col_idx = (logical.column_ids().iter())
+            .map(|x| StorageColumnRef::Idx(*x))
+            .collect_vec();
+
+        // Add an extra column for RowHandler at the end
+        if logical.with_row_handler() {
+            col_idx.push(StorageColumnRef::RowHandler);
+        }
+
+        let txn = TxnHandle::read(&table, self.txn.as_deref()).await?;
+        let (offset, limit) = (logical.offset(), logical.limit());
+        let it = match &txn {
+            TxnHandle::Owned(txn) => {
+                txn.index_scan(&col_idx, index, self.expr, offset, limit)
+                    .await
+            }
+            TxnHandle::Shared(txn) => {
+                let txn = txn.lock().await;
+                let txn = txn.as_ref().expect("transaction is finished");
+                txn.index_scan(&col_idx, index, self.expr, offset, limit)
+                    .await
+            }
+        };
+        let mut it = match it {
+            Ok(it) => it,
+            Err(err) => {
+                txn.abort().await?;
+                return Err(err.into());
+            }
+        };
+
+        loop {
+            match it.next_batch(None).await {
+                Ok(Some(chunk)) => {
+                    yield chunk;
+                    have_chunk = true;
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    txn.abort().await?;
+                    return Err(err.into());
+                }
+            }
+        }
+
+        self.stats.record_scan(it.stats());
+        txn.abort().await?;
+
+        if !have_chunk {
+            yield empty_chunk;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::array::DataChunk;
+    use crate::storage::SecondaryStorageOptions;
+    use crate::Database;
+
+    #[tokio::test]
+    async fn test_index_scan_reads_matched_blocks() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let db = Database::new_on_disk(SecondaryStorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await;
+        db.run("create table t(id int, v int)").await.unwrap();
+        // the values are scattered, so that the statistics of every block cover all values
+        let values = (0..50000)
+            .map(|i| format!("({}, {})", i, i * 7919 % 50000))
+            .join(", ");
+        db.run(&format!("insert into t values {}", values))
+            .await
+            .unwrap();
+
+        let queries = [
+            "select id from t where v = 1234",
+            "select id from t where v < 3 order by id",
+        ];
+        let rows = |chunks: &[DataChunk]| {
+            (chunks.iter())
+                .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+                .collect_vec()
+        };
+        let mut expected = vec![];
+        let mut total_blocks = 0;
+        for query in queries {
+            let (output, stats) = db.run_with_stats(query).await.unwrap();
+            total_blocks = stats.blocks_read() + stats.blocks_skipped();
+            expected.push(rows(&output));
+        }
+        assert_eq!(expected[0].len(), 1);
+        assert_eq!(expected[1].len(), 3);
+        assert!(total_blocks > 3);
+
+        db.run("create index on t(v)").await.unwrap();
+        for ((query, max_blocks), expected) in queries.iter().zip([1, 3]).zip(expected) {
+            let (output, stats) = db.run_with_stats(query).await.unwrap();
+            assert_eq!(rows(&output), expected);
+            // only the blocks containing the matched rows are read
+            assert!(stats.blocks_read() <= max_blocks);
+            assert_eq!(stats.blocks_read() + stats.blocks_skipped(), total_blocks);
+        }
+        db.shutdown().await.unwrap();
+    }
+}
@@ -1,12 +1,18 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use super::*;
-use crate::array::{ArrayBuilderImpl, DataChunk};
-use crate::catalog::TableRefId;
-use crate::storage::{Storage, Table, Transaction};
-use crate::types::{ColumnId, DataType, DataValue};
+use crate::array::{ArrayBuilderImpl, ArrayImplValidExt, DataChunk};
+use crate::binder::{BoundBinaryOp, BoundExpr, BoundInputRef};
+use crate::catalog::{find_sort_key_ids, ColumnCatalog, TableRefId};
+use crate::parser::BinaryOperator;
+use crate::storage::{
+    ExplicitTransaction, ScanOptions, Storage, StorageColumnRef, Table, TxnHandle, TxnIterator,
+};
+use crate::types::{ColumnId, DataType, DataTypeExt, DataTypeKind, DataValue};
 
 /// The executor of `insert` statement.
 pub struct InsertExecutor<S: Storage> {
@@ -14,6 +20,11 @@ pub struct InsertExecutor<S: Storage> {
     pub column_ids: Vec<ColumnId>,
     pub storage: Arc<S>,
     pub child: BoxedExecutor,
+    /// Whether the storage supports filter scan, which is used to read only the existing rows
+    /// whose primary keys may conflict with the inserted rows.
+    pub filter_scan: bool,
+    /// The transaction started by `BEGIN`, if any.
+    pub txn: Option<Arc<ExplicitTransaction<S>>>,
 }
 
 impl<S: Storage> InsertExecutor<S> {
@@ -25,24 +36,62 @@ impl<S: Storage> InsertExecutor<S> {
         // example:
         //    columns = [0: Int, 1: Bool, 3: Float, 4: String]
         //    column_ids = [4, 1]
-        // => output_columns = [Null(Int), Pick(1), Null(Float), Pick(0)]
+        // => output_columns = [Default(Int), Pick(1), Default(Float), Pick(0)]
         let output_columns = columns
             .iter()
             .map(
                 |col| match self.column_ids.iter().position(|&id| id == col.id()) {
                     Some(index) => Column::Pick { index },
-                    None => Column::Null {
+                    None => Column::Default {
                         type_: col.datatype(),
+                        value: col.default_value(),
                     },
                 },
             )
             .collect_vec();
 
-        let mut txn = table.write().await?;
+        let mut txn = TxnHandle::write(&table, self.txn.as_deref()).await?;
+
+        let key_columns = find_sort_key_ids(&columns);
+        let key_refs = (key_columns.iter())
+            .map(|&idx| StorageColumnRef::Idx(columns[idx].id()))
+            .collect_vec();
+        let key_types = (key_columns.iter())
+            .map(|&idx| columns[idx].datatype())
+            .collect_vec();
+        // Without filter scan, the keys of all existing rows are loaded at once. Otherwise, only
+        // the rows in the key range of each chunk are read for the chunk.
+        let mut existing_keys = HashSet::new();
+        if !key_columns.is_empty() && !self.filter_scan {
+            existing_keys = scan_keys(&txn, &key_refs, None).await?;
+        }
+        // The keys inserted by this statement, which are not visible to the scans of `txn`.
+        let mut inserted_keys = HashSet::new();
+
         let mut cnt = 0;
         #[for_await]
         for chunk in self.child {
             let chunk = transform_chunk(chunk?, &output_columns);
+            // reject NULLs in NOT NULL columns before appending the chunk
+            if let Err(err) = check_not_null(&columns, &chunk) {
+                txn.abort().await?;
+                return Err(err);
+            }
+            if !key_columns.is_empty() {
+                let (rows, keys) = chunk_keys(&chunk, &key_columns);
+                if self.filter_scan && !keys.is_empty() {
+                    let expr = key_range_expr(&keys, &key_types);
+                    existing_keys = scan_keys(&txn, &key_refs, Some(expr)).await?;
+                }
+                let duplicate = (keys.into_iter())
+                    .position(|key| existing_keys.contains(&key) || !inserted_keys.insert(key))
+                    .map(|i| rows[i]);
+                if let Some(row_idx) = duplicate {
+                    txn.abort().await?;
+                    let key = key_to_string(&chunk, &key_columns, row_idx);
+                    return Err(ExecutorError::UniqueViolation(key));
+                }
+            }
             cnt += chunk.cardinality();
             txn.append(chunk).await?;
         }
@@ -54,11 +103,108 @@ impl<S: Storage> InsertExecutor<S> {
     }
 }
 
+/// Check that the NOT NULL columns of a chunk of full rows have no NULLs.
+pub(super) fn check_not_null(
+    columns: &[ColumnCatalog],
+    chunk: &DataChunk,
+) -> Result<(), ExecutorError> {
+    let null_column = (columns.iter().zip(chunk.arrays()))
+        .find(|(col, array)| !col.is_nullable() && array.get_valid_bitmap().not_all());
+    match null_column {
+        Some((col, _)) => Err(ExecutorError::NullConstraintViolation(col.name().into())),
+        None => Ok(()),
+    }
+}
+
+/// Get the primary keys of the rows in a chunk of full rows, along with the indexes of the rows.
+/// Keys with NULLs are skipped, as they never conflict: NULL is not equal to any value.
+pub(super) fn chunk_keys(
+    chunk: &DataChunk,
+    key_columns: &[usize],
+) -> (Vec<usize>, Vec<Vec<DataValue>>) {
+    (0..chunk.cardinality())
+        .map(|row_idx| {
+            let key = (key_columns.iter())
+                .map(|&idx| chunk.array_at(idx).get(row_idx))
+                .collect_vec();
+            (row_idx, key)
+        })
+        .filter(|(_, key)| !key.contains(&DataValue::Null))
+        .unzip()
+}
+
+/// Format the primary key of a row as `(k1, k2, ...)`.
+pub(super) fn key_to_string(chunk: &DataChunk, key_columns: &[usize], row_idx: usize) -> String {
+    let key = (key_columns.iter())
+        .map(|&idx| chunk.array_at(idx).get_to_string(row_idx))
+        .join(", ");
+    format!("({})", key)
+}
+
+/// Scan the primary keys of the visible rows in `txn`. Deleted rows are not returned by the scan,
+/// so their keys can be inserted again.
+async fn scan_keys<S: Storage>(
+    txn: &TxnHandle<S>,
+    key_refs: &[StorageColumnRef],
+    expr: Option<BoundExpr>,
+) -> Result<HashSet<Vec<DataValue>>, ExecutorError> {
+    let mut keys = HashSet::new();
+    let options = ScanOptions {
+        expr,
+        ..Default::default()
+    };
+    let mut it = txn.scan(key_refs, options).await?;
+    while let Some(chunk) = it.next_batch(None).await? {
+        keys.extend(chunk.rows().map(|row| row.values().collect_vec()));
+    }
+    Ok(keys)
+}
+
+/// Build the filter `min_i <= key_i AND key_i <= max_i` over every column of the primary key,
+/// where `min_i` and `max_i` are the bounds of the column in `keys`. `keys` must not contain
+/// NULLs, which sort before all values and would filter out every row as the lower bound.
+///
+/// The storage compares the bounds with the zone maps of blocks, and skips the blocks whose key
+/// ranges don't overlap with `keys`. Only the rows in the remaining blocks are compared by values.
+fn key_range_expr(keys: &[Vec<DataValue>], key_types: &[DataType]) -> BoundExpr {
+    let compare = |op, index: usize, value: &DataValue| {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op,
+            left_expr: Box::new(BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: key_types[index].clone(),
+            })),
+            right_expr: Box::new(BoundExpr::Constant(value.clone())),
+            return_type: Some(DataTypeKind::Boolean.nullable()),
+        })
+    };
+    (0..key_types.len())
+        .flat_map(|index| {
+            let (min, max) = (keys.iter().map(|key| &key[index]))
+                .minmax_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+                .into_option()
+                .expect("no keys");
+            [
+                compare(BinaryOperator::GtEq, index, min),
+                compare(BinaryOperator::LtEq, index, max),
+            ]
+        })
+        .reduce(|left, right| {
+            BoundExpr::BinaryOp(BoundBinaryOp {
+                op: BinaryOperator::And,
+                left_expr: Box::new(left),
+                right_expr: Box::new(right),
+                return_type: Some(DataTypeKind::Boolean.nullable()),
+            })
+        })
+        .expect("no key columns")
+}
+
 enum Column {
     /// Pick the column at `index` from child.
     Pick { index: usize },
-    /// Null values with `type`.
-    Null { type_: DataType },
+    /// Default values of the column with `type`, which are null if the column has no default.
+    Default { type_: DataType, value: DataValue },
 }
 
 fn transform_chunk(chunk: DataChunk, output_columns: &[Column]) -> DataChunk {
@@ -66,10 +212,10 @@ fn transform_chunk(chunk: DataChunk, output_columns: &[Column]) -> DataChunk {
         .iter()
         .map(|col| match col {
             Column::Pick { index } => chunk.array_at(*index).clone(),
-            Column::Null { type_ } => {
+            Column::Default { type_, value } => {
                 let mut builder = ArrayBuilderImpl::with_capacity(chunk.cardinality(), type_);
                 for _ in 0..chunk.cardinality() {
-                    builder.push(&DataValue::Null);
+                    builder.push(value);
                 }
                 builder.finish()
             }
@@ -87,7 +233,6 @@ mod tests {
     use crate::executor::CreateTableExecutor;
     use crate::optimizer::plan_nodes::PhysicalCreateTable;
     use crate::storage::InMemoryStorage;
-    use crate::types::{DataTypeExt, DataTypeKind};
 
     #[tokio::test]
     async fn simple() {
@@ -105,19 +250,119 @@ mod tests {
                 .collect();
             }
             .boxed(),
+            filter_scan: false,
+            txn: None,
+        };
+        executor.execute().next().await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn null_constraint_violation() {
+        let storage = create_table().await;
+        let executor = InsertExecutor {
+            table_ref_id: TableRefId::new(0, 0, 0),
+            column_ids: vec![0, 1],
+            storage: storage.as_in_memory_storage(),
+            child: async_stream::try_stream! {
+                yield [
+                    ArrayImpl::Int32([Some(1), Some(2)].into_iter().collect()),
+                    ArrayImpl::Int32([Some(3), None].into_iter().collect()),
+                ]
+                .into_iter()
+                .collect();
+            }
+            .boxed(),
+            filter_scan: false,
+            txn: None,
+        };
+        let err = executor.execute().next().await.unwrap().unwrap_err();
+        assert!(matches!(err, ExecutorError::NullConstraintViolation(col) if col == "v2"));
+    }
+
+    #[tokio::test]
+    async fn insert_null_into_nullable_column() {
+        let storage = create_table().await;
+        let executor = InsertExecutor {
+            table_ref_id: TableRefId::new(0, 0, 0),
+            column_ids: vec![0, 2],
+            storage: storage.as_in_memory_storage(),
+            child: async_stream::try_stream! {
+                yield [
+                    ArrayImpl::Int32([Some(1), Some(2)].into_iter().collect()),
+                    ArrayImpl::Int32([None, Some(4)].into_iter().collect()),
+                ]
+                .into_iter()
+                .collect();
+            }
+            .boxed(),
+            filter_scan: false,
+            txn: None,
         };
         executor.execute().next().await.unwrap().unwrap();
     }
 
+    #[tokio::test]
+    async fn unique_violation() {
+        let storage = create_table_with(true).await;
+        let insert = |values: Vec<i32>| InsertExecutor {
+            table_ref_id: TableRefId::new(0, 0, 0),
+            column_ids: vec![0, 1],
+            storage: storage.as_in_memory_storage(),
+            child: async_stream::try_stream! {
+                yield [
+                    ArrayImpl::Int32(values.iter().cloned().collect()),
+                    ArrayImpl::Int32(values.iter().cloned().collect()),
+                ]
+                .into_iter()
+                .collect();
+            }
+            .boxed(),
+            filter_scan: false,
+            txn: None,
+        };
+        insert(vec![1, 2]).execute().next().await.unwrap().unwrap();
+
+        // conflict with an existing row
+        let err = insert(vec![3, 2])
+            .execute()
+            .next()
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err, ExecutorError::UniqueViolation(key) if key == "(2)"));
+
+        // conflict with a row inserted by the same statement
+        let err = insert(vec![4, 4])
+            .execute()
+            .next()
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err, ExecutorError::UniqueViolation(key) if key == "(4)"));
+
+        // the aborted statements insert nothing
+        insert(vec![3, 4]).execute().next().await.unwrap().unwrap();
+    }
+
     async fn create_table() -> StorageImpl {
+        create_table_with(false).await
+    }
+
+    /// Create a table `t(v1 int not null, v2 int not null, v3 int)`, with `v1` as the primary
+    /// key if `primary` is set.
+    async fn create_table_with(primary: bool) -> StorageImpl {
         let storage = StorageImpl::InMemoryStorage(Arc::new(InMemoryStorage::new()));
+        let mut v1 =
+            ColumnCatalog::new(0, DataTypeKind::Int(None).not_null().to_column("v1".into()));
+        v1.set_primary(primary);
         let plan = PhysicalCreateTable::new(LogicalCreateTable::new(
             0,
             0,
             "t".into(),
             vec![
-                ColumnCatalog::new(0, DataTypeKind::Int(None).not_null().to_column("v1".into())),
+                v1,
                 ColumnCatalog::new(1, DataTypeKind::Int(None).not_null().to_column("v2".into())),
+                ColumnCatalog::new(2, DataTypeKind::Int(None).nullable().to_column("v3".into())),
             ],
         ));
         let mut executor = CreateTableExecutor {
@@ -0,0 +1,119 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+//! Matcher of `like` patterns.
+
+/// An element of a compiled `like` pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    /// Match the character literally.
+    Char(char),
+    /// `_`: match any single character.
+    Any,
+    /// `%`: match any sequence of characters, including an empty one.
+    AnySeq,
+}
+
+/// A compiled `like` pattern.
+#[derive(Debug)]
+pub struct LikePattern {
+    tokens: Vec<Token>,
+}
+
+impl LikePattern {
+    /// Compile the pattern. The character following the `escape` character is matched literally.
+    pub fn new(pattern: &str, escape: Option<char>) -> Self {
+        let mut tokens = vec![];
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                c if Some(c) == escape => Token::Char(chars.next().unwrap_or(c)),
+                '%' if tokens.last() == Some(&Token::AnySeq) => continue,
+                '%' => Token::AnySeq,
+                '_' => Token::Any,
+                c => Token::Char(c),
+            };
+            tokens.push(token);
+        }
+        LikePattern { tokens }
+    }
+
+    /// Check whether the whole string matches the pattern.
+    ///
+    /// On a mismatch, the matcher backtracks to the last `%` and lets it consume one more
+    /// character, so the string is scanned without recursion.
+    pub fn is_match(&self, s: &str) -> bool {
+        let chars: Vec<char> = s.chars().collect();
+        let (mut p, mut i) = (0, 0);
+        // the position of the last `%` in the pattern and the string
+        let mut backtrack = None;
+        while i < chars.len() {
+            match self.tokens.get(p) {
+                Some(Token::AnySeq) => {
+                    p += 1;
+                    backtrack = Some((p, i));
+                }
+                Some(Token::Any) => {
+                    p += 1;
+                    i += 1;
+                }
+                Some(Token::Char(c)) if *c == chars[i] => {
+                    p += 1;
+                    i += 1;
+                }
+                _ => match backtrack {
+                    Some((bp, bi)) => {
+                        p = bp;
+                        i = bi + 1;
+                        backtrack = Some((bp, bi + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+        self.tokens[p..].iter().all(|t| *t == Token::AnySeq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("abc", "abc", true)]
+    #[test_case("abc", "abcd", false)]
+    #[test_case("abc%", "abcd", true)]
+    #[test_case("abc%", "ab", false)]
+    #[test_case("%bc", "abc", true)]
+    #[test_case("%bc", "abcd", false)]
+    #[test_case("%b%", "abc", true)]
+    #[test_case("%", "", true)]
+    #[test_case("_", "", false)]
+    #[test_case("a_c", "abc", true)]
+    #[test_case("a_c", "ac", false)]
+    #[test_case("a%b_", "axxbyb", true)]
+    #[test_case("a%b_", "axxbybz", false)]
+    #[test_case("%%a%%", "bab", true)]
+    #[test_case("数据_", "数据库", true)]
+    #[test_case(r"100\%", "100%", true)]
+    #[test_case(r"100\%", "1000", false)]
+    #[test_case(r"a\_c", "abc", false)]
+    #[test_case(r"a\\c", r"a\c", true)]
+    fn like(pattern: &str, s: &str, expected: bool) {
+        assert_eq!(LikePattern::new(pattern, Some('\\')).is_match(s), expected);
+    }
+
+    #[test]
+    fn like_without_escape() {
+        let pattern = LikePattern::new(r"a\%", None);
+        assert!(pattern.is_match(r"a\bc"));
+        assert!(!pattern.is_match("a%"));
+    }
+
+    #[test]
+    fn like_custom_escape() {
+        let pattern = LikePattern::new("a#%", Some('#'));
+        assert!(pattern.is_match("a%"));
+        assert!(!pattern.is_match("ab"));
+    }
+}
@@ -11,8 +11,14 @@ pub struct LimitExecutor {
 }
 
 impl LimitExecutor {
+    /// Skips the first `offset` rows and yields at most `limit` rows after that, slicing the
+    /// chunks that straddle the boundaries. The child is dropped as soon as the limit is reached,
+    /// so no more rows are pulled from upstream.
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
+        if self.limit == 0 {
+            return Ok(());
+        }
         // the number of rows have been processed
         let mut processed = 0;
 
@@ -23,13 +29,12 @@ impl LimitExecutor {
             let start = processed.max(self.offset) - processed;
             let end = (processed + cardinality).min(self.offset + self.limit) - processed;
             processed += cardinality;
-            if start >= end {
-                continue;
-            }
-            if (start..end) == (0..cardinality) {
-                yield batch;
-            } else {
-                yield batch.slice(start..end);
+            if start < end {
+                if (start..end) == (0..cardinality) {
+                    yield batch;
+                } else {
+                    yield batch.slice(start..end);
+                }
             }
             if processed >= self.offset + self.limit {
                 break;
@@ -52,6 +57,10 @@ mod tests {
     #[test_case(&[(0..6)], 0, 10, &[(0..6)])]
     #[test_case(&[(0..6)], 10, 0, &[])]
     #[test_case(&[(0..2), (2..4), (4..6)], 1, 4, &[(1..2), (2..4), (4..5)])]
+    #[test_case(&[(0..3), (3..6), (6..9)], 4, 1, &[(4..5)])]
+    #[test_case(&[(0..3), (3..6), (6..9)], 2, 5, &[(2..3), (3..6), (6..7)])]
+    #[test_case(&[(0..3), (3..6), (6..9)], 3, 3, &[(3..6)])]
+    #[test_case(&[(0..3), (3..6)], 5, 10, &[(5..6)])]
     #[tokio::test]
     async fn limit(
         inputs: &'static [Range<i32>],
@@ -69,6 +78,34 @@ mod tests {
         assert_eq!(actual, outputs);
     }
 
+    #[tokio::test]
+    async fn stop_pulling_after_limit() {
+        // the child fails if it is pulled after the rows are enough
+        let child = futures::stream::iter([
+            Ok(range_to_chunk(&(0..4))),
+            Ok(range_to_chunk(&(4..8))),
+            Err(ExecutorError::BuildingPlanError),
+        ]);
+        let executor = LimitExecutor {
+            child: child.boxed(),
+            offset: 2,
+            limit: 3,
+        };
+        let actual = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(
+            actual,
+            vec![range_to_chunk(&(2..4)), range_to_chunk(&(4..5))]
+        );
+
+        let executor = LimitExecutor {
+            child: futures::stream::iter([Err(ExecutorError::BuildingPlanError)]).boxed(),
+            offset: 0,
+            limit: 0,
+        };
+        let actual = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        assert!(actual.is_empty());
+    }
+
     fn range_to_chunk(range: &Range<i32>) -> DataChunk {
         [ArrayImpl::Int32(range.clone().collect())]
             .into_iter()
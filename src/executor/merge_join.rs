@@ -0,0 +1,288 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::cmp::Ordering;
+
+use super::*;
+use crate::array::{ArrayBuilderImpl, DataChunk};
+use crate::binder::BoundJoinOperator;
+use crate::types::{DataType, DataValue};
+
+/// The executor for sort-merge join.
+///
+/// Both children must be sorted by the join keys in ascending order. Rows with NULL in any of
+/// the join keys never match. The children are read chunk by chunk, and only the rows of the
+/// current key are buffered.
+pub struct MergeJoinExecutor {
+    pub left_child: BoxedExecutor,
+    pub right_child: BoxedExecutor,
+    pub join_op: BoundJoinOperator,
+    pub left_column_indexes: Vec<usize>,
+    pub right_column_indexes: Vec<usize>,
+    pub left_types: Vec<DataType>,
+    pub right_types: Vec<DataType>,
+}
+
+impl MergeJoinExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        assert!(
+            matches!(self.join_op, BoundJoinOperator::Inner),
+            "merge join only supports inner join"
+        );
+        let mut left = SortedInput::new(self.left_child, self.left_column_indexes);
+        let mut right = SortedInput::new(self.right_child, self.right_column_indexes);
+
+        let new_builders = || {
+            (self.left_types.iter())
+                .chain(self.right_types.iter())
+                .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, ty))
+                .collect_vec()
+        };
+        let mut builders = new_builders();
+        let mut num_rows = 0;
+
+        let mut left_group = left.next_group().await?;
+        let mut right_group = right.next_group().await?;
+        while let (Some((left_key, left_rows)), Some((right_key, right_rows))) =
+            (&left_group, &right_group)
+        {
+            match cmp_keys(left_key, right_key)? {
+                Ordering::Less => left_group = left.next_group().await?,
+                Ordering::Greater => right_group = right.next_group().await?,
+                Ordering::Equal => {
+                    for left_row in left_rows {
+                        for right_row in right_rows {
+                            let values = left_row.iter().chain(right_row.iter());
+                            for (builder, v) in builders.iter_mut().zip_eq(values) {
+                                builder.push(v);
+                            }
+                            num_rows += 1;
+                            if num_rows == PROCESSING_WINDOW_SIZE {
+                                yield std::mem::replace(&mut builders, new_builders())
+                                    .into_iter()
+                                    .collect();
+                                num_rows = 0;
+                            }
+                        }
+                    }
+                    left_group = left.next_group().await?;
+                    right_group = right.next_group().await?;
+                }
+            }
+        }
+        if num_rows != 0 {
+            yield builders.into_iter().collect();
+        }
+    }
+}
+
+/// The rows of a sorted child, which are read chunk by chunk and grouped by the join key.
+struct SortedInput {
+    child: BoxedExecutor,
+    column_indexes: Vec<usize>,
+    /// The rest rows of the current chunk.
+    rows: std::vec::IntoIter<Vec<DataValue>>,
+    /// The first row of the next group, which has been read to find the end of a group.
+    peeked: Option<Vec<DataValue>>,
+}
+
+impl SortedInput {
+    fn new(child: BoxedExecutor, column_indexes: Vec<usize>) -> Self {
+        SortedInput {
+            child,
+            column_indexes,
+            rows: vec![].into_iter(),
+            peeked: None,
+        }
+    }
+
+    /// Get the next row of the child.
+    async fn next_row(&mut self) -> Result<Option<Vec<DataValue>>, ExecutorError> {
+        if let Some(row) = self.peeked.take() {
+            return Ok(Some(row));
+        }
+        loop {
+            if let Some(row) = self.rows.next() {
+                return Ok(Some(row));
+            }
+            match self.child.next().await {
+                Some(chunk) => {
+                    let chunk = chunk?;
+                    self.rows = (chunk.rows())
+                        .map(|row| row.values().collect_vec())
+                        .collect_vec()
+                        .into_iter();
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Get the next join key and all the rows of it. Rows with NULL keys are skipped.
+    #[allow(clippy::type_complexity)]
+    async fn next_group(
+        &mut self,
+    ) -> Result<Option<(Vec<DataValue>, Vec<Vec<DataValue>>)>, ExecutorError> {
+        let mut group: Option<(Vec<DataValue>, Vec<Vec<DataValue>>)> = None;
+        while let Some(row) = self.next_row().await? {
+            let key = match join_key(&row, &self.column_indexes) {
+                Some(key) => key,
+                None => continue,
+            };
+            match &mut group {
+                None => group = Some((key, vec![row])),
+                Some((group_key, rows)) => {
+                    if cmp_keys(group_key, &key)? != Ordering::Equal {
+                        self.peeked = Some(row);
+                        break;
+                    }
+                    rows.push(row);
+                }
+            }
+        }
+        Ok(group)
+    }
+}
+
+/// Get the join key of a row. Returns `None` if any value of the key is NULL.
+fn join_key(row: &[DataValue], column_indexes: &[usize]) -> Option<Vec<DataValue>> {
+    let key = column_indexes.iter().map(|i| row[*i].clone()).collect_vec();
+    if key.iter().any(|v| matches!(v, DataValue::Null)) {
+        return None;
+    }
+    Some(key)
+}
+
+fn cmp_keys(key1: &[DataValue], key2: &[DataValue]) -> Result<Ordering, ExecutorError> {
+    for (v1, v2) in key1.iter().zip_eq(key2) {
+        match cmp_value(v1, v2)? {
+            Ordering::Equal => continue,
+            o => return Ok(o),
+        }
+    }
+    Ok(Ordering::Equal)
+}
+
+/// Compare two non-null values of join keys. Like PostgreSQL, floats are compared by a total
+/// order, where NaN equals to itself and is greater than any other value.
+fn cmp_value(v1: &DataValue, v2: &DataValue) -> Result<Ordering, ExecutorError> {
+    match (v1, v2) {
+        (DataValue::Float64(f1), DataValue::Float64(f2)) => Ok(f1
+            .partial_cmp(f2)
+            .unwrap_or_else(|| f1.is_nan().cmp(&f2.is_nan()))),
+        _ => (v1.partial_cmp(v2))
+            .ok_or_else(|| ExecutorError::IncomparableJoinKeys(v1.clone(), v2.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::{ArrayImpl, RowRef};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    fn chunk(keys: &[Option<i32>], values: &[i32]) -> DataChunk {
+        [
+            ArrayImpl::Int32(keys.iter().cloned().collect()),
+            ArrayImpl::Int32(values.iter().cloned().collect()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    async fn merge_join(left: Vec<DataChunk>, right: Vec<DataChunk>) -> Vec<(i32, i32, i32, i32)> {
+        let ty = || vec![DataTypeKind::Int(None).nullable(); 2];
+        let executor = MergeJoinExecutor {
+            left_child: futures::stream::iter(left.into_iter().map(Ok)).boxed(),
+            right_child: futures::stream::iter(right.into_iter().map(Ok)).boxed(),
+            join_op: BoundJoinOperator::Inner,
+            left_column_indexes: vec![0],
+            right_column_indexes: vec![0],
+            left_types: ty(),
+            right_types: ty(),
+        };
+        let chunks = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let get = |row: &RowRef<'_>, i| match row.get(i) {
+            DataValue::Int32(v) => v,
+            v => panic!("unexpected value: {:?}", v),
+        };
+        chunks
+            .iter()
+            .flat_map(|c| c.rows())
+            .map(|row| (get(&row, 0), get(&row, 1), get(&row, 2), get(&row, 3)))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_merge_join_duplicate_keys() {
+        let left = vec![
+            chunk(&[None, Some(1), Some(2)], &[0, 1, 2]),
+            chunk(&[Some(2), Some(4)], &[3, 4]),
+        ];
+        let right = vec![
+            chunk(&[None, Some(2)], &[10, 11]),
+            chunk(&[Some(2), Some(3), Some(4), Some(4)], &[12, 13, 14, 15]),
+        ];
+        let rows = merge_join(left, right).await;
+        assert_eq!(
+            rows,
+            vec![
+                (2, 2, 2, 11),
+                (2, 2, 2, 12),
+                (2, 3, 2, 11),
+                (2, 3, 2, 12),
+                (4, 4, 4, 14),
+                (4, 4, 4, 15),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_join_empty_input() {
+        let left = vec![chunk(&[Some(1), Some(2)], &[1, 2])];
+        assert!(merge_join(left.clone(), vec![]).await.is_empty());
+        assert!(merge_join(vec![], left).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_join_null_keys() {
+        let left = vec![chunk(&[None, None], &[1, 2])];
+        let right = vec![chunk(&[None], &[3])];
+        assert!(merge_join(left, right).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_join_float_keys() {
+        let chunk = |keys: &[f64]| -> DataChunk {
+            [ArrayImpl::Float64(keys.iter().map(|k| Some(*k)).collect())]
+                .into_iter()
+                .collect()
+        };
+        let ty = || vec![DataTypeKind::Double.nullable()];
+        let executor = MergeJoinExecutor {
+            left_child: futures::stream::iter([Ok(chunk(&[-1.0, 0.0, f64::NAN]))]).boxed(),
+            right_child: futures::stream::iter([Ok(chunk(&[-0.0, 2.0, f64::NAN, f64::NAN]))])
+                .boxed(),
+            join_op: BoundJoinOperator::Inner,
+            left_column_indexes: vec![0],
+            right_column_indexes: vec![0],
+            left_types: ty(),
+            right_types: ty(),
+        };
+        let chunks = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let rows = (chunks.iter())
+            .flat_map(|c| c.rows())
+            .map(|row| format!("{:?}", row.values().collect_vec()))
+            .collect_vec();
+        assert_eq!(
+            rows,
+            vec![
+                "[Float64(0.0), Float64(-0.0)]",
+                "[Float64(NaN), Float64(NaN)]",
+                "[Float64(NaN), Float64(NaN)]",
+            ]
+        );
+    }
+}
@@ -14,55 +14,92 @@
 
 use std::sync::Arc;
 
-use futures::stream::{BoxStream, StreamExt};
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 
 use crate::array::DataChunk;
+use crate::binder::BoundJoinOperator;
 use crate::optimizer::plan_nodes::*;
 use crate::optimizer::PlanVisitor;
-use crate::storage::{StorageImpl, TracedStorageError};
-use crate::types::ConvertError;
+use crate::storage::{ExplicitTxnImpl, StorageImpl, TracedStorageError};
+use crate::types::{ConvertError, DataTypeKind, DataValue};
 
 mod aggregation;
+mod alter;
+mod analyze;
 mod copy_from_file;
 mod copy_to_file;
 mod create;
+mod create_index;
+mod cross_join;
 mod delete;
+mod distinct;
 mod drop;
 mod dummy_scan;
 pub mod evaluator;
+mod exchange;
 mod explain;
 mod filter;
 mod hash_agg;
 mod hash_join;
+mod index_scan;
 mod insert;
+mod like;
 mod limit;
+mod merge_join;
 mod nested_loop_join;
 mod order;
+mod parallel_scan;
 mod projection;
+mod row_count;
+mod set_operation;
 mod simple_agg;
+mod sorted_agg;
+mod spill;
+mod stats;
+mod subquery;
 mod table_scan;
+mod top_n;
+mod truncate;
+mod update;
 mod values;
 
 pub use self::aggregation::*;
+use self::alter::*;
+use self::analyze::*;
 use self::copy_from_file::*;
 use self::copy_to_file::*;
 use self::create::*;
+use self::create_index::*;
+use self::cross_join::*;
 use self::delete::*;
+use self::distinct::*;
 use self::drop::*;
 use self::dummy_scan::*;
 use self::explain::*;
 use self::filter::*;
+pub use self::hash_agg::GroupOrder;
 use self::hash_agg::*;
 use self::hash_join::*;
+use self::index_scan::*;
 use self::insert::*;
 use self::limit::*;
+use self::merge_join::*;
 use self::nested_loop_join::*;
 use self::order::*;
+use self::parallel_scan::*;
 use self::projection::*;
+use self::row_count::*;
+use self::set_operation::*;
 use self::simple_agg::*;
+use self::sorted_agg::*;
+pub use self::stats::*;
+use self::subquery::*;
 use self::table_scan::*;
+use self::top_n::*;
+use self::truncate::*;
+use self::update::*;
 use self::values::*;
 
 /// The error type of execution.
@@ -78,7 +115,9 @@ pub enum ExecutorError {
         TracedStorageError,
     ),
     #[error("conversion error: {0}")]
-    Convert(#[from] ConvertError),
+    Convert(#[source] ConvertError),
+    #[error("cast failure: {0}")]
+    CastFailure(#[source] ConvertError),
     #[error("tuple length mismatch: expected {expected} but got {actual}")]
     LengthMismatch { expected: usize, actual: usize },
     #[error("io error")]
@@ -95,6 +134,48 @@ pub enum ExecutorError {
     ),
     #[error("value can not be null")]
     NotNullable,
+    #[error("null value in column {0} violates not-null constraint")]
+    NullConstraintViolation(String),
+    #[error("duplicate key {0} violates unique constraint")]
+    UniqueViolation(String),
+    #[error("failed to parse line {line}: {source}")]
+    ParseLine {
+        line: u64,
+        #[source]
+        source: Box<ExecutorError>,
+    },
+    #[error("more than one row returned by a subquery used as an expression")]
+    SubqueryTooManyRows,
+    #[error("{0} out of range")]
+    NumericOverflow(DataTypeKind),
+    #[error("join keys {0:?} and {1:?} are not comparable")]
+    IncomparableJoinKeys(DataValue, DataValue),
+}
+
+impl From<ConvertError> for ExecutorError {
+    fn from(e: ConvertError) -> Self {
+        use ConvertError::*;
+        match e {
+            // values can not be casted to the target type
+            ParseInt(..)
+            | ParseFloat(..)
+            | ParseBool(..)
+            | ParseDecimal(..)
+            | ParseDate(..)
+            | ParseTimestamp(..)
+            | ParseInterval(..)
+            | ParseBlob(..)
+            | ToDecimalError(..)
+            | FromDecimalError(..)
+            | ToDateError(..)
+            | FromDateError(..)
+            | FromTimestampError(..)
+            | FromIntervalError(..)
+            | FromListError(..)
+            | Cast(..) => Self::CastFailure(e),
+            Arithmetic(..) | Domain(..) => Self::Convert(e),
+        }
+    }
 }
 
 /// The maximum chunk length produced by executor at a time.
@@ -112,16 +193,91 @@ pub type BoxedExecutor = BoxStream<'static, Result<DataChunk, ExecutorError>>;
 #[derive(Clone)]
 pub struct ExecutorBuilder {
     storage: StorageImpl,
+    stats: Arc<QueryStats>,
+    group_order: GroupOrder,
+    txn: Option<ExplicitTxnImpl>,
+    /// Number of tasks running a parallel operator, e.g. hash aggregation.
+    parallelism: usize,
+    /// The maximum memory usage in bytes of an operator, e.g. the hash table of aggregation or
+    /// the rows buffered by sort, beyond which data is spilled to disk.
+    memory_limit: Option<usize>,
+    /// Set when the executor being built does not care about the order of rows from the table
+    /// scan below it, which can then produce rows in any order.
+    unordered_scan: bool,
 }
 
 impl ExecutorBuilder {
     /// Create a new executor builder.
     pub fn new(storage: StorageImpl) -> ExecutorBuilder {
-        ExecutorBuilder { storage }
+        ExecutorBuilder {
+            storage,
+            stats: Default::default(),
+            group_order: GroupOrder::default(),
+            txn: None,
+            parallelism: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            memory_limit: None,
+            unordered_scan: false,
+        }
+    }
+
+    /// Collect the statistics of built executors into `stats`.
+    pub fn with_stats(self, stats: Arc<QueryStats>) -> ExecutorBuilder {
+        ExecutorBuilder { stats, ..self }
+    }
+
+    /// Set the order of groups produced by hash aggregations.
+    pub fn with_group_order(self, group_order: GroupOrder) -> ExecutorBuilder {
+        ExecutorBuilder {
+            group_order,
+            ..self
+        }
+    }
+
+    /// Set the number of tasks running a parallel operator.
+    pub fn with_parallelism(self, parallelism: usize) -> ExecutorBuilder {
+        ExecutorBuilder {
+            parallelism,
+            ..self
+        }
+    }
+
+    /// Set the maximum memory usage of an operator, beyond which data is spilled to disk.
+    pub fn with_memory_limit(self, memory_limit: Option<usize>) -> ExecutorBuilder {
+        ExecutorBuilder {
+            memory_limit,
+            ..self
+        }
+    }
+
+    /// Run the built executors in the transaction started by `BEGIN`.
+    pub fn with_txn(self, txn: Option<ExplicitTxnImpl>) -> ExecutorBuilder {
+        ExecutorBuilder { txn, ..self }
     }
 
     pub fn build(&mut self, plan: PlanRef) -> BoxedExecutor {
-        self.visit(plan).unwrap()
+        self.build_plan(plan)
+    }
+
+    /// Build the executor of the plan, which counts the rows it produces.
+    fn build_plan(&mut self, plan: PlanRef) -> BoxedExecutor {
+        // only filters and projections pass the rows from the table scan through in order
+        if !matches!(
+            plan.node_type(),
+            PlanNodeType::PhysicalFilter
+                | PlanNodeType::PhysicalProjection
+                | PlanNodeType::PhysicalTableScan
+        ) {
+            self.unordered_scan = false;
+        }
+        let rows = self
+            .stats
+            .register_executor(format!("{:?}", plan.node_type()));
+        self.visit(plan)
+            .unwrap()
+            .inspect_ok(move |chunk| {
+                rows.fetch_add(chunk.cardinality(), std::sync::atomic::Ordering::Relaxed);
+            })
+            .boxed()
     }
 }
 
@@ -145,6 +301,21 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
         })
     }
 
+    fn visit_physical_alter_table(&mut self, plan: &PhysicalAlterTable) -> Option<BoxedExecutor> {
+        Some(match &self.storage {
+            StorageImpl::InMemoryStorage(storage) => AlterTableExecutor {
+                plan: plan.clone(),
+                storage: storage.clone(),
+            }
+            .execute(),
+            StorageImpl::SecondaryStorage(storage) => AlterTableExecutor {
+                plan: plan.clone(),
+                storage: storage.clone(),
+            }
+            .execute(),
+        })
+    }
+
     fn visit_physical_drop(&mut self, plan: &PhysicalDrop) -> Option<BoxedExecutor> {
         Some(match &self.storage {
             StorageImpl::InMemoryStorage(storage) => DropExecutor {
@@ -160,20 +331,54 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
         })
     }
 
+    fn visit_physical_truncate(&mut self, plan: &PhysicalTruncate) -> Option<BoxedExecutor> {
+        Some(match &self.storage {
+            StorageImpl::InMemoryStorage(storage) => TruncateExecutor {
+                plan: plan.clone(),
+                storage: storage.clone(),
+            }
+            .execute(),
+            StorageImpl::SecondaryStorage(storage) => TruncateExecutor {
+                plan: plan.clone(),
+                storage: storage.clone(),
+            }
+            .execute(),
+        })
+    }
+
+    fn visit_physical_create_index(&mut self, plan: &PhysicalCreateIndex) -> Option<BoxedExecutor> {
+        Some(match &self.storage {
+            StorageImpl::InMemoryStorage(storage) => CreateIndexExecutor {
+                plan: plan.clone(),
+                storage: storage.clone(),
+            }
+            .execute(),
+            StorageImpl::SecondaryStorage(storage) => CreateIndexExecutor {
+                plan: plan.clone(),
+                storage: storage.clone(),
+            }
+            .execute(),
+        })
+    }
+
     fn visit_physical_insert(&mut self, plan: &PhysicalInsert) -> Option<BoxedExecutor> {
         Some(match &self.storage {
             StorageImpl::InMemoryStorage(storage) => InsertExecutor {
                 table_ref_id: plan.logical().table_ref_id(),
                 column_ids: plan.logical().column_ids().to_vec(),
                 storage: storage.clone(),
-                child: self.visit(plan.child()).unwrap(),
+                txn: self.txn.as_ref().and_then(ExplicitTxnImpl::in_memory),
+                child: self.build_plan(plan.child()),
+                filter_scan: false,
             }
             .execute(),
             StorageImpl::SecondaryStorage(storage) => InsertExecutor {
                 table_ref_id: plan.logical().table_ref_id(),
                 column_ids: plan.logical().column_ids().to_vec(),
                 storage: storage.clone(),
-                child: self.visit(plan.child()).unwrap(),
+                txn: self.txn.as_ref().and_then(ExplicitTxnImpl::secondary),
+                child: self.build_plan(plan.child()),
+                filter_scan: true,
             }
             .execute(),
         })
@@ -183,8 +388,21 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
         &mut self,
         plan: &PhysicalNestedLoopJoin,
     ) -> Option<BoxedExecutor> {
-        let left_child = self.visit(plan.left()).unwrap();
-        let right_child = self.visit(plan.right()).unwrap();
+        let left_child = self.build_plan(plan.left());
+        let right_child = self.build_plan(plan.right());
+        if plan.logical().join_op() == BoundJoinOperator::Inner
+            && plan.logical().predicate().is_always_true()
+        {
+            return Some(
+                CrossJoinExecutor {
+                    left_child,
+                    right_child,
+                    left_types: plan.left().out_types(),
+                    right_types: plan.right().out_types(),
+                }
+                .execute(),
+            );
+        }
         Some(
             NestedLoopJoinExecutor {
                 left_child,
@@ -204,22 +422,74 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
                 plan: plan.clone(),
                 expr: None,
                 storage: storage.clone(),
+                txn: self.txn.as_ref().and_then(ExplicitTxnImpl::in_memory),
+                stats: self.stats.clone(),
             }
             .execute(),
-            StorageImpl::SecondaryStorage(storage) => TableScanExecutor {
-                plan: plan.clone(),
-                expr: plan.logical().expr().cloned(),
-                storage: storage.clone(),
+            StorageImpl::SecondaryStorage(storage) => {
+                let ordered = !std::mem::take(&mut self.unordered_scan);
+                let logical = plan.logical();
+                let parallelism = storage.options().scan_parallelism;
+                if let (Some(_), Some(predicate)) = (logical.index(), logical.predicate()) {
+                    IndexScanExecutor {
+                        plan: plan.clone(),
+                        expr: predicate.to_expr(),
+                        storage: storage.clone(),
+                        txn: self.txn.as_ref().and_then(ExplicitTxnImpl::secondary),
+                        stats: self.stats.clone(),
+                    }
+                    .execute()
+                } else if parallelism > 1
+                    // sorted scans and scans with offset or limit are not split
+                    && !logical.is_sorted()
+                    && logical.offset() == 0
+                    && logical.limit().is_none()
+                {
+                    ParallelTableScanExecutor {
+                        plan: plan.clone(),
+                        expr: logical.predicate().map(ScanPredicate::to_expr),
+                        storage: storage.clone(),
+                        txn: self.txn.as_ref().and_then(ExplicitTxnImpl::secondary),
+                        stats: self.stats.clone(),
+                        parallelism,
+                        ordered,
+                    }
+                    .execute()
+                } else {
+                    TableScanExecutor {
+                        plan: plan.clone(),
+                        expr: logical.predicate().map(ScanPredicate::to_expr),
+                        storage: storage.clone(),
+                        txn: self.txn.as_ref().and_then(ExplicitTxnImpl::secondary),
+                        stats: self.stats.clone(),
+                    }
+                    .execute()
+                }
             }
-            .execute(),
         })
     }
 
     fn visit_physical_projection(&mut self, plan: &PhysicalProjection) -> Option<BoxedExecutor> {
+        let mut project_expressions = plan.logical().project_expressions().to_vec();
+        // the dummy scan produces a single column that is not in its schema
+        let num_columns = match plan.child().node_type() {
+            PlanNodeType::Dummy => 1,
+            _ => plan.child().out_types().len(),
+        };
+        let subqueries = extract_subqueries(&mut project_expressions, num_columns);
+        let mut child = self.build_plan(plan.child());
+        if !subqueries.is_empty() {
+            child = SubqueryExecutor {
+                child,
+                subqueries,
+                storage: self.storage.clone(),
+            }
+            .execute();
+        }
         Some(
             ProjectionExecutor {
-                project_expressions: plan.logical().project_expressions().to_vec(),
-                child: self.visit(plan.child()).unwrap(),
+                project_expressions,
+                child,
             }
             .execute(),
         )
@@ -229,7 +499,7 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
         Some(
             FilterExecutor {
                 expr: plan.logical().expr().clone(),
-                child: self.visit(plan.child()).unwrap(),
+                child: self.build_plan(plan.child()),
             }
             .execute(),
         )
@@ -239,7 +509,8 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
         Some(
             OrderExecutor {
                 comparators: plan.logical().comparators().to_vec(),
-                child: self.visit(plan.child()).unwrap(),
+                child: self.build_plan(plan.child()),
+                memory_limit: self.memory_limit,
             }
             .execute(),
         )
@@ -248,7 +519,7 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
     fn visit_physical_limit(&mut self, plan: &PhysicalLimit) -> Option<BoxedExecutor> {
         Some(
             LimitExecutor {
-                child: self.visit(plan.child()).unwrap(),
+                child: self.build_plan(plan.child()),
                 offset: plan.logical().offset(),
                 limit: plan.logical().limit(),
             }
@@ -256,32 +527,100 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
         )
     }
 
+    fn visit_physical_distinct(&mut self, plan: &PhysicalDistinct) -> Option<BoxedExecutor> {
+        Some(
+            DistinctExecutor {
+                child: self.build_plan(plan.child()),
+            }
+            .execute(),
+        )
+    }
+
+    fn visit_physical_top_n(&mut self, plan: &PhysicalTopN) -> Option<BoxedExecutor> {
+        Some(
+            TopNExecutor {
+                child: self.build_plan(plan.child()),
+                offset: plan.offset(),
+                limit: plan.limit(),
+                comparators: plan.comparators().to_vec(),
+                output_types: plan.out_types(),
+            }
+            .execute(),
+        )
+    }
+
     fn visit_physical_explain(&mut self, plan: &PhysicalExplain) -> Option<BoxedExecutor> {
         Some(ExplainExecutor { plan: plan.clone() }.execute())
     }
 
     fn visit_physical_hash_agg(&mut self, plan: &PhysicalHashAgg) -> Option<BoxedExecutor> {
+        // groups are produced in the order of the sorted input, which are also sorted by keys
+        if is_grouped_by(&plan.child(), plan.logical().group_keys()) {
+            return Some(
+                SortedAggExecutor {
+                    agg_calls: plan.logical().agg_calls().to_vec(),
+                    group_keys: plan.logical().group_keys().to_vec(),
+                    child: self.build_plan(plan.child()),
+                }
+                .execute(),
+            );
+        }
+        self.unordered_scan = plan.logical().agg_calls().iter().all(is_order_insensitive);
+        let child = self.build_plan(plan.child());
+        // groups from different partitions can not be sorted without merging
+        if self.parallelism > 1
+            && self.group_order == GroupOrder::Unordered
+            && !plan.logical().group_keys().is_empty()
+        {
+            return Some(
+                ParallelHashAggExecutor {
+                    agg_calls: plan.logical().agg_calls().to_vec(),
+                    group_keys: plan.logical().group_keys().to_vec(),
+                    num_partitions: self.parallelism,
+                    memory_limit: self.memory_limit,
+                    child,
+                }
+                .execute(),
+            );
+        }
         Some(
             HashAggExecutor {
                 agg_calls: plan.logical().agg_calls().to_vec(),
                 group_keys: plan.logical().group_keys().to_vec(),
-                child: self.visit(plan.child()).unwrap(),
+                order: self.group_order,
+                memory_limit: self.memory_limit,
+                child,
             }
             .execute(),
         )
     }
 
     fn visit_physical_hash_join(&mut self, plan: &PhysicalHashJoin) -> Option<BoxedExecutor> {
-        let left_child = self.visit(plan.left()).unwrap();
-        let right_child = self.visit(plan.right()).unwrap();
+        let left_child = self.build_plan(plan.left());
+        let right_child = self.build_plan(plan.right());
         Some(
             HashJoinExecutor {
                 left_child,
                 right_child,
                 join_op: plan.logical().join_op(),
                 condition: plan.logical().predicate().to_on_clause(),
-                left_column_index: plan.left_column_index(),
-                right_column_index: plan.right_column_index(),
+                left_column_indexes: plan.left_column_indexes().to_vec(),
+                right_column_indexes: plan.right_column_indexes().to_vec(),
+                left_types: plan.left().out_types(),
+                right_types: plan.right().out_types(),
+            }
+            .execute(),
+        )
+    }
+
+    fn visit_physical_merge_join(&mut self, plan: &PhysicalMergeJoin) -> Option<BoxedExecutor> {
+        Some(
+            MergeJoinExecutor {
+                left_child: self.build_plan(plan.left()),
+                right_child: self.build_plan(plan.right()),
+                join_op: plan.logical().join_op(),
+                left_column_indexes: plan.left_column_indexes().to_vec(),
+                right_column_indexes: plan.right_column_indexes().to_vec(),
                 left_types: plan.left().out_types(),
                 right_types: plan.right().out_types(),
             }
@@ -289,29 +628,95 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
         )
     }
 
+    fn visit_physical_set_operation(
+        &mut self,
+        plan: &PhysicalSetOperation,
+    ) -> Option<BoxedExecutor> {
+        let left_child = self.build_plan(plan.left());
+        let right_child = self.build_plan(plan.right());
+        Some(
+            SetOperationExecutor {
+                left_child,
+                right_child,
+                op: plan.logical().op(),
+                all: plan.logical().all(),
+            }
+            .execute(),
+        )
+    }
+
     fn visit_physical_simple_agg(&mut self, plan: &PhysicalSimpleAgg) -> Option<BoxedExecutor> {
+        if let StorageImpl::SecondaryStorage(storage) = &self.storage {
+            if let Some(table_ref_id) = RowCountExecutor::table_of(plan) {
+                return Some(
+                    RowCountExecutor {
+                        table_ref_id,
+                        storage: storage.clone(),
+                        txn: self.txn.as_ref().and_then(ExplicitTxnImpl::secondary),
+                    }
+                    .execute(),
+                );
+            }
+        }
+        self.unordered_scan = plan.agg_calls().iter().all(is_order_insensitive);
         Some(
             SimpleAggExecutor {
                 agg_calls: plan.agg_calls().to_vec(),
-                child: self.visit(plan.child()).unwrap(),
+                child: self.build_plan(plan.child()),
             }
             .execute(),
         )
     }
 
     fn visit_physical_delete(&mut self, plan: &PhysicalDelete) -> Option<BoxedExecutor> {
-        let child = self.visit(plan.child()).unwrap();
+        let child = self.build_plan(plan.child());
         Some(match &self.storage {
             StorageImpl::InMemoryStorage(storage) => DeleteExecutor {
                 child,
                 table_ref_id: plan.logical().table_ref_id(),
                 storage: storage.clone(),
+                txn: self.txn.as_ref().and_then(ExplicitTxnImpl::in_memory),
             }
             .execute(),
             StorageImpl::SecondaryStorage(storage) => DeleteExecutor {
                 child,
                 table_ref_id: plan.logical().table_ref_id(),
                 storage: storage.clone(),
+                txn: self.txn.as_ref().and_then(ExplicitTxnImpl::secondary),
+            }
+            .execute(),
+        })
+    }
+
+    fn visit_physical_analyze(&mut self, plan: &PhysicalAnalyze) -> Option<BoxedExecutor> {
+        Some(
+            AnalyzeExecutor {
+                table_ref_id: plan.logical().table_ref_id(),
+                column_ids: plan.logical().column_ids().to_vec(),
+                catalog: self.storage.catalog().clone(),
+                child: self.build_plan(plan.child()),
+            }
+            .execute(),
+        )
+    }
+
+    fn visit_physical_update(&mut self, plan: &PhysicalUpdate) -> Option<BoxedExecutor> {
+        let child = self.build_plan(plan.child());
+        Some(match &self.storage {
+            StorageImpl::InMemoryStorage(storage) => UpdateExecutor {
+                child,
+                table_ref_id: plan.logical().table_ref_id(),
+                values: plan.logical().values().to_vec(),
+                storage: storage.clone(),
+                txn: self.txn.as_ref().and_then(ExplicitTxnImpl::in_memory),
+            }
+            .execute(),
+            StorageImpl::SecondaryStorage(storage) => UpdateExecutor {
+                child,
+                table_ref_id: plan.logical().table_ref_id(),
+                values: plan.logical().values().to_vec(),
+                storage: storage.clone(),
+                txn: self.txn.as_ref().and_then(ExplicitTxnImpl::secondary),
             }
             .execute(),
         })
@@ -337,11 +742,41 @@ impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
     fn visit_physical_copy_to_file(&mut self, plan: &PhysicalCopyToFile) -> Option<BoxedExecutor> {
         Some(
             CopyToFileExecutor {
-                child: self.visit(plan.child()).unwrap(),
+                child: self.build_plan(plan.child()),
                 path: plan.logical().path().clone(),
                 format: plan.logical().format().clone(),
+                column_names: plan.logical().column_names().to_vec(),
             }
             .execute(),
         )
     }
 }
+
+/// Whether the rows of the plan are sorted by the keys, so that the rows with equal keys are
+/// adjacent. It holds if the rows come from a sorted table scan, and the keys are the columns of
+/// a prefix of the primary key.
+fn is_grouped_by(plan: &PlanRef, keys: &[crate::binder::BoundExpr]) -> bool {
+    use crate::binder::BoundExpr;
+
+    if let Ok(filter) = plan.as_physical_filter() {
+        return is_grouped_by(&filter.child(), keys);
+    }
+    match plan.as_physical_table_scan() {
+        Ok(scan) if scan.logical().is_sorted() => {}
+        _ => return false,
+    }
+    let schema = plan.schema();
+    let mut key_indexes = vec![];
+    for key in keys {
+        match key {
+            BoundExpr::InputRef(input_ref) => match schema[input_ref.index].primary_key_index() {
+                Some(index) => key_indexes.push(index),
+                None => return false,
+            },
+            _ => return false,
+        }
+    }
+    key_indexes.sort_unstable();
+    key_indexes.dedup();
+    !keys.is_empty() && key_indexes.iter().enumerate().all(|(i, &index)| i == index)
+}
@@ -10,6 +10,10 @@ use crate::binder::{BoundExpr, BoundJoinOperator};
 use crate::types::{DataType, DataValue};
 
 /// The executor for nested loop join.
+///
+/// The right child is the build side, which is fully consumed. Then each row of the left child
+/// is joined with all rows of the right child. Rows are matched only if the condition evaluates
+/// to `true`, so a NULL condition never matches.
 pub struct NestedLoopJoinExecutor {
     pub left_child: BoxedExecutor,
     pub right_child: BoxedExecutor,
@@ -22,14 +26,9 @@ pub struct NestedLoopJoinExecutor {
 impl NestedLoopJoinExecutor {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
-        // collect all chunks from children
-        let (left_chunks, right_chunks) = async {
-            tokio::try_join!(
-                self.left_child.try_collect::<Vec<DataChunk>>(),
-                self.right_child.try_collect::<Vec<DataChunk>>(),
-            )
-        }
-        .await?;
+        // collect all chunks from the build side
+        let right_chunks = self.right_child.try_collect::<Vec<DataChunk>>().await?;
+        let right_rows = right_chunks.iter().flat_map(|c| c.rows()).collect_vec();
 
         // helper functions
         let create_builders = || {
@@ -39,80 +38,220 @@ impl NestedLoopJoinExecutor {
                 .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, ty))
                 .collect_vec()
         };
-        let left_rows = || left_chunks.iter().flat_map(|chunk| chunk.rows());
-        let right_rows = || right_chunks.iter().flat_map(|chunk| chunk.rows());
-
-        // cross join: left x right
-        let mut builders = create_builders();
-        for left_row in left_rows() {
-            for right_row in right_rows() {
-                let values = left_row.values().chain(right_row.values());
-                for (builder, v) in builders.iter_mut().zip_eq(values) {
-                    builder.push(&v);
-                }
-            }
-        }
-        let cross_chunk = builders.into_iter().collect();
+        let left_outer = matches!(
+            self.join_op,
+            BoundJoinOperator::LeftOuter | BoundJoinOperator::FullOuter
+        );
+        let right_outer = matches!(
+            self.join_op,
+            BoundJoinOperator::RightOuter | BoundJoinOperator::FullOuter
+        );
 
-        // evaluate filter bitmap
-        let filter = match self.condition.eval(&cross_chunk)? {
-            ArrayImpl::Bool(a) => a,
-            _ => panic!("unsupported value from join condition"),
+        // semi and anti join only output the left columns
+        let semi_or_anti = self.join_op.is_semi_or_anti();
+        let create_output_builders = || match semi_or_anti {
+            true => (self.left_types.iter())
+                .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, ty))
+                .collect_vec(),
+            false => create_builders(),
         };
-        yield cross_chunk.filter(filter.iter().map(|b| matches!(b, Some(true))));
 
-        // append rows for left outer join
-        if matches!(
-            self.join_op,
-            BoundJoinOperator::LeftOuter | BoundJoinOperator::FullOuter
-        ) {
-            let mut builders = create_builders();
-            let mut i = 0;
-            for left_row in left_rows() {
-                let mut matched = false;
-                for _ in right_rows() {
-                    matched |= matches!(filter.get(i), Some(true));
-                    i += 1;
+        // whether each row of the build side has been matched
+        let mut right_matched = vec![false; right_rows.len()];
+        let mut builders = create_output_builders();
+        let mut num_rows = 0;
+
+        #[for_await]
+        for chunk in self.left_child {
+            let chunk = chunk?;
+            for left_row in chunk.rows() {
+                let mut left_matched = false;
+                // whether the condition is NULL for any right row
+                let mut left_unknown = false;
+                if !right_rows.is_empty() {
+                    // join the left row with all right rows
+                    let mut cross_builders = create_builders();
+                    for right_row in &right_rows {
+                        let values = left_row.values().chain(right_row.values());
+                        for (builder, v) in cross_builders.iter_mut().zip_eq(values) {
+                            builder.push(&v);
+                        }
+                    }
+                    let cross_chunk = cross_builders.into_iter().collect();
+
+                    // evaluate filter bitmap
+                    let filter = match self.condition.eval(&cross_chunk)? {
+                        ArrayImpl::Bool(a) => a,
+                        _ => panic!("unsupported value from join condition"),
+                    };
+                    for (i, right_row) in right_rows.iter().enumerate() {
+                        match filter.get(i) {
+                            Some(true) => {}
+                            Some(false) => continue,
+                            None => {
+                                left_unknown = true;
+                                continue;
+                            }
+                        }
+                        left_matched = true;
+                        if semi_or_anti {
+                            break;
+                        }
+                        right_matched[i] = true;
+                        let values = left_row.values().chain(right_row.values());
+                        for (builder, v) in builders.iter_mut().zip_eq(values) {
+                            builder.push(&v);
+                        }
+                        num_rows += 1;
+                    }
                 }
-                if matched {
-                    continue;
+                if semi_or_anti {
+                    let emit = match self.join_op {
+                        BoundJoinOperator::LeftSemi => left_matched,
+                        BoundJoinOperator::LeftAnti => !left_matched,
+                        // `x NOT IN (...)` is unknown if `x = y` is NULL for any y
+                        _ => !left_matched && !left_unknown,
+                    };
+                    if emit {
+                        for (builder, v) in builders.iter_mut().zip_eq(left_row.values()) {
+                            builder.push(&v);
+                        }
+                        num_rows += 1;
+                    }
+                } else if !left_matched && left_outer {
+                    // append row: (left, NULL)
+                    let values =
+                        (left_row.values()).chain(self.right_types.iter().map(|_| DataValue::Null));
+                    for (builder, v) in builders.iter_mut().zip_eq(values) {
+                        builder.push(&v);
+                    }
+                    num_rows += 1;
                 }
-                // append row: (left, NULL)
-                let values =
-                    (left_row.values()).chain(self.right_types.iter().map(|_| DataValue::Null));
-                for (builder, v) in builders.iter_mut().zip_eq(values) {
-                    builder.push(&v);
+                if num_rows >= PROCESSING_WINDOW_SIZE {
+                    yield std::mem::replace(&mut builders, create_output_builders())
+                        .into_iter()
+                        .collect();
+                    num_rows = 0;
                 }
             }
-            yield builders.into_iter().collect();
         }
 
         // append rows for right outer join
-        if matches!(
-            self.join_op,
-            BoundJoinOperator::RightOuter | BoundJoinOperator::FullOuter
-        ) {
-            let mut builders = create_builders();
-            let left_row_num = left_rows().count();
-            let right_row_num = right_rows().count();
-            for (mut i, right_row) in right_rows().enumerate() {
-                // skip if the right row matches any left rows
-                let mut matched = false;
-                for _ in 0..left_row_num {
-                    matched |= matches!(filter.get(i), Some(true));
-                    i += right_row_num;
-                }
-                if matched {
-                    continue;
-                }
+        if right_outer {
+            for (right_row, _) in right_rows.iter().zip(right_matched).filter(|(_, m)| !m) {
                 // append row: (NULL, right)
                 let values =
                     (self.left_types.iter().map(|_| DataValue::Null)).chain(right_row.values());
                 for (builder, v) in builders.iter_mut().zip_eq(values) {
                     builder.push(&v);
                 }
+                num_rows += 1;
             }
+        }
+
+        if num_rows != 0 {
             yield builders.into_iter().collect();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binder::{BoundBinaryOp, BoundInputRef};
+    use crate::parser::BinaryOperator;
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    fn chunk(keys: &[Option<i32>], values: &[i32]) -> DataChunk {
+        [
+            ArrayImpl::Int32(keys.iter().cloned().collect()),
+            ArrayImpl::Int32(values.iter().cloned().collect()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn row(values: [Option<i32>; 4]) -> Vec<DataValue> {
+        values
+            .iter()
+            .map(|v| v.map_or(DataValue::Null, DataValue::Int32))
+            .collect()
+    }
+
+    /// Join `(k1, v1)` with `(k2, v2)` on `k1 = k2`.
+    async fn nested_loop_join(join_op: BoundJoinOperator) -> Vec<Vec<DataValue>> {
+        let ty = DataTypeKind::Int(None).nullable();
+        let input_ref = |index| {
+            Box::new(BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: ty.clone(),
+            }))
+        };
+        let left = chunk(&[Some(1), Some(2), None], &[0, 1, 2]);
+        let right = chunk(&[Some(1), Some(1), Some(3), None], &[10, 11, 12, 13]);
+        let executor = NestedLoopJoinExecutor {
+            left_child: futures::stream::iter([Ok(left)]).boxed(),
+            right_child: futures::stream::iter([Ok(right)]).boxed(),
+            join_op,
+            condition: BoundExpr::BinaryOp(BoundBinaryOp {
+                op: BinaryOperator::Eq,
+                left_expr: input_ref(0),
+                right_expr: input_ref(2),
+                return_type: Some(DataTypeKind::Boolean.nullable()),
+            }),
+            left_types: vec![ty.clone(); 2],
+            right_types: vec![ty.clone(); 2],
+        };
+        let chunks = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let mut rows = (chunks.iter())
+            .flat_map(|c| c.rows())
+            .map(|row| row.values().collect_vec())
+            .collect_vec();
+        rows.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        rows
+    }
+
+    #[tokio::test]
+    async fn test_left_outer_join() {
+        let rows = nested_loop_join(BoundJoinOperator::LeftOuter).await;
+        assert_eq!(
+            rows,
+            vec![
+                row([None, Some(2), None, None]),
+                row([Some(1), Some(0), Some(1), Some(10)]),
+                row([Some(1), Some(0), Some(1), Some(11)]),
+                row([Some(2), Some(1), None, None]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_right_outer_join() {
+        let rows = nested_loop_join(BoundJoinOperator::RightOuter).await;
+        assert_eq!(
+            rows,
+            vec![
+                row([None, None, None, Some(13)]),
+                row([None, None, Some(3), Some(12)]),
+                row([Some(1), Some(0), Some(1), Some(10)]),
+                row([Some(1), Some(0), Some(1), Some(11)]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_outer_join() {
+        let rows = nested_loop_join(BoundJoinOperator::FullOuter).await;
+        assert_eq!(
+            rows,
+            vec![
+                row([None, None, None, Some(13)]),
+                row([None, None, Some(3), Some(12)]),
+                row([None, Some(2), None, None]),
+                row([Some(1), Some(0), Some(1), Some(10)]),
+                row([Some(1), Some(0), Some(1), Some(11)]),
+                row([Some(2), Some(1), None, None]),
+            ]
+        );
+    }
+}
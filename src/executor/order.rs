@@ -2,62 +2,386 @@
 
 use std::cmp::Ordering;
 
+use super::spill::{SpillFile, SpillWriter};
 use super::*;
-use crate::array::{ArrayBuilderImpl, DataChunk, RowRef};
-use crate::binder::{BoundExpr, BoundOrderBy};
+use crate::array::{ArrayBuilderImpl, ArrayImpl, DataChunk};
+use crate::binder::BoundOrderBy;
+use crate::types::DataValue;
 
 /// The executor of an order operation.
+///
+/// Rows are sorted by the tuple of sort keys lexicographically. Each key has its own direction and
+/// position of NULLs. The sort is stable, i.e. rows with equal keys are kept in the input order.
+///
+/// If the estimated size of the buffered chunks exceeds `memory_limit`, they are sorted into a run
+/// and spilled to a temporary file. The runs are merged after the input ends.
 pub struct OrderExecutor {
     pub child: BoxedExecutor,
     pub comparators: Vec<BoundOrderBy>,
+    /// The maximum size of chunks sorted in memory in bytes, which is unlimited if not set.
+    pub memory_limit: Option<usize>,
 }
 
 impl OrderExecutor {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
-        // collect all chunks
-        let mut chunks = vec![];
+        let (spilled, chunks) =
+            Self::sort_runs(self.child, &self.comparators, self.memory_limit).await?;
+        let last_run = match Self::sort_chunks(chunks, &self.comparators)? {
+            Some(chunk) if spilled.is_empty() => {
+                yield chunk;
+                return Ok(());
+            }
+            Some(chunk) => chunk.split(PROCESSING_WINDOW_SIZE).collect_vec(),
+            None => vec![],
+        };
+        let runs = (spilled.into_iter())
+            .map(SpillFile::read)
+            .chain([futures::stream::iter(last_run.into_iter().map(Ok)).boxed()])
+            .collect();
         #[for_await]
-        for batch in self.child {
-            chunks.push(batch?);
+        for chunk in merge_runs(runs, self.comparators) {
+            yield chunk?;
+        }
+    }
+
+    /// Buffer the chunks of the child, and spill them as a sorted run once they exceed the
+    /// memory limit. Return the spilled runs and the chunks left in memory.
+    async fn sort_runs(
+        mut child: BoxedExecutor,
+        comparators: &[BoundOrderBy],
+        memory_limit: Option<usize>,
+    ) -> Result<(Vec<SpillFile>, Vec<DataChunk>), ExecutorError> {
+        let memory_limit = memory_limit.unwrap_or(usize::MAX);
+        let mut spilled = vec![];
+        let mut chunks = vec![];
+        let mut size = 0;
+        while let Some(batch) = child.next().await {
+            let batch = batch?;
+            size += batch.estimated_size();
+            chunks.push(batch);
+            if size > memory_limit {
+                let run = Self::sort_chunks(std::mem::take(&mut chunks), comparators)?;
+                let mut writer = SpillWriter::new()?;
+                for chunk in run.iter().flat_map(|run| run.split(PROCESSING_WINDOW_SIZE)) {
+                    writer.write(&chunk).await?;
+                }
+                spilled.push(writer.finish().await?);
+                size = 0;
+            }
+        }
+        Ok((spilled, chunks))
+    }
+
+    /// Sort the rows of chunks into a chunk. Return `None` if there is no chunk.
+    fn sort_chunks(
+        chunks: Vec<DataChunk>,
+        comparators: &[BoundOrderBy],
+    ) -> Result<Option<DataChunk>, ExecutorError> {
+        if chunks.is_empty() {
+            return Ok(None);
         }
-        // sort the indexes
-        let mut indexes = gen_index_array(&chunks);
-        let comparators = self.comparators;
-        indexes.sort_unstable_by(|row1, row2| cmp(row1, row2, &comparators));
+        // evaluate the sort keys
+        let mut keys = vec![];
+        for batch in &chunks {
+            let key_arrays = (comparators.iter())
+                .map(|cmp| cmp.expr.eval(batch))
+                .collect::<Result<Vec<_>, _>>()?;
+            for i in 0..batch.cardinality() {
+                keys.push(key_arrays.iter().map(|a| a.get(i)).collect_vec());
+            }
+        }
+        // sort the rows, each of which is paired with the index of its keys
+        let mut rows = chunks
+            .iter()
+            .flat_map(|chunk| chunk.rows())
+            .enumerate()
+            .collect_vec();
+        rows.sort_by(|(i1, _), (i2, _)| cmp_keys(&keys[*i1], &keys[*i2], comparators));
         // build chunk by the new order
         let mut arrays = vec![];
         for col_idx in 0..chunks[0].column_count() {
             let mut builder = ArrayBuilderImpl::from_type_of_array(chunks[0].array_at(col_idx));
-            for row in &indexes {
+            for (_, row) in &rows {
                 builder.push(&row.get(col_idx));
             }
             arrays.push(builder.finish());
         }
-        let chunk: DataChunk = arrays.into_iter().collect();
-        yield chunk;
+        Ok(Some(arrays.into_iter().collect()))
+    }
+}
+
+/// The current chunk of a sorted run in merging.
+struct RunHead {
+    chunk: DataChunk,
+    key_arrays: Vec<ArrayImpl>,
+    /// The index of the next row in the chunk.
+    row_idx: usize,
+    /// The sort keys of the next row.
+    key: Vec<DataValue>,
+}
+
+impl RunHead {
+    /// Get the first chunk of the rest of a run.
+    async fn next(
+        run: &mut BoxedExecutor,
+        comparators: &[BoundOrderBy],
+    ) -> Result<Option<Self>, ExecutorError> {
+        while let Some(chunk) = run.next().await {
+            let chunk = chunk?;
+            if chunk.cardinality() == 0 {
+                continue;
+            }
+            let key_arrays = (comparators.iter())
+                .map(|cmp| cmp.expr.eval(&chunk))
+                .collect::<Result<Vec<_>, _>>()?;
+            let key = key_arrays.iter().map(|a| a.get(0)).collect();
+            return Ok(Some(RunHead {
+                chunk,
+                key_arrays,
+                row_idx: 0,
+                key,
+            }));
+        }
+        Ok(None)
     }
 }
 
-/// Compare two rows by the comparators.
-fn cmp(row1: &RowRef, row2: &RowRef, comparators: &[BoundOrderBy]) -> Ordering {
-    for cmp in comparators {
-        let column_index = match &cmp.expr {
-            BoundExpr::InputRef(input_ref) => input_ref.index,
-            _ => todo!("only support order by columns now"),
+/// Merge the sorted runs into sorted chunks. Rows with equal keys are produced in the order of
+/// runs, so that the sort is stable if the runs are in the input order.
+#[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+async fn merge_runs(mut runs: Vec<BoxedExecutor>, comparators: Vec<BoundOrderBy>) {
+    let mut heads = vec![];
+    for run in &mut runs {
+        heads.push(RunHead::next(run, &comparators).await?);
+    }
+    let mut builders: Option<Vec<ArrayBuilderImpl>> = None;
+    let mut num_rows = 0;
+    loop {
+        // find the run of the smallest row, which is the first one among equal rows
+        let mut min: Option<usize> = None;
+        for (i, head) in heads.iter().enumerate() {
+            if let Some(head) = head {
+                let is_less = |min: usize| {
+                    let min_key = &heads[min].as_ref().unwrap().key;
+                    cmp_keys(&head.key, min_key, &comparators) == Ordering::Less
+                };
+                if min.map_or(true, is_less) {
+                    min = Some(i);
+                }
+            }
+        }
+        let i = match min {
+            Some(i) => i,
+            None => break,
         };
-        let v1 = row1.get(column_index);
-        let v2 = row2.get(column_index);
-        match v1.partial_cmp(&v2).unwrap() {
+
+        let head = heads[i].as_mut().unwrap();
+        let output = builders.get_or_insert_with(|| {
+            (head.chunk.arrays().iter())
+                .map(ArrayBuilderImpl::from_type_of_array)
+                .collect()
+        });
+        for (array, builder) in head.chunk.arrays().iter().zip_eq(output.iter_mut()) {
+            builder.push(&array.get(head.row_idx));
+        }
+        head.row_idx += 1;
+        if head.row_idx < head.chunk.cardinality() {
+            head.key = (head.key_arrays.iter())
+                .map(|a| a.get(head.row_idx))
+                .collect();
+        } else {
+            heads[i] = RunHead::next(&mut runs[i], &comparators).await?;
+        }
+
+        num_rows += 1;
+        if num_rows == PROCESSING_WINDOW_SIZE {
+            yield builders.take().unwrap().into_iter().collect();
+            num_rows = 0;
+        }
+    }
+    if let Some(builders) = builders {
+        yield builders.into_iter().collect();
+    }
+}
+
+/// Compare two tuples of sort keys lexicographically by the comparators.
+pub(super) fn cmp_keys(
+    key1: &[DataValue],
+    key2: &[DataValue],
+    comparators: &[BoundOrderBy],
+) -> Ordering {
+    for ((v1, v2), cmp) in key1.iter().zip_eq(key2).zip_eq(comparators) {
+        match cmp_value(v1, v2, cmp) {
             Ordering::Equal => continue,
-            o if cmp.descending => return o.reverse(),
             o => return o,
         }
     }
     Ordering::Equal
 }
 
-/// Generate an array of indexes for each element of the chunks.
-fn gen_index_array(chunks: &[DataChunk]) -> Vec<RowRef<'_>> {
-    chunks.iter().flat_map(|chunk| chunk.rows()).collect()
+/// Compare two values of a sort key by its direction and the position of NULLs.
+fn cmp_value(v1: &DataValue, v2: &DataValue, order: &BoundOrderBy) -> Ordering {
+    let nulls_first = |o: Ordering| if order.nulls_first { o } else { o.reverse() };
+    match (v1, v2) {
+        (DataValue::Null, DataValue::Null) => Ordering::Equal,
+        (DataValue::Null, _) => nulls_first(Ordering::Less),
+        (_, DataValue::Null) => nulls_first(Ordering::Greater),
+        _ if order.descending => v2.partial_cmp(v1).unwrap(),
+        _ => v1.partial_cmp(v2).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::binder::{BoundExpr, BoundInputRef};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    fn order_by(index: usize, descending: bool, nulls_first: bool) -> BoundOrderBy {
+        BoundOrderBy {
+            expr: BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: DataTypeKind::Int(None).nullable(),
+            }),
+            descending,
+            nulls_first,
+        }
+    }
+
+    async fn sort(
+        rows: &[[Option<i32>; 2]],
+        comparators: Vec<BoundOrderBy>,
+    ) -> Vec<[Option<i32>; 2]> {
+        let chunks = rows
+            .chunks(2)
+            .map(|rows| {
+                (0..2)
+                    .map(|i| ArrayImpl::Int32(rows.iter().map(|r| r[i]).collect()))
+                    .collect::<DataChunk>()
+            })
+            .collect_vec();
+        let executor = OrderExecutor {
+            child: futures::stream::iter(chunks.into_iter().map(Ok)).boxed(),
+            comparators,
+            memory_limit: None,
+        };
+        let chunks = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let get = |v: DataValue| match v {
+            DataValue::Int32(v) => Some(v),
+            DataValue::Null => None,
+            v => panic!("unexpected value: {:?}", v),
+        };
+        (chunks.iter())
+            .flat_map(|c| c.rows())
+            .map(|row| [get(row.get(0)), get(row.get(1))])
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sort_multiple_keys() {
+        let rows = [
+            [Some(2), Some(1)],
+            [Some(1), None],
+            [None, Some(3)],
+            [Some(1), Some(2)],
+            [Some(2), Some(4)],
+            [None, None],
+            [Some(1), Some(5)],
+        ];
+
+        // order by a asc, b desc nulls last
+        let sorted = sort(
+            &rows,
+            vec![order_by(0, false, true), order_by(1, true, false)],
+        )
+        .await;
+        assert_eq!(
+            sorted,
+            [
+                [None, Some(3)],
+                [None, None],
+                [Some(1), Some(5)],
+                [Some(1), Some(2)],
+                [Some(1), None],
+                [Some(2), Some(4)],
+                [Some(2), Some(1)],
+            ]
+        );
+
+        // order by a desc nulls first, b asc nulls first
+        let sorted = sort(
+            &rows,
+            vec![order_by(0, true, true), order_by(1, false, true)],
+        )
+        .await;
+        assert_eq!(
+            sorted,
+            [
+                [None, None],
+                [None, Some(3)],
+                [Some(2), Some(1)],
+                [Some(2), Some(4)],
+                [Some(1), None],
+                [Some(1), Some(2)],
+                [Some(1), Some(5)],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_external_sort() {
+        // sort by a desc nulls last, and keep the input order of b for equal a
+        let comparators = vec![order_by(0, true, false)];
+        let chunks = (0..20)
+            .map(|i| {
+                let a = (0..100).map(|v| ((v * 37 + i * 11) % 50 != 0).then(|| (v * 37 + i) % 23));
+                let b = (i * 100..i * 100 + 100).map(Some);
+                [ArrayImpl::Int32(a.collect()), ArrayImpl::Int32(b.collect())]
+                    .into_iter()
+                    .collect::<DataChunk>()
+            })
+            .collect_vec();
+        let child = || futures::stream::iter(chunks.clone().into_iter().map(Ok)).boxed();
+        let rows = |chunks: Vec<DataChunk>| {
+            (chunks.iter())
+                .flat_map(|c| c.rows().map(|row| row.values().collect_vec()))
+                .collect_vec()
+        };
+        let expected = OrderExecutor {
+            child: child(),
+            comparators: comparators.clone(),
+            memory_limit: None,
+        };
+        let expected = rows(expected.execute().try_collect().await.unwrap());
+        assert_eq!(expected.len(), 2000);
+
+        // the chunks are spilled in multiple runs
+        let memory_limit = chunks[0].estimated_size() * 3;
+        let (spilled, _) = OrderExecutor::sort_runs(child(), &comparators, Some(memory_limit))
+            .await
+            .unwrap();
+        assert!(spilled.len() > 1);
+
+        for memory_limit in [0, memory_limit] {
+            let executor = OrderExecutor {
+                child: child(),
+                comparators: comparators.clone(),
+                memory_limit: Some(memory_limit),
+            };
+            let outputs: Vec<DataChunk> = executor.execute().try_collect().await.unwrap();
+            assert!(outputs
+                .iter()
+                .all(|c| c.cardinality() <= PROCESSING_WINDOW_SIZE));
+            assert_eq!(rows(outputs), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sort_empty_input() {
+        assert!(sort(&[], vec![order_by(0, false, true)]).await.is_empty());
+    }
 }
@@ -0,0 +1,188 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use super::*;
+use crate::array::DataChunk;
+use crate::binder::BoundExpr;
+use crate::optimizer::plan_nodes::PhysicalTableScan;
+use crate::storage::{
+    ExplicitTransaction, ScanStats, SecondaryStorage, Storage, StorageColumnRef, StorageResult,
+    TxnHandle, TxnIterator,
+};
+
+/// The executor of table scan on secondary storage, which splits the rowsets of the table into
+/// disjoint partitions and scans them in parallel tasks.
+///
+/// If `ordered` is set, chunks are produced in the same order as [`TableScanExecutor`]. Otherwise
+/// chunks are produced as soon as any task scans them.
+pub struct ParallelTableScanExecutor {
+    pub plan: PhysicalTableScan,
+    pub expr: Option<BoundExpr>,
+    pub storage: Arc<SecondaryStorage>,
+    pub stats: Arc<QueryStats>,
+    /// The transaction started by `BEGIN`, if any.
+    pub txn: Option<Arc<ExplicitTransaction<SecondaryStorage>>>,
+    /// The maximum number of tasks.
+    pub parallelism: usize,
+    pub ordered: bool,
+}
+
+impl ParallelTableScanExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let table = self.storage.get_table(self.plan.logical().table_ref_id())?;
+
+        // TODO: remove this when we have schema
+        let empty_chunk =
+            TableScanExecutor::<SecondaryStorage>::build_empty_chunk(&self.plan, &table)?;
+        let mut have_chunk = false;
+
+        let mut col_idx = self
+            .plan
+            .logical()
+            .column_ids()
+            .iter()
+            .map(|x| StorageColumnRef::Idx(*x))
+            .collect_vec();
+
+        // Add an extra column for RowHandler at the end
+        if self.plan.logical().with_row_handler() {
+            col_idx.push(StorageColumnRef::RowHandler);
+        }
+
+        let txn = TxnHandle::read(&table, self.txn.as_deref()).await?;
+        let iters = match &txn {
+            TxnHandle::Owned(txn) => {
+                txn.scan_partitions(&col_idx, self.expr, self.parallelism)
+                    .await?
+            }
+            TxnHandle::Shared(txn) => {
+                let txn = txn.lock().await;
+                let txn = txn.as_ref().expect("transaction is finished");
+                txn.scan_partitions(&col_idx, self.expr, self.parallelism)
+                    .await?
+            }
+        };
+
+        // Each task buffers at most 16 chunks in its channel when ordered, and all tasks share a
+        // channel of 128 chunks otherwise.
+        let (senders, mut receivers): (Vec<_>, Vec<_>) = if self.ordered {
+            (0..iters.len()).map(|_| mpsc::channel(16)).unzip()
+        } else {
+            let (tx, rx) = mpsc::channel(128);
+            (vec![tx; iters.len()], vec![rx])
+        };
+        let handlers = iters
+            .into_iter()
+            .zip(senders)
+            .map(|(it, tx)| tokio::spawn(Self::scan_partition(it, tx)))
+            .collect_vec();
+
+        let mut result = Ok(());
+        'outer: for rx in &mut receivers {
+            while let Some(item) = rx.recv().await {
+                match item {
+                    Ok(chunk) => {
+                        yield chunk;
+                        have_chunk = true;
+                    }
+                    Err(err) => {
+                        result = Err(err);
+                        break 'outer;
+                    }
+                }
+            }
+        }
+
+        // stop the remaining tasks, and wait for them before releasing the snapshot
+        drop(receivers);
+        for handler in handlers {
+            self.stats
+                .record_scan(handler.await.expect("failed to join scan thread"));
+        }
+        txn.abort().await?;
+        result?;
+
+        if !have_chunk {
+            yield empty_chunk;
+        }
+    }
+
+    /// Scan a partition into the channel until it ends or the receiver is dropped.
+    async fn scan_partition(
+        mut it: impl TxnIterator,
+        tx: mpsc::Sender<StorageResult<DataChunk>>,
+    ) -> ScanStats {
+        while let Some(result) = it.next_batch(None).await.transpose() {
+            let is_err = result.is_err();
+            if tx.send(result).await.is_err() || is_err {
+                break;
+            }
+        }
+        it.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::array::DataChunk;
+    use crate::storage::SecondaryStorageOptions;
+    use crate::Database;
+
+    fn column_of(chunks: &[DataChunk], idx: usize) -> Vec<i64> {
+        chunks
+            .iter()
+            .flat_map(|chunk| {
+                (0..chunk.cardinality())
+                    .map(|row| chunk.array_at(idx).get_to_string(row).parse().unwrap())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_parallel_scan_output() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let db = Database::new_on_disk(SecondaryStorageOptions {
+            scan_parallelism: 3,
+            ..SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf())
+        })
+        .await;
+        db.run("create table t(v int)").await.unwrap();
+        // each insert writes a rowset
+        for i in 0..8 {
+            let values = (i * 100..i * 100 + 100)
+                .map(|v| format!("({})", v))
+                .join(", ");
+            db.run(&format!("insert into t values {}", values))
+                .await
+                .unwrap();
+        }
+        db.run("delete from t where v % 10 = 0").await.unwrap();
+        let expected = (0..800).filter(|v| v % 10 != 0).collect_vec();
+
+        // rows are scanned in order
+        let chunks = db.run("select v from t").await.unwrap();
+        assert_eq!(column_of(&chunks, 0), expected);
+
+        // every row is scanned exactly once in any order
+        let chunks = db
+            .run("select v, count(*) from t group by v")
+            .await
+            .unwrap();
+        let groups = column_of(&chunks, 0)
+            .into_iter()
+            .zip(column_of(&chunks, 1))
+            .sorted()
+            .collect_vec();
+        assert_eq!(groups, expected.iter().map(|&v| (v, 1)).collect_vec());
+
+        let chunks = db.run("select sum(v) from t").await.unwrap();
+        assert_eq!(column_of(&chunks, 0), vec![expected.iter().sum::<i64>()]);
+        db.shutdown().await.unwrap();
+    }
+}
@@ -5,6 +5,9 @@ use crate::array::DataChunk;
 use crate::binder::BoundExpr;
 
 /// The executor of project operation.
+///
+/// Each expression is evaluated against the input chunks, producing an output column whose type
+/// is the return type of the bound expression.
 pub struct ProjectionExecutor {
     pub project_expressions: Vec<BoundExpr>,
     pub child: BoxedExecutor,
@@ -25,3 +28,59 @@ impl ProjectionExecutor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::binder::{BoundBinaryOp, BoundInputRef, BoundScalarFunction, ScalarFunctionKind};
+    use crate::parser::BinaryOperator;
+    use crate::types::{DataTypeExt, DataTypeKind, DataValue};
+
+    #[tokio::test]
+    async fn project_expressions() {
+        let int = || DataTypeKind::Int(None).not_null();
+        let string = || DataTypeKind::String.not_null();
+        let input_ref =
+            |index, return_type| BoundExpr::InputRef(BoundInputRef { index, return_type });
+        let chunk: DataChunk = [
+            ArrayImpl::Int32([1, 2, 3].into_iter().collect()),
+            ArrayImpl::Int32([10, 20, 30].into_iter().collect()),
+            ArrayImpl::Utf8([Some("a"), Some("bc"), None].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        let executor = ProjectionExecutor {
+            project_expressions: vec![
+                // a + b
+                BoundExpr::BinaryOp(BoundBinaryOp {
+                    op: BinaryOperator::Plus,
+                    left_expr: Box::new(input_ref(0, int())),
+                    right_expr: Box::new(input_ref(1, int())),
+                    return_type: Some(int()),
+                }),
+                // upper(name)
+                BoundExpr::ScalarFunction(BoundScalarFunction {
+                    kind: ScalarFunctionKind::Upper,
+                    args: vec![input_ref(2, string())],
+                    return_type: string(),
+                }),
+                // 'x'
+                BoundExpr::Constant(DataValue::String("x".into())),
+            ],
+            child: futures::stream::iter([Ok(chunk)]).boxed(),
+        };
+        let outputs = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(outputs.len(), 1);
+        let expected: DataChunk = [
+            ArrayImpl::Int32([11, 22, 33].into_iter().collect()),
+            ArrayImpl::Utf8([Some("A"), Some("BC"), None].into_iter().collect()),
+            ArrayImpl::Utf8([Some("x"); 3].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        assert!(outputs[0] == expected);
+    }
+}
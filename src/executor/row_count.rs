@@ -0,0 +1,116 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use super::*;
+use crate::binder::AggKind;
+use crate::catalog::TableRefId;
+use crate::storage::{ExplicitTransaction, SecondaryStorage, Storage, Transaction, TxnHandle};
+
+/// The executor of an unfiltered `count(*)`, which sums up the row counts in rowset metadata
+/// minus the deleted rows, instead of scanning column data.
+pub struct RowCountExecutor {
+    pub table_ref_id: TableRefId,
+    pub storage: Arc<SecondaryStorage>,
+    /// The transaction started by `BEGIN`, if any.
+    pub txn: Option<Arc<ExplicitTransaction<SecondaryStorage>>>,
+}
+
+impl RowCountExecutor {
+    /// Returns the table to count if `plan` is a single `count(*)` directly over a table scan
+    /// without predicate, row handler, offset or limit.
+    pub fn table_of(plan: &PhysicalSimpleAgg) -> Option<TableRefId> {
+        match plan.agg_calls() {
+            [agg] if agg.kind == AggKind::RowCount => {}
+            _ => return None,
+        }
+        let child = plan.child();
+        let scan = child.as_physical_table_scan().ok()?.logical();
+        if scan.predicate().is_some()
+            || scan.with_row_handler()
+            || scan.offset() != 0
+            || scan.limit().is_some()
+        {
+            return None;
+        }
+        Some(scan.table_ref_id())
+    }
+
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let table = self.storage.get_table(self.table_ref_id)?;
+        let count = match TxnHandle::read(&table, self.txn.as_deref()).await? {
+            TxnHandle::Owned(txn) => {
+                let count = txn.row_count().await?;
+                txn.abort().await?;
+                count
+            }
+            TxnHandle::Shared(txn) => {
+                let txn = txn.lock().await;
+                txn.as_ref()
+                    .expect("transaction is finished")
+                    .row_count()
+                    .await?
+            }
+        };
+        yield DataChunk::single(count as i32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::array::DataChunk;
+    use crate::storage::SecondaryStorageOptions;
+    use crate::Database;
+
+    fn single_count(chunks: &[DataChunk]) -> String {
+        chunks[0].array_at(0).get_to_string(0)
+    }
+
+    #[tokio::test]
+    async fn test_count_without_reading_columns() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let options = || SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf());
+        let db = Database::new_on_disk(options()).await;
+        db.run("create table t(v int, w int)").await.unwrap();
+        let values = (0..1000).map(|v| format!("({}, {})", v, v)).join(", ");
+        db.run(&format!("insert into t values {}", values))
+            .await
+            .unwrap();
+        db.run("delete from t where v < 100").await.unwrap();
+
+        // a filtered count falls back to scanning
+        let (chunks, stats) = db
+            .run_with_stats("select count(*) from t where w >= 500")
+            .await
+            .unwrap();
+        assert_eq!(single_count(&chunks), "500");
+        assert!(stats
+            .rows_produced()
+            .iter()
+            .any(|(name, _)| name == "PhysicalTableScan"));
+        db.shutdown().await.unwrap();
+
+        // clear all column data, only block indexes are left
+        for rowset in std::fs::read_dir(tempdir.path()).unwrap() {
+            let rowset = rowset.unwrap().path();
+            if !rowset.is_dir() {
+                continue;
+            }
+            for file in std::fs::read_dir(rowset).unwrap() {
+                let file = file.unwrap().path();
+                if file.extension().map_or(false, |ext| ext == "col") {
+                    std::fs::File::create(file).unwrap();
+                }
+            }
+        }
+
+        let db = Database::new_on_disk(options()).await;
+        let (chunks, stats) = db.run_with_stats("select count(*) from t").await.unwrap();
+        assert_eq!(single_count(&chunks), "900");
+        assert_eq!(stats.blocks_read(), 0);
+        db.shutdown().await.unwrap();
+    }
+}
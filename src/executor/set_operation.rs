@@ -0,0 +1,166 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::{HashMap, HashSet};
+
+use super::hash_agg::serialize_datavalue;
+use super::*;
+use crate::array::{DataChunk, RowRef};
+use crate::binder::BoundSetOperator;
+
+/// The executor of set operations.
+///
+/// Rows are compared by their serialized values, so NULLs are considered equal to each other.
+/// `UNION ALL` streams both children. For other operators, the right child is consumed into a
+/// hash table first, then the left child is streamed.
+pub struct SetOperationExecutor {
+    pub left_child: BoxedExecutor,
+    pub right_child: BoxedExecutor,
+    pub op: BoundSetOperator,
+    pub all: bool,
+}
+
+impl SetOperationExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        if self.op == BoundSetOperator::Union {
+            let mut seen = HashSet::new();
+            #[for_await]
+            for chunk in self.left_child.chain(self.right_child) {
+                let chunk = chunk?;
+                if self.all {
+                    yield chunk;
+                    continue;
+                }
+                let visibility = chunk
+                    .rows()
+                    .map(|row| seen.insert(row_key(&row)))
+                    .collect_vec();
+                if let Some(chunk) = filter(&chunk, visibility) {
+                    yield chunk;
+                }
+            }
+            return Ok(());
+        }
+
+        // the number of occurrences of each row on the right side
+        let mut counts: HashMap<Vec<u8>, usize> = HashMap::new();
+        #[for_await]
+        for chunk in self.right_child {
+            let chunk = chunk?;
+            for row in chunk.rows() {
+                *counts.entry(row_key(&row)).or_default() += 1;
+            }
+        }
+
+        #[for_await]
+        for chunk in self.left_child {
+            let chunk = chunk?;
+            let visibility = (chunk.rows())
+                .map(|row| {
+                    let key = row_key(&row);
+                    match (self.op, self.all) {
+                        // keep `min(m, n)` duplicates
+                        (BoundSetOperator::Intersect, true) => match counts.get_mut(&key) {
+                            Some(count) if *count > 0 => {
+                                *count -= 1;
+                                true
+                            }
+                            _ => false,
+                        },
+                        (BoundSetOperator::Intersect, false) => counts.remove(&key).is_some(),
+                        // keep `max(m - n, 0)` duplicates
+                        (BoundSetOperator::Except, true) => match counts.get_mut(&key) {
+                            Some(count) if *count > 0 => {
+                                *count -= 1;
+                                false
+                            }
+                            _ => true,
+                        },
+                        // the key is inserted so that duplicates on the left side are removed
+                        (BoundSetOperator::Except, false) => counts.insert(key, 0).is_none(),
+                        (BoundSetOperator::Union, _) => unreachable!(),
+                    }
+                })
+                .collect_vec();
+            if let Some(chunk) = filter(&chunk, visibility) {
+                yield chunk;
+            }
+        }
+    }
+}
+
+/// Serialize all values of a row.
+pub(super) fn row_key(row: &RowRef<'_>) -> Vec<u8> {
+    let mut key = vec![];
+    for value in row.values() {
+        serialize_datavalue(&value, &mut key);
+    }
+    key
+}
+
+/// Filter the chunk by visibility. Returns `None` if no row is visible.
+pub(super) fn filter(chunk: &DataChunk, visibility: Vec<bool>) -> Option<DataChunk> {
+    if !visibility.contains(&true) {
+        return None;
+    }
+    Some(chunk.filter(visibility.into_iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::binder::BoundSetOperator::*;
+    use crate::types::DataValue;
+
+    const N: Option<i32> = None;
+
+    async fn set_operation(
+        op: BoundSetOperator,
+        all: bool,
+        left: &[Option<i32>],
+        right: &[Option<i32>],
+    ) -> Vec<Option<i32>> {
+        let chunk = |values: &[Option<i32>]| -> DataChunk {
+            [ArrayImpl::Int32(values.iter().cloned().collect())]
+                .into_iter()
+                .collect()
+        };
+        // split the input into chunks of one or two rows
+        let chunks = |values: &[Option<i32>]| values.chunks(2).map(|c| Ok(chunk(c))).collect_vec();
+        let executor = SetOperationExecutor {
+            left_child: futures::stream::iter(chunks(left)).boxed(),
+            right_child: futures::stream::iter(chunks(right)).boxed(),
+            op,
+            all,
+        };
+        let chunks = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let mut rows = (chunks.iter())
+            .flat_map(|c| c.rows())
+            .map(|row| match row.get(0) {
+                DataValue::Int32(v) => Some(v),
+                DataValue::Null => None,
+                v => panic!("unexpected value: {:?}", v),
+            })
+            .collect_vec();
+        rows.sort_unstable();
+        rows
+    }
+
+    #[test_case(Union, true, &[N, N, N, Some(1), Some(1), Some(1), Some(1), Some(1), Some(2), Some(3), Some(3), Some(4)])]
+    #[test_case(Union, false, &[N, Some(1), Some(2), Some(3), Some(4)])]
+    #[test_case(Intersect, true, &[N, Some(1), Some(1)])]
+    #[test_case(Intersect, false, &[N, Some(1)])]
+    #[test_case(Except, true, &[N, Some(1), Some(2), Some(4)])]
+    #[test_case(Except, false, &[Some(2), Some(4)])]
+    #[tokio::test]
+    async fn set_operations(op: BoundSetOperator, all: bool, expected: &[Option<i32>]) {
+        let left = [Some(1), Some(1), Some(1), Some(2), N, N, Some(4)];
+        let right = [Some(1), Some(1), N, Some(3), Some(3)];
+        let rows = set_operation(op, all, &left, &right).await;
+        assert_eq!(rows, expected);
+    }
+}
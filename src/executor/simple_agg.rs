@@ -5,8 +5,8 @@ use smallvec::SmallVec;
 
 use super::*;
 use crate::array::{ArrayBuilderImpl, ArrayImpl};
-use crate::binder::{AggKind, BoundAggCall};
-use crate::types::{DataTypeExt, DataTypeKind, DataValue};
+use crate::binder::{AggKind, BoundAggCall, BoundExpr};
+use crate::types::DataValue;
 
 /// The executor of simple aggregation.
 pub struct SimpleAggExecutor {
@@ -33,19 +33,17 @@ impl SimpleAggExecutor {
         Ok(())
     }
 
-    fn finish_agg(states: SmallVec<[Box<dyn AggregationState>; 16]>) -> DataChunk {
+    fn finish_agg(
+        states: SmallVec<[Box<dyn AggregationState>; 16]>,
+        agg_calls: &[BoundAggCall],
+    ) -> DataChunk {
         states
             .iter()
-            .map(|s| {
-                let result = &s.output();
-                match &result.data_type() {
-                    Some(r) => {
-                        let mut builder = ArrayBuilderImpl::with_capacity(1, r);
-                        builder.push(result);
-                        builder.finish()
-                    }
-                    None => ArrayBuilderImpl::new(&DataTypeKind::Int(None).nullable()).finish(),
-                }
+            .zip_eq(agg_calls)
+            .map(|(s, agg)| {
+                let mut builder = ArrayBuilderImpl::with_capacity(1, &agg.return_type);
+                builder.push(&s.output());
+                builder.finish()
             })
             .collect::<DataChunk>()
     }
@@ -60,7 +58,7 @@ impl SimpleAggExecutor {
             Self::execute_inner(&mut states, chunk, &self.agg_calls)?;
         }
 
-        let chunk = Self::finish_agg(states);
+        let chunk = Self::finish_agg(states, &self.agg_calls);
         yield chunk;
     }
 }
@@ -72,7 +70,7 @@ pub(super) fn create_agg_states(
 }
 
 fn create_agg_state(agg_call: &BoundAggCall) -> Box<dyn AggregationState> {
-    match agg_call.kind {
+    let state: Box<dyn AggregationState> = match agg_call.kind {
         AggKind::RowCount => Box::new(RowCountAggregationState::new(DataValue::Int32(0))),
         AggKind::Count => Box::new(CountAggregationState::new(DataValue::Int32(0))),
         AggKind::Max => Box::new(MinMaxAggregationState::new(
@@ -84,6 +82,26 @@ fn create_agg_state(agg_call: &BoundAggCall) -> Box<dyn AggregationState> {
             true,
         )),
         AggKind::Sum => Box::new(SumAggregationState::new(agg_call.return_type.kind())),
+        AggKind::StddevPop => Box::new(VarianceAggregationState::new(false, true)),
+        AggKind::StddevSamp => Box::new(VarianceAggregationState::new(true, true)),
+        AggKind::VarPop => Box::new(VarianceAggregationState::new(false, false)),
+        AggKind::VarSamp => Box::new(VarianceAggregationState::new(true, false)),
+        AggKind::BoolAnd => Box::new(BoolAndOrAggregationState::new(true)),
+        AggKind::BoolOr => Box::new(BoolAndOrAggregationState::new(false)),
+        AggKind::First => Box::new(FirstLastAggregationState::new(true)),
+        AggKind::Last => Box::new(FirstLastAggregationState::new(false)),
+        AggKind::StringAgg => {
+            let separator = match agg_call.args.get(1) {
+                Some(BoundExpr::Constant(DataValue::String(s))) => s.clone(),
+                _ => String::new(),
+            };
+            Box::new(StringAggAggregationState::new(separator))
+        }
         _ => panic!("Unsupported aggregate kind"),
+    };
+    if agg_call.distinct {
+        Box::new(DistinctAggregationState::new(state))
+    } else {
+        state
     }
 }
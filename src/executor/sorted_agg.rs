@@ -0,0 +1,219 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use smallvec::SmallVec;
+
+use super::hash_agg::{serialize_datavalue, HashKey, HashValue};
+use super::*;
+use crate::array::{ArrayBuilderImpl, ArrayImpl};
+use crate::binder::{BoundAggCall, BoundExpr};
+
+/// The executor of aggregation on the input sorted by group keys, in which the rows of a group
+/// are adjacent.
+///
+/// Only the states of the current group are kept. The result of a group is produced once a row
+/// with different group keys arrives, so the groups are produced in the order of input.
+pub struct SortedAggExecutor {
+    pub agg_calls: Vec<BoundAggCall>,
+    pub group_keys: Vec<BoundExpr>,
+    pub child: BoxedExecutor,
+}
+
+impl SortedAggExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let new_builders = || {
+            (self.group_keys.iter())
+                .map(|e| e.return_type().unwrap())
+                .chain(self.agg_calls.iter().map(|agg| agg.return_type.clone()))
+                .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, &ty))
+                .collect_vec()
+        };
+        let mut builders = new_builders();
+        let mut num_groups = 0;
+        // the serialized keys, the keys and the states of the current group
+        let mut current: Option<(Vec<u8>, HashKey, HashValue)> = None;
+        let mut key_bytes = vec![];
+
+        #[for_await]
+        for chunk in self.child {
+            let chunk = chunk?;
+            let group_cols: SmallVec<[ArrayImpl; 16]> = self
+                .group_keys
+                .iter()
+                .map(|e| e.eval(&chunk))
+                .try_collect()?;
+            let arrays: SmallVec<[ArrayImpl; 16]> = (self.agg_calls.iter())
+                .map(|agg| agg.args[0].eval(&chunk))
+                .try_collect()?;
+
+            for row_idx in 0..chunk.cardinality() {
+                key_bytes.clear();
+                for col in group_cols.iter() {
+                    serialize_datavalue(&col.get(row_idx), &mut key_bytes);
+                }
+                if !matches!(&current, Some((bytes, _, _)) if *bytes == key_bytes) {
+                    // the current group ends
+                    if let Some((_, key, states)) = current.take() {
+                        push_group(&mut builders, &key, &states);
+                        num_groups += 1;
+                        if num_groups == PROCESSING_WINDOW_SIZE {
+                            yield std::mem::replace(&mut builders, new_builders())
+                                .into_iter()
+                                .collect();
+                            num_groups = 0;
+                        }
+                    }
+                    let key = group_cols.iter().map(|col| col.get(row_idx)).collect();
+                    current = Some((key_bytes.clone(), key, create_agg_states(&self.agg_calls)));
+                }
+                let (_, _, states) = current.as_mut().unwrap();
+                for (array, state) in arrays.iter().zip_eq(states.iter_mut()) {
+                    // TODO: support aggregations with multiple arguments
+                    state.update_single(&array.get(row_idx))?;
+                }
+            }
+        }
+
+        if let Some((_, key, states)) = current {
+            push_group(&mut builders, &key, &states);
+            num_groups += 1;
+        }
+        if num_groups > 0 {
+            yield builders.into_iter().collect();
+        }
+    }
+}
+
+/// Push the keys and the result of aggregations of a group.
+fn push_group(builders: &mut [ArrayBuilderImpl], key: &HashKey, states: &HashValue) {
+    let values = (key.iter().cloned()).chain(states.iter().map(|state| state.output()));
+    for (value, builder) in values.zip_eq(builders.iter_mut()) {
+        builder.push(&value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::binder::{AggKind, BoundInputRef};
+    use crate::types::{DataTypeExt, DataTypeKind, DataValue};
+
+    fn input_ref(index: usize) -> BoundExpr {
+        BoundExpr::InputRef(BoundInputRef {
+            index,
+            return_type: DataTypeKind::Int(None).nullable(),
+        })
+    }
+
+    fn agg_calls() -> Vec<BoundAggCall> {
+        [AggKind::Sum, AggKind::Count, AggKind::Min]
+            .into_iter()
+            .map(|kind| BoundAggCall {
+                kind,
+                args: vec![input_ref(2)],
+                return_type: DataTypeKind::Int(None).nullable(),
+                distinct: false,
+            })
+            .collect()
+    }
+
+    fn collect_rows(chunks: &[DataChunk]) -> Vec<Vec<DataValue>> {
+        (chunks.iter())
+            .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sorted_agg_matches_hash_agg() {
+        // rows sorted by (v1, v2), and the groups span over chunks
+        let rows = (0..1000)
+            .map(|i| {
+                let v1 = if i < 100 { None } else { Some(i / 300) };
+                (v1, Some(i / 50), Some(i))
+            })
+            .collect_vec();
+        let chunks = rows
+            .chunks(64)
+            .map(|rows| {
+                let column = |f: fn(&(Option<i32>, Option<i32>, Option<i32>)) -> Option<i32>| {
+                    ArrayImpl::Int32(rows.iter().map(f).collect())
+                };
+                [column(|r| r.0), column(|r| r.1), column(|r| r.2)]
+                    .into_iter()
+                    .collect::<DataChunk>()
+            })
+            .collect_vec();
+        let child = || futures::stream::iter(chunks.clone().into_iter().map(Ok)).boxed();
+
+        let executor = SortedAggExecutor {
+            agg_calls: agg_calls(),
+            group_keys: vec![input_ref(0), input_ref(1)],
+            child: child(),
+        };
+        let outputs = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let rows = collect_rows(&outputs);
+
+        let executor = HashAggExecutor {
+            agg_calls: agg_calls(),
+            group_keys: vec![input_ref(0), input_ref(1)],
+            order: GroupOrder::Sorted { nulls_first: true },
+            memory_limit: None,
+            child: child(),
+        };
+        let outputs = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        let expected = collect_rows(&outputs);
+        // the groups are produced in the order of input
+        assert_eq!(rows, expected);
+
+        // no group is produced without input
+        let executor = SortedAggExecutor {
+            agg_calls: agg_calls(),
+            group_keys: vec![input_ref(0)],
+            child: futures::stream::empty().boxed(),
+        };
+        let outputs = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        assert!(outputs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sorted_agg_streams_groups() {
+        // each chunk contains 100 groups of 2 rows
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let counter = pulled.clone();
+        let child = futures::stream::iter(0..1000)
+            .map(move |i: i32| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                let keys = (i * 100..i * 100 + 100).flat_map(|k| [k, k]);
+                let chunk: DataChunk = [
+                    ArrayImpl::Int32(keys.map(Some).collect()),
+                    ArrayImpl::Int32((0..200).map(|_| None::<i32>).collect()),
+                    ArrayImpl::Int32((0..200).map(Some).collect()),
+                ]
+                .into_iter()
+                .collect();
+                Ok(chunk)
+            })
+            .boxed();
+        let mut executor = SortedAggExecutor {
+            agg_calls: agg_calls(),
+            group_keys: vec![input_ref(0)],
+            child,
+        }
+        .execute();
+
+        let mut num_groups = 0;
+        while let Some(chunk) = executor.try_next().await.unwrap() {
+            // the results are produced once enough groups end, without reading the whole input
+            let max_pulled = (num_groups + chunk.cardinality()) / 100 + 1;
+            assert!(pulled.load(Ordering::Relaxed) <= max_pulled);
+            assert!(chunk.cardinality() <= PROCESSING_WINDOW_SIZE);
+            num_groups += chunk.cardinality();
+        }
+        assert_eq!(num_groups, 100_000);
+    }
+}
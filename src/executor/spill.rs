@@ -0,0 +1,152 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::io::SeekFrom;
+
+use bytes::{Buf, BufMut};
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader, BufWriter};
+
+use super::*;
+use crate::array::{ArrayBuilderImpl, DataChunk};
+use crate::types::{DataType, DataValue};
+
+/// The writer of chunks spilled from memory to a temporary file.
+///
+/// All chunks written to a file should have the same column types.
+pub struct SpillWriter {
+    writer: BufWriter<File>,
+    types: Vec<DataType>,
+    buf: Vec<u8>,
+}
+
+/// A temporary file of spilled chunks, which is removed once dropped.
+pub struct SpillFile {
+    file: File,
+    types: Vec<DataType>,
+}
+
+impl SpillWriter {
+    /// Create a temporary file to spill chunks.
+    pub fn new() -> Result<Self, ExecutorError> {
+        let file = File::from_std(tempfile::tempfile()?);
+        Ok(SpillWriter {
+            writer: BufWriter::new(file),
+            types: vec![],
+            buf: vec![],
+        })
+    }
+
+    /// Append a chunk to the file.
+    ///
+    /// Each chunk is written as a block of the number of rows and the values of each column,
+    /// prefixed with the length of the block.
+    pub async fn write(&mut self, chunk: &DataChunk) -> Result<(), ExecutorError> {
+        if self.types.is_empty() {
+            self.types = chunk.arrays().iter().map(|a| a.data_type()).collect();
+        }
+        self.buf.clear();
+        self.buf.put_u32_le(chunk.cardinality() as u32);
+        for array in chunk.arrays() {
+            for i in 0..array.len() {
+                array.get(i).encode(&mut self.buf);
+            }
+        }
+        self.writer.write_u32_le(self.buf.len() as u32).await?;
+        self.writer.write_all(&self.buf).await?;
+        Ok(())
+    }
+
+    /// Finish writing, and return the file to read the chunks back.
+    pub async fn finish(mut self) -> Result<SpillFile, ExecutorError> {
+        self.writer.flush().await?;
+        let mut file = self.writer.into_inner();
+        file.seek(SeekFrom::Start(0)).await?;
+        Ok(SpillFile {
+            file,
+            types: self.types,
+        })
+    }
+}
+
+impl SpillFile {
+    /// Read the chunks in the order they are written.
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn read(self) {
+        let mut reader = BufReader::new(self.file);
+        let mut buf = vec![];
+        loop {
+            let len = match reader.read_u32_le().await {
+                Ok(len) => len as usize,
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => Err(e)?,
+            };
+            buf.resize(len, 0);
+            reader.read_exact(&mut buf).await?;
+
+            let mut data = &buf[..];
+            let num_rows = data.get_u32_le() as usize;
+            let mut builders = (self.types.iter())
+                .map(|ty| ArrayBuilderImpl::with_capacity(num_rows, ty))
+                .collect_vec();
+            for builder in &mut builders {
+                for _ in 0..num_rows {
+                    builder.push(&DataValue::decode(&mut data));
+                }
+            }
+            yield builders.into_iter().collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+
+    #[tokio::test]
+    async fn spill_and_read_back() {
+        let chunks = (0..3)
+            .map(|i| {
+                let chunk: DataChunk = [
+                    ArrayImpl::Int32((i * 10..i * 10 + 10).map(Some).collect()),
+                    ArrayImpl::Utf8(
+                        (0..10)
+                            .map(|v| (v % 3 != 0).then(|| format!("v{}", v)))
+                            .collect(),
+                    ),
+                    ArrayImpl::Decimal((0..10).map(|v| Some(Decimal::new(v * 150, 2))).collect()),
+                    ArrayImpl::Float64(
+                        [-0.0, 1.5, f64::NAN]
+                            .iter()
+                            .cycle()
+                            .take(10)
+                            .map(|v| Some(*v))
+                            .collect(),
+                    ),
+                ]
+                .into_iter()
+                .collect();
+                chunk
+            })
+            .collect_vec();
+
+        let mut writer = SpillWriter::new().unwrap();
+        for chunk in &chunks {
+            writer.write(chunk).await.unwrap();
+        }
+        let file = writer.finish().await.unwrap();
+        let outputs: Vec<DataChunk> = file.read().try_collect().await.unwrap();
+        assert_eq!(outputs.len(), chunks.len());
+        for (output, chunk) in outputs.iter().zip(&chunks) {
+            assert_eq!(output.cardinality(), chunk.cardinality());
+            for (a, b) in output.arrays().iter().zip(chunk.arrays()) {
+                for i in 0..a.len() {
+                    assert_eq!(a.get_to_string(i), b.get_to_string(i));
+                }
+            }
+        }
+    }
+}
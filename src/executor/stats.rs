@@ -0,0 +1,105 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::storage::ScanStats;
+
+/// Statistics collected during the execution of a query.
+#[derive(Debug, Default)]
+pub struct QueryStats {
+    /// The name and the number of rows produced of each executor, in the order of building.
+    executors: Mutex<Vec<(String, Arc<AtomicUsize>)>>,
+    blocks_read: AtomicUsize,
+    blocks_skipped: AtomicUsize,
+}
+
+impl QueryStats {
+    /// Register an executor, and return the counter of rows produced by it.
+    pub(super) fn register_executor(&self, name: String) -> Arc<AtomicUsize> {
+        let rows = Arc::new(AtomicUsize::new(0));
+        self.executors.lock().unwrap().push((name, rows.clone()));
+        rows
+    }
+
+    /// Record the statistics of a finished table scan.
+    pub(super) fn record_scan(&self, stats: ScanStats) {
+        self.blocks_read
+            .fetch_add(stats.blocks_read, Ordering::Relaxed);
+        self.blocks_skipped
+            .fetch_add(stats.blocks_skipped, Ordering::Relaxed);
+    }
+
+    /// Get the name and the number of rows produced of each executor.
+    pub fn rows_produced(&self) -> Vec<(String, usize)> {
+        self.executors
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, rows)| (name.clone(), rows.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// Get the number of blocks read by table scans.
+    pub fn blocks_read(&self) -> usize {
+        self.blocks_read.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of blocks skipped by table scans.
+    pub fn blocks_skipped(&self) -> usize {
+        self.blocks_skipped.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Display for QueryStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, rows) in self.rows_produced() {
+            writeln!(f, "{}: {} rows", name, rows)?;
+        }
+        write!(
+            f,
+            "blocks read: {}, blocks skipped: {}",
+            self.blocks_read(),
+            self.blocks_skipped()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::storage::SecondaryStorageOptions;
+    use crate::Database;
+
+    #[tokio::test]
+    async fn test_blocks_skipped_by_statistics() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let db = Database::new_on_disk(SecondaryStorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await;
+        db.run("create table t(v int)").await.unwrap();
+        let values = (0..10000).map(|v| format!("({})", v)).join(", ");
+        db.run(&format!("insert into t values {}", values))
+            .await
+            .unwrap();
+
+        let (_, stats) = db
+            .run_with_stats("select v from t where v >= 9990")
+            .await
+            .unwrap();
+        // only the last block may contain matched rows
+        assert!(stats.blocks_skipped() > 0);
+        assert_eq!(stats.blocks_read(), 1);
+        assert_eq!(
+            stats.rows_produced(),
+            vec![
+                ("PhysicalProjection".to_string(), 10),
+                ("PhysicalTableScan".to_string(), 10),
+            ]
+        );
+        db.shutdown().await.unwrap();
+    }
+}
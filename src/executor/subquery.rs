@@ -0,0 +1,256 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::array::{ArrayBuilderImpl, ArrayImpl, DataChunk};
+use crate::binder::{BoundColumnRef, BoundExpr, BoundInputRef, BoundSubquery};
+use crate::logical_planner::LogicalPlaner;
+use crate::optimizer::logical_plan_rewriter::{ExprRewriter, InputRefResolver, PlanRewriter};
+use crate::optimizer::Optimizer;
+use crate::types::DataValue;
+
+/// The executor of scalar subqueries, which appends the value of each subquery as a column to the
+/// chunks of the child.
+///
+/// Correlated subqueries are evaluated for each row of the child, with the values of outer columns
+/// substituted for their parameters. Uncorrelated subqueries are evaluated only once. A subquery
+/// results in NULL if it returns no row, and fails if it returns more than one row.
+pub struct SubqueryExecutor {
+    pub child: BoxedExecutor,
+    pub subqueries: Vec<BoundSubquery>,
+    pub storage: StorageImpl,
+}
+
+impl SubqueryExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let plans: Vec<PlanRef> = (self.subqueries.iter())
+            .map(|subquery| LogicalPlaner::default().plan_select(subquery.query.clone()))
+            .try_collect()
+            .map_err(|_| ExecutorError::BuildingPlanError)?;
+        // the values of uncorrelated subqueries
+        let mut uncorrelated_values = vec![None; self.subqueries.len()];
+
+        #[for_await]
+        for chunk in self.child {
+            let chunk = chunk?;
+            let mut arrays = chunk.arrays().to_vec();
+            for ((subquery, plan), uncorrelated_value) in (self.subqueries.iter())
+                .zip_eq(&plans)
+                .zip_eq(&mut uncorrelated_values)
+            {
+                let args: Vec<ArrayImpl> = (subquery.args.iter())
+                    .map(|arg| arg.eval(&chunk))
+                    .try_collect()?;
+                let mut builder =
+                    ArrayBuilderImpl::with_capacity(chunk.cardinality(), &subquery.return_type);
+                for row_idx in 0..chunk.cardinality() {
+                    if let Some(value) = uncorrelated_value {
+                        builder.push(value);
+                        continue;
+                    }
+                    let values = args.iter().map(|arg| arg.get(row_idx)).collect();
+                    let value = evaluate(&self.storage, plan, &subquery.params, values).await?;
+                    builder.push(&value);
+                    if subquery.params.is_empty() {
+                        *uncorrelated_value = Some(value);
+                    }
+                }
+                arrays.push(builder.finish());
+            }
+            yield arrays.into_iter().collect();
+        }
+    }
+}
+
+/// Evaluate the plan of a subquery with the values of its parameters.
+async fn evaluate(
+    storage: &StorageImpl,
+    plan: &PlanRef,
+    params: &[BoundColumnRef],
+    values: Vec<DataValue>,
+) -> Result<DataValue, ExecutorError> {
+    let plan = ParameterSubstitution { params, values }.rewrite(plan.clone());
+    let plan = InputRefResolver::default().rewrite(plan);
+    let mut optimizer = Optimizer {
+        enable_filter_scan: storage.enable_filter_scan(),
+        enable_limit_scan: storage.enable_limit_scan(),
+        enable_index_scan: storage.enable_index_scan(),
+        catalog: storage.catalog().clone(),
+    };
+    let plan = optimizer.optimize(plan);
+    let chunks: Vec<DataChunk> = ExecutorBuilder::new(storage.clone())
+        .build(plan)
+        .try_collect()
+        .await?;
+    let mut rows = chunks.iter().flat_map(|chunk| chunk.rows());
+    match (rows.next(), rows.next()) {
+        (None, _) => Ok(DataValue::Null),
+        (Some(row), None) => Ok(row.get(0)),
+        (Some(_), Some(_)) => Err(ExecutorError::SubqueryTooManyRows),
+    }
+}
+
+/// Extracts the subqueries in expressions, and replaces them with `InputRef`s to the columns
+/// appended by [`SubqueryExecutor`] after the `num_columns` input columns.
+pub(super) fn extract_subqueries(
+    exprs: &mut [BoundExpr],
+    num_columns: usize,
+) -> Vec<BoundSubquery> {
+    let mut extractor = SubqueryExtractor {
+        subqueries: vec![],
+        index: num_columns,
+    };
+    for expr in exprs {
+        extractor.visit_expr(expr);
+    }
+    extractor.subqueries
+}
+
+struct SubqueryExtractor {
+    subqueries: Vec<BoundSubquery>,
+    index: usize,
+}
+
+impl SubqueryExtractor {
+    fn visit_expr(&mut self, expr: &mut BoundExpr) {
+        use BoundExpr::*;
+        match expr {
+            Subquery(subquery) => {
+                let input_ref = InputRef(BoundInputRef {
+                    index: self.index,
+                    return_type: subquery.return_type.clone(),
+                });
+                match std::mem::replace(expr, input_ref) {
+                    Subquery(subquery) => self.subqueries.push(subquery),
+                    _ => unreachable!(),
+                }
+                self.index += 1;
+            }
+            BinaryOp(bin_op) => {
+                self.visit_expr(&mut bin_op.left_expr);
+                self.visit_expr(&mut bin_op.right_expr);
+            }
+            UnaryOp(unary_op) => self.visit_expr(&mut unary_op.expr),
+            AggCall(agg) => {
+                for arg in &mut agg.args {
+                    self.visit_expr(arg);
+                }
+            }
+            ScalarFunction(func) => {
+                for arg in &mut func.args {
+                    self.visit_expr(arg);
+                }
+            }
+            TypeCast(type_cast) => self.visit_expr(&mut type_cast.expr),
+            ExprWithAlias(expr_with_alias) => self.visit_expr(&mut expr_with_alias.expr),
+            IsNull(isnull) => self.visit_expr(&mut isnull.expr),
+            Case(case) => {
+                for (cond, result) in &mut case.when_then {
+                    self.visit_expr(cond);
+                    self.visit_expr(result);
+                }
+                if let Some(else_result) = &mut case.else_result {
+                    self.visit_expr(else_result);
+                }
+            }
+            Between(between) => {
+                self.visit_expr(&mut between.expr);
+                self.visit_expr(&mut between.low);
+                self.visit_expr(&mut between.high);
+            }
+            Like(like) => {
+                self.visit_expr(&mut like.expr);
+                self.visit_expr(&mut like.pattern);
+            }
+            Constant(_) | ColumnRef(_) | InputRef(_) | Alias(_) => {}
+        }
+    }
+}
+
+/// Substitutes the values of outer columns for the parameters in the plan of a subquery.
+struct ParameterSubstitution<'a> {
+    params: &'a [BoundColumnRef],
+    values: Vec<DataValue>,
+}
+
+impl ExprRewriter for ParameterSubstitution<'_> {
+    fn rewrite_expr(&self, expr: &mut BoundExpr) {
+        use BoundExpr::*;
+        match expr {
+            ColumnRef(column_ref) => {
+                if let Some(i) = self.params.iter().position(|param| param == column_ref) {
+                    *expr = Constant(self.values[i].clone());
+                }
+            }
+            BinaryOp(binary_op) => {
+                self.rewrite_expr(&mut *binary_op.left_expr);
+                self.rewrite_expr(&mut *binary_op.right_expr);
+            }
+            UnaryOp(unary_op) => self.rewrite_expr(&mut *unary_op.expr),
+            TypeCast(cast) => self.rewrite_expr(&mut *cast.expr),
+            AggCall(agg) => {
+                for arg in &mut agg.args {
+                    self.rewrite_expr(arg);
+                }
+            }
+            ScalarFunction(func) => {
+                for arg in &mut func.args {
+                    self.rewrite_expr(arg);
+                }
+            }
+            IsNull(isnull) => self.rewrite_expr(&mut *isnull.expr),
+            Case(case) => {
+                for (cond, result) in &mut case.when_then {
+                    self.rewrite_expr(cond);
+                    self.rewrite_expr(result);
+                }
+                if let Some(else_result) = &mut case.else_result {
+                    self.rewrite_expr(else_result);
+                }
+            }
+            Between(between) => {
+                self.rewrite_expr(&mut between.expr);
+                self.rewrite_expr(&mut between.low);
+                self.rewrite_expr(&mut between.high);
+            }
+            Like(like) => {
+                self.rewrite_expr(&mut like.expr);
+                self.rewrite_expr(&mut like.pattern);
+            }
+            ExprWithAlias(expr_with_alias) => self.rewrite_expr(&mut *expr_with_alias.expr),
+            Subquery(subquery) => {
+                for arg in &mut subquery.args {
+                    self.rewrite_expr(arg);
+                }
+            }
+            Constant(_) | InputRef(_) | Alias(_) => {}
+        }
+    }
+}
+
+impl PlanRewriter for ParameterSubstitution<'_> {
+    fn rewrite_logical_join(&mut self, join: &LogicalJoin) -> PlanRef {
+        let left = self.rewrite(join.left());
+        let right = self.rewrite(join.right());
+        Arc::new(join.clone_with_rewrite_expr(left, right, self))
+    }
+    fn rewrite_logical_projection(&mut self, proj: &LogicalProjection) -> PlanRef {
+        let child = self.rewrite(proj.child());
+        Arc::new(proj.clone_with_rewrite_expr(child, self))
+    }
+    fn rewrite_logical_aggregate(&mut self, agg: &LogicalAggregate) -> PlanRef {
+        let child = self.rewrite(agg.child());
+        Arc::new(agg.clone_with_rewrite_expr(child, self))
+    }
+    fn rewrite_logical_filter(&mut self, plan: &LogicalFilter) -> PlanRef {
+        let child = self.rewrite(plan.child());
+        Arc::new(plan.clone_with_rewrite_expr(child, self))
+    }
+    fn rewrite_logical_order(&mut self, plan: &LogicalOrder) -> PlanRef {
+        let child = self.rewrite(plan.child());
+        Arc::new(plan.clone_with_rewrite_expr(child, self))
+    }
+    fn rewrite_logical_values(&mut self, plan: &LogicalValues) -> PlanRef {
+        Arc::new(plan.clone_with_rewrite_expr(self))
+    }
+}
@@ -6,36 +6,31 @@ use super::*;
 use crate::array::{ArrayBuilder, ArrayBuilderImpl, DataChunk, I64ArrayBuilder};
 use crate::binder::BoundExpr;
 use crate::optimizer::plan_nodes::PhysicalTableScan;
-use crate::storage::{Storage, StorageColumnRef, Table, Transaction, TxnIterator};
+use crate::storage::{
+    ExplicitTransaction, ScanOptions, Storage, StorageColumnRef, Table, TxnHandle, TxnIterator,
+};
 
 /// The executor of table scan operation.
 pub struct TableScanExecutor<S: Storage> {
     pub plan: PhysicalTableScan,
     pub expr: Option<BoundExpr>,
     pub storage: Arc<S>,
+    pub stats: Arc<QueryStats>,
+    /// The transaction started by `BEGIN`, if any.
+    pub txn: Option<Arc<ExplicitTransaction<S>>>,
 }
 
 impl<S: Storage> TableScanExecutor<S> {
     /// Some executors will fail if no chunk is returned from `SeqScanExecutor`. After we have
     /// schema information in executors, this function can be removed.
-    fn build_empty_chunk(&self, table: &impl Table) -> Result<DataChunk, ExecutorError> {
+    pub(super) fn build_empty_chunk(
+        plan: &PhysicalTableScan,
+        table: &impl Table,
+    ) -> Result<DataChunk, ExecutorError> {
         let columns = table.columns()?;
-        let mut col_idx = self
-            .plan
-            .logical()
-            .column_ids()
-            .iter()
-            .map(|x| StorageColumnRef::Idx(*x))
-            .collect_vec();
-
-        // Add an extra column for RowHandler at the end
-        if self.plan.logical().with_row_handler() {
-            col_idx.push(StorageColumnRef::RowHandler);
-        }
 
         // Get n array builders
-        let mut builders = self
-            .plan
+        let mut builders = plan
             .logical()
             .column_ids()
             .iter()
@@ -43,7 +38,7 @@ impl<S: Storage> TableScanExecutor<S> {
             .map(|col| ArrayBuilderImpl::new(&col.datatype()))
             .collect::<Vec<ArrayBuilderImpl>>();
 
-        if self.plan.logical().with_row_handler() {
+        if plan.logical().with_row_handler() {
             builders.push(ArrayBuilderImpl::Int64(I64ArrayBuilder::new()));
         }
 
@@ -57,7 +52,7 @@ impl<S: Storage> TableScanExecutor<S> {
         let table = self.storage.get_table(self.plan.logical().table_ref_id())?;
 
         // TODO: remove this when we have schema
-        let empty_chunk = self.build_empty_chunk(&table)?;
+        let empty_chunk = Self::build_empty_chunk(&self.plan, &table)?;
         let mut have_chunk = false;
 
         let mut col_idx = self
@@ -73,16 +68,18 @@ impl<S: Storage> TableScanExecutor<S> {
             col_idx.push(StorageColumnRef::RowHandler);
         }
 
-        let txn = table.read().await?;
+        let txn = TxnHandle::read(&table, self.txn.as_deref()).await?;
 
         let mut it = txn
             .scan(
-                None,
-                None,
                 &col_idx,
-                self.plan.logical().is_sorted(),
-                false,
-                self.expr,
+                ScanOptions {
+                    is_sorted: self.plan.logical().is_sorted(),
+                    expr: self.expr,
+                    row_offset: self.plan.logical().offset(),
+                    row_limit: self.plan.logical().limit(),
+                    ..Default::default()
+                },
             )
             .await?;
 
@@ -103,6 +100,7 @@ impl<S: Storage> TableScanExecutor<S> {
             }
         }
 
+        self.stats.record_scan(it.stats());
         txn.abort().await?;
 
         if !have_chunk {
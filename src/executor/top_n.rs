@@ -0,0 +1,208 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use super::order::cmp_keys;
+use super::*;
+use crate::array::{ArrayBuilderImpl, DataChunk};
+use crate::binder::BoundOrderBy;
+use crate::types::{DataType, DataValue};
+
+/// The executor of a top-n operation, i.e. `ORDER BY ... LIMIT k OFFSET n`.
+///
+/// Instead of sorting all rows, it keeps the first `n + k` rows in a bounded max-heap, whose top
+/// is the last one of them in order. The rows are sorted and emitted after the child finishes.
+pub struct TopNExecutor {
+    pub child: BoxedExecutor,
+    pub offset: usize,
+    pub limit: usize,
+    pub comparators: Vec<BoundOrderBy>,
+    pub output_types: Vec<DataType>,
+}
+
+impl TopNExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let TopNExecutor {
+            child,
+            offset,
+            limit,
+            comparators,
+            output_types,
+        } = self;
+        let capacity = offset + limit;
+        if capacity == 0 {
+            return Ok(());
+        }
+
+        let mut heap = BinaryHeap::new();
+        // the sequence number of rows, to keep the input order of rows with equal keys
+        let mut seq = 0;
+
+        #[for_await]
+        for chunk in child {
+            let chunk = chunk?;
+            let key_arrays = (comparators.iter())
+                .map(|cmp| cmp.expr.eval(&chunk))
+                .collect::<Result<Vec<_>, _>>()?;
+            for i in 0..chunk.cardinality() {
+                let entry = HeapEntry {
+                    key: key_arrays.iter().map(|a| a.get(i)).collect(),
+                    seq,
+                    row: vec![],
+                    comparators: &comparators,
+                };
+                seq += 1;
+                if heap.len() == capacity {
+                    // skip the row if it is after all rows in the heap
+                    if &entry >= heap.peek().unwrap() {
+                        continue;
+                    }
+                    heap.pop();
+                }
+                heap.push(HeapEntry {
+                    row: chunk.row(i).values().collect(),
+                    ..entry
+                });
+            }
+        }
+
+        let new_builders = || {
+            (output_types.iter())
+                .map(|ty| ArrayBuilderImpl::with_capacity(PROCESSING_WINDOW_SIZE, ty))
+                .collect_vec()
+        };
+        let mut builders = new_builders();
+        let mut num_rows = 0;
+        for entry in heap.into_sorted_vec().into_iter().skip(offset) {
+            for (builder, v) in builders.iter_mut().zip_eq(&entry.row) {
+                builder.push(v);
+            }
+            num_rows += 1;
+            if num_rows == PROCESSING_WINDOW_SIZE {
+                yield std::mem::replace(&mut builders, new_builders())
+                    .into_iter()
+                    .collect();
+                num_rows = 0;
+            }
+        }
+        if num_rows != 0 {
+            yield builders.into_iter().collect();
+        }
+    }
+}
+
+/// A row in the heap, ordered by its sort key.
+struct HeapEntry<'a> {
+    key: Vec<DataValue>,
+    seq: usize,
+    row: Vec<DataValue>,
+    comparators: &'a [BoundOrderBy],
+}
+
+impl Ord for HeapEntry<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_keys(&self.key, &other.key, self.comparators).then(self.seq.cmp(&other.seq))
+    }
+}
+
+impl PartialOrd for HeapEntry<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeapEntry<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapEntry<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::binder::{BoundExpr, BoundInputRef};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    fn order_by(index: usize, descending: bool, nulls_first: bool) -> BoundOrderBy {
+        BoundOrderBy {
+            expr: BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: DataTypeKind::Int(None).nullable(),
+            }),
+            descending,
+            nulls_first,
+        }
+    }
+
+    async fn top_n(
+        chunks: Vec<DataChunk>,
+        offset: usize,
+        limit: usize,
+        comparators: Vec<BoundOrderBy>,
+    ) -> Vec<Vec<DataValue>> {
+        let executor = TopNExecutor {
+            child: futures::stream::iter(chunks.into_iter().map(Ok)).boxed(),
+            offset,
+            limit,
+            comparators,
+            output_types: vec![DataTypeKind::Int(None).nullable(); 3],
+        };
+        let chunks = executor.execute().try_collect::<Vec<_>>().await.unwrap();
+        (chunks.iter())
+            .flat_map(|c| c.rows())
+            .map(|row| row.values().collect_vec())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_top_n() {
+        // (v1, v2, id)
+        let rows: Vec<[Option<i32>; 3]> = vec![
+            [Some(1), Some(5), Some(0)],
+            [None, Some(1), Some(1)],
+            [Some(2), None, Some(2)],
+            [Some(1), Some(7), Some(3)],
+            [Some(2), Some(3), Some(4)],
+            [None, None, Some(5)],
+            [Some(1), None, Some(6)],
+            [Some(3), Some(0), Some(7)],
+        ];
+        let chunks = rows
+            .chunks(3)
+            .map(|rows| {
+                (0..3)
+                    .map(|i| ArrayImpl::Int32(rows.iter().map(|r| r[i]).collect()))
+                    .collect()
+            })
+            .collect_vec();
+
+        // order by v1 desc nulls last, v2 asc nulls first
+        let comparators = vec![order_by(0, true, false), order_by(1, false, true)];
+        let ids = |rows: Vec<Vec<DataValue>>| {
+            rows.into_iter()
+                .map(|row| match row[2] {
+                    DataValue::Int32(id) => id,
+                    _ => panic!("unexpected id"),
+                })
+                .collect_vec()
+        };
+        // the full ranking is [7, 2, 4, 6, 0, 3, 5, 1]
+        let rows = top_n(chunks.clone(), 0, 100, comparators.clone()).await;
+        assert_eq!(ids(rows), vec![7, 2, 4, 6, 0, 3, 5, 1]);
+        let rows = top_n(chunks.clone(), 0, 3, comparators.clone()).await;
+        assert_eq!(ids(rows), vec![7, 2, 4]);
+        let rows = top_n(chunks.clone(), 2, 3, comparators.clone()).await;
+        assert_eq!(ids(rows), vec![4, 6, 0]);
+        let rows = top_n(chunks.clone(), 7, 3, comparators.clone()).await;
+        assert_eq!(ids(rows), vec![1]);
+        assert!(top_n(chunks, 0, 0, comparators.clone()).await.is_empty());
+        assert!(top_n(vec![], 0, 3, comparators).await.is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use super::*;
+use crate::optimizer::plan_nodes::PhysicalTruncate;
+use crate::storage::Storage;
+
+/// The executor of `truncate` statement.
+pub struct TruncateExecutor<S: Storage> {
+    pub plan: PhysicalTruncate,
+    pub storage: Arc<S>,
+}
+
+impl<S: Storage> TruncateExecutor<S> {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        self.storage
+            .truncate_table(self.plan.logical().table_ref_id())
+            .await?;
+
+        let mut chunk = DataChunk::single(0);
+        chunk.set_header(vec!["$truncate".to_string()]);
+        yield chunk
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::insert::{check_not_null, chunk_keys, key_to_string};
+use super::*;
+use crate::array::DataChunk;
+use crate::binder::BoundExpr;
+use crate::catalog::{find_sort_key_ids, TableRefId};
+use crate::storage::{
+    ExplicitTransaction, RowHandler, ScanOptions, Storage, StorageColumnRef, Table, Transaction,
+    TxnHandle, TxnIterator,
+};
+
+/// The executor of `update` statement.
+///
+/// Each row from the child is rewritten by deleting the old version and appending the new one.
+/// The last column of the child must be the row handler.
+///
+/// The new rows are checked against the NOT NULL and primary key constraints like inserted rows.
+/// A new key may take the key of an updated row, e.g. `set pk = pk + 1`, so the conflicts with
+/// existing rows are checked after all rows are updated.
+pub struct UpdateExecutor<S: Storage> {
+    pub table_ref_id: TableRefId,
+    pub values: Vec<BoundExpr>,
+    pub storage: Arc<S>,
+    pub child: BoxedExecutor,
+    /// The transaction started by `BEGIN`, if any.
+    pub txn: Option<Arc<ExplicitTransaction<S>>>,
+}
+
+impl<S: Storage> UpdateExecutor<S> {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let table = self.storage.get_table(self.table_ref_id)?;
+        let columns = table.columns()?;
+        let key_columns = find_sort_key_ids(&columns);
+        let mut txn = TxnHandle::update(&table, self.txn.as_deref()).await?;
+
+        // The keys of the existing rows, mapped to their row handlers and formatted keys.
+        let mut existing_keys = HashMap::new();
+        if !key_columns.is_empty() {
+            let mut key_refs = (key_columns.iter())
+                .map(|&idx| StorageColumnRef::Idx(columns[idx].id()))
+                .collect_vec();
+            key_refs.push(StorageColumnRef::RowHandler);
+            let key_indexes = (0..key_columns.len()).collect_vec();
+            let mut it = txn.scan(&key_refs, ScanOptions::default()).await?;
+            while let Some(chunk) = it.next_batch(None).await? {
+                let row_handlers = chunk.array_at(key_columns.len());
+                for row_idx in 0..chunk.cardinality() {
+                    let key = (key_indexes.iter())
+                        .map(|&idx| chunk.array_at(idx).get(row_idx))
+                        .collect_vec();
+                    let formatted = key_to_string(&chunk, &key_indexes, row_idx);
+                    existing_keys.insert(key, (row_handlers.get(row_idx), formatted));
+                }
+            }
+        }
+        // The keys of the new rows, and the row handlers of the old versions.
+        let mut updated_keys = HashSet::new();
+        let mut deleted_rows = HashSet::new();
+
+        let mut cnt = 0;
+        #[for_await]
+        for chunk in self.child {
+            let chunk = chunk?;
+            let new_chunk: DataChunk = self
+                .values
+                .iter()
+                .map(|expr| expr.eval(&chunk))
+                .try_collect()?;
+            if let Err(err) = check_not_null(&columns, &new_chunk) {
+                txn.abort().await?;
+                return Err(err);
+            }
+            let row_handlers = chunk.array_at(chunk.column_count() - 1);
+            if !key_columns.is_empty() {
+                let (rows, keys) = chunk_keys(&new_chunk, &key_columns);
+                let duplicate = (keys.into_iter())
+                    .position(|key| !updated_keys.insert(key))
+                    .map(|i| rows[i]);
+                if let Some(row_idx) = duplicate {
+                    txn.abort().await?;
+                    let key = key_to_string(&new_chunk, &key_columns, row_idx);
+                    return Err(ExecutorError::UniqueViolation(key));
+                }
+                deleted_rows.extend((0..row_handlers.len()).map(|i| row_handlers.get(i)));
+            }
+            for row_handler_idx in 0..row_handlers.len() {
+                let row_handler = <S::TransactionType as Transaction>::RowHandlerType::from_column(
+                    row_handlers,
+                    row_handler_idx,
+                );
+                txn.delete(&row_handler).await?;
+            }
+            cnt += new_chunk.cardinality();
+            txn.append(new_chunk).await?;
+        }
+        // a new key conflicts with an existing row that is not updated
+        let conflict = (updated_keys.iter())
+            .filter_map(|key| existing_keys.get(key))
+            .find(|(row_handler, _)| !deleted_rows.contains(row_handler));
+        if let Some((_, key)) = conflict {
+            let key = key.clone();
+            txn.abort().await?;
+            return Err(ExecutorError::UniqueViolation(key));
+        }
+        txn.commit().await?;
+
+        let mut chunk = DataChunk::single(cnt as i32);
+        chunk.set_header(vec!["$update.row_counts".to_string()]);
+        yield chunk;
+    }
+}
@@ -0,0 +1,14 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::binder::BoundAlterTable;
+use crate::optimizer::plan_nodes::LogicalAlterTable;
+
+impl LogicalPlaner {
+    pub fn plan_alter_table(&self, stmt: BoundAlterTable) -> Result<PlanRef, LogicalPlanError> {
+        Ok(Arc::new(LogicalAlterTable::new(
+            stmt.table_ref_id,
+            stmt.column,
+        )))
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::binder::{BoundAnalyze, BoundTableRef};
+use crate::optimizer::plan_nodes::LogicalAnalyze;
+
+impl LogicalPlaner {
+    pub fn plan_analyze(&self, stmt: BoundAnalyze) -> Result<PlanRef, LogicalPlanError> {
+        if let BoundTableRef::BaseTableRef {
+            ref ref_id,
+            ref column_ids,
+            ..
+        } = stmt.table
+        {
+            let plan = self.plan_table_ref(&stmt.table, false, false)?;
+            Ok(Arc::new(LogicalAnalyze::new(
+                *ref_id,
+                column_ids.clone(),
+                plan,
+            )))
+        } else {
+            panic!("unsupported table")
+        }
+    }
+}
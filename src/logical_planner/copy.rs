@@ -19,10 +19,12 @@ impl LogicalPlaner {
         let column_types = stmt.columns.iter().map(|col| col.datatype()).collect();
         let column_descs = stmt.columns.iter().map(|col| col.desc().clone()).collect();
         if stmt.to {
+            let column_names = stmt.columns.iter().map(|col| col.name().into()).collect();
             Ok(Arc::new(LogicalCopyToFile::new(
                 path,
                 stmt.format,
                 column_types,
+                column_names,
                 Arc::new(LogicalTableScan::new(
                     stmt.table_ref_id,
                     column_ids,
@@ -0,0 +1,14 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::binder::BoundCreateIndex;
+use crate::optimizer::plan_nodes::LogicalCreateIndex;
+
+impl LogicalPlaner {
+    pub fn plan_create_index(&self, stmt: BoundCreateIndex) -> Result<PlanRef, LogicalPlanError> {
+        Ok(Arc::new(LogicalCreateIndex::new(
+            stmt.table_ref_id,
+            stmt.column_id,
+        )))
+    }
+}
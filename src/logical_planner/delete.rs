@@ -7,15 +7,12 @@ use crate::optimizer::plan_nodes::{LogicalDelete, LogicalFilter};
 impl LogicalPlaner {
     pub fn plan_delete(&self, stmt: BoundDelete) -> Result<PlanRef, LogicalPlanError> {
         if let BoundTableRef::BaseTableRef { ref ref_id, .. } = stmt.from_table {
+            let mut plan = self.plan_table_ref(&stmt.from_table, true, false)?;
+            // delete all rows if there is no `where` clause
             if let Some(expr) = stmt.where_clause {
-                let child = self.plan_table_ref(&stmt.from_table, true, false)?;
-                Ok(Arc::new(LogicalDelete::new(
-                    *ref_id,
-                    Arc::new(LogicalFilter::new(expr, child)),
-                )))
-            } else {
-                panic!("delete whole table is not supported yet")
+                plan = Arc::new(LogicalFilter::new(expr, plan));
             }
+            Ok(Arc::new(LogicalDelete::new(*ref_id, plan)))
         } else {
             panic!("unsupported table")
         }
@@ -6,6 +6,6 @@ use crate::optimizer::plan_nodes::LogicalDrop;
 
 impl LogicalPlaner {
     pub fn plan_drop(&self, stmt: BoundDrop) -> Result<PlanRef, LogicalPlanError> {
-        Ok(Arc::new(LogicalDrop::new(stmt.object)))
+        Ok(Arc::new(LogicalDrop::new(stmt.objects)))
     }
 }
@@ -6,14 +6,19 @@ use crate::optimizer::plan_nodes::{LogicalInsert, LogicalValues};
 
 impl LogicalPlaner {
     pub fn plan_insert(&self, stmt: BoundInsert) -> Result<PlanRef, LogicalPlanError> {
-        Ok(Arc::new(LogicalInsert::new(
-            stmt.table_ref_id,
-            stmt.column_ids,
-            Arc::new(LogicalValues::new(
+        let child = match stmt.select_stmt {
+            Some(select_stmt) => self.plan_select(select_stmt)?,
+            None => Arc::new(LogicalValues::new(
                 stmt.column_types,
                 stmt.column_descs,
                 stmt.values,
+                None,
             )),
+        };
+        Ok(Arc::new(LogicalInsert::new(
+            stmt.table_ref_id,
+            stmt.column_ids,
+            child,
         )))
     }
 }
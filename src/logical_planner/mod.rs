@@ -6,20 +6,31 @@ use crate::binder::BoundStatement;
 use crate::optimizer::plan_nodes::PlanRef;
 use crate::types::ConvertError;
 
+mod alter;
+mod analyze;
 mod copy;
 mod create;
+mod create_index;
 mod delete;
 mod drop;
 mod explain;
 mod insert;
 mod select;
+mod set_operation;
+mod truncate;
+mod update;
 
+pub use alter::*;
+pub use analyze::*;
 pub use copy::*;
 pub use create::*;
+pub use create_index::*;
 pub use delete::*;
 pub use drop::*;
 pub use explain::*;
 pub use insert::*;
+pub use truncate::*;
+pub use update::*;
 
 /// The error type of logical planner.
 #[derive(thiserror::Error, Debug, PartialEq)]
@@ -39,12 +50,18 @@ impl LogicalPlaner {
         use BoundStatement::*;
         match stmt {
             CreateTable(stmt) => self.plan_create_table(stmt),
+            AlterTable(stmt) => self.plan_alter_table(stmt),
             Drop(stmt) => self.plan_drop(stmt),
             Insert(stmt) => self.plan_insert(stmt),
             Copy(stmt) => self.plan_copy(stmt),
             Select(stmt) => self.plan_select(stmt),
+            SetOperation(stmt) => self.plan_set_operation(*stmt),
             Explain(stmt) => self.plan_explain(*stmt),
             Delete(stmt) => self.plan_delete(*stmt),
+            Update(stmt) => self.plan_update(*stmt),
+            Analyze(stmt) => self.plan_analyze(stmt),
+            Truncate(stmt) => self.plan_truncate(stmt),
+            CreateIndex(stmt) => self.plan_create_index(stmt),
         }
     }
 }
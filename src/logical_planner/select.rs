@@ -6,7 +6,10 @@
 //!
 //! - [`LogicalTableScan`] (from *) or dummy plan (no from)
 //! - [`LogicalFilter`] (where *)
+//! - [`LogicalAggregate`] (group by *)
+//! - [`LogicalFilter`] (having *)
 //! - [`LogicalProjection`] (select *)
+//! - [`LogicalDistinct`] (select distinct *)
 //! - [`LogicalOrder`] (order by *)
 use itertools::Itertools;
 
@@ -15,8 +18,8 @@ use crate::binder::{
     BoundAggCall, BoundExpr, BoundInputRef, BoundOrderBy, BoundSelect, BoundTableRef,
 };
 use crate::optimizer::plan_nodes::{
-    Dummy, LogicalAggregate, LogicalFilter, LogicalJoin, LogicalLimit, LogicalOrder,
-    LogicalProjection, LogicalTableScan,
+    Dummy, LogicalAggregate, LogicalDistinct, LogicalFilter, LogicalJoin, LogicalLimit,
+    LogicalOrder, LogicalProjection, LogicalTableScan, LogicalValues,
 };
 
 impl LogicalPlaner {
@@ -25,14 +28,14 @@ impl LogicalPlaner {
         let mut is_sorted = false;
 
         if let Some(table_ref) = &stmt.from_table {
-            // use `sorted` mode from the storage engine if the order by column is the primary key
-            if stmt.orderby.len() == 1 && !stmt.orderby[0].descending {
-                if let BoundExpr::ColumnRef(col_ref) = &stmt.orderby[0].expr {
-                    if col_ref.is_primary_key {
-                        is_sorted = true;
-                    }
-                }
-            }
+            // use `sorted` mode from the storage engine if the order by columns are a prefix of
+            // the primary key
+            is_sorted = !stmt.orderby.is_empty()
+                && stmt.orderby.iter().enumerate().all(|(i, orderby)| {
+                    !orderby.descending
+                        && matches!(&orderby.expr, BoundExpr::ColumnRef(col_ref)
+                            if col_ref.is_primary_key && col_ref.desc.primary_key_index() == Some(i))
+                });
             plan = self.plan_table_ref(table_ref, false, is_sorted)?;
         }
 
@@ -44,6 +47,9 @@ impl LogicalPlaner {
         for expr in &mut stmt.select_list {
             agg_extractor.visit_expr(expr);
         }
+        if let Some(expr) = &mut stmt.having {
+            agg_extractor.visit_expr(expr);
+        }
         if !agg_extractor.agg_calls.is_empty() {
             plan = Arc::new(LogicalAggregate::new(
                 agg_extractor.agg_calls,
@@ -51,6 +57,10 @@ impl LogicalPlaner {
                 plan,
             ));
         }
+        // the `having` clause filters the outputs of aggregation
+        if let Some(expr) = stmt.having {
+            plan = Arc::new(LogicalFilter::new(expr, plan));
+        }
 
         let mut alias_extractor = AliasExtractor::new(&stmt.select_list);
         let comparators = stmt
@@ -59,33 +69,43 @@ impl LogicalPlaner {
             .map(|expr| alias_extractor.visit_expr(expr))
             .collect_vec();
 
-        // TODO: support the following clauses
-        assert!(!stmt.select_distinct, "TODO: plan distinct");
-
         if !stmt.select_list.is_empty() {
             plan = Arc::new(LogicalProjection::new(stmt.select_list, plan));
         }
+        if stmt.select_distinct {
+            plan = Arc::new(LogicalDistinct::new(plan));
+        }
         if !comparators.is_empty() && !is_sorted {
             plan = Arc::new(LogicalOrder::new(comparators, plan));
         }
-        if stmt.limit.is_some() || stmt.offset.is_some() {
-            let limit = match stmt.limit {
-                Some(limit) => match limit {
-                    BoundExpr::Constant(v) => v.as_usize()?.unwrap_or(usize::MAX / 2),
-                    _ => panic!("limit only support constant expression"),
-                },
-                None => usize::MAX / 2, // avoid 'offset + limit' overflow
-            };
-            let offset = match stmt.offset {
-                Some(offset) => match offset {
-                    BoundExpr::Constant(v) => v.as_usize()?.unwrap_or(0),
-                    _ => panic!("offset only support constant expression"),
-                },
-                None => 0,
-            };
-            plan = Arc::new(LogicalLimit::new(offset, limit, plan));
+        self.plan_limit(stmt.limit, stmt.offset, plan)
+    }
+
+    /// Plan the `limit` and `offset` on the outputs of `plan`, if any.
+    pub(super) fn plan_limit(
+        &self,
+        limit: Option<BoundExpr>,
+        offset: Option<BoundExpr>,
+        plan: PlanRef,
+    ) -> Result<PlanRef, LogicalPlanError> {
+        if limit.is_none() && offset.is_none() {
+            return Ok(plan);
         }
-        Ok(plan)
+        let limit = match limit {
+            Some(limit) => match limit {
+                BoundExpr::Constant(v) => v.as_usize()?.unwrap_or(usize::MAX / 2),
+                _ => panic!("limit only support constant expression"),
+            },
+            None => usize::MAX / 2, // avoid 'offset + limit' overflow
+        };
+        let offset = match offset {
+            Some(offset) => match offset {
+                BoundExpr::Constant(v) => v.as_usize()?.unwrap_or(0),
+                _ => panic!("offset only support constant expression"),
+            },
+            None => 0,
+        };
+        Ok(Arc::new(LogicalLimit::new(offset, limit, plan)))
     }
 
     pub fn plan_table_ref(
@@ -125,6 +145,24 @@ impl LogicalPlaner {
                 }
                 Ok(plan)
             }
+            BoundTableRef::Values(values) => Ok(Arc::new(LogicalValues::new(
+                values.column_types(),
+                values.column_descs.clone(),
+                values.values.clone(),
+                Some(values.table_ref_id),
+            ))),
+            BoundTableRef::Subquery(subquery) => {
+                let plan = self.plan_select(subquery.query.clone())?;
+                // project the outputs as the columns of the subquery
+                let exprs = (plan.out_types().into_iter().enumerate())
+                    .map(|(index, return_type)| {
+                        BoundExpr::InputRef(BoundInputRef { index, return_type })
+                    })
+                    .collect();
+                Ok(Arc::new(
+                    LogicalProjection::new(exprs, plan).with_table_ref_id(subquery.table_ref_id),
+                ))
+            }
         }
     }
 }
@@ -169,10 +207,34 @@ impl AggExtractor {
                 self.visit_expr(&mut bin_op.right_expr);
             }
             UnaryOp(unary_op) => self.visit_expr(&mut unary_op.expr),
+            ScalarFunction(func) => {
+                for arg in &mut func.args {
+                    self.visit_expr(arg);
+                }
+            }
             TypeCast(type_cast) => self.visit_expr(&mut type_cast.expr),
             ExprWithAlias(expr_with_alias) => self.visit_expr(&mut expr_with_alias.expr),
             IsNull(isnull) => self.visit_expr(&mut isnull.expr),
-            Constant(_) | ColumnRef(_) | InputRef(_) | Alias(_) => {}
+            Case(case) => {
+                for (cond, result) in &mut case.when_then {
+                    self.visit_expr(cond);
+                    self.visit_expr(result);
+                }
+                if let Some(else_result) = &mut case.else_result {
+                    self.visit_expr(else_result);
+                }
+            }
+            Between(between) => {
+                self.visit_expr(&mut between.expr);
+                self.visit_expr(&mut between.low);
+                self.visit_expr(&mut between.high);
+            }
+            Like(like) => {
+                self.visit_expr(&mut like.expr);
+                self.visit_expr(&mut like.pattern);
+            }
+            // the arguments of subqueries are columns, which contain no aggregation
+            Constant(_) | ColumnRef(_) | InputRef(_) | Alias(_) | Subquery(_) => {}
         }
     }
 }
@@ -219,9 +281,11 @@ impl<'a> AliasExtractor<'a> {
                 BoundOrderBy {
                     expr: input_ref,
                     descending: expr.descending,
+                    nulls_first: expr.nulls_first,
                 }
             }
-            ColumnRef(_) => expr,
+            // positions in the select list, e.g. `order by 2`
+            ColumnRef(_) | InputRef(_) => expr,
             _ => panic!("order-by expression should be column ref or expr alias"),
         }
     }
@@ -0,0 +1,56 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::binder::{
+    BoundExpr, BoundExprWithAlias, BoundInputRef, BoundSetExpr, BoundSetOperation, BoundTypeCast,
+};
+use crate::optimizer::plan_nodes::{LogicalOrder, LogicalProjection, LogicalSetOperation};
+use crate::types::DataType;
+
+impl LogicalPlaner {
+    pub fn plan_set_operation(&self, stmt: BoundSetOperation) -> Result<PlanRef, LogicalPlanError> {
+        let left = self.plan_set_expr(stmt.left, &stmt.return_types)?;
+        let right = self.plan_set_expr(stmt.right, &stmt.return_types)?;
+        let mut plan: PlanRef = Arc::new(LogicalSetOperation::new(stmt.op, stmt.all, left, right));
+        if !stmt.orderby.is_empty() {
+            plan = Arc::new(LogicalOrder::new(stmt.orderby, plan));
+        }
+        self.plan_limit(stmt.limit, stmt.offset, plan)
+    }
+
+    /// Plan an operand of a set operation, whose outputs are casted to `return_types` if needed.
+    fn plan_set_expr(
+        &self,
+        expr: BoundSetExpr,
+        return_types: &[DataType],
+    ) -> Result<PlanRef, LogicalPlanError> {
+        let plan = match expr {
+            BoundSetExpr::Select(stmt) => self.plan_select(stmt)?,
+            BoundSetExpr::SetOperation(stmt) => self.plan_set_operation(*stmt)?,
+        };
+        let schema = plan.schema();
+        if (schema.iter().zip(return_types)).all(|(col, ty)| col.datatype().kind() == ty.kind()) {
+            return Ok(plan);
+        }
+        let exprs = (schema.iter().enumerate().zip(return_types))
+            .map(|((index, col), ty)| {
+                let mut expr = BoundExpr::InputRef(BoundInputRef {
+                    index,
+                    return_type: col.datatype().clone(),
+                });
+                if col.datatype().kind() != ty.kind() {
+                    expr = BoundExpr::TypeCast(BoundTypeCast {
+                        expr: Box::new(expr),
+                        ty: ty.kind(),
+                    });
+                }
+                // keep the column names
+                BoundExpr::ExprWithAlias(BoundExprWithAlias {
+                    expr: Box::new(expr),
+                    alias: col.name().to_string(),
+                })
+            })
+            .collect();
+        Ok(Arc::new(LogicalProjection::new(exprs, plan)))
+    }
+}
@@ -0,0 +1,11 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::binder::BoundTruncate;
+use crate::optimizer::plan_nodes::LogicalTruncate;
+
+impl LogicalPlaner {
+    pub fn plan_truncate(&self, stmt: BoundTruncate) -> Result<PlanRef, LogicalPlanError> {
+        Ok(Arc::new(LogicalTruncate::new(stmt.table_ref_id)))
+    }
+}
@@ -0,0 +1,19 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::binder::{BoundTableRef, BoundUpdate};
+use crate::optimizer::plan_nodes::{LogicalFilter, LogicalUpdate};
+
+impl LogicalPlaner {
+    pub fn plan_update(&self, stmt: BoundUpdate) -> Result<PlanRef, LogicalPlanError> {
+        if let BoundTableRef::BaseTableRef { ref ref_id, .. } = stmt.from_table {
+            let mut plan = self.plan_table_ref(&stmt.from_table, true, false)?;
+            if let Some(expr) = stmt.where_clause {
+                plan = Arc::new(LogicalFilter::new(expr, plan));
+            }
+            Ok(Arc::new(LogicalUpdate::new(*ref_id, stmt.values, plan)))
+        } else {
+            panic!("unsupported table")
+        }
+    }
+}
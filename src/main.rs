@@ -7,7 +7,7 @@ use std::sync::Mutex;
 
 use anyhow::{anyhow, Result};
 use clap::Parser;
-use risinglight::array::{datachunk_to_sqllogictest_string, DataChunk};
+use risinglight::array::{datachunk_to_sqllogictest_string, DataChunk, TableFormatter};
 use risinglight::storage::SecondaryStorageOptions;
 use risinglight::Database;
 use rustyline::error::ReadlineError;
@@ -26,6 +26,10 @@ struct Args {
     /// Whether to use in-memory engine
     #[clap(long)]
     memory: bool,
+
+    /// Whether to print the statistics of each query in interactive mode
+    #[clap(long)]
+    stats: bool,
 }
 
 // human-readable message
@@ -35,6 +39,12 @@ fn print_chunk(chunk: &DataChunk) {
             "$insert.row_counts" => {
                 println!("{} rows inserted", chunk.array_at(0).get_to_string(0))
             }
+            "$delete.row_counts" => {
+                println!("{} rows deleted", chunk.array_at(0).get_to_string(0))
+            }
+            "$update.row_counts" => {
+                println!("{} rows updated", chunk.array_at(0).get_to_string(0))
+            }
             "$create" => println!("created"),
             "$explain" => println!("{}", chunk.array_at(0).get_to_string(0)),
             _ => println!("{}", chunk),
@@ -43,8 +53,29 @@ fn print_chunk(chunk: &DataChunk) {
     }
 }
 
+// print the outputs of statements, each of which starts with a chunk with header
+fn print_chunks(chunks: &[DataChunk]) {
+    let mut start = 0;
+    while start < chunks.len() {
+        let end = (start + 1..chunks.len())
+            .find(|&i| chunks[i].header().is_some())
+            .unwrap_or(chunks.len());
+        let outputs = &chunks[start..end];
+        match outputs[0].header() {
+            Some(header) if !header[0].starts_with('$') => {
+                let types: Vec<_> = (outputs[0].arrays().iter())
+                    .map(|a| a.data_type())
+                    .collect();
+                print!("{}", TableFormatter::new(header, &types).format(outputs));
+            }
+            _ => outputs.iter().for_each(print_chunk),
+        }
+        start = end;
+    }
+}
+
 /// Run RisingLight interactive mode
-async fn interactive(db: Database) -> Result<()> {
+async fn interactive(db: Database, print_stats: bool) -> Result<()> {
     let mut rl = Editor::<()>::new();
     let history_path = dirs::cache_dir().map(|p| {
         let cache_dir = p.join("risinglight");
@@ -66,11 +97,12 @@ async fn interactive(db: Database) -> Result<()> {
         match readline {
             Ok(line) => {
                 rl.add_history_entry(line.as_str());
-                let ret = db.run(&line).await;
+                let ret = db.run_with_stats(&line).await;
                 match ret {
-                    Ok(chunks) => {
-                        for chunk in chunks {
-                            print_chunk(&chunk)
+                    Ok((chunks, stats)) => {
+                        print_chunks(&chunks);
+                        if print_stats {
+                            println!("{}", stats);
                         }
                     }
                     Err(err) => println!("{}", err),
@@ -105,9 +137,7 @@ async fn run_sql(db: Database, path: &str) -> Result<()> {
 
     info!("{}", lines);
     let chunks = db.run(&lines).await?;
-    for chunk in chunks {
-        print_chunk(&chunk)
-    }
+    print_chunks(&chunks);
 
     Ok(())
 }
@@ -192,7 +222,7 @@ async fn main() -> Result<()> {
             run_sql(db, &file).await?;
         }
     } else {
-        interactive(db).await?;
+        interactive(db, args.stats).await?;
     }
 
     Ok(())
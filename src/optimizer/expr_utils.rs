@@ -1,5 +1,7 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::collections::HashMap;
+
 use bit_set::BitSet;
 
 use super::logical_plan_rewriter::{BoolExprSimplificationRule, ExprRewriter};
@@ -61,6 +63,11 @@ pub fn input_col_refs_inner(expr: &BoundExpr, input_set: &mut BitSet) {
                 input_col_refs_inner(arg, input_set);
             }
         }
+        ScalarFunction(func) => {
+            for arg in &func.args {
+                input_col_refs_inner(arg, input_set);
+            }
+        }
         BinaryOp(binary_op) => {
             input_col_refs_inner(binary_op.left_expr.as_ref(), input_set);
             input_col_refs_inner(binary_op.right_expr.as_ref(), input_set);
@@ -68,7 +75,30 @@ pub fn input_col_refs_inner(expr: &BoundExpr, input_set: &mut BitSet) {
         UnaryOp(unary_op) => input_col_refs_inner(unary_op.expr.as_ref(), input_set),
         TypeCast(cast) => input_col_refs_inner(cast.expr.as_ref(), input_set),
         IsNull(isnull) => input_col_refs_inner(isnull.expr.as_ref(), input_set),
+        Case(case) => {
+            for (cond, result) in &case.when_then {
+                input_col_refs_inner(cond, input_set);
+                input_col_refs_inner(result, input_set);
+            }
+            if let Some(else_result) = &case.else_result {
+                input_col_refs_inner(else_result, input_set);
+            }
+        }
+        Between(between) => {
+            input_col_refs_inner(&between.expr, input_set);
+            input_col_refs_inner(&between.low, input_set);
+            input_col_refs_inner(&between.high, input_set);
+        }
+        Like(like) => {
+            input_col_refs_inner(&like.expr, input_set);
+            input_col_refs_inner(&like.pattern, input_set);
+        }
         ExprWithAlias(inner) => input_col_refs_inner(inner.expr.as_ref(), input_set),
+        Subquery(subquery) => {
+            for arg in &subquery.args {
+                input_col_refs_inner(arg, input_set);
+            }
+        }
         Constant(_) => {}
         Alias(_) => {}
     };
@@ -86,6 +116,11 @@ pub fn shift_input_col_refs(expr: &mut BoundExpr, delta: i32) {
                 shift_input_col_refs(&mut *arg, delta);
             }
         }
+        ScalarFunction(func) => {
+            for arg in &mut func.args {
+                shift_input_col_refs(&mut *arg, delta);
+            }
+        }
         BinaryOp(binary_op) => {
             shift_input_col_refs(&mut *binary_op.left_expr, delta);
             shift_input_col_refs(&mut *binary_op.right_expr, delta);
@@ -93,7 +128,140 @@ pub fn shift_input_col_refs(expr: &mut BoundExpr, delta: i32) {
         UnaryOp(unary_op) => shift_input_col_refs(&mut *unary_op.expr, delta),
         TypeCast(cast) => shift_input_col_refs(&mut *cast.expr, delta),
         IsNull(isnull) => shift_input_col_refs(&mut *isnull.expr, delta),
+        Case(case) => {
+            for (cond, result) in &mut case.when_then {
+                shift_input_col_refs(cond, delta);
+                shift_input_col_refs(result, delta);
+            }
+            if let Some(else_result) = &mut case.else_result {
+                shift_input_col_refs(else_result, delta);
+            }
+        }
+        Between(between) => {
+            shift_input_col_refs(&mut between.expr, delta);
+            shift_input_col_refs(&mut between.low, delta);
+            shift_input_col_refs(&mut between.high, delta);
+        }
+        Like(like) => {
+            shift_input_col_refs(&mut like.expr, delta);
+            shift_input_col_refs(&mut like.pattern, delta);
+        }
         ExprWithAlias(inner) => shift_input_col_refs(&mut *inner.expr, delta),
+        Subquery(subquery) => {
+            for arg in &mut subquery.args {
+                shift_input_col_refs(arg, delta);
+            }
+        }
+        Constant(_) => {}
+        Alias(_) => {}
+    };
+}
+
+/// Rewrite the input refs to the positions given by `mapping`, which must contain all the columns
+/// referenced by the expression.
+pub fn remap_input_col_refs(expr: &mut BoundExpr, mapping: &HashMap<usize, usize>) {
+    use BoundExpr::*;
+    match expr {
+        ColumnRef(_) => {}
+        InputRef(input_ref) => {
+            input_ref.index = mapping[&input_ref.index];
+        }
+        AggCall(agg) => {
+            for arg in &mut agg.args {
+                remap_input_col_refs(&mut *arg, mapping);
+            }
+        }
+        ScalarFunction(func) => {
+            for arg in &mut func.args {
+                remap_input_col_refs(&mut *arg, mapping);
+            }
+        }
+        BinaryOp(binary_op) => {
+            remap_input_col_refs(&mut *binary_op.left_expr, mapping);
+            remap_input_col_refs(&mut *binary_op.right_expr, mapping);
+        }
+        UnaryOp(unary_op) => remap_input_col_refs(&mut *unary_op.expr, mapping),
+        TypeCast(cast) => remap_input_col_refs(&mut *cast.expr, mapping),
+        IsNull(isnull) => remap_input_col_refs(&mut *isnull.expr, mapping),
+        Case(case) => {
+            for (cond, result) in &mut case.when_then {
+                remap_input_col_refs(cond, mapping);
+                remap_input_col_refs(result, mapping);
+            }
+            if let Some(else_result) = &mut case.else_result {
+                remap_input_col_refs(else_result, mapping);
+            }
+        }
+        Between(between) => {
+            remap_input_col_refs(&mut between.expr, mapping);
+            remap_input_col_refs(&mut between.low, mapping);
+            remap_input_col_refs(&mut between.high, mapping);
+        }
+        Like(like) => {
+            remap_input_col_refs(&mut like.expr, mapping);
+            remap_input_col_refs(&mut like.pattern, mapping);
+        }
+        ExprWithAlias(inner) => remap_input_col_refs(&mut *inner.expr, mapping),
+        Subquery(subquery) => {
+            for arg in &mut subquery.args {
+                remap_input_col_refs(arg, mapping);
+            }
+        }
+        Constant(_) => {}
+        Alias(_) => {}
+    };
+}
+
+/// Rewrite the input refs to the positions in the pruned input, which only outputs the columns in
+/// `cols` ordered by index.
+pub fn prune_input_col_refs(expr: &mut BoundExpr, cols: &BitSet) {
+    use BoundExpr::*;
+    match expr {
+        ColumnRef(_) => {}
+        InputRef(input_ref) => {
+            input_ref.index = cols.iter().take_while(|col| *col < input_ref.index).count();
+        }
+        AggCall(agg) => {
+            for arg in &mut agg.args {
+                prune_input_col_refs(&mut *arg, cols);
+            }
+        }
+        ScalarFunction(func) => {
+            for arg in &mut func.args {
+                prune_input_col_refs(&mut *arg, cols);
+            }
+        }
+        BinaryOp(binary_op) => {
+            prune_input_col_refs(&mut *binary_op.left_expr, cols);
+            prune_input_col_refs(&mut *binary_op.right_expr, cols);
+        }
+        UnaryOp(unary_op) => prune_input_col_refs(&mut *unary_op.expr, cols),
+        TypeCast(cast) => prune_input_col_refs(&mut *cast.expr, cols),
+        IsNull(isnull) => prune_input_col_refs(&mut *isnull.expr, cols),
+        Case(case) => {
+            for (cond, result) in &mut case.when_then {
+                prune_input_col_refs(cond, cols);
+                prune_input_col_refs(result, cols);
+            }
+            if let Some(else_result) = &mut case.else_result {
+                prune_input_col_refs(else_result, cols);
+            }
+        }
+        Between(between) => {
+            prune_input_col_refs(&mut between.expr, cols);
+            prune_input_col_refs(&mut between.low, cols);
+            prune_input_col_refs(&mut between.high, cols);
+        }
+        Like(like) => {
+            prune_input_col_refs(&mut like.expr, cols);
+            prune_input_col_refs(&mut like.pattern, cols);
+        }
+        ExprWithAlias(inner) => prune_input_col_refs(&mut *inner.expr, cols),
+        Subquery(subquery) => {
+            for arg in &mut subquery.args {
+                prune_input_col_refs(arg, cols);
+            }
+        }
         Constant(_) => {}
         Alias(_) => {}
     };
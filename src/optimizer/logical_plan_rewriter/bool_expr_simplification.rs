@@ -1,21 +1,22 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use super::*;
-use crate::binder::BoundExpr;
 use crate::binder::BoundExpr::*;
-use crate::optimizer::plan_nodes::Dummy;
+use crate::binder::{BoundExpr, BoundTypeCast};
 use crate::parser::BinaryOperator::*;
+use crate::types::DataTypeKind;
 use crate::types::DataValue::*;
 
 /// Boolean expression simplification rule will rewrite expression which compares ('>=', '<' and
 /// '=') with null. (You need `a is null`!)
 ///
-/// Moroever, when the filtering condition is always false, we will prune the child logical plan,
-/// when the filtering condition is always true, we will prune logical filter plan.
+/// Moroever, when the filtering condition is always false, we will replace the filter and its
+/// child with an empty relation, when the filtering condition is always true, we will prune
+/// logical filter plan.
 ///
 /// For example:
 ///
-/// - `select * from t where a == null` => `select ''`
+/// - `select * from t where a == null` => `select * from (values) as t`
 /// - `select * from t where 1 == 1` => `select * from t`
 pub struct BoolExprSimplificationRule;
 
@@ -34,8 +35,15 @@ impl ExprRewriter for BoolExprSimplificationRule {
                     (Or, _, Constant(Bool(true))) => Constant(Bool(true)),
                     (Or, Constant(Bool(false)), other) => other.clone(),
                     (Or, other, Constant(Bool(false))) => other.clone(),
-                    (Eq | NotEq | Gt | Lt | GtEq | LtEq, Constant(Null), _) => Constant(Null),
-                    (Eq | NotEq | Gt | Lt | GtEq | LtEq, _, Constant(Null)) => Constant(Null),
+                    (Eq | NotEq | Gt | Lt | GtEq | LtEq, left, right)
+                        if is_null(left) || is_null(right) =>
+                    {
+                        // keep the type for the parent expressions, e.g. `a = null or b`
+                        TypeCast(BoundTypeCast {
+                            expr: Box::new(Constant(Null)),
+                            ty: DataTypeKind::Boolean,
+                        })
+                    }
                     _ => BinaryOp(op.clone()),
                 }
             }
@@ -46,13 +54,23 @@ impl ExprRewriter for BoolExprSimplificationRule {
     }
 }
 
+/// Whether the expression is a NULL, or a NULL casted to a type by the binder.
+fn is_null(expr: &BoundExpr) -> bool {
+    match expr {
+        Constant(Null) => true,
+        TypeCast(cast) => matches!(&*cast.expr, Constant(Null)),
+        _ => false,
+    }
+}
+
 impl PlanRewriter for BoolExprSimplificationRule {
     fn rewrite_logical_filter(&mut self, plan: &LogicalFilter) -> PlanRef {
         let child = self.rewrite(plan.child());
         let new_plan = Arc::new(plan.clone_with_rewrite_expr(child, self));
         match &new_plan.expr() {
-            Constant(Bool(false) | Null) => Arc::new(Dummy {}),
-            Constant(Bool(true)) => return plan.child().clone(),
+            Constant(Bool(false)) => Arc::new(LogicalValues::empty(plan.schema())),
+            expr if is_null(expr) => Arc::new(LogicalValues::empty(plan.schema())),
+            Constant(Bool(true)) => new_plan.child(),
             _ => new_plan,
         }
     }
@@ -0,0 +1,331 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::binder::BoundExpr::*;
+use crate::binder::{BoundExpr, BoundInputRef, ScalarFunctionKind};
+
+/// Common subexpression elimination rule makes the expressions appearing more than once in a
+/// projection or a filter evaluated only once.
+///
+/// The common subexpressions are evaluated by a new projection below, which also passes the
+/// columns of the child through, and their occurrences are replaced with references to its output
+/// columns. A projection is added above a filter to remove the extra columns.
+///
+/// For example,
+/// `select substring(v, 1, 3), upper(substring(v, 1, 3)) from t;`
+/// will be rewritten into
+/// `select #1, upper(#1) from (select v, substring(v, 1, 3) from t);`
+pub struct CommonSubexprEliminationRule;
+
+impl PlanRewriter for CommonSubexprEliminationRule {
+    fn rewrite_logical_projection(&mut self, plan: &LogicalProjection) -> PlanRef {
+        let child = self.rewrite(plan.child());
+        let mut exprs = plan.project_expressions().to_vec();
+        match extract_common_subexprs(&mut exprs, &child) {
+            Some(new_child) => Arc::new(LogicalProjection::new(exprs, new_child)),
+            None => Arc::new(plan.clone_with_child(child)),
+        }
+    }
+
+    fn rewrite_logical_filter(&mut self, plan: &LogicalFilter) -> PlanRef {
+        let child = self.rewrite(plan.child());
+        let mut exprs = vec![plan.expr().clone()];
+        let new_child = match extract_common_subexprs(&mut exprs, &child) {
+            Some(new_child) => new_child,
+            None => return Arc::new(plan.clone_with_child(child)),
+        };
+        let filter = Arc::new(LogicalFilter::new(exprs.pop().unwrap(), new_child));
+        Arc::new(LogicalProjection::new(input_refs(&child), filter))
+    }
+
+    // The row handlers appended to the output of scans are not in the schema, so they would be
+    // dropped by the new projections.
+    fn rewrite_logical_delete(&mut self, plan: &LogicalDelete) -> PlanRef {
+        Arc::new(plan.clone())
+    }
+
+    fn rewrite_logical_update(&mut self, plan: &LogicalUpdate) -> PlanRef {
+        Arc::new(plan.clone())
+    }
+}
+
+/// Replace the common subexpressions in `exprs` with references to the columns of a projection
+/// evaluating them on `child`, and return the projection.
+///
+/// Returns `None` if there is no common subexpression.
+fn extract_common_subexprs(exprs: &mut [BoundExpr], child: &PlanRef) -> Option<PlanRef> {
+    // `BoundExpr` can not be hashed, so the expressions are counted in a list
+    let mut counts = vec![];
+    for expr in exprs.iter() {
+        count_subexprs(expr, &mut counts);
+    }
+    let mut commons = vec![];
+    for expr in exprs.iter() {
+        find_common_subexprs(expr, &counts, &mut commons);
+    }
+    if commons.is_empty() {
+        return None;
+    }
+    let mut project_expressions = input_refs(child);
+    let offset = project_expressions.len();
+    for expr in exprs.iter_mut() {
+        replace_common_subexprs(expr, &commons, offset);
+    }
+    project_expressions.extend(commons);
+    Some(Arc::new(LogicalProjection::new(
+        project_expressions,
+        child.clone(),
+    )))
+}
+
+fn count_subexprs(expr: &BoundExpr, counts: &mut Vec<(BoundExpr, usize)>) {
+    if is_computed(expr) {
+        match counts.iter_mut().find(|(e, _)| *e == *expr) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((expr.clone(), 1)),
+        }
+    }
+    for child in evaluated_children(expr) {
+        count_subexprs(child, counts);
+    }
+}
+
+/// Find the outermost expressions appearing more than once.
+fn find_common_subexprs(
+    expr: &BoundExpr,
+    counts: &[(BoundExpr, usize)],
+    commons: &mut Vec<BoundExpr>,
+) {
+    if counts.iter().any(|(e, count)| e == expr && *count > 1) {
+        if !commons.contains(expr) {
+            commons.push(expr.clone());
+        }
+        return;
+    }
+    for child in evaluated_children(expr) {
+        find_common_subexprs(child, counts, commons);
+    }
+}
+
+fn replace_common_subexprs(expr: &mut BoundExpr, commons: &[BoundExpr], offset: usize) {
+    if let Some(index) = commons.iter().position(|e| *e == *expr) {
+        *expr = InputRef(BoundInputRef {
+            index: offset + index,
+            return_type: expr.return_type().unwrap(),
+        });
+        return;
+    }
+    for child in evaluated_children_mut(expr) {
+        replace_common_subexprs(child, commons, offset);
+    }
+}
+
+/// Whether the expression is worth evaluating only once.
+fn is_computed(expr: &BoundExpr) -> bool {
+    let computed = matches!(
+        expr,
+        BinaryOp(_)
+            | UnaryOp(_)
+            | TypeCast(_)
+            | ScalarFunction(_)
+            | IsNull(_)
+            | Case(_)
+            | Between(_)
+            | Like(_)
+    );
+    computed && expr.return_type().is_some()
+}
+
+/// Get the children of an expression which are always evaluated with it.
+///
+/// The arguments of `coalesce` except the first one are skipped, since they are not evaluated
+/// once the previous arguments are not null.
+fn evaluated_children(expr: &BoundExpr) -> Vec<&BoundExpr> {
+    match expr {
+        BinaryOp(op) => vec![&*op.left_expr, &*op.right_expr],
+        UnaryOp(op) => vec![&*op.expr],
+        TypeCast(cast) => vec![&*cast.expr],
+        IsNull(isnull) => vec![&*isnull.expr],
+        ScalarFunction(func) if func.kind == ScalarFunctionKind::Coalesce => {
+            func.args.iter().take(1).collect()
+        }
+        ScalarFunction(func) => func.args.iter().collect(),
+        Case(case) => (case.when_then.iter())
+            .flat_map(|(cond, result)| [cond, result])
+            .chain(case.else_result.as_deref())
+            .collect(),
+        Between(between) => vec![&*between.expr, &*between.low, &*between.high],
+        Like(like) => vec![&*like.expr, &*like.pattern],
+        ExprWithAlias(expr_with_alias) => vec![&*expr_with_alias.expr],
+        _ => vec![],
+    }
+}
+
+/// The mutable version of [`evaluated_children`].
+fn evaluated_children_mut(expr: &mut BoundExpr) -> Vec<&mut BoundExpr> {
+    match expr {
+        BinaryOp(op) => vec![&mut *op.left_expr, &mut *op.right_expr],
+        UnaryOp(op) => vec![&mut *op.expr],
+        TypeCast(cast) => vec![&mut *cast.expr],
+        IsNull(isnull) => vec![&mut *isnull.expr],
+        ScalarFunction(func) if func.kind == ScalarFunctionKind::Coalesce => {
+            func.args.iter_mut().take(1).collect()
+        }
+        ScalarFunction(func) => func.args.iter_mut().collect(),
+        Case(case) => (case.when_then.iter_mut())
+            .flat_map(|(cond, result)| [cond, result])
+            .chain(case.else_result.as_deref_mut())
+            .collect(),
+        Between(between) => vec![&mut *between.expr, &mut *between.low, &mut *between.high],
+        Like(like) => vec![&mut *like.expr, &mut *like.pattern],
+        ExprWithAlias(expr_with_alias) => vec![&mut *expr_with_alias.expr],
+        _ => vec![],
+    }
+}
+
+/// Get the references to all columns of the plan.
+fn input_refs(plan: &PlanRef) -> Vec<BoundExpr> {
+    (plan.out_types().into_iter().enumerate())
+        .map(|(index, return_type)| InputRef(BoundInputRef { index, return_type }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binder::{BoundBinaryOp, BoundIsNull, BoundScalarFunction};
+    use crate::catalog::TableRefId;
+    use crate::parser::BinaryOperator;
+    use crate::types::{DataTypeExt, DataTypeKind, DataValue};
+
+    fn scan() -> PlanRef {
+        Arc::new(LogicalTableScan::new(
+            TableRefId::new(0, 0, 0),
+            vec![0, 1],
+            vec![
+                DataTypeKind::String.nullable().to_column("v".into()),
+                DataTypeKind::Int(None).nullable().to_column("w".into()),
+            ],
+            false,
+            false,
+            None,
+        ))
+    }
+
+    fn input_ref(index: usize) -> BoundExpr {
+        let return_type = match index {
+            0 => DataTypeKind::String.nullable(),
+            _ => DataTypeKind::Int(None).nullable(),
+        };
+        InputRef(BoundInputRef { index, return_type })
+    }
+
+    fn func(kind: ScalarFunctionKind, args: Vec<BoundExpr>) -> BoundExpr {
+        ScalarFunction(BoundScalarFunction {
+            kind,
+            args,
+            return_type: DataTypeKind::String.nullable(),
+        })
+    }
+
+    /// substring(v, 1, 3)
+    fn substring() -> BoundExpr {
+        func(
+            ScalarFunctionKind::Substring,
+            vec![
+                input_ref(0),
+                Constant(DataValue::Int32(1)),
+                Constant(DataValue::Int32(3)),
+            ],
+        )
+    }
+
+    /// Count how many times `target` is evaluated for each row by the plan.
+    fn evaluations(plan: &PlanRef, target: &BoundExpr) -> usize {
+        fn count(expr: &BoundExpr, target: &BoundExpr) -> usize {
+            let children = evaluated_children(expr);
+            let count_children: usize = children.into_iter().map(|e| count(e, target)).sum();
+            count_children + (expr == target) as usize
+        }
+        let in_node = if let Some(projection) = plan.downcast_ref::<LogicalProjection>() {
+            (projection.project_expressions().iter())
+                .map(|e| count(e, target))
+                .sum()
+        } else if let Some(filter) = plan.downcast_ref::<LogicalFilter>() {
+            count(filter.expr(), target)
+        } else {
+            0
+        };
+        let in_children: usize = (plan.children().iter())
+            .map(|child| evaluations(child, target))
+            .sum();
+        in_node + in_children
+    }
+
+    #[test]
+    fn eliminate_common_subexprs_in_projection() {
+        let upper = func(ScalarFunctionKind::Upper, vec![substring()]);
+        let plan: PlanRef = Arc::new(LogicalProjection::new(
+            vec![substring(), upper, input_ref(1)],
+            scan(),
+        ));
+        assert_eq!(evaluations(&plan, &substring()), 2);
+
+        let plan = CommonSubexprEliminationRule.rewrite(plan);
+        assert_eq!(evaluations(&plan, &substring()), 1);
+        let projection = plan.downcast_ref::<LogicalProjection>().unwrap();
+        assert_eq!(
+            projection.project_expressions(),
+            &[
+                input_ref(2),
+                func(ScalarFunctionKind::Upper, vec![input_ref(2)]),
+                input_ref(1)
+            ]
+        );
+        let child = projection.child();
+        let child = child.downcast_ref::<LogicalProjection>().unwrap();
+        assert_eq!(
+            child.project_expressions(),
+            &[input_ref(0), input_ref(1), substring()]
+        );
+    }
+
+    #[test]
+    fn eliminate_common_subexprs_in_filter() {
+        // substring(v, 1, 3) = 'abc' or substring(v, 1, 3) is null
+        let plan: PlanRef = Arc::new(LogicalFilter::new(
+            BinaryOp(BoundBinaryOp {
+                op: BinaryOperator::Or,
+                left_expr: Box::new(BinaryOp(BoundBinaryOp {
+                    op: BinaryOperator::Eq,
+                    left_expr: Box::new(substring()),
+                    right_expr: Box::new(Constant(DataValue::String("abc".into()))),
+                    return_type: Some(DataTypeKind::Boolean.nullable()),
+                })),
+                right_expr: Box::new(IsNull(BoundIsNull {
+                    expr: Box::new(substring()),
+                })),
+                return_type: Some(DataTypeKind::Boolean.nullable()),
+            }),
+            scan(),
+        ));
+        assert_eq!(evaluations(&plan, &substring()), 2);
+
+        let plan = CommonSubexprEliminationRule.rewrite(plan);
+        assert_eq!(evaluations(&plan, &substring()), 1);
+        // the extra column is removed from the output
+        assert_eq!(plan.out_types(), scan().out_types());
+
+        // expressions appearing once are kept
+        let plan: PlanRef = Arc::new(LogicalProjection::new(
+            vec![substring(), input_ref(0)],
+            scan(),
+        ));
+        let plan = CommonSubexprEliminationRule.rewrite(plan);
+        let projection = plan.downcast_ref::<LogicalProjection>().unwrap();
+        assert!(projection
+            .child()
+            .downcast_ref::<LogicalTableScan>()
+            .is_some());
+    }
+}
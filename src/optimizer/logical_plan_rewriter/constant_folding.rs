@@ -1,8 +1,10 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use super::*;
-use crate::array::ArrayImpl;
+use crate::array::DataChunk;
 use crate::binder::BoundExpr;
+use crate::parser::BinaryOperator;
+use crate::types::{DataType, DataValue};
 
 /// Constant folding rule aims to evalute the constant expression before query execution.
 ///
@@ -10,9 +12,35 @@ use crate::binder::BoundExpr;
 /// `select 3 * 2 * a from t where a >= 100 * 30;`
 /// The rule will convert it into
 /// `select 6 * a from t where a >= 3000;`
+///
+/// `AND` and `OR` are short-circuited when either side is a constant that decides the result,
+/// e.g. `false and a > 1` is folded into `false`.
 #[derive(Default)]
 pub struct ConstantFoldingRule;
 
+impl ConstantFoldingRule {
+    /// Evaluate the expression whose children are constants, and replace it with the result.
+    ///
+    /// The expression is kept if the evaluation fails, so that the error is raised on execution,
+    /// or if the result does not have the type of the expression, e.g. a typed NULL.
+    fn fold(expr: &mut BoundExpr) {
+        let array = match expr.eval(&DataChunk::single(0)) {
+            Ok(array) => array,
+            Err(_) => return,
+        };
+        let value = array.get(0);
+        let physical_kind = |ty: Option<DataType>| ty.map(|ty| ty.physical_kind());
+        if physical_kind(value.data_type()) == physical_kind(expr.return_type()) {
+            *expr = BoundExpr::Constant(value);
+        }
+    }
+}
+
+/// Whether the expression is a constant other than NULL.
+fn is_non_null_constant(expr: &BoundExpr) -> bool {
+    matches!(expr, BoundExpr::Constant(v) if *v != DataValue::Null)
+}
+
 impl ExprRewriter for ConstantFoldingRule {
     fn rewrite_expr(&self, expr: &mut BoundExpr) {
         use BoundExpr::*;
@@ -20,29 +48,31 @@ impl ExprRewriter for ConstantFoldingRule {
             BinaryOp(op) => {
                 self.rewrite_expr(&mut *op.left_expr);
                 self.rewrite_expr(&mut *op.right_expr);
-                if let (Constant(v1), Constant(v2)) = (&*op.left_expr, &*op.right_expr) {
-                    let res = ArrayImpl::from(v1)
-                        .binary_op(&op.op, &ArrayImpl::from(v2))
-                        .get(0);
-                    *expr = Constant(res);
+                let short_circuit = match (&op.op, &*op.left_expr, &*op.right_expr) {
+                    (BinaryOperator::And, Constant(DataValue::Bool(false)), _)
+                    | (BinaryOperator::And, _, Constant(DataValue::Bool(false))) => Some(false),
+                    (BinaryOperator::Or, Constant(DataValue::Bool(true)), _)
+                    | (BinaryOperator::Or, _, Constant(DataValue::Bool(true))) => Some(true),
+                    _ => None,
+                };
+                if let Some(result) = short_circuit {
+                    *expr = Constant(DataValue::Bool(result));
+                } else if is_non_null_constant(&op.left_expr)
+                    && is_non_null_constant(&op.right_expr)
+                {
+                    Self::fold(expr);
                 }
             }
             UnaryOp(op) => {
                 self.rewrite_expr(&mut *op.expr);
-                if let Constant(v) = &*op.expr {
-                    let res = ArrayImpl::from(v).unary_op(&op.op).get(0);
-                    *expr = Constant(res);
+                if is_non_null_constant(&op.expr) {
+                    Self::fold(expr);
                 }
             }
             TypeCast(cast) => {
                 self.rewrite_expr(&mut *cast.expr);
-                if let Constant(v) = &*cast.expr {
-                    if let Ok(array) = ArrayImpl::from(v).try_cast(cast.ty.clone()) {
-                        let res = array.get(0);
-                        *expr = Constant(res);
-                    }
-                    // ignore if cast failed
-                    // TODO: raise an error
+                if is_non_null_constant(&cast.expr) {
+                    Self::fold(expr);
                 }
             }
             AggCall(agg_call) => {
@@ -50,6 +80,32 @@ impl ExprRewriter for ConstantFoldingRule {
                     self.rewrite_expr(expr);
                 }
             }
+            ScalarFunction(func) => {
+                for expr in &mut func.args {
+                    self.rewrite_expr(expr);
+                }
+                if func.args.iter().all(is_non_null_constant) {
+                    Self::fold(expr);
+                }
+            }
+            Case(case) => {
+                for (cond, result) in &mut case.when_then {
+                    self.rewrite_expr(cond);
+                    self.rewrite_expr(result);
+                }
+                if let Some(else_result) = &mut case.else_result {
+                    self.rewrite_expr(else_result);
+                }
+            }
+            Between(between) => {
+                self.rewrite_expr(&mut between.expr);
+                self.rewrite_expr(&mut between.low);
+                self.rewrite_expr(&mut between.high);
+            }
+            Like(like) => {
+                self.rewrite_expr(&mut like.expr);
+                self.rewrite_expr(&mut like.pattern);
+            }
             _ => {}
         }
     }
@@ -83,3 +139,91 @@ impl PlanRewriter for ConstantFoldingRule {
         Arc::new(plan.clone_with_rewrite_expr(self))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binder::{BoundBinaryOp, BoundInputRef};
+    use crate::types::{DataTypeExt, DataTypeKind};
+    use crate::Database;
+
+    fn binary(op: BinaryOperator, left: BoundExpr, right: BoundExpr, ty: DataType) -> BoundExpr {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op,
+            left_expr: Box::new(left),
+            right_expr: Box::new(right),
+            return_type: Some(ty),
+        })
+    }
+
+    #[test]
+    fn fold_constant_expressions() {
+        let int = || DataTypeKind::Int(None).not_null();
+        let boolean = || DataTypeKind::Boolean.not_null();
+        let constant = |v| BoundExpr::Constant(DataValue::Int32(v));
+        let a = BoundExpr::InputRef(BoundInputRef {
+            index: 0,
+            return_type: int(),
+        });
+        let fold = |mut expr: BoundExpr| {
+            ConstantFoldingRule.rewrite_expr(&mut expr);
+            expr
+        };
+
+        // a + (2 * 3) => a + 6
+        let expr = binary(
+            BinaryOperator::Plus,
+            a.clone(),
+            binary(BinaryOperator::Multiply, constant(2), constant(3), int()),
+            int(),
+        );
+        assert_eq!(
+            fold(expr),
+            binary(BinaryOperator::Plus, a.clone(), constant(6), int())
+        );
+
+        // 1 = 1 => true
+        let expr = binary(BinaryOperator::Eq, constant(1), constant(1), boolean());
+        assert_eq!(fold(expr), BoundExpr::Constant(DataValue::Bool(true)));
+
+        // (1 > 2) and (a > 1) => false
+        let expr = binary(
+            BinaryOperator::And,
+            binary(BinaryOperator::Gt, constant(1), constant(2), boolean()),
+            binary(BinaryOperator::Gt, a.clone(), constant(1), boolean()),
+            boolean(),
+        );
+        assert_eq!(fold(expr), BoundExpr::Constant(DataValue::Bool(false)));
+
+        // (a > 1) or (1 < 2) => true
+        let expr = binary(
+            BinaryOperator::Or,
+            binary(BinaryOperator::Gt, a.clone(), constant(1), boolean()),
+            binary(BinaryOperator::Lt, constant(1), constant(2), boolean()),
+            boolean(),
+        );
+        assert_eq!(fold(expr), BoundExpr::Constant(DataValue::Bool(true)));
+
+        // 1 / 0 is kept to raise the error on execution
+        let expr = binary(BinaryOperator::Divide, constant(1), constant(0), int());
+        assert_eq!(fold(expr.clone()), expr);
+    }
+
+    #[tokio::test]
+    async fn where_false_is_empty_scan() {
+        let db = Database::new_in_memory();
+        db.run("create table t(a int)").await.unwrap();
+        db.run("insert into t values (1), (2)").await.unwrap();
+
+        let plans = db
+            .generate_execution_plan("select a from t where 1 > 2")
+            .unwrap();
+        let mut explain = String::new();
+        plans[0].explain(0, &mut explain).unwrap();
+        assert!(explain.contains("PhysicalValues: 0 rows"), "{}", explain);
+        assert!(!explain.contains("TableScan"), "{}", explain);
+
+        let chunks = db.run("select a from t where false").await.unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.cardinality() == 0));
+    }
+}
@@ -1,5 +1,7 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use itertools::Itertools;
+
 use super::super::plan_nodes::*;
 use super::*;
 use crate::binder::BoundJoinOperator;
@@ -24,39 +26,86 @@ impl PlanRewriter for PhysicalConverter {
     }
 
     fn rewrite_logical_limit(&mut self, logical: &LogicalLimit) -> PlanRef {
+        // merge order and limit into top N, so that only the first rows are kept
+        if let Ok(order) = logical.child().as_logical_order() {
+            let child = self.rewrite(order.child());
+            return Arc::new(PhysicalTopN::new(
+                logical.offset(),
+                logical.limit(),
+                order.comparators().to_vec(),
+                child,
+            ));
+        }
         let child = self.rewrite(logical.child());
         let logical = logical.clone_with_child(child);
         Arc::new(PhysicalLimit::new(logical))
     }
 
+    fn rewrite_logical_distinct(&mut self, logical: &LogicalDistinct) -> PlanRef {
+        let child = self.rewrite(logical.child());
+        let logical = logical.clone_with_child(child);
+        Arc::new(PhysicalDistinct::new(logical))
+    }
+
+    fn rewrite_logical_set_operation(&mut self, logical: &LogicalSetOperation) -> PlanRef {
+        let left = self.rewrite(logical.left());
+        let right = self.rewrite(logical.right());
+        let logical = logical.clone_with_left_right(left, right);
+        Arc::new(PhysicalSetOperation::new(logical))
+    }
+
     fn rewrite_logical_join(&mut self, logical_join: &LogicalJoin) -> PlanRef {
         let left = self.rewrite(logical_join.left());
         let right = self.rewrite(logical_join.right());
         let predicate = logical_join.predicate();
-        // FIXME: Currently just Inner join use HashJoin
-        if !predicate.eq_keys().is_empty() && logical_join.join_op() == BoundJoinOperator::Inner {
-            // TODO: Currently hash join just use one column pair as hash index
-            // TODO: Currently HashJoinExecutor ignores the condition, so for correctness we pull
-            // the conditions as a filter operator. And this transformation is only correct for
-            // inner join
+        let join_op = logical_join.join_op();
+        // TODO: Currently HashJoinExecutor ignores the conditions other than equal keys, so for
+        // correctness we pull them as a filter operator. And this transformation is only correct
+        // for inner join
+        let has_other_conds = !predicate.left_conds().is_empty()
+            || !predicate.right_conds().is_empty()
+            || !predicate.other_conds().is_empty();
+        let use_hash_join = match join_op {
+            BoundJoinOperator::Inner => true,
+            BoundJoinOperator::LeftOuter
+            | BoundJoinOperator::LeftSemi
+            | BoundJoinOperator::LeftAnti => !has_other_conds,
+            // NULLs in the build side only make a single key unknown for all probe rows
+            BoundJoinOperator::NullAwareLeftAnti => {
+                !has_other_conds && predicate.eq_keys().len() == 1
+            }
+            _ => false,
+        };
+        if !predicate.eq_keys().is_empty() && use_hash_join {
             let left_col_num = left.out_types().len();
-            let (left_column_index, right_column_index) = predicate.eq_keys()[0].clone();
-            let join = Arc::new(PhysicalHashJoin::new(
-                LogicalJoin::create(
-                    left,
-                    right,
-                    BoundJoinOperator::Inner,
-                    BoundExpr::Constant(DataValue::Bool(true)),
-                ),
-                left_column_index.index,
-                right_column_index.index - left_col_num,
-            ));
-            // Currently hash join just use one column pair as hash index
-            let need_pull_filter = predicate.eq_keys().len() != 1
-                || !predicate.left_conds().is_empty()
-                || !predicate.right_conds().is_empty()
-                || !predicate.other_conds().is_empty();
-            if need_pull_filter {
+            let (left_column_indexes, right_column_indexes): (Vec<_>, Vec<_>) =
+                (predicate.eq_keys().iter())
+                    .map(|(l, r)| (l.index, r.index - left_col_num))
+                    .unzip();
+            // merge the sorted inputs instead of building a hash table
+            let merge_keys = match join_op {
+                BoundJoinOperator::Inner => {
+                    merge_join_keys(&left, &right, &left_column_indexes, &right_column_indexes)
+                }
+                _ => None,
+            };
+            let logical = LogicalJoin::create(
+                left,
+                right,
+                join_op,
+                BoundExpr::Constant(DataValue::Bool(true)),
+            );
+            let join: PlanRef = match merge_keys {
+                Some((left_keys, right_keys)) => {
+                    Arc::new(PhysicalMergeJoin::new(logical, left_keys, right_keys))
+                }
+                None => Arc::new(PhysicalHashJoin::new(
+                    logical,
+                    left_column_indexes,
+                    right_column_indexes,
+                )),
+            };
+            if has_other_conds {
                 return Arc::new(PhysicalFilter::new(LogicalFilter::new(
                     predicate.to_on_clause(),
                     join,
@@ -96,16 +145,40 @@ impl PlanRewriter for PhysicalConverter {
         Arc::new(PhysicalDrop::new(logical.clone()))
     }
 
+    fn rewrite_logical_truncate(&mut self, logical: &LogicalTruncate) -> PlanRef {
+        Arc::new(PhysicalTruncate::new(logical.clone()))
+    }
+
+    fn rewrite_logical_create_index(&mut self, logical: &LogicalCreateIndex) -> PlanRef {
+        Arc::new(PhysicalCreateIndex::new(logical.clone()))
+    }
+
     fn rewrite_logical_delete(&mut self, logical: &LogicalDelete) -> PlanRef {
         let child = self.rewrite(logical.child());
         let logical = logical.clone_with_child(child);
         Arc::new(PhysicalDelete::new(logical))
     }
 
+    fn rewrite_logical_analyze(&mut self, logical: &LogicalAnalyze) -> PlanRef {
+        let child = self.rewrite(logical.child());
+        let logical = logical.clone_with_child(child);
+        Arc::new(PhysicalAnalyze::new(logical))
+    }
+
+    fn rewrite_logical_update(&mut self, logical: &LogicalUpdate) -> PlanRef {
+        let child = self.rewrite(logical.child());
+        let logical = logical.clone_with_child(child);
+        Arc::new(PhysicalUpdate::new(logical))
+    }
+
     fn rewrite_logical_create_table(&mut self, logical: &LogicalCreateTable) -> PlanRef {
         Arc::new(PhysicalCreateTable::new(logical.clone()))
     }
 
+    fn rewrite_logical_alter_table(&mut self, logical: &LogicalAlterTable) -> PlanRef {
+        Arc::new(PhysicalAlterTable::new(logical.clone()))
+    }
+
     fn rewrite_logical_copy_from_file(&mut self, logical: &LogicalCopyFromFile) -> PlanRef {
         Arc::new(PhysicalCopyFromFile::new(logical.clone()))
     }
@@ -129,3 +202,68 @@ impl PlanRewriter for PhysicalConverter {
         }
     }
 }
+
+/// The columns by which the rows of the plan are sorted in ascending order. The rows are sorted if
+/// they come from a sorted table scan, which are sorted by the primary key, or from an order by
+/// columns.
+fn sort_columns(plan: &PlanRef) -> Vec<usize> {
+    if let Ok(filter) = plan.as_physical_filter() {
+        return sort_columns(&filter.child());
+    }
+    if let Ok(order) = plan.as_physical_order() {
+        return (order.logical().comparators().iter())
+            .map_while(|cmp| match &cmp.expr {
+                BoundExpr::InputRef(input_ref) if !cmp.descending => Some(input_ref.index),
+                _ => None,
+            })
+            .collect();
+    }
+    match plan.as_physical_table_scan() {
+        Ok(scan) if scan.logical().is_sorted() => {}
+        _ => return vec![],
+    }
+    let schema = plan.schema();
+    let mut columns = vec![];
+    for key_index in 0.. {
+        match schema
+            .iter()
+            .position(|desc| desc.primary_key_index() == Some(key_index))
+        {
+            Some(column) => columns.push(column),
+            None => break,
+        }
+    }
+    columns
+}
+
+/// Get the join keys in the order by which both inputs are sorted, if the inputs can be merged.
+/// It holds if the keys of each side are a prefix of its sort columns, and the keys of both sides
+/// are at the same positions.
+fn merge_join_keys(
+    left: &PlanRef,
+    right: &PlanRef,
+    left_keys: &[usize],
+    right_keys: &[usize],
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    let left_sort_columns = sort_columns(left);
+    let right_sort_columns = sort_columns(right);
+    let mut keys = vec![];
+    for (&left_key, &right_key) in left_keys.iter().zip_eq(right_keys) {
+        let position = left_sort_columns.iter().position(|&c| c == left_key)?;
+        if right_sort_columns.get(position) != Some(&right_key) {
+            return None;
+        }
+        keys.push((position, left_key, right_key));
+    }
+    keys.sort_unstable();
+    keys.dedup();
+    if keys.is_empty()
+        || keys
+            .iter()
+            .enumerate()
+            .any(|(i, (position, ..))| i != *position)
+    {
+        return None;
+    }
+    Some(keys.into_iter().map(|(_, l, r)| (l, r)).unzip())
+}
@@ -0,0 +1,296 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use super::*;
+use crate::binder::BoundExpr;
+use crate::binder::BoundExpr::*;
+use crate::optimizer::expr_utils::{conjunctions, merge_conjunctions};
+use crate::parser::BinaryOperator;
+use crate::types::DataValue;
+
+/// Filter simplification rule removes redundant filters and filtering conditions.
+///
+/// - Stacked filters are merged into one filter on the conjunction of their conditions.
+/// - Conditions that are always true and duplicated conditions are removed. The filter is removed
+///   if no condition is left.
+/// - If the conditions can never be satisfied together, e.g. `a > 5 and a < 3`, the filter and
+///   its child are replaced with an empty relation.
+///
+/// For example,
+/// `select * from (select * from t where a > 1) where true and a > 1 and b = 2;`
+/// The rule will convert it into
+/// `select * from t where a > 1 and b = 2;`
+pub struct FilterSimplificationRule;
+
+impl PlanRewriter for FilterSimplificationRule {
+    fn rewrite_logical_filter(&mut self, plan: &LogicalFilter) -> PlanRef {
+        let child = self.rewrite(plan.child());
+        // the filter below has been simplified, and its conditions are evaluated first
+        let below = (child.downcast_ref::<LogicalFilter>())
+            .map(|filter| (conjunctions(filter.expr().clone()), filter.child()));
+        let (mut conds, child) = below.unwrap_or((vec![], child));
+        conds.extend(conjunctions(plan.expr().clone()));
+
+        let mut kept = vec![];
+        for cond in conds {
+            match cond {
+                Constant(DataValue::Bool(true)) => {}
+                Constant(DataValue::Bool(false) | DataValue::Null) => {
+                    return Arc::new(LogicalValues::empty(plan.schema()))
+                }
+                _ if kept.contains(&cond) => {}
+                _ => kept.push(cond),
+            }
+        }
+        if is_contradictory(&kept) {
+            return Arc::new(LogicalValues::empty(plan.schema()));
+        }
+        if kept.is_empty() {
+            return child;
+        }
+        Arc::new(LogicalFilter::new(
+            merge_conjunctions(kept.into_iter()),
+            child,
+        ))
+    }
+}
+
+/// One end of the range of values satisfying a comparison.
+struct Bound<'a> {
+    value: &'a DataValue,
+    inclusive: bool,
+}
+
+/// The range of values of a column satisfying a comparison with a constant.
+struct Range<'a> {
+    lower: Option<Bound<'a>>,
+    upper: Option<Bound<'a>>,
+    /// The value excluded by `<>`.
+    excluded: Option<&'a DataValue>,
+}
+
+impl<'a> Range<'a> {
+    /// Get the range of `column op value`.
+    fn new(op: &BinaryOperator, value: &'a DataValue) -> Option<Self> {
+        let bound = |inclusive| Some(Bound { value, inclusive });
+        let (lower, upper, excluded) = match op {
+            BinaryOperator::Eq => (bound(true), bound(true), None),
+            BinaryOperator::NotEq => (None, None, Some(value)),
+            BinaryOperator::Lt => (None, bound(false), None),
+            BinaryOperator::LtEq => (None, bound(true), None),
+            BinaryOperator::Gt => (bound(false), None, None),
+            BinaryOperator::GtEq => (bound(true), None, None),
+            _ => return None,
+        };
+        Some(Range {
+            lower,
+            upper,
+            excluded,
+        })
+    }
+
+    /// Whether the range only contains one value.
+    fn point(&self) -> Option<&'a DataValue> {
+        match (&self.lower, &self.upper) {
+            (Some(lower), Some(upper)) if lower.value == upper.value => Some(lower.value),
+            _ => None,
+        }
+    }
+
+    /// Whether no value is in both ranges.
+    fn is_disjoint(&self, other: &Self) -> bool {
+        is_below(&self.upper, &other.lower)
+            || is_below(&other.upper, &self.lower)
+            || (self.point().is_some() && self.point() == other.excluded)
+            || (other.point().is_some() && other.point() == self.excluded)
+    }
+}
+
+/// Whether all values below the upper bound are below the lower bound.
+fn is_below(upper: &Option<Bound>, lower: &Option<Bound>) -> bool {
+    match (upper, lower) {
+        (Some(upper), Some(lower)) => match upper.value.partial_cmp(lower.value) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => !(upper.inclusive && lower.inclusive),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Whether the conditions can not be satisfied together, i.e. two comparisons of a column with
+/// constants of the same type have no common value.
+fn is_contradictory(conds: &[BoundExpr]) -> bool {
+    let mut ranges: HashMap<usize, Vec<(Range, &DataValue)>> = HashMap::new();
+    for cond in conds {
+        let op = match cond {
+            BinaryOp(op) => op,
+            _ => continue,
+        };
+        let (index, value, op) = match (&*op.left_expr, &*op.right_expr) {
+            (InputRef(input_ref), Constant(value)) => (input_ref.index, value, op.op.clone()),
+            (Constant(value), InputRef(input_ref)) => match flip(&op.op) {
+                Some(flipped) => (input_ref.index, value, flipped),
+                None => continue,
+            },
+            _ => continue,
+        };
+        if *value == DataValue::Null {
+            continue;
+        }
+        if let Some(range) = Range::new(&op, value) {
+            ranges.entry(index).or_default().push((range, value));
+        }
+    }
+    ranges.values().any(|ranges| {
+        ranges
+            .iter()
+            .tuple_combinations()
+            .any(|((r1, v1), (r2, v2))| {
+                // values of different types are not comparable
+                std::mem::discriminant(*v1) == std::mem::discriminant(*v2) && r1.is_disjoint(r2)
+            })
+    })
+}
+
+/// Get the operator after swapping the operands of a comparison.
+fn flip(op: &BinaryOperator) -> Option<BinaryOperator> {
+    Some(match op {
+        BinaryOperator::Eq => BinaryOperator::Eq,
+        BinaryOperator::NotEq => BinaryOperator::NotEq,
+        BinaryOperator::Lt => BinaryOperator::Gt,
+        BinaryOperator::LtEq => BinaryOperator::GtEq,
+        BinaryOperator::Gt => BinaryOperator::Lt,
+        BinaryOperator::GtEq => BinaryOperator::LtEq,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binder::{BoundBinaryOp, BoundInputRef};
+    use crate::catalog::TableRefId;
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    fn scan() -> PlanRef {
+        Arc::new(LogicalTableScan::new(
+            TableRefId::new(0, 0, 0),
+            vec![0, 1],
+            ["a", "b"]
+                .iter()
+                .map(|name| {
+                    DataTypeKind::Int(None)
+                        .nullable()
+                        .to_column(name.to_string())
+                })
+                .collect(),
+            false,
+            false,
+            None,
+        ))
+    }
+
+    fn cmp(op: BinaryOperator, index: usize, value: i32) -> BoundExpr {
+        BinaryOp(BoundBinaryOp {
+            op,
+            left_expr: Box::new(InputRef(BoundInputRef {
+                index,
+                return_type: DataTypeKind::Int(None).nullable(),
+            })),
+            right_expr: Box::new(Constant(DataValue::Int32(value))),
+            return_type: Some(DataTypeKind::Boolean.nullable()),
+        })
+    }
+
+    fn filter(conds: Vec<BoundExpr>, child: PlanRef) -> PlanRef {
+        Arc::new(LogicalFilter::new(
+            merge_conjunctions(conds.into_iter()),
+            child,
+        ))
+    }
+
+    #[test]
+    fn merge_stacked_filters() {
+        let plan = filter(
+            vec![
+                Constant(DataValue::Bool(true)),
+                cmp(BinaryOperator::Gt, 0, 1),
+                cmp(BinaryOperator::Eq, 1, 2),
+            ],
+            filter(vec![cmp(BinaryOperator::Gt, 0, 1)], scan()),
+        );
+        let plan = FilterSimplificationRule.rewrite(plan);
+        let merged = plan.downcast_ref::<LogicalFilter>().unwrap();
+        assert!(merged.child().downcast_ref::<LogicalTableScan>().is_some());
+        assert_eq!(
+            conjunctions(merged.expr().clone()),
+            vec![cmp(BinaryOperator::Gt, 0, 1), cmp(BinaryOperator::Eq, 1, 2)]
+        );
+
+        // filters of only true conditions are removed
+        let plan = filter(
+            vec![Constant(DataValue::Bool(true))],
+            filter(vec![Constant(DataValue::Bool(true))], scan()),
+        );
+        let plan = FilterSimplificationRule.rewrite(plan);
+        assert!(plan.downcast_ref::<LogicalTableScan>().is_some());
+    }
+
+    #[test]
+    fn contradictory_filter_is_empty() {
+        let empty = |plan: PlanRef| {
+            let plan = FilterSimplificationRule.rewrite(plan);
+            match plan.downcast_ref::<LogicalValues>() {
+                Some(values) => values.values().is_empty() && values.column_types().len() == 2,
+                None => false,
+            }
+        };
+        let gt5 = || cmp(BinaryOperator::Gt, 0, 5);
+        assert!(empty(filter(
+            vec![gt5(), cmp(BinaryOperator::Lt, 0, 3)],
+            scan()
+        )));
+        assert!(empty(filter(
+            vec![gt5(), cmp(BinaryOperator::LtEq, 0, 5)],
+            scan()
+        )));
+        assert!(empty(filter(
+            vec![
+                cmp(BinaryOperator::Eq, 0, 1),
+                cmp(BinaryOperator::NotEq, 0, 1)
+            ],
+            scan()
+        )));
+        // contradictory conditions in stacked filters
+        assert!(empty(filter(
+            vec![cmp(BinaryOperator::Eq, 0, 2)],
+            filter(vec![cmp(BinaryOperator::Eq, 0, 1)], scan())
+        )));
+        assert!(empty(filter(
+            vec![Constant(DataValue::Bool(false))],
+            scan()
+        )));
+
+        // conditions on different columns or with common values are kept
+        assert!(!empty(filter(
+            vec![gt5(), cmp(BinaryOperator::Lt, 1, 3)],
+            scan()
+        )));
+        assert!(!empty(filter(
+            vec![gt5(), cmp(BinaryOperator::LtEq, 0, 6)],
+            scan()
+        )));
+        assert!(!empty(filter(
+            vec![
+                cmp(BinaryOperator::GtEq, 0, 5),
+                cmp(BinaryOperator::LtEq, 0, 5)
+            ],
+            scan()
+        )));
+    }
+}
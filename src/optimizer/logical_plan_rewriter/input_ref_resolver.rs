@@ -3,6 +3,7 @@
 use super::*;
 use crate::binder::*;
 use crate::catalog::ColumnRefId;
+use crate::types::ColumnId;
 
 /// Resolves column references into physical indices into the `DataChunk`.
 ///
@@ -37,6 +38,11 @@ impl ExprRewriter for InputRefResolver {
                     self.rewrite_expr(expr);
                 }
             }
+            ScalarFunction(func) => {
+                for expr in &mut func.args {
+                    self.rewrite_expr(expr);
+                }
+            }
             // rewrite sub-expressions
             BinaryOp(binary_op) => {
                 self.rewrite_expr(&mut *binary_op.left_expr);
@@ -51,9 +57,33 @@ impl ExprRewriter for InputRefResolver {
             IsNull(isnull) => {
                 self.rewrite_expr(&mut *isnull.expr);
             }
+            Case(case) => {
+                for (cond, result) in &mut case.when_then {
+                    self.rewrite_expr(cond);
+                    self.rewrite_expr(result);
+                }
+                if let Some(else_result) = &mut case.else_result {
+                    self.rewrite_expr(else_result);
+                }
+            }
+            Between(between) => {
+                self.rewrite_expr(&mut between.expr);
+                self.rewrite_expr(&mut between.low);
+                self.rewrite_expr(&mut between.high);
+            }
+            Like(like) => {
+                self.rewrite_expr(&mut like.expr);
+                self.rewrite_expr(&mut like.pattern);
+            }
             ExprWithAlias(expr_with_alias) => {
                 self.rewrite_expr(&mut *expr_with_alias.expr);
             }
+            // the subquery itself is resolved when it is evaluated
+            Subquery(subquery) => {
+                for arg in &mut subquery.args {
+                    self.rewrite_expr(arg);
+                }
+            }
             _ => {}
         }
     }
@@ -64,8 +94,14 @@ impl PlanRewriter for InputRefResolver {
         let left = self.rewrite(join.left());
         let mut resolver = Self::default();
         let right = resolver.rewrite(join.right());
+        let left_bindings = self.bindings.clone();
         self.bindings.append(&mut resolver.bindings);
-        Arc::new(join.clone_with_rewrite_expr(left, right, self))
+        let ret = Arc::new(join.clone_with_rewrite_expr(left, right, self));
+        // semi and anti joins only output the left columns
+        if join.join_op().is_semi_or_anti() {
+            self.bindings = left_bindings;
+        }
+        ret
     }
 
     fn rewrite_logical_table_scan(&mut self, plan: &LogicalTableScan) -> PlanRef {
@@ -79,14 +115,19 @@ impl PlanRewriter for InputRefResolver {
 
     fn rewrite_logical_projection(&mut self, proj: &LogicalProjection) -> PlanRef {
         let new_child = self.rewrite(proj.child());
-        let bindings = proj
-            .project_expressions()
-            .iter()
-            .map(|expr| match &expr {
-                BoundExpr::ColumnRef(col) => Some(col.column_ref_id),
-                _ => None,
-            })
-            .collect();
+        let bindings = match proj.table_ref_id() {
+            Some(table_ref_id) => (0..proj.project_expressions().len())
+                .map(|i| Some(ColumnRefId::from_table(table_ref_id, i as ColumnId)))
+                .collect(),
+            None => proj
+                .project_expressions()
+                .iter()
+                .map(|expr| match &expr {
+                    BoundExpr::ColumnRef(col) => Some(col.column_ref_id),
+                    _ => None,
+                })
+                .collect(),
+        };
         let ret = Arc::new(proj.clone_with_rewrite_expr(new_child, self));
         self.bindings = bindings;
         ret
@@ -114,7 +155,25 @@ impl PlanRewriter for InputRefResolver {
         let child = self.rewrite(plan.child());
         Arc::new(plan.clone_with_rewrite_expr(child, self))
     }
+    fn rewrite_logical_set_operation(&mut self, plan: &LogicalSetOperation) -> PlanRef {
+        let left = self.rewrite(plan.left());
+        let right = Self::default().rewrite(plan.right());
+        // the outputs can not be referred by columns of tables
+        self.bindings = vec![None; plan.out_types().len()];
+        Arc::new(plan.clone_with_left_right(left, right))
+    }
+    fn rewrite_logical_update(&mut self, plan: &LogicalUpdate) -> PlanRef {
+        let child = self.rewrite(plan.child());
+        Arc::new(plan.clone_with_rewrite_expr(child, self))
+    }
     fn rewrite_logical_values(&mut self, plan: &LogicalValues) -> PlanRef {
-        Arc::new(plan.clone_with_rewrite_expr(self))
+        let ret = Arc::new(plan.clone_with_rewrite_expr(self));
+        self.bindings = (0..plan.column_types().len())
+            .map(|i| {
+                plan.table_ref_id()
+                    .map(|id| ColumnRefId::from_table(id, i as ColumnId))
+            })
+            .collect();
+        ret
     }
 }
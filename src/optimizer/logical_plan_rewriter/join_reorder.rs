@@ -0,0 +1,494 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashMap;
+
+use bit_set::BitSet;
+
+use super::*;
+use crate::binder::{BoundExpr, BoundInputRef, BoundJoinOperator};
+use crate::catalog::RootCatalogRef;
+use crate::optimizer::expr_utils::{
+    conjunctions, input_col_refs, merge_conjunctions, remap_input_col_refs, shift_input_col_refs,
+};
+use crate::parser::BinaryOperator;
+use crate::types::DataValue;
+
+/// The maximum number of relations in a chain of joins to reorder.
+const MAX_REORDER_RELATIONS: usize = 10;
+
+/// The selectivity of a condition that can't be estimated with statistics.
+const DEFAULT_SELECTIVITY: f64 = 1.0 / 3.0;
+
+/// The selectivity of `column = constant` if the number of distinct values is unknown.
+const DEFAULT_EQ_SELECTIVITY: f64 = 0.1;
+
+/// Join reorder rule reorders a chain of inner joins with the statistics collected by `ANALYZE`,
+/// to minimize the estimated sizes of intermediate results.
+///
+/// The relations are joined greedily: in each step, the pair of inputs with the smallest
+/// estimated join size is joined, preferring pairs connected by a join condition to cross
+/// products. The larger input of each join is probed, and the smaller one is built. The columns
+/// are projected back into the original order at last.
+///
+/// A chain is kept as is if the size of any relation in it can't be estimated.
+pub struct JoinReorderRule {
+    pub catalog: RootCatalogRef,
+}
+
+/// The estimated size of a relation.
+struct Estimation {
+    rows: f64,
+    /// The number of distinct values of each output column, if known.
+    distinct: Vec<Option<f64>>,
+}
+
+/// An input of the joins being reordered.
+struct Component {
+    plan: PlanRef,
+    /// The columns of the chain output by the plan, in order.
+    cols: Vec<usize>,
+    rows: f64,
+}
+
+impl PlanRewriter for JoinReorderRule {
+    fn rewrite_logical_join(&mut self, join: &LogicalJoin) -> PlanRef {
+        if join.join_op() != BoundJoinOperator::Inner {
+            let left = self.rewrite(join.left());
+            let right = self.rewrite(join.right());
+            return Arc::new(join.clone_with_left_right(left, right));
+        }
+        let plan = self.rewrite_relations(join.clone_as_plan_ref());
+        let mut relations = vec![];
+        let mut conds = vec![];
+        flatten(plan.clone(), 0, &mut relations, &mut conds);
+        if relations.len() > MAX_REORDER_RELATIONS {
+            return plan;
+        }
+        let estimations: Option<Vec<_>> = relations.iter().map(|r| self.estimate(r)).collect();
+        match estimations {
+            Some(estimations) => reorder(plan, relations, estimations, conds),
+            None => plan,
+        }
+    }
+}
+
+impl JoinReorderRule {
+    /// Rewrite the relations of a chain of inner joins.
+    fn rewrite_relations(&mut self, plan: PlanRef) -> PlanRef {
+        match plan.as_logical_join() {
+            Ok(join) if join.join_op() == BoundJoinOperator::Inner => {
+                let left = self.rewrite_relations(join.left());
+                let right = self.rewrite_relations(join.right());
+                Arc::new(join.clone_with_left_right(left, right))
+            }
+            _ => self.rewrite(plan),
+        }
+    }
+
+    /// Estimate the size of a relation, or `None` if any table in it is not analyzed.
+    fn estimate(&self, plan: &PlanRef) -> Option<Estimation> {
+        if let Ok(scan) = plan.as_logical_table_scan() {
+            let table = self.catalog.get_table(&scan.table_ref_id())?;
+            let statistics = table.statistics()?;
+            let mut distinct = (scan.column_ids().iter())
+                .map(|id| Some(statistics.columns.get(id)?.distinct_count as f64))
+                .collect::<Vec<_>>();
+            // the row handler
+            distinct.resize(plan.out_types().len(), None);
+            let mut rows = statistics.row_count as f64;
+            if let Some(predicate) = scan.predicate() {
+                for cond in predicate.conds() {
+                    rows *= selectivity(cond, &distinct);
+                }
+            }
+            if let Some(limit) = scan.limit() {
+                rows = rows.min(limit as f64);
+            }
+            return Some(Estimation { rows, distinct });
+        }
+        if let Ok(filter) = plan.as_logical_filter() {
+            let mut estimation = self.estimate(&filter.child())?;
+            for cond in conjunctions(filter.expr().clone()) {
+                estimation.rows *= selectivity(&cond, &estimation.distinct);
+            }
+            return Some(estimation);
+        }
+        if let Ok(projection) = plan.as_logical_projection() {
+            let child = self.estimate(&projection.child())?;
+            let distinct = (projection.project_expressions().iter())
+                .map(|expr| match expr {
+                    BoundExpr::InputRef(input_ref) => child.distinct[input_ref.index],
+                    _ => None,
+                })
+                .collect();
+            return Some(Estimation {
+                rows: child.rows,
+                distinct,
+            });
+        }
+        None
+    }
+}
+
+/// Collect the relations of a chain of inner joins from left to right, and the conditions of the
+/// joins, whose input refs are the positions in the output of the whole chain.
+fn flatten(plan: PlanRef, offset: usize, relations: &mut Vec<PlanRef>, conds: &mut Vec<BoundExpr>) {
+    match plan.as_logical_join() {
+        Ok(join) if join.join_op() == BoundJoinOperator::Inner => {
+            let mut on_clause = join.predicate().to_on_clause();
+            shift_input_col_refs(&mut on_clause, offset as i32);
+            conds.extend(
+                conjunctions(on_clause)
+                    .into_iter()
+                    .filter(|cond| *cond != BoundExpr::Constant(DataValue::Bool(true))),
+            );
+            let left_cols_num = join.left().out_types().len();
+            flatten(join.left(), offset, relations, conds);
+            flatten(join.right(), offset + left_cols_num, relations, conds);
+        }
+        _ => relations.push(plan),
+    }
+}
+
+/// Estimate the selectivity of a condition.
+fn selectivity(cond: &BoundExpr, distinct: &[Option<f64>]) -> f64 {
+    if let BoundExpr::BinaryOp(op) = cond {
+        if op.op == BinaryOperator::Eq {
+            match (&*op.left_expr, &*op.right_expr) {
+                (BoundExpr::InputRef(x), BoundExpr::Constant(_))
+                | (BoundExpr::Constant(_), BoundExpr::InputRef(x)) => {
+                    return match distinct[x.index] {
+                        Some(n) => 1.0 / n.max(1.0),
+                        None => DEFAULT_EQ_SELECTIVITY,
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+    DEFAULT_SELECTIVITY
+}
+
+/// Reorder the relations of a chain of inner joins.
+fn reorder(
+    plan: PlanRef,
+    relations: Vec<PlanRef>,
+    estimations: Vec<Estimation>,
+    conds: Vec<BoundExpr>,
+) -> PlanRef {
+    // the number of distinct values of each column in the output of the chain. If unknown, the
+    // column is assumed to be unique.
+    let mut distinct = vec![];
+    let mut components = vec![];
+    for (relation, estimation) in relations.into_iter().zip(estimations) {
+        let offset = distinct.len();
+        let rows = estimation.rows;
+        distinct.extend(
+            (estimation.distinct.into_iter()).map(|n| n.unwrap_or(rows).min(rows).max(1.0)),
+        );
+        components.push(Component {
+            plan: relation,
+            cols: (offset..distinct.len()).collect(),
+            rows,
+        });
+    }
+    let mut conds: Vec<Option<(BoundExpr, BitSet)>> = conds
+        .into_iter()
+        .map(|cond| {
+            let cols = input_col_refs(&cond);
+            Some((cond, cols))
+        })
+        .collect();
+
+    while components.len() > 1 {
+        // (connected, rows, i, j)
+        let mut best: Option<(bool, f64, usize, usize)> = None;
+        for i in 0..components.len() {
+            for j in i + 1..components.len() {
+                let (connected, rows) =
+                    estimate_join(&components[i], &components[j], &conds, &distinct);
+                let better = match best {
+                    None => true,
+                    Some((best_connected, best_rows, _, _)) => {
+                        (connected, -rows) > (best_connected, -best_rows)
+                    }
+                };
+                if better {
+                    best = Some((connected, rows, i, j));
+                }
+            }
+        }
+        let (_, rows, i, j) = best.unwrap();
+        let b = components.remove(j);
+        let a = components.remove(i);
+        components.insert(i, join(a, b, rows, &mut conds));
+    }
+    let component = components.pop().unwrap();
+
+    // project the columns back into the original order
+    let out_types = plan.out_types();
+    let mut positions = vec![0; out_types.len()];
+    for (position, col) in component.cols.iter().enumerate() {
+        positions[*col] = position;
+    }
+    if positions.iter().enumerate().all(|(i, p)| i == *p) {
+        return component.plan;
+    }
+    let exprs = positions
+        .into_iter()
+        .zip(out_types)
+        .map(|(index, return_type)| BoundExpr::InputRef(BoundInputRef { index, return_type }))
+        .collect();
+    Arc::new(LogicalProjection::new(exprs, component.plan))
+}
+
+/// Whether the conditions only reference the columns in `a` and `b`.
+fn is_applicable(cols: &BitSet, a: &Component, b: &Component) -> bool {
+    cols.iter()
+        .all(|col| a.cols.contains(&col) || b.cols.contains(&col))
+}
+
+/// Estimate the size of joining two components, and whether they are connected by any condition.
+fn estimate_join(
+    a: &Component,
+    b: &Component,
+    conds: &[Option<(BoundExpr, BitSet)>],
+    distinct: &[f64],
+) -> (bool, f64) {
+    let mut connected = false;
+    let mut rows = a.rows * b.rows;
+    for (cond, cols) in conds.iter().flatten() {
+        let cross = cols.iter().any(|col| a.cols.contains(&col))
+            && cols.iter().any(|col| b.cols.contains(&col));
+        if !cross || !is_applicable(cols, a, b) {
+            continue;
+        }
+        connected = true;
+        rows *= match cond {
+            BoundExpr::BinaryOp(op) if op.op == BinaryOperator::Eq => {
+                match (&*op.left_expr, &*op.right_expr) {
+                    (BoundExpr::InputRef(x), BoundExpr::InputRef(y)) => {
+                        1.0 / distinct[x.index].max(distinct[y.index])
+                    }
+                    _ => DEFAULT_SELECTIVITY,
+                }
+            }
+            _ => DEFAULT_SELECTIVITY,
+        };
+    }
+    (connected, rows)
+}
+
+/// Join two components with all the applicable conditions. The larger one is the left side.
+fn join(
+    a: Component,
+    b: Component,
+    rows: f64,
+    conds: &mut [Option<(BoundExpr, BitSet)>],
+) -> Component {
+    let (left, right) = if b.rows > a.rows { (b, a) } else { (a, b) };
+    let mut cols = left.cols.clone();
+    cols.extend(right.cols.iter().cloned());
+    let mapping: HashMap<usize, usize> = (cols.iter().enumerate())
+        .map(|(position, col)| (*col, position))
+        .collect();
+    let mut on_conds = vec![];
+    for slot in conds.iter_mut() {
+        if matches!(slot, Some((_, cols)) if is_applicable(cols, &left, &right)) {
+            let (mut cond, _) = slot.take().unwrap();
+            remap_input_col_refs(&mut cond, &mapping);
+            on_conds.push(cond);
+        }
+    }
+    let plan = Arc::new(LogicalJoin::create(
+        left.plan,
+        right.plan,
+        BoundJoinOperator::Inner,
+        merge_conjunctions(on_conds.into_iter()),
+    ));
+    Component { plan, cols, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binder::BoundBinaryOp;
+    use crate::catalog::{
+        ColumnCatalog, ColumnStatistics, RootCatalog, TableRefId, TableStatistics,
+    };
+    use crate::types::{ColumnId, DataTypeExt, DataTypeKind};
+
+    const FACT: u32 = 0;
+    const DIM1: u32 = 1;
+    const DIM2: u32 = 2;
+
+    /// Create a star schema: `fact(id, d1, d2)` of 10000 rows, `dim1(id)` of 100 rows, and
+    /// `dim2(id, name)` of 10 rows.
+    fn catalog(analyzed: bool) -> RootCatalogRef {
+        let catalog = Arc::new(RootCatalog::new());
+        let schema = (catalog.get_database_by_id(0).unwrap())
+            .get_schema_by_id(0)
+            .unwrap();
+        let tables: [(&str, &[&str], usize, &[usize]); 3] = [
+            ("fact", &["id", "d1", "d2"], 10000, &[10000, 100, 10]),
+            ("dim1", &["id"], 100, &[100]),
+            ("dim2", &["id", "name"], 10, &[10, 10]),
+        ];
+        for (name, columns, row_count, distinct) in tables {
+            let columns = (columns.iter().enumerate())
+                .map(|(id, name)| {
+                    let desc = DataTypeKind::Int(None)
+                        .not_null()
+                        .to_column(name.to_string());
+                    ColumnCatalog::new(id as ColumnId, desc)
+                })
+                .collect();
+            let table_id = schema.add_table(name.into(), columns, false).unwrap();
+            if !analyzed && name == "dim1" {
+                continue;
+            }
+            let columns = (distinct.iter().enumerate())
+                .map(|(id, distinct_count)| {
+                    let statistics = ColumnStatistics {
+                        null_count: 0,
+                        min: DataValue::Null,
+                        max: DataValue::Null,
+                        distinct_count: *distinct_count,
+                    };
+                    (id as ColumnId, statistics)
+                })
+                .collect();
+            (schema.get_table_by_id(table_id).unwrap())
+                .set_statistics(TableStatistics { row_count, columns });
+        }
+        catalog
+    }
+
+    fn scan(table_id: u32, columns: usize) -> PlanRef {
+        Arc::new(LogicalTableScan::new(
+            TableRefId::new(0, 0, table_id),
+            (0..columns as ColumnId).collect(),
+            (0..columns)
+                .map(|i| {
+                    DataTypeKind::Int(None)
+                        .not_null()
+                        .to_column(format!("v{}", i))
+                })
+                .collect(),
+            false,
+            false,
+            None,
+        ))
+    }
+
+    fn input_ref(index: usize) -> BoundExpr {
+        BoundExpr::InputRef(BoundInputRef {
+            index,
+            return_type: DataTypeKind::Int(None).not_null(),
+        })
+    }
+
+    fn eq(left: BoundExpr, right: BoundExpr) -> BoundExpr {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op: BinaryOperator::Eq,
+            left_expr: Box::new(left),
+            right_expr: Box::new(right),
+            return_type: Some(DataTypeKind::Boolean.nullable()),
+        })
+    }
+
+    /// `dim1 join fact on dim1.id = fact.d1 join dim2 on fact.d2 = dim2.id where dim2.name = 1`
+    fn star_query() -> PlanRef {
+        let dim1_fact = LogicalJoin::create(
+            scan(DIM1, 1),
+            scan(FACT, 3),
+            BoundJoinOperator::Inner,
+            eq(input_ref(0), input_ref(2)),
+        );
+        let dim2 = LogicalFilter::new(
+            eq(input_ref(1), BoundExpr::Constant(DataValue::Int32(1))),
+            scan(DIM2, 2),
+        );
+        Arc::new(LogicalJoin::create(
+            Arc::new(dim1_fact),
+            Arc::new(dim2),
+            BoundJoinOperator::Inner,
+            eq(input_ref(3), input_ref(4)),
+        ))
+    }
+
+    /// Describe the join order of the plan.
+    fn join_order(plan: &PlanRef) -> String {
+        if let Ok(join) = plan.as_logical_join() {
+            return format!(
+                "({}, {})",
+                join_order(&join.left()),
+                join_order(&join.right())
+            );
+        }
+        if let Ok(scan) = plan.as_logical_table_scan() {
+            return ["fact", "dim1", "dim2"][scan.table_ref_id().table_id as usize].into();
+        }
+        join_order(&plan.children()[0])
+    }
+
+    #[test]
+    fn test_reorder_star_schema() {
+        let mut rule = JoinReorderRule {
+            catalog: catalog(true),
+        };
+        let plan = rule.rewrite(star_query());
+        // `dim2` is the smallest after filtering, so it's joined first and built. The
+        // intermediate result is estimated to be 1000 rows, larger than `dim1`.
+        assert_eq!(join_order(&plan), "((fact, dim2), dim1)");
+
+        // the columns are projected back into the original order
+        let projection = plan.as_logical_projection().unwrap();
+        let indexes = (projection.project_expressions().iter())
+            .map(|expr| match expr {
+                BoundExpr::InputRef(input_ref) => input_ref.index,
+                _ => panic!("not an input ref"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(indexes, vec![5, 0, 1, 2, 3, 4]);
+
+        // conditions are rewritten to the positions in the new inputs
+        let top = projection.child();
+        let top = top.as_logical_join().unwrap();
+        assert_eq!(
+            top.predicate().to_on_clause(),
+            eq(input_ref(1), input_ref(5))
+        );
+        let bottom = top.left();
+        let bottom = bottom.as_logical_join().unwrap();
+        assert_eq!(
+            bottom.predicate().to_on_clause(),
+            eq(input_ref(2), input_ref(3))
+        );
+    }
+
+    #[test]
+    fn test_not_reorder_without_statistics() {
+        let mut rule = JoinReorderRule {
+            catalog: catalog(false),
+        };
+        let plan = rule.rewrite(star_query());
+        assert_eq!(join_order(&plan), "((dim1, fact), dim2)");
+        assert!(plan.as_logical_join().is_ok());
+    }
+
+    #[test]
+    fn test_not_reorder_outer_join() {
+        let mut rule = JoinReorderRule {
+            catalog: catalog(true),
+        };
+        let plan = Arc::new(LogicalJoin::create(
+            scan(DIM1, 1),
+            scan(FACT, 3),
+            BoundJoinOperator::LeftOuter,
+            eq(input_ref(0), input_ref(2)),
+        ));
+        let plan = rule.rewrite(plan);
+        assert_eq!(join_order(&plan), "(dim1, fact)");
+    }
+}
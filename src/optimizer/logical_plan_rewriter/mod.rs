@@ -6,18 +6,24 @@ use super::plan_nodes::*;
 
 mod arith_expr_simplification;
 mod bool_expr_simplification;
+mod common_subexpr_elimination;
 mod constant_folding;
 mod constant_moving;
 mod convert_physical;
+mod filter_simplification;
 mod input_ref_resolver;
+mod join_reorder;
 
 pub use arith_expr_simplification::*;
 pub use bool_expr_simplification::*;
+pub use common_subexpr_elimination::*;
 pub use constant_folding::*;
 pub use constant_moving::*;
 pub use convert_physical::*;
+pub use filter_simplification::*;
 pub use input_ref_resolver::*;
 use itertools::Itertools;
+pub use join_reorder::*;
 use paste::paste;
 
 use crate::binder::BoundExpr;
@@ -3,6 +3,7 @@
 use bit_set::BitSet;
 
 use crate::binder::*;
+use crate::catalog::RootCatalogRef;
 
 mod expr_utils;
 mod heuristic;
@@ -26,6 +27,10 @@ use self::rules::*;
 #[derive(Default)]
 pub struct Optimizer {
     pub enable_filter_scan: bool,
+    pub enable_limit_scan: bool,
+    pub enable_index_scan: bool,
+    /// The catalog to get the statistics of tables for join reordering.
+    pub catalog: RootCatalogRef,
 }
 
 impl Optimizer {
@@ -34,16 +39,44 @@ impl Optimizer {
         let mut constant_moving_rule = ConstantMovingRule;
         let mut arith_expr_simplification_rule = ArithExprSimplificationRule;
         let mut bool_expr_simplification_rule = BoolExprSimplificationRule;
+        let mut filter_simplification_rule = FilterSimplificationRule;
         plan = constant_folding_rule.rewrite(plan);
         plan = arith_expr_simplification_rule.rewrite(plan);
         plan = bool_expr_simplification_rule.rewrite(plan);
         plan = constant_moving_rule.rewrite(plan);
+        plan = filter_simplification_rule.rewrite(plan);
         let mut rules: Vec<Box<(dyn rules::Rule + 'static)>> = vec![Box::new(FilterJoinRule {})];
         if self.enable_filter_scan {
             rules.push(Box::new(FilterScanRule {}));
         }
         let hep_optimizer = HeuristicOptimizer { rules };
         plan = hep_optimizer.optimize(plan);
+        // joins are reordered after filters are pushed down, so that the sizes of filtered
+        // relations are estimated
+        let mut join_reorder_rule = JoinReorderRule {
+            catalog: self.catalog.clone(),
+        };
+        plan = join_reorder_rule.rewrite(plan);
+        // indexes are chosen by the conditions pushed into scans
+        if self.enable_index_scan {
+            let hep_optimizer = HeuristicOptimizer {
+                rules: vec![Box::new(IndexScanRule {
+                    catalog: self.catalog.clone(),
+                }) as BoxedRule],
+            };
+            plan = hep_optimizer.optimize(plan);
+        }
+        // limits are pushed down after filters, so that limits count rows after filtering
+        if self.enable_limit_scan {
+            let hep_optimizer = HeuristicOptimizer {
+                rules: vec![Box::new(LimitScanRule {}) as BoxedRule],
+            };
+            plan = hep_optimizer.optimize(plan);
+        }
+        // common subexpressions are extracted after filters are pushed down, since the extra
+        // projections would stop the pushdown
+        let mut common_subexpr_elimination_rule = CommonSubexprEliminationRule;
+        plan = common_subexpr_elimination_rule.rewrite(plan);
         let out_types_num = plan.out_types().len();
         plan = plan.prune_col(BitSet::from_iter(0..out_types_num));
         let mut phy_converter = PhysicalConverter;
@@ -106,6 +106,14 @@ impl JoinPredicate {
         Self::new(other_conds, left_conds, right_conds, eq_keys)
     }
 
+    /// Whether the predicate has no condition, so that every pair of rows is matched.
+    pub fn is_always_true(&self) -> bool {
+        self.left_conds.is_empty()
+            && self.right_conds.is_empty()
+            && self.other_conds.is_empty()
+            && self.eq_keys.is_empty()
+    }
+
     /// Get a reference to the join predicate's non eq conds.
     pub fn other_conds(&self) -> &[BoundExpr] {
         self.other_conds.as_ref()
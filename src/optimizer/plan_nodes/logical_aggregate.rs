@@ -86,7 +86,11 @@ impl PlanNode for LogicalAggregate {
 }
 impl fmt::Display for LogicalAggregate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "LogicalAggregate: {} agg calls", self.agg_calls.len(),)
+        writeln!(
+            f,
+            "LogicalAggregate: group keys {:?}, agg calls {:?}",
+            self.group_keys, self.agg_calls
+        )
     }
 }
 
@@ -104,21 +108,25 @@ mod tests {
                     kind: AggKind::Sum,
                     args: vec![],
                     return_type: DataTypeKind::Double.not_null(),
+                    distinct: false,
                 },
                 BoundAggCall {
                     kind: AggKind::Avg,
                     args: vec![],
                     return_type: DataTypeKind::Double.not_null(),
+                    distinct: false,
                 },
                 BoundAggCall {
                     kind: AggKind::Count,
                     args: vec![],
                     return_type: DataTypeKind::Double.not_null(),
+                    distinct: false,
                 },
                 BoundAggCall {
                     kind: AggKind::RowCount,
                     args: vec![],
                     return_type: DataTypeKind::Double.not_null(),
+                    distinct: false,
                 },
             ],
             vec![],
@@ -0,0 +1,51 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+use crate::catalog::{ColumnCatalog, TableRefId};
+
+/// The logical plan of `ALTER TABLE`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalAlterTable {
+    table_ref_id: TableRefId,
+    /// The column to add.
+    column: ColumnCatalog,
+}
+
+impl LogicalAlterTable {
+    pub fn new(table_ref_id: TableRefId, column: ColumnCatalog) -> Self {
+        Self {
+            table_ref_id,
+            column,
+        }
+    }
+
+    /// Get a reference to the logical alter table's table ref id.
+    pub fn table_ref_id(&self) -> TableRefId {
+        self.table_ref_id
+    }
+
+    /// Get a reference to the logical alter table's column.
+    pub fn column(&self) -> &ColumnCatalog {
+        &self.column
+    }
+}
+impl PlanTreeNodeLeaf for LogicalAlterTable {}
+impl_plan_tree_node_for_leaf!(LogicalAlterTable);
+impl PlanNode for LogicalAlterTable {}
+
+impl fmt::Display for LogicalAlterTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalAlterTable: table {}, add column {}:{:?} default {:?}",
+            self.table_ref_id.table_id,
+            self.column.name(),
+            self.column.datatype(),
+            self.column.default_value()
+        )
+    }
+}
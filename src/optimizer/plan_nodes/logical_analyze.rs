@@ -0,0 +1,55 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+use crate::catalog::TableRefId;
+use crate::types::ColumnId;
+
+/// The logical plan of `ANALYZE`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalAnalyze {
+    table_ref_id: TableRefId,
+    /// The columns output by the child, in order.
+    column_ids: Vec<ColumnId>,
+    child: PlanRef,
+}
+
+impl LogicalAnalyze {
+    pub fn new(table_ref_id: TableRefId, column_ids: Vec<ColumnId>, child: PlanRef) -> Self {
+        Self {
+            table_ref_id,
+            column_ids,
+            child,
+        }
+    }
+
+    /// Get a reference to the logical analyze's table ref id.
+    pub fn table_ref_id(&self) -> TableRefId {
+        self.table_ref_id
+    }
+
+    /// Get a reference to the logical analyze's column ids.
+    pub fn column_ids(&self) -> &[ColumnId] {
+        self.column_ids.as_ref()
+    }
+}
+impl PlanTreeNodeUnary for LogicalAnalyze {
+    fn child(&self) -> PlanRef {
+        self.child.clone()
+    }
+
+    fn clone_with_child(&self, child: PlanRef) -> Self {
+        Self::new(self.table_ref_id(), self.column_ids.clone(), child)
+    }
+}
+impl_plan_tree_node_for_unary!(LogicalAnalyze);
+impl PlanNode for LogicalAnalyze {}
+
+impl fmt::Display for LogicalAnalyze {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "LogicalAnalyze: table {}", self.table_ref_id.table_id)
+    }
+}
@@ -18,6 +18,8 @@ pub struct LogicalCopyToFile {
     format: FileFormat,
     /// The column types.
     column_types: Vec<DataType>,
+    /// The column names, written as the header row if requested.
+    column_names: Vec<String>,
     /// The child plan.
     child: PlanRef,
 }
@@ -26,12 +28,14 @@ impl LogicalCopyToFile {
         path: PathBuf,
         format: FileFormat,
         column_types: Vec<DataType>,
+        column_names: Vec<String>,
         child: PlanRef,
     ) -> Self {
         Self {
             path,
             format,
             column_types,
+            column_names,
             child,
         }
     }
@@ -50,6 +54,11 @@ impl LogicalCopyToFile {
     pub fn column_types(&self) -> &[DataType] {
         self.column_types.as_ref()
     }
+
+    /// Get a reference to the logical copy to file's column names.
+    pub fn column_names(&self) -> &[String] {
+        self.column_names.as_ref()
+    }
 }
 impl PlanTreeNodeUnary for LogicalCopyToFile {
     fn child(&self) -> PlanRef {
@@ -61,6 +70,7 @@ impl PlanTreeNodeUnary for LogicalCopyToFile {
             self.path().clone(),
             self.format().clone(),
             self.column_types().to_vec(),
+            self.column_names().to_vec(),
             child,
         )
     }
@@ -0,0 +1,48 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+use crate::catalog::TableRefId;
+use crate::types::ColumnId;
+
+/// The logical plan of `CREATE INDEX`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalCreateIndex {
+    table_ref_id: TableRefId,
+    column_id: Option<ColumnId>,
+}
+
+impl LogicalCreateIndex {
+    pub fn new(table_ref_id: TableRefId, column_id: Option<ColumnId>) -> Self {
+        Self {
+            table_ref_id,
+            column_id,
+        }
+    }
+
+    /// Get a reference to the logical create index's table ref id.
+    pub fn table_ref_id(&self) -> TableRefId {
+        self.table_ref_id
+    }
+
+    /// Get the column to index, or `None` if it has been indexed.
+    pub fn column_id(&self) -> Option<ColumnId> {
+        self.column_id
+    }
+}
+impl PlanTreeNodeLeaf for LogicalCreateIndex {}
+impl_plan_tree_node_for_leaf!(LogicalCreateIndex);
+impl PlanNode for LogicalCreateIndex {}
+
+impl fmt::Display for LogicalCreateIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalCreateIndex: table {}, column {:?}",
+            self.table_ref_id.table_id, self.column_id
+        )
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+
+/// The logical plan of `select distinct`, which removes duplicate rows.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalDistinct {
+    child: PlanRef,
+}
+
+impl LogicalDistinct {
+    pub fn new(child: PlanRef) -> Self {
+        Self { child }
+    }
+}
+impl PlanTreeNodeUnary for LogicalDistinct {
+    fn child(&self) -> PlanRef {
+        self.child.clone()
+    }
+    #[must_use]
+    fn clone_with_child(&self, child: PlanRef) -> Self {
+        Self::new(child)
+    }
+}
+impl_plan_tree_node_for_unary!(LogicalDistinct);
+impl PlanNode for LogicalDistinct {
+    fn schema(&self) -> Vec<ColumnDesc> {
+        self.child.schema()
+    }
+
+    fn prune_col(&self, required_cols: BitSet) -> PlanRef {
+        // all columns are compared to find duplicates
+        let all_cols = BitSet::from_iter(0..self.child.out_types().len());
+        let distinct = self
+            .clone_with_child(self.child.prune_col(all_cols.clone()))
+            .into_plan_ref();
+        if required_cols.is_empty() || required_cols == all_cols {
+            return distinct;
+        }
+        project_cols(distinct, required_cols.iter())
+    }
+}
+
+impl fmt::Display for LogicalDistinct {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "LogicalDistinct")
+    }
+}
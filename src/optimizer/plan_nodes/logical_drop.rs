@@ -10,17 +10,17 @@ use crate::binder::statement::drop::Object;
 /// The logical plan of `DROP`.
 #[derive(Debug, Clone, Serialize)]
 pub struct LogicalDrop {
-    object: Object,
+    objects: Vec<Object>,
 }
 
 impl LogicalDrop {
-    pub fn new(object: Object) -> Self {
-        Self { object }
+    pub fn new(objects: Vec<Object>) -> Self {
+        Self { objects }
     }
 
-    /// Get a reference to the logical drop's object.
-    pub fn object(&self) -> &Object {
-        &self.object
+    /// Get a reference to the logical drop's objects.
+    pub fn objects(&self) -> &[Object] {
+        &self.objects
     }
 }
 impl PlanTreeNodeLeaf for LogicalDrop {}
@@ -6,6 +6,7 @@ use serde::Serialize;
 
 use super::*;
 use crate::binder::BoundExpr;
+use crate::optimizer::expr_utils::{input_col_refs, prune_input_col_refs};
 use crate::optimizer::logical_plan_rewriter::ExprRewriter;
 
 /// The logical plan of filter operation.
@@ -48,6 +49,25 @@ impl PlanNode for LogicalFilter {
     fn schema(&self) -> Vec<ColumnDesc> {
         self.child.schema()
     }
+
+    fn prune_col(&self, required_cols: BitSet) -> PlanRef {
+        let mut child_required_cols = required_cols.clone();
+        child_required_cols.union_with(&input_col_refs(&self.expr));
+        let new_child = self.child.prune_col(child_required_cols.clone());
+        let mut expr = self.expr.clone();
+        prune_input_col_refs(&mut expr, &child_required_cols);
+        let filter = LogicalFilter::new(expr, new_child).into_plan_ref();
+        if required_cols.is_empty() || required_cols == child_required_cols {
+            return filter;
+        }
+        let cols = required_cols.iter().map(|index| {
+            child_required_cols
+                .iter()
+                .take_while(|col| *col < index)
+                .count()
+        });
+        project_cols(filter, cols)
+    }
 }
 
 impl fmt::Display for LogicalFilter {
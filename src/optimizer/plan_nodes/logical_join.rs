@@ -29,7 +29,9 @@ impl LogicalJoin {
         predicate: JoinPredicate,
     ) -> Self {
         let mut schema = left_plan.schema();
-        schema.append(&mut right_plan.schema());
+        if !join_op.is_semi_or_anti() {
+            schema.append(&mut right_plan.schema());
+        }
         LogicalJoin {
             left_plan,
             right_plan,
@@ -98,6 +100,10 @@ impl PlanNode for LogicalJoin {
 
 impl fmt::Display for LogicalJoin {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "LogicalJoin: op {:?}", self.join_op)
+        writeln!(
+            f,
+            "LogicalJoin: op {:?}, predicate: {}",
+            self.join_op, self.predicate
+        )
     }
 }
@@ -47,6 +47,11 @@ impl PlanNode for LogicalLimit {
     fn schema(&self) -> Vec<ColumnDesc> {
         self.child.schema()
     }
+
+    fn prune_col(&self, required_cols: BitSet) -> PlanRef {
+        self.clone_with_child(self.child.prune_col(required_cols))
+            .into_plan_ref()
+    }
 }
 
 impl fmt::Display for LogicalLimit {
@@ -6,6 +6,7 @@ use serde::Serialize;
 
 use super::*;
 use crate::binder::BoundOrderBy;
+use crate::optimizer::expr_utils::{input_col_refs, prune_input_col_refs};
 use crate::optimizer::logical_plan_rewriter::ExprRewriter;
 
 /// The logical plan of order.
@@ -50,6 +51,29 @@ impl PlanNode for LogicalOrder {
     fn schema(&self) -> Vec<ColumnDesc> {
         self.child.schema()
     }
+
+    fn prune_col(&self, required_cols: BitSet) -> PlanRef {
+        let mut child_required_cols = required_cols.clone();
+        for cmp in &self.comparators {
+            child_required_cols.union_with(&input_col_refs(&cmp.expr));
+        }
+        let new_child = self.child.prune_col(child_required_cols.clone());
+        let mut comparators = self.comparators.clone();
+        for cmp in &mut comparators {
+            prune_input_col_refs(&mut cmp.expr, &child_required_cols);
+        }
+        let order = LogicalOrder::new(comparators, new_child).into_plan_ref();
+        if required_cols.is_empty() || required_cols == child_required_cols {
+            return order;
+        }
+        let cols = required_cols.iter().map(|index| {
+            child_required_cols
+                .iter()
+                .take_while(|col| *col < index)
+                .count()
+        });
+        project_cols(order, cols)
+    }
 }
 
 impl fmt::Display for LogicalOrder {
@@ -6,6 +6,8 @@ use serde::Serialize;
 
 use super::*;
 use crate::binder::BoundExpr;
+use crate::catalog::TableRefId;
+use crate::optimizer::expr_utils::{input_col_refs, prune_input_col_refs};
 use crate::optimizer::logical_plan_rewriter::ExprRewriter;
 
 /// The logical plan of project operation.
@@ -13,6 +15,9 @@ use crate::optimizer::logical_plan_rewriter::ExprRewriter;
 pub struct LogicalProjection {
     project_expressions: Vec<BoundExpr>,
     child: PlanRef,
+    /// The id of the subquery whose outputs are projected, by which the outputs are referred in
+    /// the outer query.
+    table_ref_id: Option<TableRefId>,
 }
 
 impl LogicalProjection {
@@ -20,9 +25,23 @@ impl LogicalProjection {
         Self {
             project_expressions,
             child,
+            table_ref_id: None,
         }
     }
 
+    /// Refer to the outputs by the columns of the table `table_ref_id`, e.g. a subquery.
+    pub fn with_table_ref_id(self, table_ref_id: TableRefId) -> Self {
+        Self {
+            table_ref_id: Some(table_ref_id),
+            ..self
+        }
+    }
+
+    /// Get the id of the table whose columns refer to the outputs, if any.
+    pub fn table_ref_id(&self) -> Option<TableRefId> {
+        self.table_ref_id
+    }
+
     /// Get a reference to the logical projection's project expressions.
     pub fn project_expressions(&self) -> &[BoundExpr] {
         self.project_expressions.as_ref()
@@ -36,7 +55,11 @@ impl LogicalProjection {
         for expr in &mut new_exprs {
             rewriter.rewrite_expr(expr);
         }
-        LogicalProjection::new(new_exprs, new_child)
+        Self {
+            project_expressions: new_exprs,
+            child: new_child,
+            table_ref_id: self.table_ref_id,
+        }
     }
 }
 impl PlanTreeNodeUnary for LogicalProjection {
@@ -45,7 +68,11 @@ impl PlanTreeNodeUnary for LogicalProjection {
     }
     #[must_use]
     fn clone_with_child(&self, child: PlanRef) -> Self {
-        Self::new(self.project_expressions().to_vec(), child)
+        Self {
+            project_expressions: self.project_expressions().to_vec(),
+            child,
+            table_ref_id: self.table_ref_id,
+        }
     }
 }
 impl_plan_tree_node_for_unary!(LogicalProjection);
@@ -71,6 +98,27 @@ impl PlanNode for LogicalProjection {
             })
             .collect()
     }
+
+    fn prune_col(&self, required_cols: BitSet) -> PlanRef {
+        // keep the first expression if nothing is required, so that the number of rows is kept
+        let mut exprs = if required_cols.is_empty() {
+            self.project_expressions[..1].to_vec()
+        } else {
+            required_cols
+                .iter()
+                .map(|index| self.project_expressions[index].clone())
+                .collect()
+        };
+        let mut child_required_cols = BitSet::new();
+        for expr in &exprs {
+            child_required_cols.union_with(&input_col_refs(expr));
+        }
+        let new_child = self.child.prune_col(child_required_cols.clone());
+        for expr in &mut exprs {
+            prune_input_col_refs(expr, &child_required_cols);
+        }
+        LogicalProjection::new(exprs, new_child).into_plan_ref()
+    }
 }
 
 impl fmt::Display for LogicalProjection {
@@ -82,8 +130,8 @@ impl fmt::Display for LogicalProjection {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::binder::{BoundColumnRef, BoundExprWithAlias, BoundTypeCast};
-    use crate::catalog::ColumnRefId;
+    use crate::binder::{BoundColumnRef, BoundExprWithAlias, BoundInputRef, BoundTypeCast};
+    use crate::catalog::{ColumnRefId, TableRefId};
     use crate::types::{DataTypeExt, DataTypeKind, DataValue};
 
     #[test]
@@ -115,4 +163,40 @@ mod tests {
         assert_eq!(column_names[2], "alias");
         assert_eq!(column_names[3], "?column?");
     }
+
+    #[test]
+    fn test_prune_scan_columns() {
+        let scan = Arc::new(LogicalTableScan::new(
+            TableRefId::new(0, 0, 0),
+            vec![0, 1, 2, 3],
+            (1..=4)
+                .map(|i| {
+                    DataTypeKind::Int(None)
+                        .not_null()
+                        .to_column(format!("v{}", i))
+                })
+                .collect(),
+            false,
+            false,
+            None,
+        ));
+        // select v3 from t
+        let plan = LogicalProjection::new(
+            vec![BoundExpr::InputRef(BoundInputRef {
+                index: 2,
+                return_type: DataTypeKind::Int(None).not_null(),
+            })],
+            scan,
+        );
+        let plan = plan.prune_col(BitSet::from_iter([0]));
+
+        let projection = plan.as_logical_projection().unwrap();
+        match &projection.project_expressions()[0] {
+            BoundExpr::InputRef(input_ref) => assert_eq!(input_ref.index, 0),
+            _ => panic!("unexpected expression"),
+        }
+        let scan = projection.child();
+        let scan = scan.as_logical_table_scan().unwrap();
+        assert_eq!(scan.column_ids(), &[2]);
+    }
 }
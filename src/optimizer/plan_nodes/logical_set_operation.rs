@@ -0,0 +1,79 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use super::*;
+use crate::binder::BoundSetOperator;
+use crate::types::DataType;
+
+/// The logical plan of set operation, e.g. `union`.
+///
+/// Both children must output the same types, except for nullability.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalSetOperation {
+    op: BoundSetOperator,
+    all: bool,
+    left_plan: PlanRef,
+    right_plan: PlanRef,
+    schema: Vec<ColumnDesc>,
+}
+
+impl LogicalSetOperation {
+    pub fn new(op: BoundSetOperator, all: bool, left_plan: PlanRef, right_plan: PlanRef) -> Self {
+        // the names of columns are from the left side
+        let schema = (left_plan.schema().into_iter())
+            .zip_eq(right_plan.schema())
+            .map(|(l, r)| {
+                let nullable = l.is_nullable() || r.is_nullable();
+                DataType::new(l.datatype().kind(), nullable).to_column(l.name().to_string())
+            })
+            .collect();
+        LogicalSetOperation {
+            op,
+            all,
+            left_plan,
+            right_plan,
+            schema,
+        }
+    }
+
+    /// Get the set operator.
+    pub fn op(&self) -> BoundSetOperator {
+        self.op
+    }
+
+    /// Whether duplicate rows are kept.
+    pub fn all(&self) -> bool {
+        self.all
+    }
+}
+
+impl PlanTreeNodeBinary for LogicalSetOperation {
+    fn left(&self) -> PlanRef {
+        self.left_plan.clone()
+    }
+    fn right(&self) -> PlanRef {
+        self.right_plan.clone()
+    }
+
+    #[must_use]
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(self.op, self.all, left, right)
+    }
+}
+impl_plan_tree_node_for_binary!(LogicalSetOperation);
+
+impl PlanNode for LogicalSetOperation {
+    fn schema(&self) -> Vec<ColumnDesc> {
+        self.schema.clone()
+    }
+}
+
+impl fmt::Display for LogicalSetOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "LogicalSetOperation: op {:?}, all {}", self.op, self.all)
+    }
+}
@@ -7,6 +7,7 @@ use serde::Serialize;
 
 use super::*;
 use crate::catalog::{ColumnDesc, TableRefId};
+use crate::optimizer::expr_utils::{input_col_refs, prune_input_col_refs};
 use crate::types::ColumnId;
 /// The logical plan of sequential scan operation.
 #[derive(Debug, Clone, Serialize)]
@@ -16,7 +17,12 @@ pub struct LogicalTableScan {
     column_descs: Vec<ColumnDesc>,
     with_row_handler: bool,
     is_sorted: bool,
-    expr: Option<BoundExpr>,
+    predicate: Option<ScanPredicate>,
+    offset: usize,
+    limit: Option<usize>,
+    /// The column whose secondary index is used by the scan to find the rows satisfying the
+    /// predicate.
+    index: Option<ColumnId>,
 }
 
 impl LogicalTableScan {
@@ -26,7 +32,7 @@ impl LogicalTableScan {
         column_descs: Vec<ColumnDesc>,
         with_row_handler: bool,
         is_sorted: bool,
-        expr: Option<BoundExpr>,
+        predicate: Option<ScanPredicate>,
     ) -> Self {
         Self {
             table_ref_id,
@@ -34,7 +40,27 @@ impl LogicalTableScan {
             column_descs,
             with_row_handler,
             is_sorted,
-            expr,
+            predicate,
+            offset: 0,
+            limit: None,
+            index: None,
+        }
+    }
+
+    /// Clone the logical table scan with the limit pushed down.
+    pub fn clone_with_limit(&self, offset: usize, limit: usize) -> Self {
+        Self {
+            offset,
+            limit: Some(limit),
+            ..self.clone()
+        }
+    }
+
+    /// Clone the logical table scan using the secondary index on the column.
+    pub fn clone_with_index(&self, column_id: ColumnId) -> Self {
+        Self {
+            index: Some(column_id),
+            ..self.clone()
         }
     }
 
@@ -63,9 +89,24 @@ impl LogicalTableScan {
         self.is_sorted
     }
 
-    /// Get a reference to the logical table scan's expr.
-    pub fn expr(&self) -> Option<&BoundExpr> {
-        self.expr.as_ref()
+    /// Get a reference to the predicate pushed down into the logical table scan.
+    pub fn predicate(&self) -> Option<&ScanPredicate> {
+        self.predicate.as_ref()
+    }
+
+    /// Get the number of leading rows to be skipped by the scan.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Get the maximum number of rows to be returned by the scan.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Get the column whose secondary index is used by the scan.
+    pub fn index(&self) -> Option<ColumnId> {
+        self.index
     }
 }
 impl PlanTreeNodeLeaf for LogicalTableScan {}
@@ -75,17 +116,61 @@ impl PlanNode for LogicalTableScan {
     fn schema(&self) -> Vec<ColumnDesc> {
         self.column_descs.clone()
     }
+
+    /// Only scan the required columns, the columns used by the predicate, and the primary key in
+    /// sorted scans. At least one column is scanned, so that the number of rows is kept.
+    fn prune_col(&self, required_cols: BitSet) -> PlanRef {
+        let mut scan_cols = required_cols.clone();
+        if let Some(predicate) = &self.predicate {
+            for cond in predicate.conds() {
+                scan_cols.union_with(&input_col_refs(cond));
+            }
+        }
+        if self.is_sorted {
+            scan_cols.extend((self.column_descs.iter()).positions(|desc| desc.is_primary()));
+        }
+        if scan_cols.is_empty() {
+            scan_cols.insert(0);
+        }
+        let predicate = self.predicate.as_ref().map(|predicate| {
+            let mut conds = predicate.conds().to_vec();
+            for cond in &mut conds {
+                prune_input_col_refs(cond, &scan_cols);
+            }
+            ScanPredicate::new(conds)
+        });
+        let scan = LogicalTableScan {
+            column_ids: scan_cols.iter().map(|i| self.column_ids[i]).collect(),
+            column_descs: scan_cols
+                .iter()
+                .map(|i| self.column_descs[i].clone())
+                .collect(),
+            predicate,
+            ..self.clone()
+        }
+        .into_plan_ref();
+        if required_cols.is_empty() || required_cols == scan_cols {
+            return scan;
+        }
+        let cols = required_cols
+            .iter()
+            .map(|index| scan_cols.iter().take_while(|col| *col < index).count());
+        project_cols(scan, cols)
+    }
 }
 impl fmt::Display for LogicalTableScan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(
                 f,
-                "LogicalTableScan: table #{}, columns [{}], with_row_handler: {}, is_sorted: {}, expr: {}",
+                "LogicalTableScan: table #{}, columns [{}], with_row_handler: {}, is_sorted: {}, predicate: {}, offset: {}, limit: {:?}{}",
                 self.table_ref_id.table_id,
                 self.column_ids.iter().map(ToString::to_string).join(", "),
                 self.with_row_handler,
                 self.is_sorted,
-                self.expr.clone().map_or_else(|| "None".to_string(), |expr| format!("{:?}", expr))
+                self.predicate.as_ref().map_or_else(|| "None".to_string(), ToString::to_string),
+                self.offset,
+                self.limit,
+                self.index.map_or_else(String::new, |id| format!(", index: {}", id))
             )
     }
 }
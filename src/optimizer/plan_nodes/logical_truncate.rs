@@ -0,0 +1,34 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+use crate::catalog::TableRefId;
+
+/// The logical plan of `TRUNCATE TABLE`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalTruncate {
+    table_ref_id: TableRefId,
+}
+
+impl LogicalTruncate {
+    pub fn new(table_ref_id: TableRefId) -> Self {
+        Self { table_ref_id }
+    }
+
+    /// Get a reference to the logical truncate's table ref id.
+    pub fn table_ref_id(&self) -> TableRefId {
+        self.table_ref_id
+    }
+}
+impl PlanTreeNodeLeaf for LogicalTruncate {}
+impl_plan_tree_node_for_leaf!(LogicalTruncate);
+impl PlanNode for LogicalTruncate {}
+
+impl fmt::Display for LogicalTruncate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "LogicalTruncate: table {}", self.table_ref_id.table_id)
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+use crate::binder::BoundExpr;
+use crate::catalog::TableRefId;
+use crate::optimizer::logical_plan_rewriter::ExprRewriter;
+
+/// The logical plan of `UPDATE`.
+///
+/// The old versions of rows from the child are deleted by their row handlers, and new versions
+/// computed by `values` are appended.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogicalUpdate {
+    table_ref_id: TableRefId,
+    values: Vec<BoundExpr>,
+    child: PlanRef,
+}
+
+impl LogicalUpdate {
+    pub fn new(table_ref_id: TableRefId, values: Vec<BoundExpr>, child: PlanRef) -> Self {
+        Self {
+            table_ref_id,
+            values,
+            child,
+        }
+    }
+
+    /// Get a reference to the logical update's table ref id.
+    pub fn table_ref_id(&self) -> TableRefId {
+        self.table_ref_id
+    }
+
+    /// Get a reference to the logical update's values.
+    pub fn values(&self) -> &[BoundExpr] {
+        self.values.as_ref()
+    }
+
+    pub fn clone_with_rewrite_expr(
+        &self,
+        new_child: PlanRef,
+        rewriter: &impl ExprRewriter,
+    ) -> Self {
+        let mut new_values = self.values.clone();
+        for expr in &mut new_values {
+            rewriter.rewrite_expr(expr);
+        }
+        LogicalUpdate::new(self.table_ref_id, new_values, new_child)
+    }
+}
+impl PlanTreeNodeUnary for LogicalUpdate {
+    fn child(&self) -> PlanRef {
+        self.child.clone()
+    }
+
+    fn clone_with_child(&self, child: PlanRef) -> Self {
+        Self::new(self.table_ref_id(), self.values.clone(), child)
+    }
+}
+impl_plan_tree_node_for_unary!(LogicalUpdate);
+impl PlanNode for LogicalUpdate {}
+
+impl fmt::Display for LogicalUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalUpdate: table {}, values {:?}",
+            self.table_ref_id.table_id, self.values
+        )
+    }
+}
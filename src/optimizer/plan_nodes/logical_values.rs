@@ -6,6 +6,7 @@ use serde::Serialize;
 
 use super::*;
 use crate::binder::BoundExpr;
+use crate::catalog::TableRefId;
 use crate::optimizer::logical_plan_rewriter::ExprRewriter;
 use crate::types::DataType;
 
@@ -15,6 +16,9 @@ pub struct LogicalValues {
     column_types: Vec<DataType>,
     schema: Vec<ColumnDesc>,
     values: Vec<Vec<BoundExpr>>,
+    /// The table ref id of the `VALUES` relation whose columns can be referred, or `None` if
+    /// the values are inserted.
+    table_ref_id: Option<TableRefId>,
 }
 
 impl LogicalValues {
@@ -22,14 +26,22 @@ impl LogicalValues {
         column_types: Vec<DataType>,
         schema: Vec<ColumnDesc>,
         values: Vec<Vec<BoundExpr>>,
+        table_ref_id: Option<TableRefId>,
     ) -> Self {
         Self {
             column_types,
             schema,
             values,
+            table_ref_id,
         }
     }
 
+    /// Create an empty relation with the schema, which produces no rows.
+    pub fn empty(schema: Vec<ColumnDesc>) -> Self {
+        let column_types = schema.iter().map(|desc| desc.datatype().clone()).collect();
+        Self::new(column_types, schema, vec![], None)
+    }
+
     /// Get a reference to the logical values's column types.
     pub fn column_types(&self) -> &[DataType] {
         self.column_types.as_ref()
@@ -39,6 +51,11 @@ impl LogicalValues {
     pub fn values(&self) -> &[Vec<BoundExpr>] {
         self.values.as_ref()
     }
+
+    /// Get the table ref id of the logical values.
+    pub fn table_ref_id(&self) -> Option<TableRefId> {
+        self.table_ref_id
+    }
     pub fn clone_with_rewrite_expr(&self, rewriter: &impl ExprRewriter) -> Self {
         let mut values = self.values().to_vec();
         for row in &mut values {
@@ -47,7 +64,12 @@ impl LogicalValues {
             }
         }
 
-        LogicalValues::new(self.column_types().to_vec(), self.schema.clone(), values)
+        LogicalValues::new(
+            self.column_types().to_vec(),
+            self.schema.clone(),
+            values,
+            self.table_ref_id,
+        )
     }
 }
 impl PlanTreeNodeLeaf for LogicalValues {}
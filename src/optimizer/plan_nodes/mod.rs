@@ -17,15 +17,21 @@ mod plan_tree_node;
 pub use plan_tree_node::*;
 mod join_predicate;
 pub use join_predicate::*;
+mod scan_predicate;
+pub use scan_predicate::*;
 
 // Import and use all plan nodes
 
 mod dummy;
 mod logical_aggregate;
+mod logical_alter_table;
+mod logical_analyze;
 mod logical_copy_from_file;
 mod logical_copy_to_file;
+mod logical_create_index;
 mod logical_create_table;
 mod logical_delete;
+mod logical_distinct;
 mod logical_drop;
 mod logical_explain;
 mod logical_filter;
@@ -34,12 +40,19 @@ mod logical_join;
 mod logical_limit;
 mod logical_order;
 mod logical_projection;
+mod logical_set_operation;
 mod logical_table_scan;
+mod logical_truncate;
+mod logical_update;
 mod logical_values;
+mod physical_alter_table;
+mod physical_analyze;
 mod physical_copy_from_file;
 mod physical_copy_to_file;
+mod physical_create_index;
 mod physical_create_table;
 mod physical_delete;
+mod physical_distinct;
 mod physical_drop;
 mod physical_explain;
 mod physical_filter;
@@ -47,19 +60,28 @@ mod physical_hash_agg;
 mod physical_hash_join;
 mod physical_insert;
 mod physical_limit;
+mod physical_merge_join;
 mod physical_nested_loop_join;
 mod physical_order;
 mod physical_projection;
+mod physical_set_operation;
 mod physical_simple_agg;
 mod physical_table_scan;
+mod physical_top_n;
+mod physical_truncate;
+mod physical_update;
 mod physical_values;
 
 pub use dummy::*;
 pub use logical_aggregate::*;
+pub use logical_alter_table::*;
+pub use logical_analyze::*;
 pub use logical_copy_from_file::*;
 pub use logical_copy_to_file::*;
+pub use logical_create_index::*;
 pub use logical_create_table::*;
 pub use logical_delete::*;
+pub use logical_distinct::*;
 pub use logical_drop::*;
 pub use logical_explain::*;
 pub use logical_filter::*;
@@ -68,12 +90,19 @@ pub use logical_join::*;
 pub use logical_limit::*;
 pub use logical_order::*;
 pub use logical_projection::*;
+pub use logical_set_operation::*;
 pub use logical_table_scan::*;
+pub use logical_truncate::*;
+pub use logical_update::*;
 pub use logical_values::*;
+pub use physical_alter_table::*;
+pub use physical_analyze::*;
 pub use physical_copy_from_file::*;
 pub use physical_copy_to_file::*;
+pub use physical_create_index::*;
 pub use physical_create_table::*;
 pub use physical_delete::*;
+pub use physical_distinct::*;
 pub use physical_drop::*;
 pub use physical_explain::*;
 pub use physical_filter::*;
@@ -81,11 +110,16 @@ pub use physical_hash_agg::*;
 pub use physical_hash_join::*;
 pub use physical_insert::*;
 pub use physical_limit::*;
+pub use physical_merge_join::*;
 pub use physical_nested_loop_join::*;
 pub use physical_order::*;
 pub use physical_projection::*;
+pub use physical_set_operation::*;
 pub use physical_simple_agg::*;
 pub use physical_table_scan::*;
+pub use physical_top_n::*;
+pub use physical_truncate::*;
+pub use physical_update::*;
 pub use physical_values::*;
 
 use crate::catalog::ColumnDesc;
@@ -129,7 +163,8 @@ pub trait PlanNode:
     }
 
     /// transform the plan node to only output the required columns ordered by index number, only
-    /// logical plan node will use it, though all plan node impl it.
+    /// logical plan node will use it, though all plan node impl it. If no column is required, the
+    /// plan node may output any columns, so that the number of rows is kept.
     fn prune_col(&self, required_cols: BitSet) -> PlanRef {
         let input_types = self.out_types();
         let mut need_prune = false;
@@ -171,6 +206,20 @@ impl dyn PlanNode {
 
 serialize_trait_object!(PlanNode);
 
+/// Project the columns at the positions from the output of the plan.
+fn project_cols(plan: PlanRef, cols: impl Iterator<Item = usize>) -> PlanRef {
+    let input_types = plan.out_types();
+    let exprs = cols
+        .map(|index| {
+            BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: input_types[index].clone(),
+            })
+        })
+        .collect();
+    LogicalProjection::new(exprs, plan).into_plan_ref()
+}
+
 /// All Plan nodes
 ///
 /// You can use it as follows:
@@ -191,6 +240,7 @@ macro_rules! for_all_plan_nodes {
             LogicalInsert,
             LogicalValues,
             LogicalCreateTable,
+            LogicalAlterTable,
             LogicalDrop,
             LogicalProjection,
             LogicalFilter,
@@ -202,10 +252,17 @@ macro_rules! for_all_plan_nodes {
             LogicalDelete,
             LogicalCopyFromFile,
             LogicalCopyToFile,
+            LogicalSetOperation,
+            LogicalUpdate,
+            LogicalAnalyze,
+            LogicalTruncate,
+            LogicalCreateIndex,
+            LogicalDistinct,
             PhysicalTableScan,
             PhysicalInsert,
             PhysicalValues,
             PhysicalCreateTable,
+            PhysicalAlterTable,
             PhysicalDrop,
             PhysicalProjection,
             PhysicalFilter,
@@ -214,11 +271,19 @@ macro_rules! for_all_plan_nodes {
             PhysicalSimpleAgg,
             PhysicalHashAgg,
             PhysicalHashJoin,
+            PhysicalMergeJoin,
             PhysicalOrder,
             PhysicalLimit,
+            PhysicalTopN,
             PhysicalDelete,
             PhysicalCopyFromFile,
-            PhysicalCopyToFile
+            PhysicalCopyToFile,
+            PhysicalSetOperation,
+            PhysicalUpdate,
+            PhysicalAnalyze,
+            PhysicalTruncate,
+            PhysicalCreateIndex,
+            PhysicalDistinct
         }
     };
 }
@@ -229,6 +294,7 @@ pub trait WithPlanNodeType {
 macro_rules! enum_plan_node_type {
     ([], $($node_name:ident),*) => {
         /// each enum value represent a [`PlanNode`] struct type, help us to dispatch and downcast
+        #[derive(Debug)]
         pub enum PlanNodeType {
             $( $node_name ),*
         }
@@ -0,0 +1,43 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+
+/// The physical plan of `ALTER TABLE`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhysicalAlterTable {
+    logical: LogicalAlterTable,
+}
+
+impl PhysicalAlterTable {
+    pub fn new(logical: LogicalAlterTable) -> Self {
+        Self { logical }
+    }
+
+    /// Get a reference to the physical alter table's logical.
+    pub fn logical(&self) -> &LogicalAlterTable {
+        &self.logical
+    }
+}
+
+impl PlanTreeNodeLeaf for PhysicalAlterTable {}
+impl_plan_tree_node_for_leaf!(PhysicalAlterTable);
+
+impl PlanNode for PhysicalAlterTable {}
+
+impl fmt::Display for PhysicalAlterTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let column = self.logical().column();
+        writeln!(
+            f,
+            "PhysicalAlterTable: table {}, add column {}:{:?} default {:?}",
+            self.logical().table_ref_id().table_id,
+            column.name(),
+            column.datatype(),
+            column.default_value()
+        )
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+
+/// The physical plan of `ANALYZE`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhysicalAnalyze {
+    logical: LogicalAnalyze,
+}
+
+impl PhysicalAnalyze {
+    pub fn new(logical: LogicalAnalyze) -> Self {
+        Self { logical }
+    }
+
+    /// Get a reference to the physical analyze's logical.
+    pub fn logical(&self) -> &LogicalAnalyze {
+        &self.logical
+    }
+}
+
+impl PlanTreeNodeUnary for PhysicalAnalyze {
+    fn child(&self) -> PlanRef {
+        self.logical.child()
+    }
+    #[must_use]
+    fn clone_with_child(&self, child: PlanRef) -> Self {
+        Self::new(self.logical().clone_with_child(child))
+    }
+}
+impl_plan_tree_node_for_unary!(PhysicalAnalyze);
+impl PlanNode for PhysicalAnalyze {}
+impl fmt::Display for PhysicalAnalyze {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalAnalyze: table {}",
+            self.logical().table_ref_id().table_id
+        )
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+
+/// The physical plan of `CREATE INDEX`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhysicalCreateIndex {
+    logical: LogicalCreateIndex,
+}
+
+impl PhysicalCreateIndex {
+    pub fn new(logical: LogicalCreateIndex) -> Self {
+        Self { logical }
+    }
+
+    /// Get a reference to the physical create index's logical.
+    pub fn logical(&self) -> &LogicalCreateIndex {
+        &self.logical
+    }
+}
+
+impl PlanTreeNodeLeaf for PhysicalCreateIndex {}
+impl_plan_tree_node_for_leaf!(PhysicalCreateIndex);
+
+impl PlanNode for PhysicalCreateIndex {}
+
+impl fmt::Display for PhysicalCreateIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalCreateIndex: table {}, column {:?}",
+            self.logical().table_ref_id().table_id,
+            self.logical().column_id()
+        )
+    }
+}
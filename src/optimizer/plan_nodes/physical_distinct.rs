@@ -0,0 +1,46 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+
+/// The physical plan of `select distinct`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhysicalDistinct {
+    logical: LogicalDistinct,
+}
+
+impl PhysicalDistinct {
+    pub fn new(logical: LogicalDistinct) -> Self {
+        Self { logical }
+    }
+
+    /// Get a reference to the physical distinct's logical.
+    pub fn logical(&self) -> &LogicalDistinct {
+        &self.logical
+    }
+}
+
+impl PlanTreeNodeUnary for PhysicalDistinct {
+    fn child(&self) -> PlanRef {
+        self.logical.child()
+    }
+    #[must_use]
+    fn clone_with_child(&self, child: PlanRef) -> Self {
+        Self::new(self.logical().clone_with_child(child))
+    }
+}
+impl_plan_tree_node_for_unary!(PhysicalDistinct);
+impl PlanNode for PhysicalDistinct {
+    fn schema(&self) -> Vec<ColumnDesc> {
+        self.logical().schema()
+    }
+}
+
+impl fmt::Display for PhysicalDistinct {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "PhysicalDistinct")
+    }
+}
@@ -41,8 +41,9 @@ impl fmt::Display for PhysicalHashAgg {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(
             f,
-            "PhysicalHashAgg: {} agg calls",
-            self.logical().agg_calls().len(),
+            "PhysicalHashAgg: group keys {:?}, agg calls {:?}",
+            self.logical().group_keys(),
+            self.logical().agg_calls(),
         )
     }
 }
@@ -10,16 +10,20 @@ use super::*;
 #[derive(Clone, Debug, Serialize)]
 pub struct PhysicalHashJoin {
     logical: LogicalJoin,
-    left_column_index: usize,
-    right_column_index: usize,
+    left_column_indexes: Vec<usize>,
+    right_column_indexes: Vec<usize>,
 }
 
 impl PhysicalHashJoin {
-    pub fn new(logical: LogicalJoin, left_column_index: usize, right_column_index: usize) -> Self {
+    pub fn new(
+        logical: LogicalJoin,
+        left_column_indexes: Vec<usize>,
+        right_column_indexes: Vec<usize>,
+    ) -> Self {
         Self {
             logical,
-            left_column_index,
-            right_column_index,
+            left_column_indexes,
+            right_column_indexes,
         }
     }
 
@@ -28,14 +32,14 @@ impl PhysicalHashJoin {
         &self.logical
     }
 
-    /// Get a reference to the physical hash join's left column index.
-    pub fn left_column_index(&self) -> usize {
-        self.left_column_index
+    /// Get a reference to the physical hash join's left column indexes.
+    pub fn left_column_indexes(&self) -> &[usize] {
+        &self.left_column_indexes
     }
 
-    /// Get a reference to the physical hash join's right column index.
-    pub fn right_column_index(&self) -> usize {
-        self.right_column_index
+    /// Get a reference to the physical hash join's right column indexes.
+    pub fn right_column_indexes(&self) -> &[usize] {
+        &self.right_column_indexes
     }
 }
 impl PlanTreeNodeBinary for PhysicalHashJoin {
@@ -50,8 +54,8 @@ impl PlanTreeNodeBinary for PhysicalHashJoin {
     fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
         Self::new(
             self.logical.clone_with_left_right(left, right),
-            self.left_column_index(),
-            self.right_column_index(),
+            self.left_column_indexes.clone(),
+            self.right_column_indexes.clone(),
         )
     }
 }
@@ -68,11 +72,10 @@ impl fmt::Display for PhysicalHashJoin {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(
             f,
-            "PhysicalHashJoin: op {:?}, left_index {:?},  right_index {:?}, predicate: {} ",
+            "PhysicalHashJoin: op {:?}, left_index {:?}, right_index {:?}",
             self.logical().join_op(),
-            self.left_column_index,
-            self.right_column_index,
-            self.logical().predicate()
+            self.left_column_indexes,
+            self.right_column_indexes,
         )
     }
 }
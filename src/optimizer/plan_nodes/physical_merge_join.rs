@@ -0,0 +1,81 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+
+/// The physical plan of sort-merge join, whose inputs are both sorted by the join keys.
+#[derive(Clone, Debug, Serialize)]
+pub struct PhysicalMergeJoin {
+    logical: LogicalJoin,
+    left_column_indexes: Vec<usize>,
+    right_column_indexes: Vec<usize>,
+}
+
+impl PhysicalMergeJoin {
+    pub fn new(
+        logical: LogicalJoin,
+        left_column_indexes: Vec<usize>,
+        right_column_indexes: Vec<usize>,
+    ) -> Self {
+        Self {
+            logical,
+            left_column_indexes,
+            right_column_indexes,
+        }
+    }
+
+    /// Get a reference to the physical merge join's logical.
+    pub fn logical(&self) -> &LogicalJoin {
+        &self.logical
+    }
+
+    /// Get a reference to the physical merge join's left column indexes.
+    pub fn left_column_indexes(&self) -> &[usize] {
+        &self.left_column_indexes
+    }
+
+    /// Get a reference to the physical merge join's right column indexes.
+    pub fn right_column_indexes(&self) -> &[usize] {
+        &self.right_column_indexes
+    }
+}
+
+impl PlanTreeNodeBinary for PhysicalMergeJoin {
+    fn left(&self) -> PlanRef {
+        self.logical.left()
+    }
+    fn right(&self) -> PlanRef {
+        self.logical.right()
+    }
+
+    #[must_use]
+    fn clone_with_left_right(&self, left: PlanRef, right: PlanRef) -> Self {
+        Self::new(
+            self.logical.clone_with_left_right(left, right),
+            self.left_column_indexes.clone(),
+            self.right_column_indexes.clone(),
+        )
+    }
+}
+impl_plan_tree_node_for_binary!(PhysicalMergeJoin);
+
+impl PlanNode for PhysicalMergeJoin {
+    fn schema(&self) -> Vec<ColumnDesc> {
+        self.logical().schema()
+    }
+}
+
+impl fmt::Display for PhysicalMergeJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalMergeJoin: op {:?}, left_index {:?}, right_index {:?}",
+            self.logical().join_op(),
+            self.left_column_indexes,
+            self.right_column_indexes,
+        )
+    }
+}
@@ -36,12 +36,15 @@ impl fmt::Display for PhysicalTableScan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(
             f,
-            "PhysicalTableScan: table #{}, columns [{}], with_row_handler: {}, is_sorted: {}, expr: {}",
+            "PhysicalTableScan: table #{}, columns [{}], with_row_handler: {}, is_sorted: {}, predicate: {}, offset: {}, limit: {:?}{}",
             self.logical().table_ref_id().table_id,
             self.logical().column_ids().iter().map(ToString::to_string).join(", "),
             self.logical().with_row_handler(),
             self.logical().is_sorted(),
-            self.logical().expr().map_or_else(|| "None".to_string(), |expr| format!("{:?}", expr))
+            self.logical().predicate().map_or_else(|| "None".to_string(), ToString::to_string),
+            self.logical().offset(),
+            self.logical().limit(),
+            self.logical().index().map_or_else(String::new, |id| format!(", index: {}", id))
         )
     }
 }
@@ -0,0 +1,74 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+use crate::binder::BoundOrderBy;
+
+/// The physical plan of top N operation, i.e. an order operation followed by a limit.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhysicalTopN {
+    offset: usize,
+    limit: usize,
+    comparators: Vec<BoundOrderBy>,
+    child: PlanRef,
+}
+
+impl PhysicalTopN {
+    pub fn new(
+        offset: usize,
+        limit: usize,
+        comparators: Vec<BoundOrderBy>,
+        child: PlanRef,
+    ) -> Self {
+        Self {
+            offset,
+            limit,
+            comparators,
+            child,
+        }
+    }
+
+    /// Get a reference to the physical top N's offset.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Get a reference to the physical top N's limit.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Get a reference to the physical top N's comparators.
+    pub fn comparators(&self) -> &[BoundOrderBy] {
+        self.comparators.as_ref()
+    }
+}
+
+impl PlanTreeNodeUnary for PhysicalTopN {
+    fn child(&self) -> PlanRef {
+        self.child.clone()
+    }
+    #[must_use]
+    fn clone_with_child(&self, child: PlanRef) -> Self {
+        Self::new(self.offset, self.limit, self.comparators.clone(), child)
+    }
+}
+impl_plan_tree_node_for_unary!(PhysicalTopN);
+impl PlanNode for PhysicalTopN {
+    fn schema(&self) -> Vec<ColumnDesc> {
+        self.child.schema()
+    }
+}
+
+impl fmt::Display for PhysicalTopN {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalTopN: offset: {}, limit: {}, order: {:?}",
+            self.offset, self.limit, self.comparators
+        )
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::*;
+
+/// The physical plan of `TRUNCATE TABLE`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhysicalTruncate {
+    logical: LogicalTruncate,
+}
+
+impl PhysicalTruncate {
+    pub fn new(logical: LogicalTruncate) -> Self {
+        Self { logical }
+    }
+
+    /// Get a reference to the physical truncate's logical.
+    pub fn logical(&self) -> &LogicalTruncate {
+        &self.logical
+    }
+}
+
+impl PlanTreeNodeLeaf for PhysicalTruncate {}
+impl_plan_tree_node_for_leaf!(PhysicalTruncate);
+
+impl PlanNode for PhysicalTruncate {}
+
+impl fmt::Display for PhysicalTruncate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalTruncate: table {}",
+            self.logical().table_ref_id().table_id
+        )
+    }
+}
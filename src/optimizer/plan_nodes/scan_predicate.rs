@@ -0,0 +1,112 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use serde::Serialize;
+
+use crate::binder::BoundExpr;
+use crate::optimizer::expr_utils::{conjunctions, input_col_refs, merge_conjunctions};
+use crate::types::DataValue;
+
+#[derive(Debug, Clone, Serialize)]
+/// the predicate pushed down into a table scan, which is evaluated by the storage.
+pub struct ScanPredicate {
+    /// the conditions that only reference scan columns, linked with AND conjunction.
+    conds: Vec<BoundExpr>,
+}
+
+impl ScanPredicate {
+    pub fn new(conds: Vec<BoundExpr>) -> Self {
+        Self { conds }
+    }
+
+    /// `create` will split the filter condition above a scan with `cols_num` output columns into
+    /// a `ScanPredicate` and the residual conditions that can't be pushed into the scan.
+    /// e.g.
+    /// ```sql
+    ///   input_ref(0) > 1 and input_ref(1) = 'a' and 1 = 1
+    /// ```
+    /// will return the predicate `input_ref(0) > 1 and input_ref(1) = 'a'` and nothing
+    /// residual, as `1 = 1` is always true. Conditions without any column, or with
+    /// expressions the storage can't evaluate, are residual.
+    pub fn create(cols_num: usize, cond: BoundExpr) -> (Self, Vec<BoundExpr>) {
+        let mut conds = vec![];
+        let mut residual_conds = vec![];
+        for cond in conjunctions(cond) {
+            if let BoundExpr::Constant(DataValue::Bool(true)) = cond {
+                continue;
+            }
+            let cols = input_col_refs(&cond);
+            let can_push = !cols.is_empty()
+                && cols.iter().all(|col| col < cols_num)
+                && Self::can_eval_in_storage(&cond);
+            if can_push {
+                conds.push(cond);
+            } else {
+                residual_conds.push(cond);
+            }
+        }
+        (Self::new(conds), residual_conds)
+    }
+
+    /// Check whether the expression can be evaluated by `eval_array_in_storage`.
+    fn can_eval_in_storage(expr: &BoundExpr) -> bool {
+        use BoundExpr::*;
+        match expr {
+            InputRef(_) | Constant(_) => true,
+            BinaryOp(binary_op) => {
+                Self::can_eval_in_storage(&binary_op.left_expr)
+                    && Self::can_eval_in_storage(&binary_op.right_expr)
+            }
+            UnaryOp(unary_op) => Self::can_eval_in_storage(&unary_op.expr),
+            TypeCast(cast) => Self::can_eval_in_storage(&cast.expr),
+            IsNull(isnull) => Self::can_eval_in_storage(&isnull.expr),
+            ScalarFunction(func) => func.args.iter().all(Self::can_eval_in_storage),
+            Case(case) => {
+                case.when_then.iter().all(|(cond, result)| {
+                    Self::can_eval_in_storage(cond) && Self::can_eval_in_storage(result)
+                }) && case
+                    .else_result
+                    .as_ref()
+                    .map_or(true, |expr| Self::can_eval_in_storage(expr))
+            }
+            Between(between) => {
+                Self::can_eval_in_storage(&between.expr)
+                    && Self::can_eval_in_storage(&between.low)
+                    && Self::can_eval_in_storage(&between.high)
+            }
+            Like(like) => {
+                Self::can_eval_in_storage(&like.expr) && Self::can_eval_in_storage(&like.pattern)
+            }
+            ColumnRef(_) | AggCall(_) | ExprWithAlias(_) | Alias(_) | Subquery(_) => false,
+        }
+    }
+
+    /// Merge with another predicate, e.g. when a filter is pushed into a scan with a predicate.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self::new(
+            self.conds
+                .iter()
+                .chain(other.conds.iter())
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Get a reference to the scan predicate's conds.
+    pub fn conds(&self) -> &[BoundExpr] {
+        self.conds.as_ref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conds.is_empty()
+    }
+
+    pub fn to_expr(&self) -> BoundExpr {
+        merge_conjunctions(self.conds.iter().cloned())
+    }
+}
+
+impl std::fmt::Display for ScanPredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.to_expr())
+    }
+}
@@ -3,8 +3,14 @@
 use std::sync::Arc;
 
 use super::*;
-use crate::optimizer::plan_nodes::{LogicalTableScan, PlanTreeNodeUnary};
+use crate::optimizer::expr_utils::merge_conjunctions;
+use crate::optimizer::plan_nodes::{
+    LogicalFilter, LogicalTableScan, PlanTreeNodeUnary, ScanPredicate,
+};
 
+/// Push the conditions of a filter into the table scan below it as a [`ScanPredicate`], so that
+/// the storage can skip blocks with statistics. Conditions that can't be evaluated by the storage
+/// are kept in the filter.
 pub struct FilterScanRule {}
 
 impl Rule for FilterScanRule {
@@ -12,13 +18,113 @@ impl Rule for FilterScanRule {
         let filter = plan.as_logical_filter()?;
         let child = filter.child();
         let scan = child.as_logical_table_scan()?.clone();
-        Ok(Arc::new(LogicalTableScan::new(
+        let (predicate, residual_conds) =
+            ScanPredicate::create(scan.column_ids().len(), filter.expr().clone());
+        if predicate.is_empty() {
+            return Err(());
+        }
+        let predicate = match scan.predicate() {
+            Some(scan_predicate) => scan_predicate.merge(&predicate),
+            None => predicate,
+        };
+        let new_scan = Arc::new(LogicalTableScan::new(
             scan.table_ref_id(),
             scan.column_ids().to_vec(),
             scan.column_descs().to_vec(),
             scan.with_row_handler(),
             scan.is_sorted(),
-            Some(filter.expr().clone()),
+            Some(predicate),
+        ));
+        if residual_conds.is_empty() {
+            return Ok(new_scan);
+        }
+        Ok(Arc::new(LogicalFilter::new(
+            merge_conjunctions(residual_conds.into_iter()),
+            new_scan,
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binder::{BoundBinaryOp, BoundExpr, BoundInputRef};
+    use crate::catalog::TableRefId;
+    use crate::parser::BinaryOperator;
+    use crate::types::{DataTypeExt, DataTypeKind, DataValue};
+
+    fn scan() -> PlanRef {
+        Arc::new(LogicalTableScan::new(
+            TableRefId::new(0, 0, 0),
+            vec![0, 1],
+            vec![
+                DataTypeKind::Int(None).not_null().to_column("v1".into()),
+                DataTypeKind::Int(None).not_null().to_column("v2".into()),
+            ],
+            false,
+            false,
+            None,
+        ))
+    }
+
+    fn binary_op(op: BinaryOperator, left: BoundExpr, right: BoundExpr) -> BoundExpr {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op,
+            left_expr: Box::new(left),
+            right_expr: Box::new(right),
+            return_type: Some(DataTypeKind::Boolean.nullable()),
+        })
+    }
+
+    fn input_ref(index: usize) -> BoundExpr {
+        BoundExpr::InputRef(BoundInputRef {
+            index,
+            return_type: DataTypeKind::Int(None).not_null(),
+        })
+    }
+
+    #[test]
+    fn test_push_all_conditions() {
+        // v1 > 1 and v2 = v1
+        let cond = binary_op(
+            BinaryOperator::And,
+            binary_op(
+                BinaryOperator::Gt,
+                input_ref(0),
+                BoundExpr::Constant(DataValue::Int32(1)),
+            ),
+            binary_op(BinaryOperator::Eq, input_ref(1), input_ref(0)),
+        );
+        let plan = Arc::new(LogicalFilter::new(cond, scan()));
+        let plan = FilterScanRule {}.apply(plan).unwrap();
+        let scan = plan.as_logical_table_scan().unwrap();
+        assert_eq!(scan.predicate().unwrap().conds().len(), 2);
+    }
+
+    #[test]
+    fn test_keep_residual_conditions() {
+        // v1 > 1 and false
+        let cond = binary_op(
+            BinaryOperator::And,
+            binary_op(
+                BinaryOperator::Gt,
+                input_ref(0),
+                BoundExpr::Constant(DataValue::Int32(1)),
+            ),
+            BoundExpr::Constant(DataValue::Bool(false)),
+        );
+        let plan = Arc::new(LogicalFilter::new(cond, scan()));
+        let plan = FilterScanRule {}.apply(plan).unwrap();
+        let filter = plan.as_logical_filter().unwrap();
+        assert!(matches!(
+            filter.expr(),
+            BoundExpr::Constant(DataValue::Bool(false))
+        ));
+        let child = filter.child();
+        let scan = child.as_logical_table_scan().unwrap();
+        assert_eq!(scan.predicate().unwrap().conds().len(), 1);
+
+        // nothing more can be pushed
+        assert!(FilterScanRule {}.apply(plan.clone()).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use super::*;
+use crate::binder::BoundExpr;
+use crate::catalog::RootCatalogRef;
+use crate::parser::BinaryOperator;
+use crate::types::{ColumnId, DataValue};
+
+/// Use the secondary index on a column for a table scan whose predicate compares the column with
+/// a constant, so that the storage only reads the blocks containing the matched rows.
+///
+/// If several indexed columns are compared, a column compared by `=` is preferred, since fewer
+/// rows are expected to match. Filters are expected to be pushed into the scan by
+/// [`FilterScanRule`] before this rule is applied.
+pub struct IndexScanRule {
+    pub catalog: RootCatalogRef,
+}
+
+impl Rule for IndexScanRule {
+    fn apply(&self, plan: PlanRef) -> Result<PlanRef, ()> {
+        let scan = plan.as_logical_table_scan()?;
+        // sorted scans merge rowsets by the sort key and read all rows anyway
+        if scan.index().is_some() || scan.is_sorted() {
+            return Err(());
+        }
+        let predicate = scan.predicate().ok_or(())?;
+        let indexes = self
+            .catalog
+            .get_table(&scan.table_ref_id())
+            .ok_or(())?
+            .indexes();
+
+        let mut chosen: Option<(ColumnId, bool)> = None;
+        for cond in predicate.conds() {
+            let (index, op) = match comparison(cond) {
+                Some(comparison) => comparison,
+                None => continue,
+            };
+            let column_id = scan.column_ids()[index];
+            let is_eq = op == BinaryOperator::Eq;
+            if indexes.contains(&column_id) && chosen.map_or(true, |(_, eq)| is_eq && !eq) {
+                chosen = Some((column_id, is_eq));
+            }
+        }
+        let (column_id, _) = chosen.ok_or(())?;
+        Ok(Arc::new(scan.clone_with_index(column_id)))
+    }
+}
+
+/// Match a condition in the form of `column <op> constant` or `constant <op> column` that can be
+/// answered by an index, and return the column and the operator.
+fn comparison(cond: &BoundExpr) -> Option<(usize, BinaryOperator)> {
+    use BinaryOperator::*;
+
+    let binary_op = match cond {
+        BoundExpr::BinaryOp(binary_op) => binary_op,
+        _ => return None,
+    };
+    if !matches!(binary_op.op, Eq | Lt | LtEq | Gt | GtEq) {
+        return None;
+    }
+    match (&*binary_op.left_expr, &*binary_op.right_expr) {
+        (BoundExpr::InputRef(input_ref), BoundExpr::Constant(value))
+        | (BoundExpr::Constant(value), BoundExpr::InputRef(input_ref))
+            if *value != DataValue::Null =>
+        {
+            Some((input_ref.index, binary_op.op.clone()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binder::{BoundBinaryOp, BoundInputRef};
+    use crate::catalog::{ColumnCatalog, RootCatalog, TableRefId};
+    use crate::optimizer::plan_nodes::{LogicalTableScan, ScanPredicate};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    /// Create a table `t(v1, v2, v3)` with indexes on `v1` and `v2`.
+    fn catalog() -> RootCatalogRef {
+        let catalog = Arc::new(RootCatalog::new());
+        let schema = (catalog.get_database_by_id(0).unwrap())
+            .get_schema_by_id(0)
+            .unwrap();
+        let columns = (["v1", "v2", "v3"].iter().enumerate())
+            .map(|(id, name)| {
+                let desc = DataTypeKind::Int(None)
+                    .nullable()
+                    .to_column(name.to_string());
+                ColumnCatalog::new(id as ColumnId, desc)
+            })
+            .collect();
+        let table_id = schema.add_table("t".into(), columns, false).unwrap();
+        let table = schema.get_table_by_id(table_id).unwrap();
+        table.add_index(0).unwrap();
+        table.add_index(1).unwrap();
+        catalog
+    }
+
+    fn cmp(op: BinaryOperator, index: usize, value: DataValue) -> BoundExpr {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op,
+            left_expr: Box::new(BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: DataTypeKind::Int(None).nullable(),
+            })),
+            right_expr: Box::new(BoundExpr::Constant(value)),
+            return_type: Some(DataTypeKind::Boolean.nullable()),
+        })
+    }
+
+    fn scan(conds: Vec<BoundExpr>) -> PlanRef {
+        Arc::new(LogicalTableScan::new(
+            TableRefId::new(0, 0, 0),
+            vec![2, 0, 1],
+            ["v3", "v1", "v2"]
+                .iter()
+                .map(|name| {
+                    DataTypeKind::Int(None)
+                        .nullable()
+                        .to_column(name.to_string())
+                })
+                .collect(),
+            false,
+            false,
+            (!conds.is_empty()).then(|| ScanPredicate::new(conds)),
+        ))
+    }
+
+    fn index_of(plan: Result<PlanRef, ()>) -> Option<ColumnId> {
+        plan.ok()
+            .and_then(|plan| plan.as_logical_table_scan().unwrap().index())
+    }
+
+    #[test]
+    fn choose_index() {
+        let rule = IndexScanRule { catalog: catalog() };
+        let int = DataValue::Int32;
+
+        // the column compared by `=` is preferred
+        let plan = scan(vec![
+            cmp(BinaryOperator::Gt, 1, int(1)),
+            cmp(BinaryOperator::Eq, 2, int(2)),
+        ]);
+        assert_eq!(index_of(rule.apply(plan)), Some(1));
+        let plan = scan(vec![
+            cmp(BinaryOperator::LtEq, 2, int(1)),
+            cmp(BinaryOperator::Gt, 1, int(2)),
+        ]);
+        assert_eq!(index_of(rule.apply(plan.clone())), Some(1));
+
+        // the index is chosen once
+        let plan = rule.apply(plan).unwrap();
+        assert!(rule.apply(plan).is_err());
+
+        // no indexed column is compared with a constant
+        let plan = scan(vec![cmp(BinaryOperator::Eq, 0, int(1))]);
+        assert!(rule.apply(plan).is_err());
+        let plan = scan(vec![cmp(BinaryOperator::NotEq, 1, int(1))]);
+        assert!(rule.apply(plan).is_err());
+        let plan = scan(vec![cmp(BinaryOperator::Eq, 1, DataValue::Null)]);
+        assert!(rule.apply(plan).is_err());
+        assert!(rule.apply(scan(vec![])).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use super::*;
+use crate::optimizer::plan_nodes::PlanTreeNodeUnary;
+
+/// Push the limit into table scan, so that the scan can stop early and skip leading rows.
+///
+/// The limit is pushed through projections, as they don't change the number of rows. Filters are
+/// expected to be pushed into the scan by [`FilterScanRule`] before this rule is applied.
+pub struct LimitScanRule {}
+
+impl Rule for LimitScanRule {
+    fn apply(&self, plan: PlanRef) -> Result<PlanRef, ()> {
+        let limit = plan.as_logical_limit()?;
+        let child = limit.child();
+        if let Ok(projection) = child.as_logical_projection() {
+            let scan = projection.child();
+            let scan = scan.as_logical_table_scan()?;
+            let scan = Arc::new(scan.clone_with_limit(limit.offset(), limit.limit()));
+            return Ok(Arc::new(projection.clone_with_child(scan)));
+        }
+        let scan = child.as_logical_table_scan()?;
+        Ok(Arc::new(
+            scan.clone_with_limit(limit.offset(), limit.limit()),
+        ))
+    }
+}
@@ -4,8 +4,12 @@ use super::plan_nodes::PlanRef;
 
 mod filter_join_rule;
 mod filter_scan_rule;
+mod index_scan_rule;
+mod limit_scan_rule;
 pub use filter_join_rule::*;
 pub use filter_scan_rule::*;
+pub use index_scan_rule::*;
+pub use limit_scan_rule::*;
 
 pub trait Rule: Send + Sync + 'static {
     fn apply(&self, plan: PlanRef) -> Result<PlanRef, ()>;
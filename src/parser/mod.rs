@@ -9,7 +9,121 @@ use sqlparser::parser::Parser;
 pub use sqlparser::parser::ParserError;
 
 /// Parse the SQL string into a list of ASTs.
+///
+/// Parameter placeholders `$n` are parsed as quoted identifiers `"$n"`, and indexes created
+/// without a name are given one.
 pub fn parse(sql: &str) -> Result<Vec<Statement>, ParserError> {
     let dialect = PostgreSqlDialect {};
-    Parser::parse_sql(&dialect, sql)
+    let sql = name_anonymous_indexes(&quote_placeholders(sql));
+    Parser::parse_sql(&dialect, &sql)
+}
+
+/// The name given to indexes created without a name, which the parser requires.
+const ANONYMOUS_INDEX_NAME: &str = "__anonymous_index";
+
+/// Insert a name into `CREATE [UNIQUE] INDEX ON ...` outside of string literals and quoted
+/// identifiers.
+fn name_anonymous_indexes(sql: &str) -> String {
+    let mut output = String::with_capacity(sql.len());
+    // the last words outside of quotes, in lower case
+    let mut words: Vec<String> = vec![];
+    let mut quote = None;
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                words.clear();
+            }
+            None if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                    word.push(c);
+                    chars.next();
+                }
+                let lower = word.to_lowercase();
+                let anonymous = lower == "on"
+                    && matches!(
+                        words.iter().map(|w| w.as_str()).collect::<Vec<_>>()[..],
+                        [.., "create", "index"] | [.., "create", "unique", "index"]
+                    );
+                if anonymous {
+                    output.push_str(ANONYMOUS_INDEX_NAME);
+                    output.push(' ');
+                }
+                output.push_str(&word);
+                words.push(lower);
+                continue;
+            }
+            None if c.is_whitespace() => {}
+            None => words.clear(),
+        }
+        output.push(c);
+    }
+    output
+}
+
+/// Quote parameter placeholders `$n` outside of string literals and quoted identifiers,
+/// which the tokenizer does not support.
+fn quote_placeholders(sql: &str) -> String {
+    let mut output = String::with_capacity(sql.len());
+    let mut quote = None;
+    let mut chars = sql.chars().peekable();
+    let mut prev = ' ';
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '$'
+                && !(prev.is_alphanumeric() || prev == '_')
+                && chars.peek().map_or(false, |c| c.is_ascii_digit()) =>
+            {
+                output.push_str("\"$");
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    output.push(d);
+                    chars.next();
+                }
+                output.push('"');
+                prev = '"';
+                continue;
+            }
+            None => {}
+        }
+        output.push(c);
+        prev = c;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_placeholders() {
+        assert_eq!(
+            quote_placeholders("select * from t where a = $1 and b = $12"),
+            r#"select * from t where a = "$1" and b = "$12""#
+        );
+        assert_eq!(
+            quote_placeholders(r#"select '$1', "$2", a$3 from t"#),
+            r#"select '$1', "$2", a$3 from t"#
+        );
+    }
+
+    #[test]
+    fn test_name_anonymous_indexes() {
+        assert_eq!(
+            name_anonymous_indexes("CREATE INDEX ON t(a); create unique index on t (b)"),
+            "CREATE INDEX __anonymous_index ON t(a); create unique index __anonymous_index on t (b)"
+        );
+        assert_eq!(
+            name_anonymous_indexes("create index idx on t(a); select 'create index on'"),
+            "create index idx on t(a); select 'create index on'"
+        );
+        assert!(parse("create index on t(a)").is_ok());
+    }
 }
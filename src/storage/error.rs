@@ -4,7 +4,7 @@ use std::backtrace::Backtrace;
 
 use thiserror::Error;
 
-use crate::types::ColumnId;
+use crate::types::{ColumnId, ConvertError};
 
 #[derive(Error, Debug)]
 pub enum StorageError {
@@ -25,11 +25,15 @@ pub enum StorageError {
     #[error("Decode error: {0}")]
     Decode(String),
     #[error("Invalid checksum: found {0}, expected {1}")]
-    Checksum(u64, u64),
+    ChecksumMismatch(u64, u64),
     #[error("Prost encode error: {0}")]
     ProstEncode(prost::EncodeError),
     #[error("Prost decode error: {0}")]
     ProstDecode(prost::DecodeError),
+    #[error("incomplete rowset {0}: {1}")]
+    IncompleteRowset(String, String),
+    #[error("failed to evaluate the filter: {0}")]
+    Filter(#[from] ConvertError),
 }
 
 impl From<std::io::Error> for TracedStorageError {
@@ -89,7 +93,16 @@ impl TracedStorageError {
     }
 
     pub fn checksum(found: u64, expected: u64) -> Self {
-        StorageError::Checksum(found, expected).into()
+        StorageError::ChecksumMismatch(found, expected).into()
+    }
+
+    pub fn incomplete_rowset(path: impl ToString, reason: impl ToString) -> Self {
+        StorageError::IncompleteRowset(path.to_string(), reason.to_string()).into()
+    }
+
+    /// Get the underlying [`StorageError`].
+    pub fn inner(&self) -> &StorageError {
+        &self.source
     }
 }
 
@@ -0,0 +1,260 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use super::{
+    InMemoryStorage, ScanOptions, SecondaryStorage, Storage, StorageColumnRef, StorageImpl,
+    StorageResult, Table, Transaction,
+};
+use crate::array::DataChunk;
+use crate::catalog::TableRefId;
+
+/// The transaction of a table shared by the statements in an [`ExplicitTransaction`]. It is taken
+/// out when the explicit transaction finishes.
+type SharedTxn<T> = Arc<Mutex<Option<T>>>;
+
+/// A transaction started by `BEGIN`, which groups the following statements until `COMMIT` or
+/// `ROLLBACK`.
+///
+/// A storage transaction is kept for each table in the explicit transaction. The statements read
+/// and write the tables through these transactions without committing them, so their writes are
+/// only visible to the later statements of the same explicit transaction. `COMMIT` commits the
+/// transactions of all tables, and `ROLLBACK` aborts them, which discards the written rowsets and
+/// the deleted rows.
+pub struct ExplicitTransaction<S: Storage> {
+    txns: Mutex<HashMap<TableRefId, SharedTxn<S::TransactionType>>>,
+    /// Set when a statement fails in the transaction, after which it can only be rolled back.
+    failed: AtomicBool,
+}
+
+impl<S: Storage> ExplicitTransaction<S> {
+    /// Begin a transaction, which captures the snapshots of the tables at once.
+    pub async fn begin(storage: &S, table_ids: &[TableRefId]) -> StorageResult<Self> {
+        let mut txns = HashMap::new();
+        for &table_id in table_ids {
+            let table = storage.get_table(table_id)?;
+            let txn = table.update().await?;
+            txns.insert(table_id, Arc::new(Mutex::new(Some(txn))));
+        }
+        Ok(Self {
+            txns: Mutex::new(txns),
+            failed: AtomicBool::new(false),
+        })
+    }
+
+    /// Get the transaction of a table, which is started if the table is not accessed before.
+    async fn txn_of(&self, table: &S::TableType) -> StorageResult<SharedTxn<S::TransactionType>> {
+        let mut txns = self.txns.lock().await;
+        if let Some(txn) = txns.get(&table.table_id()) {
+            return Ok(txn.clone());
+        }
+        let txn = Arc::new(Mutex::new(Some(table.update().await?)));
+        txns.insert(table.table_id(), txn.clone());
+        Ok(txn)
+    }
+
+    /// Mark the transaction as failed.
+    pub fn set_failed(&self) {
+        self.failed.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether a statement has failed in the transaction.
+    pub fn is_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Commit the transactions of all tables.
+    pub async fn commit(&self) -> StorageResult<()> {
+        let txns = std::mem::take(&mut *self.txns.lock().await);
+        for txn in txns.into_values() {
+            if let Some(txn) = txn.lock().await.take() {
+                txn.commit().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Abort the transactions of all tables.
+    pub async fn rollback(&self) -> StorageResult<()> {
+        let txns = std::mem::take(&mut *self.txns.lock().await);
+        for txn in txns.into_values() {
+            if let Some(txn) = txn.lock().await.take() {
+                txn.abort().await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An [`ExplicitTransaction`] on either storage engine.
+#[derive(Clone)]
+pub enum ExplicitTxnImpl {
+    InMemory(Arc<ExplicitTransaction<InMemoryStorage>>),
+    Secondary(Arc<ExplicitTransaction<SecondaryStorage>>),
+}
+
+impl ExplicitTxnImpl {
+    /// Begin a transaction on the tables of the storage.
+    pub async fn begin(storage: &StorageImpl, table_ids: &[TableRefId]) -> StorageResult<Self> {
+        Ok(match storage {
+            StorageImpl::InMemoryStorage(storage) => Self::InMemory(Arc::new(
+                ExplicitTransaction::begin(storage, table_ids).await?,
+            )),
+            StorageImpl::SecondaryStorage(storage) => Self::Secondary(Arc::new(
+                ExplicitTransaction::begin(storage, table_ids).await?,
+            )),
+        })
+    }
+
+    pub fn in_memory(&self) -> Option<Arc<ExplicitTransaction<InMemoryStorage>>> {
+        match self {
+            Self::InMemory(txn) => Some(txn.clone()),
+            Self::Secondary(_) => None,
+        }
+    }
+
+    pub fn secondary(&self) -> Option<Arc<ExplicitTransaction<SecondaryStorage>>> {
+        match self {
+            Self::Secondary(txn) => Some(txn.clone()),
+            Self::InMemory(_) => None,
+        }
+    }
+
+    pub fn set_failed(&self) {
+        match self {
+            Self::InMemory(txn) => txn.set_failed(),
+            Self::Secondary(txn) => txn.set_failed(),
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        match self {
+            Self::InMemory(txn) => txn.is_failed(),
+            Self::Secondary(txn) => txn.is_failed(),
+        }
+    }
+
+    pub async fn commit(&self) -> StorageResult<()> {
+        match self {
+            Self::InMemory(txn) => txn.commit().await,
+            Self::Secondary(txn) => txn.commit().await,
+        }
+    }
+
+    pub async fn rollback(&self) -> StorageResult<()> {
+        match self {
+            Self::InMemory(txn) => txn.rollback().await,
+            Self::Secondary(txn) => txn.rollback().await,
+        }
+    }
+}
+
+/// The transaction used by a statement on a table.
+pub enum TxnHandle<S: Storage> {
+    /// A transaction started by the statement, which is committed at the end of the statement.
+    Owned(S::TransactionType),
+    /// The transaction of the table in an [`ExplicitTransaction`]. The writes of the statement
+    /// are flushed at the end of the statement, and committed by `COMMIT`.
+    Shared(SharedTxn<S::TransactionType>),
+}
+
+impl<S: Storage> TxnHandle<S> {
+    /// Get a transaction to read the table.
+    pub async fn read(
+        table: &S::TableType,
+        explicit: Option<&ExplicitTransaction<S>>,
+    ) -> StorageResult<Self> {
+        match explicit {
+            Some(explicit) => Ok(Self::Shared(explicit.txn_of(table).await?)),
+            None => Ok(Self::Owned(table.read().await?)),
+        }
+    }
+
+    /// Get a transaction to append rows to the table.
+    pub async fn write(
+        table: &S::TableType,
+        explicit: Option<&ExplicitTransaction<S>>,
+    ) -> StorageResult<Self> {
+        match explicit {
+            Some(explicit) => Ok(Self::Shared(explicit.txn_of(table).await?)),
+            None => Ok(Self::Owned(table.write().await?)),
+        }
+    }
+
+    /// Get a transaction that might delete or update rows of the table.
+    pub async fn update(
+        table: &S::TableType,
+        explicit: Option<&ExplicitTransaction<S>>,
+    ) -> StorageResult<Self> {
+        match explicit {
+            Some(explicit) => Ok(Self::Shared(explicit.txn_of(table).await?)),
+            None => Ok(Self::Owned(table.update().await?)),
+        }
+    }
+
+    /// Scan one or multiple columns. See [`Transaction::scan`].
+    pub async fn scan(
+        &self,
+        col_idx: &[StorageColumnRef],
+        options: ScanOptions,
+    ) -> StorageResult<<S::TransactionType as Transaction>::TxnIteratorType> {
+        match self {
+            Self::Owned(txn) => txn.scan(None, None, col_idx, options).await,
+            Self::Shared(txn) => {
+                let txn = txn.lock().await;
+                let txn = txn.as_ref().expect("transaction is finished");
+                txn.scan(None, None, col_idx, options).await
+            }
+        }
+    }
+
+    pub async fn append(&mut self, columns: DataChunk) -> StorageResult<()> {
+        match self {
+            Self::Owned(txn) => txn.append(columns).await,
+            Self::Shared(txn) => {
+                let mut txn = txn.lock().await;
+                let txn = txn.as_mut().expect("transaction is finished");
+                txn.append(columns).await
+            }
+        }
+    }
+
+    pub async fn delete(
+        &mut self,
+        id: &<S::TransactionType as Transaction>::RowHandlerType,
+    ) -> StorageResult<()> {
+        match self {
+            Self::Owned(txn) => txn.delete(id).await,
+            Self::Shared(txn) => {
+                let mut txn = txn.lock().await;
+                let txn = txn.as_mut().expect("transaction is finished");
+                txn.delete(id).await
+            }
+        }
+    }
+
+    /// Commit the owned transaction, or flush the writes of the statement to the shared one.
+    pub async fn commit(self) -> StorageResult<()> {
+        match self {
+            Self::Owned(txn) => txn.commit().await,
+            Self::Shared(txn) => {
+                let mut txn = txn.lock().await;
+                let txn = txn.as_mut().expect("transaction is finished");
+                txn.flush().await
+            }
+        }
+    }
+
+    /// Abort the owned transaction. The shared one is left to the explicit transaction, which
+    /// can only be rolled back after a statement fails.
+    pub async fn abort(self) -> StorageResult<()> {
+        match self {
+            Self::Owned(txn) => txn.abort().await,
+            Self::Shared(_) => Ok(()),
+        }
+    }
+}
@@ -23,7 +23,7 @@ use std::sync::{Arc, Mutex};
 
 use super::{Storage, StorageError, StorageResult, TracedStorageError};
 use crate::catalog::{ColumnCatalog, RootCatalog, RootCatalogRef, TableRefId};
-use crate::types::{DatabaseId, SchemaId};
+use crate::types::{ColumnId, DatabaseId, SchemaId};
 
 mod table;
 pub use table::InMemoryTable;
@@ -65,6 +65,9 @@ impl InMemoryStorage {
 impl Storage for InMemoryStorage {
     type CreateTableResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
     type DropTableResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
+    type AddColumnResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
+    type TruncateTableResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
+    type CreateIndexResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
     type TransactionType = InMemoryTransaction;
     type TableType = InMemoryTable;
 
@@ -128,4 +131,68 @@ impl Storage for InMemoryStorage {
             Ok(())
         }
     }
+
+    fn add_column<'a>(
+        &'a self,
+        table_id: TableRefId,
+        column: &'a ColumnCatalog,
+    ) -> Self::AddColumnResultFuture<'a> {
+        async move {
+            let mut tables = self.tables.lock().unwrap();
+            let table = tables
+                .get(&table_id)
+                .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?
+                .clone();
+            self.catalog
+                .get_table(&table_id)
+                .unwrap()
+                .add_column(column.clone())
+                .map_err(|_| StorageError::Duplicated("column", column.name().into()))?;
+
+            // fill the column of existing rows with the default value
+            table.inner.write().unwrap().add_column(column);
+            let mut columns = table.columns.to_vec();
+            columns.push(column.clone());
+            let table = InMemoryTable {
+                columns: columns.into(),
+                ..table
+            };
+            tables.insert(table_id, table);
+            Ok(())
+        }
+    }
+
+    fn truncate_table(&self, table_id: TableRefId) -> Self::TruncateTableResultFuture<'_> {
+        async move {
+            let mut tables = self.tables.lock().unwrap();
+            let table = tables
+                .get(&table_id)
+                .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?;
+            // transactions started before keep the old chunks
+            let table = InMemoryTable::new(table_id, &table.columns);
+            tables.insert(table_id, table);
+            if let Some(table) = self.catalog.get_table(&table_id) {
+                table.clear_statistics();
+            }
+            Ok(())
+        }
+    }
+
+    fn create_index(
+        &self,
+        table_id: TableRefId,
+        column_id: ColumnId,
+    ) -> Self::CreateIndexResultFuture<'_> {
+        async move {
+            // the index is only recorded, as chunks in memory are always scanned
+            let table = self
+                .catalog
+                .get_table(&table_id)
+                .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?;
+            table
+                .add_index(column_id)
+                .map_err(|_| TracedStorageError::duplicated("index", column_id))?;
+            Ok(())
+        }
+    }
 }
@@ -7,7 +7,7 @@ use std::vec::Vec;
 use futures::Future;
 
 use super::*;
-use crate::array::DataChunk;
+use crate::array::{ArrayBuilderImpl, DataChunk};
 use crate::catalog::TableRefId;
 use crate::storage::Table;
 
@@ -45,6 +45,20 @@ impl InMemoryTableInner {
         Ok(())
     }
 
+    /// Append a column filled with its default value to all chunks.
+    pub fn add_column(&mut self, column: &ColumnCatalog) {
+        let default = column.default_value();
+        for chunk in &mut self.chunks {
+            let mut builder =
+                ArrayBuilderImpl::with_capacity(chunk.cardinality(), &column.datatype());
+            for _ in 0..chunk.cardinality() {
+                builder.push(&default);
+            }
+            let arrays = chunk.arrays().iter().cloned();
+            *chunk = arrays.chain([builder.finish()]).collect();
+        }
+    }
+
     pub fn get_all_chunks(&self) -> Vec<DataChunk> {
         self.chunks.clone()
     }
@@ -9,10 +9,9 @@ use tracing::warn;
 
 use super::table::InMemoryTableInnerRef;
 use super::{InMemoryRowHandler, InMemoryTable, InMemoryTxnIterator};
-use crate::array::{ArrayBuilderImpl, ArrayImplBuilderPickExt, ArrayImplSortExt, DataChunk};
-use crate::binder::BoundExpr;
-use crate::catalog::{find_sort_key_id, ColumnCatalog};
-use crate::storage::{StorageColumnRef, StorageResult, Transaction};
+use crate::array::{ArrayBuilderImpl, ArrayImplBuilderPickExt, DataChunk};
+use crate::catalog::{find_sort_key_ids, ColumnCatalog};
+use crate::storage::{ScanOptions, StorageColumnRef, StorageResult, Transaction};
 
 /// A transaction running on `InMemoryStorage`.
 pub struct InMemoryTransaction {
@@ -56,12 +55,13 @@ impl InMemoryTransaction {
     }
 }
 
-/// If primary key is found in [`ColumnCatalog`], sort all in-memory data using that key.
+/// If primary key is found in [`ColumnCatalog`], sort all in-memory data using the key columns.
 fn sort_datachunk_by_pk(
     chunks: &Arc<Vec<DataChunk>>,
     column_infos: &[ColumnCatalog],
 ) -> Arc<Vec<DataChunk>> {
-    if let Some(sort_key_id) = find_sort_key_id(column_infos) {
+    let sort_key_ids = find_sort_key_ids(column_infos);
+    if !sort_key_ids.is_empty() {
         if chunks.is_empty() {
             return chunks.clone();
         }
@@ -81,7 +81,13 @@ fn sort_datachunk_by_pk(
             .into_iter()
             .map(|builder| builder.finish())
             .collect_vec();
-        let sorted_index = arrays[sort_key_id].get_sorted_indices();
+        let mut sorted_index = (0..arrays[0].len()).collect_vec();
+        sorted_index.sort_by(|&a, &b| {
+            (sort_key_ids.iter())
+                .map(|&id| arrays[id].get(a).partial_cmp(&arrays[id].get(b)).unwrap())
+                .find(|ord| ord.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
 
         let chunk = arrays
             .into_iter()
@@ -113,18 +119,29 @@ impl Transaction for InMemoryTransaction {
 
     type AbortResultFuture<'a> = impl Future<Output = StorageResult<()>> + Send + 'a;
 
+    type FlushResultFuture<'a> = impl Future<Output = StorageResult<()>> + Send + 'a;
+
     // TODO: remove this unused variable
     fn scan<'a>(
         &'a self,
         begin_sort_key: Option<&'a [u8]>,
         end_sort_key: Option<&'a [u8]>,
         col_idx: &'a [StorageColumnRef],
-        is_sorted: bool,
-        reversed: bool,
-        expr: Option<BoundExpr>,
+        options: ScanOptions,
     ) -> Self::ScanResultFuture<'a> {
         async move {
+            let ScanOptions {
+                is_sorted,
+                reversed,
+                expr,
+                row_offset,
+                row_limit,
+            } = options;
             assert!(expr.is_none(), "MemTxn doesn't support filter scan");
+            assert!(
+                row_offset == 0 && row_limit.is_none(),
+                "MemTxn doesn't support limit scan"
+            );
             assert!(
                 begin_sort_key.is_none(),
                 "sort_key is not supported in InMemoryEngine for now"
@@ -135,17 +152,26 @@ impl Transaction for InMemoryTransaction {
             );
             assert!(!reversed, "reverse iterator is not supported for now");
 
+            // the rows written by this transaction follow the snapshot, so that their row ids
+            // are the same as they will be after committing
+            let (snapshot, deleted_rows) =
+                if self.buffer.is_empty() && self.delete_buffer.is_empty() {
+                    (self.snapshot.clone(), self.deleted_rows.clone())
+                } else {
+                    let chunks = self.snapshot.iter().chain(&self.buffer).cloned().collect();
+                    let deleted_rows = (self.deleted_rows.iter())
+                        .chain(&self.delete_buffer)
+                        .cloned()
+                        .collect();
+                    (Arc::new(chunks), Arc::new(deleted_rows))
+                };
             let snapshot = if is_sorted {
-                sort_datachunk_by_pk(&self.snapshot, &self.column_infos)
+                sort_datachunk_by_pk(&snapshot, &self.column_infos)
             } else {
-                self.snapshot.clone()
+                snapshot
             };
 
-            Ok(InMemoryTxnIterator::new(
-                snapshot,
-                self.deleted_rows.clone(),
-                col_idx,
-            ))
+            Ok(InMemoryTxnIterator::new(snapshot, deleted_rows, col_idx))
         }
     }
 
@@ -163,6 +189,11 @@ impl Transaction for InMemoryTransaction {
         }
     }
 
+    fn flush(&mut self) -> Self::FlushResultFuture<'_> {
+        // the buffered rows are always visible to scans
+        async move { Ok(()) }
+    }
+
     fn commit<'a>(mut self) -> Self::CommitResultFuture<'a> {
         async move {
             let mut table = self.table.write().unwrap();
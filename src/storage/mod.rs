@@ -11,6 +11,9 @@ pub use secondary::{SecondaryStorage, StorageOptions as SecondaryStorageOptions}
 mod error;
 pub use error::{StorageError, StorageResult, TracedStorageError};
 
+mod explicit_txn;
+pub use explicit_txn::*;
+
 mod chunk;
 use std::future::Future;
 use std::sync::Arc;
@@ -20,8 +23,8 @@ use enum_dispatch::enum_dispatch;
 
 use crate::array::{ArrayImpl, DataChunk};
 use crate::binder::BoundExpr;
-use crate::catalog::{ColumnCatalog, TableRefId};
-use crate::types::{DatabaseId, SchemaId};
+use crate::catalog::{ColumnCatalog, RootCatalogRef, TableRefId};
+use crate::types::{ColumnId, DatabaseId, SchemaId};
 
 #[enum_dispatch(StorageDispatch)]
 #[derive(Clone)]
@@ -50,6 +53,27 @@ impl StorageImpl {
             Self::InMemoryStorage(_) => false,
         }
     }
+
+    pub fn enable_limit_scan(&self) -> bool {
+        match self {
+            Self::SecondaryStorage(_) => true,
+            Self::InMemoryStorage(_) => false,
+        }
+    }
+
+    pub fn enable_index_scan(&self) -> bool {
+        match self {
+            Self::SecondaryStorage(_) => true,
+            Self::InMemoryStorage(_) => false,
+        }
+    }
+
+    pub fn catalog(&self) -> &RootCatalogRef {
+        match self {
+            Self::SecondaryStorage(storage) => storage.catalog(),
+            Self::InMemoryStorage(storage) => storage.catalog(),
+        }
+    }
 }
 
 /// Represents a storage engine.
@@ -60,6 +84,15 @@ pub trait Storage: Sync + Send + 'static {
     where
         Self: 'a;
     type DropTableResultFuture<'a>: Future<Output = StorageResult<()>> + Send + 'a
+    where
+        Self: 'a;
+    type AddColumnResultFuture<'a>: Future<Output = StorageResult<()>> + Send + 'a
+    where
+        Self: 'a;
+    type TruncateTableResultFuture<'a>: Future<Output = StorageResult<()>> + Send + 'a
+    where
+        Self: 'a;
+    type CreateIndexResultFuture<'a>: Future<Output = StorageResult<()>> + Send + 'a
     where
         Self: 'a;
 
@@ -80,6 +113,26 @@ pub trait Storage: Sync + Send + 'static {
     fn get_table(&self, table_id: TableRefId) -> StorageResult<Self::TableType>;
 
     fn drop_table(&self, table_id: TableRefId) -> Self::DropTableResultFuture<'_>;
+
+    /// Add a column to the end of a table. The rows already in the table are read with the
+    /// default value of the column.
+    fn add_column<'a>(
+        &'a self,
+        table_id: TableRefId,
+        column: &'a ColumnCatalog,
+    ) -> Self::AddColumnResultFuture<'a>;
+
+    /// Remove all rows of a table and reset its statistics, keeping the table definition.
+    /// Transactions started before still see the old rows.
+    fn truncate_table(&self, table_id: TableRefId) -> Self::TruncateTableResultFuture<'_>;
+
+    /// Create a secondary index on a column of a table, which is used by scans filtering the
+    /// column. The index is built on the existing rows and maintained for the new rows.
+    fn create_index(
+        &self,
+        table_id: TableRefId,
+        column_id: ColumnId,
+    ) -> Self::CreateIndexResultFuture<'_>;
 }
 
 /// A table in the storage engine. [`Table`] is by default a reference to a table,
@@ -131,6 +184,21 @@ pub trait RowHandler: Sync + Send + 'static {
     fn from_column(column: &ArrayImpl, idx: usize) -> Self;
 }
 
+/// The options of [`Transaction::scan`].
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Whether the rows are returned in the order of the sort key.
+    pub is_sorted: bool,
+    /// Whether the rows are returned in the reversed order.
+    pub reversed: bool,
+    /// The filter of the rows.
+    pub expr: Option<BoundExpr>,
+    /// The number of leading rows to skip.
+    pub row_offset: usize,
+    /// The maximum number of rows to return.
+    pub row_limit: Option<usize>,
+}
+
 /// Represents a transaction in storage engine.
 pub trait Transaction: Sync + Send + 'static {
     /// Type of the table iterator
@@ -152,17 +220,23 @@ pub trait Transaction: Sync + Send + 'static {
     where
         Self: 'a;
     type AbortResultFuture<'a>: Future<Output = StorageResult<()>> + Send + 'a
+    where
+        Self: 'a;
+    type FlushResultFuture<'a>: Future<Output = StorageResult<()>> + Send + 'a
     where
         Self: 'a;
     /// Scan one or multiple columns.
+    ///
+    /// The rows appended and deleted by this transaction are visible to the scan once flushed.
+    ///
+    /// The first `row_offset` rows are skipped, and at most `row_limit` rows are returned after
+    /// that. Both count rows after filtering with `expr`.
     fn scan<'a>(
         &'a self,
         begin_sort_key: Option<&'a [u8]>,
         end_sort_key: Option<&'a [u8]>,
         col_idx: &'a [StorageColumnRef],
-        is_sorted: bool,
-        reversed: bool,
-        expr: Option<BoundExpr>,
+        options: ScanOptions,
     ) -> Self::ScanResultFuture<'a>;
 
     /// Append data to the table. Generally, `columns` should be in the same order as
@@ -172,6 +246,10 @@ pub trait Transaction: Sync + Send + 'static {
     /// Delete a record.
     fn delete<'a>(&'a mut self, id: &'a Self::RowHandlerType) -> Self::DeleteResultFuture<'a>;
 
+    /// Make the rows appended so far visible to the later scans of this transaction. They are
+    /// still invisible to other transactions until the transaction is committed.
+    fn flush(&mut self) -> Self::FlushResultFuture<'_>;
+
     /// Commit a transaction.
     fn commit<'a>(self) -> Self::CommitResultFuture<'a>;
 
@@ -187,4 +265,25 @@ pub trait TxnIterator: Send {
 
     /// get next batch of elements
     fn next_batch(&mut self, expected_size: Option<usize>) -> Self::NextFuture<'_>;
+
+    /// Get the statistics of the scan so far.
+    fn stats(&self) -> ScanStats {
+        ScanStats::default()
+    }
+}
+
+/// Statistics of a table scan, which show how many blocks are pruned by the filter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScanStats {
+    /// The number of blocks read.
+    pub blocks_read: usize,
+    /// The number of blocks skipped without reading, as no row in them can match the filter.
+    pub blocks_skipped: usize,
+}
+
+impl std::ops::AddAssign for ScanStats {
+    fn add_assign(&mut self, other: Self) {
+        self.blocks_read += other.blocks_read;
+        self.blocks_skipped += other.blocks_skipped;
+    }
 }
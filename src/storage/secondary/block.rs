@@ -24,10 +24,28 @@ pub use char_block_iterator::*;
 pub use primitive_nullable_block_iterator::*;
 mod block_index_builder;
 pub use block_index_builder::*;
+mod bit_packed_block_builder;
+mod bit_packed_block_iterator;
+pub use bit_packed_block_builder::*;
+pub use bit_packed_block_iterator::*;
+mod varchar_nullable_block_builder;
+mod varchar_nullable_block_iterator;
+pub use varchar_nullable_block_builder::*;
+pub use varchar_nullable_block_iterator::*;
+mod dictionary_block_iterator;
+pub use dictionary_block_iterator::*;
+mod rle_block_builder;
 mod varchar_block_iterator;
+pub use rle_block_builder::*;
+mod list_block_builder;
+mod list_block_iterator;
+mod rle_block_iterator;
 use bytes::{Buf, BufMut, Bytes};
+pub use list_block_builder::*;
+pub use list_block_iterator::*;
 use risinglight_proto::rowset::block_checksum::ChecksumType;
 use risinglight_proto::rowset::block_index::BlockType;
+pub use rle_block_iterator::*;
 pub use varchar_block_iterator::*;
 
 use super::StorageResult;
@@ -78,7 +96,8 @@ pub trait BlockIterator<A: Array> {
     fn remaining_items(&self) -> usize;
 }
 
-/// A key in block cache contains `rowset_id`, `column_id` and `block_id`.
+/// A key in block cache contains `rowset_id`, `column_id` and `block_id`, and the column of the
+/// secondary index if the block belongs to a secondary index of the rowset.
 ///
 /// TODO: support per-table self-increment RowSet Id. Currently, all tables share one RowSet ID
 /// generator.
@@ -87,6 +106,7 @@ pub struct BlockCacheKey {
     pub rowset_id: u32,
     pub storage_column_id: u32,
     pub block_id: u32,
+    pub secondary_index: Option<u32>,
 }
 
 impl BlockCacheKey {
@@ -104,6 +124,11 @@ impl BlockCacheKey {
         self.rowset_id = rowset_id;
         self
     }
+
+    pub fn secondary_index(mut self, column_id: Option<u32>) -> Self {
+        self.secondary_index = column_id;
+        self
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -0,0 +1,184 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::marker::PhantomData;
+
+use bitvec::prelude::{BitVec, Lsb0};
+use risinglight_proto::rowset::BlockStatistics;
+
+use super::super::statistics::{MinMaxStatisticsBuilder, StatisticsBuilder};
+use super::super::PrimitiveFixedWidthEncode;
+use super::BlockBuilder;
+
+/// Encodes integers into a block with frame-of-reference bit-packing.
+///
+/// Each value is stored as its offset from the minimum value of the block (the frame of
+/// reference), using just enough bits to represent the range of the block. The frame of
+/// reference and the bit width are recorded in the block index.
+///
+/// The layout is the packed data (LSB first), followed by a u8 bitmap if there is any null
+/// element. Null elements are packed as the frame of reference.
+pub struct BitPackedBlockBuilder<T: PrimitiveFixedWidthEncode> {
+    values: Vec<i64>,
+    bitmap: BitVec<u8, Lsb0>,
+    min: Option<i64>,
+    max: Option<i64>,
+    target_size: usize,
+    _phantom: PhantomData<T>,
+}
+
+/// Number of bits needed to store offsets within `[min, max]`.
+pub fn bit_width_of(min: i64, max: i64) -> u32 {
+    let range = max.wrapping_sub(min) as u64;
+    64 - range.leading_zeros()
+}
+
+impl<T: PrimitiveFixedWidthEncode> BitPackedBlockBuilder<T> {
+    pub fn new(target_size: usize) -> Self {
+        assert!(T::BIT_PACKABLE, "type is not bit-packable");
+        Self {
+            values: vec![],
+            bitmap: BitVec::new(),
+            min: None,
+            max: None,
+            target_size,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Check if any null element has been appended.
+    pub fn has_null(&self) -> bool {
+        !self.bitmap.all()
+    }
+
+    /// The frame of reference of the block, i.e. the minimum non-null value.
+    pub fn frame_of_reference(&self) -> i64 {
+        self.min.unwrap_or(0)
+    }
+
+    /// Number of bits of each packed value.
+    pub fn bit_width(&self) -> u32 {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => bit_width_of(min, max),
+            _ => 0,
+        }
+    }
+
+    fn size_with(&self, rows: usize, bit_width: u32) -> usize {
+        let data_len = (rows * bit_width as usize + 7) / 8;
+        let bitmap_len = (rows + 7) / 8;
+        data_len + bitmap_len
+    }
+}
+
+impl<T: PrimitiveFixedWidthEncode> BlockBuilder<T::ArrayType> for BitPackedBlockBuilder<T> {
+    fn append(&mut self, item: Option<&T>) {
+        if let Some(item) = item {
+            let value = item.to_i64();
+            self.min = Some(self.min.map_or(value, |min| min.min(value)));
+            self.max = Some(self.max.map_or(value, |max| max.max(value)));
+            self.values.push(value);
+            self.bitmap.push(true);
+        } else {
+            self.values.push(0);
+            self.bitmap.push(false);
+        }
+    }
+
+    fn estimated_size(&self) -> usize {
+        self.size_with(self.values.len(), self.bit_width())
+    }
+
+    fn should_finish(&self, next_item: &Option<&T>) -> bool {
+        if self.values.is_empty() {
+            return false;
+        }
+        // a new item may widen the range of the whole block
+        let bit_width = match (next_item, self.min, self.max) {
+            (Some(item), Some(min), Some(max)) => {
+                let value = item.to_i64();
+                bit_width_of(min.min(value), max.max(value))
+            }
+            _ => self.bit_width(),
+        };
+        self.size_with(self.values.len() + 1, bit_width) > self.target_size
+    }
+
+    fn get_statistics(&self) -> Vec<BlockStatistics> {
+        let mut data = Vec::with_capacity(self.values.len() * T::WIDTH);
+        for (&value, valid) in self.values.iter().zip(self.bitmap.iter()) {
+            if *valid {
+                T::from_i64(value).encode(&mut data);
+            }
+        }
+        let mut stats_builder = StatisticsBuilder::new();
+        let mut min_max_builder = MinMaxStatisticsBuilder::new();
+        for mut item in data.chunks(T::WIDTH) {
+            stats_builder.add_item(Some(item));
+            min_max_builder.add_item(Some(T::decode(&mut item)));
+        }
+        let mut stats = stats_builder.get_statistics();
+        stats.extend(min_max_builder.get_statistics());
+        stats
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let base = self.frame_of_reference();
+        let bit_width = self.bit_width();
+        let has_null = self.has_null();
+        let mut data = Vec::with_capacity(self.estimated_size());
+
+        let mut buffer = 0u128;
+        let mut buffer_bits = 0;
+        for (&value, valid) in self.values.iter().zip(self.bitmap.iter()) {
+            let offset = if *valid {
+                value.wrapping_sub(base) as u64
+            } else {
+                0
+            };
+            buffer |= (offset as u128) << buffer_bits;
+            buffer_bits += bit_width;
+            while buffer_bits >= 8 {
+                data.push(buffer as u8);
+                buffer >>= 8;
+                buffer_bits -= 8;
+            }
+        }
+        if buffer_bits > 0 {
+            data.push(buffer as u8);
+        }
+
+        if has_null {
+            data.extend(self.bitmap.as_raw_slice().iter());
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_bit_packed_i32() {
+        let mut builder = BitPackedBlockBuilder::<i32>::new(128);
+        for item in [1000, 1003, 1001, 1007] {
+            builder.append(Some(&item));
+        }
+        assert_eq!(builder.frame_of_reference(), 1000);
+        assert_eq!(builder.bit_width(), 3);
+        // 4 items of 3 bits, and the bitmap
+        assert_eq!(builder.estimated_size(), 3);
+        assert!(!builder.should_finish(&Some(&1004)));
+        assert!(!builder.has_null());
+        // [0, 3, 1, 7] packed into 12 bits
+        assert_eq!(builder.finish(), vec![0b01_011_000, 0b1110]);
+    }
+
+    #[test]
+    fn test_bit_width() {
+        assert_eq!(bit_width_of(5, 5), 0);
+        assert_eq!(bit_width_of(1000, 1050), 6);
+        assert_eq!(bit_width_of(i32::MIN as i64, i32::MAX as i64), 32);
+        assert_eq!(bit_width_of(i64::MIN, i64::MAX), 64);
+    }
+}
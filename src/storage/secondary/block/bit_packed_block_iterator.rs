@@ -0,0 +1,162 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::marker::PhantomData;
+
+use bitvec::prelude::Lsb0;
+use bitvec::slice::BitSlice;
+
+use super::super::PrimitiveFixedWidthEncode;
+use super::{Block, BlockIterator};
+use crate::array::{Array, ArrayBuilder};
+
+/// Scans one or several arrays from a bit-packed block.
+pub struct BitPackedBlockIterator<T: PrimitiveFixedWidthEncode> {
+    /// Block content
+    block: Block,
+
+    /// Total count of elements in block
+    row_count: usize,
+
+    /// Frame of reference of the block
+    base: i64,
+
+    /// Number of bits of each packed value
+    bit_width: u32,
+
+    /// Whether the packed data is followed by a null bitmap
+    nullable: bool,
+
+    /// Indicates the beginning row of the next batch
+    next_row: usize,
+
+    _phantom: PhantomData<T>,
+}
+
+impl<T: PrimitiveFixedWidthEncode> BitPackedBlockIterator<T> {
+    pub fn new(block: Block, row_count: usize, base: i64, bit_width: u32, nullable: bool) -> Self {
+        Self {
+            block,
+            row_count,
+            base,
+            bit_width,
+            nullable,
+            next_row: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Unpack the value at `row`.
+    fn value_at(&self, row: usize) -> T {
+        if self.bit_width == 0 {
+            return T::from_i64(self.base);
+        }
+        let bit_pos = row * self.bit_width as usize;
+        let (byte_pos, shift) = (bit_pos / 8, bit_pos % 8);
+        let byte_len = (shift + self.bit_width as usize + 7) / 8;
+        let mut buffer = 0u128;
+        for (i, &byte) in self.block[byte_pos..byte_pos + byte_len].iter().enumerate() {
+            buffer |= (byte as u128) << (i * 8);
+        }
+        let mask = u64::MAX >> (64 - self.bit_width);
+        let offset = (buffer >> shift) as u64 & mask;
+        T::from_i64(self.base.wrapping_add(offset as i64))
+    }
+}
+
+impl<T: PrimitiveFixedWidthEncode> BlockIterator<T::ArrayType> for BitPackedBlockIterator<T> {
+    fn next_batch(
+        &mut self,
+        expected_size: Option<usize>,
+        builder: &mut <T::ArrayType as Array>::Builder,
+    ) -> usize {
+        if self.next_row >= self.row_count {
+            return 0;
+        }
+
+        let end_row = match expected_size {
+            Some(expected_size) => {
+                assert!(expected_size > 0);
+                self.row_count.min(self.next_row + expected_size)
+            }
+            None => self.row_count,
+        };
+        let data_len = (self.row_count * self.bit_width as usize + 7) / 8;
+        let bitmap = BitSlice::<u8, Lsb0>::from_slice(&self.block[data_len..]);
+
+        let cnt = end_row - self.next_row;
+        for row in self.next_row..end_row {
+            if !self.nullable || bitmap[row] {
+                builder.push(Some(&self.value_at(row)));
+            } else {
+                builder.push(None);
+            }
+        }
+        self.next_row = end_row;
+
+        cnt
+    }
+
+    fn skip(&mut self, cnt: usize) {
+        self.next_row += cnt;
+    }
+
+    fn remaining_items(&self) -> usize {
+        self.row_count - self.next_row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::array::{ArrayToVecExt, I32ArrayBuilder};
+    use crate::storage::secondary::block::{BitPackedBlockBuilder, BlockBuilder};
+
+    #[test]
+    fn test_scan_bit_packed_i32() {
+        let items = [Some(1000), None, Some(1050), Some(1023), None, Some(1001)];
+        let mut builder = BitPackedBlockBuilder::<i32>::new(128);
+        for item in &items {
+            builder.append(item.as_ref());
+        }
+        let (base, bit_width) = (builder.frame_of_reference(), builder.bit_width());
+        assert_eq!((base, bit_width), (1000, 6));
+        let data = builder.finish();
+
+        let mut scanner =
+            BitPackedBlockIterator::<i32>::new(Bytes::from(data), 6, base, bit_width, true);
+
+        scanner.skip(1);
+        assert_eq!(scanner.remaining_items(), 5);
+
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(Some(2), &mut builder), 2);
+        assert_eq!(builder.finish().to_vec(), items[1..3]);
+
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 3);
+        assert_eq!(builder.finish().to_vec(), items[3..]);
+
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 0);
+    }
+
+    #[test]
+    fn test_scan_bit_packed_i32_extremes() {
+        let items = [Some(i32::MIN), Some(0), Some(i32::MAX), Some(-1)];
+        let mut builder = BitPackedBlockBuilder::<i32>::new(128);
+        for item in &items {
+            builder.append(item.as_ref());
+        }
+        let (base, bit_width) = (builder.frame_of_reference(), builder.bit_width());
+        assert_eq!(bit_width, 32);
+        let data = builder.finish();
+
+        let mut scanner =
+            BitPackedBlockIterator::<i32>::new(Bytes::from(data), 4, base, bit_width, false);
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 4);
+        assert_eq!(builder.finish().to_vec(), items);
+    }
+}
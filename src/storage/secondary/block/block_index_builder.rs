@@ -51,6 +51,9 @@ impl BlockIndexBuilder {
             /// TODO(chi): support sort key
             first_key: "".into(),
             stats,
+            no_nulls: false,
+            frame_of_reference: 0,
+            bit_width: 0,
         });
 
         // the new block will begin at the current row count
@@ -75,6 +78,18 @@ impl BlockIndexBuilder {
         column_data.append(block_data);
     }
 
+    /// Mark the last finished block as a nullable block without null values.
+    pub fn mark_no_nulls(&mut self) {
+        self.indexes.last_mut().unwrap().no_nulls = true;
+    }
+
+    /// Record the frame of reference and bit width of the last finished bit-packed block.
+    pub fn mark_bit_packed(&mut self, frame_of_reference: i64, bit_width: u32) {
+        let index = self.indexes.last_mut().unwrap();
+        index.frame_of_reference = frame_of_reference;
+        index.bit_width = bit_width;
+    }
+
     /// Add new rows into the block index
     pub fn add_rows(&mut self, rows: usize) {
         self.row_count += rows;
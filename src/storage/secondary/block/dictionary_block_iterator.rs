@@ -0,0 +1,89 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::sync::Arc;
+
+use super::BlockIterator;
+use crate::array::{Array, ArrayBuilder, I32Array, I32ArrayBuilder, Utf8Array, Utf8ArrayBuilder};
+
+/// Scans one or several arrays from a dictionary-encoded block. The codes are read from the
+/// inner block iterator, and converted back to strings with the dictionary of the column.
+pub struct DictionaryBlockIterator<B: BlockIterator<I32Array>> {
+    /// Iterator of the codes
+    block_iter: B,
+
+    /// Sorted dictionary of the column
+    dictionary: Arc<[String]>,
+}
+
+impl<B: BlockIterator<I32Array>> DictionaryBlockIterator<B> {
+    pub fn new(block_iter: B, dictionary: Arc<[String]>) -> Self {
+        Self {
+            block_iter,
+            dictionary,
+        }
+    }
+}
+
+impl<B: BlockIterator<I32Array>> BlockIterator<Utf8Array> for DictionaryBlockIterator<B> {
+    fn next_batch(
+        &mut self,
+        expected_size: Option<usize>,
+        builder: &mut Utf8ArrayBuilder,
+    ) -> usize {
+        let mut codes_builder = I32ArrayBuilder::with_capacity(expected_size.unwrap_or(0));
+        let cnt = self
+            .block_iter
+            .next_batch(expected_size, &mut codes_builder);
+        for code in codes_builder.finish().iter() {
+            builder.push(code.map(|code| self.dictionary[*code as usize].as_str()));
+        }
+        cnt
+    }
+
+    fn skip(&mut self, cnt: usize) {
+        self.block_iter.skip(cnt);
+    }
+
+    fn remaining_items(&self) -> usize {
+        self.block_iter.remaining_items()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::array::ArrayToVecExt;
+    use crate::storage::secondary::block::{
+        BlockBuilder, PlainPrimitiveNullableBlockBuilder, PlainPrimitiveNullableBlockIterator,
+    };
+
+    #[test]
+    fn test_scan_dictionary() {
+        let mut builder = PlainPrimitiveNullableBlockBuilder::<i32>::new(128);
+        builder.append(Some(&1));
+        builder.append(None);
+        builder.append(Some(&0));
+        builder.append(Some(&1));
+        let data = builder.finish();
+
+        let block_iter = PlainPrimitiveNullableBlockIterator::<i32>::new(Bytes::from(data), 4);
+        let dictionary: Arc<[String]> = vec!["apple".to_string(), "banana".to_string()].into();
+        let mut scanner = DictionaryBlockIterator::new(block_iter, dictionary);
+
+        scanner.skip(1);
+        assert_eq!(scanner.remaining_items(), 3);
+
+        let mut builder = Utf8ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(Some(2), &mut builder), 2);
+        assert_eq!(
+            builder.finish().to_vec(),
+            vec![None, Some("apple".to_string())]
+        );
+
+        let mut builder = Utf8ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 1);
+        assert_eq!(builder.finish().to_vec(), vec![Some("banana".to_string())]);
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use bitvec::prelude::{BitVec, Lsb0};
+use bytes::BufMut;
+use risinglight_proto::rowset::BlockStatistics;
+
+use super::super::statistics::StatisticsBuilder;
+use super::BlockBuilder;
+use crate::array::ListArray;
+use crate::types::ListRef;
+
+/// Encodes lists into a block, with null element support. The data layout is
+/// ```plain
+/// | offset (u32) | offset | offset | elements | elements | elements | bitmap |
+/// ```
+///
+/// The elements of all lists are flattened, and each element is encoded by
+/// [`DataValue::encode`](crate::types::DataValue::encode). Each offset is the end position of
+/// the elements of a list, so that the i-th list can be located in O(1) by the (i-1)-th and i-th
+/// offsets. Null lists are stored as empty lists.
+pub struct PlainListBlockBuilder {
+    data: Vec<u8>,
+    offsets: Vec<u32>,
+    bitmap: BitVec<u8, Lsb0>,
+    target_size: usize,
+}
+
+impl PlainListBlockBuilder {
+    pub fn new(target_size: usize) -> Self {
+        let data = Vec::with_capacity(target_size);
+        Self {
+            data,
+            offsets: vec![],
+            bitmap: BitVec::new(),
+            target_size,
+        }
+    }
+
+    /// Encoded size of a list.
+    fn encoded_len(item: &ListRef) -> usize {
+        let mut buf = vec![];
+        for value in item.iter() {
+            value.encode(&mut buf);
+        }
+        buf.len()
+    }
+}
+
+impl BlockBuilder<ListArray> for PlainListBlockBuilder {
+    fn append(&mut self, item: Option<&ListRef>) {
+        if let Some(item) = item {
+            for value in item.iter() {
+                value.encode(&mut self.data);
+            }
+        }
+        self.offsets.push(self.data.len() as u32);
+        self.bitmap.push(item.is_some());
+    }
+
+    fn estimated_size(&self) -> usize {
+        let bitmap_byte_len = (self.bitmap.len() + 7) / 8;
+        self.data.len() + self.offsets.len() * std::mem::size_of::<u32>() + bitmap_byte_len
+    }
+
+    fn should_finish(&self, next_item: &Option<&ListRef>) -> bool {
+        !self.bitmap.is_empty()
+            && self.estimated_size()
+                + next_item.map(Self::encoded_len).unwrap_or(0)
+                + std::mem::size_of::<u32>()
+                + 1
+                > self.target_size
+    }
+
+    fn get_statistics(&self) -> Vec<BlockStatistics> {
+        let starts = std::iter::once(0).chain(self.offsets.iter().map(|pos| *pos as usize));
+        let mut stats_builder = StatisticsBuilder::new();
+        for ((start, end), valid) in starts.zip(&self.offsets).zip(self.bitmap.iter()) {
+            stats_builder.add_item(valid.then(|| &self.data[start..*end as usize]));
+        }
+        stats_builder.get_statistics()
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let mut encoded_data = vec![];
+        for offset in self.offsets {
+            encoded_data.put_u32_le(offset);
+        }
+        encoded_data.extend(self.data);
+        encoded_data.extend(self.bitmap.as_raw_slice().iter());
+        encoded_data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DataValue, List};
+
+    #[test]
+    fn test_build_list() {
+        let list = List::from(vec![DataValue::Int32(1), DataValue::Null]);
+        let mut builder = PlainListBlockBuilder::new(128);
+        builder.append(Some(&list));
+        builder.append(None);
+        builder.append(Some(&List::from(vec![])));
+        // an Int32 is encoded in 5 bytes and a NULL in 1 byte
+        assert_eq!(builder.estimated_size(), 6 + 3 * 4 + 1);
+        assert!(!builder.should_finish(&Some(&list)));
+        let data = builder.finish();
+        assert_eq!(data.len(), 6 + 3 * 4 + 1);
+        assert_eq!(data[data.len() - 1], 0b101);
+    }
+}
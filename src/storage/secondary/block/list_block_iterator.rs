@@ -0,0 +1,146 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use bitvec::prelude::Lsb0;
+use bitvec::slice::BitSlice;
+use bytes::Buf;
+
+use super::{Block, BlockIterator};
+use crate::array::{ArrayBuilder, ListArray, ListArrayBuilder};
+use crate::types::{DataValue, List};
+
+/// Size of each offset in the block.
+const OFFSET: usize = std::mem::size_of::<u32>();
+
+/// Scans one or several arrays from a block encoded by
+/// [`PlainListBlockBuilder`](super::PlainListBlockBuilder).
+pub struct PlainListBlockIterator {
+    /// Block content
+    block: Block,
+
+    /// Total count of elements in block
+    row_count: usize,
+
+    /// Indicates the beginning row of the next batch
+    next_row: usize,
+}
+
+impl PlainListBlockIterator {
+    pub fn new(block: Block, row_count: usize) -> Self {
+        Self {
+            block,
+            row_count,
+            next_row: 0,
+        }
+    }
+
+    /// Decode the `row`-th list of the block, located in O(1) by the offsets.
+    fn value_at(&self, row: usize) -> List {
+        let offset = |row: usize| (&self.block[row * OFFSET..]).get_u32_le() as usize;
+        let from = if row == 0 { 0 } else { offset(row - 1) };
+        let to = offset(row);
+        let mut data = &self.block[OFFSET * self.row_count + from..OFFSET * self.row_count + to];
+        let mut values = vec![];
+        while data.has_remaining() {
+            values.push(DataValue::decode(&mut data));
+        }
+        List::from(values)
+    }
+
+    /// Null bitmap of the block, which follows the offsets and the elements.
+    fn bitmap(&self) -> &BitSlice<u8, Lsb0> {
+        let bitmap_start = match self.row_count {
+            0 => 0,
+            _ => {
+                let last_offset = (&self.block[(self.row_count - 1) * OFFSET..]).get_u32_le();
+                OFFSET * self.row_count + last_offset as usize
+            }
+        };
+        BitSlice::from_slice(&self.block[bitmap_start..])
+    }
+}
+
+impl BlockIterator<ListArray> for PlainListBlockIterator {
+    fn next_batch(
+        &mut self,
+        expected_size: Option<usize>,
+        builder: &mut ListArrayBuilder,
+    ) -> usize {
+        if self.next_row >= self.row_count {
+            return 0;
+        }
+
+        let mut cnt = 0;
+        loop {
+            if let Some(expected_size) = expected_size {
+                assert!(expected_size > 0);
+                if cnt >= expected_size {
+                    break;
+                }
+            }
+
+            if self.next_row >= self.row_count {
+                break;
+            }
+
+            if self.bitmap()[self.next_row] {
+                builder.push(Some(&self.value_at(self.next_row)));
+            } else {
+                builder.push(None);
+            }
+
+            cnt += 1;
+            self.next_row += 1;
+        }
+
+        cnt
+    }
+
+    fn skip(&mut self, cnt: usize) {
+        self.next_row += cnt;
+    }
+
+    fn remaining_items(&self) -> usize {
+        self.row_count - self.next_row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::array::ArrayToVecExt;
+    use crate::storage::secondary::block::{BlockBuilder, PlainListBlockBuilder};
+
+    #[test]
+    fn test_scan_list() {
+        let items = [
+            Some(List::from(vec![DataValue::Int32(1), DataValue::Int32(2)])),
+            None,
+            Some(List::from(vec![])),
+            Some(List::from(vec![DataValue::Null, DataValue::Int32(3)])),
+            Some(List::from(vec![DataValue::String("abc".into())])),
+        ];
+        let mut builder = PlainListBlockBuilder::new(128);
+        for item in &items {
+            builder.append(item.as_deref());
+        }
+        let data = builder.finish();
+
+        let mut scanner = PlainListBlockIterator::new(Bytes::from(data), items.len());
+
+        scanner.skip(1);
+        assert_eq!(scanner.remaining_items(), 4);
+
+        let mut builder = ListArrayBuilder::new();
+        assert_eq!(scanner.next_batch(Some(2), &mut builder), 2);
+        assert_eq!(builder.finish().to_vec(), items[1..3]);
+
+        let mut builder = ListArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 2);
+        assert_eq!(builder.finish().to_vec(), items[3..]);
+
+        let mut builder = ListArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 0);
+    }
+}
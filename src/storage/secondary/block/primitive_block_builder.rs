@@ -5,7 +5,7 @@ use std::marker::PhantomData;
 use risinglight_proto::rowset::BlockStatistics;
 
 use super::super::encode::PrimitiveFixedWidthEncode;
-use super::super::statistics::StatisticsBuilder;
+use super::super::statistics::{MinMaxStatisticsBuilder, StatisticsBuilder};
 use super::BlockBuilder;
 
 /// Encodes fixed-width data into a block. The layout is simply an array of
@@ -43,10 +43,14 @@ impl<T: PrimitiveFixedWidthEncode> BlockBuilder<T::ArrayType> for PlainPrimitive
 
     fn get_statistics(&self) -> Vec<BlockStatistics> {
         let mut stats_builder = StatisticsBuilder::new();
-        for item in self.data.chunks(T::WIDTH) {
+        let mut min_max_builder = MinMaxStatisticsBuilder::new();
+        for mut item in self.data.chunks(T::WIDTH) {
             stats_builder.add_item(Some(item));
+            min_max_builder.add_item(Some(T::decode(&mut item)));
         }
-        stats_builder.get_statistics()
+        let mut stats = stats_builder.get_statistics();
+        stats.extend(min_max_builder.get_statistics());
+        stats
     }
 
     fn finish(self) -> Vec<u8> {
@@ -6,7 +6,7 @@ use bitvec::prelude::{BitVec, Lsb0};
 use itertools::enumerate;
 use risinglight_proto::rowset::BlockStatistics;
 
-use super::super::statistics::StatisticsBuilder;
+use super::super::statistics::{MinMaxStatisticsBuilder, StatisticsBuilder};
 use super::super::PrimitiveFixedWidthEncode;
 use super::BlockBuilder;
 
@@ -31,6 +31,19 @@ impl<T: PrimitiveFixedWidthEncode> PlainPrimitiveNullableBlockBuilder<T> {
             _phantom: PhantomData,
         }
     }
+
+    /// Check if any null element has been appended.
+    pub fn has_null(&self) -> bool {
+        !self.bitmap.all()
+    }
+
+    /// Finish the block without the null bitmap. The layout is the same as
+    /// [`PlainPrimitiveBlockBuilder`](super::PlainPrimitiveBlockBuilder), and should only be used
+    /// if there is no null element.
+    pub fn finish_without_bitmap(self) -> Vec<u8> {
+        debug_assert!(!self.has_null());
+        self.data
+    }
 }
 
 impl<T: PrimitiveFixedWidthEncode> BlockBuilder<T::ArrayType>
@@ -57,12 +70,16 @@ impl<T: PrimitiveFixedWidthEncode> BlockBuilder<T::ArrayType>
 
     fn get_statistics(&self) -> Vec<BlockStatistics> {
         let mut stats_builder = StatisticsBuilder::new();
-        for (idx, item) in enumerate(self.data.chunks(T::WIDTH)) {
+        let mut min_max_builder = MinMaxStatisticsBuilder::new();
+        for (idx, mut item) in enumerate(self.data.chunks(T::WIDTH)) {
             if self.bitmap[idx] {
                 stats_builder.add_item(Some(item));
+                min_max_builder.add_item(Some(T::decode(&mut item)));
             }
         }
-        stats_builder.get_statistics()
+        let mut stats = stats_builder.get_statistics();
+        stats.extend(min_max_builder.get_statistics());
+        stats
     }
 
     fn finish(self) -> Vec<u8> {
@@ -90,4 +107,14 @@ mod tests {
         let expected_data: Vec<u8> = vec![1, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0, 13];
         assert_eq!(data, expected_data);
     }
+
+    #[test]
+    fn test_build_i32_without_bitmap() {
+        let mut builder = PlainPrimitiveNullableBlockBuilder::<i32>::new(128);
+        builder.append(Some(&1));
+        builder.append(Some(&3));
+        assert!(!builder.has_null());
+        let data = builder.finish_without_bitmap();
+        assert_eq!(data, vec![1, 0, 0, 0, 3, 0, 0, 0]);
+    }
 }
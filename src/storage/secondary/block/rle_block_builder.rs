@@ -0,0 +1,157 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use bytes::BufMut;
+use risinglight_proto::rowset::BlockStatistics;
+
+use super::BlockBuilder;
+use crate::array::Array;
+
+/// Encodes data into a run-length block. Each run of identical values is stored once in the
+/// inner block, together with the length of the run.
+///
+/// The layout is as follows:
+///
+/// ```plain
+/// | rle_num (u32) | run_length (u16) | run_length (u16) | ... | inner block data |
+/// ```
+pub struct RLEBlockBuilder<A, B>
+where
+    A: Array,
+    A::Item: PartialEq,
+    B: BlockBuilder<A>,
+{
+    /// Builder of the distinct values of each run
+    block_builder: B,
+
+    /// Length of each run
+    rle_counts: Vec<u16>,
+
+    /// Value of the last run. `None` indicates a null value.
+    previous_value: Option<<A::Item as ToOwned>::Owned>,
+
+    target_size: usize,
+
+    _phantom: PhantomData<A>,
+}
+
+impl<A, B> RLEBlockBuilder<A, B>
+where
+    A: Array,
+    A::Item: PartialEq,
+    B: BlockBuilder<A>,
+{
+    pub fn new(block_builder: B, target_size: usize) -> Self {
+        Self {
+            block_builder,
+            rle_counts: vec![],
+            previous_value: None,
+            target_size,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Check if the item can be appended to the last run.
+    fn extends_last_run(&self, item: &Option<&A::Item>) -> bool {
+        match self.rle_counts.last() {
+            Some(&count) if count < u16::MAX => {
+                self.previous_value.as_ref().map(Borrow::borrow) == *item
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<A, B> BlockBuilder<A> for RLEBlockBuilder<A, B>
+where
+    A: Array,
+    A::Item: PartialEq,
+    B: BlockBuilder<A>,
+{
+    fn append(&mut self, item: Option<&A::Item>) {
+        if self.extends_last_run(&item) {
+            *self.rle_counts.last_mut().unwrap() += 1;
+            return;
+        }
+        self.block_builder.append(item);
+        self.previous_value = item.map(ToOwned::to_owned);
+        self.rle_counts.push(1);
+    }
+
+    fn estimated_size(&self) -> usize {
+        std::mem::size_of::<u32>()
+            + self.rle_counts.len() * std::mem::size_of::<u16>()
+            + self.block_builder.estimated_size()
+    }
+
+    fn should_finish(&self, next_item: &Option<&A::Item>) -> bool {
+        if self.rle_counts.is_empty() || self.extends_last_run(next_item) {
+            return false;
+        }
+        self.block_builder.should_finish(next_item)
+            || self.estimated_size() + std::mem::size_of::<u16>() > self.target_size
+    }
+
+    fn get_statistics(&self) -> Vec<BlockStatistics> {
+        self.block_builder.get_statistics()
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.estimated_size());
+        data.put_u32_le(self.rle_counts.len() as u32);
+        for count in self.rle_counts {
+            data.put_u16_le(count);
+        }
+        data.extend(self.block_builder.finish());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::I32Array;
+    use crate::storage::secondary::block::{
+        PlainPrimitiveBlockBuilder, PlainPrimitiveNullableBlockBuilder,
+    };
+
+    #[test]
+    fn test_build_rle_i32() {
+        let mut builder =
+            RLEBlockBuilder::<I32Array, _>::new(PlainPrimitiveBlockBuilder::<i32>::new(128), 128);
+        for item in [1, 1, 1, 2, 2, 3] {
+            builder.append(Some(&item));
+        }
+        // 3 runs: 4 bytes of header, 2 bytes of length and 4 bytes of value for each
+        assert_eq!(builder.estimated_size(), 4 + 3 * 2 + 3 * 4);
+        assert!(!builder.should_finish(&Some(&3)));
+        assert_eq!(builder.finish().len(), 4 + 3 * 2 + 3 * 4);
+    }
+
+    #[test]
+    fn test_build_rle_nullable_i32() {
+        let mut builder = RLEBlockBuilder::<I32Array, _>::new(
+            PlainPrimitiveNullableBlockBuilder::<i32>::new(128),
+            128,
+        );
+        builder.append(None);
+        builder.append(None);
+        builder.append(Some(&1));
+        builder.append(Some(&1));
+        builder.append(None);
+        assert_eq!(builder.rle_counts, vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_build_rle_long_run() {
+        let mut builder =
+            RLEBlockBuilder::<I32Array, _>::new(PlainPrimitiveBlockBuilder::<i32>::new(128), 128);
+        for _ in 0..(u16::MAX as usize + 1) {
+            assert!(!builder.should_finish(&Some(&1)));
+            builder.append(Some(&1));
+        }
+        assert_eq!(builder.rle_counts, vec![u16::MAX, 1]);
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use bytes::Buf;
+
+use super::{Block, BlockIterator};
+use crate::array::{Array, ArrayBuilder};
+
+/// Split a run-length block into the run lengths and the inner block.
+///
+/// Returns the number of runs, the encoded run lengths and the inner block data.
+pub fn decode_rle_block(block: Block) -> (usize, Block, Block) {
+    let rle_num = (&block[..]).get_u32_le() as usize;
+    let rle_length = std::mem::size_of::<u32>() + rle_num * std::mem::size_of::<u16>();
+    let rle_data = block.slice(std::mem::size_of::<u32>()..rle_length);
+    let block_data = block.slice(rle_length..);
+    (rle_num, rle_data, block_data)
+}
+
+/// Scans one or several arrays from a run-length block. Values of each run are read from
+/// the inner block iterator, and repeated by the length of the run.
+pub struct RLEBlockIterator<A, B>
+where
+    A: Array,
+    B: BlockIterator<A>,
+{
+    /// Iterator of the distinct values of each run
+    block_iter: B,
+
+    /// Encoded length of each run
+    rle_block: Block,
+
+    /// Total count of elements in block
+    row_count: usize,
+
+    /// Indicates the beginning row of the next batch
+    next_row: usize,
+
+    /// Index of the current run
+    cur_run: usize,
+
+    /// Number of items consumed in the current run
+    cur_run_consumed: usize,
+
+    /// Value of the current run. `None` if it hasn't been read from the inner block iterator.
+    cur_value: Option<Option<<A::Item as ToOwned>::Owned>>,
+
+    _phantom: PhantomData<A>,
+}
+
+impl<A, B> RLEBlockIterator<A, B>
+where
+    A: Array,
+    B: BlockIterator<A>,
+{
+    pub fn new(block_iter: B, rle_block: Block, row_count: usize) -> Self {
+        Self {
+            block_iter,
+            rle_block,
+            row_count,
+            next_row: 0,
+            cur_run: 0,
+            cur_run_consumed: 0,
+            cur_value: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn run_length(&self, run: usize) -> usize {
+        (&self.rle_block[run * std::mem::size_of::<u16>()..]).get_u16_le() as usize
+    }
+
+    /// Move to the next run, skipping the value of the current run if it hasn't been read.
+    fn next_run(&mut self) {
+        if self.cur_value.take().is_none() {
+            self.block_iter.skip(1);
+        }
+        self.cur_run += 1;
+        self.cur_run_consumed = 0;
+    }
+}
+
+impl<A, B> BlockIterator<A> for RLEBlockIterator<A, B>
+where
+    A: Array,
+    B: BlockIterator<A>,
+{
+    fn next_batch(&mut self, expected_size: Option<usize>, builder: &mut A::Builder) -> usize {
+        if self.next_row >= self.row_count {
+            return 0;
+        }
+
+        let mut cnt = 0;
+        loop {
+            if let Some(expected_size) = expected_size {
+                assert!(expected_size > 0);
+                if cnt >= expected_size {
+                    break;
+                }
+            }
+
+            if self.next_row >= self.row_count {
+                break;
+            }
+
+            if self.cur_value.is_none() {
+                let mut value_builder = A::Builder::with_capacity(1);
+                self.block_iter.next_batch(Some(1), &mut value_builder);
+                let value = value_builder.finish();
+                self.cur_value = Some(value.get(0).map(ToOwned::to_owned));
+            }
+
+            let mut to_push = self.run_length(self.cur_run) - self.cur_run_consumed;
+            if let Some(expected_size) = expected_size {
+                to_push = to_push.min(expected_size - cnt);
+            }
+            let value = self
+                .cur_value
+                .as_ref()
+                .unwrap()
+                .as_ref()
+                .map(Borrow::borrow);
+            for _ in 0..to_push {
+                builder.push(value);
+            }
+
+            cnt += to_push;
+            self.next_row += to_push;
+            self.cur_run_consumed += to_push;
+            if self.cur_run_consumed == self.run_length(self.cur_run) {
+                self.next_run();
+            }
+        }
+
+        cnt
+    }
+
+    fn skip(&mut self, mut cnt: usize) {
+        cnt = cnt.min(self.remaining_items());
+        self.next_row += cnt;
+        while cnt > 0 {
+            let remaining = self.run_length(self.cur_run) - self.cur_run_consumed;
+            if cnt < remaining {
+                self.cur_run_consumed += cnt;
+                break;
+            }
+            cnt -= remaining;
+            self.next_run();
+        }
+    }
+
+    fn remaining_items(&self) -> usize {
+        self.row_count - self.next_row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::array::{ArrayToVecExt, I32Array, I32ArrayBuilder};
+    use crate::storage::secondary::block::{
+        BlockBuilder, PlainPrimitiveBlockIterator, PlainPrimitiveNullableBlockBuilder,
+        PlainPrimitiveNullableBlockIterator, RLEBlockBuilder,
+    };
+    use crate::storage::secondary::PlainPrimitiveBlockBuilder;
+
+    #[test]
+    fn test_scan_rle_i32() {
+        let mut builder =
+            RLEBlockBuilder::<I32Array, _>::new(PlainPrimitiveBlockBuilder::<i32>::new(128), 128);
+        for item in [1, 1, 1, 2, 2, 3, 3, 3, 3] {
+            builder.append(Some(&item));
+        }
+        let data = builder.finish();
+
+        let (rle_num, rle_data, block_data) = decode_rle_block(Bytes::from(data));
+        assert_eq!(rle_num, 3);
+        let block_iter = PlainPrimitiveBlockIterator::<i32>::new(block_data, rle_num);
+        let mut scanner = RLEBlockIterator::<I32Array, _>::new(block_iter, rle_data, 9);
+
+        scanner.skip(2);
+        assert_eq!(scanner.remaining_items(), 7);
+
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(Some(2), &mut builder), 2);
+        assert_eq!(builder.finish().to_vec(), vec![Some(1), Some(2)]);
+
+        scanner.skip(2);
+
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 3);
+        assert_eq!(builder.finish().to_vec(), vec![Some(3), Some(3), Some(3)]);
+
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 0);
+    }
+
+    #[test]
+    fn test_scan_rle_nullable_i32() {
+        let mut builder = RLEBlockBuilder::<I32Array, _>::new(
+            PlainPrimitiveNullableBlockBuilder::<i32>::new(128),
+            128,
+        );
+        let items = [None, None, Some(1), Some(1), None, Some(2)];
+        for item in items {
+            builder.append(item.as_ref());
+        }
+        let data = builder.finish();
+
+        let (rle_num, rle_data, block_data) = decode_rle_block(Bytes::from(data));
+        let block_iter = PlainPrimitiveNullableBlockIterator::<i32>::new(block_data, rle_num);
+        let mut scanner = RLEBlockIterator::<I32Array, _>::new(block_iter, rle_data, 6);
+
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 6);
+        assert_eq!(builder.finish().to_vec(), items.to_vec());
+    }
+}
@@ -3,7 +3,7 @@
 use bytes::BufMut;
 use risinglight_proto::rowset::BlockStatistics;
 
-use super::super::statistics::StatisticsBuilder;
+use super::super::statistics::{BytesMinMaxStatisticsBuilder, StatisticsBuilder};
 use super::BlockBuilder;
 use crate::array::Utf8Array;
 
@@ -11,6 +11,9 @@ use crate::array::Utf8Array;
 /// ```plain
 /// | offset (u32) | offset | offset | data | data | data |
 /// ```
+///
+/// Each offset is the end position of an item in the concatenated UTF-8 data, so that the i-th
+/// item can be located in O(1) by the (i-1)-th and i-th offsets.
 pub struct PlainVarcharBlockBuilder {
     data: Vec<u8>,
     offsets: Vec<u32>,
@@ -26,6 +29,29 @@ impl PlainVarcharBlockBuilder {
             target_size,
         }
     }
+
+    /// Iterate over the bytes of all appended items.
+    pub(super) fn items(&self) -> impl Iterator<Item = &[u8]> {
+        let starts = std::iter::once(0).chain(self.offsets.iter().map(|pos| *pos as usize));
+        starts
+            .zip(self.offsets.iter())
+            .map(|(start, end)| &self.data[start..*end as usize])
+    }
+}
+
+/// Build distinct value, min and max statistics of the items. Null items are ignored.
+pub(super) fn varchar_statistics<'a>(
+    items: impl Iterator<Item = Option<&'a [u8]>>,
+) -> Vec<BlockStatistics> {
+    let mut stats_builder = StatisticsBuilder::new();
+    let mut min_max_builder = BytesMinMaxStatisticsBuilder::new();
+    for item in items {
+        stats_builder.add_item(item);
+        min_max_builder.add_item(item);
+    }
+    let mut stats = stats_builder.get_statistics();
+    stats.extend(min_max_builder.get_statistics());
+    stats
 }
 
 impl BlockBuilder<Utf8Array> for PlainVarcharBlockBuilder {
@@ -48,15 +74,7 @@ impl BlockBuilder<Utf8Array> for PlainVarcharBlockBuilder {
     }
 
     fn get_statistics(&self) -> Vec<BlockStatistics> {
-        let mut stats_builder = StatisticsBuilder::new();
-        let mut last_pos: usize = 0;
-        let mut cur_pos;
-        for pos in &self.offsets {
-            cur_pos = *pos as usize;
-            stats_builder.add_item(Some(&self.data[last_pos..cur_pos]));
-            last_pos = cur_pos;
-        }
-        stats_builder.get_statistics()
+        varchar_statistics(self.items().map(Some))
     }
 
     fn finish(self) -> Vec<u8> {
@@ -5,6 +5,9 @@ use bytes::Buf;
 use super::{Block, BlockIterator};
 use crate::array::{ArrayBuilder, Utf8Array, Utf8ArrayBuilder};
 
+/// Size of each offset in the block.
+const OFFSET: usize = std::mem::size_of::<u32>();
+
 /// Scans one or several arrays from the block content.
 pub struct PlainVarcharBlockIterator {
     /// Block content
@@ -25,6 +28,26 @@ impl PlainVarcharBlockIterator {
             next_row: 0,
         }
     }
+
+    /// Get the `row`-th item of the block in O(1) by the offsets.
+    pub fn value_at(&self, row: usize) -> &str {
+        let offset = |row: usize| (&self.block[row * OFFSET..]).get_u32_le() as usize;
+        let from = if row == 0 { 0 } else { offset(row - 1) };
+        let to = offset(row);
+        let data_buffer = &self.block[OFFSET * self.row_count..];
+        std::str::from_utf8(&data_buffer[from..to]).unwrap()
+    }
+
+    /// Size of the offsets and the data of a block with `row_count` items.
+    pub fn encoded_len(block: &[u8], row_count: usize) -> usize {
+        match row_count {
+            0 => 0,
+            _ => {
+                let last_offset = (&block[(row_count - 1) * OFFSET..]).get_u32_le() as usize;
+                OFFSET * row_count + last_offset
+            }
+        }
+    }
 }
 
 impl BlockIterator<Utf8Array> for PlainVarcharBlockIterator {
@@ -40,11 +63,6 @@ impl BlockIterator<Utf8Array> for PlainVarcharBlockIterator {
         // TODO(chi): error handling on corrupted block
 
         let mut cnt = 0;
-        const OFFSET: usize = std::mem::size_of::<u32>();
-        let offsets_length = OFFSET * self.row_count;
-        let offset_buffer = &self.block[0..offsets_length];
-        let data_buffer = &self.block[offsets_length..];
-
         loop {
             if let Some(expected_size) = expected_size {
                 assert!(expected_size > 0);
@@ -57,19 +75,7 @@ impl BlockIterator<Utf8Array> for PlainVarcharBlockIterator {
                 break;
             }
 
-            let from;
-            let to;
-
-            if self.next_row == 0 {
-                let mut cur_offsets = offset_buffer;
-                from = 0;
-                to = cur_offsets.get_u32_le() as usize;
-            } else {
-                let mut cur_offsets = &offset_buffer[(self.next_row - 1) * OFFSET..];
-                from = cur_offsets.get_u32_le() as usize;
-                to = cur_offsets.get_u32_le() as usize;
-            }
-            builder.push(Some(std::str::from_utf8(&data_buffer[from..to]).unwrap()));
+            builder.push(Some(self.value_at(self.next_row)));
 
             cnt += 1;
             self.next_row += 1;
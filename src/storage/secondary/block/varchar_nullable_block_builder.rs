@@ -0,0 +1,96 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use bitvec::prelude::{BitVec, Lsb0};
+use risinglight_proto::rowset::BlockStatistics;
+
+use super::varchar_block_builder::varchar_statistics;
+use super::{BlockBuilder, PlainVarcharBlockBuilder};
+use crate::array::Utf8Array;
+
+/// Encodes offset and data into a block, with null element support.
+///
+/// The layout is the same as [`PlainVarcharBlockBuilder`] followed by a u8 bitmap. Null elements
+/// are stored as empty strings.
+///
+/// ```plain
+/// | offset (u32) | offset | offset | data | data | data | bitmap |
+/// ```
+pub struct PlainVarcharNullableBlockBuilder {
+    inner: PlainVarcharBlockBuilder,
+    bitmap: BitVec<u8, Lsb0>,
+    target_size: usize,
+}
+
+impl PlainVarcharNullableBlockBuilder {
+    pub fn new(target_size: usize) -> Self {
+        Self {
+            inner: PlainVarcharBlockBuilder::new(target_size),
+            bitmap: BitVec::new(),
+            target_size,
+        }
+    }
+
+    /// Check if any null element has been appended.
+    pub fn has_null(&self) -> bool {
+        !self.bitmap.all()
+    }
+
+    /// Finish the block without the null bitmap. The layout is the same as
+    /// [`PlainVarcharBlockBuilder`], and should only be used if there is no null element.
+    pub fn finish_without_bitmap(self) -> Vec<u8> {
+        debug_assert!(!self.has_null());
+        self.inner.finish()
+    }
+}
+
+impl BlockBuilder<Utf8Array> for PlainVarcharNullableBlockBuilder {
+    fn append(&mut self, item: Option<&str>) {
+        self.inner.append(Some(item.unwrap_or("")));
+        self.bitmap.push(item.is_some());
+    }
+
+    fn estimated_size(&self) -> usize {
+        let bitmap_byte_len = (self.bitmap.len() + 7) / 8;
+        self.inner.estimated_size() + bitmap_byte_len
+    }
+
+    fn should_finish(&self, next_item: &Option<&str>) -> bool {
+        !self.bitmap.is_empty()
+            && self.estimated_size()
+                + next_item.map(|x| x.len()).unwrap_or(0)
+                + std::mem::size_of::<u32>()
+                + 1
+                > self.target_size
+    }
+
+    fn get_statistics(&self) -> Vec<BlockStatistics> {
+        let items = self.inner.items().zip(self.bitmap.iter());
+        varchar_statistics(items.map(|(item, valid)| valid.then(|| item)))
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let mut data = self.inner.finish();
+        data.extend(self.bitmap.as_raw_slice().iter());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_nullable_str() {
+        let mut builder = PlainVarcharNullableBlockBuilder::new(128);
+        builder.append(Some("233"));
+        builder.append(None);
+        builder.append(Some(""));
+        builder.append(Some("23333"));
+        assert_eq!(builder.estimated_size(), 8 + 4 * 4 + 1);
+        assert!(builder.has_null());
+        assert!(!builder.should_finish(&Some("23333333")));
+        let data = builder.finish();
+        assert_eq!(data.len(), 8 + 4 * 4 + 1);
+        assert_eq!(data[data.len() - 1], 0b1101);
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use bitvec::prelude::Lsb0;
+use bitvec::slice::BitSlice;
+
+use super::{Block, BlockIterator, PlainVarcharBlockIterator};
+use crate::array::{ArrayBuilder, Utf8Array, Utf8ArrayBuilder};
+
+/// Scans one or several arrays from the block content, with null element support.
+pub struct PlainVarcharNullableBlockIterator {
+    /// Iterator on the offsets and data of the block, used to locate the items
+    values: PlainVarcharBlockIterator,
+
+    /// Null bitmap of the block
+    bitmap: Block,
+
+    /// Total count of elements in block
+    row_count: usize,
+
+    /// Indicates the beginning row of the next batch
+    next_row: usize,
+}
+
+impl PlainVarcharNullableBlockIterator {
+    pub fn new(block: Block, row_count: usize) -> Self {
+        let bitmap_start = PlainVarcharBlockIterator::encoded_len(&block, row_count);
+        Self {
+            values: PlainVarcharBlockIterator::new(block.slice(..bitmap_start), row_count),
+            bitmap: block.slice(bitmap_start..),
+            row_count,
+            next_row: 0,
+        }
+    }
+}
+
+impl BlockIterator<Utf8Array> for PlainVarcharNullableBlockIterator {
+    fn next_batch(
+        &mut self,
+        expected_size: Option<usize>,
+        builder: &mut Utf8ArrayBuilder,
+    ) -> usize {
+        if self.next_row >= self.row_count {
+            return 0;
+        }
+
+        let bitmap_slice = BitSlice::<u8, Lsb0>::from_slice(&self.bitmap);
+        let mut cnt = 0;
+        loop {
+            if let Some(expected_size) = expected_size {
+                assert!(expected_size > 0);
+                if cnt >= expected_size {
+                    break;
+                }
+            }
+
+            if self.next_row >= self.row_count {
+                break;
+            }
+
+            if bitmap_slice[self.next_row] {
+                builder.push(Some(self.values.value_at(self.next_row)));
+            } else {
+                builder.push(None);
+            }
+
+            cnt += 1;
+            self.next_row += 1;
+        }
+
+        cnt
+    }
+
+    fn skip(&mut self, cnt: usize) {
+        self.next_row += cnt;
+    }
+
+    fn remaining_items(&self) -> usize {
+        self.row_count - self.next_row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Buf, Bytes};
+
+    use super::*;
+    use crate::array::ArrayToVecExt;
+    use crate::storage::secondary::block::{BlockBuilder, PlainVarcharNullableBlockBuilder};
+
+    #[test]
+    fn test_scan_nullable_varchar() {
+        let items = [Some("233"), None, Some(""), Some("23333"), None, Some("")];
+        let mut builder = PlainVarcharNullableBlockBuilder::new(128);
+        for item in items {
+            builder.append(item);
+        }
+        let data = builder.finish();
+
+        // offsets are the end positions of the items, bounded by the data length
+        let offsets = (0..items.len())
+            .map(|i| (&data[i * 4..]).get_u32_le() as usize)
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![3, 3, 3, 8, 8, 8]);
+        assert_eq!(
+            PlainVarcharBlockIterator::encoded_len(&data, items.len()),
+            4 * items.len() + 8
+        );
+        assert_eq!(data.len(), 4 * items.len() + 8 + 1);
+
+        let mut scanner = PlainVarcharNullableBlockIterator::new(Bytes::from(data), items.len());
+
+        scanner.skip(1);
+        assert_eq!(scanner.remaining_items(), 5);
+
+        let mut builder = Utf8ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(Some(3), &mut builder), 3);
+        assert_eq!(
+            builder.finish().to_vec(),
+            vec![None, Some("".to_string()), Some("23333".to_string())]
+        );
+
+        let mut builder = Utf8ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 2);
+        assert_eq!(builder.finish().to_vec(), vec![None, Some("".to_string())]);
+
+        let mut builder = Utf8ArrayBuilder::new();
+        assert_eq!(scanner.next_batch(None, &mut builder), 0);
+    }
+}
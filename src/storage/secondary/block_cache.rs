@@ -0,0 +1,178 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use super::{Block, BlockCacheKey};
+
+/// LRU cache of blocks read from column files, bounded by the total size of blocks in bytes.
+///
+/// [`BlockCache`] is a reference to the shared cache, and can be cloned without much overhead.
+#[derive(Clone)]
+pub struct BlockCache {
+    inner: Arc<BlockCacheInner>,
+}
+
+struct BlockCacheInner {
+    /// Maximum total size of cached blocks in bytes
+    capacity: usize,
+    lru: Mutex<LruState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct LruState {
+    /// Cached blocks and their last access time
+    entries: HashMap<BlockCacheKey, (Block, u64)>,
+    /// Keys ordered by their last access time, from the least recently used
+    access_order: BTreeMap<u64, BlockCacheKey>,
+    /// Logical clock of accesses
+    clock: u64,
+    /// Total size of cached blocks in bytes
+    size: usize,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &BlockCacheKey) -> Option<Block> {
+        self.clock += 1;
+        let clock = self.clock;
+        let (block, access_time) = self.entries.get_mut(key)?;
+        let key = self.access_order.remove(access_time).unwrap();
+        *access_time = clock;
+        self.access_order.insert(clock, key);
+        Some(block.clone())
+    }
+
+    fn remove(&mut self, key: &BlockCacheKey) {
+        if let Some((block, access_time)) = self.entries.remove(key) {
+            self.access_order.remove(&access_time);
+            self.size -= block.len();
+        }
+    }
+
+    fn evict_lru(&mut self) {
+        let key = match self.access_order.values().next() {
+            Some(key) => key.clone(),
+            None => return,
+        };
+        self.remove(&key);
+    }
+}
+
+impl BlockCache {
+    /// Create a block cache holding blocks of at most `capacity` bytes in total.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(BlockCacheInner {
+                capacity,
+                lru: Mutex::new(LruState::default()),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Get a block from cache, and mark it as the most recently used one.
+    pub fn get(&self, key: &BlockCacheKey) -> Option<Block> {
+        let block = self.inner.lru.lock().touch(key);
+        let counter = if block.is_some() {
+            &self.inner.hits
+        } else {
+            &self.inner.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        block
+    }
+
+    /// Insert a block into cache, evicting least recently used blocks if the cache is full.
+    /// Blocks larger than the capacity are not cached.
+    pub fn insert(&self, key: BlockCacheKey, block: Block) {
+        if block.len() > self.inner.capacity {
+            return;
+        }
+        let mut lru = self.inner.lru.lock();
+        lru.remove(&key);
+        while lru.size + block.len() > self.inner.capacity {
+            lru.evict_lru();
+        }
+        lru.clock += 1;
+        let clock = lru.clock;
+        lru.size += block.len();
+        lru.access_order.insert(clock, key.clone());
+        lru.entries.insert(key, (block, clock));
+    }
+
+    /// Maximum total size of cached blocks in bytes.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Current total size of cached blocks in bytes.
+    pub fn size(&self) -> usize {
+        self.inner.lru.lock().size
+    }
+
+    /// Number of lookups that found the block in cache.
+    pub fn hits(&self) -> u64 {
+        self.inner.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of lookups that didn't find the block in cache.
+    pub fn misses(&self) -> u64 {
+        self.inner.misses.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn key(block_id: u32) -> BlockCacheKey {
+        BlockCacheKey::default().block(block_id)
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = BlockCache::new(30);
+        for block_id in 0..3 {
+            cache.insert(key(block_id), Bytes::from(vec![0; 10]));
+        }
+        assert_eq!(cache.size(), 30);
+
+        // block 0 becomes the most recently used one, so block 1 is evicted
+        assert!(cache.get(&key(0)).is_some());
+        cache.insert(key(3), Bytes::from(vec![0; 10]));
+        assert_eq!(cache.size(), 30);
+        assert!(cache.get(&key(1)).is_none());
+        assert!(cache.get(&key(0)).is_some());
+        assert!(cache.get(&key(2)).is_some());
+        assert!(cache.get(&key(3)).is_some());
+        assert_eq!(cache.hits(), 4);
+        assert_eq!(cache.misses(), 1);
+
+        // a large block evicts several blocks
+        cache.insert(key(4), Bytes::from(vec![0; 25]));
+        assert_eq!(cache.size(), 25);
+        assert!(cache.get(&key(0)).is_none());
+
+        // blocks larger than capacity are not cached
+        cache.insert(key(5), Bytes::from(vec![0; 31]));
+        assert!(cache.get(&key(5)).is_none());
+        assert!(cache.get(&key(4)).is_some());
+    }
+
+    #[test]
+    fn test_insert_existing_key() {
+        let cache = BlockCache::new(30);
+        cache.insert(key(0), Bytes::from(vec![0; 10]));
+        cache.insert(key(0), Bytes::from(vec![1; 20]));
+        assert_eq!(cache.size(), 20);
+        assert_eq!(cache.get(&key(0)).unwrap()[0], 1);
+    }
+}
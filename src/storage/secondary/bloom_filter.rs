@@ -0,0 +1,192 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+//! Bloom filter of all values in a column, which is used to skip rowsets on point lookups.
+//!
+//! Keys of the filter are the encoded values. Primitive values are encoded in the same
+//! fixed-width format as the column data, strings are encoded as UTF-8 bytes, and lists are
+//! encoded by [`DataValue::encode`]. Null values are not added to the filter.
+
+use risinglight_proto::rowset::BloomFilter as BloomFilterProto;
+
+use super::PrimitiveFixedWidthEncode;
+use crate::array::{Array, ArrayImpl};
+use crate::types::DataValue;
+
+/// Number of bits for each key. 10 bits per key gives a false-positive rate of about 1%.
+const BITS_PER_KEY: usize = 10;
+
+/// Hash a key with two independent hash functions: CRC32 and 32-bit FNV-1a.
+///
+/// The hash functions must be stable, as the filter is persisted.
+fn hash_key(key: &[u8]) -> (u32, u32) {
+    let h1 = crc32fast::hash(key);
+    let h2 = key.iter().fold(0x811c_9dc5u32, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+    });
+    (h1, h2)
+}
+
+/// Builds a [`BloomFilter`] from keys.
+#[derive(Default)]
+pub struct BloomFilterBuilder {
+    hashes: Vec<(u32, u32)>,
+    buffer: Vec<u8>,
+}
+
+impl BloomFilterBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_key(&mut self, key: &[u8]) {
+        self.hashes.push(hash_key(key));
+    }
+
+    /// Add all non-null values of an array to the filter.
+    pub fn add_array(&mut self, array: &ArrayImpl) {
+        match array {
+            ArrayImpl::Bool(array) => self.add_primitive_array(array),
+            ArrayImpl::Int32(array) => self.add_primitive_array(array),
+            ArrayImpl::Int64(array) => {
+                for item in array.iter().flatten() {
+                    self.add_key(&item.to_le_bytes());
+                }
+            }
+            ArrayImpl::Float64(array) => self.add_primitive_array(array),
+            ArrayImpl::Utf8(array) => {
+                for item in array.iter().flatten() {
+                    self.add_key(item.as_bytes());
+                }
+            }
+            ArrayImpl::Blob(array) => {
+                for item in array.iter().flatten() {
+                    self.add_key(item);
+                }
+            }
+            ArrayImpl::Decimal(array) => self.add_primitive_array(array),
+            ArrayImpl::Date(array) => self.add_primitive_array(array),
+            ArrayImpl::Timestamp(array) => self.add_primitive_array(array),
+            ArrayImpl::Interval(array) => self.add_primitive_array(array),
+            ArrayImpl::List(array) => {
+                for item in array.iter().flatten() {
+                    self.buffer.clear();
+                    DataValue::List(item.to_owned()).encode(&mut self.buffer);
+                    self.hashes.push(hash_key(&self.buffer));
+                }
+            }
+        }
+    }
+
+    fn add_primitive_array<A>(&mut self, array: &A)
+    where
+        A: Array,
+        A::Item: PrimitiveFixedWidthEncode,
+    {
+        for item in array.iter().flatten() {
+            self.buffer.clear();
+            item.encode(&mut self.buffer);
+            self.hashes.push(hash_key(&self.buffer));
+        }
+    }
+
+    pub fn finish(self) -> BloomFilter {
+        // use at least 64 bits to avoid a high false-positive rate on small filters
+        let num_bits = (self.hashes.len() * BITS_PER_KEY).max(64);
+        let num_bytes = (num_bits + 7) / 8;
+        let num_bits = num_bytes * 8;
+        // k = ln(2) * bits_per_key minimizes the false-positive rate
+        let num_hashes = ((BITS_PER_KEY as f64 * std::f64::consts::LN_2) as u32).clamp(1, 30);
+
+        let mut bits = vec![0u8; num_bytes];
+        for (h1, h2) in self.hashes {
+            for bit in probe_bits(h1, h2, num_hashes, num_bits) {
+                bits[bit / 8] |= 1 << (bit % 8);
+            }
+        }
+        BloomFilter { num_hashes, bits }
+    }
+}
+
+/// Get the bits of a key with double hashing.
+fn probe_bits(h1: u32, h2: u32, num_hashes: u32, num_bits: usize) -> impl Iterator<Item = usize> {
+    (0..num_hashes).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) as usize) % num_bits)
+}
+
+/// A Bloom filter of a column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    num_hashes: u32,
+    bits: Vec<u8>,
+}
+
+impl BloomFilter {
+    /// Check if the key may be in the filter. A `false` return value means that the key is
+    /// definitely absent.
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        let (h1, h2) = hash_key(key);
+        let num_bits = self.bits.len() * 8;
+        if num_bits == 0 {
+            return true;
+        }
+        probe_bits(h1, h2, self.num_hashes, num_bits)
+            .all(|bit| self.bits[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+
+    pub fn to_proto(&self) -> BloomFilterProto {
+        BloomFilterProto {
+            num_hashes: self.num_hashes,
+            bits: self.bits.clone(),
+        }
+    }
+
+    pub fn from_proto(proto: BloomFilterProto) -> Self {
+        Self {
+            num_hashes: proto.num_hashes,
+            bits: proto.bits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut builder = BloomFilterBuilder::new();
+        for i in 0..10000 {
+            builder.add_key(format!("key{}", i).as_bytes());
+        }
+        let filter = BloomFilter::from_proto(builder.finish().to_proto());
+
+        // no false negatives
+        for i in 0..10000 {
+            assert!(filter.may_contain(format!("key{}", i).as_bytes()));
+        }
+
+        // false-positive rate should be about 1%
+        let false_positives = (10000..20000)
+            .filter(|i| filter.may_contain(format!("key{}", i).as_bytes()))
+            .count();
+        assert!(
+            false_positives < 300,
+            "false positives: {}",
+            false_positives
+        );
+    }
+
+    #[test]
+    fn test_bloom_filter_array() {
+        let mut builder = BloomFilterBuilder::new();
+        builder.add_array(&ArrayImpl::Int32(
+            [Some(1), None, Some(3)].into_iter().collect(),
+        ));
+        let filter = builder.finish();
+        assert!(filter.may_contain(&1i32.to_le_bytes()));
+        assert!(filter.may_contain(&3i32.to_le_bytes()));
+        let absent = (100..200i32)
+            .filter(|i| filter.may_contain(&i.to_le_bytes()))
+            .count();
+        assert!(absent < 10);
+    }
+}
@@ -8,20 +8,49 @@ pub fn build_checksum(checksum_type: ChecksumType, block_data: &[u8]) -> u64 {
     match checksum_type {
         ChecksumType::None => 0,
         ChecksumType::Crc32 => crc32fast::hash(block_data) as u64,
+        ChecksumType::Crc32c => crc32c::crc32c(block_data) as u64,
     }
 }
 
+/// Verify the checksum of data. Verification is skipped if the checksum type is `None`.
 pub fn verify_checksum(
     checksum_type: ChecksumType,
     index_data: &[u8],
     checksum: u64,
 ) -> StorageResult<()> {
-    let chksum = match checksum_type {
-        ChecksumType::None => 0,
-        ChecksumType::Crc32 => crc32fast::hash(index_data) as u64,
-    };
+    if checksum_type == ChecksumType::None {
+        return Ok(());
+    }
+    let chksum = build_checksum(checksum_type, index_data);
     if chksum != checksum {
         return Err(TracedStorageError::checksum(chksum, checksum));
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::StorageError;
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(build_checksum(ChecksumType::Crc32c, b""), 0);
+        assert_eq!(
+            build_checksum(ChecksumType::Crc32c, b"123456789"),
+            0xe306_9283
+        );
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let data = b"risinglight";
+        for checksum_type in [ChecksumType::Crc32, ChecksumType::Crc32c] {
+            let checksum = build_checksum(checksum_type, data);
+            verify_checksum(checksum_type, data, checksum).unwrap();
+            let err = verify_checksum(checksum_type, b"risingdark", checksum).unwrap_err();
+            assert!(matches!(err.inner(), StorageError::ChecksumMismatch(_, _)));
+        }
+        verify_checksum(ChecksumType::None, data, 0x2333).unwrap();
+    }
+}
@@ -11,6 +11,10 @@ mod char_column_builder;
 mod column_builder;
 mod column_iterator;
 mod concrete_column_iterator;
+mod default_column_iterator;
+mod dictionary_column_builder;
+mod list_column_builder;
+mod list_column_factory;
 mod primitive_column_builder;
 mod primitive_column_factory;
 mod row_handler_sequencer;
@@ -22,6 +26,10 @@ use bitvec::vec::BitVec;
 pub use column_builder::*;
 pub use column_iterator::*;
 pub use concrete_column_iterator::*;
+pub use default_column_iterator::*;
+pub use dictionary_column_builder::*;
+pub use list_column_builder::*;
+pub use list_column_factory::*;
 pub use primitive_column_builder::*;
 pub use primitive_column_factory::*;
 use risinglight_proto::rowset::BlockIndex;
@@ -32,9 +40,8 @@ use std::sync::{Arc, Mutex};
 
 use bytes::Bytes;
 pub use char_column_factory::*;
-use moka::future::Cache;
 
-use super::{Block, BlockCacheKey, BlockHeader, ColumnIndex, BLOCK_HEADER_SIZE};
+use super::{Block, BlockCache, BlockCacheKey, BlockHeader, ColumnIndex, BLOCK_HEADER_SIZE};
 use crate::array::Array;
 use crate::storage::secondary::verify_checksum;
 use crate::storage::{StorageResult, TracedStorageError};
@@ -114,7 +121,7 @@ pub enum ColumnReadableFile {
 pub struct Column {
     index: ColumnIndex,
     file: ColumnReadableFile,
-    block_cache: Cache<BlockCacheKey, Block>,
+    block_cache: BlockCache,
     base_block_key: BlockCacheKey,
 }
 
@@ -122,7 +129,7 @@ impl Column {
     pub fn new(
         index: ColumnIndex,
         file: ColumnReadableFile,
-        block_cache: Cache<BlockCacheKey, Block>,
+        block_cache: BlockCache,
         base_block_key: BlockCacheKey,
     ) -> Self {
         Self {
@@ -181,7 +188,7 @@ impl Column {
             .unwrap()?;
 
             // TODO(chi): we should invalidate cache item after a RowSet has been compacted.
-            self.block_cache.insert(key, block.clone()).await;
+            self.block_cache.insert(key, block.clone());
 
             do_verify_checksum = true;
             block
@@ -5,6 +5,7 @@ use risinglight_proto::rowset::BlockIndex;
 
 use super::super::{
     BlockBuilder, BlockIndexBuilder, PlainCharBlockBuilder, PlainVarcharBlockBuilder,
+    PlainVarcharNullableBlockBuilder,
 };
 use super::{append_one_by_one, ColumnBuilder};
 use crate::array::{Array, Utf8Array};
@@ -14,6 +15,7 @@ use crate::storage::secondary::ColumnBuilderOptions;
 pub(super) enum CharBlockBuilderImpl {
     PlainFixedChar(PlainCharBlockBuilder),
     PlainVarchar(PlainVarcharBlockBuilder),
+    PlainVarcharNullable(PlainVarcharNullableBlockBuilder),
 }
 
 /// Column builder of char types.
@@ -47,6 +49,7 @@ impl CharColumnBuilder {
     }
 
     fn finish_builder(&mut self) {
+        let mut no_nulls = false;
         let (block_type, stats, mut block_data) = match self.current_builder.take().unwrap() {
             CharBlockBuilderImpl::PlainFixedChar(builder) => (
                 BlockType::PlainFixedChar,
@@ -58,10 +61,27 @@ impl CharColumnBuilder {
                 builder.get_statistics(),
                 builder.finish(),
             ),
+            // omit the null bitmap if there is no null value in the block
+            CharBlockBuilderImpl::PlainVarcharNullable(builder) if !builder.has_null() => {
+                no_nulls = true;
+                (
+                    BlockType::PlainVarcharNullable,
+                    builder.get_statistics(),
+                    builder.finish_without_bitmap(),
+                )
+            }
+            CharBlockBuilderImpl::PlainVarcharNullable(builder) => (
+                BlockType::PlainVarcharNullable,
+                builder.get_statistics(),
+                builder.finish(),
+            ),
         };
 
         self.block_index_builder
             .finish_block(block_type, &mut self.data, &mut block_data, stats);
+        if no_nulls {
+            self.block_index_builder.mark_no_nulls();
+        }
     }
 }
 
@@ -80,16 +100,19 @@ impl ColumnBuilder<Utf8Array> for CharColumnBuilder {
                             ),
                         ));
                     }
-                    (None, _) => {
+                    (None, false) => {
                         self.current_builder = Some(CharBlockBuilderImpl::PlainVarchar(
                             PlainVarcharBlockBuilder::new(self.options.target_block_size - 16),
                         ));
                     }
-                    (char_width, nullable) => unimplemented!(
-                        "width {:?} with nullable {} not implemented",
-                        char_width,
-                        nullable
-                    ),
+                    // nullable chars are stored as varchars
+                    (_, true) => {
+                        self.current_builder = Some(CharBlockBuilderImpl::PlainVarcharNullable(
+                            PlainVarcharNullableBlockBuilder::new(
+                                self.options.target_block_size - 16,
+                            ),
+                        ));
+                    }
                 }
             }
 
@@ -100,6 +123,9 @@ impl ColumnBuilder<Utf8Array> for CharColumnBuilder {
                 CharBlockBuilderImpl::PlainVarchar(builder) => {
                     append_one_by_one(&mut iter, builder)
                 }
+                CharBlockBuilderImpl::PlainVarcharNullable(builder) => {
+                    append_one_by_one(&mut iter, builder)
+                }
             };
 
             self.block_index_builder.add_rows(row_count);
@@ -1,5 +1,7 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::sync::Arc;
+
 use risinglight_proto::rowset::block_index::BlockType;
 use risinglight_proto::rowset::BlockIndex;
 
@@ -7,13 +9,18 @@ use super::super::{Block, BlockIterator};
 use super::{BlockIteratorFactory, ConcreteColumnIterator};
 use crate::array::{Utf8Array, Utf8ArrayBuilder};
 use crate::storage::secondary::block::{
-    FakeBlockIterator, PlainCharBlockIterator, PlainVarcharBlockIterator,
+    DictionaryBlockIterator, FakeBlockIterator, PlainCharBlockIterator,
+    PlainPrimitiveBlockIterator, PlainPrimitiveNullableBlockIterator, PlainVarcharBlockIterator,
+    PlainVarcharNullableBlockIterator,
 };
 
 /// All supported block iterators for char types.
 pub enum CharBlockIteratorImpl {
     PlainFixedChar(PlainCharBlockIterator),
     PlainVarchar(PlainVarcharBlockIterator),
+    PlainVarcharNullable(PlainVarcharNullableBlockIterator),
+    Dictionary(DictionaryBlockIterator<PlainPrimitiveBlockIterator<i32>>),
+    DictionaryNullable(DictionaryBlockIterator<PlainPrimitiveNullableBlockIterator<i32>>),
     Fake(FakeBlockIterator<Utf8Array>),
 }
 
@@ -26,6 +33,9 @@ impl BlockIterator<Utf8Array> for CharBlockIteratorImpl {
         match self {
             Self::PlainFixedChar(it) => it.next_batch(expected_size, builder),
             Self::PlainVarchar(it) => it.next_batch(expected_size, builder),
+            Self::PlainVarcharNullable(it) => it.next_batch(expected_size, builder),
+            Self::Dictionary(it) => it.next_batch(expected_size, builder),
+            Self::DictionaryNullable(it) => it.next_batch(expected_size, builder),
             Self::Fake(it) => it.next_batch(expected_size, builder),
         }
     }
@@ -34,6 +44,9 @@ impl BlockIterator<Utf8Array> for CharBlockIteratorImpl {
         match self {
             Self::PlainFixedChar(it) => it.skip(cnt),
             Self::PlainVarchar(it) => it.skip(cnt),
+            Self::PlainVarcharNullable(it) => it.skip(cnt),
+            Self::Dictionary(it) => it.skip(cnt),
+            Self::DictionaryNullable(it) => it.skip(cnt),
             Self::Fake(it) => it.skip(cnt),
         }
     }
@@ -42,6 +55,9 @@ impl BlockIterator<Utf8Array> for CharBlockIteratorImpl {
         match self {
             Self::PlainFixedChar(it) => it.remaining_items(),
             Self::PlainVarchar(it) => it.remaining_items(),
+            Self::PlainVarcharNullable(it) => it.remaining_items(),
+            Self::Dictionary(it) => it.remaining_items(),
+            Self::DictionaryNullable(it) => it.remaining_items(),
             Self::Fake(it) => it.remaining_items(),
         }
     }
@@ -49,11 +65,29 @@ impl BlockIterator<Utf8Array> for CharBlockIteratorImpl {
 
 pub struct CharBlockIteratorFactory {
     char_width: Option<usize>,
+
+    /// Sorted dictionary of dictionary-encoded columns
+    dictionary: Option<Arc<[String]>>,
 }
 
 impl CharBlockIteratorFactory {
     pub fn new(char_width: Option<usize>) -> Self {
-        Self { char_width }
+        Self {
+            char_width,
+            dictionary: None,
+        }
+    }
+
+    /// Set the dictionary used to decode dictionary-encoded blocks.
+    pub fn with_dictionary(mut self, dictionary: Option<Arc<[String]>>) -> Self {
+        self.dictionary = dictionary;
+        self
+    }
+
+    fn dictionary(&self) -> Arc<[String]> {
+        self.dictionary
+            .clone()
+            .expect("dictionary not found for dictionary-encoded block")
     }
 }
 
@@ -79,6 +113,25 @@ impl BlockIteratorFactory<Utf8Array> for CharBlockIteratorFactory {
                 let it = PlainVarcharBlockIterator::new(block, index.row_count as usize);
                 CharBlockIteratorImpl::PlainVarchar(it)
             }
+            // nullable blocks without null values are encoded as plain blocks
+            (BlockType::PlainVarcharNullable, _) if index.no_nulls => {
+                let it = PlainVarcharBlockIterator::new(block, index.row_count as usize);
+                CharBlockIteratorImpl::PlainVarchar(it)
+            }
+            (BlockType::PlainVarcharNullable, _) => {
+                let it = PlainVarcharNullableBlockIterator::new(block, index.row_count as usize);
+                CharBlockIteratorImpl::PlainVarcharNullable(it)
+            }
+            (BlockType::Dictionary, _) => {
+                let it = PlainPrimitiveBlockIterator::new(block, index.row_count as usize);
+                let it = DictionaryBlockIterator::new(it, self.dictionary());
+                CharBlockIteratorImpl::Dictionary(it)
+            }
+            (BlockType::DictionaryNullable, _) => {
+                let it = PlainPrimitiveNullableBlockIterator::new(block, index.row_count as usize);
+                let it = DictionaryBlockIterator::new(it, self.dictionary());
+                CharBlockIteratorImpl::DictionaryNullable(it)
+            }
             _ => todo!(),
         };
         it.skip(start_pos - index.first_rowid as usize);
@@ -7,9 +7,9 @@ use super::char_column_builder::CharColumnBuilder;
 use super::primitive_column_builder::{
     DateColumnBuilder, DecimalColumnBuilder, F64ColumnBuilder, I32ColumnBuilder,
 };
-use super::{BoolColumnBuilder, ColumnBuilder};
+use super::{BoolColumnBuilder, ColumnBuilder, DictionaryColumnBuilder, ListColumnBuilder};
 use crate::array::ArrayImpl;
-use crate::storage::secondary::column::IntervalColumnBuilder;
+use crate::storage::secondary::column::{IntervalColumnBuilder, TimestampColumnBuilder};
 use crate::types::{DataType, DataTypeKind};
 
 /// [`ColumnBuilder`] of all types
@@ -18,9 +18,12 @@ pub enum ColumnBuilderImpl {
     Float64(F64ColumnBuilder),
     Bool(BoolColumnBuilder),
     Utf8(CharColumnBuilder),
+    Dictionary(DictionaryColumnBuilder),
     Decimal(DecimalColumnBuilder),
     Date(DateColumnBuilder),
+    Timestamp(TimestampColumnBuilder),
     Interval(IntervalColumnBuilder),
+    List(ListColumnBuilder),
 }
 
 impl ColumnBuilderImpl {
@@ -35,6 +38,12 @@ impl ColumnBuilderImpl {
             DataTypeKind::Float(_) | DataTypeKind::Double => {
                 Self::Float64(F64ColumnBuilder::new(datatype.is_nullable(), options))
             }
+            DataTypeKind::Char(_) | DataTypeKind::Varchar(_) if options.is_dictionary => {
+                Self::Dictionary(DictionaryColumnBuilder::new(
+                    datatype.is_nullable(),
+                    options,
+                ))
+            }
             DataTypeKind::Char(char_width) => Self::Utf8(CharColumnBuilder::new(
                 datatype.is_nullable(),
                 char_width,
@@ -54,9 +63,13 @@ impl ColumnBuilderImpl {
             DataTypeKind::Date => {
                 Self::Date(DateColumnBuilder::new(datatype.is_nullable(), options))
             }
+            DataTypeKind::Timestamp => {
+                Self::Timestamp(TimestampColumnBuilder::new(datatype.is_nullable(), options))
+            }
             DataTypeKind::Interval => {
                 Self::Interval(IntervalColumnBuilder::new(datatype.is_nullable(), options))
             }
+            DataTypeKind::Array(_) => Self::List(ListColumnBuilder::new(options)),
             other_datatype => todo!("column builder for {:?} is not implemented", other_datatype),
         }
     }
@@ -67,22 +80,36 @@ impl ColumnBuilderImpl {
             (Self::Bool(builder), ArrayImpl::Bool(array)) => builder.append(array),
             (Self::Float64(builder), ArrayImpl::Float64(array)) => builder.append(array),
             (Self::Utf8(builder), ArrayImpl::Utf8(array)) => builder.append(array),
+            (Self::Dictionary(builder), ArrayImpl::Utf8(array)) => builder.append(array),
             (Self::Decimal(builder), ArrayImpl::Decimal(array)) => builder.append(array),
             (Self::Date(builder), ArrayImpl::Date(array)) => builder.append(array),
+            (Self::Timestamp(builder), ArrayImpl::Timestamp(array)) => builder.append(array),
             (Self::Interval(builder), ArrayImpl::Interval(array)) => builder.append(array),
+            (Self::List(builder), ArrayImpl::List(array)) => builder.append(array),
             _ => todo!(),
         }
     }
 
+    /// Get the dictionary of the column, if the column is dictionary-encoded.
+    pub fn dictionary(&self) -> Option<Vec<String>> {
+        match self {
+            Self::Dictionary(builder) => Some(builder.dictionary()),
+            _ => None,
+        }
+    }
+
     pub fn finish(self) -> (Vec<BlockIndex>, Vec<u8>) {
         match self {
             Self::Int32(builder) => builder.finish(),
             Self::Bool(builder) => builder.finish(),
             Self::Float64(builder) => builder.finish(),
             Self::Utf8(builder) => builder.finish(),
+            Self::Dictionary(builder) => builder.finish(),
             Self::Decimal(builder) => builder.finish(),
             Self::Date(builder) => builder.finish(),
+            Self::Timestamp(builder) => builder.finish(),
             Self::Interval(builder) => builder.finish(),
+            Self::List(builder) => builder.finish(),
         }
     }
 }
@@ -4,12 +4,14 @@ use bitvec::prelude::BitVec;
 
 use super::{
     BoolColumnIterator, CharBlockIteratorFactory, CharColumnIterator, Column, ColumnIterator,
-    DecimalColumnIterator, F64ColumnIterator, I32ColumnIterator, PrimitiveBlockIteratorFactory,
-    StorageResult,
+    DecimalColumnIterator, DefaultColumnIterator, F64ColumnIterator, I32ColumnIterator,
+    ListBlockIteratorFactory, ListColumnIterator, PrimitiveBlockIteratorFactory, StorageResult,
 };
 use crate::array::{Array, ArrayImpl};
 use crate::catalog::ColumnCatalog;
-use crate::storage::secondary::column::{DateColumnIterator, IntervalColumnIterator};
+use crate::storage::secondary::column::{
+    DateColumnIterator, IntervalColumnIterator, TimestampColumnIterator,
+};
 use crate::types::DataTypeKind;
 
 /// [`ColumnIteratorImpl`] of all types
@@ -20,7 +22,11 @@ pub enum ColumnIteratorImpl {
     Char(CharColumnIterator),
     Decimal(DecimalColumnIterator),
     Date(DateColumnIterator),
+    Timestamp(TimestampColumnIterator),
     Interval(IntervalColumnIterator),
+    List(ListColumnIterator),
+    /// A column not stored in the rowset.
+    Default(DefaultColumnIterator),
 }
 
 impl ColumnIteratorImpl {
@@ -42,22 +48,11 @@ impl ColumnIteratorImpl {
                 F64ColumnIterator::new(column, start_pos, PrimitiveBlockIteratorFactory::new())
                     .await?,
             ),
-            DataTypeKind::Char(width) => Self::Char(
-                CharColumnIterator::new(
-                    column,
-                    start_pos,
-                    CharBlockIteratorFactory::new(width.map(|x| x as usize)),
-                )
-                .await?,
-            ),
-            DataTypeKind::Varchar(width) => Self::Char(
-                CharColumnIterator::new(
-                    column,
-                    start_pos,
-                    CharBlockIteratorFactory::new(width.map(|x| x as usize)),
-                )
-                .await?,
-            ),
+            DataTypeKind::Char(width) | DataTypeKind::Varchar(width) => {
+                let factory = CharBlockIteratorFactory::new(width.map(|x| x as usize))
+                    .with_dictionary(column.index().dictionary());
+                Self::Char(CharColumnIterator::new(column, start_pos, factory).await?)
+            }
             DataTypeKind::Decimal(_, _) => Self::Decimal(
                 DecimalColumnIterator::new(column, start_pos, PrimitiveBlockIteratorFactory::new())
                     .await?,
@@ -66,14 +61,22 @@ impl ColumnIteratorImpl {
                 DateColumnIterator::new(column, start_pos, PrimitiveBlockIteratorFactory::new())
                     .await?,
             ),
-            DataTypeKind::Interval => Self::Interval(
-                IntervalColumnIterator::new(
+            DataTypeKind::Timestamp => Self::Timestamp(
+                TimestampColumnIterator::new(
                     column,
                     start_pos,
                     PrimitiveBlockIteratorFactory::new(),
                 )
                 .await?,
             ),
+            DataTypeKind::Interval => {
+                let factory =
+                    PrimitiveBlockIteratorFactory::new().with_version(column.index().version());
+                Self::Interval(IntervalColumnIterator::new(column, start_pos, factory).await?)
+            }
+            DataTypeKind::Array(_) => Self::List(
+                ListColumnIterator::new(column, start_pos, ListBlockIteratorFactory).await?,
+            ),
             other_datatype => todo!(
                 "column iterator for {:?} is not implemented",
                 other_datatype
@@ -112,9 +115,16 @@ impl ColumnIteratorImpl {
             Self::Date(it) => {
                 Self::erase_concrete_type(it.next_batch(expected_size, filter_bitmap).await?)
             }
+            Self::Timestamp(it) => {
+                Self::erase_concrete_type(it.next_batch(expected_size, filter_bitmap).await?)
+            }
             Self::Interval(it) => {
                 Self::erase_concrete_type(it.next_batch(expected_size, filter_bitmap).await?)
             }
+            Self::List(it) => {
+                Self::erase_concrete_type(it.next_batch(expected_size, filter_bitmap).await?)
+            }
+            Self::Default(it) => it.next_batch(expected_size),
         };
         Ok(result)
     }
@@ -127,7 +137,10 @@ impl ColumnIteratorImpl {
             Self::Char(it) => it.fetch_hint(),
             Self::Decimal(it) => it.fetch_hint(),
             Self::Date(it) => it.fetch_hint(),
+            Self::Timestamp(it) => it.fetch_hint(),
             Self::Interval(it) => it.fetch_hint(),
+            Self::List(it) => it.fetch_hint(),
+            Self::Default(it) => it.fetch_hint(),
         }
     }
 
@@ -139,7 +152,10 @@ impl ColumnIteratorImpl {
             Self::Char(it) => it.fetch_current_row_id(),
             Self::Decimal(it) => it.fetch_current_row_id(),
             Self::Date(it) => it.fetch_current_row_id(),
+            Self::Timestamp(it) => it.fetch_current_row_id(),
             Self::Interval(it) => it.fetch_current_row_id(),
+            Self::List(it) => it.fetch_current_row_id(),
+            Self::Default(it) => it.fetch_current_row_id(),
         }
     }
 
@@ -151,7 +167,10 @@ impl ColumnIteratorImpl {
             Self::Char(it) => it.skip(cnt),
             Self::Decimal(it) => it.skip(cnt),
             Self::Date(it) => it.skip(cnt),
+            Self::Timestamp(it) => it.skip(cnt),
             Self::Interval(it) => it.skip(cnt),
+            Self::List(it) => it.skip(cnt),
+            Self::Default(it) => it.skip(cnt),
         }
     }
 }
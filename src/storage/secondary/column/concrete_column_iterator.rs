@@ -5,7 +5,7 @@ use std::cmp::min;
 use bitvec::prelude::BitVec;
 use futures::Future;
 use risinglight_proto::rowset::block_index::BlockType;
-use risinglight_proto::rowset::BlockIndex;
+use risinglight_proto::rowset::{BlockIndex, BlockStatistics};
 
 use super::super::{Block, BlockIterator};
 use super::{Column, ColumnIterator, ColumnSeekPosition};
@@ -77,6 +77,17 @@ impl<A: Array, F: BlockIteratorFactory<A>> ConcreteColumnIterator<A, F> {
         })
     }
 
+    /// Get the statistics of the block containing the next row, e.g. min and max values, which
+    /// can be used to skip the block. Returns an empty slice if the iterator has finished.
+    pub fn block_stats(&self) -> &[BlockStatistics] {
+        let index = self.column.index();
+        let block_index = index.index(index.block_of_row(self.current_row_id));
+        if self.finished || self.current_row_id >= block_index.first_rowid + block_index.row_count {
+            return &[];
+        }
+        &block_index.stats
+    }
+
     pub async fn next_batch_inner(
         &mut self,
         expected_size: Option<usize>,
@@ -85,6 +96,18 @@ impl<A: Array, F: BlockIteratorFactory<A>> ConcreteColumnIterator<A, F> {
             return Ok(None);
         }
 
+        // The current block is faked after skipping rows, so we need to fetch it before reading
+        if self.is_fake_iter {
+            self.is_fake_iter = false;
+            let (header, block) = self.column.get_block(self.current_block_id).await?;
+            self.block_iterator = self.factory.get_iterator_for(
+                header.block_type,
+                block,
+                self.column.index().index(self.current_block_id),
+                self.current_row_id as usize,
+            );
+        }
+
         let capacity = if let Some(expected_size) = expected_size {
             expected_size
         } else {
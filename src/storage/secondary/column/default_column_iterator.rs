@@ -0,0 +1,93 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use crate::array::{ArrayBuilderImpl, ArrayImpl};
+use crate::catalog::ColumnCatalog;
+use crate::types::{DataType, DataValue};
+
+/// Iterates on a column that is not stored in a rowset, i.e. a column added by `ALTER TABLE`
+/// after the rowset is written. All rows of the column have its default value.
+pub struct DefaultColumnIterator {
+    /// Default value of the column
+    value: DataValue,
+
+    /// Data type of the column
+    data_type: DataType,
+
+    /// Total count of rows in the rowset
+    row_count: u32,
+
+    /// Indicates the row id of the next batch
+    current_row_id: u32,
+}
+
+impl DefaultColumnIterator {
+    pub fn new(column_info: &ColumnCatalog, row_count: u32, start_pos: u32) -> Self {
+        Self {
+            value: column_info.default_value(),
+            data_type: column_info.datatype(),
+            row_count,
+            current_row_id: start_pos,
+        }
+    }
+
+    fn remaining_items(&self) -> usize {
+        self.row_count.saturating_sub(self.current_row_id) as usize
+    }
+
+    pub fn next_batch(&mut self, expected_size: Option<usize>) -> Option<(u32, ArrayImpl)> {
+        let remaining = self.remaining_items();
+        if remaining == 0 {
+            return None;
+        }
+        let size = expected_size.map_or(remaining, |size| size.min(remaining));
+        let mut builder = ArrayBuilderImpl::with_capacity(size, &self.data_type);
+        for _ in 0..size {
+            builder.push(&self.value);
+        }
+        let first_row_id = self.current_row_id;
+        self.current_row_id += size as u32;
+        Some((first_row_id, builder.finish()))
+    }
+
+    /// All remaining rows can be fetched without I/O.
+    pub fn fetch_hint(&self) -> usize {
+        self.remaining_items()
+    }
+
+    pub fn fetch_current_row_id(&self) -> u32 {
+        self.current_row_id
+    }
+
+    pub fn skip(&mut self, cnt: usize) {
+        self.current_row_id += cnt as u32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayToVecExt;
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    #[test]
+    fn test_default_column_iterator() {
+        let mut column_info =
+            ColumnCatalog::new(1, DataTypeKind::Int(None).not_null().to_column("v".into()));
+        column_info.set_default(DataValue::Int32(7));
+        let mut it = DefaultColumnIterator::new(&column_info, 10, 2);
+
+        let (row_id, array) = it.next_batch(Some(3)).unwrap();
+        assert_eq!(row_id, 2);
+        match array {
+            ArrayImpl::Int32(array) => assert_eq!(array.to_vec(), vec![Some(7); 3]),
+            _ => panic!("unexpected array type"),
+        }
+
+        it.skip(1);
+        assert_eq!(it.fetch_current_row_id(), 6);
+        assert_eq!(it.fetch_hint(), 4);
+        let (row_id, array) = it.next_batch(None).unwrap();
+        assert_eq!((row_id, array.len()), (6, 4));
+        assert!(it.next_batch(None).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashMap;
+use std::iter::Peekable;
+
+use itertools::Itertools;
+use risinglight_proto::rowset::block_index::BlockType;
+use risinglight_proto::rowset::block_statistics::BlockStatisticsType;
+use risinglight_proto::rowset::{BlockIndex, BlockStatistics};
+
+use super::super::{
+    BlockBuilder, BlockIndexBuilder, PlainPrimitiveBlockBuilder, PlainPrimitiveNullableBlockBuilder,
+};
+use super::{append_one_by_one, ColumnBuilder};
+use crate::array::{Array, I32Array, Utf8Array};
+use crate::storage::secondary::ColumnBuilderOptions;
+
+/// Column builder of dictionary-encoded char types.
+///
+/// The builder collects all distinct strings of the column into a dictionary. When finishing,
+/// the dictionary is sorted, and each row is encoded as the position of its value in the sorted
+/// dictionary. The dictionary itself should be stored in the index file of the column.
+pub struct DictionaryColumnBuilder {
+    options: ColumnBuilderOptions,
+
+    /// Indicates whether the current column accepts null elements
+    nullable: bool,
+
+    /// Map from distinct values to their codes in insertion order
+    dictionary: HashMap<String, u32>,
+
+    /// Codes in insertion order of all rows
+    codes: Vec<Option<u32>>,
+}
+
+impl DictionaryColumnBuilder {
+    pub fn new(nullable: bool, options: ColumnBuilderOptions) -> Self {
+        Self {
+            options,
+            nullable,
+            dictionary: HashMap::new(),
+            codes: vec![],
+        }
+    }
+
+    /// Get the sorted dictionary of the column.
+    pub fn dictionary(&self) -> Vec<String> {
+        self.dictionary.keys().cloned().sorted().collect()
+    }
+}
+
+/// Build a block of codes from the iterator, return the row count, statistics and the encoded
+/// block data.
+fn build_block<'a>(
+    iter: &mut Peekable<impl Iterator<Item = Option<&'a i32>>>,
+    mut builder: impl BlockBuilder<I32Array>,
+) -> (usize, Vec<BlockStatistics>, Vec<u8>) {
+    let (row_count, _) = append_one_by_one(iter, &mut builder);
+    // min and max of the codes are not meaningful for the column
+    let stats = builder
+        .get_statistics()
+        .into_iter()
+        .filter(|stat| {
+            !matches!(
+                stat.block_stat_type(),
+                BlockStatisticsType::MinValue | BlockStatisticsType::MaxValue
+            )
+        })
+        .collect();
+    (row_count, stats, builder.finish())
+}
+
+impl ColumnBuilder<Utf8Array> for DictionaryColumnBuilder {
+    fn append(&mut self, array: &Utf8Array) {
+        for item in array.iter() {
+            let code = item.map(|item| match self.dictionary.get(item) {
+                Some(code) => *code,
+                None => {
+                    let code = self.dictionary.len() as u32;
+                    self.dictionary.insert(item.to_string(), code);
+                    code
+                }
+            });
+            self.codes.push(code);
+        }
+    }
+
+    fn finish(self) -> (Vec<BlockIndex>, Vec<u8>) {
+        // map insertion-order codes to the positions in the sorted dictionary
+        let mut sorted_codes = vec![0; self.dictionary.len()];
+        for (position, (_, code)) in self
+            .dictionary
+            .iter()
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .enumerate()
+        {
+            sorted_codes[*code as usize] = position as i32;
+        }
+        let codes: I32Array = self
+            .codes
+            .iter()
+            .map(|code| code.map(|code| sorted_codes[code as usize]))
+            .collect();
+
+        let mut data = vec![];
+        let mut block_index_builder = BlockIndexBuilder::new(self.options.clone());
+        let mut iter = codes.iter().peekable();
+        let target_size = self.options.target_block_size - 16;
+        while iter.peek().is_some() {
+            let (block_type, (row_count, stats, mut block_data)) = if self.nullable {
+                (
+                    BlockType::DictionaryNullable,
+                    build_block(
+                        &mut iter,
+                        PlainPrimitiveNullableBlockBuilder::new(target_size),
+                    ),
+                )
+            } else {
+                (
+                    BlockType::Dictionary,
+                    build_block(&mut iter, PlainPrimitiveBlockBuilder::new(target_size)),
+                )
+            };
+            block_index_builder.add_rows(row_count);
+            block_index_builder.finish_block(block_type, &mut data, &mut block_data, stats);
+        }
+
+        (block_index_builder.into_index(), data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_dictionary_column_builder() {
+        let mut builder =
+            DictionaryColumnBuilder::new(true, ColumnBuilderOptions::default_for_block_test());
+        for _ in 0..100 {
+            builder.append(&Utf8Array::from_iter([
+                Some("b"),
+                None,
+                Some("a"),
+                Some("b"),
+            ]));
+        }
+        assert_eq!(builder.dictionary(), vec!["a".to_string(), "b".to_string()]);
+        let (index, _) = builder.finish();
+        assert_eq!(index.iter().map(|index| index.row_count).sum::<u32>(), 400);
+    }
+}
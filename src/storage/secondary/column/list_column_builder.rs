@@ -0,0 +1,95 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use risinglight_proto::rowset::block_index::BlockType;
+use risinglight_proto::rowset::BlockIndex;
+
+use super::super::{BlockBuilder, BlockIndexBuilder, PlainListBlockBuilder};
+use super::{append_one_by_one, ColumnBuilder};
+use crate::array::{Array, ListArray};
+use crate::storage::secondary::ColumnBuilderOptions;
+
+/// Column builder of list types.
+///
+/// All blocks are encoded as [`PlainListBlockBuilder`], which always has a null bitmap.
+pub struct ListColumnBuilder {
+    data: Vec<u8>,
+    options: ColumnBuilderOptions,
+
+    /// Current block builder
+    current_builder: Option<PlainListBlockBuilder>,
+
+    /// Block index builder
+    block_index_builder: BlockIndexBuilder,
+}
+
+impl ListColumnBuilder {
+    pub fn new(options: ColumnBuilderOptions) -> Self {
+        Self {
+            data: vec![],
+            block_index_builder: BlockIndexBuilder::new(options.clone()),
+            options,
+            current_builder: None,
+        }
+    }
+
+    fn finish_builder(&mut self) {
+        let builder = self.current_builder.take().unwrap();
+        let stats = builder.get_statistics();
+        let mut block_data = builder.finish();
+        self.block_index_builder.finish_block(
+            BlockType::PlainList,
+            &mut self.data,
+            &mut block_data,
+            stats,
+        );
+    }
+}
+
+impl ColumnBuilder<ListArray> for ListColumnBuilder {
+    fn append(&mut self, array: &ListArray) {
+        let mut iter = array.iter().peekable();
+
+        while iter.peek().is_some() {
+            let target_size = self.options.target_block_size - 16;
+            let builder = (self.current_builder)
+                .get_or_insert_with(|| PlainListBlockBuilder::new(target_size));
+            let (row_count, should_finish) = append_one_by_one(&mut iter, builder);
+
+            self.block_index_builder.add_rows(row_count);
+
+            // finish the current block
+            if should_finish {
+                self.finish_builder();
+            }
+        }
+    }
+
+    fn finish(mut self) -> (Vec<BlockIndex>, Vec<u8>) {
+        self.finish_builder();
+
+        (self.block_index_builder.into_index(), self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DataValue, List};
+
+    #[test]
+    fn test_list_column_builder() {
+        // an Int32 is encoded in 5 bytes, and a list of 3 takes 15 + 4 bytes and a bit of bitmap
+        let item_each_block = (128 - 16) / 20;
+        let list = List::from(vec![DataValue::Int32(1); 3]);
+        let mut builder = ListColumnBuilder::new(ColumnBuilderOptions::default_for_block_test());
+        for _ in 0..10 {
+            builder.append(&ListArray::from_iter(
+                [Some(&list)].iter().cycle().cloned().take(item_each_block),
+            ));
+        }
+        let (index, _) = builder.finish();
+        assert_eq!(index.len(), 10);
+        assert_eq!(index[3].first_rowid as usize, item_each_block * 3);
+        assert_eq!(index[3].row_count as usize, item_each_block);
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use risinglight_proto::rowset::block_index::BlockType;
+use risinglight_proto::rowset::BlockIndex;
+
+use super::super::{Block, BlockIterator};
+use super::{BlockIteratorFactory, ConcreteColumnIterator};
+use crate::array::{ListArray, ListArrayBuilder};
+use crate::storage::secondary::block::{FakeBlockIterator, PlainListBlockIterator};
+
+/// All supported block iterators for list types.
+pub enum ListBlockIteratorImpl {
+    PlainList(PlainListBlockIterator),
+    Fake(FakeBlockIterator<ListArray>),
+}
+
+impl BlockIterator<ListArray> for ListBlockIteratorImpl {
+    fn next_batch(
+        &mut self,
+        expected_size: Option<usize>,
+        builder: &mut ListArrayBuilder,
+    ) -> usize {
+        match self {
+            Self::PlainList(it) => it.next_batch(expected_size, builder),
+            Self::Fake(it) => it.next_batch(expected_size, builder),
+        }
+    }
+
+    fn skip(&mut self, cnt: usize) {
+        match self {
+            Self::PlainList(it) => it.skip(cnt),
+            Self::Fake(it) => it.skip(cnt),
+        }
+    }
+
+    fn remaining_items(&self) -> usize {
+        match self {
+            Self::PlainList(it) => it.remaining_items(),
+            Self::Fake(it) => it.remaining_items(),
+        }
+    }
+}
+
+pub struct ListBlockIteratorFactory;
+
+/// Column iterators on list types
+pub type ListColumnIterator = ConcreteColumnIterator<ListArray, ListBlockIteratorFactory>;
+
+impl BlockIteratorFactory<ListArray> for ListBlockIteratorFactory {
+    type BlockIteratorImpl = ListBlockIteratorImpl;
+
+    fn get_iterator_for(
+        &self,
+        block_type: BlockType,
+        block: Block,
+        index: &BlockIndex,
+        start_pos: usize,
+    ) -> Self::BlockIteratorImpl {
+        let mut it = match block_type {
+            BlockType::PlainList => ListBlockIteratorImpl::PlainList(PlainListBlockIterator::new(
+                block,
+                index.row_count as usize,
+            )),
+            _ => todo!(),
+        };
+        it.skip(start_pos - index.first_rowid as usize);
+        it
+    }
+
+    fn get_fake_iterator(&self, index: &BlockIndex, start_pos: usize) -> Self::BlockIteratorImpl {
+        let mut it = ListBlockIteratorImpl::Fake(FakeBlockIterator::new(index.row_count as usize));
+        it.skip(start_pos - index.first_rowid as usize);
+        it
+    }
+}
@@ -7,17 +7,21 @@ use risinglight_proto::rowset::BlockIndex;
 use rust_decimal::Decimal;
 
 use super::super::{
-    BlockBuilder, BlockIndexBuilder, ColumnBuilderOptions, PlainPrimitiveBlockBuilder,
-    PlainPrimitiveNullableBlockBuilder, PrimitiveFixedWidthEncode,
+    BitPackedBlockBuilder, BlockBuilder, BlockIndexBuilder, ColumnBuilderOptions,
+    PlainPrimitiveBlockBuilder, PlainPrimitiveNullableBlockBuilder, PrimitiveFixedWidthEncode,
+    RLEBlockBuilder,
 };
 use super::ColumnBuilder;
 use crate::array::Array;
-use crate::types::{Date, Interval};
+use crate::types::{Date, Interval, Timestamp};
 
 /// All supported block builders for primitive types.
 pub(super) enum BlockBuilderImpl<T: PrimitiveFixedWidthEncode> {
     Plain(PlainPrimitiveBlockBuilder<T>),
     PlainNullable(PlainPrimitiveNullableBlockBuilder<T>),
+    RunLength(RLEBlockBuilder<T::ArrayType, PlainPrimitiveBlockBuilder<T>>),
+    RunLengthNullable(RLEBlockBuilder<T::ArrayType, PlainPrimitiveNullableBlockBuilder<T>>),
+    BitPacked(BitPackedBlockBuilder<T>),
 }
 
 pub type I32ColumnBuilder = PrimitiveColumnBuilder<i32>;
@@ -25,6 +29,7 @@ pub type F64ColumnBuilder = PrimitiveColumnBuilder<f64>;
 pub type BoolColumnBuilder = PrimitiveColumnBuilder<bool>;
 pub type DecimalColumnBuilder = PrimitiveColumnBuilder<Decimal>;
 pub type DateColumnBuilder = PrimitiveColumnBuilder<Date>;
+pub type TimestampColumnBuilder = PrimitiveColumnBuilder<Timestamp>;
 pub type IntervalColumnBuilder = PrimitiveColumnBuilder<Interval>;
 
 /// Column builder of primitive types.
@@ -55,19 +60,57 @@ impl<T: PrimitiveFixedWidthEncode> PrimitiveColumnBuilder<T> {
     }
 
     fn finish_builder(&mut self) {
+        let mut no_nulls = false;
+        let mut bit_packed = None;
         let (block_type, stats, mut block_data) = match self.current_builder.take().unwrap() {
             BlockBuilderImpl::Plain(builder) => {
                 (BlockType::Plain, builder.get_statistics(), builder.finish())
             }
+            // omit the null bitmap if there is no null value in the block
+            BlockBuilderImpl::PlainNullable(builder) if !builder.has_null() => {
+                no_nulls = true;
+                (
+                    BlockType::PlainNullable,
+                    builder.get_statistics(),
+                    builder.finish_without_bitmap(),
+                )
+            }
             BlockBuilderImpl::PlainNullable(builder) => (
                 BlockType::PlainNullable,
                 builder.get_statistics(),
                 builder.finish(),
             ),
+            BlockBuilderImpl::RunLength(builder) => (
+                BlockType::RunLength,
+                builder.get_statistics(),
+                builder.finish(),
+            ),
+            BlockBuilderImpl::RunLengthNullable(builder) => (
+                BlockType::RunLengthNullable,
+                builder.get_statistics(),
+                builder.finish(),
+            ),
+            // the null bitmap is only written if there is any null value in the block
+            BlockBuilderImpl::BitPacked(builder) => {
+                bit_packed = Some((builder.frame_of_reference(), builder.bit_width()));
+                let block_type = if builder.has_null() {
+                    BlockType::BitPackedNullable
+                } else {
+                    BlockType::BitPacked
+                };
+                (block_type, builder.get_statistics(), builder.finish())
+            }
         };
 
         self.block_index_builder
             .finish_block(block_type, &mut self.data, &mut block_data, stats);
+        if no_nulls {
+            self.block_index_builder.mark_no_nulls();
+        }
+        if let Some((frame_of_reference, bit_width)) = bit_packed {
+            self.block_index_builder
+                .mark_bit_packed(frame_of_reference, bit_width);
+        }
     }
 }
 
@@ -106,22 +149,37 @@ impl<T: PrimitiveFixedWidthEncode> ColumnBuilder<T::ArrayType> for PrimitiveColu
 
         while iter.peek().is_some() {
             if self.current_builder.is_none() {
-                if self.nullable {
-                    self.current_builder = Some(BlockBuilderImpl::PlainNullable(
-                        PlainPrimitiveNullableBlockBuilder::new(
-                            self.options.target_block_size - 16,
-                        ),
-                    ));
-                } else {
-                    self.current_builder = Some(BlockBuilderImpl::Plain(
-                        PlainPrimitiveBlockBuilder::new(self.options.target_block_size - 16),
-                    ));
-                }
+                let target_size = self.options.target_block_size - 16;
+                let is_bit_packing = self.options.is_bit_packing && T::BIT_PACKABLE;
+                self.current_builder = Some(match (self.nullable, self.options.is_rle) {
+                    _ if is_bit_packing => {
+                        BlockBuilderImpl::BitPacked(BitPackedBlockBuilder::new(target_size))
+                    }
+                    (true, false) => BlockBuilderImpl::PlainNullable(
+                        PlainPrimitiveNullableBlockBuilder::new(target_size),
+                    ),
+                    (false, false) => {
+                        BlockBuilderImpl::Plain(PlainPrimitiveBlockBuilder::new(target_size))
+                    }
+                    (true, true) => BlockBuilderImpl::RunLengthNullable(RLEBlockBuilder::new(
+                        PlainPrimitiveNullableBlockBuilder::new(target_size),
+                        target_size,
+                    )),
+                    (false, true) => BlockBuilderImpl::RunLength(RLEBlockBuilder::new(
+                        PlainPrimitiveBlockBuilder::new(target_size),
+                        target_size,
+                    )),
+                });
             }
 
             let (row_count, should_finish) = match self.current_builder.as_mut().unwrap() {
                 BlockBuilderImpl::Plain(builder) => append_one_by_one(&mut iter, builder),
                 BlockBuilderImpl::PlainNullable(builder) => append_one_by_one(&mut iter, builder),
+                BlockBuilderImpl::RunLength(builder) => append_one_by_one(&mut iter, builder),
+                BlockBuilderImpl::RunLengthNullable(builder) => {
+                    append_one_by_one(&mut iter, builder)
+                }
+                BlockBuilderImpl::BitPacked(builder) => append_one_by_one(&mut iter, builder),
             };
 
             self.block_index_builder.add_rows(row_count);
@@ -219,4 +277,43 @@ mod tests {
         }
         builder.finish();
     }
+
+    #[test]
+    fn test_nullable_i32_column_builder_no_nulls() {
+        let build = |items: Vec<Option<i32>>| {
+            let mut builder =
+                I32ColumnBuilder::new(true, ColumnBuilderOptions::default_for_block_test());
+            builder.append(&I32Array::from_iter(items));
+            let (index, data) = builder.finish();
+            assert_eq!(index.len(), 1);
+            (index[0].no_nulls, data.len())
+        };
+        let (no_nulls, no_null_size) = build(vec![Some(1); 16]);
+        assert!(no_nulls);
+        let (no_nulls, mixed_size) = build([Some(1), None].into_iter().cycle().take(16).collect());
+        assert!(!no_nulls);
+        let (no_nulls, all_null_size) = build(vec![None; 16]);
+        assert!(!no_nulls);
+
+        // the bitmap of 16 items takes 2 bytes
+        assert_eq!(no_null_size + 2, mixed_size);
+        assert_eq!(all_null_size, mixed_size);
+    }
+
+    #[test]
+    fn test_rle_i32_column_builder() {
+        let build = |options: ColumnBuilderOptions| {
+            let mut builder = I32ColumnBuilder::new(false, options);
+            builder.append(&I32Array::from_iter(
+                [Some(1)].iter().cycle().cloned().take(1000),
+            ));
+            builder.finish()
+        };
+        let (plain_index, plain_data) = build(ColumnBuilderOptions::default_for_block_test());
+        let (rle_index, rle_data) = build(ColumnBuilderOptions::default_for_rle_block_test());
+        assert_eq!(rle_index.len(), 1);
+        assert_eq!(rle_index[0].row_count, 1000);
+        assert!(rle_data.len() < plain_data.len());
+        assert!(plain_index.len() > rle_index.len());
+    }
 }
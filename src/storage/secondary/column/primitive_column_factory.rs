@@ -7,18 +7,22 @@ use risinglight_proto::rowset::BlockIndex;
 use rust_decimal::Decimal;
 
 use super::super::{
-    Block, BlockIterator, PlainPrimitiveBlockIterator, PlainPrimitiveNullableBlockIterator,
-    PrimitiveFixedWidthEncode,
+    decode_rle_block, BitPackedBlockIterator, Block, BlockIterator, PlainPrimitiveBlockIterator,
+    PlainPrimitiveNullableBlockIterator, PrimitiveFixedWidthEncode, RLEBlockIterator,
+    SECONDARY_INDEX_VERSION,
 };
 use super::{BlockIteratorFactory, ConcreteColumnIterator};
 use crate::array::Array;
 use crate::storage::secondary::block::FakeBlockIterator;
-use crate::types::{Date, Interval};
+use crate::types::{Date, Interval, Timestamp};
 
 /// All supported block iterators for primitive types.
 pub enum PrimitiveBlockIteratorImpl<T: PrimitiveFixedWidthEncode> {
     Plain(PlainPrimitiveBlockIterator<T>),
     PlainNullable(PlainPrimitiveNullableBlockIterator<T>),
+    RunLength(RLEBlockIterator<T::ArrayType, PlainPrimitiveBlockIterator<T>>),
+    RunLengthNullable(RLEBlockIterator<T::ArrayType, PlainPrimitiveNullableBlockIterator<T>>),
+    BitPacked(BitPackedBlockIterator<T>),
     Fake(FakeBlockIterator<T::ArrayType>),
 }
 
@@ -31,6 +35,9 @@ impl<T: PrimitiveFixedWidthEncode> BlockIterator<T::ArrayType> for PrimitiveBloc
         match self {
             Self::Plain(it) => it.next_batch(expected_size, builder),
             Self::PlainNullable(it) => it.next_batch(expected_size, builder),
+            Self::RunLength(it) => it.next_batch(expected_size, builder),
+            Self::RunLengthNullable(it) => it.next_batch(expected_size, builder),
+            Self::BitPacked(it) => it.next_batch(expected_size, builder),
             Self::Fake(it) => it.next_batch(expected_size, builder),
         }
     }
@@ -39,6 +46,9 @@ impl<T: PrimitiveFixedWidthEncode> BlockIterator<T::ArrayType> for PrimitiveBloc
         match self {
             Self::Plain(it) => it.skip(cnt),
             Self::PlainNullable(it) => it.skip(cnt),
+            Self::RunLength(it) => it.skip(cnt),
+            Self::RunLengthNullable(it) => it.skip(cnt),
+            Self::BitPacked(it) => it.skip(cnt),
             Self::Fake(it) => it.skip(cnt),
         }
     }
@@ -47,21 +57,48 @@ impl<T: PrimitiveFixedWidthEncode> BlockIterator<T::ArrayType> for PrimitiveBloc
         match self {
             Self::Plain(it) => it.remaining_items(),
             Self::PlainNullable(it) => it.remaining_items(),
+            Self::RunLength(it) => it.remaining_items(),
+            Self::RunLengthNullable(it) => it.remaining_items(),
+            Self::BitPacked(it) => it.remaining_items(),
             Self::Fake(it) => it.remaining_items(),
         }
     }
 }
 
 pub struct PrimitiveBlockIteratorFactory<T: PrimitiveFixedWidthEncode> {
+    version: u32,
     _phantom: PhantomData<T>,
 }
 
 impl<T: PrimitiveFixedWidthEncode> PrimitiveBlockIteratorFactory<T> {
     pub fn new() -> Self {
         Self {
+            version: SECONDARY_INDEX_VERSION,
             _phantom: PhantomData,
         }
     }
+
+    /// Set the version of the index format the blocks were written with.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Re-encode the first `count` values of a block written with an older layout into the
+    /// current one. The rest of the block, such as the null bitmap, is kept as is.
+    fn upgrade_block(&self, block: Block, count: usize) -> Block {
+        let width = T::width_of_version(self.version);
+        if width == T::WIDTH {
+            return block;
+        }
+        let mut data = &block[..count * width];
+        let mut upgraded = Vec::with_capacity(block.len() + count * (T::WIDTH - width));
+        for _ in 0..count {
+            T::decode_of_version(&mut data, self.version).encode(&mut upgraded);
+        }
+        upgraded.extend_from_slice(&block[count * width..]);
+        upgraded.into()
+    }
 }
 
 /// Column iterators on primitive types
@@ -75,6 +112,7 @@ pub type F64ColumnIterator = PrimitiveColumnIterator<f64>;
 pub type BoolColumnIterator = PrimitiveColumnIterator<bool>;
 pub type DecimalColumnIterator = PrimitiveColumnIterator<Decimal>;
 pub type DateColumnIterator = PrimitiveColumnIterator<Date>;
+pub type TimestampColumnIterator = PrimitiveColumnIterator<Timestamp>;
 pub type IntervalColumnIterator = PrimitiveColumnIterator<Interval>;
 
 impl<T: PrimitiveFixedWidthEncode> BlockIteratorFactory<T::ArrayType>
@@ -91,13 +129,45 @@ impl<T: PrimitiveFixedWidthEncode> BlockIteratorFactory<T::ArrayType>
     ) -> Self::BlockIteratorImpl {
         let mut it = match block_type {
             BlockType::Plain => {
+                let block = self.upgrade_block(block, index.row_count as usize);
+                let it = PlainPrimitiveBlockIterator::new(block, index.row_count as usize);
+                PrimitiveBlockIteratorImpl::Plain(it)
+            }
+            // nullable blocks without null values are encoded as plain blocks
+            BlockType::PlainNullable if index.no_nulls => {
+                let block = self.upgrade_block(block, index.row_count as usize);
                 let it = PlainPrimitiveBlockIterator::new(block, index.row_count as usize);
                 PrimitiveBlockIteratorImpl::Plain(it)
             }
             BlockType::PlainNullable => {
+                let block = self.upgrade_block(block, index.row_count as usize);
                 let it = PlainPrimitiveNullableBlockIterator::new(block, index.row_count as usize);
                 PrimitiveBlockIteratorImpl::PlainNullable(it)
             }
+            BlockType::RunLength => {
+                let (rle_num, rle_data, block_data) = decode_rle_block(block);
+                let block_data = self.upgrade_block(block_data, rle_num);
+                let block_iter = PlainPrimitiveBlockIterator::new(block_data, rle_num);
+                let it = RLEBlockIterator::new(block_iter, rle_data, index.row_count as usize);
+                PrimitiveBlockIteratorImpl::RunLength(it)
+            }
+            BlockType::RunLengthNullable => {
+                let (rle_num, rle_data, block_data) = decode_rle_block(block);
+                let block_data = self.upgrade_block(block_data, rle_num);
+                let block_iter = PlainPrimitiveNullableBlockIterator::new(block_data, rle_num);
+                let it = RLEBlockIterator::new(block_iter, rle_data, index.row_count as usize);
+                PrimitiveBlockIteratorImpl::RunLengthNullable(it)
+            }
+            BlockType::BitPacked | BlockType::BitPackedNullable => {
+                let it = BitPackedBlockIterator::new(
+                    block,
+                    index.row_count as usize,
+                    index.frame_of_reference,
+                    index.bit_width,
+                    block_type == BlockType::BitPackedNullable,
+                );
+                PrimitiveBlockIteratorImpl::BitPacked(it)
+            }
             _ => todo!(),
         };
         it.skip(start_pos - index.first_rowid as usize);
@@ -128,7 +198,7 @@ mod tests {
     async fn test_scan_i32() {
         let tempdir = tempfile::tempdir().unwrap();
         let rowset = helper_build_rowset(&tempdir, false, 1000).await;
-        let column = rowset.column(0);
+        let column = rowset.column(0).await.unwrap();
         let mut scanner = PrimitiveColumnIterator::<i32>::new(
             column.clone(),
             0,
@@ -181,7 +251,7 @@ mod tests {
     async fn test_scan_i32_with_filter() {
         let tempdir = tempfile::tempdir().unwrap();
         let rowset = helper_build_rowset(&tempdir, false, 1020).await;
-        let column = rowset.column(0);
+        let column = rowset.column(0).await.unwrap();
 
         let none_array = vec![None; 1020];
         let value_array = [1, 2, 3]
@@ -323,7 +393,7 @@ mod tests {
         let len = 1020;
         let tempdir = tempfile::tempdir().unwrap();
         let rowset = helper_build_rowset(&tempdir, false, len).await;
-        let column = rowset.column(0);
+        let column = rowset.column(0).await.unwrap();
 
         skip_helper(column.clone(), len / 2, len).await;
         skip_helper(column.clone(), len, len).await;
@@ -8,10 +8,10 @@ use tokio::sync::oneshot::Receiver;
 use tracing::{info, warn};
 
 use super::{SecondaryStorage, SecondaryTable, Snapshot};
-use crate::catalog::find_sort_key_id;
+use crate::catalog::{find_sort_key_ids, TableRefId};
 use crate::storage::secondary::column::ColumnSeekPosition;
 use crate::storage::secondary::concat_iterator::ConcatIterator;
-use crate::storage::secondary::manifest::{AddRowSetEntry, DeleteRowsetEntry};
+use crate::storage::secondary::manifest::{AddRowSetEntry, DeleteDVEntry, DeleteRowsetEntry};
 use crate::storage::secondary::merge_iterator::MergeIterator;
 use crate::storage::secondary::rowset::{DiskRowset, RowsetBuilder};
 use crate::storage::secondary::version_manager::EpochOp;
@@ -24,33 +24,45 @@ pub struct Compactor {
     stop: Receiver<()>,
 }
 
-impl Compactor {
-    pub fn new(storage: Arc<SecondaryStorage>, stop: Receiver<()>) -> Self {
-        Self { storage, stop }
+impl SecondaryStorage {
+    /// Compact the small rowsets of a table into one. Returns whether any rowset is compacted.
+    ///
+    /// Rowsets are picked from the oldest one as long as their total on-disk size is within
+    /// `target_rowset_size`. The rows are merged in the order of primary key if any, and the
+    /// deleted rows are removed.
+    pub async fn compact_table(&self, table_id: TableRefId) -> StorageResult<bool> {
+        let table = self.get_table_inner(table_id)?;
+        let _guard = self.txn_mgr.lock_for_compaction(table.table_id()).await;
+        // pin the snapshot after locking, so that no deletion happens during compaction
+        let (epoch, snapshot) = self.version.pin();
+        let ret = self.compact_table_with_snapshot(&*snapshot, table).await;
+        self.version.unpin(epoch);
+        ret
     }
 
-    async fn compact_table(&self, snapshot: &Snapshot, table: SecondaryTable) -> StorageResult<()> {
+    async fn compact_table_with_snapshot(
+        &self,
+        snapshot: &Snapshot,
+        table: SecondaryTable,
+    ) -> StorageResult<bool> {
         let rowsets = if let Some(rowsets) = snapshot.get_rowsets_of(table.table_id()) {
             rowsets
         } else {
             // No rowset available for this table
-            return Ok(());
+            return Ok(false);
         };
         let mut selected_rowsets = vec![];
         let mut current_size = 0;
-        for rowset_id in rowsets {
-            let rowset = self
-                .storage
-                .version
-                .get_rowset(table.table_id(), *rowset_id);
-            let on_disk_size = rowset.on_disk_size();
-            if on_disk_size + current_size <= self.storage.options.target_rowset_size as u64 {
+        for rowset_id in rowsets.iter().sorted() {
+            let rowset = self.version.get_rowset(table.table_id(), *rowset_id);
+            let on_disk_size = rowset.on_disk_size().await?;
+            if on_disk_size + current_size <= self.options.target_rowset_size as u64 {
                 current_size += on_disk_size;
                 selected_rowsets.push(rowset);
             }
         }
         if selected_rowsets.len() <= 1 {
-            return Ok(());
+            return Ok(false);
         }
 
         // sort RowSets by id so that the output RowSet will have old rows in the front and new rows
@@ -62,19 +74,27 @@ impl Compactor {
             .collect_vec()
             .into();
         let mut iters = vec![];
+        let mut selected_dvs = vec![];
         for rowset in &selected_rowsets {
             let dvs = snapshot
                 .get_dvs_of(table.table_id(), rowset.rowset_id())
                 .map(|dvs| {
                     dvs.iter()
-                        .map(|dv_id| self.storage.version.get_dv(table.table_id(), *dv_id))
+                        .map(|dv_id| self.version.get_dv(table.table_id(), *dv_id))
                         .collect_vec()
                 })
                 .unwrap_or_default();
+            selected_dvs.extend(dvs.iter().map(|dv| (dv.rowset_id(), dv.dv_id())));
 
             iters.push(
                 rowset
-                    .iter(column_refs.clone(), dvs, ColumnSeekPosition::start(), None)
+                    .iter_with_columns(
+                        table.columns.clone(),
+                        column_refs.clone(),
+                        dvs,
+                        ColumnSeekPosition::start(),
+                        None,
+                    )
                     .await?,
             );
         }
@@ -82,8 +102,8 @@ impl Compactor {
         let rowset_id = table.generate_rowset_id();
         let directory = table.get_rowset_path(rowset_id);
 
-        let sort_key = find_sort_key_id(&table.columns);
-        let mut iter: SecondaryIterator = if let Some(sort_key) = sort_key {
+        let sort_key = find_sort_key_ids(&table.columns);
+        let mut iter: SecondaryIterator = if !sort_key.is_empty() {
             MergeIterator::new(
                 iters.into_iter().map(|iter| iter.into()).collect_vec(),
                 sort_key,
@@ -101,42 +121,57 @@ impl Compactor {
             ColumnBuilderOptions::from_storage_options(&table.storage_options),
         );
 
+        let mut row_cnt = 0;
         while let Some(batch) = iter.next_batch(None).await? {
-            builder.append(batch.to_data_chunk());
+            let chunk = batch.to_data_chunk();
+            row_cnt += chunk.cardinality();
+            builder.append(chunk);
         }
 
-        builder.finish_and_flush().await?;
+        let mut changes = vec![];
 
-        let rowset = DiskRowset::open(
-            directory,
-            table.columns.clone(),
-            self.storage.block_cache.clone(),
-            rowset_id,
-            self.storage.options.io_backend,
-        )
-        .await?;
-
-        // Add RowSets
-        let add_rowset_op = EpochOp::AddRowSet((
-            AddRowSetEntry {
-                rowset_id: rowset.rowset_id(),
-                table_id: table.table_ref_id,
-            },
-            rowset,
-        ));
+        // Add the new RowSet if any row is left
+        if row_cnt > 0 {
+            builder.finish_and_flush().await?;
+
+            let rowset = DiskRowset::open(
+                directory,
+                table.columns.clone(),
+                self.block_cache.clone(),
+                rowset_id,
+                self.options.io_backend,
+            )
+            .await?;
+            let rowset = Arc::new(rowset);
+            table.build_indexes(&rowset).await?;
 
-        let mut changes = vec![add_rowset_op];
+            changes.push(EpochOp::AddRowSet((
+                AddRowSetEntry {
+                    rowset_id: rowset.rowset_id(),
+                    table_id: table.table_ref_id,
+                },
+                rowset,
+            )));
+        } else {
+            tokio::fs::remove_dir_all(&directory).await?;
+        }
 
-        // Remove old RowSets
-        // and TODO: remove old DVs
+        // Remove old RowSets and their DVs
         changes.extend(selected_rowsets.iter().map(|x| {
             EpochOp::DeleteRowSet(DeleteRowsetEntry {
                 rowset_id: x.rowset_id(),
                 table_id: table.table_ref_id,
             })
         }));
+        changes.extend(selected_dvs.into_iter().map(|(rowset_id, dv_id)| {
+            EpochOp::DeleteDV(DeleteDVEntry {
+                table_id: table.table_ref_id,
+                dv_id,
+                rowset_id,
+            })
+        }));
 
-        self.storage.version.commit_changes(changes).await?;
+        self.version.commit_changes(changes).await?;
 
         info!(
             "compaction complete: {} -> {}",
@@ -144,31 +179,41 @@ impl Compactor {
             rowset_id
         );
 
-        Ok(())
+        Ok(true)
+    }
+}
+
+impl Compactor {
+    pub fn new(storage: Arc<SecondaryStorage>, stop: Receiver<()>) -> Self {
+        Self { storage, stop }
     }
 
     pub async fn run(mut self) -> StorageResult<()> {
         loop {
-            {
-                let tables = self.storage.tables.read().clone();
-                let (epoch, snapshot) = self.storage.version.pin();
-                for (_, table) in tables {
-                    if let Some(_guard) = self
+            let tables = self.storage.tables.read().clone();
+            for (_, table) in tables {
+                if let Some(_guard) = self
+                    .storage
+                    .txn_mgr
+                    .try_lock_for_compaction(table.table_id())
+                {
+                    // pin the snapshot after locking, so that no deletion happens during
+                    // compaction
+                    let (epoch, snapshot) = self.storage.version.pin();
+                    if let Err(err) = self
                         .storage
-                        .txn_mgr
-                        .try_lock_for_compaction(table.table_id())
+                        .compact_table_with_snapshot(&*snapshot, table)
+                        .await
                     {
-                        if let Err(err) = self.compact_table(&*snapshot, table).await {
-                            warn!("failed to compact: {:?}", err);
-                        }
+                        warn!("failed to compact: {:?}", err);
                     }
+                    self.storage.version.unpin(epoch);
                 }
-                match self.stop.try_recv() {
-                    Ok(_) => break,
-                    Err(tokio::sync::oneshot::error::TryRecvError::Closed) => break,
-                    _ => {}
-                }
-                self.storage.version.unpin(epoch);
+            }
+            match self.stop.try_recv() {
+                Ok(_) => break,
+                Err(tokio::sync::oneshot::error::TryRecvError::Closed) => break,
+                _ => {}
             }
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
@@ -176,3 +221,205 @@ impl Compactor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::{ArrayImpl, DataChunk};
+    use crate::catalog::ColumnCatalog;
+    use crate::storage::secondary::{SecondaryRowHandler, StorageOptions};
+    use crate::storage::{RowHandler, ScanOptions, Storage, Table, Transaction, TxnIterator};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    async fn scan_all(table: &SecondaryTable, with_row_handler: bool) -> Vec<DataChunk> {
+        let txn = table.read().await.unwrap();
+        let mut col_idx = vec![StorageColumnRef::Idx(0)];
+        if with_row_handler {
+            col_idx.push(StorageColumnRef::RowHandler);
+        }
+        let mut it = txn
+            .scan(None, None, &col_idx, ScanOptions::default())
+            .await
+            .unwrap();
+        let mut chunks = vec![];
+        while let Some(chunk) = it.next_batch(None).await.unwrap() {
+            chunks.push(chunk);
+        }
+        txn.abort().await.unwrap();
+        chunks
+    }
+
+    #[tokio::test]
+    async fn test_compact_table() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [
+            ColumnCatalog::new(
+                0,
+                DataTypeKind::Int(None)
+                    .not_null()
+                    .to_column_primary_key("v1".into()),
+            ),
+            ColumnCatalog::new(1, DataTypeKind::Int(None).not_null().to_column("v2".into())),
+        ];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table_id = TableRefId::new(0, 0, 0);
+        let table = storage.get_table(table_id).unwrap();
+
+        // 10 small rowsets with interleaving keys
+        for i in 0..10 {
+            let mut txn = table.write().await.unwrap();
+            txn.append(
+                [
+                    ArrayImpl::Int32((0..10).map(|j| j * 10 + i).collect()),
+                    ArrayImpl::Int32((0..10).map(|j| -(j * 10 + i)).collect()),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .await
+            .unwrap();
+            txn.commit().await.unwrap();
+        }
+
+        // delete the multiples of 7
+        let mut txn = table.update().await.unwrap();
+        for chunk in scan_all(&table, true).await {
+            for i in 0..chunk.cardinality() {
+                if let ArrayImpl::Int32(array) = chunk.array_at(0) {
+                    if array.get(i).unwrap() % 7 == 0 {
+                        let row_handler = SecondaryRowHandler::from_column(chunk.array_at(1), i);
+                        txn.delete(&row_handler).await.unwrap();
+                    }
+                }
+            }
+        }
+        txn.commit().await.unwrap();
+
+        assert!(storage.compact_table(table_id).await.unwrap());
+        let (epoch, snapshot) = storage.version.pin();
+        let rowsets = snapshot.get_rowsets_of(table.table_id()).unwrap();
+        assert_eq!(rowsets.len(), 1);
+        for rowset_id in rowsets {
+            assert!(snapshot.get_dvs_of(table.table_id(), *rowset_id).is_none());
+        }
+        storage.version.unpin(epoch);
+
+        // the rows are in the order of primary key, and the deleted rows are removed
+        let mut rows = vec![];
+        for chunk in scan_all(&table, false).await {
+            match chunk.array_at(0) {
+                ArrayImpl::Int32(array) => rows.extend(array.iter().map(|x| *x.unwrap())),
+                _ => unreachable!(),
+            }
+        }
+        assert_eq!(rows, (0..100).filter(|x| x % 7 != 0).collect_vec());
+
+        // nothing to compact
+        assert!(!storage.compact_table(table_id).await.unwrap());
+    }
+
+    fn list_rowsets(path: &std::path::Path) -> Vec<String> {
+        std::fs::read_dir(path)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir() && path.file_name().unwrap() != "dv")
+            .map(|path| path.file_name().unwrap().to_str().unwrap().to_string())
+            .sorted()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_scan_during_compaction() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table_id = TableRefId::new(0, 0, 0);
+        let table = storage.get_table(table_id).unwrap();
+        for i in 0..10 {
+            let mut txn = table.write().await.unwrap();
+            txn.append(
+                [ArrayImpl::Int32((i * 10..i * 10 + 10).collect())]
+                    .into_iter()
+                    .collect(),
+            )
+            .await
+            .unwrap();
+            txn.commit().await.unwrap();
+        }
+        let mut txn = table.update().await.unwrap();
+        for chunk in scan_all(&table, true).await {
+            for i in 0..chunk.cardinality() {
+                if let ArrayImpl::Int32(array) = chunk.array_at(0) {
+                    if array.get(i).unwrap() % 7 == 0 {
+                        let row_handler = SecondaryRowHandler::from_column(chunk.array_at(1), i);
+                        txn.delete(&row_handler).await.unwrap();
+                    }
+                }
+            }
+        }
+        txn.commit().await.unwrap();
+        let old_rowsets = list_rowsets(tempdir.path());
+        assert_eq!(old_rowsets.len(), 10);
+
+        // start a long-running scan
+        let txn = table.read().await.unwrap();
+        let mut it = txn
+            .scan(
+                None,
+                None,
+                &[StorageColumnRef::Idx(0)],
+                ScanOptions::default(),
+            )
+            .await
+            .unwrap();
+        let mut rows = vec![];
+        let mut collect = |chunk: DataChunk| match chunk.array_at(0) {
+            ArrayImpl::Int32(array) => rows.extend(array.iter().map(|x| *x.unwrap())),
+            _ => unreachable!(),
+        };
+        collect(it.next_batch(None).await.unwrap().unwrap());
+
+        // compaction replaces all rowsets, but the files are kept for the pinned snapshot
+        assert!(storage.compact_table(table_id).await.unwrap());
+        storage.version.do_vacuum().await.unwrap();
+        assert_eq!(list_rowsets(tempdir.path()).len(), 11);
+
+        while let Some(chunk) = it.next_batch(None).await.unwrap() {
+            collect(chunk);
+        }
+        rows.sort_unstable();
+        assert_eq!(rows, (0..100).filter(|x| x % 7 != 0).collect_vec());
+
+        // the iterator still holds the rowsets after the snapshot is unpinned
+        txn.abort().await.unwrap();
+        storage.version.do_vacuum().await.unwrap();
+        assert_eq!(list_rowsets(tempdir.path()).len(), 11);
+
+        drop(it);
+        storage.version.do_vacuum().await.unwrap();
+        let new_rowsets = list_rowsets(tempdir.path());
+        assert_eq!(new_rowsets.len(), 1);
+        assert!(!old_rowsets.contains(&new_rowsets[0]));
+        assert_eq!(
+            std::fs::read_dir(tempdir.path().join("dv"))
+                .unwrap()
+                .count(),
+            0
+        );
+        let chunks = scan_all(&table, false).await;
+        assert_eq!(chunks.iter().map(|c| c.cardinality()).sum::<usize>(), 85);
+    }
+}
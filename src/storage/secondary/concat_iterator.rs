@@ -1,7 +1,7 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use super::{RowSetIterator, SecondaryIteratorImpl};
-use crate::storage::{StorageChunk, StorageResult};
+use crate::storage::{ScanStats, StorageChunk, StorageResult};
 
 /// [`ConcatIterator`] concats data from `RowSet`s and yields data
 /// from them one by one. This iterator should only be used on
@@ -39,6 +39,14 @@ impl ConcatIterator {
             }
         }
     }
+
+    pub fn stats(&self) -> ScanStats {
+        let mut stats = ScanStats::default();
+        for iter in &self.iters {
+            stats += iter.stats();
+        }
+        stats
+    }
 }
 
 impl SecondaryIteratorImpl for ConcatIterator {}
@@ -10,6 +10,11 @@ use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
 
 use crate::storage::StorageResult;
 
+/// Records the deleted rows of a rowset. Rowsets are immutable once flushed, so deletes are
+/// stored in separate DV files and applied to the visibility bitmap during scan.
+///
+/// A DV file is a sequence of length-delimited [`DeleteRecord`]s, so more deletes can be appended
+/// to an existing file with [`DeleteVector::append_to`].
 pub struct DeleteVector {
     dv_id: u64,
     rowset_id: u32,
@@ -53,6 +58,18 @@ impl DeleteVector {
         Ok(())
     }
 
+    /// Append delete records to a DV file, creating the file if it doesn't exist.
+    pub async fn append_to(path: impl AsRef<Path>, deletes: &[DeleteRecord]) -> StorageResult<()> {
+        let mut file = tokio::fs::OpenOptions::default()
+            .append(true)
+            .create(true)
+            .open(path)
+            .await?;
+        Self::write_all(&mut file, deletes).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+
     pub fn new(dv_id: u64, rowset_id: u32, deletes: Vec<DeleteRecord>) -> Self {
         let mut deletes = deletes.into_iter().map(|x| x.row_id).collect_vec();
         deletes.sort_unstable();
@@ -73,6 +90,28 @@ impl DeleteVector {
         self.rowset_id
     }
 
+    /// Mark a row as deleted.
+    pub fn delete_row(&mut self, row_id: u32) {
+        if let Err(pos) = self.deletes.binary_search(&row_id) {
+            self.deletes.insert(pos, row_id);
+        }
+    }
+
+    /// Check if a row is deleted.
+    pub fn is_deleted(&self, row_id: u32) -> bool {
+        self.deletes.binary_search(&row_id).is_ok()
+    }
+
+    /// Get the number of deleted rows.
+    pub fn num_deletes(&self) -> usize {
+        self.deletes.len()
+    }
+
+    /// Get the sorted row ids of deleted rows.
+    pub fn deletes(&self) -> &[u32] {
+        &self.deletes
+    }
+
     /// Apply the current DV info to a visibility bitmap
     pub fn apply_to(&self, data: &mut BitVec, offset_row_id: u32) {
         let pos = self.deletes.partition_point(|x| *x < offset_row_id);
@@ -123,4 +162,44 @@ mod tests {
         dv.apply_to(&mut bv, 4);
         assert_eq!(bv, bitvec![1, 0, 1]);
     }
+
+    #[test]
+    fn test_dv_delete_row() {
+        let mut dv = DeleteVector::new(0, 0, vec![DeleteRecord { row_id: 5 }]);
+        dv.delete_row(2);
+        dv.delete_row(7);
+        dv.delete_row(5);
+        assert_eq!(dv.num_deletes(), 3);
+        assert!(dv.is_deleted(2));
+        assert!(!dv.is_deleted(3));
+
+        let mut bv = BitVec::new();
+        bv.resize(8, true);
+        dv.apply_to(&mut bv, 0);
+        assert_eq!(bv, bitvec![1, 1, 0, 1, 1, 0, 1, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_dv_append() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("0_0_0.dv");
+        DeleteVector::append_to(
+            &path,
+            &[DeleteRecord { row_id: 9 }, DeleteRecord { row_id: 1 }],
+        )
+        .await
+        .unwrap();
+        DeleteVector::append_to(
+            &path,
+            &[DeleteRecord { row_id: 4 }, DeleteRecord { row_id: 1 }],
+        )
+        .await
+        .unwrap();
+
+        let dv = DeleteVector::open(0, 0, &path).await.unwrap();
+        assert_eq!(dv.num_deletes(), 3);
+        for row_id in [1, 4, 9] {
+            assert!(dv.is_deleted(row_id));
+        }
+    }
 }
@@ -3,11 +3,13 @@
 use bytes::{Buf, BufMut};
 use rust_decimal::Decimal;
 
-use crate::array::{Array, BoolArray, DateArray, DecimalArray, F64Array, I32Array, IntervalArray};
-use crate::types::{Date, Interval};
+use crate::array::{
+    Array, BoolArray, DateArray, DecimalArray, F64Array, I32Array, IntervalArray, TimestampArray,
+};
+use crate::types::{Date, Interval, Timestamp};
 
 /// Encode a primitive value into fixed-width buffer
-pub trait PrimitiveFixedWidthEncode: Copy + Clone + 'static + Send + Sync {
+pub trait PrimitiveFixedWidthEncode: Copy + Clone + PartialOrd + 'static + Send + Sync {
     /// Width of each element
     const WIDTH: usize;
     const DEAFULT_VALUE: &'static Self;
@@ -19,6 +21,29 @@ pub trait PrimitiveFixedWidthEncode: Copy + Clone + 'static + Send + Sync {
 
     /// Decode a data from a bytes array.
     fn decode(buffer: &mut impl Buf) -> Self;
+
+    /// Width of each element in blocks written with index format `version`.
+    fn width_of_version(_version: u32) -> usize {
+        Self::WIDTH
+    }
+
+    /// Decode a data written with index format `version`.
+    fn decode_of_version(buffer: &mut impl Buf, _version: u32) -> Self {
+        Self::decode(buffer)
+    }
+
+    /// Whether the type is an integer that can be bit-packed.
+    const BIT_PACKABLE: bool = false;
+
+    /// Convert the value to `i64`. Only called if `BIT_PACKABLE` is true.
+    fn to_i64(&self) -> i64 {
+        unreachable!("type is not bit-packable")
+    }
+
+    /// Convert the value from `i64`. Only called if `BIT_PACKABLE` is true.
+    fn from_i64(_value: i64) -> Self {
+        unreachable!("type is not bit-packable")
+    }
 }
 
 impl PrimitiveFixedWidthEncode for bool {
@@ -38,6 +63,7 @@ impl PrimitiveFixedWidthEncode for bool {
 impl PrimitiveFixedWidthEncode for i32 {
     const WIDTH: usize = std::mem::size_of::<i32>();
     const DEAFULT_VALUE: &'static i32 = &0;
+    const BIT_PACKABLE: bool = true;
 
     type ArrayType = I32Array;
 
@@ -48,6 +74,14 @@ impl PrimitiveFixedWidthEncode for i32 {
     fn decode(buffer: &mut impl Buf) -> Self {
         buffer.get_i32_le()
     }
+
+    fn to_i64(&self) -> i64 {
+        *self as i64
+    }
+
+    fn from_i64(value: i64) -> Self {
+        value as i32
+    }
 }
 
 impl PrimitiveFixedWidthEncode for f64 {
@@ -95,8 +129,24 @@ impl PrimitiveFixedWidthEncode for Date {
     }
 }
 
+impl PrimitiveFixedWidthEncode for Timestamp {
+    const WIDTH: usize = std::mem::size_of::<i64>();
+    const DEAFULT_VALUE: &'static Self = &Timestamp::new(0);
+
+    type ArrayType = TimestampArray;
+
+    fn encode(&self, buffer: &mut impl BufMut) {
+        buffer.put_i64(self.get_inner());
+    }
+
+    fn decode(buffer: &mut impl Buf) -> Self {
+        Timestamp::new(buffer.get_i64())
+    }
+}
+
 impl PrimitiveFixedWidthEncode for Interval {
-    const WIDTH: usize = std::mem::size_of::<i32>() + std::mem::size_of::<i32>();
+    const WIDTH: usize =
+        std::mem::size_of::<i32>() + std::mem::size_of::<i32>() + std::mem::size_of::<i64>();
     const DEAFULT_VALUE: &'static Self = &Interval::from_days(0);
 
     type ArrayType = IntervalArray;
@@ -104,11 +154,66 @@ impl PrimitiveFixedWidthEncode for Interval {
     fn encode(&self, buffer: &mut impl BufMut) {
         buffer.put_i32(self.num_months());
         buffer.put_i32(self.days());
+        buffer.put_i64(self.usecs());
     }
 
     fn decode(buffer: &mut impl Buf) -> Self {
         let months = buffer.get_i32();
         let days = buffer.get_i32();
-        Interval::from_md(months, days)
+        let usecs = buffer.get_i64();
+        Interval::from_mdu(months, days, usecs)
+    }
+
+    /// Before version 3, intervals are encoded without the microsecond component.
+    fn width_of_version(version: u32) -> usize {
+        match version {
+            0..=2 => std::mem::size_of::<i32>() + std::mem::size_of::<i32>(),
+            _ => Self::WIDTH,
+        }
+    }
+
+    fn decode_of_version(buffer: &mut impl Buf, version: u32) -> Self {
+        match version {
+            0..=2 => {
+                let months = buffer.get_i32();
+                let days = buffer.get_i32();
+                Interval::from_md(months, days)
+            }
+            _ => Self::decode(buffer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_timestamp() {
+        let mut buffer = vec![];
+        for v in [0, -1, i64::MAX, 1_640_995_200_000_000] {
+            buffer.clear();
+            Timestamp::new(v).encode(&mut buffer);
+            assert_eq!(buffer.len(), Timestamp::WIDTH);
+            assert_eq!(Timestamp::decode(&mut &buffer[..]), Timestamp::new(v));
+        }
+    }
+
+    #[test]
+    fn test_decode_legacy_interval() {
+        let mut buffer = vec![];
+        buffer.put_i32(14);
+        buffer.put_i32(-3);
+        assert_eq!(buffer.len(), Interval::width_of_version(2));
+        assert_eq!(
+            Interval::decode_of_version(&mut &buffer[..], 2),
+            Interval::from_md(14, -3)
+        );
+
+        buffer.clear();
+        let interval = Interval::from_mdu(14, -3, 1_000_001);
+        interval.encode(&mut buffer);
+        assert_eq!(buffer.len(), Interval::width_of_version(3));
+        assert_eq!(Interval::decode_of_version(&mut &buffer[..], 3), interval);
     }
 }
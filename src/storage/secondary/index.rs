@@ -5,15 +5,18 @@ use std::sync::Arc;
 use bytes::Buf;
 use prost::Message;
 use risinglight_proto::rowset::block_checksum::ChecksumType;
-use risinglight_proto::rowset::BlockIndex;
+use risinglight_proto::rowset::{BlockIndex, ColumnIndexMeta};
 
-use super::{ColumnSeekPosition, SECONDARY_INDEX_MAGIC};
-use crate::storage::secondary::{verify_checksum, INDEX_FOOTER_SIZE};
+use super::{BloomFilter, ColumnSeekPosition, SECONDARY_INDEX_MAGIC, SECONDARY_INDEX_VERSION};
+use crate::storage::secondary::{verify_checksum, INDEX_FOOTER_SIZE, LEGACY_INDEX_FOOTER_SIZE};
 use crate::storage::{StorageResult, TracedStorageError};
 
 #[derive(Clone)]
 pub struct ColumnIndex {
     indexes: Arc<[BlockIndex]>,
+    dictionary: Option<Arc<[String]>>,
+    bloom_filter: Option<Arc<BloomFilter>>,
+    version: u32,
 }
 
 impl ColumnIndex {
@@ -29,15 +32,62 @@ impl ColumnIndex {
         self.indexes.len()
     }
 
+    /// Get the number of rows in the column.
+    pub fn row_count(&self) -> usize {
+        match self.indexes.last() {
+            Some(index) => (index.first_rowid + index.row_count) as usize,
+            None => 0,
+        }
+    }
+
+    /// Get the sorted dictionary if the column is dictionary-encoded.
+    pub fn dictionary(&self) -> Option<Arc<[String]>> {
+        self.dictionary.clone()
+    }
+
+    /// Get the version of the index format the column was written with.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Check if the column may contain the encoded value. A `false` return value means that
+    /// the value is definitely absent. If the column has no Bloom filter, always return `true`.
+    pub fn may_contain(&self, key: &[u8]) -> bool {
+        match &self.bloom_filter {
+            Some(bloom_filter) => bloom_filter.may_contain(key),
+            None => true,
+        }
+    }
+
     pub fn from_bytes(data: &[u8]) -> StorageResult<Self> {
         // TODO(chi): error handling
-        let mut index_data = &data[..data.len() - INDEX_FOOTER_SIZE];
-        let mut footer = &data[data.len() - INDEX_FOOTER_SIZE..];
+        // the legacy footer has the magic number where the current footer has the version
+        let is_legacy = data.len() >= LEGACY_INDEX_FOOTER_SIZE
+            && (&data[data.len() - LEGACY_INDEX_FOOTER_SIZE..]).get_u32() == SECONDARY_INDEX_MAGIC;
+        let footer_size = if is_legacy {
+            LEGACY_INDEX_FOOTER_SIZE
+        } else {
+            INDEX_FOOTER_SIZE
+        };
+        if data.len() < footer_size {
+            return Err(TracedStorageError::decode(
+                "failed to decode column index: truncated footer",
+            ));
+        }
+        let mut index_data = &data[..data.len() - footer_size];
+        let mut footer = &data[data.len() - footer_size..];
         if footer.get_u32() != SECONDARY_INDEX_MAGIC {
             return Err(TracedStorageError::decode(
                 "failed to decode column index: invalid magic",
             ));
         }
+        let version = if is_legacy { 0 } else { footer.get_u32() };
+        if version > SECONDARY_INDEX_VERSION {
+            return Err(TracedStorageError::decode(format!(
+                "failed to decode column index: unsupported version {}",
+                version
+            )));
+        }
         let length = footer.get_u64() as usize;
         let checksum_type = ChecksumType::from_i32(footer.get_i32())
             .ok_or_else(|| TracedStorageError::decode("invalid checksum type"))?;
@@ -50,8 +100,19 @@ impl ColumnIndex {
             indexes.push(index);
         }
 
+        let meta = if index_data.has_remaining() {
+            ColumnIndexMeta::decode_length_delimited(&mut index_data)?
+        } else {
+            ColumnIndexMeta::default()
+        };
+
         Ok(Self {
             indexes: indexes.into(),
+            dictionary: meta.dictionary.map(|dictionary| dictionary.values.into()),
+            bloom_filter: meta
+                .bloom_filter
+                .map(|bloom_filter| Arc::new(BloomFilter::from_proto(bloom_filter))),
+            version,
         })
     }
 
@@ -87,3 +148,35 @@ impl ColumnIndex {
         pp - 1
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::secondary::IndexBuilder;
+
+    #[test]
+    fn test_decode_legacy_footer() {
+        let mut builder = IndexBuilder::new(ChecksumType::Crc32, 0);
+        for first_rowid in [0, 10] {
+            builder.append(BlockIndex {
+                first_rowid,
+                row_count: 10,
+                ..Default::default()
+            });
+        }
+        let data = builder.finish();
+        let index = ColumnIndex::from_bytes(&data).unwrap();
+        assert_eq!(index.version(), SECONDARY_INDEX_VERSION);
+        assert_eq!(index.len(), 2);
+
+        // remove the version from the footer
+        let version_offset = data.len() - INDEX_FOOTER_SIZE + 4;
+        let mut legacy = data[..version_offset].to_vec();
+        legacy.extend_from_slice(&data[version_offset + 4..]);
+        assert_eq!(legacy.len(), data.len() - 4);
+        let index = ColumnIndex::from_bytes(&legacy).unwrap();
+        assert_eq!(index.version(), 0);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.block_of_row(15), 1);
+    }
+}
@@ -3,26 +3,37 @@
 use bytes::BufMut;
 use prost::Message;
 use risinglight_proto::rowset::block_checksum::ChecksumType;
-use risinglight_proto::rowset::BlockIndex;
+use risinglight_proto::rowset::{BlockIndex, ColumnDictionary, ColumnIndexMeta};
 
-use super::build_checksum;
+use super::{build_checksum, BloomFilter};
 
 pub const SECONDARY_INDEX_MAGIC: u32 = 0x2333;
-pub const INDEX_FOOTER_SIZE: usize = 4 + 8 + 4 + 8;
+/// Version of the index format. Version 1 adds min and max statistics to block indexes. Version 2
+/// omits the null bitmap of nullable blocks without null values, which is flagged by `no_nulls`.
+/// Version 3 widens intervals from 8 to 16 bytes to store the microsecond component. Version 4
+/// adds the frame of reference and bit width of bit-packed blocks.
+pub const SECONDARY_INDEX_VERSION: u32 = 4;
+pub const INDEX_FOOTER_SIZE: usize = 4 + 4 + 8 + 4 + 8;
+/// Size of the footer before version 1, which has no version field.
+pub const LEGACY_INDEX_FOOTER_SIZE: usize = 4 + 8 + 4 + 8;
 
 /// Builds index file for a column.
 ///
 /// Currently, Secondary uses a very simple index format. `.idx` file is
 /// simply a sequence of protubuf [`BlockIndex`] message. When a developer
 /// needs to read a column, they will need to read them to memory at once.
-/// The last 24 bytes of the index file is the checksum.
+/// If the column is dictionary-encoded or has a Bloom filter, a protobuf [`ColumnIndexMeta`]
+/// message follows the block indexes. The last 28 bytes of the index file is the footer, which
+/// contains the version of the index format and the checksum. Index files written before
+/// version 1 have a 24-byte footer without the version, and are read as version 0.
 ///
 /// ```plain
-/// | index | index | index | index | ... | magic number (4B) | block count (8B) | checksum type (4B) | checksum (8B) |
+/// | index | index | index | index | ... | meta (optional) | magic number (4B) | version (4B) | block count (8B) | checksum type (4B) | checksum (8B) |
 pub struct IndexBuilder {
     data: Vec<u8>,
     cnt: usize,
     checksum_type: ChecksumType,
+    meta: ColumnIndexMeta,
 }
 impl IndexBuilder {
     pub fn new(checksum_type: ChecksumType, _target_entries: usize) -> Self {
@@ -30,9 +41,20 @@ impl IndexBuilder {
             data: vec![],
             cnt: 0,
             checksum_type,
+            meta: ColumnIndexMeta::default(),
         }
     }
 
+    /// Set the sorted dictionary of a dictionary-encoded column.
+    pub fn set_dictionary(&mut self, values: Vec<String>) {
+        self.meta.dictionary = Some(ColumnDictionary { values });
+    }
+
+    /// Set the Bloom filter of all values in the column.
+    pub fn set_bloom_filter(&mut self, bloom_filter: &BloomFilter) {
+        self.meta.bloom_filter = Some(bloom_filter.to_proto());
+    }
+
     pub fn append(&mut self, index: BlockIndex) {
         self.cnt += 1;
         index.encode_length_delimited(&mut self.data).unwrap()
@@ -40,9 +62,13 @@ impl IndexBuilder {
 
     pub fn finish(self) -> Vec<u8> {
         let mut data = self.data;
+        if self.meta != ColumnIndexMeta::default() {
+            self.meta.encode_length_delimited(&mut data).unwrap();
+        }
         let checksum = build_checksum(self.checksum_type, data.as_slice());
 
         data.put_u32(SECONDARY_INDEX_MAGIC);
+        data.put_u32(SECONDARY_INDEX_VERSION);
 
         data.put_u64(self.cnt as u64);
 
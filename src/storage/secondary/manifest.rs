@@ -17,9 +17,13 @@ use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
 use tracing::warn;
 
 use super::version_manager::EpochOp;
-use super::{SecondaryStorage, SecondaryTable, StorageResult, TracedStorageError};
+use super::{
+    ColumnBuilderOptions, SecondaryStorage, SecondaryTable, SecondaryTransaction, StorageResult,
+    TracedStorageError,
+};
 use crate::catalog::{ColumnCatalog, TableRefId};
-use crate::types::{DatabaseId, SchemaId};
+use crate::storage::Transaction;
+use crate::types::{ColumnId, DatabaseId, SchemaId};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CreateTableEntry {
@@ -34,6 +38,23 @@ pub struct DropTableEntry {
     pub table_id: TableRefId,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AddColumnEntry {
+    pub table_id: TableRefId,
+    pub column: ColumnCatalog,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TruncateTableEntry {
+    pub table_id: TableRefId,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CreateIndexEntry {
+    pub table_id: TableRefId,
+    pub column_id: ColumnId,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AddRowSetEntry {
     pub table_id: TableRefId,
@@ -63,6 +84,9 @@ pub struct DeleteDVEntry {
 pub enum ManifestOperation {
     CreateTable(CreateTableEntry),
     DropTable(DropTableEntry),
+    AddColumn(AddColumnEntry),
+    TruncateTable(TruncateTableEntry),
+    CreateIndex(CreateIndexEntry),
     AddRowSet(AddRowSetEntry),
     DeleteRowSet(DeleteRowsetEntry),
     AddDV(AddDVEntry),
@@ -89,27 +113,43 @@ impl Manifest {
         Ok(Self { file })
     }
 
+    /// Read all committed operations in the manifest in order.
+    ///
+    /// A corrupted or truncated tail, e.g. left by a crash during appending, is skipped with a
+    /// warning, and truncated from the file so that later entries can be appended.
     pub async fn replay(&mut self) -> StorageResult<Vec<ManifestOperation>> {
-        let mut data = String::new();
+        let mut data = vec![];
         self.file.seek(SeekFrom::Start(0)).await?;
         let mut reader = BufReader::new(&mut self.file);
 
         // TODO: don't read all to memory
-        reader.read_to_string(&mut data).await?;
+        reader.read_to_end(&mut data).await?;
 
-        let stream = Deserializer::from_str(&data).into_iter::<ManifestOperation>();
+        let mut stream = Deserializer::from_slice(&data).into_iter::<ManifestOperation>();
 
         let mut ops = vec![];
         let mut buffered_ops = vec![];
         let mut begin = false;
-
-        for value in stream {
-            let value = value?;
+        // the end of the last committed transaction
+        let mut committed_offset = 0;
+
+        while let Some(value) = stream.next() {
+            let value = match value {
+                Ok(value) => value,
+                Err(err) => {
+                    warn!(
+                        "manifest: skip corrupted entries after offset {}: {}",
+                        committed_offset, err
+                    );
+                    break;
+                }
+            };
             match value {
                 ManifestOperation::Begin => begin = true,
                 ManifestOperation::End => {
                     ops.append(&mut buffered_ops);
                     begin = false;
+                    committed_offset = stream.byte_offset();
                 }
                 op => {
                     if begin {
@@ -125,6 +165,18 @@ impl Manifest {
             warn!("manifest: find uncommitted entries");
         }
 
+        if committed_offset < data.len() {
+            warn!(
+                "manifest: truncate {} bytes of incomplete entries",
+                data.len() - committed_offset
+            );
+            self.file.set_len(committed_offset as u64).await?;
+            self.file.sync_data().await?;
+        }
+        self.file
+            .seek(SeekFrom::Start(committed_offset as u64))
+            .await?;
+
         Ok(ops)
     }
 
@@ -249,4 +301,152 @@ impl SecondaryStorage {
 
         Ok(())
     }
+
+    pub(super) fn apply_add_column(&self, entry: &AddColumnEntry) -> StorageResult<()> {
+        let AddColumnEntry { table_id, column } = entry.clone();
+
+        let mut tables = self.tables.write();
+        let table = tables
+            .get(&table_id)
+            .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?;
+        let table_catalog = self
+            .catalog
+            .get_table(&table_id)
+            .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?;
+        table_catalog
+            .add_column(column.clone())
+            .map_err(|_| TracedStorageError::duplicated("column", column.name()))?;
+
+        // Transactions started before keep the old columns, and new ones see the added column.
+        let mut columns = table.columns.to_vec();
+        columns.push(column);
+        let indexed_columns = table.indexed_columns.clone();
+        let mut table = SecondaryTable::new(
+            self.options.clone(),
+            table_id,
+            &columns,
+            self.next_id.clone(),
+            self.version.clone(),
+            self.block_cache.clone(),
+            self.txn_mgr.clone(),
+        );
+        table.indexed_columns = indexed_columns;
+        tables.insert(table_id, table);
+
+        Ok(())
+    }
+
+    pub(super) async fn add_column_inner(
+        &self,
+        table_id: TableRefId,
+        column: &ColumnCatalog,
+    ) -> StorageResult<()> {
+        let entry = AddColumnEntry {
+            table_id,
+            column: column.clone(),
+        };
+
+        // persist to manifest first
+        self.version
+            .commit_changes(vec![EpochOp::AddColumn(entry.clone())])
+            .await?;
+
+        // then apply to catalog
+        self.apply_add_column(&entry)?;
+
+        Ok(())
+    }
+
+    pub(super) fn apply_truncate_table(&self, entry: &TruncateTableEntry) -> StorageResult<()> {
+        let TruncateTableEntry { table_id } = entry.clone();
+
+        let table_catalog = self
+            .catalog
+            .get_table(&table_id)
+            .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?;
+        table_catalog.clear_statistics();
+
+        Ok(())
+    }
+
+    pub(super) async fn truncate_table_inner(&self, table_id: TableRefId) -> StorageResult<()> {
+        let table = self.get_table_inner(table_id)?;
+        // no compaction or deletion should add files based on the removed rowsets
+        let _guard = self.txn_mgr.lock_for_truncation(table.table_id()).await;
+        let entry = TruncateTableEntry { table_id };
+
+        // persist to manifest first, the files are vacuumed when no snapshot needs them
+        self.version
+            .commit_changes(vec![EpochOp::TruncateTable(entry.clone())])
+            .await?;
+
+        // then apply to catalog
+        self.apply_truncate_table(&entry)?;
+
+        Ok(())
+    }
+
+    pub(super) fn apply_create_index(&self, entry: &CreateIndexEntry) -> StorageResult<()> {
+        let CreateIndexEntry {
+            table_id,
+            column_id,
+        } = entry.clone();
+
+        let mut tables = self.tables.write();
+        let table = tables
+            .get_mut(&table_id)
+            .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?;
+        let table_catalog = self
+            .catalog
+            .get_table(&table_id)
+            .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?;
+        table_catalog
+            .add_index(column_id)
+            .map_err(|_| TracedStorageError::duplicated("index", column_id))?;
+
+        // Rowsets flushed by transactions started before are not indexed, and are scanned with
+        // their filters instead.
+        table.indexed_columns.push(column_id);
+
+        Ok(())
+    }
+
+    pub(super) async fn create_index_inner(
+        &self,
+        table_id: TableRefId,
+        column_id: ColumnId,
+    ) -> StorageResult<()> {
+        if self
+            .get_table_inner(table_id)?
+            .indexed_columns
+            .contains(&column_id)
+        {
+            return Err(TracedStorageError::duplicated("index", column_id));
+        }
+        let entry = CreateIndexEntry {
+            table_id,
+            column_id,
+        };
+
+        // persist to manifest first
+        self.version
+            .commit_changes(vec![EpochOp::CreateIndex(entry.clone())])
+            .await?;
+
+        // then apply to catalog, so that new rowsets are indexed once written
+        self.apply_create_index(&entry)?;
+
+        // and build the index on the existing rowsets
+        let table = self.get_table_inner(table_id)?;
+        let storage_column_id = table.column_map[&column_id];
+        let txn = SecondaryTransaction::start(&table, true, false).await?;
+        let result = txn
+            .build_index(
+                storage_column_id,
+                ColumnBuilderOptions::from_storage_options(&self.options),
+            )
+            .await;
+        txn.abort().await?;
+        result
+    }
 }
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use super::{SecondaryIterator, SecondaryIteratorImpl};
 use crate::array::{ArrayBuilderImpl, ArrayImpl, ArrayImplBuilderPickExt, I32Array};
-use crate::storage::{PackedVec, StorageChunk, StorageResult};
+use crate::storage::{PackedVec, ScanStats, StorageChunk, StorageResult};
 
 /// [`MergeIterator`] merges data from multiple sorted `RowSet`s.
 /// This iterator should be used on sorted mode with overlapping sort keys.
@@ -20,8 +20,8 @@ pub struct MergeIterator {
     /// Indicates whether an iterator has reached its end
     has_finished: Vec<bool>,
 
-    /// The column id to be used as sort key
-    sort_key_idx: usize,
+    /// The column ids to be used as sort key, in the order of comparison
+    sort_key_idx: Vec<usize>,
 
     /// The min-heap of all pending data. Heap will be no larger than
     /// `len(iters)` size. Each element represents `(iter_id, batch_row_id)`.
@@ -34,7 +34,7 @@ pub struct MergeIterator {
 }
 
 impl MergeIterator {
-    pub fn new(iters: Vec<SecondaryIterator>, sort_key_idx: usize) -> Self {
+    pub fn new(iters: Vec<SecondaryIterator>, sort_key_idx: Vec<usize>) -> Self {
         Self {
             sort_key_idx,
             chunk_buffer: vec![None; iters.len()],
@@ -50,19 +50,19 @@ impl MergeIterator {
         (left_id, left_batch_row_id): &(usize, usize),
         (right_id, right_batch_row_id): &(usize, usize),
     ) -> std::cmp::Ordering {
-        let left_data = self.chunk_buffer[*left_id]
-            .as_ref()
-            .unwrap()
-            .array_at(self.sort_key_idx)
-            .get(*left_batch_row_id);
-        let right_data = self.chunk_buffer[*right_id]
-            .as_ref()
-            .unwrap()
-            .array_at(self.sort_key_idx)
-            .get(*right_batch_row_id);
-
-        // TODO: handle can-not-compare
-        left_data.partial_cmp(&right_data).unwrap()
+        let left_chunk = self.chunk_buffer[*left_id].as_ref().unwrap();
+        let right_chunk = self.chunk_buffer[*right_id].as_ref().unwrap();
+        for &idx in &self.sort_key_idx {
+            let left_data = left_chunk.array_at(idx).get(*left_batch_row_id);
+            let right_data = right_chunk.array_at(idx).get(*right_batch_row_id);
+
+            // TODO: handle can-not-compare
+            match left_data.partial_cmp(&right_data).unwrap() {
+                std::cmp::Ordering::Equal => continue,
+                ord => return ord,
+            }
+        }
+        std::cmp::Ordering::Equal
     }
 
     fn compare_in_heap(&self, left_idx: usize, right_idx: usize) -> std::cmp::Ordering {
@@ -297,6 +297,14 @@ impl MergeIterator {
 
         Ok(Some(StorageChunk::construct(None, arrays).unwrap()))
     }
+
+    pub fn stats(&self) -> ScanStats {
+        let mut stats = ScanStats::default();
+        for iter in &self.iters {
+            stats += iter.stats();
+        }
+        stats
+    }
 }
 
 impl SecondaryIteratorImpl for MergeIterator {}
@@ -328,7 +336,7 @@ mod tests {
                 I32Array::from_iter([4, 5, 6].map(Some)),
             ),
         ]);
-        let mut merge_iterator = MergeIterator::new(vec![iter1.into()], 0);
+        let mut merge_iterator = MergeIterator::new(vec![iter1.into()], vec![0]);
         let batch = merge_iterator.next_batch(Some(1)).await.unwrap().unwrap();
         let array: &I32Array = batch.array_at(0).as_ref().try_into().unwrap();
         assert_eq!(array.to_vec(), vec![Some(1)]);
@@ -356,7 +364,7 @@ mod tests {
                 I32Array::from_iter([7, 8, 9].map(Some)),
             ),
         ]);
-        let mut merge_iterator = MergeIterator::new(vec![iter1.into(), iter2.into()], 0);
+        let mut merge_iterator = MergeIterator::new(vec![iter1.into(), iter2.into()], vec![0]);
         let answers = vec![vec![1, 2, 3, 4, 4, 5], vec![6], vec![6], vec![7, 9]];
         for answer in answers {
             let batch = merge_iterator.next_batch(None).await.unwrap().unwrap();
@@ -13,6 +13,8 @@ mod transaction;
 pub use transaction::*;
 mod options;
 pub use options::*;
+mod block_cache;
+pub use block_cache::*;
 
 // internal modules and structures
 mod delete_vector;
@@ -32,6 +34,8 @@ mod index;
 use index::*;
 mod index_builder;
 use index_builder::*;
+mod bloom_filter;
+use bloom_filter::*;
 mod encode;
 use encode::*;
 mod compactor;
@@ -54,7 +58,6 @@ use std::future::Future;
 use std::sync::atomic::{AtomicU32, AtomicU64};
 use std::sync::Arc;
 
-use moka::future::Cache;
 use parking_lot::RwLock;
 use tokio::sync::oneshot::Sender;
 use tokio::sync::Mutex;
@@ -77,7 +80,7 @@ pub struct SecondaryStorage {
     options: Arc<StorageOptions>,
 
     /// Block cache of the storage engine
-    block_cache: Cache<BlockCacheKey, Block>,
+    block_cache: BlockCache,
 
     /// Next RowSet Id and DV Id of the current storage engine
     next_id: Arc<(AtomicU32, AtomicU64)>,
@@ -109,6 +112,16 @@ impl SecondaryStorage {
         &self.catalog
     }
 
+    /// Options of the storage engine.
+    pub fn options(&self) -> &StorageOptions {
+        &self.options
+    }
+
+    /// Block cache of the storage engine, which exposes hit and miss counters.
+    pub fn block_cache(&self) -> &BlockCache {
+        &self.block_cache
+    }
+
     pub async fn spawn_compactor(self: &Arc<Self>) {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let storage = self.clone();
@@ -154,6 +167,9 @@ impl SecondaryStorage {
 impl Storage for SecondaryStorage {
     type CreateTableResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
     type DropTableResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
+    type AddColumnResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
+    type TruncateTableResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
+    type CreateIndexResultFuture<'a> = impl Future<Output = StorageResult<()>> + 'a;
     type TransactionType = SecondaryTransaction;
     type TableType = SecondaryTable;
 
@@ -177,4 +193,24 @@ impl Storage for SecondaryStorage {
     fn drop_table(&self, table_id: TableRefId) -> Self::DropTableResultFuture<'_> {
         async move { self.drop_table_inner(table_id).await }
     }
+
+    fn add_column<'a>(
+        &'a self,
+        table_id: TableRefId,
+        column: &'a ColumnCatalog,
+    ) -> Self::AddColumnResultFuture<'a> {
+        async move { self.add_column_inner(table_id, column).await }
+    }
+
+    fn truncate_table(&self, table_id: TableRefId) -> Self::TruncateTableResultFuture<'_> {
+        async move { self.truncate_table_inner(table_id).await }
+    }
+
+    fn create_index(
+        &self,
+        table_id: TableRefId,
+        column_id: ColumnId,
+    ) -> Self::CreateIndexResultFuture<'_> {
+        async move { self.create_index_inner(table_id, column_id).await }
+    }
 }
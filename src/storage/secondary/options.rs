@@ -20,7 +20,7 @@ pub struct StorageOptions {
     /// Path of the storage engine
     pub path: PathBuf,
 
-    /// Number of cache entrires
+    /// Capacity (in bytes) of the block cache
     pub cache_size: usize,
 
     /// Target size (in bytes) of RowSets
@@ -29,18 +29,37 @@ pub struct StorageOptions {
     /// Target size (in bytes) of blocks
     pub target_block_size: usize,
 
+    /// Target number of rows of the chunks handed to rowset builders. Smaller chunks appended to
+    /// a transaction are buffered until the target is reached.
+    pub target_chunk_rows: usize,
+
     /// I/O Backend used by the storage engine
     pub io_backend: IOBackend,
 
     /// Checksum type used by columns
     pub checksum_type: ChecksumType,
+
+    /// Whether to use run-length encoding for primitive columns
+    pub is_rle: bool,
+
+    /// Whether to use bit-packed encoding for integer columns
+    pub is_bit_packing: bool,
+
+    /// Whether to use dictionary encoding for char columns
+    pub is_dictionary: bool,
+
+    /// Whether to build Bloom filters for columns
+    pub bloom_filter: bool,
+
+    /// Maximum number of tasks scanning the rowsets of a table in parallel
+    pub scan_parallelism: usize,
 }
 
 impl StorageOptions {
     pub fn default_for_cli() -> Self {
         Self {
             path: PathBuf::new().join("risinglight.secondary.db"),
-            cache_size: 1024,
+            cache_size: 256 * (1 << 20),         // 256MB
             target_rowset_size: 256 * (1 << 20), // 256MB
             target_block_size: 64 * (1 << 10),   // 64KB
             io_backend: if cfg!(target_os = "windows") {
@@ -50,17 +69,29 @@ impl StorageOptions {
                 IOBackend::PositionedRead
             },
             checksum_type: ChecksumType::Crc32,
+            target_chunk_rows: 64 * 1024,
+            is_rle: false,
+            is_bit_packing: false,
+            is_dictionary: false,
+            bloom_filter: false,
+            scan_parallelism: std::thread::available_parallelism().map_or(1, |n| n.get()),
         }
     }
 
     pub fn default_for_test(path: PathBuf) -> Self {
         Self {
             path,
-            cache_size: 1024,
+            cache_size: 16 * (1 << 20),        // 16MB
             target_rowset_size: 1 << 20,       // 1MB
             target_block_size: 16 * (1 << 10), // 16KB
             io_backend: IOBackend::NormalRead,
             checksum_type: ChecksumType::None,
+            target_chunk_rows: 64 * 1024,
+            is_rle: false,
+            is_bit_packing: false,
+            is_dictionary: false,
+            bloom_filter: false,
+            scan_parallelism: 4,
         }
     }
 }
@@ -71,15 +102,35 @@ pub struct ColumnBuilderOptions {
     /// Target size (in bytes) of blocks
     pub target_block_size: usize,
 
+    /// Target size (in bytes) of RowSets, estimated from the appended chunks
+    pub target_rowset_size: usize,
+
     /// Checksum type used by columns
     pub checksum_type: ChecksumType,
+
+    /// Whether to use run-length encoding for primitive columns
+    pub is_rle: bool,
+
+    /// Whether to use bit-packed encoding for integer columns
+    pub is_bit_packing: bool,
+
+    /// Whether to use dictionary encoding for char columns
+    pub is_dictionary: bool,
+
+    /// Whether to build Bloom filters for columns
+    pub bloom_filter: bool,
 }
 
 impl ColumnBuilderOptions {
     pub fn from_storage_options(options: &StorageOptions) -> Self {
         Self {
             target_block_size: options.target_block_size,
+            target_rowset_size: options.target_rowset_size,
             checksum_type: options.checksum_type,
+            is_rle: options.is_rle,
+            is_bit_packing: options.is_bit_packing,
+            is_dictionary: options.is_dictionary,
+            bloom_filter: options.bloom_filter,
         }
     }
 
@@ -87,7 +138,12 @@ impl ColumnBuilderOptions {
     pub fn default_for_test() -> Self {
         Self {
             target_block_size: 4096,
+            target_rowset_size: 1 << 20,
             checksum_type: ChecksumType::Crc32,
+            is_rle: false,
+            is_bit_packing: false,
+            is_dictionary: false,
+            bloom_filter: false,
         }
     }
 
@@ -95,7 +151,25 @@ impl ColumnBuilderOptions {
     pub fn default_for_block_test() -> Self {
         Self {
             target_block_size: 128,
+            target_rowset_size: 1 << 20,
+            checksum_type: ChecksumType::None,
+            is_rle: false,
+            is_bit_packing: false,
+            is_dictionary: false,
+            bloom_filter: false,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn default_for_rle_block_test() -> Self {
+        Self {
+            target_block_size: 128,
+            target_rowset_size: 1 << 20,
             checksum_type: ChecksumType::None,
+            is_rle: true,
+            is_bit_packing: false,
+            is_dictionary: false,
+            bloom_filter: false,
         }
     }
 }
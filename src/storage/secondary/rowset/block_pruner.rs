@@ -0,0 +1,209 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::cmp::Ordering;
+
+use risinglight_proto::rowset::block_statistics::BlockStatisticsType;
+use risinglight_proto::rowset::BlockIndex;
+use rust_decimal::Decimal;
+use sqlparser::ast::BinaryOperator;
+
+use super::DiskRowset;
+use crate::binder::BoundExpr;
+use crate::storage::secondary::PrimitiveFixedWidthEncode;
+use crate::storage::{StorageColumnRef, StorageResult};
+use crate::types::{DataValue, Date, Timestamp};
+
+/// Finds the rows of a [`DiskRowset`] that can't match the filter of a scan.
+///
+/// Each condition of the filter in the form of `column <op> constant` is checked against the
+/// min and max statistics of each block of the column, and the Bloom filter of the column for
+/// equality. A block is skipped if any condition can't be satisfied by its rows.
+#[derive(Default)]
+pub struct BlockPruner {
+    /// The sorted and non-overlapping row ranges `[begin, end)` to be skipped.
+    skipped_ranges: Vec<(u32, u32)>,
+}
+
+impl BlockPruner {
+    pub async fn new(
+        rowset: &DiskRowset,
+        column_refs: &[StorageColumnRef],
+        expr: &BoundExpr,
+    ) -> StorageResult<Self> {
+        let mut ranges = vec![];
+        let mut conds = vec![];
+        conjunctions(expr, &mut conds);
+        for cond in conds {
+            let (index, op, value) = match comparison(cond) {
+                Some(comparison) => comparison,
+                None => continue,
+            };
+            let column_idx = match column_refs[index] {
+                // columns not stored in the rowset have no statistics
+                StorageColumnRef::Idx(idx) if idx as usize >= rowset.column_count() => continue,
+                StorageColumnRef::Idx(idx) => idx as usize,
+                StorageColumnRef::RowHandler => continue,
+            };
+            let physical_kind = rowset.column_info(column_idx).datatype().physical_kind();
+            if value.data_type().map(|ty| ty.physical_kind()) != Some(physical_kind) {
+                continue;
+            }
+            let column = rowset.column(column_idx).await?;
+            if op == BinaryOperator::Eq {
+                if let Some(key) = bloom_filter_key(value) {
+                    if !column.index().may_contain(&key) {
+                        ranges.push((0, column.index().row_count() as u32));
+                        continue;
+                    }
+                }
+            }
+            for block_index in column.index().indexes() {
+                if let Some((min, max)) = min_max_of_block(block_index, value) {
+                    if !may_match(&op, &min, &max, value) {
+                        let begin = block_index.first_rowid;
+                        ranges.push((begin, begin + block_index.row_count));
+                    }
+                }
+            }
+        }
+
+        let mut pruner = Self::default();
+        pruner.skip_ranges(ranges);
+        Ok(pruner)
+    }
+
+    /// Skip the rows in the ranges `[begin, end)` as well.
+    pub fn skip_ranges(&mut self, ranges: impl IntoIterator<Item = (u32, u32)>) {
+        let mut all_ranges = std::mem::take(&mut self.skipped_ranges);
+        all_ranges.extend(ranges);
+        all_ranges.sort_unstable();
+        for (begin, end) in all_ranges {
+            match self.skipped_ranges.last_mut() {
+                Some(last) if begin <= last.1 => last.1 = last.1.max(end),
+                _ => self.skipped_ranges.push((begin, end)),
+            }
+        }
+    }
+
+    /// If the row is skipped, get the end of the skipped range containing it.
+    pub fn skip_to(&self, row_id: u32) -> Option<u32> {
+        let pp = self
+            .skipped_ranges
+            .partition_point(|(begin, _)| *begin <= row_id);
+        if pp == 0 {
+            return None;
+        }
+        let (_, end) = self.skipped_ranges[pp - 1];
+        if row_id < end {
+            Some(end)
+        } else {
+            None
+        }
+    }
+
+    /// Get the beginning of the next skipped range after the row.
+    pub fn next_skipped(&self, row_id: u32) -> Option<u32> {
+        let pp = self
+            .skipped_ranges
+            .partition_point(|(begin, _)| *begin <= row_id);
+        self.skipped_ranges.get(pp).map(|(begin, _)| *begin)
+    }
+}
+
+pub(super) fn conjunctions<'a>(expr: &'a BoundExpr, conds: &mut Vec<&'a BoundExpr>) {
+    match expr {
+        BoundExpr::BinaryOp(binary_op) if binary_op.op == BinaryOperator::And => {
+            conjunctions(&binary_op.left_expr, conds);
+            conjunctions(&binary_op.right_expr, conds);
+        }
+        _ => conds.push(expr),
+    }
+}
+
+/// Match a condition in the form of `column <op> constant` or `constant <op> column`, and return
+/// it as `column <op> constant`.
+pub(super) fn comparison(cond: &BoundExpr) -> Option<(usize, BinaryOperator, &DataValue)> {
+    use BinaryOperator::*;
+
+    let binary_op = match cond {
+        BoundExpr::BinaryOp(binary_op) => binary_op,
+        _ => return None,
+    };
+    if !matches!(binary_op.op, Eq | Lt | LtEq | Gt | GtEq) {
+        return None;
+    }
+    match (&*binary_op.left_expr, &*binary_op.right_expr) {
+        (BoundExpr::InputRef(input_ref), BoundExpr::Constant(value)) => {
+            Some((input_ref.index, binary_op.op.clone(), value))
+        }
+        (BoundExpr::Constant(value), BoundExpr::InputRef(input_ref)) => {
+            let op = match binary_op.op {
+                Lt => Gt,
+                LtEq => GtEq,
+                Gt => Lt,
+                GtEq => LtEq,
+                _ => Eq,
+            };
+            Some((input_ref.index, op, value))
+        }
+        _ => None,
+    }
+}
+
+/// Encode the value in the same way as the keys of [`BloomFilter`](super::super::BloomFilter).
+/// Floats and decimals are not supported, as equal values may have different encodings.
+fn bloom_filter_key(value: &DataValue) -> Option<Vec<u8>> {
+    let mut key = vec![];
+    match value {
+        DataValue::Bool(v) => v.encode(&mut key),
+        DataValue::Int32(v) => v.encode(&mut key),
+        DataValue::Int64(v) => key.extend_from_slice(&v.to_le_bytes()),
+        DataValue::String(v) => key.extend_from_slice(v.as_bytes()),
+        DataValue::Date(v) => v.encode(&mut key),
+        DataValue::Timestamp(v) => v.encode(&mut key),
+        _ => return None,
+    }
+    Some(key)
+}
+
+/// Decode the min and max statistics of a block into values of the same type as `value`.
+fn min_max_of_block(index: &BlockIndex, value: &DataValue) -> Option<(DataValue, DataValue)> {
+    let decode = |ty: BlockStatisticsType| {
+        let stat = index
+            .stats
+            .iter()
+            .find(|stat| stat.block_stat_type() == ty)?;
+        let mut body = &stat.body[..];
+        Some(match value {
+            DataValue::Bool(_) => DataValue::Bool(bool::decode(&mut body)),
+            DataValue::Int32(_) => DataValue::Int32(i32::decode(&mut body)),
+            DataValue::Float64(_) => DataValue::Float64(f64::decode(&mut body)),
+            DataValue::Decimal(_) => DataValue::Decimal(Decimal::decode(&mut body)),
+            DataValue::Date(_) => DataValue::Date(Date::decode(&mut body)),
+            DataValue::Timestamp(_) => DataValue::Timestamp(Timestamp::decode(&mut body)),
+            DataValue::String(_) => DataValue::String(String::from_utf8(body.to_vec()).ok()?),
+            _ => return None,
+        })
+    };
+    Some((
+        decode(BlockStatisticsType::MinValue)?,
+        decode(BlockStatisticsType::MaxValue)?,
+    ))
+}
+
+/// Check whether any value in `[min, max]` may satisfy `value <op> constant`. Incomparable values
+/// such as NaN may always match.
+fn may_match(op: &BinaryOperator, min: &DataValue, max: &DataValue, value: &DataValue) -> bool {
+    use Ordering::*;
+    match op {
+        BinaryOperator::Eq => {
+            !matches!(min.partial_cmp(value), Some(Greater))
+                && !matches!(max.partial_cmp(value), Some(Less))
+        }
+        BinaryOperator::Lt => !matches!(min.partial_cmp(value), Some(Greater | Equal)),
+        BinaryOperator::LtEq => !matches!(min.partial_cmp(value), Some(Greater)),
+        BinaryOperator::Gt => !matches!(max.partial_cmp(value), Some(Less | Equal)),
+        BinaryOperator::GtEq => !matches!(max.partial_cmp(value), Some(Less)),
+        _ => true,
+    }
+}
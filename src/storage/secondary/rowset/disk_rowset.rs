@@ -1,15 +1,18 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-use itertools::Itertools;
-use moka::future::Cache;
-use tokio::fs::OpenOptions;
+use tokio::fs::{self, OpenOptions};
 use tokio::io::AsyncReadExt;
+use tokio::sync::OnceCell;
 
-use super::super::{Block, BlockCacheKey, Column, ColumnIndex, ColumnSeekPosition, IOBackend};
-use super::{path_of_data_column, path_of_index_column, RowSetIterator};
+use super::super::{BlockCache, BlockCacheKey, Column, ColumnIndex, ColumnSeekPosition, IOBackend};
+use super::{
+    index_columns, path_of_data_column, path_of_index_column, path_of_secondary_index,
+    RowSetIterator, SecondaryIndex,
+};
 use crate::binder::BoundExpr;
 use crate::catalog::ColumnCatalog;
 use crate::storage::secondary::column::ColumnReadableFile;
@@ -19,74 +22,155 @@ use crate::storage::{StorageColumnRef, StorageResult};
 /// Represents a column in Secondary.
 ///
 /// [`DiskRowset`] contains all necessary information, e.g. column info, rowset location.
+///
+/// Columns are opened on first access, so that the files of columns never used by queries are
+/// not opened.
 pub struct DiskRowset {
+    directory: PathBuf,
     column_infos: Arc<[ColumnCatalog]>,
-    columns: Vec<Column>,
+    columns: Vec<OnceCell<Column>>,
+    block_cache: BlockCache,
     rowset_id: u32,
+    io_backend: IOBackend,
+    /// The secondary indexes opened, by the storage column id of the indexed column.
+    secondary_indexes: Mutex<HashMap<usize, Arc<SecondaryIndex>>>,
+    /// If the rowset is a secondary index of another rowset, the column id of the indexed column.
+    indexed_column: Option<u32>,
 }
 
 impl DiskRowset {
     pub async fn open(
         directory: PathBuf,
         column_infos: Arc<[ColumnCatalog]>,
-        block_cache: Cache<BlockCacheKey, Block>,
+        block_cache: BlockCache,
         rowset_id: u32,
         io_backend: IOBackend,
     ) -> StorageResult<Self> {
-        let mut columns = vec![];
-
-        for (id, column_info) in column_infos.iter().enumerate() {
-            let file = OpenOptions::default()
-                .read(true)
-                .write(false)
-                .open(path_of_data_column(&directory, column_info))
-                .await?;
-
-            let mut index = OpenOptions::default()
-                .read(true)
-                .write(false)
-                .open(path_of_index_column(&directory, column_info))
-                .await?;
-
-            // TODO(chi): add an index cache later
-            let mut index_content = vec![];
-            index.read_to_end(&mut index_content).await?;
-
-            let column = Column::new(
-                ColumnIndex::from_bytes(&index_content)?,
-                match io_backend {
-                    IOBackend::NormalRead => {
-                        ColumnReadableFile::NormalRead(Arc::new(Mutex::new(file.into_std().await)))
-                    }
-                    IOBackend::PositionedRead => {
-                        ColumnReadableFile::PositionedRead(Arc::new(file.into_std().await))
-                    }
-                },
-                block_cache.clone(),
-                BlockCacheKey::default().rowset(rowset_id).column(id as u32),
-            );
-            columns.push(column);
-        }
-
         Ok(Self {
+            directory,
+            columns: column_infos.iter().map(|_| OnceCell::new()).collect(),
             column_infos,
-            columns,
+            block_cache,
             rowset_id,
+            io_backend,
+            secondary_indexes: Mutex::new(HashMap::new()),
+            indexed_column: None,
         })
     }
 
-    pub fn column(&self, storage_column_id: usize) -> Column {
-        self.columns[storage_column_id].clone()
+    /// Get the column, and open its files if it has not been opened.
+    pub async fn column(&self, storage_column_id: usize) -> StorageResult<Column> {
+        let column = self.columns[storage_column_id]
+            .get_or_try_init(|| self.open_column(storage_column_id))
+            .await?;
+        Ok(column.clone())
+    }
+
+    async fn open_column(&self, storage_column_id: usize) -> StorageResult<Column> {
+        let column_info = &self.column_infos[storage_column_id];
+        let file = OpenOptions::default()
+            .read(true)
+            .write(false)
+            .open(path_of_data_column(&self.directory, column_info))
+            .await?;
+
+        Ok(Column::new(
+            self.read_index(storage_column_id).await?,
+            match self.io_backend {
+                IOBackend::NormalRead => {
+                    ColumnReadableFile::NormalRead(Arc::new(Mutex::new(file.into_std().await)))
+                }
+                IOBackend::PositionedRead => {
+                    ColumnReadableFile::PositionedRead(Arc::new(file.into_std().await))
+                }
+            },
+            self.block_cache.clone(),
+            BlockCacheKey::default()
+                .rowset(self.rowset_id)
+                .secondary_index(self.indexed_column)
+                .column(storage_column_id as u32),
+        ))
+    }
+
+    /// Read the block index of a column, without opening its data file.
+    async fn read_index(&self, storage_column_id: usize) -> StorageResult<ColumnIndex> {
+        let column_info = &self.column_infos[storage_column_id];
+        let mut index = OpenOptions::default()
+            .read(true)
+            .write(false)
+            .open(path_of_index_column(&self.directory, column_info))
+            .await?;
+
+        // TODO(chi): add an index cache later
+        let mut index_content = vec![];
+        index.read_to_end(&mut index_content).await?;
+        ColumnIndex::from_bytes(&index_content)
     }
 
     pub fn column_info(&self, storage_column_id: usize) -> &ColumnCatalog {
         &self.column_infos[storage_column_id]
     }
 
+    /// Get the number of columns stored in the rowset.
+    pub fn column_count(&self) -> usize {
+        self.column_infos.len()
+    }
+
     pub fn rowset_id(&self) -> u32 {
         self.rowset_id
     }
 
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Get the secondary index on a column, and open it if it has not been opened. Returns `None`
+    /// if the index is not built, e.g. the rowset is written before the index is created.
+    pub async fn secondary_index(
+        &self,
+        storage_column_id: usize,
+    ) -> StorageResult<Option<Arc<SecondaryIndex>>> {
+        if let Some(index) = self
+            .secondary_indexes
+            .lock()
+            .unwrap()
+            .get(&storage_column_id)
+        {
+            return Ok(Some(index.clone()));
+        }
+        let column_info = &self.column_infos[storage_column_id];
+        let directory = path_of_secondary_index(&self.directory, column_info);
+        if fs::metadata(&directory).await.is_err() {
+            return Ok(None);
+        }
+        let columns = index_columns(column_info);
+        // an index of no value has no column file
+        let rowset = if fs::metadata(path_of_data_column(&directory, &columns[0]))
+            .await
+            .is_ok()
+        {
+            let mut rowset = DiskRowset::open(
+                directory,
+                columns,
+                self.block_cache.clone(),
+                self.rowset_id,
+                self.io_backend,
+            )
+            .await?;
+            // blocks of the index are cached apart from the blocks of the rowset itself
+            rowset.indexed_column = Some(column_info.id());
+            Some(Arc::new(rowset))
+        } else {
+            None
+        };
+        let index = Arc::new(SecondaryIndex::new(rowset));
+        self.secondary_indexes
+            .lock()
+            .unwrap()
+            .insert(storage_column_id, index.clone());
+        Ok(Some(index))
+    }
+
     pub async fn iter(
         self: &Arc<Self>,
         column_refs: Arc<[StorageColumnRef]>,
@@ -94,27 +178,61 @@ impl DiskRowset {
         seek_pos: ColumnSeekPosition,
         expr: Option<BoundExpr>,
     ) -> StorageResult<RowSetIterator> {
-        RowSetIterator::new(self.clone(), column_refs, dvs, seek_pos, expr).await
+        let column_infos = self.column_infos.clone();
+        self.iter_with_columns(column_infos, column_refs, dvs, seek_pos, expr)
+            .await
     }
 
-    pub fn on_disk_size(&self) -> u64 {
-        self.columns
-            .iter()
-            .map(|x| x.on_disk_size())
-            .sum1()
-            .unwrap_or(0)
+    /// Iterate on the rowset with the columns of the table, which may have more columns added
+    /// after the rowset is written. These columns are read with their default values.
+    pub async fn iter_with_columns(
+        self: &Arc<Self>,
+        column_infos: Arc<[ColumnCatalog]>,
+        column_refs: Arc<[StorageColumnRef]>,
+        dvs: Vec<Arc<DeleteVector>>,
+        seek_pos: ColumnSeekPosition,
+        expr: Option<BoundExpr>,
+    ) -> StorageResult<RowSetIterator> {
+        RowSetIterator::new(self.clone(), column_infos, column_refs, dvs, seek_pos, expr).await
+    }
+
+    /// Get the number of rows in the rowset, including deleted rows. The number is read from the
+    /// first opened column, or from the index of the first column, so that no data file is opened.
+    pub async fn row_count(&self) -> StorageResult<usize> {
+        match self.columns.iter().find_map(OnceCell::get) {
+            Some(column) => Ok(column.index().row_count()),
+            None => Ok(self.read_index(0).await?.row_count()),
+        }
+    }
+
+    /// Get the on-disk size of all columns, which opens all columns.
+    pub async fn on_disk_size(&self) -> StorageResult<u64> {
+        let mut size = 0;
+        for id in 0..self.columns.len() {
+            size += self.column(id).await?.on_disk_size();
+        }
+        Ok(size)
     }
 }
 
 #[cfg(test)]
 pub mod tests {
+    use bytes::Buf;
+    use itertools::Itertools;
+    use risinglight_proto::rowset::block_checksum::ChecksumType;
+    use risinglight_proto::rowset::block_index::BlockType;
+    use risinglight_proto::rowset::block_statistics::BlockStatisticsType;
     use tempfile::TempDir;
 
     use super::*;
-    use crate::array::ArrayImpl;
+    use crate::array::{ArrayImpl, ArrayToVecExt};
     use crate::storage::secondary::rowset::rowset_builder::RowsetBuilder;
-    use crate::storage::secondary::ColumnBuilderOptions;
-    use crate::types::{DataTypeExt, DataTypeKind};
+    use crate::storage::secondary::{
+        CharBlockIteratorFactory, CharColumnIterator, ColumnBuilderOptions, ColumnIterator,
+        PrimitiveBlockIteratorFactory, PrimitiveColumnIterator,
+    };
+    use crate::storage::StorageError;
+    use crate::types::{DataType, DataTypeExt, DataTypeKind};
 
     pub async fn helper_build_rowset(tempdir: &TempDir, nullable: bool, len: usize) -> DiskRowset {
         let columns = vec![
@@ -185,7 +303,7 @@ pub mod tests {
         DiskRowset::open(
             tempdir.path().to_path_buf(),
             columns.into(),
-            Cache::new(2333),
+            BlockCache::new(1 << 20),
             0,
             IOBackend::NormalRead,
         )
@@ -197,7 +315,491 @@ pub mod tests {
     async fn test_get_block() {
         let tempdir = tempfile::tempdir().unwrap();
         let rowset = helper_build_rowset(&tempdir, true, 1000).await;
-        let column = rowset.column(0);
+        let column = rowset.column(0).await.unwrap();
         column.get_block(0).await.unwrap();
     }
+
+    async fn helper_build_single_column_rowset(
+        tempdir: &TempDir,
+        options: ColumnBuilderOptions,
+        datatype: DataType,
+        array: ArrayImpl,
+    ) -> DiskRowset {
+        let columns = vec![ColumnCatalog::new(0, datatype.to_column("v1".to_string()))];
+        let mut builder = RowsetBuilder::new(columns.clone().into(), tempdir.path(), options);
+        builder.append([array].into_iter().collect());
+        builder.finish_and_flush().await.unwrap();
+
+        DiskRowset::open(
+            tempdir.path().to_path_buf(),
+            columns.into(),
+            BlockCache::new(1 << 20),
+            0,
+            IOBackend::NormalRead,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rle_rowset_size() {
+        let array = ArrayImpl::Int32([1].into_iter().cycle().take(1000).collect());
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let plain_rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions::default_for_test(),
+            DataTypeKind::Int(None).not_null(),
+            array.clone(),
+        )
+        .await;
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let rle_rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions {
+                is_rle: true,
+                ..ColumnBuilderOptions::default_for_test()
+            },
+            DataTypeKind::Int(None).not_null(),
+            array,
+        )
+        .await;
+
+        assert!(
+            rle_rowset.on_disk_size().await.unwrap() < plain_rowset.on_disk_size().await.unwrap()
+        );
+
+        let mut scanner = PrimitiveColumnIterator::<i32>::new(
+            rle_rowset.column(0).await.unwrap(),
+            0,
+            PrimitiveBlockIteratorFactory::new(),
+        )
+        .await
+        .unwrap();
+        let mut recv_data = vec![];
+        while let Some((_, data)) = scanner.next_batch(Some(300), None).await.unwrap() {
+            recv_data.extend(data.to_vec());
+        }
+        assert_eq!(recv_data, vec![Some(1); 1000]);
+    }
+
+    #[tokio::test]
+    async fn test_bit_packed_rowset() {
+        let items = (0..10000).map(|i| Some(1000 + i * 7 % 51)).collect_vec();
+        let array = ArrayImpl::Int32(items.iter().cloned().collect());
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let plain_rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions::default_for_test(),
+            DataTypeKind::Int(None).not_null(),
+            array.clone(),
+        )
+        .await;
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let packed_rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions {
+                is_bit_packing: true,
+                ..ColumnBuilderOptions::default_for_test()
+            },
+            DataTypeKind::Int(None).not_null(),
+            array,
+        )
+        .await;
+
+        // values in [1000, 1050] take 6 bits instead of 32 bits
+        let plain_size = plain_rowset.on_disk_size().await.unwrap();
+        let packed_size = packed_rowset.on_disk_size().await.unwrap();
+        assert!(
+            packed_size * 4 < plain_size,
+            "packed = {}, plain = {}",
+            packed_size,
+            plain_size
+        );
+
+        let column = packed_rowset.column(0).await.unwrap();
+        let (header, _) = column.get_block(0).await.unwrap();
+        assert_eq!(header.block_type, BlockType::BitPacked);
+        let index = column.index().index(0);
+        assert_eq!(index.frame_of_reference, 1000);
+        assert_eq!(index.bit_width, 6);
+
+        let mut scanner =
+            PrimitiveColumnIterator::<i32>::new(column, 0, PrimitiveBlockIteratorFactory::new())
+                .await
+                .unwrap();
+        let mut recv_data = vec![];
+        while let Some((_, data)) = scanner.next_batch(Some(300), None).await.unwrap() {
+            recv_data.extend(data.to_vec());
+        }
+        assert_eq!(recv_data, items);
+    }
+
+    #[tokio::test]
+    async fn test_dictionary_rowset() {
+        let items = ["apple", "banana", "cherry"]
+            .into_iter()
+            .map(Some)
+            .cycle()
+            .take(1000)
+            .collect_vec();
+        let array = ArrayImpl::Utf8(items.iter().cloned().collect());
+        let datatype = DataTypeKind::Varchar(None).nullable();
+        let file_size = |tempdir: &TempDir| -> u64 {
+            std::fs::read_dir(tempdir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().metadata().unwrap().len())
+                .sum()
+        };
+
+        let plain_tempdir = tempfile::tempdir().unwrap();
+        helper_build_single_column_rowset(
+            &plain_tempdir,
+            ColumnBuilderOptions::default_for_test(),
+            datatype.clone(),
+            array.clone(),
+        )
+        .await;
+
+        let dict_tempdir = tempfile::tempdir().unwrap();
+        let dict_rowset = helper_build_single_column_rowset(
+            &dict_tempdir,
+            ColumnBuilderOptions {
+                is_dictionary: true,
+                ..ColumnBuilderOptions::default_for_test()
+            },
+            datatype,
+            array,
+        )
+        .await;
+
+        assert!(file_size(&dict_tempdir) < file_size(&plain_tempdir));
+
+        let column = dict_rowset.column(0).await.unwrap();
+        let factory =
+            CharBlockIteratorFactory::new(None).with_dictionary(column.index().dictionary());
+        let mut scanner = CharColumnIterator::new(column, 0, factory).await.unwrap();
+        let mut recv_data = vec![];
+        while let Some((_, data)) = scanner.next_batch(Some(300), None).await.unwrap() {
+            recv_data.extend(data.to_vec());
+        }
+        assert_eq!(
+            recv_data,
+            items
+                .iter()
+                .map(|item| item.map(|s| s.to_string()))
+                .collect_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nullable_varchar_rowset() {
+        // non-empty strings, then empty strings mixed with nulls, then all nulls
+        let items = (0..3000)
+            .map(|i| match i {
+                0..=999 => Some(format!("v{}", i * 7 % 1000)),
+                1000..=1999 if i % 3 == 0 => Some("".to_string()),
+                _ => None,
+            })
+            .collect_vec();
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions::default_for_block_test(),
+            DataTypeKind::Varchar(None).nullable(),
+            ArrayImpl::Utf8(items.iter().map(|item| item.as_deref()).collect()),
+        )
+        .await;
+
+        let column = rowset.column(0).await.unwrap();
+        let indexes = column.index().indexes();
+        assert!(indexes.len() > 1);
+        for index in indexes {
+            let first_rowid = index.first_rowid as usize;
+            let items = &items[first_rowid..first_rowid + index.row_count as usize];
+            assert_eq!(index.no_nulls, items.iter().all(Option::is_some));
+
+            // min and max statistics are recorded for pruning
+            let min_max = index
+                .stats
+                .iter()
+                .filter(|stat| {
+                    matches!(
+                        stat.block_stat_type(),
+                        BlockStatisticsType::MinValue | BlockStatisticsType::MaxValue
+                    )
+                })
+                .map(|stat| String::from_utf8(stat.body.clone()).unwrap())
+                .collect_vec();
+            let values = items.iter().flatten().cloned().collect_vec();
+            match (values.iter().min(), values.iter().max()) {
+                (Some(min), Some(max)) => assert_eq!(min_max, vec![min.clone(), max.clone()]),
+                _ => assert!(min_max.is_empty()),
+            }
+        }
+
+        let mut scanner = CharColumnIterator::new(column, 0, CharBlockIteratorFactory::new(None))
+            .await
+            .unwrap();
+        let mut recv_data = vec![];
+        while let Some((_, data)) = scanner.next_batch(Some(77), None).await.unwrap() {
+            recv_data.extend(data.to_vec());
+        }
+        assert_eq!(recv_data, items);
+    }
+
+    #[tokio::test]
+    async fn test_min_max_statistics() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions::default_for_test(),
+            DataTypeKind::Int(None).not_null(),
+            ArrayImpl::Int32((0..10000).collect()),
+        )
+        .await;
+
+        let column = rowset.column(0).await.unwrap();
+        assert!(column.index().len() > 1);
+        let mut scanner =
+            PrimitiveColumnIterator::<i32>::new(column, 0, PrimitiveBlockIteratorFactory::new())
+                .await
+                .unwrap();
+        loop {
+            let stats = scanner
+                .block_stats()
+                .iter()
+                .map(|stat| (stat.block_stat_type(), (&stat.body[..]).get_i32_le()))
+                .filter(|(ty, _)| {
+                    matches!(
+                        ty,
+                        BlockStatisticsType::MinValue | BlockStatisticsType::MaxValue
+                    )
+                })
+                .collect_vec();
+            let (first_row_id, data) = match scanner.next_batch(None, None).await.unwrap() {
+                Some(batch) => batch,
+                None => {
+                    assert!(stats.is_empty());
+                    break;
+                }
+            };
+            let first_row_id = first_row_id as i32;
+            assert_eq!(
+                stats,
+                vec![
+                    (BlockStatisticsType::MinValue, first_row_id),
+                    (
+                        BlockStatisticsType::MaxValue,
+                        first_row_id + data.len() as i32 - 1
+                    ),
+                ]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nullable_rowset_no_nulls() {
+        // no-null rows, then mixed rows, then all-null rows
+        let data = (0..3000i32)
+            .map(|i| match i {
+                0..=999 => Some(i),
+                1000..=1999 if i % 3 == 0 => Some(i),
+                _ => None,
+            })
+            .collect_vec();
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions::default_for_block_test(),
+            DataTypeKind::Int(None).nullable(),
+            ArrayImpl::Int32(data.iter().cloned().collect()),
+        )
+        .await;
+
+        let column = rowset.column(0).await.unwrap();
+        let indexes = column.index().indexes();
+        for index in indexes {
+            let first_rowid = index.first_rowid as usize;
+            let items = &data[first_rowid..first_rowid + index.row_count as usize];
+            assert_eq!(index.no_nulls, items.iter().all(Option::is_some));
+        }
+        assert!(indexes.iter().any(|index| index.no_nulls));
+        assert!(indexes.iter().any(|index| !index.no_nulls));
+
+        let mut scanner =
+            PrimitiveColumnIterator::<i32>::new(column, 0, PrimitiveBlockIteratorFactory::new())
+                .await
+                .unwrap();
+        let mut recv_data = vec![];
+        while let Some((_, array)) = scanner.next_batch(None, None).await.unwrap() {
+            recv_data.extend(array.to_vec());
+        }
+        assert_eq!(recv_data, data);
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_rowset() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions {
+                bloom_filter: true,
+                ..ColumnBuilderOptions::default_for_test()
+            },
+            DataTypeKind::Int(None).not_null(),
+            ArrayImpl::Int32((0..1000).map(|x| x * 2).collect()),
+        )
+        .await;
+
+        let index = rowset.column(0).await.unwrap().index().clone();
+        for i in 0..1000i32 {
+            assert!(index.may_contain(&(i * 2).to_le_bytes()));
+        }
+        let false_positives = (0..1000i32)
+            .filter(|i| index.may_contain(&(i * 2 + 1).to_le_bytes()))
+            .count();
+        assert!(false_positives < 50);
+
+        // columns without Bloom filter may contain any value
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = helper_build_single_column_rowset(
+            &tempdir,
+            ColumnBuilderOptions::default_for_test(),
+            DataTypeKind::Int(None).not_null(),
+            ArrayImpl::Int32((0..1000).collect()),
+        )
+        .await;
+        assert!(rowset
+            .column(0)
+            .await
+            .unwrap()
+            .index()
+            .may_contain(&5000i32.to_le_bytes()));
+    }
+
+    #[tokio::test]
+    async fn test_checksum_mismatch() {
+        for checksum_type in [
+            ChecksumType::None,
+            ChecksumType::Crc32,
+            ChecksumType::Crc32c,
+        ] {
+            let tempdir = tempfile::tempdir().unwrap();
+            let rowset = helper_build_single_column_rowset(
+                &tempdir,
+                ColumnBuilderOptions {
+                    checksum_type,
+                    ..ColumnBuilderOptions::default_for_test()
+                },
+                DataTypeKind::Int(None).not_null(),
+                ArrayImpl::Int32((0..100).collect()),
+            )
+            .await;
+            assert_eq!(rowset.column(0).await.unwrap().index().indexes().len(), 1);
+
+            // flip the last byte of the only block
+            let path = path_of_data_column(tempdir.path(), &rowset.column_infos[0]);
+            let mut data = std::fs::read(&path).unwrap();
+            *data.last_mut().unwrap() ^= 0xff;
+            std::fs::write(&path, data).unwrap();
+
+            let result = rowset.column(0).await.unwrap().get_block(0).await;
+            if checksum_type == ChecksumType::None {
+                result.unwrap();
+            } else {
+                let err = result.unwrap_err();
+                assert!(matches!(err.inner(), StorageError::ChecksumMismatch(_, _)));
+            }
+        }
+    }
+
+    async fn helper_scan_rowset(rowset: DiskRowset) {
+        let mut it = Arc::new(rowset)
+            .iter(
+                vec![StorageColumnRef::Idx(0), StorageColumnRef::Idx(1)].into(),
+                vec![],
+                ColumnSeekPosition::RowId(0),
+                None,
+            )
+            .await
+            .unwrap();
+        while it.next_batch(None).await.unwrap().is_some() {}
+    }
+
+    #[tokio::test]
+    async fn test_block_cache() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = helper_build_rowset(&tempdir, true, 1000).await;
+        let column_infos = rowset.column_infos.clone();
+        let num_blocks = rowset.column(0).await.unwrap().index().len()
+            + rowset.column(1).await.unwrap().index().len();
+        let open = |block_cache: BlockCache| {
+            DiskRowset::open(
+                tempdir.path().to_path_buf(),
+                column_infos.clone(),
+                block_cache,
+                0,
+                IOBackend::NormalRead,
+            )
+        };
+
+        // the second scan reads all blocks from cache
+        let block_cache = BlockCache::new(1 << 30);
+        helper_scan_rowset(open(block_cache.clone()).await.unwrap()).await;
+        assert_eq!(block_cache.misses(), num_blocks as u64);
+        let hits = block_cache.hits();
+        helper_scan_rowset(open(block_cache.clone()).await.unwrap()).await;
+        assert!(block_cache.hits() >= hits + num_blocks as u64);
+        assert_eq!(block_cache.misses(), num_blocks as u64);
+
+        // a small cache evicts least recently used blocks, so only the last blocks are cached
+        let capacity = block_cache.size() / 4;
+        let block_cache = BlockCache::new(capacity);
+        helper_scan_rowset(open(block_cache.clone()).await.unwrap()).await;
+        assert!(block_cache.size() <= capacity);
+        let last_block = rowset.column(0).await.unwrap().index().len() as u32 - 1;
+        let key = BlockCacheKey::default().column(0).block(last_block);
+        assert!(block_cache.get(&key).is_some());
+        let key = BlockCacheKey::default().column(0).block(0);
+        assert!(block_cache.get(&key).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_open_referenced_columns_only() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = helper_build_rowset(&tempdir, true, 1000).await;
+
+        // files of unreferenced columns are never opened, so the scan works without them
+        for column_info in &rowset.column_infos[1..] {
+            std::fs::remove_file(path_of_data_column(tempdir.path(), column_info)).unwrap();
+            std::fs::remove_file(path_of_index_column(tempdir.path(), column_info)).unwrap();
+        }
+        let rowset = DiskRowset::open(
+            tempdir.path().to_path_buf(),
+            rowset.column_infos.clone(),
+            BlockCache::new(1 << 20),
+            0,
+            IOBackend::NormalRead,
+        )
+        .await
+        .unwrap();
+        let mut it = Arc::new(rowset)
+            .iter(
+                vec![StorageColumnRef::Idx(0)].into(),
+                vec![],
+                ColumnSeekPosition::RowId(0),
+                None,
+            )
+            .await
+            .unwrap();
+        let mut row_count = 0;
+        while let Some(chunk) = it.next_batch(None).await.unwrap() {
+            row_count += chunk.cardinality();
+        }
+        assert_eq!(row_count, 100 * 1000);
+    }
 }
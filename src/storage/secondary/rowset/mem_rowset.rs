@@ -9,9 +9,9 @@ use itertools::Itertools;
 
 use super::rowset_builder::RowsetBuilder;
 use crate::array::{ArrayBuilderImpl, DataChunk};
-use crate::catalog::{find_sort_key_id, ColumnCatalog};
+use crate::catalog::{find_sort_key_ids, ColumnCatalog};
 use crate::storage::secondary::ColumnBuilderOptions;
-use crate::storage::StorageResult;
+use crate::storage::{StorageResult, TracedStorageError};
 use crate::types::{DataValue, Row};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -37,18 +37,26 @@ pub trait MemTable {
     fn flush(self) -> StorageResult<DataChunk>;
 }
 
+/// A memory table that keeps rows ordered by the (possibly composite) primary key, and rejects
+/// rows with duplicated keys.
 pub struct BTreeMapMemTable {
     columns: Arc<[ColumnCatalog]>,
-    primary_key_idx: usize,
-    multi_btree_map: BTreeMultiMap<ComparableDataValue, Row>,
+    primary_key_idx: Vec<usize>,
+    multi_btree_map: BTreeMultiMap<Vec<ComparableDataValue>, Row>,
+    /// Count of rows in the table
+    row_cnt: usize,
+    /// Estimated size (in bytes) of the appended chunks
+    estimated_size: usize,
 }
 
 impl BTreeMapMemTable {
-    fn new(columns: Arc<[ColumnCatalog]>, primary_key_idx: usize) -> Self {
+    fn new(columns: Arc<[ColumnCatalog]>, primary_key_idx: Vec<usize>) -> Self {
         Self {
             columns,
             primary_key_idx,
             multi_btree_map: BTreeMultiMap::new(),
+            row_cnt: 0,
+            estimated_size: 0,
         }
     }
 }
@@ -56,11 +64,23 @@ impl BTreeMapMemTable {
 impl MemTable for BTreeMapMemTable {
     fn append(&mut self, columns: DataChunk) -> StorageResult<()> {
         for row_idx in 0..columns.cardinality() {
-            self.multi_btree_map.insert(
-                ComparableDataValue(columns.array_at(self.primary_key_idx).get(row_idx)),
-                columns.row(row_idx).values().collect(),
-            );
+            let key = (self.primary_key_idx.iter())
+                .map(|&idx| ComparableDataValue(columns.array_at(idx).get(row_idx)))
+                .collect_vec();
+            if self.multi_btree_map.contains_key(&key) {
+                let key = (self.primary_key_idx.iter())
+                    .map(|&idx| columns.array_at(idx).get_to_string(row_idx))
+                    .join(", ");
+                return Err(TracedStorageError::duplicated(
+                    "primary key",
+                    format!("({})", key),
+                ));
+            }
+            self.multi_btree_map
+                .insert(key, columns.row(row_idx).values().collect());
+            self.row_cnt += 1;
         }
+        self.estimated_size += columns.estimated_size();
         Ok(())
     }
 
@@ -129,6 +149,14 @@ impl SecondaryMemRowset<BTreeMapMemTable> {
         self.mem_table.append(columns)
     }
 
+    /// Check whether the rowset should be flushed before appending `next_chunk`, so that it stays
+    /// within the target size.
+    pub fn should_finish(&self, next_chunk: &DataChunk) -> bool {
+        self.mem_table.row_cnt > 0
+            && self.mem_table.estimated_size + next_chunk.estimated_size()
+                > self.rowset_builder.target_size()
+    }
+
     /// Flush memory table to disk and return a handler
     pub async fn flush(self) -> StorageResult<()> {
         let chunk = self.mem_table.flush()?;
@@ -146,6 +174,10 @@ impl SecondaryMemRowset<ColumnMemTable> {
         Ok(())
     }
 
+    pub fn should_finish(&self, next_chunk: &DataChunk) -> bool {
+        self.rowset_builder.should_finish(next_chunk)
+    }
+
     pub async fn flush(self) -> StorageResult<()> {
         self.rowset_builder.finish_and_flush().await?;
         Ok(())
@@ -164,7 +196,8 @@ impl SecondaryMemRowsetImpl {
         column_options: ColumnBuilderOptions,
         rowset_id: u32,
     ) -> Self {
-        if let Some(sort_key_idx) = find_sort_key_id(&columns) {
+        let sort_key_idx = find_sort_key_ids(&columns);
+        if !sort_key_idx.is_empty() {
             Self::BTree(SecondaryMemRowset::<BTreeMapMemTable> {
                 mem_table: BTreeMapMemTable::new(columns.clone(), sort_key_idx),
                 rowset_builder: RowsetBuilder::new(columns, directory, column_options),
@@ -193,6 +226,14 @@ impl SecondaryMemRowsetImpl {
         }
     }
 
+    /// Check whether the rowset should be flushed before appending `next_chunk`.
+    pub fn should_finish(&self, next_chunk: &DataChunk) -> bool {
+        match self {
+            Self::BTree(btree_table) => btree_table.should_finish(next_chunk),
+            Self::Column(column_table) => column_table.should_finish(next_chunk),
+        }
+    }
+
     pub fn get_rowset_id(&self) -> u32 {
         match self {
             Self::BTree(ref mem) => mem.rowset_id,
@@ -16,7 +16,8 @@
 //! |- 01.col     data for v1
 //! |- 01.sort    sort index for v1, which stores RowId + Key -> Block mapping
 //! |- 02.col     data for v2
-//! \- 02.idx     normal index for v2, which stores RowId -> Block mapping
+//! |- 02.idx     normal index for v2, which stores RowId -> Block mapping
+//! \- 02.index   secondary index on v2 if created, a rowset of sorted values and their RowIds
 //! ```
 //!
 //! Data flushed to directory will be immutable, and the directory content will remain
@@ -40,5 +41,9 @@ pub use mem_rowset::*;
 pub use rowset_builder::*;
 mod disk_rowset;
 pub use disk_rowset::*;
+mod block_pruner;
+pub use block_pruner::*;
 mod rowset_iterator;
 pub use rowset_iterator::*;
+mod secondary_index;
+pub use secondary_index::*;
@@ -3,11 +3,12 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use futures::future::{try_join, try_join_all};
 use itertools::Itertools;
 use tokio::fs::{File, OpenOptions};
 use tokio::io::{AsyncWriteExt, BufWriter};
 
-use super::super::{ColumnBuilderImpl, IndexBuilder};
+use super::super::{BloomFilterBuilder, ColumnBuilderImpl, IndexBuilder};
 use crate::array::DataChunk;
 use crate::catalog::ColumnCatalog;
 use crate::storage::secondary::ColumnBuilderOptions;
@@ -38,12 +39,18 @@ pub struct RowsetBuilder {
     /// Column data builders
     builders: Vec<ColumnBuilderImpl>,
 
+    /// Bloom filter builders of each column, if enabled
+    bloom_filter_builders: Vec<Option<BloomFilterBuilder>>,
+
     /// Output directory of the rowset
     directory: PathBuf,
 
     /// Count of rows in this rowset
     row_cnt: u32,
 
+    /// Estimated size (in bytes) of the appended chunks
+    estimated_size: usize,
+
     /// Column builder options
     column_options: ColumnBuilderOptions,
 }
@@ -61,21 +68,41 @@ impl RowsetBuilder {
                     ColumnBuilderImpl::new_from_datatype(&column.datatype(), column_options.clone())
                 })
                 .collect_vec(),
+            bloom_filter_builders: columns
+                .iter()
+                .map(|_| column_options.bloom_filter.then(BloomFilterBuilder::new))
+                .collect_vec(),
             directory: directory.as_ref().to_path_buf(),
             columns,
             row_cnt: 0,
+            estimated_size: 0,
             column_options,
         }
     }
 
     pub fn append(&mut self, chunk: DataChunk) {
         self.row_cnt += chunk.cardinality() as u32;
+        self.estimated_size += chunk.estimated_size();
 
         for idx in 0..chunk.column_count() {
             self.builders[idx].append(chunk.array_at(idx));
+            if let Some(builder) = &mut self.bloom_filter_builders[idx] {
+                builder.add_array(chunk.array_at(idx));
+            }
         }
     }
 
+    /// Target size (in bytes) of the rowset.
+    pub fn target_size(&self) -> usize {
+        self.column_options.target_rowset_size
+    }
+
+    /// Check whether the rowset should be finished before appending `next_chunk`, so that it stays
+    /// within the target size. An empty rowset is never finished.
+    pub fn should_finish(&self, next_chunk: &DataChunk) -> bool {
+        self.row_cnt > 0 && self.estimated_size + next_chunk.estimated_size() > self.target_size()
+    }
+
     async fn pipe_to_file(path: impl AsRef<Path>, data: Vec<u8>) -> StorageResult<()> {
         let file = OpenOptions::new()
             .write(true)
@@ -99,25 +126,43 @@ impl RowsetBuilder {
     }
 
     pub async fn finish_and_flush(self) -> StorageResult<()> {
-        for (column_info, builder) in self.columns.iter().zip(self.builders) {
+        let directory = &self.directory;
+        let mut flush_futures = vec![];
+
+        for ((column_info, builder), bloom_filter_builder) in self
+            .columns
+            .iter()
+            .zip(self.builders)
+            .zip(self.bloom_filter_builders)
+        {
+            let dictionary = builder.dictionary();
             let (index, data) = builder.finish();
 
-            Self::pipe_to_file(path_of_data_column(&self.directory, column_info), data).await?;
-
             let mut index_builder =
                 IndexBuilder::new(self.column_options.checksum_type, index.len());
             for index in index {
                 index_builder.append(index);
             }
+            if let Some(dictionary) = dictionary {
+                index_builder.set_dictionary(dictionary);
+            }
+            if let Some(bloom_filter_builder) = bloom_filter_builder {
+                index_builder.set_bloom_filter(&bloom_filter_builder.finish());
+            }
 
-            Self::pipe_to_file(
-                path_of_index_column(&self.directory, column_info),
-                index_builder.finish(),
-            )
-            .await?;
+            // data and index files of all columns are written concurrently
+            flush_futures.push(try_join(
+                Self::pipe_to_file(path_of_data_column(directory, column_info), data),
+                Self::pipe_to_file(
+                    path_of_index_column(directory, column_info),
+                    index_builder.finish(),
+                ),
+            ));
         }
 
-        Self::sync_dir(&self.directory).await?;
+        try_join_all(flush_futures).await?;
+
+        Self::sync_dir(directory).await?;
 
         Ok(())
     }
@@ -157,4 +202,44 @@ mod tests {
 
         builder.finish_and_flush().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_rowset_flush_multiple_columns() {
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let columns: Arc<[ColumnCatalog]> = (0..8)
+            .map(|id| {
+                ColumnCatalog::new(
+                    id,
+                    DataTypeKind::Int(None)
+                        .nullable()
+                        .to_column(format!("v{}", id)),
+                )
+            })
+            .collect_vec()
+            .into();
+        let new_builder = || {
+            let mut builder = RowsetBuilder::new(
+                columns.clone(),
+                tempdir.path(),
+                ColumnBuilderOptions::default_for_test(),
+            );
+            builder.append(
+                (0..8)
+                    .map(|_| ArrayImpl::Int32((0..1000).collect()))
+                    .collect(),
+            );
+            builder
+        };
+
+        new_builder().finish_and_flush().await.unwrap();
+
+        for column in columns.iter() {
+            assert!(path_of_data_column(tempdir.path(), column).exists());
+            assert!(path_of_index_column(tempdir.path(), column).exists());
+        }
+
+        // flushing to the same directory again should fail
+        assert!(new_builder().finish_and_flush().await.is_err());
+    }
 }
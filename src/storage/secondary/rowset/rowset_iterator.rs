@@ -6,13 +6,17 @@ use bitvec::prelude::BitVec;
 use smallvec::smallvec;
 
 use super::super::{
-    ColumnIteratorImpl, ColumnSeekPosition, RowHandlerSequencer, SecondaryIteratorImpl,
+    ColumnIndex, ColumnIteratorImpl, ColumnSeekPosition, DefaultColumnIterator,
+    RowHandlerSequencer, SecondaryIteratorImpl,
 };
-use super::DiskRowset;
+use super::{BlockPruner, DiskRowset};
 use crate::array::{Array, ArrayImpl};
 use crate::binder::BoundExpr;
+use crate::catalog::ColumnCatalog;
 use crate::storage::secondary::DeleteVector;
-use crate::storage::{PackedVec, StorageChunk, StorageColumnRef, StorageResult};
+use crate::storage::{
+    PackedVec, ScanStats, StorageChunk, StorageColumnRef, StorageError, StorageResult,
+};
 
 /// When `expected_size` is not specified, we should limit the maximum size of the chunk.
 const ROWSET_MAX_OUTPUT: usize = 65536;
@@ -24,11 +28,18 @@ pub struct RowSetIterator {
     dvs: Vec<Arc<DeleteVector>>,
     column_iterators: Vec<Option<ColumnIteratorImpl>>,
     filter_expr: Option<(BoundExpr, BitVec)>,
+    block_pruner: Option<BlockPruner>,
+    /// The index of the first scanned column, whose blocks are counted in `stats`.
+    stats_index: ColumnIndex,
+    /// The number of leading blocks of `stats_index` that have been counted.
+    counted_blocks: usize,
+    stats: ScanStats,
 }
 
 impl RowSetIterator {
     pub async fn new(
         rowset: Arc<DiskRowset>,
+        column_infos: Arc<[ColumnCatalog]>,
         column_refs: Arc<[StorageColumnRef]>,
         dvs: Vec<Arc<DeleteVector>>,
         seek_pos: ColumnSeekPosition,
@@ -57,22 +68,47 @@ impl RowSetIterator {
         }
 
         let mut column_iterators: Vec<Option<ColumnIteratorImpl>> = vec![];
+        let mut stats_index = None;
 
         for column_ref in &*column_refs {
             // TODO: parallel seek
             match column_ref {
                 StorageColumnRef::RowHandler => column_iterators.push(None),
-                StorageColumnRef::Idx(idx) => column_iterators.push(Some(
-                    ColumnIteratorImpl::new(
-                        rowset.column(*idx as usize),
-                        rowset.column_info(*idx as usize),
-                        start_row_id,
-                    )
-                    .await?,
-                )),
+                StorageColumnRef::Idx(idx) if *idx as usize >= rowset.column_count() => {
+                    let row_count = rowset.row_count().await? as u32;
+                    column_iterators.push(Some(ColumnIteratorImpl::Default(
+                        DefaultColumnIterator::new(
+                            &column_infos[*idx as usize],
+                            row_count,
+                            start_row_id,
+                        ),
+                    )))
+                }
+                StorageColumnRef::Idx(idx) => {
+                    let column = rowset.column(*idx as usize).await?;
+                    stats_index.get_or_insert_with(|| column.index().clone());
+                    column_iterators.push(Some(
+                        ColumnIteratorImpl::new(
+                            column,
+                            rowset.column_info(*idx as usize),
+                            start_row_id,
+                        )
+                        .await?,
+                    ))
+                }
             };
         }
+        // count the blocks of the first stored column if only the default columns are scanned
+        let stats_index = match stats_index {
+            Some(index) => index,
+            None => rowset.column(0).await?.index().clone(),
+        };
+        // blocks before the start row are neither read nor skipped
+        let counted_blocks = stats_index
+            .indexes()
+            .partition_point(|index| index.first_rowid + index.row_count <= start_row_id);
 
+        let mut block_pruner = None;
         let filter_expr = if let Some(expr) = expr {
             let filter_column = expr.get_filter_column(column_refs.len());
             // assert filter column is not all false
@@ -80,6 +116,7 @@ impl RowSetIterator {
                 filter_column.any(),
                 "There should be at least 1 filter column"
             );
+            block_pruner = Some(BlockPruner::new(&rowset, &column_refs, &expr).await?);
             Some((expr, filter_column))
         } else {
             None
@@ -91,13 +128,89 @@ impl RowSetIterator {
             dvs,
             column_iterators,
             filter_expr,
+            block_pruner,
+            stats_index,
+            counted_blocks,
+            stats: ScanStats::default(),
         })
     }
 
+    /// Get the statistics of the scan so far.
+    pub fn stats(&self) -> ScanStats {
+        self.stats
+    }
+
+    /// Only fetch the blocks containing the rows, which should be sorted, e.g. the rows found by
+    /// a secondary index. The other blocks are skipped.
+    pub fn restrict_to_rows(&mut self, row_ids: &[u32]) {
+        let ranges = (self.stats_index.indexes().iter())
+            .map(|index| (index.first_rowid, index.first_rowid + index.row_count))
+            .filter(|(begin, end)| {
+                let next = row_ids.partition_point(|row_id| row_id < begin);
+                row_ids.get(next).map_or(true, |row_id| row_id >= end)
+            })
+            .collect::<Vec<_>>();
+        self.block_pruner
+            .get_or_insert_with(BlockPruner::default)
+            .skip_ranges(ranges);
+    }
+
+    /// Count the blocks starting before `row_id` as read, as their rows have been fetched.
+    fn count_read_blocks(&mut self, row_id: u32) {
+        let indexes = self.stats_index.indexes();
+        while let Some(index) = indexes.get(self.counted_blocks) {
+            if index.first_rowid >= row_id {
+                break;
+            }
+            self.stats.blocks_read += 1;
+            self.counted_blocks += 1;
+        }
+    }
+
+    /// Count the blocks inside the skipped rows `[begin, end)` as skipped.
+    fn count_skipped_blocks(&mut self, begin: u32, end: u32) {
+        self.count_read_blocks(begin);
+        let indexes = self.stats_index.indexes();
+        while let Some(index) = indexes.get(self.counted_blocks) {
+            if index.first_rowid + index.row_count > end {
+                break;
+            }
+            self.stats.blocks_skipped += 1;
+            self.counted_blocks += 1;
+        }
+    }
+
+    /// Get the row id of the next row to be fetched.
+    fn current_row_id(&self) -> u32 {
+        self.column_iterators
+            .iter()
+            .flatten()
+            .next()
+            .unwrap()
+            .fetch_current_row_id()
+    }
+
     pub async fn next_batch_inner(
         &mut self,
         expected_size: Option<usize>,
     ) -> StorageResult<(bool, Option<StorageChunk>)> {
+        // Skip the rows that can't match the filter according to block statistics, and stop
+        // before the next skipped rows so that they are not fetched.
+        let mut max_fetch_size = None;
+        if let Some(block_pruner) = &self.block_pruner {
+            let row_id = self.current_row_id();
+            if let Some(end) = block_pruner.skip_to(row_id) {
+                for it in self.column_iterators.iter_mut().flatten() {
+                    it.skip((end - row_id) as usize);
+                }
+                self.count_skipped_blocks(row_id, end);
+                return Ok((false, None));
+            }
+            max_fetch_size = block_pruner
+                .next_skipped(row_id)
+                .map(|begin| (begin - row_id) as usize);
+        }
+
         let filter_context = self.filter_expr.as_ref();
         let fetch_size = if let Some(x) = expected_size {
             x
@@ -116,7 +229,11 @@ impl RowSetIterator {
                     }
                 }
             }
-            min.unwrap_or(ROWSET_MAX_OUTPUT)
+            min.map_or(ROWSET_MAX_OUTPUT, |min| min.min(ROWSET_MAX_OUTPUT))
+        };
+        let fetch_size = match max_fetch_size {
+            Some(max_fetch_size) => fetch_size.min(max_fetch_size),
+            None => fetch_size,
         };
 
         let mut arrays: PackedVec<Option<ArrayImpl>> = smallvec![];
@@ -136,10 +253,7 @@ impl RowSetIterator {
         // that we can avoid unnecessary scan on filter column at next
         if !self.dvs.is_empty() {
             // Get the start row id first
-            let start_row_id = self.column_iterators[0]
-                .as_ref()
-                .unwrap()
-                .fetch_current_row_id();
+            let start_row_id = self.current_row_id();
 
             // Initialize visibility map and apply delete vector to it
             let mut visi = BitVec::new();
@@ -190,7 +304,7 @@ impl RowSetIterator {
             // Need to optimize
             let bool_array = match expr
                 .eval_array_in_storage(&arrays, common_chunk_range.1)
-                .unwrap()
+                .map_err(StorageError::Filter)?
             {
                 ArrayImpl::Bool(a) => a,
                 _ => panic!("filters can only accept bool array"),
@@ -305,7 +419,9 @@ impl RowSetIterator {
             let (finished, batch) = self.next_batch_inner(expected_size).await?;
             if finished {
                 return Ok(None);
-            } else if let Some(batch) = batch {
+            }
+            self.count_read_blocks(self.current_row_id());
+            if let Some(batch) = batch {
                 return Ok(Some(batch));
             }
         }
@@ -323,9 +439,13 @@ mod tests {
     use super::*;
     use crate::array::{Array, ArrayToVecExt};
     use crate::binder::{BoundBinaryOp, BoundInputRef};
+    use crate::catalog::ColumnCatalog;
     use crate::storage::secondary::rowset::tests::helper_build_rowset;
-    use crate::storage::secondary::SecondaryRowHandler;
-    use crate::types::{DataType, DataValue, PhysicalDataTypeKind};
+    use crate::storage::secondary::rowset::RowsetBuilder;
+    use crate::storage::secondary::{
+        BlockCache, ColumnBuilderOptions, IOBackend, SecondaryRowHandler,
+    };
+    use crate::types::{DataType, DataTypeExt, DataValue, PhysicalDataTypeKind};
 
     #[tokio::test]
     async fn test_rowset_iterator() {
@@ -466,4 +586,182 @@ mod tests {
             unreachable!()
         }
     }
+
+    #[tokio::test]
+    async fn test_rowset_iterator_with_deletes() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = Arc::new(helper_build_rowset(&tempdir, false, 1000).await);
+
+        // delete scattered rows across blocks, with deletes split into several DVs
+        let deleted = [0, 1, 7, 500, 501, 999, 1000, 12345, 99999];
+        let mut dv1 = DeleteVector::new(0, 0, vec![]);
+        let mut dv2 = DeleteVector::new(1, 0, vec![]);
+        for (i, row_id) in deleted.iter().enumerate() {
+            if i % 2 == 0 {
+                dv1.delete_row(*row_id);
+            } else {
+                dv2.delete_row(*row_id);
+            }
+        }
+
+        let mut it = rowset
+            .iter(
+                vec![StorageColumnRef::RowHandler, StorageColumnRef::Idx(0)].into(),
+                vec![Arc::new(dv1), Arc::new(dv2)],
+                ColumnSeekPosition::RowId(0),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let mut row_ids = vec![];
+        while let Some(chunk) = it.next_batch(Some(1000)).await.unwrap() {
+            let chunk = chunk.to_data_chunk();
+            if let ArrayImpl::Int64(array) = chunk.array_at(0) {
+                row_ids.extend(
+                    array
+                        .iter()
+                        .map(|x| SecondaryRowHandler::from(*x.unwrap()).row_id()),
+                );
+            } else {
+                unreachable!()
+            }
+        }
+
+        let expected = (0..100000)
+            .filter(|row_id| !deleted.contains(row_id))
+            .collect_vec();
+        assert_eq!(row_ids, expected);
+    }
+
+    /// Build a rowset of a single column `v1` with the values.
+    async fn helper_build_int_rowset(
+        tempdir: &tempfile::TempDir,
+        options: ColumnBuilderOptions,
+        block_cache: BlockCache,
+        values: impl Iterator<Item = i32>,
+    ) -> DiskRowset {
+        let columns: Arc<[ColumnCatalog]> = vec![ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None)
+                .not_null()
+                .to_column("v1".to_string()),
+        )]
+        .into();
+        let mut builder = RowsetBuilder::new(columns.clone(), tempdir.path(), options);
+        builder.append([ArrayImpl::Int32(values.collect())].into_iter().collect());
+        builder.finish_and_flush().await.unwrap();
+        DiskRowset::open(
+            tempdir.path().to_path_buf(),
+            columns,
+            block_cache,
+            0,
+            IOBackend::NormalRead,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn helper_compare_v1(op: BinaryOperator, value: i32) -> BoundExpr {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op,
+            left_expr: Box::new(BoundExpr::InputRef(BoundInputRef {
+                index: 0,
+                return_type: DataTypeKind::Int(None).not_null(),
+            })),
+            right_expr: Box::new(BoundExpr::Constant(DataValue::Int32(value))),
+            return_type: Some(DataTypeKind::Boolean.not_null()),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_rowset_iterator_with_block_pruning() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let block_cache = BlockCache::new(1 << 20);
+        let rowset = Arc::new(
+            helper_build_int_rowset(
+                &tempdir,
+                ColumnBuilderOptions::default_for_test(),
+                block_cache.clone(),
+                0..10000,
+            )
+            .await,
+        );
+        assert!(rowset.column(0).await.unwrap().index().len() > 2);
+
+        let mut it = rowset
+            .iter(
+                vec![StorageColumnRef::Idx(0), StorageColumnRef::RowHandler].into(),
+                vec![],
+                ColumnSeekPosition::RowId(0),
+                Some(helper_compare_v1(BinaryOperator::GtEq, 9990)),
+            )
+            .await
+            .unwrap();
+        let mut values = vec![];
+        while let Some(chunk) = it.next_batch(None).await.unwrap() {
+            let chunk = chunk.to_data_chunk();
+            if let ArrayImpl::Int32(array) = chunk.array_at(0) {
+                values.extend(array.iter().map(|x| *x.unwrap()));
+            } else {
+                unreachable!()
+            }
+        }
+        assert_eq!(values, (9990..10000).collect_vec());
+        // besides the first block read on creating the iterator, only the last block is read
+        assert_eq!(block_cache.misses(), 2);
+        let stats = it.stats();
+        assert_eq!(stats.blocks_read, 1);
+        assert_eq!(
+            stats.blocks_skipped,
+            rowset.column(0).await.unwrap().index().len() - 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_block_pruner_with_bloom_filter() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = helper_build_int_rowset(
+            &tempdir,
+            ColumnBuilderOptions {
+                bloom_filter: true,
+                ..ColumnBuilderOptions::default_for_test()
+            },
+            BlockCache::new(1 << 20),
+            (0..1000).map(|x| x * 2),
+        )
+        .await;
+        let column_refs = [StorageColumnRef::Idx(0)];
+        let skip_to = |op, value, row_id| {
+            let expr = helper_compare_v1(op, value);
+            let rowset = &rowset;
+            let column_refs = &column_refs;
+            async move {
+                BlockPruner::new(rowset, column_refs, &expr)
+                    .await
+                    .unwrap()
+                    .skip_to(row_id)
+            }
+        };
+
+        // values out of range are skipped by statistics
+        assert_eq!(skip_to(BinaryOperator::Eq, -1, 0).await, Some(1000));
+        assert_eq!(skip_to(BinaryOperator::Lt, 0, 0).await, Some(1000));
+        assert_eq!(skip_to(BinaryOperator::Gt, 1998, 0).await, Some(1000));
+
+        // existing values are never skipped
+        for row_id in 0..1000 {
+            let value = row_id as i32 * 2;
+            assert_eq!(skip_to(BinaryOperator::Eq, value, row_id).await, None);
+        }
+
+        // absent values are mostly skipped by the Bloom filter
+        let mut skipped = 0;
+        for value in (0..1000).map(|x| x * 2 + 1) {
+            if skip_to(BinaryOperator::Eq, value, 0).await == Some(1000) {
+                skipped += 1;
+            }
+        }
+        assert!(skipped > 950);
+    }
 }
@@ -0,0 +1,180 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use sqlparser::ast::BinaryOperator;
+use tokio::fs;
+
+use super::block_pruner::{comparison, conjunctions};
+use super::{path_of_column, DiskRowset, RowsetBuilder};
+use crate::array::{Array, ArrayBuilderImpl, ArrayImpl, DataChunk};
+use crate::binder::{BoundBinaryOp, BoundExpr, BoundInputRef};
+use crate::catalog::ColumnCatalog;
+use crate::storage::secondary::{ColumnBuilderOptions, ColumnSeekPosition};
+use crate::storage::{StorageColumnRef, StorageResult};
+use crate::types::{DataTypeExt, DataTypeKind, DataValue};
+
+pub fn path_of_secondary_index(base: impl AsRef<Path>, column_info: &ColumnCatalog) -> PathBuf {
+    path_of_column(base, column_info, ".index")
+}
+
+/// Get the columns of the secondary index on a column: the values of the column, and the ids of
+/// the rows containing them.
+pub fn index_columns(column_info: &ColumnCatalog) -> Arc<[ColumnCatalog]> {
+    vec![
+        ColumnCatalog::new(0, column_info.datatype().to_column("key".into())),
+        ColumnCatalog::new(
+            1,
+            DataTypeKind::Int(None)
+                .not_null()
+                .to_column("row_id".into()),
+        ),
+    ]
+    .into()
+}
+
+/// A secondary index on a column of a [`DiskRowset`], which maps the values of the column to the
+/// ids of the rows containing them.
+///
+/// The index is stored as a rowset of [`index_columns`] in the directory of the indexed rowset,
+/// whose rows are the non-null values of the column in ascending order. As the values are sorted,
+/// a lookup only reads the blocks of the index whose statistics may match the conditions.
+pub struct SecondaryIndex {
+    /// The rowset of the index, or `None` if the column has no non-null value.
+    rowset: Option<Arc<DiskRowset>>,
+}
+
+impl SecondaryIndex {
+    pub fn new(rowset: Option<Arc<DiskRowset>>) -> Self {
+        Self { rowset }
+    }
+
+    /// Build the index on a column of the rowset if it has not been built.
+    ///
+    /// The index is written to a temporary directory and then renamed, so that a partially
+    /// written index is never opened.
+    pub async fn build(
+        rowset: &Arc<DiskRowset>,
+        storage_column_id: usize,
+        column_options: ColumnBuilderOptions,
+    ) -> StorageResult<()> {
+        let column_info = rowset.column_info(storage_column_id);
+        let directory = path_of_secondary_index(rowset.directory(), column_info);
+        if fs::metadata(&directory).await.is_ok() {
+            return Ok(());
+        }
+
+        let mut entries = vec![];
+        let mut iter = rowset
+            .iter(
+                vec![StorageColumnRef::Idx(storage_column_id as u32)].into(),
+                vec![],
+                ColumnSeekPosition::start(),
+                None,
+            )
+            .await?;
+        let mut row_id: u32 = 0;
+        while let Some(chunk) = iter.next_batch(None).await? {
+            let array = chunk.array_at(0);
+            for idx in 0..array.len() {
+                let value = array.get(idx);
+                if value != DataValue::Null {
+                    entries.push((value, row_id));
+                }
+                row_id += 1;
+            }
+        }
+        // the stable sort keeps the rows of equal values in ascending order
+        entries.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let temp_directory = directory.with_extension("index.tmp");
+        if fs::metadata(&temp_directory).await.is_ok() {
+            fs::remove_dir_all(&temp_directory).await?;
+        }
+        fs::create_dir(&temp_directory).await?;
+        if !entries.is_empty() {
+            let columns = index_columns(column_info);
+            let mut keys = ArrayBuilderImpl::with_capacity(entries.len(), &columns[0].datatype());
+            for (value, _) in &entries {
+                keys.push(value);
+            }
+            let row_ids =
+                ArrayImpl::Int32(entries.iter().map(|(_, id)| Some(*id as i32)).collect());
+            let mut builder = RowsetBuilder::new(columns, &temp_directory, column_options);
+            builder.append([keys.finish(), row_ids].into_iter().collect::<DataChunk>());
+            builder.finish_and_flush().await?;
+        }
+        fs::rename(&temp_directory, &directory).await?;
+        Ok(())
+    }
+
+    /// Find the ids of the rows satisfying all the conditions, in ascending order. The
+    /// conditions should be got from [`index_conditions`].
+    pub async fn lookup(&self, conds: &[BoundExpr]) -> StorageResult<Vec<u32>> {
+        let rowset = match &self.rowset {
+            Some(rowset) => rowset,
+            None => return Ok(vec![]),
+        };
+        let expr = (conds.iter().cloned())
+            .reduce(|left, right| {
+                BoundExpr::BinaryOp(BoundBinaryOp {
+                    op: BinaryOperator::And,
+                    left_expr: Box::new(left),
+                    right_expr: Box::new(right),
+                    return_type: Some(DataTypeKind::Boolean.nullable()),
+                })
+            })
+            .expect("no condition to look up");
+        let mut iter = rowset
+            .iter(
+                vec![StorageColumnRef::Idx(0), StorageColumnRef::Idx(1)].into(),
+                vec![],
+                ColumnSeekPosition::start(),
+                Some(expr),
+            )
+            .await?;
+        let mut row_ids = vec![];
+        while let Some(chunk) = iter.next_batch(None).await? {
+            match chunk.to_data_chunk().array_at(1) {
+                ArrayImpl::Int32(array) => {
+                    row_ids.extend(array.iter().map(|id| *id.unwrap() as u32))
+                }
+                _ => unreachable!(),
+            }
+        }
+        row_ids.sort_unstable();
+        Ok(row_ids)
+    }
+}
+
+/// Get the conditions in the form of `column <op> constant` on a column from the filter of a
+/// scan, and rewrite them to be evaluated on the secondary index of the column.
+pub fn index_conditions(
+    expr: &BoundExpr,
+    column_refs: &[StorageColumnRef],
+    storage_column_id: usize,
+    column_info: &ColumnCatalog,
+) -> Vec<BoundExpr> {
+    let mut conds = vec![];
+    conjunctions(expr, &mut conds);
+    conds
+        .into_iter()
+        .filter_map(comparison)
+        .filter(|(index, _, _)| {
+            column_refs[*index] == StorageColumnRef::Idx(storage_column_id as u32)
+        })
+        .map(|(_, op, value)| {
+            BoundExpr::BinaryOp(BoundBinaryOp {
+                op,
+                left_expr: Box::new(BoundExpr::InputRef(BoundInputRef {
+                    index: 0,
+                    return_type: column_info.datatype(),
+                })),
+                right_expr: Box::new(BoundExpr::Constant(value.clone())),
+                return_type: Some(DataTypeKind::Boolean.nullable()),
+            })
+        })
+        .collect()
+}
@@ -10,11 +10,18 @@
 //!
 //! RowCount is NOT a precise statistics. It simply adds up the row counts of all blocks. As there
 //! might be rows deleted in deletion vector, the aggregated RowCount is not always accurate.
+//!
+//! ## MinValue and MaxValue
+//!
+//! MinValue and MaxValue are the minimum and maximum non-null values of a block, encoded in the
+//! same fixed-width format as the block data, or as raw UTF-8 bytes for varchar blocks. They are
+//! only recorded for primitive and plain varchar columns, and can be used to skip blocks on scan.
+//! The body can only be decoded with the column type, which is required by global aggregation.
 
 use risinglight_proto::rowset::block_statistics::BlockStatisticsType;
 
 use super::index::ColumnIndex;
-use crate::types::DataValue;
+use crate::types::{DataType, DataValue};
 
 mod row_count;
 use row_count::*;
@@ -22,6 +29,8 @@ mod distinct_value;
 use distinct_value::*;
 mod statistics_builder;
 pub use statistics_builder::*;
+mod min_max;
+pub use min_max::*;
 
 /// Get the aggregated statistics from pre-aggregated per-block statistics.
 pub trait StatisticsGlobalAgg {
@@ -31,9 +40,13 @@ pub trait StatisticsGlobalAgg {
 
 pub fn create_statistics_global_aggregator(
     ty: BlockStatisticsType,
+    datatype: &DataType,
 ) -> Box<dyn StatisticsGlobalAgg> {
     match ty {
         BlockStatisticsType::RowCount => Box::new(RowCountGlobalAgg::create()),
         BlockStatisticsType::DistinctValue => Box::new(DistinctValueGlobalAgg::create()),
+        BlockStatisticsType::MinValue | BlockStatisticsType::MaxValue => {
+            Box::new(MinMaxGlobalAgg::create(ty, datatype.kind()))
+        }
     }
 }
@@ -0,0 +1,247 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use risinglight_proto::rowset::block_statistics::BlockStatisticsType;
+use risinglight_proto::rowset::BlockStatistics;
+use rust_decimal::Decimal;
+
+use super::StatisticsGlobalAgg;
+use crate::storage::secondary::index::ColumnIndex;
+use crate::storage::secondary::PrimitiveFixedWidthEncode;
+use crate::types::{DataTypeKind, DataValue, Date, Interval, Timestamp};
+
+/// Builds min and max statistics of a block of primitive values. Null values are ignored.
+pub struct MinMaxStatisticsBuilder<T: PrimitiveFixedWidthEncode> {
+    min: Option<T>,
+    max: Option<T>,
+}
+
+impl<T: PrimitiveFixedWidthEncode> MinMaxStatisticsBuilder<T> {
+    pub fn new() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn add_item(&mut self, item: Option<T>) {
+        if let Some(item) = item {
+            if self.min.map_or(true, |min| item < min) {
+                self.min = Some(item);
+            }
+            if self.max.map_or(true, |max| item > max) {
+                self.max = Some(item);
+            }
+        }
+    }
+
+    /// Get min and max statistics. The body of each statistics is the fixed-width encoding of the
+    /// value. Returns nothing if there is no non-null item.
+    pub fn get_statistics(self) -> Vec<BlockStatistics> {
+        let encode = |block_stat_type: BlockStatisticsType, value: T| {
+            let mut body = vec![];
+            value.encode(&mut body);
+            BlockStatistics {
+                block_stat_type: block_stat_type as i32,
+                body,
+            }
+        };
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => vec![
+                encode(BlockStatisticsType::MinValue, min),
+                encode(BlockStatisticsType::MaxValue, max),
+            ],
+            _ => vec![],
+        }
+    }
+}
+
+/// Builds min and max statistics of a block of variable-length values, such as strings. The body
+/// of each statistics is the raw bytes of the value. Null values are ignored.
+pub struct BytesMinMaxStatisticsBuilder<'a> {
+    min: Option<&'a [u8]>,
+    max: Option<&'a [u8]>,
+}
+
+impl<'a> BytesMinMaxStatisticsBuilder<'a> {
+    pub fn new() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn add_item(&mut self, item: Option<&'a [u8]>) {
+        if let Some(item) = item {
+            if self.min.map_or(true, |min| item < min) {
+                self.min = Some(item);
+            }
+            if self.max.map_or(true, |max| item > max) {
+                self.max = Some(item);
+            }
+        }
+    }
+
+    /// Get min and max statistics. Returns nothing if there is no non-null item.
+    pub fn get_statistics(self) -> Vec<BlockStatistics> {
+        let stat = |block_stat_type: BlockStatisticsType, value: &[u8]| BlockStatistics {
+            block_stat_type: block_stat_type as i32,
+            body: value.to_vec(),
+        };
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => vec![
+                stat(BlockStatisticsType::MinValue, min),
+                stat(BlockStatisticsType::MaxValue, max),
+            ],
+            _ => vec![],
+        }
+    }
+}
+
+/// Gather the minimum or maximum value of a column from the min and max statistics of blocks.
+/// Deleted rows are not excluded, so the value may be out of the range of the visible rows.
+pub struct MinMaxGlobalAgg {
+    ty: BlockStatisticsType,
+    datatype: DataTypeKind,
+    value: Option<DataValue>,
+}
+
+impl MinMaxGlobalAgg {
+    pub fn create(ty: BlockStatisticsType, datatype: DataTypeKind) -> Self {
+        Self {
+            ty,
+            datatype,
+            value: None,
+        }
+    }
+}
+
+impl StatisticsGlobalAgg for MinMaxGlobalAgg {
+    fn apply_batch(&mut self, index: &ColumnIndex) {
+        for block_index in index.indexes() {
+            for stat in &block_index.stats {
+                if stat.block_stat_type() != self.ty {
+                    continue;
+                }
+                let value = match decode_min_max(&self.datatype, &stat.body, index.version()) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let replace = match &self.value {
+                    None => true,
+                    Some(current) if self.ty == BlockStatisticsType::MinValue => value < *current,
+                    Some(current) => value > *current,
+                };
+                if replace {
+                    self.value = Some(value);
+                }
+            }
+        }
+    }
+
+    fn get_output(&self) -> DataValue {
+        self.value.clone().unwrap_or(DataValue::Null)
+    }
+}
+
+/// Decode the body of min or max statistics written with index format `version`.
+fn decode_min_max(datatype: &DataTypeKind, mut body: &[u8], version: u32) -> Option<DataValue> {
+    Some(match datatype {
+        DataTypeKind::Boolean => DataValue::Bool(bool::decode_of_version(&mut body, version)),
+        DataTypeKind::Int(_) => DataValue::Int32(i32::decode_of_version(&mut body, version)),
+        DataTypeKind::Float(_) | DataTypeKind::Double => {
+            DataValue::Float64(f64::decode_of_version(&mut body, version))
+        }
+        DataTypeKind::Decimal(_, _) => {
+            DataValue::Decimal(Decimal::decode_of_version(&mut body, version))
+        }
+        DataTypeKind::Date => DataValue::Date(Date::decode_of_version(&mut body, version)),
+        DataTypeKind::Timestamp => {
+            DataValue::Timestamp(Timestamp::decode_of_version(&mut body, version))
+        }
+        DataTypeKind::Interval => {
+            DataValue::Interval(Interval::decode_of_version(&mut body, version))
+        }
+        DataTypeKind::Char(_) | DataTypeKind::Varchar(_) => {
+            DataValue::String(String::from_utf8(body.to_vec()).ok()?)
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Buf;
+
+    use super::*;
+
+    #[test]
+    fn test_min_max() {
+        let mut builder = MinMaxStatisticsBuilder::<i32>::new();
+        builder.add_item(Some(3));
+        builder.add_item(None);
+        builder.add_item(Some(-1));
+        builder.add_item(Some(2));
+        let stats = builder.get_statistics();
+        assert_eq!(
+            stats[0].block_stat_type,
+            BlockStatisticsType::MinValue as i32
+        );
+        assert_eq!((&stats[0].body[..]).get_i32_le(), -1);
+        assert_eq!(
+            stats[1].block_stat_type,
+            BlockStatisticsType::MaxValue as i32
+        );
+        assert_eq!((&stats[1].body[..]).get_i32_le(), 3);
+
+        let mut builder = MinMaxStatisticsBuilder::<i32>::new();
+        builder.add_item(None);
+        assert!(builder.get_statistics().is_empty());
+    }
+
+    #[test]
+    fn test_bytes_min_max() {
+        let mut builder = BytesMinMaxStatisticsBuilder::new();
+        for item in [
+            Some("banana"),
+            None,
+            Some(""),
+            Some("cherry"),
+            Some("apple"),
+        ] {
+            builder.add_item(item.map(str::as_bytes));
+        }
+        let stats = builder.get_statistics();
+        assert_eq!(stats[0].body, b"");
+        assert_eq!(stats[1].body, b"cherry");
+    }
+
+    #[test]
+    fn test_min_max_global_agg() {
+        use risinglight_proto::rowset::block_checksum::ChecksumType;
+        use risinglight_proto::rowset::BlockIndex;
+
+        use crate::storage::secondary::IndexBuilder;
+
+        let mut index_builder = IndexBuilder::new(ChecksumType::None, 0);
+        for items in [[Some(3), Some(5)], [None, None], [Some(-2), Some(4)]] {
+            let mut builder = MinMaxStatisticsBuilder::<i32>::new();
+            for item in items {
+                builder.add_item(item);
+            }
+            index_builder.append(BlockIndex {
+                stats: builder.get_statistics(),
+                ..Default::default()
+            });
+        }
+        let index = ColumnIndex::from_bytes(&index_builder.finish()).unwrap();
+
+        let datatype = DataTypeKind::Int(None);
+        let mut min = MinMaxGlobalAgg::create(BlockStatisticsType::MinValue, datatype.clone());
+        let mut max = MinMaxGlobalAgg::create(BlockStatisticsType::MaxValue, datatype);
+        assert_eq!(min.get_output(), DataValue::Null);
+        min.apply_batch(&index);
+        max.apply_batch(&index);
+        assert_eq!(min.get_output(), DataValue::Int32(-2));
+        assert_eq!(max.get_output(), DataValue::Int32(5));
+    }
+}
@@ -1,17 +1,20 @@
 // Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::atomic::{AtomicU32, AtomicU64};
 use std::sync::Arc;
 
-use moka::future::Cache;
 use parking_lot::RwLock;
 use tokio::fs;
 use tokio::sync::Mutex;
 use tracing::info;
 
-use super::{DiskRowset, Manifest, SecondaryStorage, StorageOptions, StorageResult};
-use crate::catalog::RootCatalog;
+use super::{
+    path_of_data_column, path_of_index_column, BlockCache, ColumnIndex, DiskRowset, Manifest,
+    SecondaryStorage, StorageOptions, StorageResult, TracedStorageError,
+};
+use crate::catalog::{ColumnCatalog, RootCatalog};
 use crate::storage::secondary::manifest::*;
 use crate::storage::secondary::transaction_manager::TransactionManager;
 use crate::storage::secondary::version_manager::{EpochOp, VersionManager};
@@ -43,7 +46,7 @@ impl SecondaryStorage {
         let engine = Self {
             catalog: Arc::new(catalog),
             tables: RwLock::new(tables),
-            block_cache: Cache::new(options.cache_size as u64),
+            block_cache: BlockCache::new(options.cache_size),
             options: options.clone(),
             next_id: Arc::new((AtomicU32::new(0), AtomicU64::new(0))),
             version: Arc::new(VersionManager::new(manifest, options.clone())),
@@ -64,6 +67,21 @@ impl SecondaryStorage {
                 }
                 ManifestOperation::DropTable(entry) => {
                     engine.apply_drop_table(&entry)?;
+                    let table_id = entry.table_id.table_id;
+                    rowsets_to_open.retain(|(id, _), _| *id != table_id);
+                    dvs_to_open.retain(|(id, _, _), _| *id != table_id);
+                }
+                ManifestOperation::AddColumn(entry) => {
+                    engine.apply_add_column(&entry)?;
+                }
+                ManifestOperation::TruncateTable(entry) => {
+                    engine.apply_truncate_table(&entry)?;
+                    let table_id = entry.table_id.table_id;
+                    rowsets_to_open.retain(|(id, _), _| *id != table_id);
+                    dvs_to_open.retain(|(id, _, _), _| *id != table_id);
+                }
+                ManifestOperation::CreateIndex(entry) => {
+                    engine.apply_create_index(&entry)?;
                 }
                 ManifestOperation::AddRowSet(entry) => {
                     engine
@@ -129,15 +147,17 @@ impl SecondaryStorage {
 
         for (_, entry) in rowsets_to_open {
             let table = tables.get(&entry.table_id).unwrap();
+            let path = table.get_rowset_path(entry.rowset_id);
+            let columns = validate_rowset_files(&path, &table.columns).await?;
             let disk_rowset = DiskRowset::open(
-                table.get_rowset_path(entry.rowset_id),
-                table.columns.clone(),
+                path,
+                table.columns[..columns].into(),
                 engine.block_cache.clone(),
                 entry.rowset_id,
                 options.io_backend,
             )
             .await?;
-            changeset.push(EpochOp::AddRowSet((entry, disk_rowset)));
+            changeset.push(EpochOp::AddRowSet((entry, Arc::new(disk_rowset))));
         }
 
         for (_, entry) in dvs_to_open {
@@ -160,3 +180,305 @@ impl SecondaryStorage {
         Ok(engine)
     }
 }
+
+/// Check that the data and index files of all columns in a rowset are complete, and return the
+/// number of columns stored in the rowset.
+///
+/// A rowset written before some columns are added by `ALTER TABLE` doesn't contain the files of
+/// these columns, which always have a default value and come after the original ones.
+///
+/// Files may be missing or truncated if the process crashes while flushing the rowset, in which
+/// case [`StorageError::IncompleteRowset`](crate::storage::StorageError::IncompleteRowset) is
+/// returned instead of serving corrupt data. The checksum of each index is verified, and the
+/// blocks it refers to should be within the data file.
+async fn validate_rowset_files(path: &Path, columns: &[ColumnCatalog]) -> StorageResult<usize> {
+    let incomplete = |reason: String| TracedStorageError::incomplete_rowset(path.display(), reason);
+    for (idx, column) in columns.iter().enumerate() {
+        let data_file = path_of_data_column(path, column);
+        let index_file = path_of_index_column(path, column);
+        let data_size = fs::metadata(&data_file).await.ok().map(|meta| meta.len());
+        let index_exists = fs::metadata(&index_file).await.is_ok();
+        if data_size.is_none() && !index_exists && idx > 0 && column.desc().default().is_some() {
+            return Ok(idx);
+        }
+        let data_size = match data_size {
+            Some(size) => size,
+            None => return Err(incomplete(format!("missing {}", data_file.display()))),
+        };
+        if !index_exists {
+            return Err(incomplete(format!("missing {}", index_file.display())));
+        }
+        let index = ColumnIndex::from_bytes(&fs::read(&index_file).await?).map_err(|err| {
+            incomplete(format!("invalid {}: {}", index_file.display(), err.inner()))
+        })?;
+        let end = (index.indexes().iter())
+            .map(|index| index.offset + index.length)
+            .max()
+            .unwrap_or(0);
+        if end > data_size {
+            return Err(incomplete(format!(
+                "{} has {} bytes, but the blocks end at {}",
+                data_file.display(),
+                data_size,
+                end
+            )));
+        }
+    }
+    Ok(columns.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use risinglight_proto::rowset::block_checksum::ChecksumType;
+
+    use super::*;
+    use crate::storage::{SecondaryStorageOptions, StorageError};
+    use crate::Database;
+
+    async fn count(db: &Database) -> String {
+        let chunks = db.run("select count(*) from t").await.unwrap();
+        chunks[0].array_at(0).get_to_string(0)
+    }
+
+    #[tokio::test]
+    async fn test_recover_from_interrupted_flush() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let options = || SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf());
+
+        let db = Database::new_on_disk(options()).await;
+        db.run("create table t(v int)").await.unwrap();
+        db.run("insert into t values (1), (2), (3)").await.unwrap();
+        db.shutdown().await.unwrap();
+
+        // simulate a crash while flushing a rowset: its files are written, but the manifest
+        // entries are cut off in the middle
+        let rowset = std::fs::read_dir(tempdir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.is_dir() && path.file_name().unwrap() != "dv")
+            .unwrap();
+        let name = rowset.file_name().unwrap().to_str().unwrap().to_string();
+        let (table_id, _) = name.split_once('_').unwrap();
+        let orphan = tempdir.path().join(format!("{}_{}", table_id, 1000));
+        std::fs::create_dir(&orphan).unwrap();
+        std::fs::write(orphan.join("0.col"), b"garbage").unwrap();
+        let manifest = tempdir.path().join("manifest.json");
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&manifest)
+            .unwrap();
+        file.write_all(br#""Begin"{"AddRowSet":{"table_id":{"database_id"#)
+            .unwrap();
+        drop(file);
+
+        // the incomplete entries and the orphan rowset are discarded
+        let db = Database::new_on_disk(options()).await;
+        assert_eq!(count(&db).await, "3");
+        assert!(!orphan.exists());
+
+        // the incomplete tail is truncated, so later entries are still replayed
+        db.run("insert into t values (4)").await.unwrap();
+        db.shutdown().await.unwrap();
+        let db = Database::new_on_disk(options()).await;
+        assert_eq!(count(&db).await, "4");
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recover_with_missing_rowset_files() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let options = || SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf());
+
+        let db = Database::new_on_disk(options()).await;
+        db.run("create table t(v int)").await.unwrap();
+        db.run("insert into t values (1), (2), (3)").await.unwrap();
+        db.shutdown().await.unwrap();
+
+        for rowset in std::fs::read_dir(tempdir.path()).unwrap() {
+            let rowset = rowset.unwrap().path();
+            if rowset.is_dir() && rowset.file_name().unwrap() != "dv" {
+                for file in std::fs::read_dir(rowset).unwrap() {
+                    let file = file.unwrap().path();
+                    if file.extension().map_or(false, |ext| ext == "idx") {
+                        std::fs::remove_file(file).unwrap();
+                    }
+                }
+            }
+        }
+
+        let err = SecondaryStorage::open(options()).await.err().unwrap();
+        assert!(matches!(err.inner(), StorageError::IncompleteRowset(_, _)));
+    }
+
+    #[tokio::test]
+    async fn test_reject_incomplete_rowset() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let options = || SecondaryStorageOptions {
+            checksum_type: ChecksumType::Crc32,
+            ..SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf())
+        };
+
+        let db = Database::new_on_disk(options()).await;
+        db.run("create table t(a int, b int)").await.unwrap();
+        db.run("insert into t values (1, 10), (2, 20), (3, 30)")
+            .await
+            .unwrap();
+        db.shutdown().await.unwrap();
+
+        let rowset = std::fs::read_dir(tempdir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.is_dir() && path.file_name().unwrap() != "dv")
+            .unwrap();
+        let files = |ext: &str| {
+            let mut files = std::fs::read_dir(&rowset)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|file| file.extension().map_or(false, |e| e == ext))
+                .collect::<Vec<_>>();
+            files.sort();
+            files
+        };
+        let is_incomplete = |result: StorageResult<SecondaryStorage>| {
+            matches!(
+                result.err().unwrap().inner(),
+                StorageError::IncompleteRowset(_, _)
+            )
+        };
+
+        // a truncated data file
+        let data_file = files("col")[0].clone();
+        let data = std::fs::read(&data_file).unwrap();
+        std::fs::write(&data_file, &data[..data.len() / 2]).unwrap();
+        assert!(is_incomplete(SecondaryStorage::open(options()).await));
+        std::fs::write(&data_file, &data).unwrap();
+
+        // an index whose checksum mismatches
+        let index_file = files("idx")[1].clone();
+        let index = std::fs::read(&index_file).unwrap();
+        let mut corrupted = index.clone();
+        corrupted[0] ^= 0xff;
+        std::fs::write(&index_file, &corrupted).unwrap();
+        assert!(is_incomplete(SecondaryStorage::open(options()).await));
+        // or is truncated
+        std::fs::write(&index_file, &index[..4]).unwrap();
+        assert!(is_incomplete(SecondaryStorage::open(options()).await));
+        std::fs::write(&index_file, &index).unwrap();
+
+        // the rowset is accepted once its files are restored
+        let db = Database::new_on_disk(options()).await;
+        assert_eq!(count(&db).await, "3");
+        db.shutdown().await.unwrap();
+
+        // a missing index file of one column
+        std::fs::remove_file(&index_file).unwrap();
+        assert!(is_incomplete(SecondaryStorage::open(options()).await));
+    }
+
+    #[tokio::test]
+    async fn test_drop_table_removes_files() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let options = || SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf());
+        let rowsets = || {
+            std::fs::read_dir(tempdir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.is_dir() && path.file_name().unwrap() != "dv")
+                .count()
+        };
+
+        let db = Database::new_on_disk(options()).await;
+        db.run("create table t(v int)").await.unwrap();
+        db.run("insert into t values (1), (2), (3)").await.unwrap();
+        assert_eq!(rowsets(), 1);
+        db.run("drop table t").await.unwrap();
+        db.run("drop table if exists t").await.unwrap();
+        assert!(db.run("drop table t").await.is_err());
+        db.shutdown().await.unwrap();
+
+        // the rowsets of the dropped table are vacuumed
+        let db = Database::new_on_disk(options()).await;
+        assert_eq!(rowsets(), 0);
+
+        // a table with the same name can be created again
+        db.run("create table t(v int)").await.unwrap();
+        db.run("insert into t values (4), (5)").await.unwrap();
+        assert_eq!(count(&db).await, "2");
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_truncate_table_removes_files() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let options = || SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf());
+        let rowsets = || {
+            std::fs::read_dir(tempdir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.is_dir() && path.file_name().unwrap() != "dv")
+                .count()
+        };
+
+        let db = Database::new_on_disk(options()).await;
+        db.run("create table t(v int)").await.unwrap();
+        db.run("insert into t values (1), (2), (3)").await.unwrap();
+        db.run("delete from t where v = 1").await.unwrap();
+        db.run("truncate table t").await.unwrap();
+        assert_eq!(count(&db).await, "0");
+        db.run("insert into t values (4), (5)").await.unwrap();
+        assert_eq!(count(&db).await, "2");
+        db.shutdown().await.unwrap();
+
+        // only the rowset inserted after truncation is kept
+        let db = Database::new_on_disk(options()).await;
+        assert_eq!(rowsets(), 1);
+        assert_eq!(count(&db).await, "2");
+        db.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_add_column_to_existing_rowsets() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let options = || SecondaryStorageOptions::default_for_test(tempdir.path().to_path_buf());
+        let rows = |chunks: Vec<crate::array::DataChunk>| {
+            let mut rows = vec![];
+            for chunk in chunks {
+                for i in 0..chunk.cardinality() {
+                    rows.push(
+                        (0..chunk.column_count())
+                            .map(|j| chunk.array_at(j).get_to_string(i))
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    );
+                }
+            }
+            rows
+        };
+
+        let db = Database::new_on_disk(options()).await;
+        db.run("create table t(v int)").await.unwrap();
+        db.run("insert into t values (1), (2)").await.unwrap();
+        db.run("alter table t add column c int default 5")
+            .await
+            .unwrap();
+        db.run("insert into t values (3, 6)").await.unwrap();
+        db.run("insert into t(v) values (4)").await.unwrap();
+        let expected = vec!["1 5", "2 5", "3 6", "4 5"];
+        let chunks = db.run("select * from t order by v").await.unwrap();
+        assert_eq!(rows(chunks), expected);
+        db.shutdown().await.unwrap();
+
+        // the old rowsets still lack the column after recovery
+        let db = Database::new_on_disk(options()).await;
+        let chunks = db.run("select * from t order by v").await.unwrap();
+        assert_eq!(rows(chunks), expected);
+        let chunks = db
+            .run("select v from t where c = 5 order by v")
+            .await
+            .unwrap();
+        assert_eq!(rows(chunks), vec!["1", "2", "4"]);
+        db.shutdown().await.unwrap();
+    }
+}
@@ -5,8 +5,6 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicU32, AtomicU64};
 use std::sync::Arc;
 
-use moka::future::Cache;
-
 use super::*;
 use crate::catalog::TableRefId;
 use crate::storage::Table;
@@ -27,6 +25,9 @@ pub struct SecondaryTable {
     /// Mapping from [`ColumnId`] to column index in `columns`.
     pub column_map: HashMap<ColumnId, usize>,
 
+    /// Columns with a secondary index, which is built for each new rowset.
+    pub indexed_columns: Vec<ColumnId>,
+
     /// Root directory of the storage
     pub storage_options: Arc<StorageOptions>,
 
@@ -39,7 +40,7 @@ pub struct SecondaryTable {
 
     /// Block cache of the storage engine. Note that this should be removed after we have
     /// refactored the storage API to have snapshot interface.
-    pub block_cache: Cache<BlockCacheKey, Block>,
+    pub block_cache: BlockCache,
 
     /// Next RowSet Id and DV Id of the current storage engine
     next_id: Arc<(AtomicU32, AtomicU64)>,
@@ -52,7 +53,7 @@ impl SecondaryTable {
         columns: &[ColumnCatalog],
         next_id: Arc<(AtomicU32, AtomicU64)>,
         version: Arc<VersionManager>,
-        block_cache: Cache<BlockCacheKey, Block>,
+        block_cache: BlockCache,
         txn_mgr: Arc<TransactionManager>,
     ) -> Self {
         Self {
@@ -62,6 +63,7 @@ impl SecondaryTable {
                 .enumerate()
                 .map(|(idx, col)| (col.id(), idx))
                 .collect(),
+            indexed_columns: vec![],
             table_ref_id,
             storage_options,
             next_id,
@@ -102,6 +104,20 @@ impl SecondaryTable {
     pub async fn lock_for_deletion(&self) -> TransactionLock {
         self.txn_mgr.lock_for_deletion(self.table_id()).await
     }
+
+    /// Build the secondary indexes of the table on a new rowset.
+    pub async fn build_indexes(&self, rowset: &Arc<DiskRowset>) -> StorageResult<()> {
+        for column_id in &self.indexed_columns {
+            let storage_column_id = self.column_map[column_id];
+            SecondaryIndex::build(
+                rowset,
+                storage_column_id,
+                ColumnBuilderOptions::from_storage_options(&self.storage_options),
+            )
+            .await?;
+        }
+        Ok(())
+    }
 }
 
 impl Table for SecondaryTable {
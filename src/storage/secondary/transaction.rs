@@ -11,16 +11,17 @@ use tracing::{info, warn};
 
 use super::version_manager::{Snapshot, VersionManager};
 use super::{
-    AddDVEntry, AddRowSetEntry, ColumnBuilderOptions, ColumnSeekPosition, ConcatIterator,
-    DeleteVector, DiskRowset, EpochOp, MergeIterator, RowSetIterator, SecondaryMemRowsetImpl,
-    SecondaryRowHandler, SecondaryTable, SecondaryTableTxnIterator, TransactionLock,
+    index_conditions, AddDVEntry, AddRowSetEntry, ColumnBuilderOptions, ColumnMemTable,
+    ColumnSeekPosition, ConcatIterator, DeleteVector, DiskRowset, EpochOp, MemTable, MergeIterator,
+    RowSetIterator, SecondaryIndex, SecondaryMemRowsetImpl, SecondaryRowHandler, SecondaryTable,
+    SecondaryTableTxnIterator, TransactionLock,
 };
 use crate::array::DataChunk;
 use crate::binder::BoundExpr;
-use crate::catalog::find_sort_key_id;
+use crate::catalog::find_sort_key_ids;
 use crate::storage::secondary::statistics::create_statistics_global_aggregator;
-use crate::storage::{StorageColumnRef, StorageResult, Transaction};
-use crate::types::DataValue;
+use crate::storage::{ScanOptions, StorageColumnRef, StorageResult, Transaction, TxnIterator};
+use crate::types::{ColumnId, DataValue};
 
 /// A transaction running on `SecondaryStorage`.
 pub struct SecondaryTransaction {
@@ -32,6 +33,13 @@ pub struct SecondaryTransaction {
     /// Includes all to-be-committed data.
     mem: Option<SecondaryMemRowsetImpl>,
 
+    /// Rows appended but not yet handed to `mem`. Small chunks are accumulated here, so that
+    /// `mem` receives chunks of `target_chunk_rows` rows.
+    append_buffer: Option<ColumnMemTable>,
+
+    /// Number of rows in `append_buffer`
+    buffered_rows: usize,
+
     /// Includes all to-be-deleted rows
     delete_buffer: Vec<SecondaryRowHandler>,
 
@@ -48,16 +56,11 @@ pub struct SecondaryTransaction {
     epoch: u64,
 
     /// The rowsets produced in the txn.
-    to_be_committed_rowsets: Vec<DiskRowset>,
+    to_be_committed_rowsets: Vec<Arc<DiskRowset>>,
 
     delete_lock: Option<TransactionLock>,
 
     read_only: bool,
-
-    /// Total size of written data in the current txn
-    ///
-    /// TODO: we only calculate batch insert here. Need to estimate delete vector size.
-    total_size: usize,
 }
 
 impl SecondaryTransaction {
@@ -74,6 +77,8 @@ impl SecondaryTransaction {
         Ok(Self {
             finished: false,
             mem: None,
+            append_buffer: None,
+            buffered_rows: 0,
             delete_buffer: vec![],
             table: table.clone(),
             version: table.version.clone(),
@@ -86,11 +91,15 @@ impl SecondaryTransaction {
             },
             to_be_committed_rowsets: vec![],
             read_only,
-            total_size: 0,
         })
     }
 
     async fn flush_rowset(&mut self) -> StorageResult<()> {
+        self.flush_append_buffer().await?;
+        self.flush_mem_rowset().await
+    }
+
+    async fn flush_mem_rowset(&mut self) -> StorageResult<()> {
         // only flush when we have memtables
         let mem = if let Some(mem) = self.mem.take() {
             mem
@@ -111,6 +120,8 @@ impl SecondaryTransaction {
             self.table.storage_options.io_backend,
         )
         .await?;
+        let on_disk = Arc::new(on_disk);
+        self.table.build_indexes(&on_disk).await?;
 
         self.to_be_committed_rowsets.push(on_disk);
 
@@ -196,10 +207,15 @@ impl SecondaryTransaction {
         begin_sort_key: Option<&[u8]>,
         end_sort_key: Option<&[u8]>,
         col_idx: &[StorageColumnRef],
-        is_sorted: bool,
-        reversed: bool,
-        expr: Option<BoundExpr>,
+        options: ScanOptions,
     ) -> StorageResult<SecondaryTableTxnIterator> {
+        let ScanOptions {
+            is_sorted,
+            reversed,
+            expr,
+            mut row_offset,
+            row_limit,
+        } = options;
         assert!(
             begin_sort_key.is_none(),
             "sort_key is not supported in SecondaryEngine for now"
@@ -212,46 +228,66 @@ impl SecondaryTransaction {
 
         let mut iters: Vec<RowSetIterator> = vec![];
 
-        if let Some(rowsets) = self.snapshot.get_rowsets_of(self.table.table_id()) {
-            for rowset_id in rowsets {
-                let rowset = self.version.get_rowset(self.table.table_id(), *rowset_id);
+        // If rowsets are concatenated and no row is filtered, the number of rows in each rowset is
+        // known from block indexes. Until we meet a rowset with deletes, leading rows are skipped
+        // without reading blocks, and rowsets after the limit are not scanned.
+        let mut by_row_count = !is_sorted && expr.is_none();
+        let mut rows_to_scan = row_limit;
 
-                // Get DV id and read DVs
-                let dvs = self
-                    .snapshot
-                    .get_dvs_of(self.table.table_id(), *rowset_id)
-                    .map(|dvs| {
-                        dvs.iter()
-                            .map(|dv_id| self.version.get_dv(self.table.table_id(), *dv_id))
-                            .collect_vec()
-                    })
-                    .unwrap_or_default();
+        for (rowset, dvs) in self.visible_rowsets() {
+            let mut seek_pos = ColumnSeekPosition::start();
+            let mut reach_limit = false;
+            by_row_count &= dvs.is_empty();
+            if by_row_count {
+                let row_count = rowset.row_count().await?;
+                if row_offset >= row_count {
+                    row_offset -= row_count;
+                    continue;
+                }
+                seek_pos = ColumnSeekPosition::RowId(row_offset as u32);
+                if let Some(limit) = &mut rows_to_scan {
+                    let rows = row_count - row_offset;
+                    reach_limit = rows >= *limit;
+                    *limit = limit.saturating_sub(rows);
+                }
+                row_offset = 0;
+            }
 
-                iters.push(
-                    rowset
-                        .iter(
-                            col_idx.into(),
-                            dvs,
-                            ColumnSeekPosition::start(),
-                            expr.clone(),
-                        )
-                        .await?,
-                )
+            iters.push(
+                rowset
+                    .iter_with_columns(
+                        self.table.columns.clone(),
+                        col_idx.into(),
+                        dvs,
+                        seek_pos,
+                        expr.clone(),
+                    )
+                    .await?,
+            );
+            if reach_limit {
+                break;
             }
         }
 
         let final_iter = if iters.len() == 1 {
             iters.pop().unwrap().into()
         } else if is_sorted {
-            let sort_key = find_sort_key_id(&self.table.columns);
-            if let Some(sort_key) = sort_key {
-                let real_col_idx = col_idx.iter().position(|x| match x {
-                    StorageColumnRef::Idx(y) => *y as usize == sort_key,
-                    _ => false,
-                });
+            let sort_key = find_sort_key_ids(&self.table.columns);
+            if !sort_key.is_empty() {
+                let real_col_idx = (sort_key.iter())
+                    .map(|&sort_key| {
+                        col_idx
+                            .iter()
+                            .position(|x| match x {
+                                StorageColumnRef::Idx(y) => *y as usize == sort_key,
+                                _ => false,
+                            })
+                            .expect("sort key not in column list")
+                    })
+                    .collect();
                 MergeIterator::new(
                     iters.into_iter().map(|iter| iter.into()).collect_vec(),
-                    real_col_idx.expect("sort key not in column list"),
+                    real_col_idx,
                 )
                 .into()
             } else {
@@ -261,43 +297,311 @@ impl SecondaryTransaction {
             ConcatIterator::new(iters).into()
         };
 
-        Ok(SecondaryTableTxnIterator::new(final_iter))
+        Ok(SecondaryTableTxnIterator::new(
+            final_iter, row_offset, row_limit,
+        ))
+    }
+
+    /// Split an unsorted scan into at most `parallelism` scans of disjoint rowsets, which can be
+    /// run by different tasks. Each scan covers consecutive rowsets with roughly the same number
+    /// of rows, so concatenating their outputs in order gives the output of a single scan.
+    ///
+    /// The returned iterators hold the rowsets they read, and must be drained before the
+    /// transaction is committed or aborted to keep the rowsets from being vacuumed.
+    pub async fn scan_partitions(
+        &self,
+        col_idx: &[StorageColumnRef],
+        expr: Option<BoundExpr>,
+        parallelism: usize,
+    ) -> StorageResult<Vec<SecondaryTableTxnIterator>> {
+        let rowsets = self.visible_rowsets();
+        let mut total_rows = 0;
+        let mut row_counts = Vec::with_capacity(rowsets.len());
+        for (rowset, _) in &rowsets {
+            let row_count = rowset.row_count().await?;
+            total_rows += row_count;
+            row_counts.push(row_count);
+        }
+
+        let mut partitions = vec![];
+        let mut iters = vec![];
+        let mut rows = 0;
+        for ((rowset, dvs), row_count) in rowsets.into_iter().zip(row_counts) {
+            iters.push(
+                rowset
+                    .iter_with_columns(
+                        self.table.columns.clone(),
+                        col_idx.into(),
+                        dvs,
+                        ColumnSeekPosition::start(),
+                        expr.clone(),
+                    )
+                    .await?,
+            );
+            // close the partition once it reaches its share of the total rows
+            rows += row_count;
+            if partitions.len() + 1 < parallelism
+                && rows * parallelism >= (partitions.len() + 1) * total_rows
+            {
+                partitions.push(std::mem::take(&mut iters));
+            }
+        }
+        if !iters.is_empty() {
+            partitions.push(iters);
+        }
+
+        Ok(partitions
+            .into_iter()
+            .map(|mut iters| {
+                let iter = if iters.len() == 1 {
+                    iters.pop().unwrap().into()
+                } else {
+                    ConcatIterator::new(iters).into()
+                };
+                SecondaryTableTxnIterator::new(iter, 0, None)
+            })
+            .collect())
+    }
+
+    /// Scan the table with the filter `expr`, using the secondary index on the column to only
+    /// read the blocks containing the rows satisfying the conditions on it. Rowsets without the
+    /// index, e.g. the rowsets written before the index is created, are scanned with the filter.
+    pub async fn index_scan(
+        &self,
+        col_idx: &[StorageColumnRef],
+        column_id: ColumnId,
+        expr: BoundExpr,
+        row_offset: usize,
+        row_limit: Option<usize>,
+    ) -> StorageResult<SecondaryTableTxnIterator> {
+        let storage_column_id = self.table.column_map[&column_id];
+        let conds = index_conditions(
+            &expr,
+            col_idx,
+            storage_column_id,
+            &self.table.columns[storage_column_id],
+        );
+
+        let mut iters: Vec<RowSetIterator> = vec![];
+        for (rowset, dvs) in self.visible_rowsets() {
+            // the column may be added after the rowset is written
+            let index = if storage_column_id < rowset.column_count() && !conds.is_empty() {
+                rowset.secondary_index(storage_column_id).await?
+            } else {
+                None
+            };
+            let mut iter = rowset
+                .iter_with_columns(
+                    self.table.columns.clone(),
+                    col_idx.into(),
+                    dvs,
+                    ColumnSeekPosition::start(),
+                    Some(expr.clone()),
+                )
+                .await?;
+            if let Some(index) = index {
+                iter.restrict_to_rows(&index.lookup(&conds).await?);
+            }
+            iters.push(iter);
+        }
+
+        let iter = if iters.len() == 1 {
+            iters.pop().unwrap().into()
+        } else {
+            ConcatIterator::new(iters).into()
+        };
+        Ok(SecondaryTableTxnIterator::new(iter, row_offset, row_limit))
+    }
+
+    /// Build the secondary index on a column of the visible rowsets.
+    pub(super) async fn build_index(
+        &self,
+        storage_column_id: usize,
+        column_options: ColumnBuilderOptions,
+    ) -> StorageResult<()> {
+        for (rowset, _) in self.visible_rowsets() {
+            // the column may be added after the rowset is written
+            if storage_column_id < rowset.column_count() {
+                SecondaryIndex::build(&rowset, storage_column_id, column_options.clone()).await?;
+            }
+        }
+        Ok(())
     }
 
     /// Aggregate block statistics of one column. In the future, we might support predicate
     /// push-down, and this function will add filter-scan-aggregate functionality.
     ///
-    /// This function can gather multiple statistics at a time (in the future).
-    pub fn aggreagate_block_stat(
+    /// This function can gather multiple statistics at a time (in the future). The columns
+    /// without block statistics, e.g. the row handler, are aggregated by a normal scan.
+    pub async fn aggreagate_block_stat(
         &self,
         ty: &[(BlockStatisticsType, StorageColumnRef)],
-    ) -> Vec<DataValue> {
+    ) -> StorageResult<Vec<DataValue>> {
         let mut agg = ty
             .iter()
-            .map(|(ty, _)| create_statistics_global_aggregator(*ty))
+            .map(|(ty, col_idx)| match col_idx {
+                StorageColumnRef::Idx(idx) => Some(create_statistics_global_aggregator(
+                    *ty,
+                    &self.table.columns[*idx as usize].datatype(),
+                )),
+                _ => None,
+            })
             .collect_vec();
 
         if let Some(rowsets) = self.snapshot.get_rowsets_of(self.table.table_id()) {
             for rowset_id in rowsets {
                 let rowset = self.version.get_rowset(self.table.table_id(), *rowset_id);
                 for ((_, col_idx), agg) in ty.iter().zip(agg.iter_mut()) {
-                    let user_col_idx = match col_idx {
-                        StorageColumnRef::Idx(idx) => idx,
-                        _ => panic!("unsupported column ref for block aggregation"),
+                    let (user_col_idx, agg) = match (col_idx, agg) {
+                        (StorageColumnRef::Idx(idx), Some(agg)) => (idx, agg),
+                        _ => continue,
                     };
-                    let column = rowset.column(*user_col_idx as usize);
+                    // columns added after the rowset is written have no block statistics
+                    if *user_col_idx as usize >= rowset.column_count() {
+                        continue;
+                    }
+                    let column = rowset.column(*user_col_idx as usize).await?;
                     agg.apply_batch(column.index());
                 }
             }
         }
 
-        agg.into_iter().map(|agg| agg.get_output()).collect_vec()
+        let mut outputs = Vec::with_capacity(ty.len());
+        for ((ty, col_idx), agg) in ty.iter().zip(agg) {
+            outputs.push(match agg {
+                Some(agg) => agg.get_output(),
+                None => self.scan_stat(*ty, col_idx).await?,
+            });
+        }
+        Ok(outputs)
+    }
+
+    /// Compute a statistics of a column by scanning all of its values.
+    async fn scan_stat(
+        &self,
+        ty: BlockStatisticsType,
+        col_idx: &StorageColumnRef,
+    ) -> StorageResult<DataValue> {
+        let mut iter = self
+            .scan_inner(
+                None,
+                None,
+                std::slice::from_ref(col_idx),
+                ScanOptions::default(),
+            )
+            .await?;
+        let mut values = vec![];
+        while let Some(chunk) = iter.next_batch(None).await? {
+            let array = chunk.array_at(0);
+            values.extend((0..array.len()).map(|i| array.get(i)));
+        }
+        let non_null = values.iter().filter(|v| !matches!(v, DataValue::Null));
+        // NaN is not comparable with other floats, and is treated as equal to them
+        let cmp = |v1: &&DataValue, v2: &&DataValue| {
+            v1.partial_cmp(v2).unwrap_or(std::cmp::Ordering::Equal)
+        };
+        Ok(match ty {
+            BlockStatisticsType::RowCount => DataValue::Int64(values.len() as i64),
+            BlockStatisticsType::DistinctValue => {
+                DataValue::Int64(values.iter().unique().count() as i64)
+            }
+            BlockStatisticsType::MinValue => {
+                non_null.min_by(cmp).cloned().unwrap_or(DataValue::Null)
+            }
+            BlockStatisticsType::MaxValue => {
+                non_null.max_by(cmp).cloned().unwrap_or(DataValue::Null)
+            }
+        })
+    }
+
+    /// Count the visible rows of the table from rowset metadata, without reading any block of
+    /// column data. Rows deleted in multiple DVs are only subtracted once.
+    pub async fn row_count(&self) -> StorageResult<usize> {
+        let mut count = 0;
+        for (rowset, dvs) in self.visible_rowsets() {
+            let deleted = dvs.iter().map(|dv| dv.deletes()).kmerge().dedup().count();
+            count += rowset.row_count().await? - deleted;
+        }
+        Ok(count)
+    }
+
+    /// Get the rowsets visible to this transaction with their DVs, including the rowsets flushed
+    /// by this transaction, and the rows deleted by this transaction as an extra DV.
+    fn visible_rowsets(&self) -> Vec<(Arc<DiskRowset>, Vec<Arc<DeleteVector>>)> {
+        let table_id = self.table.table_id();
+        let mut rowsets = vec![];
+        if let Some(rowset_ids) = self.snapshot.get_rowsets_of(table_id) {
+            for rowset_id in rowset_ids {
+                let rowset = self.version.get_rowset(table_id, *rowset_id);
+                let dvs = self
+                    .snapshot
+                    .get_dvs_of(table_id, *rowset_id)
+                    .map(|dvs| {
+                        dvs.iter()
+                            .map(|dv_id| self.version.get_dv(table_id, *dv_id))
+                            .collect_vec()
+                    })
+                    .unwrap_or_default();
+                rowsets.push((rowset, dvs));
+            }
+        }
+        rowsets
+            .extend((self.to_be_committed_rowsets.iter()).map(|rowset| (rowset.clone(), vec![])));
+        for (rowset, dvs) in &mut rowsets {
+            let deletes = (self.delete_buffer.iter())
+                .filter(|row_handler| row_handler.rowset_id() == rowset.rowset_id())
+                .map(|row_handler| DeleteRecord {
+                    row_id: row_handler.row_id(),
+                })
+                .collect_vec();
+            if !deletes.is_empty() {
+                // the DV is only kept in memory until committed, so its id is never referenced
+                dvs.push(Arc::new(DeleteVector::new(0, rowset.rowset_id(), deletes)));
+            }
+        }
+        rowsets
     }
 
     pub async fn append_inner(&mut self, columns: DataChunk) -> StorageResult<()> {
         if self.read_only {
             panic!("Txn is read-only but append is called");
         }
+        let target_chunk_rows = self.table.storage_options.target_chunk_rows;
+        // large chunks are handed to the memtable directly
+        if self.append_buffer.is_none() && columns.cardinality() >= target_chunk_rows {
+            return self.append_to_mem_rowset(columns).await;
+        }
+        let table_columns = &self.table.columns;
+        let buffer = self
+            .append_buffer
+            .get_or_insert_with(|| ColumnMemTable::new(table_columns.clone()));
+        self.buffered_rows += columns.cardinality();
+        buffer.append(columns)?;
+        if self.buffered_rows >= target_chunk_rows {
+            self.flush_append_buffer().await?;
+        }
+        Ok(())
+    }
+
+    /// Hand the buffered rows to the memtable.
+    async fn flush_append_buffer(&mut self) -> StorageResult<()> {
+        if let Some(buffer) = self.append_buffer.take() {
+            self.buffered_rows = 0;
+            self.append_to_mem_rowset(buffer.flush()?).await?;
+        }
+        Ok(())
+    }
+
+    async fn append_to_mem_rowset(&mut self, columns: DataChunk) -> StorageResult<()> {
+        // roll over to a new rowset if the current one would exceed the target size
+        if let Some(mem) = &self.mem {
+            if mem.should_finish(&columns) {
+                self.flush_mem_rowset().await?;
+            }
+        }
+        if columns.estimated_size() > self.table.storage_options.target_rowset_size {
+            warn!("DataChunk is too big, exceeding target_rowset_size.")
+        }
         if self.mem.is_none() {
             let rowset_id = self.table.generate_rowset_id();
             let directory = self.table.get_rowset_path(rowset_id);
@@ -310,17 +614,7 @@ impl SecondaryTransaction {
                 rowset_id,
             ));
         }
-        let mem = self.mem.as_mut().unwrap();
-        self.total_size += columns.estimated_size();
-        mem.append(columns).await?;
-        if self.total_size >= self.table.storage_options.target_rowset_size {
-            if self.total_size >= self.table.storage_options.target_rowset_size * 2 {
-                warn!("DataChunk is too big, target_row_size exceed 2x limit.")
-            }
-            self.total_size = 0;
-            self.flush_rowset().await?;
-        }
-        Ok(())
+        self.mem.as_mut().unwrap().append(columns).await
     }
 }
 
@@ -340,25 +634,18 @@ impl Transaction for SecondaryTransaction {
 
     type AbortResultFuture<'a> = impl Future<Output = StorageResult<()>> + Send + 'a;
 
+    type FlushResultFuture<'a> = impl Future<Output = StorageResult<()>> + Send + 'a;
+
     fn scan<'a>(
         &'a self,
         begin_sort_key: Option<&'a [u8]>,
         end_sort_key: Option<&'a [u8]>,
         col_idx: &'a [StorageColumnRef],
-        is_sorted: bool,
-        reversed: bool,
-        expr: Option<BoundExpr>,
+        options: ScanOptions,
     ) -> Self::ScanResultFuture<'a> {
         async move {
-            self.scan_inner(
-                begin_sort_key,
-                end_sort_key,
-                col_idx,
-                is_sorted,
-                reversed,
-                expr,
-            )
-            .await
+            self.scan_inner(begin_sort_key, end_sort_key, col_idx, options)
+                .await
         }
     }
 
@@ -377,14 +664,28 @@ impl Transaction for SecondaryTransaction {
         }
     }
 
+    fn flush(&mut self) -> Self::FlushResultFuture<'_> {
+        async move { self.flush_rowset().await }
+    }
+
     fn commit<'a>(self) -> Self::CommitResultFuture<'a> {
         async move { self.commit_inner().await }
     }
 
     fn abort<'a>(mut self) -> Self::AbortResultFuture<'a> {
         async move {
+            // the RowSets written by the txn will never be referenced
+            let mut rowset_ids = (self.to_be_committed_rowsets.drain(..))
+                .map(|rowset| rowset.rowset_id())
+                .collect_vec();
+            rowset_ids.extend(self.mem.take().map(|mem| mem.get_rowset_id()));
+
             self.finished = true;
             self.version.unpin(self.epoch);
+
+            for rowset_id in rowset_ids {
+                tokio::fs::remove_dir_all(self.table.get_rowset_path(rowset_id)).await?;
+            }
             Ok(())
         }
     }
@@ -398,3 +699,383 @@ impl Drop for SecondaryTransaction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::catalog::{ColumnCatalog, TableRefId};
+    use crate::storage::secondary::{SecondaryStorage, StorageOptions};
+    use crate::storage::{RowHandler, Storage, Table, TxnIterator};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    async fn helper_scan_with_limit(
+        txn: &SecondaryTransaction,
+        row_offset: usize,
+        row_limit: usize,
+    ) -> Vec<i32> {
+        let mut it = txn
+            .scan(
+                None,
+                None,
+                &[StorageColumnRef::Idx(0)],
+                ScanOptions {
+                    row_offset,
+                    row_limit: Some(row_limit),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let mut rows = vec![];
+        while let Some(chunk) = it.next_batch(None).await.unwrap() {
+            match chunk.array_at(0) {
+                ArrayImpl::Int32(array) => rows.extend(array.iter().map(|x| *x.unwrap())),
+                _ => unreachable!(),
+            }
+        }
+        rows
+    }
+
+    #[tokio::test]
+    async fn test_scan_with_limit() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v1".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table = storage
+            .get_table(TableRefId {
+                database_id: 0,
+                schema_id: 0,
+                table_id: 0,
+            })
+            .unwrap();
+
+        // 3 rowsets of 10000 rows, each of which has several blocks
+        for i in 0..3 {
+            let mut txn = table.write().await.unwrap();
+            txn.append(
+                [ArrayImpl::Int32((i * 10000..(i + 1) * 10000).collect())]
+                    .into_iter()
+                    .collect(),
+            )
+            .await
+            .unwrap();
+            txn.commit().await.unwrap();
+        }
+
+        let txn = table.read().await.unwrap();
+        let block_cache = storage.block_cache();
+
+        // only the first block is read
+        let misses = block_cache.misses();
+        assert_eq!(
+            helper_scan_with_limit(&txn, 0, 10).await,
+            (0..10).collect_vec()
+        );
+        assert_eq!(block_cache.misses(), misses + 1);
+
+        // leading rowsets and blocks are skipped by row counts
+        let misses = block_cache.misses();
+        assert_eq!(
+            helper_scan_with_limit(&txn, 25000, 10).await,
+            (25000..25010).collect_vec()
+        );
+        assert_eq!(block_cache.misses(), misses + 1);
+
+        // the last block of the first rowset and the first block of the second rowset are read
+        let misses = block_cache.misses();
+        assert_eq!(
+            helper_scan_with_limit(&txn, 9995, 10).await,
+            (9995..10005).collect_vec()
+        );
+        assert_eq!(block_cache.misses(), misses + 2);
+
+        assert_eq!(
+            helper_scan_with_limit(&txn, 29995, 10).await,
+            (29995..30000).collect_vec()
+        );
+        assert!(helper_scan_with_limit(&txn, 30000, 10).await.is_empty());
+        assert!(helper_scan_with_limit(&txn, 0, 0).await.is_empty());
+
+        txn.abort().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_own_writes() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v1".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table = storage.get_table(TableRefId::new(0, 0, 0)).unwrap();
+
+        let mut txn = table.update().await.unwrap();
+        txn.append([ArrayImpl::Int32((0..10).collect())].into_iter().collect())
+            .await
+            .unwrap();
+        txn.flush().await.unwrap();
+
+        // delete the first 5 rows written by the txn
+        let mut it = txn
+            .scan(
+                None,
+                None,
+                &[StorageColumnRef::RowHandler],
+                ScanOptions::default(),
+            )
+            .await
+            .unwrap();
+        let chunk = it.next_batch(None).await.unwrap().unwrap();
+        for idx in 0..5 {
+            let row_handler = SecondaryRowHandler::from_column(chunk.array_at(0), idx);
+            txn.delete(&row_handler).await.unwrap();
+        }
+        assert_eq!(
+            helper_scan_with_limit(&txn, 0, 100).await,
+            (5..10).collect_vec()
+        );
+        assert_eq!(txn.row_count().await.unwrap(), 5);
+
+        // the writes are invisible to other txns before committing
+        let other = table.read().await.unwrap();
+        assert!(helper_scan_with_limit(&other, 0, 100).await.is_empty());
+        other.abort().await.unwrap();
+
+        txn.commit().await.unwrap();
+        let txn = table.read().await.unwrap();
+        assert_eq!(
+            helper_scan_with_limit(&txn, 0, 100).await,
+            (5..10).collect_vec()
+        );
+        txn.abort().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scan_partitions() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v1".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table = storage.get_table(TableRefId::new(0, 0, 0)).unwrap();
+
+        // write 5 rowsets of 10 rows
+        let mut txn = table.write().await.unwrap();
+        for i in 0..5 {
+            txn.append(
+                [ArrayImpl::Int32((i * 10..i * 10 + 10).collect())]
+                    .into_iter()
+                    .collect(),
+            )
+            .await
+            .unwrap();
+            txn.flush_rowset().await.unwrap();
+        }
+        txn.commit().await.unwrap();
+
+        let txn = table.read().await.unwrap();
+        for parallelism in 1..=6 {
+            let iters = txn
+                .scan_partitions(&[StorageColumnRef::Idx(0)], None, parallelism)
+                .await
+                .unwrap();
+            assert_eq!(iters.len(), parallelism.min(5));
+            let mut rows = vec![];
+            for mut it in iters {
+                while let Some(chunk) = it.next_batch(None).await.unwrap() {
+                    match chunk.array_at(0) {
+                        ArrayImpl::Int32(array) => rows.extend(array.iter().map(|x| *x.unwrap())),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            assert_eq!(rows, (0..50).collect_vec());
+        }
+        txn.abort().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_abort_removes_written_rowsets() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v1".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table = storage.get_table(TableRefId::new(0, 0, 0)).unwrap();
+
+        let mut txn = table.write().await.unwrap();
+        txn.append([ArrayImpl::Int32((0..10).collect())].into_iter().collect())
+            .await
+            .unwrap();
+        txn.flush_rowset().await.unwrap();
+        txn.append([ArrayImpl::Int32((10..20).collect())].into_iter().collect())
+            .await
+            .unwrap();
+        txn.abort().await.unwrap();
+
+        let rowsets = std::fs::read_dir(tempdir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir() && path.file_name().unwrap() != "dv")
+            .count();
+        assert_eq!(rowsets, 0);
+    }
+
+    #[tokio::test]
+    async fn test_buffer_single_row_appends() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v1".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table = storage.get_table(TableRefId::new(0, 0, 0)).unwrap();
+
+        let mut txn = table.write().await.unwrap();
+        for i in 0..100_000 {
+            txn.append(
+                [ArrayImpl::Int32([i].into_iter().collect())]
+                    .into_iter()
+                    .collect(),
+            )
+            .await
+            .unwrap();
+        }
+        // the rows are handed to the rowset builder in chunks of the target size
+        assert_eq!(txn.buffered_rows, 100_000 - 64 * 1024);
+        txn.commit().await.unwrap();
+
+        let rowsets = std::fs::read_dir(tempdir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir() && path.file_name().unwrap() != "dv")
+            .count();
+        assert_eq!(rowsets, 1);
+
+        let txn = table.read().await.unwrap();
+        assert_eq!(txn.row_count().await.unwrap(), 100_000);
+        assert_eq!(
+            helper_scan_with_limit(&txn, 99_990, 10).await,
+            (99_990..100_000).collect_vec()
+        );
+        txn.abort().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rollover_rowsets() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions {
+            target_rowset_size: 64 * 1024,
+            target_chunk_rows: 1000,
+            ..StorageOptions::default_for_test(tempdir.path().to_path_buf())
+        })
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v1".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table = storage.get_table(TableRefId::new(0, 0, 0)).unwrap();
+
+        let mut txn = table.write().await.unwrap();
+        for i in 0..100 {
+            txn.append(
+                [ArrayImpl::Int32((i * 1000..(i + 1) * 1000).collect())]
+                    .into_iter()
+                    .collect(),
+            )
+            .await
+            .unwrap();
+        }
+        txn.commit().await.unwrap();
+
+        // 100000 rows of 4 bytes take about 400KB
+        let rowset_sizes = std::fs::read_dir(tempdir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_dir() && path.file_name().unwrap() != "dv")
+            .map(|path| {
+                std::fs::read_dir(path)
+                    .unwrap()
+                    .map(|entry| entry.unwrap().metadata().unwrap().len())
+                    .sum::<u64>()
+            })
+            .collect_vec();
+        assert!(rowset_sizes.len() > 5, "{:?}", rowset_sizes);
+        assert!(rowset_sizes.iter().all(|size| *size <= 64 * 1024));
+
+        let txn = table.read().await.unwrap();
+        assert_eq!(txn.row_count().await.unwrap(), 100_000);
+        txn.abort().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_block_stat_by_scan() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v1".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table = storage.get_table(TableRefId::new(0, 0, 0)).unwrap();
+
+        let mut txn = table.write().await.unwrap();
+        txn.append([ArrayImpl::Int32((0..10).collect())].into_iter().collect())
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+
+        // the row handler has no block statistics, and is aggregated by a scan
+        let txn = table.read().await.unwrap();
+        let stats = txn
+            .aggreagate_block_stat(&[
+                (BlockStatisticsType::RowCount, StorageColumnRef::Idx(0)),
+                (BlockStatisticsType::RowCount, StorageColumnRef::RowHandler),
+                (
+                    BlockStatisticsType::DistinctValue,
+                    StorageColumnRef::RowHandler,
+                ),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(stats, vec![DataValue::Int64(10); 3]);
+        txn.abort().await.unwrap();
+    }
+}
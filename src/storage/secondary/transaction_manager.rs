@@ -73,4 +73,9 @@ impl TransactionManager {
     pub async fn lock_for_deletion(&self, table: u32) -> TransactionLock {
         self.lock(table).await
     }
+
+    /// Get a lock for truncation
+    pub async fn lock_for_truncation(&self, table: u32) -> TransactionLock {
+        self.lock(table).await
+    }
 }
@@ -7,7 +7,7 @@ use enum_dispatch::enum_dispatch;
 
 use super::{ConcatIterator, MergeIterator, RowSetIterator};
 use crate::array::DataChunk;
-use crate::storage::{StorageChunk, StorageResult, TxnIterator};
+use crate::storage::{ScanStats, StorageChunk, StorageResult, TxnIterator};
 
 #[enum_dispatch]
 pub enum SecondaryIterator {
@@ -28,11 +28,25 @@ pub trait SecondaryIteratorImpl {}
 /// To achieve this, we must enable GAT.
 pub struct SecondaryTableTxnIterator {
     iter: SecondaryIterator,
+
+    /// Number of rows to be skipped
+    row_offset: usize,
+
+    /// Maximum number of rows to be returned
+    row_limit: Option<usize>,
 }
 
 impl SecondaryTableTxnIterator {
-    pub(super) fn new(iter: SecondaryIterator) -> Self {
-        Self { iter }
+    pub(super) fn new(
+        iter: SecondaryIterator,
+        row_offset: usize,
+        row_limit: Option<usize>,
+    ) -> Self {
+        Self {
+            iter,
+            row_offset,
+            row_limit,
+        }
     }
 }
 
@@ -50,6 +64,16 @@ impl SecondaryIterator {
             SecondaryIterator::Test(iter) => iter.next_batch(expected_size).await,
         }
     }
+
+    pub fn stats(&self) -> ScanStats {
+        match self {
+            SecondaryIterator::Concat(iter) => iter.stats(),
+            SecondaryIterator::Merge(iter) => iter.stats(),
+            SecondaryIterator::RowSet(iter) => iter.stats(),
+            #[cfg(test)]
+            SecondaryIterator::Test(_) => ScanStats::default(),
+        }
+    }
 }
 
 impl TxnIterator for SecondaryTableTxnIterator {
@@ -57,11 +81,88 @@ impl TxnIterator for SecondaryTableTxnIterator {
 
     fn next_batch(&mut self, expected_size: Option<usize>) -> Self::NextFuture<'_> {
         async move {
-            Ok(self
-                .iter
-                .next_batch(expected_size)
-                .await?
-                .map(|x| x.to_data_chunk()))
+            loop {
+                // don't fetch more rows than needed, so that we stop reading blocks early
+                let expected_size = match self.row_limit {
+                    Some(0) => return Ok(None),
+                    Some(limit) => {
+                        let remaining = self.row_offset + limit;
+                        Some(expected_size.map_or(remaining, |size| size.min(remaining)))
+                    }
+                    None => expected_size,
+                };
+                let chunk = match self.iter.next_batch(expected_size).await? {
+                    Some(chunk) => chunk.to_data_chunk(),
+                    None => return Ok(None),
+                };
+
+                let cardinality = chunk.cardinality();
+                if self.row_offset >= cardinality {
+                    self.row_offset -= cardinality;
+                    continue;
+                }
+                let start = std::mem::take(&mut self.row_offset);
+                let end = match &mut self.row_limit {
+                    Some(limit) => {
+                        let end = cardinality.min(start + *limit);
+                        *limit -= end - start;
+                        end
+                    }
+                    None => cardinality,
+                };
+                if (start..end) == (0..cardinality) {
+                    return Ok(Some(chunk));
+                }
+                return Ok(Some(chunk.slice(start..end)));
+            }
+        }
+    }
+
+    fn stats(&self) -> ScanStats {
+        self.iter.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bitvec::prelude::*;
+    use smallvec::smallvec;
+
+    use super::*;
+    use crate::array::{ArrayImpl, ArrayToVecExt};
+    use crate::storage::secondary::tests::TestIterator;
+
+    #[tokio::test]
+    async fn test_txn_iterator_with_limit() {
+        // rows 0..4, 4..8 and 8..12, with odd rows filtered out in the second chunk
+        let chunks = vec![
+            StorageChunk::construct(
+                None,
+                smallvec![Arc::new(ArrayImpl::Int32((0..4).collect()))],
+            ),
+            StorageChunk::construct(
+                Some(bitvec![1, 0, 1, 0]),
+                smallvec![Arc::new(ArrayImpl::Int32((4..8).collect()))],
+            ),
+            StorageChunk::construct(
+                None,
+                smallvec![Arc::new(ArrayImpl::Int32((8..12).collect()))],
+            ),
+        ];
+        let iter = SecondaryIterator::Test(TestIterator::new(
+            chunks.into_iter().map(Option::unwrap).collect(),
+        ));
+        let mut iter = SecondaryTableTxnIterator::new(iter, 3, Some(4));
+
+        let mut rows = vec![];
+        while let Some(chunk) = iter.next_batch(None).await.unwrap() {
+            match chunk.array_at(0) {
+                ArrayImpl::Int32(array) => rows.extend(array.to_vec()),
+                _ => unreachable!(),
+            }
         }
+        assert_eq!(rows, vec![Some(3), Some(4), Some(6), Some(8)]);
     }
 }
@@ -2,6 +2,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::lock::Mutex;
 use parking_lot::Mutex as PLMutex;
@@ -16,7 +17,10 @@ use super::{DeleteVector, DiskRowset, StorageOptions, StorageResult};
 pub enum EpochOp {
     CreateTable(CreateTableEntry),
     DropTable(DropTableEntry),
-    AddRowSet((AddRowSetEntry, DiskRowset)),
+    AddColumn(AddColumnEntry),
+    TruncateTable(TruncateTableEntry),
+    CreateIndex(CreateIndexEntry),
+    AddRowSet((AddRowSetEntry, Arc<DiskRowset>)),
     DeleteRowSet(DeleteRowsetEntry),
     AddDV((AddDVEntry, DeleteVector)),
     DeleteDV(DeleteDVEntry),
@@ -68,6 +72,17 @@ impl Snapshot {
         }
     }
 
+    /// Remove all RowSets and DVs of a table, and return them as RowSet IDs and (RowSetId, DVId).
+    pub fn delete_table(&mut self, table_id: u32) -> (Vec<u32>, Vec<(u32, u64)>) {
+        let rowsets = self.rowsets.remove(&table_id).unwrap_or_default();
+        let dvs = self.dvs.remove(&table_id).unwrap_or_default();
+        let dvs = dvs
+            .into_iter()
+            .flat_map(|(rowset_id, dvs)| dvs.into_iter().map(move |dv_id| (rowset_id, dv_id)))
+            .collect();
+        (rowsets.into_iter().collect(), dvs)
+    }
+
     pub fn get_dvs_of(&self, table_id: u32, rowset_id: u32) -> Option<&HashSet<u64>> {
         if let Some(rowset) = self.dvs.get(&table_id) {
             if let Some(dvs) = rowset.get(&rowset_id) {
@@ -85,6 +100,16 @@ impl Snapshot {
     }
 }
 
+/// The RowSets and DVs that can be removed physically.
+#[derive(Debug, Default)]
+pub struct VacuumSet {
+    /// (TableId, RowSetId) of RowSets.
+    pub rowsets: Vec<(u32, u32)>,
+
+    /// (TableId, RowSetId, DVId) of DVs.
+    pub dvs: Vec<(u32, u32, u64)>,
+}
+
 #[derive(Default)]
 pub struct VersionManagerInner {
     /// To make things easy, we store the full snapshot of each epoch. In the future, we will use a
@@ -103,6 +128,9 @@ pub struct VersionManagerInner {
     /// Deletion to apply in each epoch.
     rowset_deletion_to_apply: HashMap<u64, Vec<(u32, u32)>>,
 
+    /// DV deletion to apply in each epoch, as (TableId, RowSetId, DVId).
+    dv_deletion_to_apply: HashMap<u64, Vec<(u32, u32, u64)>>,
+
     /// Current epoch number.
     epoch: u64,
 }
@@ -175,6 +203,7 @@ impl VersionManager {
         let mut entries;
         let current_epoch;
         let mut rowset_deletion_to_apply = vec![];
+        let mut dv_deletion_to_apply = vec![];
 
         {
             // Hold the inner lock, so as to apply the changes to the current status, and add new
@@ -203,14 +232,40 @@ impl VersionManager {
                     EpochOp::CreateTable(entry) => {
                         entries.push(ManifestOperation::CreateTable(entry))
                     }
-                    EpochOp::DropTable(entry) => entries.push(ManifestOperation::DropTable(entry)),
+                    EpochOp::AddColumn(entry) => entries.push(ManifestOperation::AddColumn(entry)),
+                    EpochOp::CreateIndex(entry) => {
+                        entries.push(ManifestOperation::CreateIndex(entry))
+                    }
+                    // The RowSets and DVs of a dropped or truncated table are vacuumed later.
+                    EpochOp::DropTable(entry) => {
+                        let table_id = entry.table_id.table_id;
+                        let (rowsets, dvs) = snapshot.delete_table(table_id);
+                        rowset_deletion_to_apply
+                            .extend(rowsets.into_iter().map(|rowset_id| (table_id, rowset_id)));
+                        dv_deletion_to_apply.extend(
+                            (dvs.into_iter())
+                                .map(|(rowset_id, dv_id)| (table_id, rowset_id, dv_id)),
+                        );
+                        entries.push(ManifestOperation::DropTable(entry))
+                    }
+                    EpochOp::TruncateTable(entry) => {
+                        let table_id = entry.table_id.table_id;
+                        let (rowsets, dvs) = snapshot.delete_table(table_id);
+                        rowset_deletion_to_apply
+                            .extend(rowsets.into_iter().map(|rowset_id| (table_id, rowset_id)));
+                        dv_deletion_to_apply.extend(
+                            (dvs.into_iter())
+                                .map(|(rowset_id, dv_id)| (table_id, rowset_id, dv_id)),
+                        );
+                        entries.push(ManifestOperation::TruncateTable(entry))
+                    }
 
                     // For other operations, maintain the snapshot in version manager
                     EpochOp::AddRowSet((entry, rowset)) => {
                         // record the rowset into the pool
                         inner
                             .rowsets
-                            .insert((entry.table_id.table_id, entry.rowset_id), Arc::new(rowset));
+                            .insert((entry.table_id.table_id, entry.rowset_id), rowset);
                         // update the snapshot
                         snapshot.add_rowset(entry.table_id.table_id, entry.rowset_id);
                         entries.push(ManifestOperation::AddRowSet(entry));
@@ -230,7 +285,11 @@ impl VersionManager {
                         entries.push(ManifestOperation::AddDV(entry));
                     }
                     EpochOp::DeleteDV(entry) => {
-                        // TODO: record delete op and apply it later
+                        dv_deletion_to_apply.push((
+                            entry.table_id.table_id,
+                            entry.rowset_id,
+                            entry.dv_id,
+                        ));
                         snapshot.delete_dv(entry.table_id.table_id, entry.rowset_id, entry.dv_id);
                         entries.push(ManifestOperation::DeleteDV(entry));
                     }
@@ -250,6 +309,9 @@ impl VersionManager {
         inner
             .rowset_deletion_to_apply
             .insert(epoch, rowset_deletion_to_apply);
+        inner
+            .dv_deletion_to_apply
+            .insert(epoch, dv_deletion_to_apply);
 
         Ok(epoch)
     }
@@ -290,8 +352,14 @@ impl VersionManager {
         inner.dvs.get(&(table_id, dv_id)).unwrap().clone()
     }
 
-    pub async fn find_vacuum(self: &Arc<Self>) -> StorageResult<Vec<(u32, u32)>> {
+    /// Find the RowSets and DVs that can be removed physically, and remove them from the pool.
+    ///
+    /// A deletion is applied only if no snapshot before its epoch is pinned, and no one holds the
+    /// object any more, e.g. an iterator that outlives its transaction. Otherwise, it is kept for
+    /// the next vacuum.
+    pub async fn find_vacuum(self: &Arc<Self>) -> StorageResult<VacuumSet> {
         let mut inner = self.inner.lock();
+        let inner = &mut *inner;
         let min_pinned_epoch = inner.ref_cnt.keys().min().cloned();
 
         // If there is no pinned epoch, all deletions can be applied.
@@ -299,30 +367,65 @@ impl VersionManager {
 
         let can_apply = |epoch, vacuum_epoch| epoch <= vacuum_epoch;
 
+        // Snapshots before the vacuum epoch will never be pinned again.
+        inner.status.retain(|epoch, _| *epoch >= vacuum_epoch);
+
         // Fetch to-be-applied deletions.
-        let mut deletions = vec![];
+        let mut rowset_deletions = vec![];
         for (epoch, deletion) in &inner.rowset_deletion_to_apply {
             if can_apply(*epoch, vacuum_epoch) {
-                deletions.extend(deletion.iter().cloned());
+                rowset_deletions.extend(deletion.iter().cloned());
             }
         }
         inner
             .rowset_deletion_to_apply
             .retain(|k, _| !can_apply(*k, vacuum_epoch));
-        for deletion in &deletions {
-            let rowset = inner.rowsets.remove(deletion).unwrap();
-            match Arc::try_unwrap(rowset) {
-                Ok(rowset) => drop(rowset),
-                Err(_) => panic!("rowset {:?} is still being used", deletion),
+        let mut dv_deletions = vec![];
+        for (epoch, deletion) in &inner.dv_deletion_to_apply {
+            if can_apply(*epoch, vacuum_epoch) {
+                dv_deletions.extend(deletion.iter().cloned());
             }
         }
-        Ok(deletions)
+        inner
+            .dv_deletion_to_apply
+            .retain(|k, _| !can_apply(*k, vacuum_epoch));
+
+        // Postpone the deletions of objects still being used.
+        let rowsets = &mut inner.rowsets;
+        let (rowsets_to_remove, rowsets_in_use): (Vec<_>, Vec<_>) = rowset_deletions
+            .into_iter()
+            .partition(|deletion| Arc::strong_count(&rowsets[deletion]) == 1);
+        for deletion in &rowsets_to_remove {
+            rowsets.remove(deletion).unwrap();
+        }
+        let dvs = &mut inner.dvs;
+        let (dvs_to_remove, dvs_in_use): (Vec<_>, Vec<_>) = dv_deletions
+            .into_iter()
+            .partition(|(table_id, _, dv_id)| Arc::strong_count(&dvs[&(*table_id, *dv_id)]) == 1);
+        for (table_id, _, dv_id) in &dvs_to_remove {
+            dvs.remove(&(*table_id, *dv_id)).unwrap();
+        }
+        if !rowsets_in_use.is_empty() {
+            (inner
+                .rowset_deletion_to_apply
+                .entry(vacuum_epoch)
+                .or_default())
+            .extend(rowsets_in_use);
+        }
+        if !dvs_in_use.is_empty() {
+            (inner.dv_deletion_to_apply.entry(vacuum_epoch).or_default()).extend(dvs_in_use);
+        }
+
+        Ok(VacuumSet {
+            rowsets: rowsets_to_remove,
+            dvs: dvs_to_remove,
+        })
     }
 
     pub async fn do_vacuum(self: &Arc<Self>) -> StorageResult<()> {
-        let deletions = self.find_vacuum().await?;
+        let VacuumSet { rowsets, dvs } = self.find_vacuum().await?;
 
-        for (table_id, rowset_id) in deletions {
+        for (table_id, rowset_id) in rowsets {
             let path = self
                 .storage_options
                 .path
@@ -331,6 +434,15 @@ impl VersionManager {
             tokio::fs::remove_dir_all(path).await?;
         }
 
+        for (table_id, rowset_id, dv_id) in dvs {
+            let path = self
+                .storage_options
+                .path
+                .join(format!("dv/{}_{}_{}.dv", table_id, rowset_id, dv_id));
+            info!("vacuum DV {}_{}_{}", table_id, rowset_id, dv_id);
+            tokio::fs::remove_file(path).await?;
+        }
+
         Ok(())
     }
 
@@ -339,12 +451,61 @@ impl VersionManager {
         mut stop: tokio::sync::mpsc::UnboundedReceiver<()>,
     ) -> StorageResult<()> {
         let mut vacuum_notifier = self.rx.lock().take().unwrap();
+        // retry the postponed deletions periodically
+        let mut interval = tokio::time::interval(Duration::from_secs(10));
         loop {
             select! {
                 Some(_) = vacuum_notifier.recv() => self.do_vacuum().await?,
+                _ = interval.tick() => self.do_vacuum().await?,
                 Some(_) = stop.recv() => break
             }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::catalog::{ColumnCatalog, TableRefId};
+    use crate::storage::secondary::SecondaryStorage;
+    use crate::storage::{Storage, Table, Transaction};
+    use crate::types::{DataTypeExt, DataTypeKind};
+
+    #[tokio::test]
+    async fn test_vacuum_after_snapshot_released() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let storage = SecondaryStorage::open(StorageOptions::default_for_test(
+            tempdir.path().to_path_buf(),
+        ))
+        .await
+        .unwrap();
+        let columns = [ColumnCatalog::new(
+            0,
+            DataTypeKind::Int(None).not_null().to_column("v".into()),
+        )];
+        storage.create_table(0, 0, "t", &columns).await.unwrap();
+        let table_id = TableRefId::new(0, 0, 0);
+        let table = storage.get_table(table_id).unwrap();
+        let mut txn = table.write().await.unwrap();
+        txn.append([ArrayImpl::Int32((0..10).collect())].into_iter().collect())
+            .await
+            .unwrap();
+        txn.commit().await.unwrap();
+        let (epoch, snapshot) = storage.version.pin();
+        let rowset_id = *snapshot.get_rowsets_of(0).unwrap().iter().next().unwrap();
+        storage.version.unpin(epoch);
+        let path = table.get_rowset_path(rowset_id);
+
+        // the rowset is still referenced by the snapshot of a reader
+        let reader = table.read().await.unwrap();
+        storage.drop_table(table_id).await.unwrap();
+        storage.version.do_vacuum().await.unwrap();
+        assert!(path.exists());
+
+        reader.abort().await.unwrap();
+        storage.version.do_vacuum().await.unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -4,8 +4,9 @@ use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 use chrono::{Datelike, NaiveDate};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+use crate::types::interval::USECS_PER_DAY;
 use crate::types::Interval;
 
 /// The same as `NaiveDate::from_ymd(1970, 1, 1).num_days_from_ce()`.
@@ -13,7 +14,7 @@ use crate::types::Interval;
 pub const UNIX_EPOCH_DAYS: i32 = 719_163;
 
 /// Date type
-#[derive(PartialOrd, PartialEq, Debug, Copy, Clone, Default, Hash, Serialize)]
+#[derive(PartialOrd, PartialEq, Debug, Copy, Clone, Default, Hash, Serialize, Deserialize)]
 pub struct Date(i32);
 
 impl Date {
@@ -39,29 +40,24 @@ impl FromStr for Date {
 impl std::ops::Add<Interval> for Date {
     type Output = Date;
 
+    /// Add months first and then days, like PostgreSQL does. As there is no time part in a
+    /// date, the microsecond part of the interval is rounded down to whole days.
     fn add(self, rhs: Interval) -> Self::Output {
-        // Add days
-        let days = self.0 + rhs.days();
-        let date = NaiveDate::from_num_days_from_ce(days + UNIX_EPOCH_DAYS);
+        let date = NaiveDate::from_num_days_from_ce(self.0 + UNIX_EPOCH_DAYS);
 
         // Add months and years
-        let mut day = date.day();
-        let mut month = date.month() as i32 + rhs.months();
-        let mut year = date.year() + rhs.years();
-        if month > 12 {
-            month -= 12;
-            year += 1;
-        } else if month <= 0 {
-            month += 12;
-            year -= 1;
-        }
-        assert!((1..=12).contains(&month));
+        let months = date.year() * 12 + date.month0() as i32 + rhs.num_months();
+        let year = months.div_euclid(12);
+        let month = months.rem_euclid(12) + 1;
 
         // Fix the days after changing date.
         // For example, 1970.1.31 + 1 month = 1970.2.28
-        day = day.min(get_month_days(year, month as usize));
+        let day = date.day().min(get_month_days(year, month as usize));
+        let date = NaiveDate::from_ymd(year, month as u32, day);
 
-        Date::new(NaiveDate::from_ymd(year, month as u32, day).num_days_from_ce() - UNIX_EPOCH_DAYS)
+        // Add days
+        let days = rhs.days() + rhs.usecs().div_euclid(USECS_PER_DAY) as i32;
+        Date::new(date.num_days_from_ce() - UNIX_EPOCH_DAYS + days)
     }
 }
 
@@ -123,3 +119,55 @@ impl Display for Date {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::interval::USECS_PER_SEC;
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_date_add_interval() {
+        assert_eq!(
+            date("2022-01-31") + Interval::from_days(1),
+            date("2022-02-01")
+        );
+        assert_eq!(
+            date("2022-01-31") + Interval::from_months(1),
+            date("2022-02-28")
+        );
+        assert_eq!(
+            date("2024-01-31") + Interval::from_months(1),
+            date("2024-02-29")
+        );
+        assert_eq!(
+            date("2022-11-30") + Interval::from_months(3),
+            date("2023-02-28")
+        );
+        assert_eq!(
+            date("2022-03-31") - Interval::from_months(1),
+            date("2022-02-28")
+        );
+        assert_eq!(
+            date("2022-01-15") - Interval::from_months(13),
+            date("2020-12-15")
+        );
+        // months are added before days
+        assert_eq!(
+            date("2022-01-31") + Interval::from_md(1, 1),
+            date("2022-03-01")
+        );
+        // microseconds are rounded down to whole days
+        assert_eq!(
+            date("2022-02-28") + Interval::from_usecs(36 * 3600 * USECS_PER_SEC),
+            date("2022-03-01")
+        );
+        assert_eq!(
+            date("2022-03-01") - Interval::from_usecs(USECS_PER_SEC),
+            date("2022-02-28")
+        );
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use bytes::{Buf, BufMut};
+use rust_decimal::Decimal;
+
+use super::{Blob, DataValue, Date, Interval, List, Timestamp};
+
+impl DataValue {
+    /// Encode the value with a tag of its type. Unlike the serialization of group keys, the value
+    /// is decoded exactly as it is, e.g. the scale of decimals is kept.
+    pub fn encode(&self, buf: &mut impl BufMut) {
+        match self {
+            DataValue::Null => buf.put_u8(0),
+            DataValue::Bool(v) => {
+                buf.put_u8(1);
+                buf.put_u8(*v as u8);
+            }
+            DataValue::Int32(v) => {
+                buf.put_u8(2);
+                buf.put_i32_le(*v);
+            }
+            DataValue::Int64(v) => {
+                buf.put_u8(3);
+                buf.put_i64_le(*v);
+            }
+            DataValue::Float64(v) => {
+                buf.put_u8(4);
+                buf.put_f64_le(*v);
+            }
+            DataValue::String(v) => {
+                buf.put_u8(5);
+                buf.put_u32_le(v.len() as u32);
+                buf.put_slice(v.as_bytes());
+            }
+            DataValue::Blob(v) => {
+                let bytes: &[u8] = v;
+                buf.put_u8(6);
+                buf.put_u32_le(bytes.len() as u32);
+                buf.put_slice(bytes);
+            }
+            DataValue::Decimal(v) => {
+                buf.put_u8(7);
+                buf.put_slice(&v.serialize());
+            }
+            DataValue::Date(v) => {
+                buf.put_u8(8);
+                buf.put_i32_le(v.get_inner());
+            }
+            DataValue::Timestamp(v) => {
+                buf.put_u8(9);
+                buf.put_i64_le(v.get_inner());
+            }
+            DataValue::Interval(v) => {
+                buf.put_u8(10);
+                buf.put_i32_le(v.num_months());
+                buf.put_i32_le(v.days());
+                buf.put_i64_le(v.usecs());
+            }
+            DataValue::List(v) => {
+                buf.put_u8(11);
+                buf.put_u32_le(v.len() as u32);
+                for value in v.iter() {
+                    value.encode(buf);
+                }
+            }
+        }
+    }
+
+    /// Decode a value encoded by [`DataValue::encode`], and advance the buffer.
+    pub fn decode(buf: &mut impl Buf) -> DataValue {
+        match buf.get_u8() {
+            0 => DataValue::Null,
+            1 => DataValue::Bool(buf.get_u8() != 0),
+            2 => DataValue::Int32(buf.get_i32_le()),
+            3 => DataValue::Int64(buf.get_i64_le()),
+            4 => DataValue::Float64(buf.get_f64_le()),
+            5 => {
+                let len = buf.get_u32_le() as usize;
+                let bytes = buf.copy_to_bytes(len).to_vec();
+                DataValue::String(String::from_utf8(bytes).expect("invalid utf-8 string"))
+            }
+            6 => {
+                let len = buf.get_u32_le() as usize;
+                DataValue::Blob(Blob::from(buf.copy_to_bytes(len).to_vec()))
+            }
+            7 => {
+                let mut bytes = [0; 16];
+                buf.copy_to_slice(&mut bytes);
+                DataValue::Decimal(Decimal::deserialize(bytes))
+            }
+            8 => DataValue::Date(Date::new(buf.get_i32_le())),
+            9 => DataValue::Timestamp(Timestamp::new(buf.get_i64_le())),
+            10 => {
+                let months = buf.get_i32_le();
+                let days = buf.get_i32_le();
+                let usecs = buf.get_i64_le();
+                DataValue::Interval(Interval::from_mdu(months, days, usecs))
+            }
+            11 => {
+                let len = buf.get_u32_le() as usize;
+                let values = (0..len).map(|_| DataValue::decode(buf)).collect::<Vec<_>>();
+                DataValue::List(List::from(values))
+            }
+            tag => panic!("invalid tag of encoded value: {}", tag),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        let values = [
+            DataValue::Null,
+            DataValue::Bool(true),
+            DataValue::Int32(1),
+            DataValue::Int64(2),
+            DataValue::Float64(1.5),
+            DataValue::String("abc".into()),
+            DataValue::Blob(Blob::from(vec![1, 2])),
+            DataValue::Decimal(Decimal::new(150, 2)),
+            DataValue::Date(Date::new(1)),
+            DataValue::Timestamp(Timestamp::new(1)),
+            DataValue::Interval(Interval::from_mdu(1, 2, 3)),
+            DataValue::List(List::from(vec![DataValue::Int32(1), DataValue::Null])),
+        ];
+        let mut buf = vec![];
+        for value in &values {
+            value.encode(&mut buf);
+        }
+        let mut data = &buf[..];
+        for value in &values {
+            assert_eq!(&DataValue::decode(&mut data), value);
+        }
+        assert!(data.is_empty());
+    }
+}
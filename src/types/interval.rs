@@ -2,34 +2,73 @@
 
 use std::fmt::{Display, Formatter};
 use std::ops::Neg;
+use std::str::FromStr;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use super::ConvertError;
+
+pub const USECS_PER_SEC: i64 = 1_000_000;
+pub const USECS_PER_DAY: i64 = 86_400 * USECS_PER_SEC;
 
 /// Interval type
-#[derive(PartialOrd, PartialEq, Debug, Copy, Clone, Default, Hash, Serialize)]
+///
+/// Like PostgreSQL, an interval is composed of months, days and microseconds, as the number of
+/// days in a month and the number of microseconds in a day are not fixed.
+#[derive(PartialOrd, PartialEq, Debug, Copy, Clone, Default, Hash, Serialize, Deserialize)]
 pub struct Interval {
     months: i32,
     days: i32,
+    usecs: i64,
 }
 
 impl Interval {
     pub const fn from_days(days: i32) -> Self {
-        Interval { months: 0, days }
+        Interval {
+            months: 0,
+            days,
+            usecs: 0,
+        }
     }
 
     pub const fn from_months(months: i32) -> Self {
-        Interval { months, days: 0 }
+        Interval {
+            months,
+            days: 0,
+            usecs: 0,
+        }
     }
 
     pub const fn from_years(years: i32) -> Self {
         Interval {
             months: years * 12,
             days: 0,
+            usecs: 0,
+        }
+    }
+
+    pub const fn from_usecs(usecs: i64) -> Self {
+        Interval {
+            months: 0,
+            days: 0,
+            usecs,
         }
     }
 
     pub const fn from_md(months: i32, days: i32) -> Self {
-        Interval { months, days }
+        Interval {
+            months,
+            days,
+            usecs: 0,
+        }
+    }
+
+    pub const fn from_mdu(months: i32, days: i32, usecs: i64) -> Self {
+        Interval {
+            months,
+            days,
+            usecs,
+        }
     }
 
     pub const fn years(&self) -> i32 {
@@ -47,6 +86,11 @@ impl Interval {
     pub const fn num_months(&self) -> i32 {
         self.months
     }
+
+    /// Get the microsecond component of the interval.
+    pub const fn usecs(&self) -> i64 {
+        self.usecs
+    }
 }
 
 impl Neg for Interval {
@@ -56,6 +100,7 @@ impl Neg for Interval {
         Interval {
             months: -self.months,
             days: -self.days,
+            usecs: -self.usecs,
         }
     }
 }
@@ -68,6 +113,103 @@ impl Display for Interval {
             self.years(),
             self.months(),
             self.days()
-        )
+        )?;
+        if self.usecs != 0 {
+            let sign = if self.usecs < 0 { "-" } else { "" };
+            let usecs = self.usecs.unsigned_abs();
+            let secs = usecs / USECS_PER_SEC as u64;
+            write!(
+                f,
+                " {}{:02}:{:02}:{:02}",
+                sign,
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60
+            )?;
+            if usecs % USECS_PER_SEC as u64 != 0 {
+                write!(f, ".{:06}", usecs % USECS_PER_SEC as u64)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = ConvertError;
+
+    /// Parse an interval from a list of quantities and units, e.g. `1 year 2 months 3 days`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ConvertError::ParseInterval(s.to_string());
+        let tokens = s.split_whitespace().collect::<Vec<_>>();
+        if tokens.is_empty() || tokens.len() % 2 != 0 {
+            return Err(err());
+        }
+        let (mut months, mut days, mut usecs) = (0i64, 0i64, 0i64);
+        for pair in tokens.chunks(2) {
+            let num = pair[0].parse::<i64>().map_err(|_| err())?;
+            let unit = pair[1].to_lowercase();
+            let unit = unit.strip_suffix('s').unwrap_or(&unit);
+            let (field, factor) = match unit {
+                "year" => (&mut months, 12),
+                "month" | "mon" => (&mut months, 1),
+                "week" => (&mut days, 7),
+                "day" => (&mut days, 1),
+                "hour" => (&mut usecs, 3600 * USECS_PER_SEC),
+                "minute" | "min" => (&mut usecs, 60 * USECS_PER_SEC),
+                "second" | "sec" => (&mut usecs, USECS_PER_SEC),
+                "millisecond" => (&mut usecs, 1000),
+                "microsecond" => (&mut usecs, 1),
+                _ => return Err(err()),
+            };
+            let value = num
+                .checked_mul(factor)
+                .and_then(|value| value.checked_add(*field))
+                .ok_or_else(err)?;
+            *field = value;
+        }
+        let to_i32 = |num: i64| i32::try_from(num).map_err(|_| err());
+        Ok(Interval::from_mdu(to_i32(months)?, to_i32(days)?, usecs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!("1 day".parse::<Interval>().unwrap(), Interval::from_days(1));
+        assert_eq!(
+            "1 year 2 months 3 days".parse::<Interval>().unwrap(),
+            Interval::from_md(14, 3)
+        );
+        assert_eq!(
+            "-1 hour 30 minutes 5 microseconds"
+                .parse::<Interval>()
+                .unwrap(),
+            Interval::from_usecs(-1800 * USECS_PER_SEC + 5)
+        );
+        assert!("1".parse::<Interval>().is_err());
+        assert!("1 fortnight".parse::<Interval>().is_err());
+        // out of range
+        assert!("9223372036854775807 hours".parse::<Interval>().is_err());
+        assert!("2147483647 years".parse::<Interval>().is_err());
+        assert!("2147483647 days 1 day".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn test_display_interval() {
+        assert_eq!(
+            Interval::from_md(14, 3).to_string(),
+            "1 years 2 months 3 days"
+        );
+        assert_eq!(
+            Interval::from_mdu(0, 1, 3723 * USECS_PER_SEC + 5).to_string(),
+            "0 years 0 months 1 days 01:02:03.000005"
+        );
+        assert_eq!(
+            Interval::from_usecs(-USECS_PER_SEC).to_string(),
+            "0 years 0 months 0 days -00:00:01"
+        );
     }
 }
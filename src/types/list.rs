@@ -0,0 +1,154 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+
+use super::DataValue;
+
+/// A list of values, e.g. `ARRAY[1, 2, 3]`.
+#[derive(PartialEq, Eq, PartialOrd, Hash, Clone, Serialize, Deserialize)]
+pub struct List(Vec<DataValue>);
+
+impl From<&[DataValue]> for List {
+    fn from(values: &[DataValue]) -> Self {
+        List(values.into())
+    }
+}
+
+impl From<Vec<DataValue>> for List {
+    fn from(values: Vec<DataValue>) -> Self {
+        List(values)
+    }
+}
+
+impl Borrow<ListRef> for List {
+    fn borrow(&self) -> &ListRef {
+        &*self
+    }
+}
+
+impl AsRef<ListRef> for List {
+    fn as_ref(&self) -> &ListRef {
+        &*self
+    }
+}
+
+impl Deref for List {
+    type Target = ListRef;
+
+    fn deref(&self) -> &Self::Target {
+        ListRef::new(&self.0)
+    }
+}
+
+impl fmt::Debug for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_ref())
+    }
+}
+
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+/// A slice of a list.
+#[repr(transparent)]
+#[derive(PartialEq, PartialOrd)]
+pub struct ListRef([DataValue]);
+
+impl ListRef {
+    pub fn new(values: &[DataValue]) -> &Self {
+        // SAFETY: `&ListRef` and `&[DataValue]` have the same layout.
+        unsafe { std::mem::transmute(values) }
+    }
+
+    /// Get the element at the 1-based position. Returns `None` if out of range.
+    pub fn get_1based(&self, index: i64) -> Option<&DataValue> {
+        let index = usize::try_from(index.checked_sub(1)?).ok()?;
+        self.0.get(index)
+    }
+}
+
+impl ToOwned for ListRef {
+    type Owned = List;
+
+    fn to_owned(&self) -> Self::Owned {
+        self.as_ref().into()
+    }
+}
+
+impl AsRef<[DataValue]> for ListRef {
+    fn as_ref(&self) -> &[DataValue] {
+        &self.0
+    }
+}
+
+impl Deref for ListRef {
+    type Target = [DataValue];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ListRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", &self.0)
+    }
+}
+
+impl fmt::Display for ListRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, v) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, ",")?;
+            }
+            match v {
+                DataValue::Null => write!(f, "NULL")?,
+                DataValue::Bool(v) => write!(f, "{v}")?,
+                DataValue::Int32(v) => write!(f, "{v}")?,
+                DataValue::Int64(v) => write!(f, "{v}")?,
+                DataValue::Float64(v) => write!(f, "{v}")?,
+                DataValue::String(v) => write!(f, "{v}")?,
+                DataValue::Blob(v) => write!(f, "{v}")?,
+                DataValue::Decimal(v) => write!(f, "{v}")?,
+                DataValue::Date(v) => write!(f, "{v}")?,
+                DataValue::Timestamp(v) => write!(f, "{v}")?,
+                DataValue::Interval(v) => write!(f, "{v}")?,
+                DataValue::List(v) => write!(f, "{v}")?,
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_to_string() {
+        let l = List::from(vec![
+            DataValue::Int32(1),
+            DataValue::Null,
+            DataValue::Int32(3),
+        ]);
+        assert_eq!(l.to_string(), "{1,NULL,3}");
+    }
+
+    #[test]
+    fn list_get_1based() {
+        let l = List::from(vec![DataValue::Int32(1), DataValue::Int32(2)]);
+        assert_eq!(l.get_1based(1), Some(&DataValue::Int32(1)));
+        assert_eq!(l.get_1based(2), Some(&DataValue::Int32(2)));
+        assert_eq!(l.get_1based(0), None);
+        assert_eq!(l.get_1based(3), None);
+        assert_eq!(l.get_1based(i64::MIN), None);
+    }
+}
@@ -6,19 +6,25 @@ use num_traits::ToPrimitive;
 use rust_decimal::prelude::FromStr;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use sqlparser::ast::BinaryOperator;
 pub use sqlparser::ast::DataType as DataTypeKind;
 
 use crate::for_all_variants;
 
 mod blob;
 mod date;
+mod encoding;
 mod interval;
+mod list;
 mod native;
+mod timestamp;
 
 pub use self::blob::*;
 pub use self::date::*;
 pub use self::interval::*;
+pub use self::list::*;
 pub use self::native::*;
+pub use self::timestamp::*;
 
 /// Physical data type
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -31,7 +37,9 @@ pub enum PhysicalDataTypeKind {
     Bool,
     Decimal,
     Date,
+    Timestamp,
     Interval,
+    List,
 }
 
 impl From<DataTypeKind> for PhysicalDataTypeKind {
@@ -46,7 +54,9 @@ impl From<DataTypeKind> for PhysicalDataTypeKind {
             Boolean => Self::Bool,
             Decimal(_, _) => Self::Decimal,
             Date => Self::Date,
+            Timestamp => Self::Timestamp,
             Interval => Self::Interval,
+            Array(_) => Self::List,
             _ => todo!("physical type for {:?} is not supported", kind),
         }
     }
@@ -92,6 +102,18 @@ impl DataType {
     pub fn physical_kind(&self) -> PhysicalDataTypeKind {
         self.physical_kind.clone()
     }
+
+    /// Returns `true` if the type is a numeric type.
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self.kind,
+            DataTypeKind::Int(_)
+                | DataTypeKind::BigInt(_)
+                | DataTypeKind::Float(_)
+                | DataTypeKind::Double
+                | DataTypeKind::Decimal(_, _)
+        )
+    }
 }
 
 /// The extension methods for [`DataType`].
@@ -119,7 +141,7 @@ pub(crate) type TableId = u32;
 pub(crate) type ColumnId = u32;
 
 /// Primitive SQL value.
-#[derive(Debug, Clone, PartialOrd, Serialize)]
+#[derive(Debug, Clone, PartialOrd, Serialize, Deserialize)]
 pub enum DataValue {
     // NOTE: Null comes first.
     // => NULL is less than any non-NULL values
@@ -132,7 +154,9 @@ pub enum DataValue {
     Blob(Blob),
     Decimal(Decimal),
     Date(Date),
+    Timestamp(Timestamp),
     Interval(Interval),
+    List(List),
 }
 
 /// Implement dispatch functions for `PartialEq`
@@ -167,7 +191,9 @@ impl Hash for DataValue {
             Self::Blob(v) => v.hash(state),
             Self::Decimal(v) => v.hash(state),
             Self::Date(v) => v.hash(state),
+            Self::Timestamp(v) => v.hash(state),
             Self::Interval(v) => v.hash(state),
+            Self::List(v) => v.hash(state),
         }
     }
 }
@@ -227,7 +253,9 @@ impl DataValue {
             Self::Blob(_) => false,
             Self::Decimal(v) => v.is_sign_positive(),
             Self::Date(_) => false,
+            Self::Timestamp(_) => false,
             Self::Interval(_) => false,
+            Self::List(_) => false,
             Self::Null => false,
         }
     }
@@ -243,7 +271,15 @@ impl DataValue {
             Self::Blob(_) => Some(DataTypeKind::Blob(0).not_null()),
             Self::Decimal(_) => Some(DataTypeKind::Decimal(None, None).not_null()),
             Self::Date(_) => Some(DataTypeKind::Date.not_null()),
+            Self::Timestamp(_) => Some(DataTypeKind::Timestamp.not_null()),
             Self::Interval(_) => Some(DataTypeKind::Interval.not_null()),
+            Self::List(v) => {
+                // the element type is taken from the first non-NULL element
+                let element = (v.iter())
+                    .find_map(|v| v.data_type())
+                    .map_or(DataTypeKind::Int(None), |ty| ty.kind());
+                Some(DataTypeKind::Array(Box::new(element)).not_null())
+            }
             Self::Null => None,
         }
     }
@@ -273,6 +309,9 @@ impl DataValue {
             &DataValue::Date(d) => {
                 return Err(ConvertError::Cast(d.to_string(), "usize"));
             }
+            &DataValue::Timestamp(t) => {
+                return Err(ConvertError::Cast(t.to_string(), "usize"));
+            }
             &DataValue::Interval(i) => {
                 return Err(ConvertError::Cast(i.to_string(), "usize"));
             }
@@ -282,6 +321,9 @@ impl DataValue {
             DataValue::Blob(v) => {
                 return Err(ConvertError::Cast(v.to_string(), "usize"));
             }
+            DataValue::List(v) => {
+                return Err(ConvertError::Cast(v.to_string(), "usize"));
+            }
         }))
     }
 }
@@ -299,6 +341,8 @@ pub enum ConvertError {
     ParseDecimal(String, rust_decimal::Error),
     #[error("failed to convert string {0:?} to date: {:?}")]
     ParseDate(String, chrono::ParseError),
+    #[error("failed to convert string {0:?} to timestamp: {:?}")]
+    ParseTimestamp(String, chrono::ParseError),
     #[error("failed to convert string {0:?} to interval")]
     ParseInterval(String),
     #[error("failed to convert string {0:?} to blob: {:?}")]
@@ -311,10 +355,18 @@ pub enum ConvertError {
     ToDateError(DataTypeKind),
     #[error("failed to convert {0:?} from date")]
     FromDateError(DataTypeKind),
+    #[error("failed to convert {0:?} from timestamp")]
+    FromTimestampError(DataTypeKind),
     #[error("failed to convert {0:?} from interval")]
     FromIntervalError(DataTypeKind),
+    #[error("failed to convert {0:?} from list")]
+    FromListError(DataTypeKind),
     #[error("failed to cast {0} to type {1}")]
     Cast(String, &'static str),
+    #[error("numeric overflow or division by zero: {0:?} {1} {2:?}")]
+    Arithmetic(DataValue, BinaryOperator, DataValue),
+    #[error("arguments out of the domain of function {0}: {1:?}")]
+    Domain(String, Vec<DataValue>),
 }
 
 /// memory table row type
@@ -0,0 +1,111 @@
+// Copyright 2022 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::types::interval::USECS_PER_DAY;
+use crate::types::Date;
+
+/// Timestamp type without time zone.
+///
+/// It is stored as the number of microseconds since 1970-01-01 00:00:00.
+#[derive(PartialOrd, PartialEq, Debug, Copy, Clone, Default, Hash, Serialize, Deserialize)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub const fn new(inner: i64) -> Self {
+        Timestamp(inner)
+    }
+
+    /// Get the inner value of timestamp type
+    pub fn get_inner(&self) -> i64 {
+        self.0
+    }
+
+    /// Get the date part of the timestamp.
+    pub fn date(&self) -> Date {
+        Date::new(self.0.div_euclid(USECS_PER_DAY) as i32)
+    }
+
+    /// Convert to a chrono datetime.
+    pub fn to_naive(self) -> NaiveDateTime {
+        let secs = self.0.div_euclid(1_000_000);
+        let nsecs = self.0.rem_euclid(1_000_000) as u32 * 1000;
+        NaiveDateTime::from_timestamp(secs, nsecs)
+    }
+}
+
+impl From<Date> for Timestamp {
+    /// The time part is midnight.
+    fn from(date: Date) -> Self {
+        Timestamp(date.get_inner() as i64 * USECS_PER_DAY)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = chrono::ParseError;
+
+    /// Parse `YYYY-MM-DD HH:MM:SS[.ffffff]`. The separator can be a `T` as in ISO-8601, and the
+    /// time part can be omitted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let datetime = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f"))
+            .or_else(|_| NaiveDate::parse_from_str(s, "%Y-%m-%d").map(|d| d.and_hms(0, 0, 0)))?;
+        Ok(Timestamp(
+            datetime.timestamp() * 1_000_000 + datetime.timestamp_subsec_micros() as i64,
+        ))
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_naive().format("%Y-%m-%d %H:%M:%S%.f"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(s: &str) -> Timestamp {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_timestamp_parse_and_format() {
+        assert_eq!(timestamp("1970-01-01 00:00:00"), Timestamp::new(0));
+        assert_eq!(
+            timestamp("1970-01-01 00:00:01.5"),
+            Timestamp::new(1_500_000)
+        );
+        assert_eq!(timestamp("1969-12-31 23:59:59"), Timestamp::new(-1_000_000));
+        assert_eq!(
+            timestamp("2022-01-01T12:00:00"),
+            timestamp("2022-01-01 12:00:00")
+        );
+        assert_eq!(timestamp("2022-01-01"), timestamp("2022-01-01 00:00:00"));
+        assert!("2022-13-01 00:00:00".parse::<Timestamp>().is_err());
+        assert!("12:00:00".parse::<Timestamp>().is_err());
+
+        assert_eq!(
+            timestamp("2022-01-01 12:00:00").to_string(),
+            "2022-01-01 12:00:00"
+        );
+        assert_eq!(
+            timestamp("1969-12-31 23:59:59.123456").to_string(),
+            "1969-12-31 23:59:59.123456"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_date() {
+        let date: Date = "2022-01-01".parse().unwrap();
+        assert_eq!(timestamp("2022-01-01 23:59:59").date(), date);
+        assert_eq!(Timestamp::from(date), timestamp("2022-01-01 00:00:00"));
+        let date: Date = "1969-12-31".parse().unwrap();
+        assert_eq!(timestamp("1969-12-31 12:00:00").date(), date);
+    }
+}